@@ -22,6 +22,9 @@ struct DeriveNHContextSerDeFieldOpts {
     entity: bool,
     #[darling(default)]
     skip_and_default: bool,
+    /// Field may be missing in older files
+    #[darling(default)]
+    default: bool,
 }
 
 pub fn derive_nh_context_deserialize(input: TokenStream) -> TokenStream {
@@ -61,6 +64,9 @@ pub fn derive_nh_context_deserialize(input: TokenStream) -> TokenStream {
                             .ok_or_else(|| NHDeserializeError::StructureError(format!("missing field {} on instance of {}", stringify!(#field_name), stringify!(#ident))))?,
                         deserializer)?,
                 });
+            } else if o.default {
+                basic_fields_def.push(quote! { #[serde(default)] #field_name : #field_type, });
+                basic_fields_move.push(quote! { #field_name : helper . #field_name, });
             } else {
                 basic_fields_def.push(quote! { #field_name : #field_type, });
                 basic_fields_move.push(quote! { #field_name : helper . #field_name, });
@@ -21,6 +21,9 @@ struct DeriveNHContextSerDeFieldOpts {
     entity: bool,
     #[darling(default)]
     skip_and_default: bool,
+    #[expect(dead_code)]
+    #[darling(default)]
+    default: bool,
 }
 
 pub fn derive_nh_context_serialize(input: TokenStream) -> TokenStream {
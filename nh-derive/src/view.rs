@@ -138,6 +138,18 @@ pub fn derive_view(input: TokenStream) -> TokenStream {
         .iter()
         .map(|e| quote! { #e.deep_copy_clone(uuid_present, tlc, c, m) })
         .collect::<Vec<_>>();
+    let arms_connection_endpoints = arms_immutable
+        .iter()
+        .map(|e| quote! { #e.connection_endpoints() })
+        .collect::<Vec<_>>();
+    let arms_connected_views = arms_immutable
+        .iter()
+        .map(|e| quote! { #e.connected_views() })
+        .collect::<Vec<_>>();
+    let arms_waypoints = arms_immutable
+        .iter()
+        .map(|e| quote! { #e.waypoints() })
+        .collect::<Vec<_>>();
     let arms_deep_copy_relink = arms_mutable
         .iter()
         .map(|e| quote! { #e.deep_copy_relink(c, m) })
@@ -285,6 +297,21 @@ pub fn derive_view(input: TokenStream) -> TokenStream {
                     #(#arms_delete_when),*
                 }
             }
+            fn connection_endpoints(&self) -> Option<(ViewUuid, ViewUuid)> {
+                match self {
+                    #(#arms_connection_endpoints),*
+                }
+            }
+            fn connected_views(&self) -> Vec<(ViewUuid, ViewUuid)> {
+                match self {
+                    #(#arms_connected_views),*
+                }
+            }
+            fn waypoints(&self) -> Vec<(ViewUuid, egui::Pos2)> {
+                match self {
+                    #(#arms_waypoints),*
+                }
+            }
             fn deep_copy_walk(
                 &self,
                 requested: Option<&HashSet<ViewUuid>>,
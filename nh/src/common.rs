@@ -1,11 +1,37 @@
+pub mod abbreviations;
+pub mod annotations;
 pub mod canvas;
+pub mod clustering;
 pub mod controller;
+pub mod csv;
+pub mod document_import;
+pub mod document_links;
+pub mod document_stats;
+pub mod drawio;
 pub mod entity;
 pub mod eref;
+pub mod external_links;
 pub mod fluent;
+pub mod html_export;
+pub mod images;
+pub mod issue_links;
+pub mod layers;
+pub mod layout;
+pub mod macros;
+pub mod math;
+pub mod naming;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod project_repair;
 pub mod project_serde;
+pub mod raster;
+pub mod report;
+pub mod rich_text;
 pub mod search;
+pub mod symbols;
+pub mod todos;
 pub mod ufoption;
 pub mod ui_ext;
 pub mod uuid;
+pub mod variants;
 pub mod views;
+pub mod xml;
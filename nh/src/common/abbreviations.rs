@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AbbreviationEntry {
+    pub abbreviation: String,
+    pub expansion: String,
+}
+
+/// Project-level dictionary of abbreviations used in element names
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AbbreviationDictionary {
+    /// Whether all acronyms used in names must have an entry
+    #[serde(default)]
+    pub require_defined: bool,
+    #[serde(default)]
+    pub entries: Vec<AbbreviationEntry>,
+}
+
+impl AbbreviationDictionary {
+    pub fn clear(&mut self) {
+        self.require_defined = false;
+        self.entries.clear();
+    }
+
+    fn lookup(&self, word: &str) -> Option<&AbbreviationEntry> {
+        self.entries.iter().find(|e| {
+            !e.abbreviation.is_empty() && e.abbreviation.to_lowercase() == word.to_lowercase()
+        })
+    }
+
+    /// Entries for abbreviations occurring in the name, each listed once
+    pub fn expansions_in(&self, name: &str) -> Vec<&AbbreviationEntry> {
+        let mut found: Vec<&AbbreviationEntry> = Vec::new();
+        for word in words(name) {
+            if let Some(e) = self.lookup(word)
+                && !found.iter().any(|f| std::ptr::eq(*f, e))
+            {
+                found.push(e);
+            }
+        }
+        found
+    }
+
+    /// Acronyms occurring in the name without an entry, each listed once
+    pub fn undefined_acronyms<'a>(&self, name: &'a str) -> Vec<&'a str> {
+        let mut found = Vec::new();
+        for word in words(name) {
+            if is_acronym(word) && self.lookup(word).is_none() && !found.contains(&word) {
+                found.push(word);
+            }
+        }
+        found
+    }
+}
+
+/// Splits the name into words on non-alphanumeric characters and on case changes,
+/// so that `parseHTTPRequest` yields `parse`, `HTTP` and `Request`
+pub fn words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in name.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (idx, c) = chars[i];
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).is_some_and(|e| e.1.is_lowercase());
+            if c.is_uppercase() && (prev.is_lowercase() || (prev.is_uppercase() && next_is_lower)) {
+                words.push(&part[start..idx]);
+                start = idx;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words
+}
+
+/// Word of at least two characters, written in capitals apart from digits
+pub fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_uppercase()).count() >= 2
+        && word.chars().all(|c| c.is_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acronyms_are_found_in_mixed_case_names() {
+        assert_eq!(
+            words("parseHTTPRequest for_B2B_API"),
+            vec!["parse", "HTTP", "Request", "for", "B2B", "API"]
+        );
+
+        let dictionary = AbbreviationDictionary {
+            require_defined: true,
+            entries: vec![
+                AbbreviationEntry {
+                    abbreviation: "HTTP".to_owned(),
+                    expansion: "Hypertext Transfer Protocol".to_owned(),
+                },
+                AbbreviationEntry {
+                    abbreviation: "qty".to_owned(),
+                    expansion: "quantity".to_owned(),
+                },
+            ],
+        };
+        let expansions = dictionary.expansions_in("HTTPServer QTY http");
+        assert_eq!(expansions.len(), 2);
+        assert_eq!(expansions[0].expansion, "Hypertext Transfer Protocol");
+        assert_eq!(expansions[1].expansion, "quantity");
+
+        assert_eq!(
+            dictionary.undefined_acronyms("HTTP API for UI, API and I/O"),
+            vec!["API", "UI"]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use eframe::egui;
+
+use super::canvas::{Highlight, NHCanvas, Stroke};
+use super::controller::GlobalDrawingContext;
+
+/// Tool used to draw on the annotation layer instead of editing the elements
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnnotationTool {
+    Freehand,
+    Arrow,
+    Highlight,
+    Eraser,
+}
+
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum AnnotationShape {
+    Freehand(Vec<egui::Pos2>),
+    Arrow(egui::Pos2, egui::Pos2),
+    Highlight(egui::Rect),
+}
+
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Annotation {
+    pub uuid: uuid::Uuid,
+    pub shape: AnnotationShape,
+    pub color: egui::Color32,
+}
+
+impl Annotation {
+    const STROKE_WIDTH: f32 = 2.0;
+    const ARROWHEAD_SIZE: f32 = 12.0;
+    /// Freehand points closer to the previous one are not recorded
+    const MIN_POINT_DISTANCE: f32 = 2.0;
+
+    pub fn new(tool: AnnotationTool, color: egui::Color32, pos: egui::Pos2) -> Option<Self> {
+        let shape = match tool {
+            AnnotationTool::Freehand => AnnotationShape::Freehand(vec![pos]),
+            AnnotationTool::Arrow => AnnotationShape::Arrow(pos, pos),
+            AnnotationTool::Highlight => {
+                AnnotationShape::Highlight(egui::Rect::from_two_pos(pos, pos))
+            }
+            AnnotationTool::Eraser => return None,
+        };
+        Some(Self {
+            uuid: uuid::Uuid::now_v7(),
+            shape,
+            color,
+        })
+    }
+
+    /// Extends the shape being drawn to the pointer position
+    pub fn extend_to(&mut self, start: egui::Pos2, pos: egui::Pos2) {
+        match &mut self.shape {
+            AnnotationShape::Freehand(points) => {
+                if points
+                    .last()
+                    .is_none_or(|e| e.distance(pos) >= Self::MIN_POINT_DISTANCE)
+                {
+                    points.push(pos);
+                }
+            }
+            AnnotationShape::Arrow(_, end) => *end = pos,
+            AnnotationShape::Highlight(rect) => *rect = egui::Rect::from_two_pos(start, pos),
+        }
+    }
+
+    /// Shapes without any extent, such as from a click with the arrow tool, are not kept
+    pub fn is_degenerate(&self) -> bool {
+        match &self.shape {
+            AnnotationShape::Freehand(points) => points.len() < 2,
+            AnnotationShape::Arrow(a, b) => a == b,
+            AnnotationShape::Highlight(rect) => rect.area() == 0.0,
+        }
+    }
+
+    pub fn contains(&self, pos: egui::Pos2, tolerance: f32) -> bool {
+        match &self.shape {
+            AnnotationShape::Freehand(points) => points
+                .windows(2)
+                .any(|w| segment_distance(pos, w[0], w[1]) <= tolerance),
+            AnnotationShape::Arrow(a, b) => segment_distance(pos, *a, *b) <= tolerance,
+            AnnotationShape::Highlight(rect) => rect.expand(tolerance).contains(pos),
+        }
+    }
+
+    pub fn draw_in(&self, canvas: &mut dyn NHCanvas) {
+        let stroke = Stroke::new_solid(Self::STROKE_WIDTH, self.color);
+        match &self.shape {
+            AnnotationShape::Freehand(points) => {
+                for w in points.windows(2) {
+                    canvas.draw_line([w[0], w[1]], stroke, Highlight::NONE);
+                }
+            }
+            AnnotationShape::Arrow(a, b) => {
+                canvas.draw_line([*a, *b], stroke, Highlight::NONE);
+                let direction = (*b - *a).normalized();
+                if direction != egui::Vec2::ZERO {
+                    let back = *b - direction * Self::ARROWHEAD_SIZE;
+                    let side = direction.rot90() * Self::ARROWHEAD_SIZE / 2.0;
+                    canvas.draw_polygon(
+                        vec![*b, back + side, back - side],
+                        self.color,
+                        stroke,
+                        Highlight::NONE,
+                    );
+                }
+            }
+            AnnotationShape::Highlight(rect) => canvas.draw_rectangle(
+                *rect,
+                egui::CornerRadius::ZERO,
+                self.color.gamma_multiply(0.3),
+                stroke,
+                Highlight::NONE,
+            ),
+        }
+    }
+}
+
+fn segment_distance(pos: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 {
+        ((pos - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    pos.distance(a + ab * t)
+}
+
+/// Freehand drawings over a diagram view, such as review remarks. They are saved with
+/// the view, but are not part of the model.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AnnotationLayer {
+    pub visible: bool,
+    /// In draw order
+    pub annotations: Vec<Annotation>,
+}
+
+impl Default for AnnotationLayer {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl AnnotationLayer {
+    pub fn draw_in(&self, canvas: &mut dyn NHCanvas, drawn: Option<&Annotation>) {
+        if !self.visible {
+            return;
+        }
+        for e in self.annotations.iter().chain(drawn) {
+            e.draw_in(canvas);
+        }
+    }
+
+    /// Removes the topmost annotation at the position, returns whether there was one
+    pub fn erase_at(&mut self, pos: egui::Pos2, tolerance: f32) -> bool {
+        let Some(idx) = self
+            .annotations
+            .iter()
+            .rposition(|e| e.contains(pos, tolerance))
+        else {
+            return false;
+        };
+        self.annotations.remove(idx);
+        true
+    }
+
+    pub fn show_properties(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        tool: &mut Option<AnnotationTool>,
+        color: &mut egui::Color32,
+    ) {
+        ui.label(gdc.translate_0("nh-annotations"));
+        ui.checkbox(&mut self.visible, gdc.translate_0("nh-annotations-visible"));
+        if !self.visible {
+            *tool = None;
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for (t, label) in [
+                (None, "nh-annotations-none"),
+                (Some(AnnotationTool::Freehand), "nh-annotations-freehand"),
+                (Some(AnnotationTool::Arrow), "nh-annotations-arrow"),
+                (Some(AnnotationTool::Highlight), "nh-annotations-highlight"),
+                (Some(AnnotationTool::Eraser), "nh-annotations-eraser"),
+            ] {
+                ui.selectable_value(tool, t, gdc.translate_0(label));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(gdc.translate_0("nh-annotations-color"));
+            ui.color_edit_button_srgba(color);
+        });
+        if !self.annotations.is_empty()
+            && ui.button(gdc.translate_0("nh-annotations-clear")).clicked()
+        {
+            self.annotations.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erase_removes_topmost_hit() {
+        let mut layer = AnnotationLayer::default();
+        for _ in 0..2 {
+            let mut e = Annotation::new(
+                AnnotationTool::Arrow,
+                egui::Color32::RED,
+                egui::Pos2::new(0.0, 0.0),
+            )
+            .unwrap();
+            e.extend_to(egui::Pos2::ZERO, egui::Pos2::new(100.0, 0.0));
+            layer.annotations.push(e);
+        }
+        let top = layer.annotations[1].uuid;
+
+        assert!(!layer.erase_at(egui::Pos2::new(50.0, 10.0), 5.0));
+        assert!(layer.erase_at(egui::Pos2::new(50.0, 3.0), 5.0));
+        assert_eq!(layer.annotations.len(), 1);
+        assert_ne!(layer.annotations[0].uuid, top);
+    }
+
+    #[test]
+    fn clicks_make_degenerate_shapes() {
+        let pos = egui::Pos2::new(10.0, 10.0);
+        for tool in [
+            AnnotationTool::Freehand,
+            AnnotationTool::Arrow,
+            AnnotationTool::Highlight,
+        ] {
+            let mut e = Annotation::new(tool, egui::Color32::RED, pos).unwrap();
+            assert!(e.is_degenerate());
+            e.extend_to(pos, pos + egui::Vec2::new(20.0, 20.0));
+            assert!(!e.is_degenerate());
+        }
+    }
+}
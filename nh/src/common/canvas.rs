@@ -1,6 +1,12 @@
 use eframe::egui;
 
+use std::collections::HashMap;
 use std::io::Write;
+use std::sync::Arc;
+
+use super::controller::GlobalDrawingContext;
+use super::images::ProjectImage;
+use super::uuid::ViewUuid;
 use std::ops::{BitAnd, BitOr, RangeInclusive};
 
 // find unique intersection between segments (s1a, s1b) and (s2a, s2b)
@@ -451,6 +457,11 @@ pub enum ArrowheadType {
     FullTriangle,
     EmptyRhombus,
     FullRhombus,
+    /// Crow's foot notation, the outer mark is the minimum and the inner mark the maximum
+    CrowsFoot {
+        optional: bool,
+        many: bool,
+    },
 }
 
 fn atan2(a: egui::Pos2, b: egui::Pos2) -> f32 {
@@ -465,7 +476,9 @@ impl ArrowheadType {
     // that is the furthest from the focal_point
     pub fn get_intersect(&self, focal_point: egui::Pos2, other: egui::Pos2) -> egui::Pos2 {
         match self {
-            ArrowheadType::None | ArrowheadType::OpenTriangle => focal_point,
+            ArrowheadType::None | ArrowheadType::OpenTriangle | ArrowheadType::CrowsFoot { .. } => {
+                focal_point
+            }
             ArrowheadType::EmptyTriangle
             | ArrowheadType::EmptyTriangleWith(..)
             | ArrowheadType::FullTriangle
@@ -556,12 +569,53 @@ impl ArrowheadType {
                     highlight,
                 );
             }
+            ArrowheadType::CrowsFoot { optional, many } => {
+                let along = egui::Vec2::new(outward_angle.cos(), outward_angle.sin());
+                let across = along.rot90() * ARROWHEAD_SIDE_LENGTH / 2.0;
+                let bar = |distance: f32| {
+                    let center = focal_point + along * distance;
+                    [center - across, center + across]
+                };
+
+                if *many {
+                    let apex = focal_point + along * ARROWHEAD_SIDE_LENGTH;
+                    for foot in [focal_point - across, focal_point + across] {
+                        canvas.draw_line(
+                            [apex, foot],
+                            Stroke::new_solid(1.0, primary_color),
+                            highlight,
+                        );
+                    }
+                } else {
+                    canvas.draw_line(
+                        bar(ARROWHEAD_SIDE_LENGTH / 2.0),
+                        Stroke::new_solid(1.0, primary_color),
+                        highlight,
+                    );
+                }
+                if *optional {
+                    let radius = ARROWHEAD_SIDE_LENGTH / 4.0;
+                    canvas.draw_ellipse(
+                        focal_point + along * (ARROWHEAD_SIDE_LENGTH * 1.25 + radius),
+                        egui::Vec2::splat(radius),
+                        secondary_color,
+                        Stroke::new_solid(1.0, primary_color),
+                        highlight,
+                    );
+                } else {
+                    canvas.draw_line(
+                        bar(ARROWHEAD_SIDE_LENGTH * 1.25),
+                        Stroke::new_solid(1.0, primary_color),
+                        highlight,
+                    );
+                }
+            }
         }
     }
 }
 
 // TODO: double, squiggly
-#[derive(Clone, Copy, PartialEq, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub enum LineType {
     #[default]
     Solid,
@@ -606,6 +660,24 @@ impl Stroke {
             line_type: LineType::Dotted,
         }
     }
+
+    /// Thicker solid stroke in the color of the first set highlight, if any
+    pub fn highlighted(self, h: Highlight, highlight_colors: &[egui::Color32; 4]) -> Self {
+        if h.count() == 0 {
+            self
+        } else {
+            Stroke::new_solid(
+                2.0 * self.width,
+                match h {
+                    Highlight { selected, .. } if selected => highlight_colors[0],
+                    Highlight { valid, .. } if valid => highlight_colors[1],
+                    Highlight { invalid, .. } if invalid => highlight_colors[2],
+                    Highlight { warning, .. } if warning => highlight_colors[3],
+                    _ => unreachable!(),
+                },
+            )
+        }
+    }
 }
 
 impl From<Stroke> for egui::Stroke {
@@ -714,6 +786,52 @@ pub const CLASS_MIDDLE_FONT_SIZE: f32 = 15.0;
 pub const CLASS_BOTTOM_FONT_SIZE: f32 = 12.0;
 pub const CLASS_ITEM_FONT_SIZE: f32 = 10.0;
 
+/// Fits the text into the maximum width (no limit when not positive), either by cutting it
+/// off with an ellipsis or by breaking it into lines at spaces. Words longer than the width
+/// are kept whole when wrapping.
+pub fn fit_text_width(
+    text: &str,
+    max_width: f32,
+    wrap: bool,
+    mut measure: impl FnMut(&str) -> f32,
+) -> std::borrow::Cow<'_, str> {
+    if max_width <= 0.0 || text.is_empty() || measure(text) <= max_width {
+        return std::borrow::Cow::Borrowed(text);
+    }
+
+    if wrap {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in text.split(' ') {
+            if current.is_empty() {
+                current.push_str(word);
+                continue;
+            }
+            let candidate = format!("{} {}", current, word);
+            if measure(&candidate) <= max_width {
+                current = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut current, word.to_owned()));
+            }
+        }
+        lines.push(current);
+        std::borrow::Cow::Owned(lines.join("\n"))
+    } else {
+        // Longest prefix which still fits together with the ellipsis, the whole text does not
+        let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        let (mut low, mut high) = (0, boundaries.len() - 1);
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            if measure(&format!("{}…", &text[..boundaries[mid]])) <= max_width {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        std::borrow::Cow::Owned(format!("{}…", text[..boundaries[low]].trim_end()))
+    }
+}
+
 pub enum HeaderLocation {
     Horizontal(RangeInclusive<f32>),
     Vertical(RangeInclusive<f32>),
@@ -777,6 +895,31 @@ pub trait NHCanvas {
     );
 
     fn draw_header_text(&mut self, _pos: HeaderLocation, _text: &str) {}
+
+    /// Called before the view draws itself, see [`StyledCanvas`]
+    fn begin_element(&mut self, _view: &ViewUuid) {}
+    fn end_element(&mut self) {}
+
+    /// Line of a connection between elements, see [`LineJumpCanvas`]
+    fn draw_connection_line(
+        &mut self,
+        points: [egui::Pos2; 2],
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.draw_line(points, stroke, highlight);
+    }
+
+    /// Draws a raster image stretched over `rect`.
+    /// When `tile` is given, the image is placed there and repeated to fill `rect` instead.
+    fn draw_image(
+        &mut self,
+        _rect: egui::Rect,
+        _image: &ProjectImage,
+        _tile: Option<egui::Rect>,
+        _opacity: f32,
+    ) {
+    }
 }
 
 pub struct UiCanvas {
@@ -795,6 +938,8 @@ pub struct UiCanvas {
     highlight_filter: Highlight,
     header_horizontal: bool,
     header_vertical: bool,
+    /// Images drawn so far, needed to rasterize the canvas off-screen
+    used_images: HashMap<egui::TextureId, Arc<egui::ColorImage>>,
 }
 
 impl UiCanvas {
@@ -872,9 +1017,14 @@ impl UiCanvas {
             highlight_filter,
             header_horizontal: enable_headers.0,
             header_vertical: enable_headers.1,
+            used_images: HashMap::new(),
         }
     }
 
+    pub fn into_used_images(self) -> HashMap<egui::TextureId, Arc<egui::ColorImage>> {
+        self.used_images
+    }
+
     pub fn clear(&self, color: egui::Color32) {
         self.main_area_painter.rect(
             self.canvas,
@@ -925,21 +1075,7 @@ impl UiCanvas {
     }
 
     fn filtered_stroke(&self, stroke: Stroke, h: Highlight) -> Stroke {
-        let h = &h & self.highlight_filter;
-        if h.count() == 0 {
-            stroke
-        } else {
-            Stroke::new_solid(
-                2.0 * stroke.width,
-                match h {
-                    Highlight { selected, .. } if selected => self.highlight_colors[0],
-                    Highlight { valid, .. } if valid => self.highlight_colors[1],
-                    Highlight { invalid, .. } if invalid => self.highlight_colors[2],
-                    Highlight { warning, .. } if warning => self.highlight_colors[3],
-                    _ => unreachable!(),
-                },
-            )
-        }
+        stroke.highlighted(&h & self.highlight_filter, &self.highlight_colors)
     }
 }
 
@@ -1176,6 +1312,36 @@ impl NHCanvas for UiCanvas {
             _ => {}
         }
     }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        let translated_rect = (rect * self.camera_scale)
+            .translate(self.canvas.min.to_vec2() + self.camera_offset.to_vec2());
+        if !self.canvas.intersects(translated_rect) {
+            return;
+        }
+        let uv = match tile {
+            None => egui::Rect::from_min_max(egui::Pos2::ZERO, egui::Pos2::new(1.0, 1.0)),
+            Some(t) => egui::Rect::from_min_max(
+                ((rect.min - t.min) / t.size()).to_pos2(),
+                ((rect.max - t.min) / t.size()).to_pos2(),
+            ),
+        };
+
+        let texture = image.texture(self.main_area_painter.ctx());
+        self.main_area_painter.image(
+            texture.id(),
+            translated_rect,
+            uv,
+            egui::Color32::WHITE.gamma_multiply(opacity),
+        );
+        self.used_images.insert(texture.id(), image.image.clone());
+    }
 }
 
 pub struct MeasuringCanvas<'a> {
@@ -1195,10 +1361,16 @@ impl<'a> MeasuringCanvas<'a> {
         self.bounds
     }
 
-    pub fn scale_offset_to_fit(&self, rect: egui::Vec2) -> (f32, egui::Pos2) {
-        let scale_x = rect.x / self.bounds.width();
-        let scale_y = rect.y / self.bounds.height();
-        let scale_min = scale_x.min(scale_y);
+    /// Scale and offset drawing the measured content centered in the area, `padding` away from
+    /// its edges. Content without size is only centered.
+    pub fn scale_offset_to_fit(&self, rect: egui::Vec2, padding: f32) -> (f32, egui::Pos2) {
+        let inner = (rect - egui::Vec2::splat(2.0 * padding)).max(egui::Vec2::splat(1.0));
+        let scale_x = inner.x / self.bounds.width();
+        let scale_y = inner.y / self.bounds.height();
+        let scale_min = match scale_x.min(scale_y) {
+            s if s.is_finite() && s > 0.0 => s,
+            _ => 1.0,
+        };
         let offset_x =
             (rect.x - self.bounds().width() * scale_min) / 2.0 - self.bounds.min.x * scale_min;
         let offset_y =
@@ -1278,6 +1450,16 @@ impl<'a> NHCanvas for MeasuringCanvas<'a> {
         let rect = self.measure_text(position, anchor, text, font_size);
         self.bounds = self.bounds.union(rect);
     }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        _image: &ProjectImage,
+        _tile: Option<egui::Rect>,
+        _opacity: f32,
+    ) {
+        self.bounds = self.bounds.union(rect);
+    }
 }
 
 pub struct SVGCanvas<'a> {
@@ -1312,21 +1494,7 @@ impl<'a> SVGCanvas<'a> {
     }
 
     fn filtered_stroke(&self, stroke: Stroke, h: Highlight) -> Stroke {
-        let h = &h & self.highlight_filter;
-        if h.count() == 0 {
-            stroke
-        } else {
-            Stroke::new_solid(
-                2.0 * stroke.width,
-                match h {
-                    Highlight { selected, .. } if selected => self.highlight_colors[0],
-                    Highlight { valid, .. } if valid => self.highlight_colors[1],
-                    Highlight { invalid, .. } if invalid => self.highlight_colors[2],
-                    Highlight { warning, .. } if warning => self.highlight_colors[3],
-                    _ => unreachable!(),
-                },
-            )
-        }
+        stroke.highlighted(&h & self.highlight_filter, &self.highlight_colors)
     }
 
     pub fn into_bytes(self) -> Result<Vec<u8>, std::io::Error> {
@@ -1533,4 +1701,1048 @@ impl<'a> NHCanvas for SVGCanvas<'a> {
         self.element_buffer.push(format!(r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="middle">{}</text>
 "#, rect.center().x + self.camera_offset.x, rect.center().y + self.camera_offset.y, font_size, text_color.to_hex(), tspans));
     }
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        let href = format!("data:{};base64,{}", image.mime_type(), image.to_base64());
+        let rect = rect.translate(self.camera_offset.to_vec2());
+
+        match tile {
+            None => {
+                self.element_buffer.push(format!(
+                    r#"<image href="{}" x="{}" y="{}" width="{}" height="{}" opacity="{}" preserveAspectRatio="none"/>
+"#,
+                    href,
+                    rect.min.x,
+                    rect.min.y,
+                    rect.width(),
+                    rect.height(),
+                    opacity,
+                ));
+            }
+            Some(tile) => {
+                let tile = tile.translate(self.camera_offset.to_vec2());
+                let id = format!("image-tile-{}", self.element_buffer.len());
+                self.element_buffer.push(format!(
+                    r#"<defs><pattern id="{}" patternUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}"><image href="{}" width="{}" height="{}" preserveAspectRatio="none"/></pattern></defs>
+<rect x="{}" y="{}" width="{}" height="{}" fill="url(#{})" opacity="{}"/>
+"#,
+                    id,
+                    tile.min.x,
+                    tile.min.y,
+                    tile.width(),
+                    tile.height(),
+                    href,
+                    tile.width(),
+                    tile.height(),
+                    rect.min.x,
+                    rect.min.y,
+                    rect.width(),
+                    rect.height(),
+                    id,
+                    opacity,
+                ));
+            }
+        }
+    }
+}
+
+/// Fill drawn over the background color of an element
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum FillPattern {
+    #[default]
+    Plain,
+    Hatched,
+    CrossHatched,
+}
+
+/// Appearance of an element overriding how its view draws it
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ElementStyle {
+    pub line_type: Option<LineType>,
+    pub corner_radius: Option<u8>,
+    pub fill_pattern: FillPattern,
+    /// Multiplies all font sizes used by the element
+    pub font_scale: Option<f32>,
+    pub bold: bool,
+}
+
+impl ElementStyle {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns true when the style was changed
+    pub fn show_properties(&mut self, gdc: &GlobalDrawingContext, ui: &mut egui::Ui) -> bool {
+        let old = *self;
+
+        let line_types = [
+            (None, "nh-style-default"),
+            (Some(LineType::Solid), "nh-style-line-solid"),
+            (Some(LineType::Dashed), "nh-style-line-dashed"),
+            (Some(LineType::Dotted), "nh-style-line-dotted"),
+        ];
+        egui::ComboBox::from_label(gdc.translate_0("nh-style-line"))
+            .selected_text(
+                line_types
+                    .iter()
+                    .find(|e| e.0 == self.line_type)
+                    .map(|e| gdc.translate_0(e.1))
+                    .unwrap_or_default(),
+            )
+            .show_ui(ui, |ui| {
+                for (t, label) in line_types {
+                    ui.selectable_value(&mut self.line_type, t, gdc.translate_0(label));
+                }
+            });
+        ui.horizontal(|ui| {
+            let mut overridden = self.corner_radius.is_some();
+            ui.checkbox(&mut overridden, gdc.translate_0("nh-style-cornerradius"));
+            let mut radius = self.corner_radius.unwrap_or(0);
+            ui.add_enabled(overridden, egui::DragValue::new(&mut radius).range(0..=50));
+            self.corner_radius = overridden.then_some(radius);
+        });
+        let fill_patterns = [
+            (FillPattern::Plain, "nh-style-fill-plain"),
+            (FillPattern::Hatched, "nh-style-fill-hatched"),
+            (FillPattern::CrossHatched, "nh-style-fill-crosshatched"),
+        ];
+        egui::ComboBox::from_label(gdc.translate_0("nh-style-fill"))
+            .selected_text(
+                fill_patterns
+                    .iter()
+                    .find(|e| e.0 == self.fill_pattern)
+                    .map(|e| gdc.translate_0(e.1))
+                    .unwrap_or_default(),
+            )
+            .show_ui(ui, |ui| {
+                for (p, label) in fill_patterns {
+                    ui.selectable_value(&mut self.fill_pattern, p, gdc.translate_0(label));
+                }
+            });
+        ui.horizontal(|ui| {
+            let mut overridden = self.font_scale.is_some();
+            ui.checkbox(&mut overridden, gdc.translate_0("nh-style-fontscale"));
+            let mut scale = self.font_scale.unwrap_or(1.0);
+            ui.add_enabled(
+                overridden,
+                egui::DragValue::new(&mut scale)
+                    .range(0.5..=3.0)
+                    .speed(0.05),
+            );
+            self.font_scale = overridden.then_some(scale);
+        });
+        ui.checkbox(&mut self.bold, gdc.translate_0("nh-style-bold"));
+
+        *self != old
+    }
+}
+
+/// Appearance of elements newly placed from the toolbox of a diagram type,
+/// applied on top of what the element constructors use
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ElementDefaults {
+    pub width: Option<f32>,
+    pub font_scale: Option<f32>,
+    pub background_color: Option<egui::Color32>,
+}
+
+impl ElementDefaults {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Style of the placed element, if the defaults affect it
+    pub fn style(&self) -> Option<ElementStyle> {
+        self.font_scale.map(|e| ElementStyle {
+            font_scale: Some(e),
+            ..Default::default()
+        })
+    }
+
+    /// Elements sized by their contents have no extent when placed, those are kept as they are
+    pub fn resized(&self, rect: egui::Rect) -> Option<egui::Rect> {
+        let width = self.width?;
+        (rect.width() > 0.0 && rect.height() > 0.0 && rect.width() != width)
+            .then(|| egui::Rect::from_min_size(rect.min, egui::Vec2::new(width, rect.height())))
+    }
+
+    pub fn show_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut overridden = self.width.is_some();
+            ui.checkbox(&mut overridden, "Width");
+            let mut width = self.width.unwrap_or(100.0);
+            ui.add_enabled(
+                overridden,
+                egui::DragValue::new(&mut width).range(20.0..=1000.0),
+            );
+            self.width = overridden.then_some(width);
+        });
+        ui.horizontal(|ui| {
+            let mut overridden = self.font_scale.is_some();
+            ui.checkbox(&mut overridden, "Font scale");
+            let mut scale = self.font_scale.unwrap_or(1.0);
+            ui.add_enabled(
+                overridden,
+                egui::DragValue::new(&mut scale)
+                    .range(0.5..=3.0)
+                    .speed(0.05),
+            );
+            self.font_scale = overridden.then_some(scale);
+        });
+        ui.horizontal(|ui| {
+            let mut overridden = self.background_color.is_some();
+            ui.checkbox(&mut overridden, "Background color");
+            let mut color = self.background_color.unwrap_or(egui::Color32::WHITE);
+            ui.add_enabled_ui(overridden, |ui| {
+                egui::widgets::color_picker::color_edit_button_srgba(
+                    ui,
+                    &mut color,
+                    egui::widgets::color_picker::Alpha::OnlyBlend,
+                );
+            });
+            self.background_color = overridden.then_some(color);
+        });
+    }
+}
+
+/// Canvas applying styles of the elements drawn in it, which are announced using
+/// [`NHCanvas::begin_element`] and [`NHCanvas::end_element`]. Nested elements without
+/// a style of their own use the style of their container.
+pub struct StyledCanvas<'a> {
+    inner: &'a mut dyn NHCanvas,
+    styles: &'a HashMap<ViewUuid, ElementStyle>,
+    stack: Vec<Option<ElementStyle>>,
+}
+
+impl<'a> StyledCanvas<'a> {
+    const HATCH_SPACING: f32 = 8.0;
+    /// Smaller shapes, such as handles, are never hatched
+    const HATCH_MIN_SIZE: f32 = 3.0 * Self::HATCH_SPACING;
+
+    pub fn new(inner: &'a mut dyn NHCanvas, styles: &'a HashMap<ViewUuid, ElementStyle>) -> Self {
+        Self {
+            inner,
+            styles,
+            stack: Vec::new(),
+        }
+    }
+
+    fn style(&self) -> Option<ElementStyle> {
+        self.stack.last().copied().flatten()
+    }
+
+    /// Draws the fill and then the outline, so that line types apply to all shapes
+    fn draw_styled_shape(
+        &mut self,
+        outline: Vec<egui::Pos2>,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+        style: ElementStyle,
+        draw_fill: impl FnOnce(&mut dyn NHCanvas, Stroke),
+    ) {
+        let restyled_stroke = match style.line_type {
+            Some(line_type) if stroke.width > 0.0 && stroke.line_type != line_type => {
+                Some(Stroke {
+                    line_type,
+                    ..stroke
+                })
+            }
+            _ => None,
+        };
+        draw_fill(
+            self.inner,
+            if restyled_stroke.is_some() {
+                Stroke::NONE
+            } else {
+                stroke
+            },
+        );
+
+        if color != egui::Color32::TRANSPARENT && style.fill_pattern != FillPattern::Plain {
+            let bounds = egui::Rect::from_points(&outline);
+            if bounds.width() >= Self::HATCH_MIN_SIZE && bounds.height() >= Self::HATCH_MIN_SIZE {
+                let hatch_color = if stroke.color == egui::Color32::TRANSPARENT {
+                    egui::Color32::BLACK
+                } else {
+                    stroke.color
+                };
+                let hatch_stroke = Stroke::new_solid(0.5, hatch_color.gamma_multiply(0.5));
+                let mut normals = vec![egui::Vec2::new(1.0, -1.0).normalized()];
+                if style.fill_pattern == FillPattern::CrossHatched {
+                    normals.push(egui::Vec2::new(1.0, 1.0).normalized());
+                }
+                for n in normals {
+                    for s in hatch_segments(&outline, n, Self::HATCH_SPACING) {
+                        self.inner.draw_line(s, hatch_stroke, Highlight::NONE);
+                    }
+                }
+            }
+        }
+
+        if let Some(stroke) = restyled_stroke {
+            for (i, a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                self.inner.draw_line([*a, b], stroke, highlight);
+            }
+        }
+    }
+}
+
+/// Parts of parallel lines with the given normal inside of the polygon
+fn hatch_segments(
+    polygon: &[egui::Pos2],
+    normal: egui::Vec2,
+    spacing: f32,
+) -> Vec<[egui::Pos2; 2]> {
+    let direction = normal.rot90();
+    let (min, max) = polygon
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |acc, p| {
+            let d = p.to_vec2().dot(normal);
+            (acc.0.min(d), acc.1.max(d))
+        });
+
+    let mut segments = Vec::new();
+    let mut c = (min / spacing).ceil() * spacing;
+    while c <= max {
+        let mut crossings: Vec<egui::Pos2> = polygon
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| {
+                let b = polygon[(i + 1) % polygon.len()];
+                let (da, db) = (a.to_vec2().dot(normal) - c, b.to_vec2().dot(normal) - c);
+                ((da < 0.0) != (db < 0.0)).then(|| *a + (b - *a) * (da / (da - db)))
+            })
+            .collect();
+        crossings.sort_by(|a, b| {
+            a.to_vec2()
+                .dot(direction)
+                .total_cmp(&b.to_vec2().dot(direction))
+        });
+        for pair in crossings.chunks_exact(2) {
+            segments.push([pair[0], pair[1]]);
+        }
+        c += spacing;
+    }
+    segments
+}
+
+fn ellipse_outline(position: egui::Pos2, radius: egui::Vec2) -> Vec<egui::Pos2> {
+    const SEGMENTS: usize = 32;
+    (0..SEGMENTS)
+        .map(|i| {
+            let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+            position + egui::Vec2::new(angle.cos() * radius.x, angle.sin() * radius.y)
+        })
+        .collect()
+}
+
+fn rounded_rect_outline(rect: egui::Rect, radius: f32) -> Vec<egui::Pos2> {
+    let r = radius.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    if r <= 0.0 {
+        return vec![
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+        ];
+    }
+    let inner = rect.shrink(r);
+    [
+        (inner.right_bottom(), 0.0),
+        (inner.left_bottom(), 0.25),
+        (inner.left_top(), 0.5),
+        (inner.right_top(), 0.75),
+    ]
+    .into_iter()
+    .flat_map(|(center, start)| {
+        (0..=4).map(move |i| {
+            let angle = (start + i as f32 / 16.0) * std::f32::consts::TAU;
+            center + egui::Vec2::new(angle.cos(), angle.sin()) * r
+        })
+    })
+    .collect()
+}
+
+impl NHCanvas for StyledCanvas<'_> {
+    fn ui_scale(&self) -> Option<f32> {
+        self.inner.ui_scale()
+    }
+
+    fn begin_element(&mut self, view: &ViewUuid) {
+        let style = self.styles.get(view).copied().or(self.style());
+        self.stack.push(style);
+        self.inner.begin_element(view);
+    }
+    fn end_element(&mut self) {
+        self.stack.pop();
+        self.inner.end_element();
+    }
+
+    fn draw_line(&mut self, points: [egui::Pos2; 2], stroke: Stroke, highlight: Highlight) {
+        let stroke = match self.style().and_then(|e| e.line_type) {
+            Some(line_type) => Stroke {
+                line_type,
+                ..stroke
+            },
+            None => stroke,
+        };
+        self.inner.draw_line(points, stroke, highlight);
+    }
+    fn draw_rectangle(
+        &mut self,
+        rect: egui::Rect,
+        corner_radius: egui::CornerRadius,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let Some(style) = self.style().filter(|_| rect != egui::Rect::EVERYTHING) else {
+            self.inner
+                .draw_rectangle(rect, corner_radius, color, stroke, highlight);
+            return;
+        };
+        let corner_radius = style
+            .corner_radius
+            .map(egui::CornerRadius::same)
+            .unwrap_or(corner_radius);
+        self.draw_styled_shape(
+            rounded_rect_outline(rect, corner_radius.nw as f32),
+            color,
+            stroke,
+            highlight,
+            style,
+            |c, stroke| c.draw_rectangle(rect, corner_radius, color, stroke, highlight),
+        );
+    }
+    fn draw_ellipse(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let Some(style) = self.style() else {
+            self.inner
+                .draw_ellipse(position, radius, color, stroke, highlight);
+            return;
+        };
+        self.draw_styled_shape(
+            ellipse_outline(position, radius),
+            color,
+            stroke,
+            highlight,
+            style,
+            |c, stroke| c.draw_ellipse(position, radius, color, stroke, highlight),
+        );
+    }
+    fn draw_ellipse_proximity(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        max_distance: f32,
+        highlight: Highlight,
+    ) {
+        self.inner
+            .draw_ellipse_proximity(position, radius, color, stroke, max_distance, highlight);
+    }
+    fn draw_polygon(
+        &mut self,
+        vertices: Vec<egui::Pos2>,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let Some(style) = self.style() else {
+            self.inner.draw_polygon(vertices, color, stroke, highlight);
+            return;
+        };
+        self.draw_styled_shape(
+            vertices.clone(),
+            color,
+            stroke,
+            highlight,
+            style,
+            |c, stroke| c.draw_polygon(vertices, color, stroke, highlight),
+        );
+    }
+
+    fn measure_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+    ) -> egui::Rect {
+        let scale = self.style().and_then(|e| e.font_scale).unwrap_or(1.0);
+        self.inner
+            .measure_text(position, anchor, text, font_size * scale)
+    }
+    fn draw_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+        text_color: egui::Color32,
+    ) {
+        let style = self.style().unwrap_or_default();
+        let font_size = font_size * style.font_scale.unwrap_or(1.0);
+        self.inner
+            .draw_text(position, anchor, text, font_size, text_color);
+        // Emulated, as not all canvases support font weights
+        if style.bold {
+            self.inner.draw_text(
+                position + egui::Vec2::new(font_size / 16.0, 0.0),
+                anchor,
+                text,
+                font_size,
+                text_color,
+            );
+        }
+    }
+
+    fn draw_header_text(&mut self, pos: HeaderLocation, text: &str) {
+        self.inner.draw_header_text(pos, text);
+    }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        self.inner.draw_image(rect, image, tile, opacity);
+    }
+}
+
+/// Which color profile a diagram is drawn with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum CanvasTheme {
+    /// Light or dark depending on the theme of the application
+    #[default]
+    FollowApp,
+    Light,
+    Dark,
+}
+
+impl CanvasTheme {
+    pub fn is_dark(&self, app_dark_mode: bool) -> bool {
+        match self {
+            CanvasTheme::FollowApp => app_dark_mode,
+            CanvasTheme::Light => false,
+            CanvasTheme::Dark => true,
+        }
+    }
+}
+
+/// Colors replacing the default colors views are drawn with, which are those of [`ColorProfile::LIGHT`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorProfile {
+    pub background: egui::Color32,
+    pub gridlines: egui::Color32,
+    /// Background of elements
+    pub fill: egui::Color32,
+    /// Strokes and text
+    pub foreground: egui::Color32,
+    /// Fills specific to a domain, with the colors replacing them
+    pub fills: &'static [(egui::Color32, egui::Color32)],
+}
+
+impl ColorProfile {
+    pub const LIGHT: Self = Self {
+        background: egui::Color32::WHITE,
+        gridlines: egui::Color32::from_rgb(220, 220, 220),
+        fill: egui::Color32::WHITE,
+        foreground: egui::Color32::BLACK,
+        fills: &[],
+    };
+    pub const DARK: Self = Self {
+        background: egui::Color32::from_rgb(30, 30, 30),
+        gridlines: egui::Color32::from_rgb(60, 60, 60),
+        fill: egui::Color32::from_rgb(45, 45, 45),
+        foreground: egui::Color32::from_rgb(220, 220, 220),
+        fills: &[],
+    };
+
+    pub const fn with_fills(self, fills: &'static [(egui::Color32, egui::Color32)]) -> Self {
+        Self { fills, ..self }
+    }
+
+    /// Colors explicitly chosen by the user are kept as they are
+    pub fn map(&self, color: egui::Color32) -> egui::Color32 {
+        if color == Self::LIGHT.foreground {
+            self.foreground
+        } else if color == Self::LIGHT.fill {
+            self.fill
+        } else if let Some((_, replacement)) = self.fills.iter().find(|e| e.0 == color) {
+            *replacement
+        } else {
+            color
+        }
+    }
+    pub fn map_background(&self, color: egui::Color32) -> egui::Color32 {
+        if color == Self::LIGHT.background {
+            self.background
+        } else {
+            color
+        }
+    }
+    pub fn map_gridlines(&self, color: egui::Color32) -> egui::Color32 {
+        if color == Self::LIGHT.gridlines {
+            self.gridlines
+        } else {
+            color
+        }
+    }
+}
+
+/// Canvas replacing the default colors according to a [`ColorProfile`]
+pub struct ThemedCanvas<'a> {
+    inner: &'a mut dyn NHCanvas,
+    profile: ColorProfile,
+}
+
+impl<'a> ThemedCanvas<'a> {
+    pub fn new(inner: &'a mut dyn NHCanvas, profile: ColorProfile) -> Self {
+        Self { inner, profile }
+    }
+
+    fn stroke(&self, stroke: Stroke) -> Stroke {
+        Stroke {
+            color: self.profile.map(stroke.color),
+            ..stroke
+        }
+    }
+}
+
+impl NHCanvas for ThemedCanvas<'_> {
+    fn ui_scale(&self) -> Option<f32> {
+        self.inner.ui_scale()
+    }
+
+    fn begin_element(&mut self, view: &ViewUuid) {
+        self.inner.begin_element(view);
+    }
+    fn end_element(&mut self) {
+        self.inner.end_element();
+    }
+
+    fn draw_line(&mut self, points: [egui::Pos2; 2], stroke: Stroke, highlight: Highlight) {
+        self.inner.draw_line(points, self.stroke(stroke), highlight);
+    }
+    fn draw_rectangle(
+        &mut self,
+        rect: egui::Rect,
+        corner_radius: egui::CornerRadius,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner.draw_rectangle(
+            rect,
+            corner_radius,
+            self.profile.map(color),
+            self.stroke(stroke),
+            highlight,
+        );
+    }
+    fn draw_ellipse(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner.draw_ellipse(
+            position,
+            radius,
+            self.profile.map(color),
+            self.stroke(stroke),
+            highlight,
+        );
+    }
+    fn draw_ellipse_proximity(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        max_distance: f32,
+        highlight: Highlight,
+    ) {
+        self.inner.draw_ellipse_proximity(
+            position,
+            radius,
+            self.profile.map(color),
+            self.stroke(stroke),
+            max_distance,
+            highlight,
+        );
+    }
+    fn draw_polygon(
+        &mut self,
+        vertices: Vec<egui::Pos2>,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner.draw_polygon(
+            vertices,
+            self.profile.map(color),
+            self.stroke(stroke),
+            highlight,
+        );
+    }
+
+    fn measure_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+    ) -> egui::Rect {
+        self.inner.measure_text(position, anchor, text, font_size)
+    }
+    fn draw_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+        text_color: egui::Color32,
+    ) {
+        self.inner.draw_text(
+            position,
+            anchor,
+            text,
+            font_size,
+            self.profile.map(text_color),
+        );
+    }
+
+    fn draw_header_text(&mut self, pos: HeaderLocation, text: &str) {
+        self.inner.draw_header_text(pos, text);
+    }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        self.inner.draw_image(rect, image, tile, opacity);
+    }
+}
+
+/// Position of the intersection along `a`, if the segments cross each other
+fn segment_intersection(a: [egui::Pos2; 2], b: [egui::Pos2; 2]) -> Option<f32> {
+    let (da, db) = (a[1] - a[0], b[1] - b[0]);
+    let denominator = da.x * db.y - da.y * db.x;
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = b[0] - a[0];
+    let t = (offset.x * db.y - offset.y * db.x) / denominator;
+    let u = (offset.x * da.y - offset.y * da.x) / denominator;
+    (t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0).then_some(t)
+}
+
+/// Splits the line into segments, replacing its crossings with the others by semicircular hops
+fn line_jump_segments(
+    line: [egui::Pos2; 2],
+    others: impl Iterator<Item = [egui::Pos2; 2]>,
+    radius: f32,
+) -> Vec<[egui::Pos2; 2]> {
+    const HOP_STEPS: usize = 6;
+
+    let length = (line[1] - line[0]).length();
+    if length <= 2.0 * radius {
+        return vec![line];
+    }
+    let direction = (line[1] - line[0]) / length;
+    // Hops bulge upwards, or to the left for vertical lines
+    let normal = {
+        let n = direction.rot90();
+        if n.y > 0.0 || (n.y == 0.0 && n.x > 0.0) {
+            -n
+        } else {
+            n
+        }
+    };
+
+    let mut crossings: Vec<f32> = others
+        .flat_map(|e| segment_intersection(line, e))
+        .map(|t| t * length)
+        .filter(|d| *d > radius && *d < length - radius)
+        .collect();
+    crossings.sort_by(f32::total_cmp);
+    crossings.dedup_by(|d, previous| *d - *previous < 2.0 * radius);
+
+    let mut segments = Vec::new();
+    let mut start = line[0];
+    for d in crossings {
+        let center = line[0] + direction * d;
+        segments.push([start, center - direction * radius]);
+        let mut previous = center - direction * radius;
+        for i in 1..=HOP_STEPS {
+            let p = if i == HOP_STEPS {
+                center + direction * radius
+            } else {
+                let angle = std::f32::consts::PI * i as f32 / HOP_STEPS as f32;
+                center - direction * radius * angle.cos() + normal * radius * angle.sin()
+            };
+            segments.push([previous, p]);
+            previous = p;
+        }
+        start = center + direction * radius;
+    }
+    segments.push([start, line[1]]);
+    segments
+}
+
+/// Canvas drawing connection lines with hops over the connection lines of other elements
+/// drawn before them, so that crossing connections can be told apart
+pub struct LineJumpCanvas<'a> {
+    inner: &'a mut dyn NHCanvas,
+    enabled: bool,
+    /// Connection lines drawn so far, with the element which drew them
+    drawn: Vec<(Option<ViewUuid>, [egui::Pos2; 2])>,
+    elements: Vec<ViewUuid>,
+}
+
+impl<'a> LineJumpCanvas<'a> {
+    const HOP_RADIUS: f32 = 4.0;
+
+    pub fn new(inner: &'a mut dyn NHCanvas, enabled: bool) -> Self {
+        Self {
+            inner,
+            enabled,
+            drawn: Vec::new(),
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl NHCanvas for LineJumpCanvas<'_> {
+    fn ui_scale(&self) -> Option<f32> {
+        self.inner.ui_scale()
+    }
+
+    fn begin_element(&mut self, view: &ViewUuid) {
+        self.elements.push(*view);
+        self.inner.begin_element(view);
+    }
+    fn end_element(&mut self) {
+        self.elements.pop();
+        self.inner.end_element();
+    }
+
+    fn draw_connection_line(
+        &mut self,
+        points: [egui::Pos2; 2],
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        if !self.enabled {
+            self.inner.draw_connection_line(points, stroke, highlight);
+            return;
+        }
+        let element = self.elements.last().copied();
+        let others = self.drawn.iter().filter(|e| e.0 != element).map(|e| e.1);
+        for e in line_jump_segments(points, others, Self::HOP_RADIUS) {
+            self.inner.draw_line(e, stroke, highlight);
+        }
+        self.drawn.push((element, points));
+    }
+
+    fn draw_line(&mut self, points: [egui::Pos2; 2], stroke: Stroke, highlight: Highlight) {
+        self.inner.draw_line(points, stroke, highlight);
+    }
+    fn draw_rectangle(
+        &mut self,
+        rect: egui::Rect,
+        corner_radius: egui::CornerRadius,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner
+            .draw_rectangle(rect, corner_radius, color, stroke, highlight);
+    }
+    fn draw_ellipse(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner
+            .draw_ellipse(position, radius, color, stroke, highlight);
+    }
+    fn draw_ellipse_proximity(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        max_distance: f32,
+        highlight: Highlight,
+    ) {
+        self.inner
+            .draw_ellipse_proximity(position, radius, color, stroke, max_distance, highlight);
+    }
+    fn draw_polygon(
+        &mut self,
+        vertices: Vec<egui::Pos2>,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        self.inner.draw_polygon(vertices, color, stroke, highlight);
+    }
+
+    fn measure_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+    ) -> egui::Rect {
+        self.inner.measure_text(position, anchor, text, font_size)
+    }
+    fn draw_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+        text_color: egui::Color32,
+    ) {
+        self.inner
+            .draw_text(position, anchor, text, font_size, text_color);
+    }
+
+    fn draw_header_text(&mut self, pos: HeaderLocation, text: &str) {
+        self.inner.draw_header_text(pos, text);
+    }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        self.inner.draw_image(rect, image, tile, opacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_jumps_hop_over_crossings() {
+        let line = [egui::Pos2::new(0.0, 0.0), egui::Pos2::new(100.0, 0.0)];
+        let crossing = [egui::Pos2::new(50.0, -20.0), egui::Pos2::new(50.0, 20.0)];
+        let parallel = [egui::Pos2::new(0.0, 10.0), egui::Pos2::new(100.0, 10.0)];
+
+        assert_eq!(
+            line_jump_segments(line, [parallel].into_iter(), 4.0),
+            vec![line]
+        );
+        let segments = line_jump_segments(line, [crossing, parallel].into_iter(), 4.0);
+        assert_eq!(segments.first().map(|e| e[0]), Some(line[0]));
+        assert_eq!(segments.last().map(|e| e[1]), Some(line[1]));
+        assert!(segments.iter().flatten().any(|p| p.y < -3.9));
+        assert!(segments.iter().flatten().all(|p| p.y <= 0.0));
+        for w in segments.windows(2) {
+            assert!(w[0][1].distance(w[1][0]) < 0.01);
+        }
+    }
+
+    #[test]
+    fn hatching_stays_inside_shape() {
+        let rect = egui::Rect::from_min_size(egui::Pos2::new(10.0, 20.0), egui::Vec2::splat(40.0));
+        let outline = rounded_rect_outline(rect, 0.0);
+        let segments = hatch_segments(&outline, egui::Vec2::new(1.0, -1.0).normalized(), 8.0);
+
+        assert!(!segments.is_empty());
+        for [a, b] in segments {
+            assert!(rect.expand(0.01).contains(a) && rect.expand(0.01).contains(b));
+            assert!(((b - a).x - (b - a).y).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn rounded_outline_is_clamped() {
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::new(20.0, 10.0));
+        for p in rounded_rect_outline(rect, 50.0) {
+            assert!(rect.expand(0.01).contains(p));
+        }
+    }
+
+    #[test]
+    fn domain_fills_are_replaced() {
+        const FILL: egui::Color32 = egui::Color32::LIGHT_GRAY;
+        const DARKENED: egui::Color32 = egui::Color32::DARK_GRAY;
+        let profile = ColorProfile::DARK.with_fills(&[(FILL, DARKENED)]);
+
+        assert_eq!(profile.map(FILL), DARKENED);
+        assert_eq!(profile.map(egui::Color32::WHITE), ColorProfile::DARK.fill);
+        assert_eq!(profile.map(egui::Color32::RED), egui::Color32::RED);
+        assert_eq!(ColorProfile::LIGHT.map(FILL), FILL);
+    }
+
+    #[test]
+    fn long_text_is_truncated_or_wrapped() {
+        let measure = |t: &str| t.chars().count() as f32;
+        assert_eq!(fit_text_width("short", 10.0, false, measure), "short");
+        assert_eq!(
+            fit_text_width("a long signature", 0.0, false, measure),
+            "a long signature"
+        );
+        assert_eq!(
+            fit_text_width("a long signature", 8.0, false, measure),
+            "a long…"
+        );
+        assert_eq!(
+            fit_text_width("a long signature here", 8.0, true, measure),
+            "a long\nsignature\nhere"
+        );
+    }
+
+    #[test]
+    fn defaults_resize_only_sized_elements() {
+        let defaults = ElementDefaults {
+            width: Some(150.0),
+            ..Default::default()
+        };
+        let rect =
+            egui::Rect::from_min_size(egui::Pos2::new(10.0, 10.0), egui::Vec2::new(100.0, 50.0));
+        assert_eq!(
+            defaults.resized(rect),
+            Some(egui::Rect::from_min_size(
+                rect.min,
+                egui::Vec2::new(150.0, 50.0)
+            ))
+        );
+        let placed = egui::Rect::from_min_max(rect.min, rect.min);
+        assert_eq!(defaults.resized(placed), None);
+        assert_eq!(ElementDefaults::default().resized(rect), None);
+    }
 }
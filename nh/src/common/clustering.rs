@@ -0,0 +1,173 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClusterMode {
+    #[default]
+    None,
+    StronglyConnected,
+    Connected,
+    Packages,
+}
+
+impl ClusterMode {
+    pub const ALL: [Self; 4] = [
+        Self::None,
+        Self::StronglyConnected,
+        Self::Connected,
+        Self::Packages,
+    ];
+
+    pub fn message_name(&self) -> &'static str {
+        match self {
+            Self::None => "nh-view-clusternodes-none",
+            Self::StronglyConnected => "nh-view-clusternodes-stronglyconnected",
+            Self::Connected => "nh-view-clusternodes-connected",
+            Self::Packages => "nh-view-clusternodes-packages",
+        }
+    }
+}
+
+fn index_graph<T: Copy + Eq + Hash>(nodes: &[T], edges: &[(T, T)]) -> Vec<Vec<usize>> {
+    let indices: HashMap<T, usize> = nodes.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+    let mut adjacency = vec![Vec::new(); nodes.len()];
+    for (a, b) in edges {
+        if let (Some(a), Some(b)) = (indices.get(a), indices.get(b)) {
+            adjacency[*a].push(*b);
+        }
+    }
+    adjacency
+}
+
+fn into_components<T: Copy>(nodes: &[T], mut components: Vec<Vec<usize>>) -> Vec<Vec<T>> {
+    for c in components.iter_mut() {
+        c.sort();
+    }
+    components.sort_by_key(|e| e[0]);
+    components
+        .into_iter()
+        .map(|c| c.into_iter().map(|i| nodes[i]).collect())
+        .collect()
+}
+
+/// Partitions the nodes into strongly connected components of the directed graph.
+///
+/// Components and their members keep the order of `nodes`. Edges to unknown nodes are ignored.
+pub fn strongly_connected_components<T: Copy + Eq + Hash>(
+    nodes: &[T],
+    edges: &[(T, T)],
+) -> Vec<Vec<T>> {
+    let adjacency = index_graph(nodes, edges);
+    let mut index: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut low = vec![0; nodes.len()];
+    let mut on_stack = vec![false; nodes.len()];
+    let mut stack = Vec::new();
+    let mut counter = 0;
+    let mut components = Vec::new();
+
+    for start in 0..nodes.len() {
+        if index[start].is_some() {
+            continue;
+        }
+        index[start] = Some(counter);
+        low[start] = counter;
+        counter += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        let mut call_stack = vec![(start, 0)];
+
+        while let Some((v, next)) = call_stack.last_mut() {
+            let v = *v;
+            if let Some(w) = adjacency[v].get(*next).copied() {
+                *next += 1;
+                match index[w] {
+                    None => {
+                        index[w] = Some(counter);
+                        low[w] = counter;
+                        counter += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        call_stack.push((w, 0));
+                    }
+                    Some(wi) if on_stack[w] => low[v] = low[v].min(wi),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some((u, _)) = call_stack.last() {
+                low[*u] = low[*u].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    into_components(nodes, components)
+}
+
+/// Partitions the nodes into connected components, ignoring the direction of edges.
+///
+/// Components and their members keep the order of `nodes`. Edges to unknown nodes are ignored.
+pub fn connected_components<T: Copy + Eq + Hash>(nodes: &[T], edges: &[(T, T)]) -> Vec<Vec<T>> {
+    let mut adjacency = index_graph(nodes, edges);
+    for a in 0..adjacency.len() {
+        for b in adjacency[a].clone() {
+            adjacency[b].push(a);
+        }
+    }
+
+    let mut visited = vec![false; nodes.len()];
+    let mut components = Vec::new();
+    for start in 0..nodes.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut component = vec![start];
+        let mut i = 0;
+        while let Some(v) = component.get(i).copied() {
+            i += 1;
+            for w in &adjacency[v] {
+                if !visited[*w] {
+                    visited[*w] = true;
+                    component.push(*w);
+                }
+            }
+        }
+        components.push(component);
+    }
+
+    into_components(nodes, components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_form_strongly_connected_components() {
+        let edges = [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)];
+        let components = strongly_connected_components(&[1, 2, 3, 4, 5, 6, 7], &edges);
+        assert_eq!(
+            components,
+            vec![vec![1, 2, 3], vec![4, 5], vec![6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn connected_components_ignore_direction() {
+        let edges = [(2, 1), (3, 2), (5, 4), (1, 99)];
+        let components = connected_components(&[1, 2, 3, 4, 5, 6], &edges);
+        assert_eq!(components, vec![vec![1, 2, 3], vec![4, 5], vec![6]]);
+    }
+}
@@ -1,9 +1,18 @@
 use super::entity::{Entity, EntityUuid};
 use super::eref::ERef;
 use super::project_serde::{NHContextDeserialize, NHContextSerialize};
+use super::ufoption::UFOption;
 use super::uuid::{ModelUuid, ViewUuid};
-use super::views::ordered_views::OrderedViews;
-use crate::common::canvas::{self, Highlight, NHCanvas, NHShape, UiCanvas};
+use super::views::ordered_views::{OrderedViews, ZOrderLock};
+use crate::common::annotations::{Annotation, AnnotationLayer, AnnotationTool};
+use crate::common::canvas::{
+    self, CanvasTheme, ColorProfile, ElementDefaults, ElementStyle, Highlight, LineJumpCanvas,
+    NHCanvas, NHShape, StyledCanvas, ThemedCanvas, UiCanvas,
+};
+use crate::common::clustering::{self, ClusterMode};
+use crate::common::images::{BackgroundImage, ImageElement, ProjectImage};
+use crate::common::layers::{DiagramLayer, DiagramLayers, LayersOverview};
+use crate::common::layout::{self, DiagramLayout, ElementLayout};
 use crate::common::search::FullTextSearchable;
 use crate::common::ui_ext::UiExt;
 use crate::common::uuid::ControllerUuid;
@@ -18,29 +27,38 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::{Arc, RwLock};
 
+/// Guideline coordinate, with the extent of its element along the guideline
+type Guideline = (f32, egui::Align, ViewUuid, egui::Rangef);
+/// Coordinate of the guideline snapped to, with the range its guide is drawn along
+type SnappedGuideline = Option<(f32, egui::Rangef)>;
+
 pub struct SnapManager {
     input_restriction: egui::Rect,
     max_delta: egui::Vec2,
-    guidelines_x: Vec<(f32, egui::Align, ViewUuid)>,
-    guidelines_y: Vec<(f32, egui::Align, ViewUuid)>,
-    best_xy: RwLock<(Option<f32>, Option<f32>)>,
+    guidelines_x: Vec<Guideline>,
+    guidelines_y: Vec<Guideline>,
+    /// Size of the grid dragged elements snap to when not aligned with other elements
+    grid: Option<f32>,
+    best_xy: RwLock<(SnappedGuideline, SnappedGuideline)>,
 }
 
 impl SnapManager {
-    pub fn new(input_restriction: egui::Rect, max_delta: egui::Vec2) -> Self {
+    pub fn new(input_restriction: egui::Rect, max_delta: egui::Vec2, grid: Option<f32>) -> Self {
         Self {
             input_restriction,
             max_delta,
             guidelines_x: Vec::new(),
             guidelines_y: Vec::new(),
+            grid,
             best_xy: RwLock::new((None, None)),
         }
     }
     pub fn add_shape(&mut self, uuid: ViewUuid, shape: canvas::NHShape) {
-        if shape.bounding_box().intersects(self.input_restriction) {
+        let bb = shape.bounding_box();
+        if bb.intersects(self.input_restriction) {
             for e in shape.guidelines_anchors().into_iter() {
-                self.guidelines_x.push((e.0.x, e.1, uuid));
-                self.guidelines_y.push((e.0.y, e.1, uuid));
+                self.guidelines_x.push((e.0.x, e.1, uuid, bb.y_range()));
+                self.guidelines_y.push((e.0.y, e.1, uuid, bb.x_range()));
             }
         }
     }
@@ -49,81 +67,113 @@ impl SnapManager {
         self.guidelines_y.sort_by(|a, b| a.0.total_cmp(&b.0));
     }
 
-    pub fn coerce<F>(&self, s: canvas::NHShape, uuids_filter: F) -> egui::Pos2
+    /// Closest guideline within `max_delta` of any of the coordinates, as the difference,
+    /// the guideline coordinate, and the extent of all the elements aligned to it
+    fn closest<F>(
+        guidelines: &[Guideline],
+        max_delta: f32,
+        coordinates: impl Iterator<Item = f32>,
+        uuids_filter: &F,
+    ) -> Option<(f32, f32, egui::Rangef)>
     where
         F: Fn(&ViewUuid) -> bool,
     {
-        *self.best_xy.write().unwrap() = (None, None);
-        let mut least_x: Option<(f32, f32)> = None;
-        let mut least_y: Option<(f32, f32)> = None;
-        let center = s.center();
-
-        // Naive guidelines coordinate matching
-        for p in s.guidelines_anchors().into_iter() {
-            let start_x = self
-                .guidelines_x
-                .binary_search_by(|probe| probe.0.total_cmp(&(p.0.x - self.max_delta.x)))
-                .unwrap_or_else(|e| e);
-            let end_x = self
-                .guidelines_x
-                .binary_search_by(|probe| probe.0.total_cmp(&(p.0.x + self.max_delta.x)))
-                .unwrap_or_else(|e| e);
-            for g in self.guidelines_x[start_x..end_x]
-                .iter()
-                .filter(|e| uuids_filter(&e.2))
-            {
-                if least_x.is_none_or(|b| (p.0.x - g.0).abs() < b.0.abs()) {
-                    least_x = Some((p.0.x - g.0, g.0));
-                }
-            }
-            let start_y = self
-                .guidelines_y
-                .binary_search_by(|probe| probe.0.total_cmp(&(p.0.y - self.max_delta.y)))
+        let mut least: Option<(f32, f32, egui::Rangef)> = None;
+        for p in coordinates {
+            let start = guidelines
+                .binary_search_by(|probe| probe.0.total_cmp(&(p - max_delta)))
                 .unwrap_or_else(|e| e);
-            let end_y = self
-                .guidelines_y
-                .binary_search_by(|probe| probe.0.total_cmp(&(p.0.y + self.max_delta.y)))
+            let end = guidelines
+                .binary_search_by(|probe| probe.0.total_cmp(&(p + max_delta)))
                 .unwrap_or_else(|e| e);
-            for g in self.guidelines_y[start_y..end_y]
-                .iter()
-                .filter(|e| uuids_filter(&e.2))
-            {
-                if least_y.is_none_or(|b| (p.0.y - g.0).abs() < b.0.abs()) {
-                    least_y = Some((p.0.y - g.0, g.0));
+            for g in guidelines[start..end].iter().filter(|e| uuids_filter(&e.2)) {
+                match least.as_mut() {
+                    Some(b) if (g.0 - b.1).abs() < f32::EPSILON => {
+                        b.2 = egui::Rangef::new(b.2.min.min(g.3.min), b.2.max.max(g.3.max));
+                    }
+                    Some(b) if (p - g.0).abs() >= b.0.abs() => {}
+                    _ => least = Some((p - g.0, g.0, g.3)),
                 }
             }
         }
+        least.filter(|e| e.0.abs() < max_delta)
+    }
 
+    pub fn coerce<F>(&self, s: canvas::NHShape, uuids_filter: F) -> egui::Pos2
+    where
+        F: Fn(&ViewUuid) -> bool,
+    {
+        *self.best_xy.write().unwrap() = (None, None);
+        let center = s.center();
+        let anchors = s.guidelines_anchors();
+
+        // Naive guidelines coordinate matching
         // TODO: try pairwise projection of guidelines with matching Align
+        let least_x = Self::closest(
+            &self.guidelines_x,
+            self.max_delta.x,
+            anchors.iter().map(|e| e.0.x),
+            &uuids_filter,
+        );
+        let least_y = Self::closest(
+            &self.guidelines_y,
+            self.max_delta.y,
+            anchors.iter().map(|e| e.0.y),
+            &uuids_filter,
+        );
 
-        least_x = least_x.filter(|e| e.0.abs() < self.max_delta.x);
-        least_y = least_y.filter(|e| e.0.abs() < self.max_delta.y);
-        *self.best_xy.write().unwrap() = (least_x.map(|e| e.1), least_y.map(|e| e.1));
-        egui::Pos2::new(
-            center.x - least_x.map(|e| e.0).unwrap_or(0.0),
-            center.y - least_y.map(|e| e.0).unwrap_or(0.0),
-        )
+        // Without a guide, the top left corner snaps to the grid
+        let bb = s.bounding_box();
+        let to_grid = |p: f32| {
+            self.grid
+                .filter(|e| *e > 0.0)
+                .map_or(0.0, |e| p - (p / e).round() * e)
+        };
+        let delta = egui::Vec2::new(
+            least_x.map_or_else(|| to_grid(bb.min.x), |e| e.0),
+            least_y.map_or_else(|| to_grid(bb.min.y), |e| e.0),
+        );
+
+        // Guides span from the coerced shape to the elements it is aligned with
+        let coerced = bb.translate(-delta);
+        let span = |e: egui::Rangef, own: egui::Rangef| {
+            egui::Rangef::new(e.min.min(own.min), e.max.max(own.max))
+        };
+        *self.best_xy.write().unwrap() = (
+            least_x.map(|e| (e.1, span(e.2, coerced.y_range()))),
+            least_y.map(|e| (e.1, span(e.2, coerced.x_range()))),
+        );
+        center - delta
+    }
+
+    /// Position of a placed element, snapped to the nearest grid point
+    pub fn snap_to_grid(&self, pos: egui::Pos2) -> egui::Pos2 {
+        match self.grid.filter(|e| *e > 0.0) {
+            Some(size) => (pos / size).round() * size,
+            None => pos,
+        }
     }
 
     pub fn draw_best(&self, canvas: &mut dyn NHCanvas, color: egui::Color32, rect: egui::Rect) {
+        const OVERHANG: f32 = 10.0;
         let (best_x, best_y) = *self.best_xy.read().unwrap();
-        if let Some(bx) = best_x {
+        if let Some((bx, span)) = best_x {
             canvas.draw_line(
                 [
-                    egui::Pos2::new(bx, rect.min.y),
-                    egui::Pos2::new(bx, rect.max.y),
+                    egui::Pos2::new(bx, (span.min - OVERHANG).max(rect.min.y)),
+                    egui::Pos2::new(bx, (span.max + OVERHANG).min(rect.max.y)),
                 ],
-                canvas::Stroke::new_solid(1.0, color),
+                canvas::Stroke::new_dashed(1.0, color),
                 canvas::Highlight::NONE,
             );
         }
-        if let Some(by) = best_y {
+        if let Some((by, span)) = best_y {
             canvas.draw_line(
                 [
-                    egui::Pos2::new(rect.min.x, by),
-                    egui::Pos2::new(rect.max.x, by),
+                    egui::Pos2::new((span.min - OVERHANG).max(rect.min.x), by),
+                    egui::Pos2::new((span.max + OVERHANG).min(rect.max.x), by),
                 ],
-                canvas::Stroke::new_solid(1.0, color),
+                canvas::Stroke::new_dashed(1.0, color),
                 canvas::Highlight::NONE,
             );
         }
@@ -137,11 +187,52 @@ impl Default for SnapManager {
             max_delta: egui::Vec2::ZERO,
             guidelines_x: Vec::new(),
             guidelines_y: Vec::new(),
+            grid: None,
             best_xy: RwLock::new((None, None)),
         }
     }
 }
 
+/// Grid of a diagram view, drawn while editing and in exported images when requested
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DiagramGrid {
+    pub size: f32,
+    pub visible: bool,
+    /// Dragged elements snap their top left corner to the grid,
+    /// resize handles and placed elements their position
+    pub snap: bool,
+}
+
+impl DiagramGrid {
+    pub const DEFAULT_SIZE: f32 = 50.0;
+
+    pub fn snap_size(&self) -> Option<f32> {
+        self.snap.then_some(self.size)
+    }
+
+    /// Edited copy of the grid, to be applied as a command when it differs
+    pub fn show_properties(&self, gdc: &GlobalDrawingContext, ui: &mut egui::Ui) -> Option<Self> {
+        let mut grid = self.clone();
+        ui.label(gdc.translate_0("nh-grid"));
+        ui.add(
+            egui::Slider::new(&mut grid.size, 5.0..=200.0).text(gdc.translate_0("nh-grid-size")),
+        );
+        ui.checkbox(&mut grid.visible, gdc.translate_0("nh-grid-visible"));
+        ui.checkbox(&mut grid.snap, gdc.translate_0("nh-grid-snap"));
+        (grid != *self).then_some(grid)
+    }
+}
+
+impl Default for DiagramGrid {
+    fn default() -> Self {
+        Self {
+            size: Self::DEFAULT_SIZE,
+            visible: true,
+            snap: false,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum ProjectCommand {
     SimpleProjectCommand(SimpleProjectCommand),
@@ -156,10 +247,50 @@ pub enum ProjectCommand {
         ERef<dyn DiagramController>,
     ),
     DeleteDiagram(ViewUuid),
+    /// Sets or unsets the diagram as the one new diagrams of its type are copied from
+    SetDiagramTemplate(ViewUuid, bool),
+    /// Records that the first diagram was duplicated from the second one
+    MarkDiagramVariant(ViewUuid, ViewUuid),
+    /// Models changed outside of a diagram, views showing them need to be refreshed
+    RefreshModels(Vec<ModelUuid>),
+    AddTodo(ModelUuid),
+    AddExternalLink(ModelUuid),
+    AddIssueLink(ModelUuid),
+    /// Asks for the diagram double-clicking the element drills down into
+    LinkSubDiagram(ModelUuid),
+    SetSubDiagram(ModelUuid, Option<ViewUuid>),
+    /// Asks for the target of the hyperlink on the element view in the diagram
+    LinkElement(ViewUuid, ViewUuid),
+    SetElementLink(ViewUuid, ViewUuid, Option<ElementLink>),
+    AddLayer(ViewUuid, String),
+    RenameLayer(ViewUuid, uuid::Uuid, String),
+    /// Asks for an image file and uses it as the background of the diagram
+    SetBackgroundImage(ViewUuid),
+    /// Asks for an image file and places it in the diagram at the position
+    AddImageElement(ViewUuid, egui::Pos2),
+    /// Places the image from the system clipboard in the diagram at the position
+    PasteImageElement(ViewUuid, egui::Pos2),
+    /// Places the library symbol in the diagram at the position, or in the last focused diagram
+    PlaceSymbol(uuid::Uuid, Option<(ViewUuid, egui::Pos2)>),
+    ApplyLayout(ViewUuid, DiagramLayout),
+    /// Applies the property change, serialized as JSON, to the elements with the given models
+    /// in the diagram, or to the selected elements if none are given
+    ApplyPropertyChange(ViewUuid, Option<Vec<ModelUuid>>, Arc<String>),
+    /// Asks for a folder and writes the files, given by names and contents, into it
+    WriteFiles(Vec<(String, String)>),
 
     AddNewDocument(ViewUuid, String),
+    /// Asks for Markdown or text files and adds them as documents
+    ImportDocuments,
     DuplicateDocument(ViewUuid),
     DeleteDocument(ViewUuid),
+    /// Adds an element named after the characters of the document to the last focused diagram,
+    /// replacing them with a link to the element
+    PromoteToElement(ViewUuid, std::ops::Range<usize>),
+    /// Asks for a file and exports the diagram into it as an interactive HTML page
+    ExportInteractiveHtml(ViewUuid),
+    /// Asks for a file and writes Markdown documentation of the whole project into it
+    GenerateDocumentation,
 }
 
 impl From<SimpleProjectCommand> for ProjectCommand {
@@ -179,6 +310,7 @@ pub enum SimpleProjectCommand {
     Exit(bool),
     SwapTopLanguages,
     CycleShadesProfiles,
+    CopyAsImage { selected_only: bool },
 }
 
 impl From<DiagramCommand> for SimpleProjectCommand {
@@ -197,8 +329,34 @@ pub enum DiagramCommand {
     DeleteSelectedElements(Option<DeleteKind>),
     CutSelectedElements,
     CopySelectedElements,
+    /// Copies an SVG fragment of only the selected elements as text
+    CopySelectedAsSvg,
+    /// Copies PlantUML description of only the selected elements, if the diagram supports it
+    CopySelectedAsPlantUml,
+    /// Copies positions and sizes of all elements, keyed by their models, as JSON
+    CopyLayoutAsJson,
+    /// Asks for a layout copied by `CopyLayoutAsJson` and applies it
+    ApplyLayoutFromJson,
     PasteClipboardElements(Option<ModelUuid>, Option<(i64, i64)>),
     ArrangeSelected(Arrangement),
+    /// Resizes selected elements to the size of the last selected one
+    MatchSizeSelected(SizeMatch),
+    /// Moves a top-level view to the given position of the draw order, 0 being the back
+    MoveInDrawOrder(ViewUuid, usize),
+    LockInDrawOrder(ViewUuid, Option<ZOrderLock>),
+    SetLayerVisible(uuid::Uuid, bool),
+    SetLayerLocked(uuid::Uuid, bool),
+    RemoveLayer(uuid::Uuid),
+    /// Moves a layer to the given position, 0 being the back
+    MoveLayer(uuid::Uuid, usize),
+    /// Moves selected top-level views to the layer, None being the default layer
+    AssignSelectedToLayer(Option<uuid::Uuid>),
+    SetAnnotationsVisible(bool),
+    /// Locked views can be selected, but not moved or resized
+    SetElementLocked(ViewUuid, bool),
+    LockSelectedElements(bool),
+    /// Replaces the background image, keeping its placement, or removes it
+    SetBackgroundImage(Option<uuid::Uuid>),
     ColorSelected(u8, MGlobalColor),
     HighlightAllElements(/*set: */ bool, Highlight),
     HighlightElement(EntityUuid, /*set: */ bool, Highlight),
@@ -207,9 +365,59 @@ pub enum DiagramCommand {
     ResetPosition,
     AddZoomPercent(i64),
     ResetScale,
-    ZoomToFit { selected_only: bool },
+    ZoomToFit {
+        selected_only: bool,
+    },
     CreateViewFor(ModelUuid),
     DeleteViewFor(ModelUuid, /*including_model:*/ bool),
+    /// Adds a child of the only selected element, if the diagram supports it
+    AddChildElement,
+    /// Adds a sibling of the only selected element, if the diagram supports it
+    AddSiblingElement,
+    /// Arranges top-level elements into trees following their connections
+    LayoutAsTree {
+        radial: bool,
+    },
+    /// Arranges top-level elements into layers so that their connections point down, or up
+    LayoutLayered {
+        upward: bool,
+    },
+    LiveLayout(LiveLayoutCommand),
+}
+
+/// Control of the live force-directed layout, which moves elements without recording them
+/// until it is frozen
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+pub enum LiveLayoutCommand {
+    Start,
+    /// Stops the layout, committing the positions as a single undoable command
+    Freeze,
+    /// Stops the layout, returning the elements to their positions before it was started
+    Discard,
+}
+
+impl LiveLayoutCommand {
+    /// Menu items controlling the live layout of the given view, for diagrams laid out as graphs
+    pub fn show_menu(view_uuid: &ViewUuid, ui: &mut egui::Ui, commands: &mut Vec<ProjectCommand>) {
+        ui.menu_button("Force-directed layout", |ui| {
+            for (label, c) in [
+                ("▶ Start live preview", Self::Start),
+                ("❄ Freeze", Self::Freeze),
+                ("✖ Discard", Self::Discard),
+            ] {
+                if ui.button(label).clicked() {
+                    commands.push(
+                        SimpleProjectCommand::SpecificDiagramCommand(
+                            *view_uuid,
+                            DiagramCommand::LiveLayout(c),
+                        )
+                        .into(),
+                    );
+                    ui.close();
+                }
+            }
+        });
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
@@ -220,6 +428,13 @@ pub enum Arrangement {
     SendToBack,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+pub enum SizeMatch {
+    Width,
+    Height,
+    Both,
+}
+
 pub enum HierarchyNode {
     Folder(
         ViewUuid,
@@ -375,7 +590,7 @@ pub fn mglobalcolor_edit_button(
                 );
                 ui.label(color.to_hex());
             }
-            MGlobalColor::Global(uuid) => match gdc.global_colors.colors.get(uuid) {
+            MGlobalColor::Global(uuid) => match gdc.global_colors.resolve(uuid) {
                 None => {
                     painter.rect(
                         response.rect,
@@ -541,6 +756,8 @@ pub fn mglobalcolor_edit_button(
 pub struct ColorBundle {
     pub colors_order: Vec<uuid::Uuid>,
     pub colors: HashMap<uuid::Uuid, (String, egui::Color32)>,
+    /// Colors merged into other colors, so that elements using them keep following the palette
+    pub aliases: HashMap<uuid::Uuid, uuid::Uuid>,
 }
 
 impl ColorBundle {
@@ -548,28 +765,77 @@ impl ColorBundle {
         Self {
             colors_order: Vec::new(),
             colors: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
+    pub fn resolve(&self, uuid: &uuid::Uuid) -> Option<&(String, egui::Color32)> {
+        self.colors
+            .get(uuid)
+            .or_else(|| self.colors.get(self.aliases.get(uuid)?))
+    }
     pub fn get(&self, c: &MGlobalColor) -> Option<egui::Color32> {
         match c {
             MGlobalColor::None => None,
             MGlobalColor::Local(color32) => Some(*color32),
-            MGlobalColor::Global(uuid) => self.colors.get(uuid).map(|e| e.1),
+            MGlobalColor::Global(uuid) => self.resolve(uuid).map(|e| e.1),
+        }
+    }
+    /// Removes the color from the palette, reassigning all its uses to the other color
+    pub fn merge(&mut self, from: uuid::Uuid, into: uuid::Uuid) {
+        if from == into || !self.colors.contains_key(&into) {
+            return;
+        }
+        self.colors_order.retain(|e| *e != from);
+        self.colors.remove(&from);
+        for v in self.aliases.values_mut() {
+            if *v == from {
+                *v = into;
+            }
+        }
+        self.aliases.insert(from, into);
+    }
+    pub fn move_color(&mut self, from: usize, to: usize) {
+        if from < self.colors_order.len() && to < self.colors_order.len() {
+            let id = self.colors_order.remove(from);
+            self.colors_order.insert(to, id);
         }
     }
     pub fn clear(&mut self) {
         self.colors_order.clear();
         self.colors.clear();
+        self.aliases.clear();
     }
 }
 
 pub const TOOL_PALETTE_MIN_HEIGHT: u32 = 15;
 pub const TOOL_PALETTE_MAX_HEIGHT: u32 = 200;
+/// Below this item height previews are unreadable, so only names are shown
+const TOOL_PALETTE_PREVIEW_MIN_HEIGHT: f32 = 24.0;
+/// Size of the preview in the tooltip of a palette item
+const TOOL_PALETTE_TOOLTIP_SIZE: f32 = 160.0;
 pub struct GlobalDrawingContext {
     pub global_colors: ColorBundle,
+    pub symbols: crate::common::symbols::SymbolLibrary,
+    pub images: crate::common::images::ImageLibrary,
+    pub abbreviations: crate::common::abbreviations::AbbreviationDictionary,
+    /// Elements whose linked external files changed after their last edit
+    pub outdated_models: HashSet<ModelUuid>,
+    /// Elements with linked issues
+    pub issue_linked_models: HashSet<ModelUuid>,
     pub fluent_bundle: fluent_bundle::FluentBundle<fluent_bundle::FluentResource>,
+    /// Preferred languages, used both for the UI and for translated model content
+    pub languages_order: Vec<unic_langid::LanguageIdentifier>,
     pub shortcuts: HashMap<SimpleProjectCommand, egui::KeyboardShortcut>,
     pub tool_palette_item_height: u32,
+    /// Whether camera jumps and applied layouts are shown as short animations
+    pub animate_transitions: bool,
+    /// Distance in screen points within which dragged elements snap to alignment guides,
+    /// 0 disables snapping
+    pub snap_tolerance: f32,
+    /// Whether the application uses a dark theme, which diagrams follow by default
+    pub dark_mode: bool,
+    /// Appearance of newly placed elements, by diagram type
+    pub element_defaults: HashMap<String, ElementDefaults>,
     pub model_labels: LabelProvider,
 }
 
@@ -629,6 +895,10 @@ impl LabelProvider {
     pub fn insert(&mut self, uuid: ModelUuid, label: Arc<String>) {
         self.labels.insert(uuid, label);
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ModelUuid, &Arc<String>)> {
+        self.labels.iter()
+    }
 }
 
 pub trait View: Entity {
@@ -676,6 +946,8 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
         key: egui::Key,
     );
 
+    /// Appearance defaults of the diagram type, applied to elements placed by tools
+    fn set_element_defaults(&mut self, defaults: ElementDefaults);
     fn new_ui_canvas(
         &mut self,
         context: &GlobalDrawingContext,
@@ -690,6 +962,13 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
         canvas: &mut dyn NHCanvas,
         mouse_pos: Option<egui::Pos2>,
     );
+    /// Draws only the selected elements, along with their contents
+    fn draw_selected_in(
+        &mut self,
+        context: &GlobalDrawingContext,
+        settings: &dyn DiagramSettings,
+        canvas: &mut dyn NHCanvas,
+    );
 
     fn show_context_menu(
         &mut self,
@@ -699,6 +978,50 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
         commands: &mut Vec<ProjectCommand>,
     );
     fn unset_context_menu(&mut self);
+    /// Returns the model of the topmost element under the pointer
+    fn model_at_pointer(&self, ui: &egui::Ui, response: &egui::Response) -> Option<ModelUuid>;
+    /// Returns the hyperlink of the topmost element under the pointer, if it has one
+    fn element_link_at_pointer(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ElementLink>;
+    fn element_link(&self, view: &ViewUuid) -> Option<ElementLink>;
+    fn set_element_link(&mut self, view: ViewUuid, link: Option<ElementLink>);
+    fn add_layer(&mut self, name: String);
+    fn rename_layer(&mut self, layer: &uuid::Uuid, name: String);
+    fn element_category(&self, model: &ModelUuid) -> Option<ElementCategory>;
+    /// Shows a name editor over the element, returns false if it cannot be renamed in place
+    fn start_inline_rename(&mut self, model: &ModelUuid) -> bool;
+    /// Returns models of all selected elements
+    fn selected_models(&self) -> Vec<ModelUuid>;
+    /// Returns the area covered by all selected elements, if any are selected
+    fn selection_bounds(&self) -> Option<egui::Rect>;
+    /// Returns top-level views from the back to the front, with their models and locks
+    fn draw_order(&self) -> Vec<(ViewUuid, ModelUuid, Option<ZOrderLock>)>;
+    fn layers(&self) -> LayersOverview;
+    /// Forgets what is kept for views which are gone, unless undo or redo can restore them
+    fn prune_view_state(&mut self, restorable: &HashSet<ViewUuid>);
+    /// Returns structural problems of the views, such as connections to views which are not present
+    fn check_problems(&self) -> Vec<CheckProblem>;
+    /// Returns the images shown by the diagram, both in the background and as image elements
+    fn referenced_images(&self) -> Vec<uuid::Uuid>;
+    /// Places the image at the position, or near the top left corner of the visible area
+    fn add_image_element(&mut self, image: &ProjectImage, position: Option<egui::Pos2>);
+    /// Places the library symbol the same way as an image
+    fn add_symbol_element(
+        &mut self,
+        symbol: uuid::Uuid,
+        view_box: egui::Rect,
+        position: Option<egui::Pos2>,
+    );
+    /// Returns positions and sizes of all elements and vertices of connections
+    fn layout(&self) -> DiagramLayout;
+    /// Shows elements moving from the previous layout to their current positions
+    fn animate_layout_from(&mut self, previous: &DiagramLayout);
+    fn grid(&self) -> DiagramGrid;
+    /// Background and gridlines colors, according to the color profile of the view
+    fn canvas_colors(&self, context: &GlobalDrawingContext) -> (egui::Color32, egui::Color32);
 
     fn show_toolbar(
         &mut self,
@@ -724,6 +1047,8 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
         settings: &dyn DiagramSettings,
         ui: &mut egui::Ui,
     );
+    /// Exchanges the current camera with the given one, see [`DiagramCamera`]
+    fn swap_camera(&mut self, camera: &mut DiagramCamera);
     fn show_menubar_edit_options(
         &mut self,
         context: &GlobalDrawingContext,
@@ -755,6 +1080,47 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
             DomainT::PropChangeT,
         >,
     >;
+    /// Returns commands moving and resizing elements present in the layout to match it
+    fn layout_to_sensitives(
+        &mut self,
+        layout: &DiagramLayout,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    >;
+    /// Removes locked views from commands moving, resizing, deleting or reordering them
+    fn without_locked(
+        &self,
+        commands: Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    >;
+    /// Returns commands adding a new element with the given model uuid and name,
+    /// empty if the diagram has no such element
+    fn named_element_to_sensitives(
+        &mut self,
+        model: ModelUuid,
+        name: &str,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    >;
     fn apply_command(
         &mut self,
         command: &InsensitiveCommand<
@@ -782,6 +1148,29 @@ pub trait DiagramView2<DomainT: Domain>: DiagramView {
     fn shallow_copy(&self) -> ERef<Self>;
 }
 
+/// Inconsistency found by checking a diagram, e.g. by `nh --check`
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize)]
+pub struct CheckProblem {
+    pub view: Option<ViewUuid>,
+    pub model: Option<ModelUuid>,
+    pub message: String,
+}
+
+/// Element of a diagram model as listed in generated documentation
+#[derive(Clone, Debug)]
+pub struct ElementDescription {
+    pub model: ModelUuid,
+    pub stereotype: Arc<String>,
+    pub comment: Arc<String>,
+}
+
+/// Textual format a diagram model can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextExportFormat {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
 pub trait DiagramController: Any + NHContextSerialize {
     fn uuid(&self) -> Arc<ControllerUuid>;
     fn model_uuid(&self) -> Arc<ModelUuid>;
@@ -842,6 +1231,13 @@ pub trait DiagramController: Any + NHContextSerialize {
         canvas: &mut dyn NHCanvas,
         mouse_pos: Option<egui::Pos2>,
     );
+    fn draw_selected_in(
+        &mut self,
+        uuid: &ViewUuid,
+        context: &GlobalDrawingContext,
+        settings: &dyn DiagramSettings,
+        canvas: &mut dyn NHCanvas,
+    );
 
     fn show_context_menu(
         &mut self,
@@ -853,6 +1249,88 @@ pub trait DiagramController: Any + NHContextSerialize {
         affected_models: &mut HashSet<ModelUuid>,
     );
     fn unset_context_menu(&mut self, uuid: &ViewUuid);
+    /// Returns the view the element under the pointer decomposes into, and whether it was newly created
+    fn drill_down(
+        &mut self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<(ViewUuid, bool)>;
+    fn model_at_pointer(
+        &self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ModelUuid>;
+    fn element_link_at_pointer(
+        &self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ElementLink>;
+    fn element_link(&self, uuid: &ViewUuid, view: &ViewUuid) -> Option<ElementLink>;
+    fn set_element_link(&mut self, uuid: &ViewUuid, view: ViewUuid, link: Option<ElementLink>);
+    fn add_layer(&mut self, uuid: &ViewUuid, name: String);
+    fn rename_layer(&mut self, uuid: &ViewUuid, layer: &uuid::Uuid, name: String);
+    fn element_category(&self, uuid: &ViewUuid, model: &ModelUuid) -> Option<ElementCategory>;
+    fn start_inline_rename(&mut self, uuid: &ViewUuid, model: &ModelUuid) -> bool;
+    fn selected_models(&self, uuid: &ViewUuid) -> Vec<ModelUuid>;
+    fn selection_bounds(&self, uuid: &ViewUuid) -> Option<egui::Rect>;
+    fn draw_order(&self, uuid: &ViewUuid) -> Vec<(ViewUuid, ModelUuid, Option<ZOrderLock>)>;
+    fn layers(&self, uuid: &ViewUuid) -> LayersOverview;
+    /// Forgets what is kept for deleted views, before the project is saved
+    fn prune_view_state(&mut self);
+    /// Returns problems of both the view and the model
+    fn check_problems(&self, uuid: &ViewUuid) -> Vec<CheckProblem>;
+    /// Returns all elements of the model in hierarchy order
+    fn element_descriptions(&self) -> Vec<ElementDescription>;
+    fn referenced_images(&self, uuid: &ViewUuid) -> Vec<uuid::Uuid>;
+    fn add_image_element(
+        &mut self,
+        uuid: &ViewUuid,
+        image: &ProjectImage,
+        position: Option<egui::Pos2>,
+    );
+    fn add_symbol_element(
+        &mut self,
+        uuid: &ViewUuid,
+        symbol: uuid::Uuid,
+        view_box: egui::Rect,
+        position: Option<egui::Pos2>,
+    );
+    fn layout(&self, uuid: &ViewUuid) -> DiagramLayout;
+    fn grid(&self, uuid: &ViewUuid) -> DiagramGrid;
+    fn canvas_colors(
+        &self,
+        uuid: &ViewUuid,
+        context: &GlobalDrawingContext,
+    ) -> (egui::Color32, egui::Color32);
+    /// Moves elements of the view to match the layout as a single undoable command
+    fn apply_layout(
+        &mut self,
+        uuid: &ViewUuid,
+        layout: &DiagramLayout,
+        affected_models: &mut HashSet<ModelUuid>,
+    );
+    /// Adds the element text promoted from a document becomes, returns false when
+    /// the diagram has no such element
+    fn add_named_element(
+        &mut self,
+        uuid: &ViewUuid,
+        model: ModelUuid,
+        name: &str,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) -> bool;
+    /// Applies the property change, serialized as JSON, to the views of the models as a single
+    /// undoable command, or to the selected views if no models are given.
+    /// Returns false when it is not a property change of this diagram or there is nothing to change.
+    fn apply_property_change(
+        &mut self,
+        uuid: &ViewUuid,
+        models: Option<&[ModelUuid]>,
+        change: &str,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) -> bool;
 
     fn show_toolbar(
         &mut self,
@@ -861,12 +1339,15 @@ pub trait DiagramController: Any + NHContextSerialize {
         settings: &dyn DiagramSettings,
         ui: &mut egui::Ui,
     );
+    /// Shows properties of the selection, pushing the property changes made, serialized as JSON,
+    /// into `property_changes`
     fn show_properties(
         &mut self,
         uuid: &ViewUuid,
         context: &GlobalDrawingContext,
         ui: &mut egui::Ui,
         affected_models: &mut HashSet<ModelUuid>,
+        property_changes: &mut Vec<String>,
     ) -> Option<Box<dyn CustomModal>>;
     fn show_outline(
         &mut self,
@@ -875,6 +1356,7 @@ pub trait DiagramController: Any + NHContextSerialize {
         settings: &dyn DiagramSettings,
         ui: &mut egui::Ui,
     );
+    fn swap_camera(&mut self, uuid: &ViewUuid, camera: &mut DiagramCamera);
     fn show_menubar_edit_options(
         &mut self,
         uuid: &ViewUuid,
@@ -935,6 +1417,23 @@ pub trait DiagramController: Any + NHContextSerialize {
         ui: &mut egui::Ui,
         uuid: &ViewUuid,
     ) -> Option<(ViewUuid, Option<ERef<dyn DiagramController>>)>;
+    /// Deep copy of the view into a new controller with new models
+    fn duplicate_view(&self, uuid: &ViewUuid) -> (ViewUuid, ERef<dyn DiagramController>);
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat];
+    /// Translated model content is exported in the first available of `languages`
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String>;
+    /// Exports only the elements selected in the view, if the format supports it
+    fn export_selected_text(
+        &self,
+        uuid: &ViewUuid,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String>;
 
     fn full_text_search(&self, acc: &mut crate::common::search::Searcher);
 }
@@ -968,19 +1467,112 @@ pub enum DeleteKind {
     DeleteAll,
 }
 
-#[derive(Clone, Copy)]
+/// Kinds of elements double-clicking can be configured for
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ElementCategory {
+    Element,
+    /// Element containing other elements, such as a package
+    Container,
+    Connection,
+}
+
+impl ElementCategory {
+    pub const ALL: [Self; 3] = [Self::Element, Self::Container, Self::Connection];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ElementCategory::Element => "Elements",
+            ElementCategory::Container => "Containers",
+            ElementCategory::Connection => "Connections",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum DoubleClickAction {
+    OpenProperties,
+    InlineRename,
+    /// Opens the diagram the element decomposes into
+    #[default]
+    DrillDown,
+    /// Opens the file linked to the element, or a document linking to it
+    FollowLink,
+}
+
+impl DoubleClickAction {
+    pub const ALL: [Self; 4] = [
+        Self::OpenProperties,
+        Self::InlineRename,
+        Self::DrillDown,
+        Self::FollowLink,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DoubleClickAction::OpenProperties => "Open properties",
+            DoubleClickAction::InlineRename => "Rename in place",
+            DoubleClickAction::DrillDown => "Drill down",
+            DoubleClickAction::FollowLink => "Follow link",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct DoubleClickActions {
+    pub element: DoubleClickAction,
+    pub container: DoubleClickAction,
+    pub connection: DoubleClickAction,
+}
+
+impl DoubleClickActions {
+    pub fn get_mut(&mut self, category: ElementCategory) -> &mut DoubleClickAction {
+        match category {
+            ElementCategory::Element => &mut self.element,
+            ElementCategory::Container => &mut self.container,
+            ElementCategory::Connection => &mut self.connection,
+        }
+    }
+    pub fn get(&self, category: ElementCategory) -> DoubleClickAction {
+        match category {
+            ElementCategory::Element => self.element,
+            ElementCategory::Container => self.container,
+            ElementCategory::Connection => self.connection,
+        }
+    }
+}
+
+/// Target of a hyperlink on an element view
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ElementLink {
+    Diagram(ViewUuid),
+    Document(ViewUuid),
+    Url(String),
+}
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct ModifierSettings {
     pub default_delete_kind: Option<DeleteKind>,
     pub delete_view_modifier: Option<ModifierKeys>,
     pub delete_model_if_modifier: Option<ModifierKeys>,
     pub delete_all_modifier: Option<ModifierKeys>,
+    #[serde(skip)]
     sorted_delete_kinds: [(Option<ModifierKeys>, DeleteKind); 3],
 
     pub hold_selection: Option<ModifierKeys>,
     pub alternative_tool_mode: Option<ModifierKeys>,
+
+    #[serde(default)]
+    pub double_click_actions: DoubleClickActions,
+    /// Held while double-clicking an element to follow its hyperlink instead
+    #[serde(default = "ModifierSettings::default_follow_element_link")]
+    pub follow_element_link: Option<ModifierKeys>,
 }
 
 impl ModifierSettings {
+    fn default_follow_element_link() -> Option<ModifierKeys> {
+        Some(ModifierKeys::COMMAND)
+    }
+
     pub fn sort_delete_kinds(&mut self) {
         self.sorted_delete_kinds[0] = (self.delete_view_modifier, DeleteKind::DeleteView);
         self.sorted_delete_kinds[1] = (
@@ -1015,11 +1607,14 @@ impl Default for ModifierSettings {
 
             hold_selection: Some(ModifierKeys::COMMAND),
             alternative_tool_mode: Some(ModifierKeys::ALT),
+
+            double_click_actions: Default::default(),
+            follow_element_link: Self::default_follow_element_link(),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ModifierKeys {
     pub alt: bool,
     pub command: bool, // mac_cmd || win_ctrl || linux_ctrl
@@ -1680,6 +2275,10 @@ pub enum InsensitiveCommand<
         including_model: bool,
     },
     PropertyChange(HashSet<ViewUuid>, PropChangeT),
+    /// Kept by the diagram, the default style removes the override
+    ElementStyleChange(HashSet<ViewUuid>, ElementStyle),
+    /// Kept by the diagram like element styles
+    GridChange(DiagramGrid),
     Macro(Arc<String>, usize, Arc<Vec<Self>>),
 }
 
@@ -1689,6 +2288,47 @@ impl<
     PropChangeT: TryMerge + Clone + Debug,
 > InsensitiveCommand<OrdinalMovementT, AddElementT, PropChangeT>
 {
+    /// Views the command refers to, including those of added elements listed by `added_views`
+    fn referenced_views<F>(&self, added_views: &F, into: &mut HashSet<ViewUuid>)
+    where
+        F: Fn(&AddElementT, &mut HashSet<ViewUuid>),
+    {
+        match self {
+            Self::HighlightAll(..)
+            | Self::SelectByDrag(..)
+            | Self::MovePositionalAll(..)
+            | Self::GridChange(..) => {}
+            Self::HighlightSpecific(set, ..)
+            | Self::MovePositional(set, _)
+            | Self::MoveOrdinal(set, _)
+            | Self::ResizeElementsBy(set, ..)
+            | Self::DeleteSpecificElements(set, _)
+            | Self::ArrangeSpecificElements(set, _)
+            | Self::PropertyChange(set, _)
+            | Self::ElementStyleChange(set, _) => into.extend(set.iter().copied()),
+            Self::ResizeElementTo(view, _) => {
+                into.insert(*view);
+            }
+            Self::AddDependency {
+                target, element, ..
+            } => {
+                into.insert(*target);
+                added_views(element, into);
+            }
+            Self::RemoveDependency {
+                target, element, ..
+            } => {
+                into.insert(*target);
+                into.insert(*element);
+            }
+            Self::Macro(_, _, cmds) => {
+                for e in cmds.iter() {
+                    e.referenced_views(added_views, into);
+                }
+            }
+        }
+    }
+
     fn info_text<F, T>(&self, gdc: &GlobalDrawingContext, diagram_name: &str, f: F) -> T
     where
         F: FnOnce(&str) -> T,
@@ -1741,10 +2381,12 @@ impl<
                     (gdc.get_message("nh-viewcommand-removeelementsfrom"), 1)
                 }
             }
-            InsensitiveCommand::PropertyChange(uuids, ..) => (
+            InsensitiveCommand::PropertyChange(uuids, ..)
+            | InsensitiveCommand::ElementStyleChange(uuids, _) => (
                 gdc.get_message("nh-viewcommand-modifyelements"),
                 uuids.len(),
             ),
+            InsensitiveCommand::GridChange(..) => (gdc.get_message("nh-viewcommand-changegrid"), 0),
             InsensitiveCommand::Macro(msg, arg, _) => (gdc.get_message(msg), *arg),
             InsensitiveCommand::HighlightAll(..)
             | InsensitiveCommand::HighlightSpecific(..)
@@ -1797,12 +2439,19 @@ impl<
             ) if uuids1 == uuids2 => change1
                 .try_merge(change2)
                 .map(|e| InsensitiveCommand::PropertyChange(uuids1.clone(), e)),
+            (
+                InsensitiveCommand::ElementStyleChange(uuids1, _),
+                InsensitiveCommand::ElementStyleChange(uuids2, _),
+            ) if uuids1 == uuids2 => Some(newer.clone()),
+            (InsensitiveCommand::GridChange(_), InsensitiveCommand::GridChange(_)) => {
+                Some(newer.clone())
+            }
             _ => None,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct ColorChangeData {
     pub slot: u8,
     pub color: MGlobalColor,
@@ -1832,13 +2481,22 @@ pub trait Domain: Sized + 'static {
         + Clone;
     type ViewTargettingSectionT: Into<Self::CommonElementT>;
     type QueryableT<'a>: Queryable<'a, Self>;
+    /// Per-diagram choices of how elements are drawn, which do not affect the model
+    type NotationT: Clone + Default = ();
     type ToolT: Tool<Self>;
     type OrdinalMovementT: Clone + Debug;
     type AddCommandElementT: From<Self::CommonElementViewT>
         + TryInto<Self::CommonElementViewT>
         + Clone
         + Debug;
-    type PropChangeT: From<ColorChangeData> + TryInto<ColorChangeData> + TryMerge + Clone + Debug;
+    /// Serializable, so that property changes can be recorded in macros
+    type PropChangeT: From<ColorChangeData>
+        + TryInto<ColorChangeData>
+        + TryMerge
+        + Clone
+        + Debug
+        + serde::Serialize
+        + serde::de::DeserializeOwned;
 }
 
 pub trait ElementVisitor<T: ?Sized> {
@@ -1899,9 +2557,11 @@ pub trait Queryable<'a, DomainT: Domain> {
         models_to_views: &'a HashMap<ModelUuid, ViewUuid>,
         flattened_views: &'a HashMap<ViewUuid, (DomainT::CommonElementViewT, ViewUuid)>,
         flattened_views_status: &'a HashMap<ViewUuid, SelectionStatus>,
+        notation: &'a DomainT::NotationT,
     ) -> Self;
 
     fn get_root(&self) -> ViewUuid;
+    fn notation(&self) -> &DomainT::NotationT;
     fn get_parent(&self, v: &ViewUuid) -> Option<ViewUuid>;
     fn is_contained(&self, v: &ViewUuid, within: &ViewUuid) -> bool;
     fn are_siblings(&self, a: &ViewUuid, b: &ViewUuid) -> bool;
@@ -1923,6 +2583,9 @@ pub trait Queryable<'a, DomainT: Domain> {
     fn get_viewuuid_for(&self, m: &ModelUuid) -> Option<ViewUuid>;
     fn get_view_for(&self, m: &ModelUuid) -> Option<DomainT::CommonElementViewT>;
     fn selected_views(&self) -> HashSet<ViewUuid>;
+    fn for_each_view<F>(&self, f: F)
+    where
+        F: FnMut(&DomainT::CommonElementViewT);
 }
 
 pub struct GenericQueryable<'a, DomainT: Domain> {
@@ -1930,6 +2593,7 @@ pub struct GenericQueryable<'a, DomainT: Domain> {
     models_to_views: &'a HashMap<ModelUuid, ViewUuid>,
     flattened_views: &'a HashMap<ViewUuid, (DomainT::CommonElementViewT, ViewUuid)>,
     flattened_views_status: &'a HashMap<ViewUuid, SelectionStatus>,
+    notation: &'a DomainT::NotationT,
 }
 
 impl<'a, DomainT: Domain> Queryable<'a, DomainT> for GenericQueryable<'a, DomainT> {
@@ -1938,18 +2602,23 @@ impl<'a, DomainT: Domain> Queryable<'a, DomainT> for GenericQueryable<'a, Domain
         models_to_views: &'a HashMap<ModelUuid, ViewUuid>,
         flattened_views: &'a HashMap<ViewUuid, (DomainT::CommonElementViewT, ViewUuid)>,
         flattened_views_status: &'a HashMap<ViewUuid, SelectionStatus>,
+        notation: &'a DomainT::NotationT,
     ) -> Self {
         Self {
             root,
             models_to_views,
             flattened_views,
             flattened_views_status,
+            notation,
         }
     }
 
     fn get_root(&self) -> ViewUuid {
         self.root
     }
+    fn notation(&self) -> &DomainT::NotationT {
+        self.notation
+    }
     fn get_parent(&self, v: &ViewUuid) -> Option<ViewUuid> {
         self.flattened_views.get(v).map(|e| e.1)
     }
@@ -2017,6 +2686,27 @@ impl<'a, DomainT: Domain> Queryable<'a, DomainT> for GenericQueryable<'a, Domain
             .map(|e| *e.0)
             .collect()
     }
+    fn for_each_view<F>(&self, mut f: F)
+    where
+        F: FnMut(&DomainT::CommonElementViewT),
+    {
+        self.flattened_views.values().for_each(|e| f(&e.0));
+    }
+}
+
+pub const NAME_PATTERN_HINT: &str =
+    "{n} is replaced by the lowest number not used by another element";
+
+/// Replaces `{n}` in a name pattern of a palette item with the lowest positive number
+/// giving a name not yet taken. Names without `{n}` are returned as they are.
+pub fn expand_name_pattern(pattern: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !pattern.contains("{n}") {
+        return pattern.to_owned();
+    }
+    (1..)
+        .map(|n| pattern.replace("{n}", &n.to_string()))
+        .find(|e| !is_taken(e))
+        .unwrap()
 }
 
 pub trait Tool<DomainT: Domain> {
@@ -2177,6 +2867,18 @@ pub trait ElementControllerGen2<DomainT: Domain>:
     fn delete_when(&self, _deleting: &HashSet<ViewUuid>) -> bool {
         false
     }
+    /// Source and target views of a straight connection, which can be bundled with its parallels
+    fn connection_endpoints(&self) -> Option<(ViewUuid, ViewUuid)> {
+        None
+    }
+    /// Pairs of source and target views of a connection, regardless of its shape
+    fn connected_views(&self) -> Vec<(ViewUuid, ViewUuid)> {
+        Vec::new()
+    }
+    /// Movable vertices of a connection, in a stable order
+    fn waypoints(&self) -> Vec<(ViewUuid, egui::Pos2)> {
+        Vec::new()
+    }
 
     // Create a deep copy, including the models
     fn deep_copy_walk(
@@ -2236,6 +2938,56 @@ pub trait ControllerAdapter<DomainT: Domain>:
         gdc: &GlobalDrawingContext,
         ui: &mut egui::Ui,
     ) -> Option<ERef<Self::DiagramViewT>>;
+
+    /// Returns the view the given element decomposes into, along with the view if it had to be created
+    fn decomposition_view(
+        &mut self,
+        _element: &ModelUuid,
+        _view_exists: &dyn Fn(&ViewUuid) -> bool,
+    ) -> Option<(ViewUuid, Option<ERef<Self::DiagramViewT>>)> {
+        None
+    }
+    fn show_menubar_diagram_options(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        _view_uuid: &ViewUuid,
+        _represented_models: &dyn Fn(&ViewUuid) -> Option<HashSet<ModelUuid>>,
+        _ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+    }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[]
+    }
+    /// Must return Some for all formats returned by `text_export_formats`
+    fn export_text(
+        &self,
+        _format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        None
+    }
+    /// Exports only the given elements, None if the format does not support it
+    fn export_selected_text(
+        &self,
+        _format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+        _selection: &HashSet<ModelUuid>,
+    ) -> Option<String> {
+        None
+    }
+    /// Domain-specific validation of the model
+    fn check_problems(&self) -> Vec<CheckProblem> {
+        Vec::new()
+    }
+    /// Stereotype and comment of the element shown in generated documentation
+    fn stereotype_and_comment(
+        &self,
+        _element: &DomainT::CommonElementT,
+    ) -> (Arc<String>, Arc<String>) {
+        Default::default()
+    }
 }
 
 #[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
@@ -2866,7 +3618,15 @@ where
         ui_scale: Option<f32>,
     ) -> (Box<dyn NHCanvas>, egui::Response, Option<egui::Pos2>) {
         let view = self.views.get(uuid).unwrap();
-        view.write().new_ui_canvas(context, ui, ui_scale)
+        let mut view = view.write();
+        view.set_element_defaults(
+            context
+                .element_defaults
+                .get(self.adapter.controller_type())
+                .copied()
+                .unwrap_or_default(),
+        );
+        view.new_ui_canvas(context, ui, ui_scale)
     }
 
     fn draw_in(
@@ -2880,8 +3640,18 @@ where
         let view = self.views.get(uuid).unwrap();
         view.write().draw_in(context, settings, canvas, mouse_pos);
     }
-
-    fn show_context_menu(
+    fn draw_selected_in(
+        &mut self,
+        uuid: &ViewUuid,
+        context: &GlobalDrawingContext,
+        settings: &dyn DiagramSettings,
+        canvas: &mut dyn NHCanvas,
+    ) {
+        let view = self.views.get(uuid).unwrap();
+        view.write().draw_selected_in(context, settings, canvas);
+    }
+
+    fn show_context_menu(
         &mut self,
         uuid: &ViewUuid,
         context: &GlobalDrawingContext,
@@ -2898,6 +3668,299 @@ where
         let view = self.views.get(uuid).unwrap();
         view.write().unset_context_menu();
     }
+    fn drill_down(
+        &mut self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<(ViewUuid, bool)> {
+        let model_uuid = self
+            .views
+            .get(uuid)
+            .and_then(|e| e.read().model_at_pointer(ui, response))?;
+        let views = &self.views;
+        let (view_uuid, new_view) = self
+            .adapter
+            .decomposition_view(&model_uuid, &|e| views.get(e).is_some())?;
+        let is_new = new_view.is_some();
+        if let Some(new_view) = new_view {
+            self.views.push(view_uuid, new_view);
+        }
+        Some((view_uuid, is_new))
+    }
+    fn model_at_pointer(
+        &self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ModelUuid> {
+        self.views
+            .get(uuid)
+            .and_then(|e| e.read().model_at_pointer(ui, response))
+    }
+    fn element_link_at_pointer(
+        &self,
+        uuid: &ViewUuid,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ElementLink> {
+        self.views
+            .get(uuid)
+            .and_then(|e| e.read().element_link_at_pointer(ui, response))
+    }
+    fn element_link(&self, uuid: &ViewUuid, view: &ViewUuid) -> Option<ElementLink> {
+        self.views
+            .get(uuid)
+            .and_then(|e| e.read().element_link(view))
+    }
+    fn set_element_link(&mut self, uuid: &ViewUuid, view: ViewUuid, link: Option<ElementLink>) {
+        if let Some(e) = self.views.get(uuid) {
+            e.write().set_element_link(view, link);
+        }
+    }
+    fn add_layer(&mut self, uuid: &ViewUuid, name: String) {
+        if let Some(e) = self.views.get(uuid) {
+            e.write().add_layer(name);
+        }
+    }
+    fn rename_layer(&mut self, uuid: &ViewUuid, layer: &uuid::Uuid, name: String) {
+        if let Some(e) = self.views.get(uuid) {
+            e.write().rename_layer(layer, name);
+        }
+    }
+    fn element_category(&self, uuid: &ViewUuid, model: &ModelUuid) -> Option<ElementCategory> {
+        self.views
+            .get(uuid)
+            .and_then(|e| e.read().element_category(model))
+    }
+    fn start_inline_rename(&mut self, uuid: &ViewUuid, model: &ModelUuid) -> bool {
+        self.views
+            .get(uuid)
+            .is_some_and(|e| e.write().start_inline_rename(model))
+    }
+    fn selected_models(&self, uuid: &ViewUuid) -> Vec<ModelUuid> {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().selected_models())
+            .unwrap_or_default()
+    }
+    fn selection_bounds(&self, uuid: &ViewUuid) -> Option<egui::Rect> {
+        self.views
+            .get(uuid)
+            .and_then(|e| e.read().selection_bounds())
+    }
+    fn draw_order(&self, uuid: &ViewUuid) -> Vec<(ViewUuid, ModelUuid, Option<ZOrderLock>)> {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().draw_order())
+            .unwrap_or_default()
+    }
+    fn layers(&self, uuid: &ViewUuid) -> LayersOverview {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().layers())
+            .unwrap_or_default()
+    }
+    fn prune_view_state(&mut self) {
+        // Views which undo or redo would add back, including their children
+        let added_views = |element: &DomainT::AddCommandElementT, into: &mut HashSet<ViewUuid>| {
+            if let Ok(mut view) = TryInto::<DomainT::CommonElementViewT>::try_into(element.clone())
+            {
+                let mut statuses = HashMap::new();
+                view.head_count(&mut HashMap::new(), &mut statuses, &mut HashMap::new());
+                into.extend(statuses.into_keys());
+            }
+        };
+        for uuid in self.views.keys() {
+            let Some(view) = self.views.get(uuid) else {
+                continue;
+            };
+            let mut restorable = HashSet::new();
+            for e in self.undo_stack.iter().filter(|e| e.0 == *uuid) {
+                e.1.referenced_views(&added_views, &mut restorable);
+                for c in &e.2 {
+                    c.referenced_views(&added_views, &mut restorable);
+                }
+            }
+            for e in self.redo_stack.iter().filter(|e| e.0 == *uuid) {
+                e.1.referenced_views(&added_views, &mut restorable);
+            }
+            view.write().prune_view_state(&restorable);
+        }
+    }
+    fn check_problems(&self, uuid: &ViewUuid) -> Vec<CheckProblem> {
+        let mut problems = self.adapter.check_problems();
+        if let Some(v) = self.views.get(uuid) {
+            problems.extend(v.read().check_problems());
+        }
+        problems
+    }
+    fn element_descriptions(&self) -> Vec<ElementDescription> {
+        struct DescriptionVisitor<'a, DomainT: Domain, AdapterT: ControllerAdapter<DomainT>> {
+            adapter: &'a AdapterT,
+            descriptions: Vec<ElementDescription>,
+            domain: PhantomData<DomainT>,
+        }
+        impl<DomainT: Domain, AdapterT: ControllerAdapter<DomainT>>
+            DescriptionVisitor<'_, DomainT, AdapterT>
+        {
+            fn describe(&mut self, e: &DomainT::CommonElementT) {
+                let (stereotype, comment) = self.adapter.stereotype_and_comment(e);
+                self.descriptions.push(ElementDescription {
+                    model: *e.uuid(),
+                    stereotype,
+                    comment,
+                });
+            }
+        }
+        impl<DomainT: Domain, AdapterT: ControllerAdapter<DomainT>>
+            ElementVisitor<DomainT::CommonElementT> for DescriptionVisitor<'_, DomainT, AdapterT>
+        {
+            fn open_complex(&mut self, e: &DomainT::CommonElementT) {
+                self.describe(e);
+            }
+            fn close_complex(&mut self, _e: &DomainT::CommonElementT) {}
+            fn visit_simple(&mut self, e: &DomainT::CommonElementT) {
+                self.describe(e);
+            }
+        }
+        impl<DomainT: Domain, AdapterT: ControllerAdapter<DomainT>>
+            DiagramVisitor<DomainT::DiagramModelT> for DescriptionVisitor<'_, DomainT, AdapterT>
+        {
+            fn open_diagram(&mut self, _e: &DomainT::DiagramModelT) {}
+            fn close_diagram(&mut self, _e: &DomainT::DiagramModelT) {}
+        }
+
+        let mut visitor = DescriptionVisitor {
+            adapter: &self.adapter,
+            descriptions: Vec::new(),
+            domain: PhantomData,
+        };
+        self.adapter.model().read().accept(&mut visitor);
+        visitor.descriptions
+    }
+    fn referenced_images(&self, uuid: &ViewUuid) -> Vec<uuid::Uuid> {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().referenced_images())
+            .unwrap_or_default()
+    }
+    fn grid(&self, uuid: &ViewUuid) -> DiagramGrid {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().grid())
+            .unwrap_or_default()
+    }
+    fn canvas_colors(
+        &self,
+        uuid: &ViewUuid,
+        context: &GlobalDrawingContext,
+    ) -> (egui::Color32, egui::Color32) {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().canvas_colors(context))
+            .unwrap_or((
+                ColorProfile::LIGHT.background,
+                ColorProfile::LIGHT.gridlines,
+            ))
+    }
+    fn add_image_element(
+        &mut self,
+        uuid: &ViewUuid,
+        image: &ProjectImage,
+        position: Option<egui::Pos2>,
+    ) {
+        if let Some(view) = self.views.get(uuid) {
+            view.write().add_image_element(image, position);
+        }
+    }
+    fn add_symbol_element(
+        &mut self,
+        uuid: &ViewUuid,
+        symbol: uuid::Uuid,
+        view_box: egui::Rect,
+        position: Option<egui::Pos2>,
+    ) {
+        if let Some(view) = self.views.get(uuid) {
+            view.write().add_symbol_element(symbol, view_box, position);
+        }
+    }
+    fn layout(&self, uuid: &ViewUuid) -> DiagramLayout {
+        self.views
+            .get(uuid)
+            .map(|e| e.read().layout())
+            .unwrap_or_default()
+    }
+    fn apply_layout(
+        &mut self,
+        uuid: &ViewUuid,
+        layout: &DiagramLayout,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) {
+        let Some(view) = self.views.get(uuid).cloned() else {
+            return;
+        };
+        let previous = view.read().layout();
+        let commands = view.write().layout_to_sensitives(layout);
+        let commands = view.read().without_locked(commands);
+        self.apply_commands(uuid, commands, true, affected_models);
+        view.write().animate_layout_from(&previous);
+    }
+
+    fn add_named_element(
+        &mut self,
+        uuid: &ViewUuid,
+        model: ModelUuid,
+        name: &str,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) -> bool {
+        let Some(view) = self.views.get(uuid) else {
+            return false;
+        };
+        let commands = view.write().named_element_to_sensitives(model, name);
+        if commands.is_empty() {
+            return false;
+        }
+        self.apply_commands(uuid, commands, true, affected_models);
+        true
+    }
+
+    fn apply_property_change(
+        &mut self,
+        uuid: &ViewUuid,
+        models: Option<&[ModelUuid]>,
+        change: &str,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) -> bool {
+        let Some(view) = self.views.get(uuid) else {
+            return false;
+        };
+        let Ok(change) = serde_json::from_str::<DomainT::PropChangeT>(change) else {
+            return false;
+        };
+        let targets: HashSet<_> = {
+            let r = view.read();
+            let selected;
+            let models = match models {
+                Some(e) => e,
+                None => {
+                    selected = r.selected_models();
+                    &selected
+                }
+            };
+            models.iter().flat_map(|e| r.get_view_for(e)).collect()
+        };
+        if targets.is_empty() {
+            return false;
+        }
+        self.apply_commands(
+            uuid,
+            vec![InsensitiveCommand::PropertyChange(targets, change)],
+            true,
+            affected_models,
+        );
+        true
+    }
 
     fn show_toolbar(
         &mut self,
@@ -2916,10 +3979,15 @@ where
         context: &GlobalDrawingContext,
         ui: &mut egui::Ui,
         affected_models: &mut HashSet<ModelUuid>,
+        property_changes: &mut Vec<String>,
     ) -> Option<Box<dyn CustomModal>> {
         let view = self.views.get(uuid).unwrap();
         let mut commands = Vec::new();
         let r = view.write().show_properties(context, ui, &mut commands);
+        property_changes.extend(commands.iter().flat_map(|e| match e {
+            InsensitiveCommand::PropertyChange(_, p) => serde_json::to_string(p).ok(),
+            _ => None,
+        }));
         self.apply_commands(uuid, commands, true, affected_models);
         r
     }
@@ -2935,6 +4003,12 @@ where
         view.write().show_outline(context, settings, ui)
     }
 
+    fn swap_camera(&mut self, uuid: &ViewUuid, camera: &mut DiagramCamera) {
+        if let Some(view) = self.views.get(uuid) {
+            view.write().swap_camera(camera);
+        }
+    }
+
     fn show_menubar_edit_options(
         &mut self,
         uuid: &ViewUuid,
@@ -2967,6 +4041,18 @@ where
         ui: &mut egui::Ui,
         commands: &mut Vec<ProjectCommand>,
     ) {
+        let views = &self.views;
+        self.adapter.show_menubar_diagram_options(
+            context,
+            uuid,
+            &|e| {
+                views
+                    .get(e)
+                    .map(|v| v.read().represented_models().keys().cloned().collect())
+            },
+            ui,
+            commands,
+        );
         let view = self.views.get(uuid).unwrap();
         view.write()
             .show_menubar_diagram_options(context, ui, commands);
@@ -2983,6 +4069,7 @@ where
         let commands = view
             .write()
             .diagram_command_to_sensitives(command, clipboard);
+        let commands = view.read().without_locked(commands);
         self.apply_commands(uuid, commands, true, affected_models);
     }
 
@@ -3114,6 +4201,42 @@ where
         }
     }
 
+    fn duplicate_view(&self, uuid: &ViewUuid) -> (ViewUuid, ERef<dyn DiagramController>) {
+        let view = self.views.get(uuid).unwrap();
+        let new_view = view.read().deep_copy();
+        let new_view_uuid = *new_view.read().uuid();
+        let new_view_model = new_view.read().model();
+        (
+            new_view_uuid,
+            ERef::new(Self::new(
+                ControllerUuid::now_v7(),
+                self.adapter.clone_with_model(new_view_model),
+                vec![new_view],
+            )),
+        )
+    }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        self.adapter.text_export_formats()
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        self.adapter.export_text(format, languages)
+    }
+    fn export_selected_text(
+        &self,
+        uuid: &ViewUuid,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        let selection = self.selected_models(uuid).into_iter().collect();
+        self.adapter
+            .export_selected_text(format, languages, &selection)
+    }
+
     fn show_duplication_menu(
         &mut self,
         gdc: &GlobalDrawingContext,
@@ -3124,18 +4247,8 @@ where
             .button(gdc.translate_0("nh-tab-projecthierarchy-duplicate"))
             .clicked()
         {
-            let view = self.views.get(uuid).unwrap();
-            let new_view = view.read().deep_copy();
-            let new_view_uuid = *new_view.read().uuid();
-            let new_view_model = new_view.read().model();
-            return Some((
-                new_view_uuid,
-                Some(ERef::new(Self::new(
-                    ControllerUuid::now_v7(),
-                    self.adapter.clone_with_model(new_view_model),
-                    vec![new_view],
-                ))),
-            ));
+            let (new_view_uuid, new_controller) = self.duplicate_view(uuid);
+            return Some((new_view_uuid, Some(new_controller)));
         }
         if ui
             .button(gdc.translate_0("nh-tab-projecthierarchy-duplicateshared"))
@@ -3213,13 +4326,58 @@ pub trait DiagramAdapter<DomainT: Domain>:
         q: &DomainT::QueryableT<'_>,
         element: DomainT::CommonElementT,
     ) -> Result<DomainT::CommonElementViewT, HashSet<ModelUuid>>;
+    /// Creates the element text promoted from a document becomes, such as a class,
+    /// if the diagram has one
+    fn new_named_element(
+        &self,
+        _uuid: ModelUuid,
+        _name: &str,
+        _position: egui::Pos2,
+    ) -> Option<DomainT::CommonElementViewT> {
+        None
+    }
+    /// Creates an element along with the connection making it a child of the given one,
+    /// for diagrams built from the keyboard, such as mind maps
+    fn new_child_element(
+        &self,
+        _parent: &DomainT::CommonElementViewT,
+        _position: egui::Pos2,
+    ) -> Option<(DomainT::CommonElementViewT, DomainT::CommonElementViewT)> {
+        None
+    }
     fn label_for(&self, element: &DomainT::CommonElementT) -> Arc<String>;
+    /// Returns the name of the element, if it can be renamed using `name_change`
+    fn element_name(&self, _element: &DomainT::CommonElementT) -> Option<Arc<String>> {
+        None
+    }
+    /// Property change renaming elements whose names are returned by `element_name`
+    fn name_change(&self, _name: Arc<String>) -> Option<DomainT::PropChangeT> {
+        None
+    }
 
     fn background_color(&self, global_colors: &ColorBundle) -> egui::Color32;
     fn gridlines_color(&self, global_colors: &ColorBundle) -> egui::Color32;
+    /// Replaces the default colors of the views, which are meant for a light background.
+    ///
+    /// Domains drawing fills other than white should replace them using
+    /// [`ColorProfile::with_fills`], or keep [`ColorProfile::LIGHT`].
+    fn color_profile(&self, dark: bool) -> ColorProfile {
+        if dark {
+            ColorProfile::DARK
+        } else {
+            ColorProfile::LIGHT
+        }
+    }
+    fn notation(&self) -> DomainT::NotationT {
+        Default::default()
+    }
     fn enable_headers(&self) -> (bool, bool) {
         (false, false)
     }
+    /// Kind shown in the heading of the diagram frame, such as `class` or `sd`
+    fn frame_kind(&self) -> &'static str {
+        "diagram"
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -3291,6 +4449,38 @@ pub struct DiagramControllerGen2<DomainT: Domain, DiagramAdapterT: DiagramAdapte
     adapter: DiagramAdapterT,
     #[nh_context_serde(entity)]
     owned_views: OrderedViews<DomainT::CommonElementViewT>,
+    /// Views pinned behind or in front of the others, which locked views cannot change.
+    /// Entries are kept for deleted views, so that undoing the deletion restores the pin
+    #[nh_context_serde(default)]
+    z_order_locks: HashMap<ViewUuid, ZOrderLock>,
+    /// Hyperlinks of element views, also kept for deleted views
+    #[nh_context_serde(default)]
+    element_links: HashMap<ViewUuid, ElementLink>,
+    /// Style overrides of element views, also kept for deleted views
+    #[nh_context_serde(default)]
+    element_styles: HashMap<ViewUuid, ElementStyle>,
+    /// Views locked regardless of their layer, also kept for deleted views
+    #[nh_context_serde(default)]
+    locked_views: HashSet<ViewUuid>,
+    #[nh_context_serde(default)]
+    background: UFOption<BackgroundImage>,
+    /// In draw order, shared by all domains as they are not part of the model
+    #[nh_context_serde(default)]
+    images: Vec<ImageElement>,
+    #[nh_context_serde(default)]
+    grid: DiagramGrid,
+    /// Kind in the heading of the frame drawn around all elements, if shown
+    #[nh_context_serde(default)]
+    frame: UFOption<String>,
+    #[nh_context_serde(default)]
+    annotations: AnnotationLayer,
+    #[nh_context_serde(default)]
+    layers: DiagramLayers,
+    #[nh_context_serde(default)]
+    theme: CanvasTheme,
+    /// Whether connections hop over the connections drawn before them where they cross
+    #[nh_context_serde(default)]
+    line_jumps: bool,
     #[nh_context_serde(skip_and_default)]
     temporaries: DiagramControllerGen2Temporaries<DomainT>,
 }
@@ -3301,20 +4491,138 @@ struct DiagramControllerGen2Temporaries<DomainT: Domain> {
     flattened_views: HashMap<ViewUuid, (DomainT::CommonElementViewT, ViewUuid)>,
     flattened_views_status: HashMap<ViewUuid, SelectionStatus>,
     flattened_represented_models: HashMap<ModelUuid, ViewUuid>,
-    _layers: Vec<bool>,
 
-    camera_offset: egui::Pos2,
-    camera_scale: f32,
+    camera: DiagramCamera,
     last_unhandled_mouse_pos: Option<egui::Pos2>,
-    last_interactive_canvas_rect: egui::Rect,
+    snap_tolerance: f32,
+    element_defaults: ElementDefaults,
     snap_manager: SnapManager,
     current_tool: Option<DomainT::ToolT>,
     select_by_drag: Option<(egui::Pos2, egui::Pos2)>,
     context_menu_target: Option<(egui::Pos2, ViewUuid, ModelUuid)>,
+    selected_image: Option<uuid::Uuid>,
+    /// Last element added to the selection, the reference for `MatchSizeSelected`
+    last_selected: Option<ViewUuid>,
+    /// Image being moved, or resized when true
+    dragged_image: Option<(uuid::Uuid, bool)>,
+    annotation_tool: Option<AnnotationTool>,
+    annotation_color: egui::Color32,
+    /// Annotation being drawn and the position it was started at
+    drawn_annotation: Option<(Annotation, egui::Pos2)>,
+
+    bundle_connections: bool,
+    expanded_bundles: HashSet<(ViewUuid, ViewUuid)>,
+    connection_bundles: Vec<ConnectionBundle>,
+    bundled_connections: HashSet<ViewUuid>,
+
+    cluster_mode: ClusterMode,
+    expanded_clusters: HashSet<ViewUuid>,
+    element_clusters: Vec<ElementCluster>,
+    clustered_views: HashSet<ViewUuid>,
+    cluster_links: Vec<([egui::Pos2; 2], usize)>,
+
+    live_layout: bool,
+    live_layout_offsets: HashMap<ViewUuid, egui::Vec2>,
+
+    layout_transition: Option<LayoutTransition>,
+
+    inline_rename: Option<InlineRename>,
 
     last_change_flag: bool,
 }
 
+/// Name of an element being edited directly on the canvas
+struct InlineRename {
+    view: ViewUuid,
+    name: String,
+    focused: bool,
+}
+
+/// Duration of animated camera and layout transitions, in seconds
+const TRANSITION_DURATION: f64 = 0.3;
+
+/// Eased progress of a transition, its start is taken from the first frame it is shown in
+fn transition_progress(start: &mut Option<f64>, now: f64) -> f32 {
+    let t = ((now - *start.get_or_insert(now)) / TRANSITION_DURATION).clamp(0.0, 1.0) as f32;
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Position and zoom of the canvas a diagram is shown in.
+///
+/// A diagram keeps the camera of its main tab, additional viewports of the same diagram
+/// keep their own and swap it in while they are shown.
+pub struct DiagramCamera {
+    offset: egui::Pos2,
+    scale: f32,
+    canvas_rect: egui::Rect,
+    transition: Option<CameraTransition>,
+}
+
+impl Default for DiagramCamera {
+    fn default() -> Self {
+        Self {
+            offset: egui::Pos2::ZERO,
+            scale: 1.0,
+            canvas_rect: egui::Rect::ZERO,
+            transition: None,
+        }
+    }
+}
+
+/// Camera movement towards a target, cancelled when the camera is moved by other means
+struct CameraTransition {
+    from: (egui::Pos2, f32),
+    to: (egui::Pos2, f32),
+    /// Camera as last set by the transition
+    shown: (egui::Pos2, f32),
+    start: Option<f64>,
+}
+
+/// Movement of elements, already placed at their new positions, shown after the fact.
+///
+/// The elements are moved back and then forward directly, bypassing the undo stack.
+struct LayoutTransition {
+    /// Offsets of views relative to their parents, which move their contents
+    offsets: Vec<(ViewUuid, egui::Vec2)>,
+    shown: f32,
+    start: Option<f64>,
+}
+
+/// Parallel straight connections between the same pair of elements
+struct ConnectionBundle {
+    endpoints: (ViewUuid, ViewUuid),
+    members: Vec<ViewUuid>,
+    points: [egui::Pos2; 2],
+}
+
+impl ConnectionBundle {
+    const BADGE_RADIUS: f32 = 9.0;
+
+    fn badge_position(&self) -> egui::Pos2 {
+        (self.points[0] + self.points[1].to_vec2()) / 2.0
+    }
+}
+
+/// Top-level connection with the pairs of top-level elements it connects
+type TopLevelConnection = (ViewUuid, Vec<(ViewUuid, ViewUuid)>);
+
+/// Top-level elements which can be collapsed into a single node, identified by the smallest member
+struct ElementCluster {
+    key: ViewUuid,
+    members: Vec<ViewUuid>,
+    bounds: egui::Rect,
+}
+
+impl ElementCluster {
+    const COLLAPSED_SIZE: egui::Vec2 = egui::Vec2::new(120.0, 50.0);
+
+    fn collapsed_shape(&self) -> canvas::NHShape {
+        canvas::NHShape::Rect {
+            inner: egui::Rect::from_center_size(self.bounds.center(), Self::COLLAPSED_SIZE),
+        }
+    }
+}
+
 impl<DomainT: Domain> Default for DiagramControllerGen2Temporaries<DomainT> {
     fn default() -> Self {
         Self {
@@ -3322,15 +4630,33 @@ impl<DomainT: Domain> Default for DiagramControllerGen2Temporaries<DomainT> {
             flattened_views: Default::default(),
             flattened_views_status: Default::default(),
             flattened_represented_models: Default::default(),
-            _layers: Default::default(),
-            camera_offset: Default::default(),
-            camera_scale: 1.0,
+            camera: Default::default(),
             last_unhandled_mouse_pos: Default::default(),
-            last_interactive_canvas_rect: egui::Rect::ZERO,
+            snap_tolerance: 0.0,
+            element_defaults: Default::default(),
             snap_manager: Default::default(),
             current_tool: Default::default(),
             select_by_drag: Default::default(),
             context_menu_target: Default::default(),
+            selected_image: None,
+            last_selected: None,
+            dragged_image: None,
+            annotation_tool: None,
+            annotation_color: egui::Color32::RED,
+            drawn_annotation: None,
+            bundle_connections: false,
+            expanded_bundles: Default::default(),
+            connection_bundles: Default::default(),
+            bundled_connections: Default::default(),
+            cluster_mode: Default::default(),
+            expanded_clusters: Default::default(),
+            element_clusters: Default::default(),
+            clustered_views: Default::default(),
+            cluster_links: Default::default(),
+            live_layout: false,
+            live_layout_offsets: Default::default(),
+            layout_transition: None,
+            inline_rename: None,
             last_change_flag: Default::default(),
         }
     }
@@ -3350,6 +4676,18 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             name,
             adapter,
             owned_views: OrderedViews::new(owned_views),
+            z_order_locks: HashMap::new(),
+            element_links: HashMap::new(),
+            element_styles: HashMap::new(),
+            locked_views: HashSet::new(),
+            background: UFOption::None,
+            images: Vec::new(),
+            grid: Default::default(),
+            frame: UFOption::None,
+            annotations: Default::default(),
+            layers: Default::default(),
+            theme: Default::default(),
+            line_jumps: false,
             temporaries: DiagramControllerGen2Temporaries::default(),
         });
         ret.write().initialize();
@@ -3358,10 +4696,51 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
     }
 
     fn initialize(&mut self) {
+        self.owned_views.apply_locks(&self.z_order_locks);
         // Initialize flattened_* fields, etc.
         self.head_count();
     }
 
+    /// Locked views, by themselves or by their layer, cannot be moved, resized, deleted or reordered
+    fn is_locked(&self, view: &ViewUuid) -> bool {
+        self.locked_views.contains(view) || self.layers.is_locked(view)
+    }
+
+    fn topmost_view_at(&self, local_pos: egui::Pos2) -> Option<(ViewUuid, ModelUuid)> {
+        let all_containing_views: Vec<ViewUuid> = self
+            .temporaries
+            .flattened_views
+            .iter()
+            .filter(|e| e.1.0.min_shape().contains(local_pos))
+            .map(|e| *e.0)
+            .collect();
+        let mut parents = HashSet::new();
+        for e in &all_containing_views {
+            let mut it = *e;
+            'parents: loop {
+                let Some((_, parent)) = self.temporaries.flattened_views.get(&it) else {
+                    break 'parents;
+                };
+                parents.insert(*parent);
+                it = *parent;
+            }
+        }
+        all_containing_views
+            .into_iter()
+            .filter(|e| !parents.contains(e))
+            .map(|e| {
+                let m = *self
+                    .temporaries
+                    .flattened_views
+                    .get(&e)
+                    .unwrap()
+                    .0
+                    .model_uuid();
+                (e, m)
+            })
+            .next()
+    }
+
     fn depends_on(&self) -> Vec<EntityUuid> {
         std::iter::once(self.model().read().tagged_uuid()).collect()
     }
@@ -3372,6 +4751,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
 
     const MIN_ZOOM_FACTOR: f32 = 0.01;
     const MAX_ZOOM_FACTOR: f32 = 10.0;
+    const LIVE_LAYOUT_MAX_STEP: f32 = 5.0;
 
     fn handle_event(
         &mut self,
@@ -3388,10 +4768,18 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             >,
         >,
     ) -> bool {
+        if let Some(tool) = self.temporaries.annotation_tool
+            && self.annotations.visible
+        {
+            self.handle_annotation_event(tool, event);
+            return true;
+        }
+
         // Collect alignment guides
         self.temporaries.snap_manager = SnapManager::new(
-            self.temporaries.last_interactive_canvas_rect,
-            egui::Vec2::splat(10.0 / self.temporaries.camera_scale),
+            self.temporaries.camera.canvas_rect,
+            egui::Vec2::splat(self.temporaries.snap_tolerance / self.temporaries.camera.scale),
+            self.grid.snap_size(),
         );
         self.owned_views
             .event_order_foreach_mut(|v| v.collect_allignment(&mut self.temporaries.snap_manager));
@@ -3403,18 +4791,56 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
         let ehc = EventHandlingContext {
             modifier_settings,
             modifiers,
-            ui_scale: self.temporaries.camera_scale,
+            ui_scale: self.temporaries.camera.scale,
             all_elements: &self.temporaries.flattened_views_status,
             snap_manager: &self.temporaries.snap_manager,
         };
+        let notation = self.adapter.notation();
         let q = DomainT::QueryableT::new(
             *self.uuid,
             &self.temporaries.flattened_represented_models,
             &self.temporaries.flattened_views,
             &self.temporaries.flattened_views_status,
+            &notation,
         );
 
+        if let InputEvent::Click(pos) = event
+            && let Some(b) = self
+                .temporaries
+                .connection_bundles
+                .iter()
+                .find(|b| b.badge_position().distance(pos) <= ConnectionBundle::BADGE_RADIUS)
+        {
+            if !self.temporaries.expanded_bundles.remove(&b.endpoints) {
+                self.temporaries.expanded_bundles.insert(b.endpoints);
+            }
+            return true;
+        }
+        if let InputEvent::Click(pos) = event
+            && let Some(c) = self.temporaries.element_clusters.iter().find(|c| {
+                if self.temporaries.expanded_clusters.contains(&c.key) {
+                    c.bounds.right_top().distance(pos) <= ConnectionBundle::BADGE_RADIUS
+                } else {
+                    c.collapsed_shape().contains(pos)
+                }
+            })
+        {
+            if !self.temporaries.expanded_clusters.remove(&c.key) {
+                self.temporaries.expanded_clusters.insert(c.key);
+            }
+            return true;
+        }
+
+        let bundled_connections = &self.temporaries.bundled_connections;
+        let clustered_views = &self.temporaries.clustered_views;
+        let layers = &self.layers;
         let child = self.owned_views.event_order_find_mut(|v| {
+            if bundled_connections.contains(&v.uuid())
+                || clustered_views.contains(&v.uuid())
+                || layers.is_inactive(&v.uuid())
+            {
+                return None;
+            }
             let r = v.handle_event(
                 event,
                 &ehc,
@@ -3467,6 +4893,21 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             }
         });
 
+        if child.is_none()
+            && self.temporaries.current_tool.is_none()
+            && let Some(handled) = Self::handle_image_event(
+                &mut self.images,
+                &mut self.temporaries.selected_image,
+                &mut self.temporaries.dragged_image,
+                self.temporaries.camera.scale,
+                event,
+                &mut commands,
+            )
+        {
+            commands_accumulator.extend(commands);
+            return handled;
+        }
+
         let handled = match event {
             InputEvent::MouseUp(_) => {
                 self.temporaries.select_by_drag = None;
@@ -3501,7 +4942,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
                     .is_ok();
 
                 if !handled && let Some(t) = self.temporaries.current_tool.as_mut() {
-                    t.add_position(pos);
+                    t.add_position(ehc.snap_manager.snap_to_grid(pos));
                 }
 
                 let mut tool = self.temporaries.current_tool.take();
@@ -3535,59 +4976,904 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             }
         }
 
-        commands_accumulator.extend(commands);
+        drop(q);
+        self.apply_element_defaults(&mut commands);
+        commands_accumulator.extend(self.without_locked(commands));
 
         handled
     }
 
-    fn set_clipboard_from_selected(&self, clipboard: &mut Vec<Box<dyn Any>>) {
-        let selected = self
-            .temporaries
-            .flattened_views_status
+    /// Applies the appearance defaults of the diagram type to elements placed by tools
+    fn apply_element_defaults(
+        &self,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) {
+        let defaults = self.temporaries.element_defaults;
+        if defaults.is_default() {
+            return;
+        }
+        // Elements being moved between containers are added as well, but already exist.
+        // Connections have no size and their color slots differ.
+        let placed: Vec<DomainT::CommonElementViewT> = commands
+            .iter()
+            .flat_map(|e| match e {
+                InsensitiveCommand::AddDependency { element, .. } => {
+                    element.clone().try_into().ok()
+                }
+                _ => None,
+            })
+            .filter(|e: &DomainT::CommonElementViewT| {
+                !self.temporaries.flattened_views.contains_key(&*e.uuid())
+                    && e.connected_views().is_empty()
+            })
+            .collect();
+
+        for view in placed {
+            let uuid = *view.uuid();
+            if let Some(style) = defaults.style() {
+                commands.push(InsensitiveCommand::ElementStyleChange(
+                    std::iter::once(uuid).collect(),
+                    style,
+                ));
+            }
+            if let Some(color) = defaults.background_color {
+                let ccd = ColorChangeData {
+                    slot: 0,
+                    color: MGlobalColor::Local(color),
+                };
+                commands.push(InsensitiveCommand::PropertyChange(
+                    std::iter::once(uuid).collect(),
+                    ccd.into(),
+                ));
+            }
+            if let Some(rect) = defaults.resized(view.bounding_box()) {
+                commands.push(InsensitiveCommand::ResizeElementTo(uuid, rect));
+            }
+        }
+    }
+
+    /// While an annotation tool is active, the annotation layer gets all events
+    fn handle_annotation_event(&mut self, tool: AnnotationTool, event: InputEvent) {
+        const ERASER_TOLERANCE: f32 = 5.0;
+
+        match event {
+            InputEvent::MouseDown(pos) => {
+                self.temporaries.drawn_annotation =
+                    Annotation::new(tool, self.temporaries.annotation_color, pos).map(|e| (e, pos));
+            }
+            InputEvent::Drag { from, delta } => {
+                if let Some((annotation, start)) = self.temporaries.drawn_annotation.as_mut() {
+                    annotation.extend_to(*start, from + delta);
+                }
+            }
+            InputEvent::MouseUp(_) => {
+                if let Some((annotation, _)) = self.temporaries.drawn_annotation.take()
+                    && !annotation.is_degenerate()
+                {
+                    self.annotations.annotations.push(annotation);
+                }
+            }
+            InputEvent::Click(pos) => {
+                if tool == AnnotationTool::Eraser {
+                    self.annotations
+                        .erase_at(pos, ERASER_TOLERANCE / self.temporaries.camera.scale);
+                }
+            }
+        }
+    }
+
+    /// Position of placed images and symbols, near the top left corner of the visible area if not given
+    fn placement_position(&self, position: Option<egui::Pos2>) -> egui::Pos2 {
+        position.unwrap_or_else(|| {
+            (-self.temporaries.camera.offset.to_vec2() / self.temporaries.camera.scale
+                + egui::Vec2::splat(10.0))
+            .to_pos2()
+        })
+    }
+
+    /// Image elements are behind all other elements, so they only get events no element handled
+    fn handle_image_event(
+        images: &mut [ImageElement],
+        selected_image: &mut Option<uuid::Uuid>,
+        dragged_image: &mut Option<(uuid::Uuid, bool)>,
+        ui_scale: f32,
+        event: InputEvent,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) -> Option<bool> {
+        let selected = *selected_image;
+        let hit = |pos: egui::Pos2| {
+            images.iter().rev().find_map(|e| {
+                let resize = selected == Some(e.uuid) && e.resize_handle(ui_scale).contains(pos);
+                (resize || e.bounds.contains(pos)).then_some((e.uuid, resize))
+            })
+        };
+
+        match event {
+            InputEvent::MouseDown(pos) => {
+                *dragged_image = Some(hit(pos)?);
+                Some(true)
+            }
+            InputEvent::Drag { delta, .. } => {
+                let (uuid, resize) = (*dragged_image)?;
+                let image = images.iter_mut().find(|e| e.uuid == uuid)?;
+                if resize {
+                    image.resize_by(delta);
+                } else {
+                    image.bounds = image.bounds.translate(delta);
+                }
+                Some(true)
+            }
+            InputEvent::MouseUp(_) => {
+                *dragged_image = None;
+                None
+            }
+            InputEvent::Click(pos) => {
+                *selected_image = hit(pos).map(|e| e.0);
+                (*selected_image)?;
+                commands.push(InsensitiveCommand::HighlightAll(false, Highlight::SELECTED));
+                Some(true)
+            }
+        }
+    }
+
+    /// Draws the element scaled to fit the area
+    fn draw_palette_preview(
+        view: &mut DomainT::CommonElementViewT,
+        q: &DomainT::QueryableT<'_>,
+        gdc: &GlobalDrawingContext,
+        settings: &DomainT::SettingsT,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+    ) {
+        const PADDING: f32 = 2.0;
+
+        let painter = painter.with_clip_rect(rect);
+        let mut mc = canvas::MeasuringCanvas::new(&painter);
+        view.draw_in(q, gdc, settings, &mut mc, &None);
+        let (scale, offset) = mc.scale_offset_to_fit(rect.size(), PADDING);
+        let mut c = canvas::UiCanvas::new(
+            painter,
+            rect,
+            offset,
+            scale,
+            None,
+            None,
+            Highlight::NONE,
+            (false, false),
+        );
+        c.clear(egui::Color32::GRAY);
+        view.draw_in(q, gdc, settings, &mut c, &None);
+    }
+
+    fn set_clipboard_from_selected(&self, clipboard: &mut Vec<Box<dyn Any>>) {
+        let selected = self
+            .temporaries
+            .flattened_views_status
+            .iter()
+            .filter(|e| e.1.selected())
+            .map(|e| *e.0)
+            .collect();
+        *clipboard = Self::elements_deep_copy(
+            Some(&selected),
+            |_| false,
+            HashMap::new(),
+            self.owned_views
+                .iter_event_order_pairs()
+                .map(|e| (e.0, e.1.clone())),
+        )
+        .into_values()
+        .map(|e| Box::new(e) as Box<dyn Any>)
+        .collect();
+    }
+
+    fn elements_deep_copy<VI>(
+        requested: Option<&HashSet<ViewUuid>>,
+        view_uuid_present: impl Fn(&ViewUuid) -> bool,
+        existing_models: HashMap<ModelUuid, DomainT::CommonElementT>,
+        source_views: VI,
+    ) -> HashMap<ViewUuid, DomainT::CommonElementViewT>
+    where
+        VI: Iterator<Item = (ViewUuid, DomainT::CommonElementViewT)>,
+    {
+        let mut top_level_views = HashMap::new();
+        let mut views = HashMap::new();
+        let mut models = existing_models;
+
+        for (_uuid, c) in source_views {
+            c.deep_copy_walk(
+                requested,
+                &view_uuid_present,
+                &mut top_level_views,
+                &mut views,
+                &mut models,
+            );
+        }
+        for v in top_level_views.values_mut() {
+            v.deep_copy_relink(&views, &models);
+        }
+
+        top_level_views
+    }
+
+    fn collect_connection_bundles(&mut self) {
+        self.temporaries.connection_bundles.clear();
+        self.temporaries.bundled_connections.clear();
+        if !self.temporaries.bundle_connections {
+            return;
+        }
+
+        let mut groups: Vec<((ViewUuid, ViewUuid), Vec<ViewUuid>)> = Vec::new();
+        for (k, v) in self.owned_views.iter_event_order_pairs() {
+            let Some((a, b)) = v.connection_endpoints() else {
+                continue;
+            };
+            let endpoints = if a < b { (a, b) } else { (b, a) };
+            match groups.iter_mut().find(|e| e.0 == endpoints) {
+                Some(g) => g.1.push(k),
+                None => groups.push((endpoints, vec![k])),
+            }
+        }
+
+        for (endpoints, members) in groups.into_iter().filter(|e| e.1.len() > 1) {
+            let (Some((a, _)), Some((b, _))) = (
+                self.temporaries.flattened_views.get(&endpoints.0),
+                self.temporaries.flattened_views.get(&endpoints.1),
+            ) else {
+                continue;
+            };
+            let (a, b) = (a.min_shape(), b.min_shape());
+            let points = [
+                a.center_intersect(b.center()),
+                b.center_intersect(a.center()),
+            ];
+            if !self.temporaries.expanded_bundles.contains(&endpoints) {
+                self.temporaries
+                    .bundled_connections
+                    .extend(members.iter().copied());
+            }
+            self.temporaries.connection_bundles.push(ConnectionBundle {
+                endpoints,
+                members,
+                points,
+            });
+        }
+    }
+
+    fn draw_connection_bundles(&self, canvas: &mut dyn NHCanvas) {
+        for b in &self.temporaries.connection_bundles {
+            if b.members
+                .iter()
+                .all(|e| self.temporaries.clustered_views.contains(e))
+            {
+                continue;
+            }
+            let expanded = self.temporaries.expanded_bundles.contains(&b.endpoints);
+            if !expanded {
+                canvas.draw_line(
+                    b.points,
+                    canvas::Stroke::new_solid(
+                        (1.0 + b.members.len() as f32).min(6.0),
+                        egui::Color32::BLACK,
+                    ),
+                    canvas::Highlight::NONE,
+                );
+            }
+            let badge = b.badge_position();
+            canvas.draw_ellipse(
+                badge,
+                egui::Vec2::splat(ConnectionBundle::BADGE_RADIUS),
+                egui::Color32::WHITE,
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            canvas.draw_text(
+                badge,
+                egui::Align2::CENTER_CENTER,
+                &if expanded {
+                    "−".to_owned()
+                } else {
+                    b.members.len().to_string()
+                },
+                canvas::CLASS_TOP_FONT_SIZE,
+                egui::Color32::BLACK,
+            );
+        }
+    }
+
+    /// Top-level elements which are not connections, and top-level connections
+    /// with the pairs of top-level elements they connect
+    fn top_level_graph(&self) -> (Vec<ViewUuid>, Vec<TopLevelConnection>) {
+        let top_level_ancestor = |mut v: ViewUuid| {
+            while let Some((_, parent)) = self.temporaries.flattened_views.get(&v)
+                && *parent != *self.uuid
+            {
+                v = *parent;
+            }
+            v
+        };
+
+        let mut nodes = Vec::new();
+        let mut connections = Vec::new();
+        for (k, v) in self.owned_views.iter_event_order_pairs() {
+            let ends = v.connected_views();
+            if ends.is_empty() {
+                nodes.push(k);
+            } else {
+                connections.push((
+                    k,
+                    ends.into_iter()
+                        .map(|(a, b)| (top_level_ancestor(a), top_level_ancestor(b)))
+                        .filter(|(a, b)| a != b)
+                        .collect::<Vec<_>>(),
+                ));
+            }
+        }
+        (nodes, connections)
+    }
+
+    /// Moves top-level elements to the centers computed from their sizes and connections
+    fn top_level_layout_to_sensitives(
+        &mut self,
+        arrange: impl FnOnce(&[egui::Vec2], &[(usize, usize)]) -> Vec<egui::Pos2>,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    > {
+        let (nodes, connections) = self.top_level_graph();
+        if nodes.is_empty() {
+            return vec![];
+        }
+        let indices: HashMap<_, _> = nodes.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+        let bounds: Vec<_> = nodes
+            .iter()
+            .map(|e| {
+                self.temporaries
+                    .flattened_views
+                    .get(e)
+                    .map(|v| v.0.bounding_box())
+                    .unwrap_or(egui::Rect::ZERO)
+            })
+            .collect();
+        let edges: Vec<_> = connections
+            .iter()
+            .flat_map(|e| e.1.iter())
+            .flat_map(|(a, b)| Some((*indices.get(a)?, *indices.get(b)?)))
+            .collect();
+        let centers = arrange(&bounds.iter().map(|e| e.size()).collect::<Vec<_>>(), &edges);
+
+        // The arranged elements keep the top left corner of their area
+        let placed: Vec<_> = bounds
+            .iter()
+            .zip(centers)
+            .map(|(b, c)| egui::Rect::from_center_size(c, b.size()))
+            .collect();
+        let offset = bounds
+            .iter()
+            .fold(egui::Rect::NOTHING, |a, b| a.union(*b))
+            .min
+            - placed
+                .iter()
+                .fold(egui::Rect::NOTHING, |a, b| a.union(*b))
+                .min;
+        let mut target = DiagramLayout::default();
+        for (view, b) in nodes.iter().zip(placed) {
+            if let Some((v, _)) = self.temporaries.flattened_views.get(view) {
+                target.elements.insert(
+                    *v.model_uuid(),
+                    ElementLayout {
+                        bounds: b.translate(offset),
+                        waypoints: Vec::new(),
+                    },
+                );
+            }
+        }
+        self.layout_to_sensitives(&target)
+    }
+
+    /// Shows the name editor over the element being renamed, the name is changed on Enter
+    fn show_inline_rename(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) {
+        let Some(mut rename) = self.temporaries.inline_rename.take() else {
+            return;
+        };
+        let Some((view, _)) = self.temporaries.flattened_views.get(&rename.view) else {
+            return;
+        };
+        let bounds = view.bounding_box();
+        let scale = self.temporaries.camera.scale;
+        let pos = response.rect.min
+            + self.temporaries.camera.offset.to_vec2()
+            + bounds.min.to_vec2() * scale;
+
+        let mut finished = false;
+        egui::Area::new(egui::Id::new(("inline rename", rename.view)))
+            .fixed_pos(pos)
+            .order(egui::Order::Foreground)
+            .show(ui.ctx(), |ui| {
+                let r = ui.add(
+                    egui::TextEdit::singleline(&mut rename.name)
+                        .desired_width((bounds.width() * scale).max(100.0)),
+                );
+                if !rename.focused {
+                    r.request_focus();
+                    rename.focused = true;
+                } else if r.lost_focus() {
+                    // Escape or clicking elsewhere discards the new name
+                    if ui.input(|i| i.key_pressed(egui::Key::Enter))
+                        && let Some(change) =
+                            self.adapter.name_change(Arc::new(rename.name.clone()))
+                    {
+                        commands.push(InsensitiveCommand::PropertyChange(
+                            std::iter::once(rename.view).collect(),
+                            change,
+                        ));
+                    }
+                    finished = true;
+                }
+            });
+        if !finished {
+            self.temporaries.inline_rename = Some(rename);
+        }
+    }
+
+    /// Moves the view without recording the movement on the undo stack
+    fn move_directly(&mut self, uuid: ViewUuid, delta: egui::Vec2) {
+        self.apply_command_inner(
+            &InsensitiveCommand::MovePositional(std::iter::once(uuid).collect(), delta),
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        );
+    }
+
+    /// Moves the camera to the target, over time if transitions are animated
+    fn start_camera_transition(&mut self, offset: egui::Pos2, scale: f32) {
+        let current = (
+            self.temporaries.camera.offset,
+            self.temporaries.camera.scale,
+        );
+        self.temporaries.camera.transition = Some(CameraTransition {
+            from: current,
+            to: (offset, scale),
+            shown: current,
+            start: None,
+        });
+    }
+
+    fn step_transitions(&mut self, animate: bool, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        let mut running = false;
+
+        if let Some(mut t) = self.temporaries.camera.transition.take()
+            && (
+                self.temporaries.camera.offset,
+                self.temporaries.camera.scale,
+            ) == t.shown
+        {
+            let p = if animate {
+                transition_progress(&mut t.start, now)
+            } else {
+                1.0
+            };
+            self.temporaries.camera.offset = t.from.0.lerp(t.to.0, p);
+            self.temporaries.camera.scale = egui::lerp(t.from.1..=t.to.1, p);
+            t.shown = (
+                self.temporaries.camera.offset,
+                self.temporaries.camera.scale,
+            );
+            if p < 1.0 {
+                self.temporaries.camera.transition = Some(t);
+                running = true;
+            }
+        }
+
+        if let Some(mut t) = self.temporaries.layout_transition.take() {
+            let p = if animate {
+                transition_progress(&mut t.start, now)
+            } else {
+                1.0
+            };
+            for (k, offset) in t.offsets.iter() {
+                self.move_directly(*k, *offset * (p - t.shown));
+            }
+            t.shown = p;
+            if p < 1.0 {
+                self.temporaries.layout_transition = Some(t);
+                running = true;
+            }
+        }
+
+        if running {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Places elements of a running layout transition at their final positions
+    fn finish_layout_transition(&mut self) {
+        if let Some(t) = self.temporaries.layout_transition.take() {
+            for (k, offset) in t.offsets.iter() {
+                self.move_directly(*k, *offset * (1.0 - t.shown));
+            }
+        }
+    }
+
+    /// Moves unpinned (unselected) elements by one step of a force-directed layout.
+    ///
+    /// The movement bypasses the undo stack while the layout is running and is replayed
+    /// as a single command once it is paused.
+    fn step_live_layout(
+        &mut self,
+        ctx: &egui::Context,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) {
+        let move_directly = |s: &mut Self, uuid: ViewUuid, delta: egui::Vec2| {
+            s.move_directly(uuid, delta);
+        };
+
+        if !self.temporaries.live_layout {
+            if self.temporaries.live_layout_offsets.is_empty() {
+                return;
+            }
+            let mut moves = Vec::new();
+            for (k, delta) in std::mem::take(&mut self.temporaries.live_layout_offsets) {
+                move_directly(self, k, -delta);
+                moves.push(InsensitiveCommand::MovePositional(
+                    std::iter::once(k).collect(),
+                    delta,
+                ));
+            }
+            commands.push(InsensitiveCommand::Macro(
+                "nh-viewcommand-livelayout".to_owned().into(),
+                moves.len(),
+                moves.into(),
+            ));
+            return;
+        }
+
+        let (nodes, connections) = self.top_level_graph();
+        let indices: HashMap<_, _> = nodes.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+        let bounds: Vec<_> = nodes
+            .iter()
+            .map(|e| {
+                self.temporaries
+                    .flattened_views
+                    .get(e)
+                    .map(|v| v.0.bounding_box())
+                    .unwrap_or(egui::Rect::ZERO)
+            })
+            .collect();
+        let positions: Vec<_> = bounds.iter().map(|e| e.center()).collect();
+        let pinned: Vec<_> = nodes
+            .iter()
+            .map(|e| {
+                self.temporaries
+                    .flattened_views_status
+                    .get(e)
+                    .is_some_and(|s| s.selected())
+            })
+            .collect();
+        let edges: Vec<_> = connections
+            .iter()
+            .flat_map(|e| e.1.iter())
+            .flat_map(|(a, b)| Some((*indices.get(a)?, *indices.get(b)?)))
+            .collect();
+        let ideal_distance = 80.0
+            + bounds
+                .iter()
+                .map(|e| e.width().max(e.height()))
+                .sum::<f32>()
+                / bounds.len().max(1) as f32;
+
+        let step = layout::force_directed_step(
+            &positions,
+            &edges,
+            &pinned,
+            ideal_distance,
+            Self::LIVE_LAYOUT_MAX_STEP,
+        );
+        for (k, delta) in nodes.into_iter().zip(step) {
+            if delta != egui::Vec2::ZERO {
+                move_directly(self, k, delta);
+                *self.temporaries.live_layout_offsets.entry(k).or_default() += delta;
+            }
+        }
+        ctx.request_repaint();
+    }
+
+    fn collect_element_clusters(&mut self) {
+        self.temporaries.element_clusters.clear();
+        self.temporaries.clustered_views.clear();
+        self.temporaries.cluster_links.clear();
+        if self.temporaries.cluster_mode == ClusterMode::None {
+            return;
+        }
+
+        let flattened_views = &self.temporaries.flattened_views;
+        let (nodes, connections) = self.top_level_graph();
+        let edges: Vec<_> = connections
+            .iter()
+            .flat_map(|e| e.1.iter().copied())
+            .collect();
+
+        let groups = match self.temporaries.cluster_mode {
+            ClusterMode::None => Vec::new(),
+            ClusterMode::StronglyConnected => {
+                clustering::strongly_connected_components(&nodes, &edges)
+            }
+            ClusterMode::Connected => clustering::connected_components(&nodes, &edges),
+            ClusterMode::Packages => {
+                let parents: HashSet<_> = flattened_views.values().map(|e| e.1).collect();
+                nodes
+                    .iter()
+                    .filter(|e| parents.contains(e))
+                    .map(|e| vec![*e])
+                    .collect()
+            }
+        };
+
+        let mut collapsed_members = HashMap::new();
+        for members in groups
+            .into_iter()
+            .filter(|e| e.len() > 1 || self.temporaries.cluster_mode == ClusterMode::Packages)
+        {
+            let Some(key) = members.iter().min().copied() else {
+                continue;
+            };
+            let bounds = members
+                .iter()
+                .flat_map(|e| flattened_views.get(e))
+                .map(|e| e.0.bounding_box())
+                .fold(egui::Rect::NOTHING, |a, b| a.union(b));
+            if !self.temporaries.expanded_clusters.contains(&key) {
+                for m in &members {
+                    collapsed_members.insert(*m, self.temporaries.element_clusters.len());
+                }
+                self.temporaries
+                    .clustered_views
+                    .extend(members.iter().copied());
+            }
+            self.temporaries.element_clusters.push(ElementCluster {
+                key,
+                members,
+                bounds,
+            });
+        }
+
+        // Replace connections leading into collapsed clusters with aggregated links
+        type ClusterEnd = (Option<usize>, ViewUuid);
+        let element_clusters = &self.temporaries.element_clusters;
+        let representative = |v: &ViewUuid| match collapsed_members.get(v) {
+            Some(c) => (Some(*c), element_clusters[*c].key),
+            None => (None, *v),
+        };
+        let mut links: Vec<(ClusterEnd, ClusterEnd, usize)> = Vec::new();
+        for (k, ends) in &connections {
+            if !ends.iter().any(|(a, b)| {
+                collapsed_members.contains_key(a) || collapsed_members.contains_key(b)
+            }) {
+                continue;
+            }
+            self.temporaries.clustered_views.insert(*k);
+            for (a, b) in ends {
+                let (a, b) = (representative(a), representative(b));
+                if a == b {
+                    continue;
+                }
+                let (a, b) = if a.1 < b.1 { (a, b) } else { (b, a) };
+                match links.iter_mut().find(|e| e.0 == a && e.1 == b) {
+                    Some(l) => l.2 += 1,
+                    None => links.push((a, b, 1)),
+                }
+            }
+        }
+
+        let shape_of = |e: &ClusterEnd| match e.0 {
+            Some(c) => Some(element_clusters[c].collapsed_shape()),
+            None => flattened_views.get(&e.1).map(|v| v.0.min_shape()),
+        };
+        for (a, b, count) in links {
+            let (Some(a), Some(b)) = (shape_of(&a), shape_of(&b)) else {
+                continue;
+            };
+            self.temporaries.cluster_links.push((
+                [
+                    a.center_intersect(b.center()),
+                    b.center_intersect(a.center()),
+                ],
+                count,
+            ));
+        }
+    }
+
+    fn draw_element_clusters(&self, context: &GlobalDrawingContext, canvas: &mut dyn NHCanvas) {
+        for (points, count) in &self.temporaries.cluster_links {
+            canvas.draw_line(
+                *points,
+                canvas::Stroke::new_solid((*count as f32).min(6.0), egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+        }
+
+        for c in &self.temporaries.element_clusters {
+            let (badge, text) = if self.temporaries.expanded_clusters.contains(&c.key) {
+                canvas.draw_rectangle(
+                    c.bounds.expand(5.0),
+                    egui::CornerRadius::same(5),
+                    egui::Color32::TRANSPARENT,
+                    canvas::Stroke::new_dashed(1.0, egui::Color32::GRAY),
+                    canvas::Highlight::NONE,
+                );
+                (c.bounds.right_top(), "−".to_owned())
+            } else {
+                let rect = c.collapsed_shape().bounding_box();
+                canvas.draw_rectangle(
+                    rect,
+                    egui::CornerRadius::same(5),
+                    egui::Color32::from_rgb(235, 235, 255),
+                    canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                    canvas::Highlight::NONE,
+                );
+                canvas.draw_text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    &context.translate_0("nh-view-clusternodes-cluster"),
+                    canvas::CLASS_MIDDLE_FONT_SIZE,
+                    egui::Color32::BLACK,
+                );
+                (rect.right_top(), c.members.len().to_string())
+            };
+            canvas.draw_ellipse(
+                badge,
+                egui::Vec2::splat(ConnectionBundle::BADGE_RADIUS),
+                egui::Color32::WHITE,
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            canvas.draw_text(
+                badge,
+                egui::Align2::CENTER_CENTER,
+                &text,
+                canvas::CLASS_TOP_FONT_SIZE,
+                egui::Color32::BLACK,
+            );
+        }
+    }
+
+    /// Draws the UML diagram frame around all elements, with the heading in a pentagon
+    fn draw_frame(&self, kind: &str, canvas: &mut dyn NHCanvas) {
+        const MARGIN: f32 = 20.0;
+        const PADDING: egui::Vec2 = egui::Vec2::new(6.0, 3.0);
+        const CORNER: f32 = 8.0;
+        let content = self
+            .owned_views
+            .iter_event_order_pairs()
+            .fold(egui::Rect::NOTHING, |acc, e| acc.union(e.1.bounding_box()));
+        if !content.is_positive() {
+            return;
+        }
+
+        let heading = format!("{} {}", kind, self.name);
+        let text_size = canvas
+            .measure_text(
+                egui::Pos2::ZERO,
+                egui::Align2::LEFT_TOP,
+                &heading,
+                canvas::CLASS_MIDDLE_FONT_SIZE,
+            )
+            .size();
+        let heading_size = text_size + 2.0 * PADDING + egui::Vec2::new(CORNER, 0.0);
+        let frame = egui::Rect::from_min_max(
+            content.min - egui::Vec2::new(MARGIN, MARGIN + heading_size.y),
+            content.max + egui::Vec2::splat(MARGIN),
+        );
+        let frame = frame.union(egui::Rect::from_min_size(
+            frame.min,
+            heading_size + egui::Vec2::splat(MARGIN),
+        ));
+
+        let stroke = canvas::Stroke::new_solid(1.0, egui::Color32::BLACK);
+        canvas.draw_rectangle(
+            frame,
+            egui::CornerRadius::ZERO,
+            egui::Color32::TRANSPARENT,
+            stroke,
+            canvas::Highlight::NONE,
+        );
+        let corner = frame.min + heading_size;
+        canvas.draw_polygon(
+            vec![
+                frame.min,
+                egui::Pos2::new(corner.x, frame.min.y),
+                egui::Pos2::new(corner.x, corner.y - CORNER),
+                egui::Pos2::new(corner.x - CORNER, corner.y),
+                egui::Pos2::new(frame.min.x, corner.y),
+            ],
+            egui::Color32::WHITE,
+            stroke,
+            canvas::Highlight::NONE,
+        );
+        canvas.draw_text(
+            frame.min + PADDING,
+            egui::Align2::LEFT_TOP,
+            &heading,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            egui::Color32::BLACK,
+        );
+    }
+
+    fn color_profile(&self, context: &GlobalDrawingContext) -> ColorProfile {
+        self.adapter
+            .color_profile(self.theme.is_dark(context.dark_mode))
+    }
+
+    /// Edits the style of all selected views, starting from the style of any of them
+    fn show_style_properties(
+        &self,
+        context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) {
+        let selected: Vec<ViewUuid> = self
+            .temporaries
+            .flattened_views_status
             .iter()
             .filter(|e| e.1.selected())
             .map(|e| *e.0)
             .collect();
-        *clipboard = Self::elements_deep_copy(
-            Some(&selected),
-            |_| false,
-            HashMap::new(),
-            self.owned_views
-                .iter_event_order_pairs()
-                .map(|e| (e.0, e.1.clone())),
-        )
-        .into_values()
-        .map(|e| Box::new(e) as Box<dyn Any>)
-        .collect();
-    }
-
-    fn elements_deep_copy<VI>(
-        requested: Option<&HashSet<ViewUuid>>,
-        view_uuid_present: impl Fn(&ViewUuid) -> bool,
-        existing_models: HashMap<ModelUuid, DomainT::CommonElementT>,
-        source_views: VI,
-    ) -> HashMap<ViewUuid, DomainT::CommonElementViewT>
-    where
-        VI: Iterator<Item = (ViewUuid, DomainT::CommonElementViewT)>,
-    {
-        let mut top_level_views = HashMap::new();
-        let mut views = HashMap::new();
-        let mut models = existing_models;
+        let Some(first) = selected.first() else {
+            return;
+        };
 
-        for (_uuid, c) in source_views {
-            c.deep_copy_walk(
-                requested,
-                &view_uuid_present,
-                &mut top_level_views,
-                &mut views,
-                &mut models,
-            );
+        ui.add_space(super::views::VIEW_MODEL_PROPERTIES_BLOCK_SPACING);
+        ui.label(context.translate_0("nh-style"));
+        let mut style = self.element_styles.get(first).copied().unwrap_or_default();
+        let mut changed = style.show_properties(context, ui);
+        if !style.is_default() && ui.button(context.translate_0("nh-style-reset")).clicked() {
+            style = ElementStyle::default();
+            changed = true;
         }
-        for v in top_level_views.values_mut() {
-            v.deep_copy_relink(&views, &models);
+        if changed {
+            commands.push(InsensitiveCommand::ElementStyleChange(
+                selected.into_iter().collect(),
+                style,
+            ));
         }
-
-        top_level_views
     }
 
     fn head_count(&mut self) {
@@ -3627,6 +5913,12 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
         >,
         affected_models: &mut HashSet<ModelUuid>,
     ) {
+        if let InsensitiveCommand::HighlightSpecific(uuids, true, h) = command
+            && h.selected
+            && uuids.len() == 1
+        {
+            self.temporaries.last_selected = uuids.iter().next().copied();
+        }
         match command {
             InsensitiveCommand::HighlightAll(..)
             | InsensitiveCommand::HighlightSpecific(..)
@@ -3672,6 +5964,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
                     affected_models.extend(model_transitives.into_keys());
 
                     self.owned_views.push(uuid, view);
+                    self.owned_views.apply_locks(&self.z_order_locks);
                 }
             }
             InsensitiveCommand::RemoveDependency {
@@ -3733,6 +6026,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             }
             InsensitiveCommand::ArrangeSpecificElements(uuids, arr) => {
                 self.owned_views.apply_arrangement(uuids, *arr);
+                self.owned_views.apply_locks(&self.z_order_locks);
             }
             InsensitiveCommand::PropertyChange(uuids, _property) => {
                 if uuids.is_empty() || uuids.contains(&*self.uuid) {
@@ -3741,6 +6035,28 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
                         .apply_property_change_fun(&self.uuid, command, undo_accumulator);
                 }
             }
+            InsensitiveCommand::ElementStyleChange(uuids, style) => {
+                for uuid in uuids {
+                    let previous = if style.is_default() {
+                        self.element_styles.remove(uuid)
+                    } else {
+                        self.element_styles.insert(*uuid, *style)
+                    }
+                    .unwrap_or_default();
+                    if previous != *style {
+                        undo_accumulator.push(InsensitiveCommand::ElementStyleChange(
+                            std::iter::once(*uuid).collect(),
+                            previous,
+                        ));
+                    }
+                }
+            }
+            InsensitiveCommand::GridChange(grid) => {
+                if *grid != self.grid {
+                    let previous = std::mem::replace(&mut self.grid, grid.clone());
+                    undo_accumulator.push(InsensitiveCommand::GridChange(previous));
+                }
+            }
             InsensitiveCommand::Macro(_, _, cmds) => {
                 for e in cmds.iter() {
                     self.apply_command_inner(e, undo_accumulator, affected_models);
@@ -3749,8 +6065,16 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
         }
 
         if !matches!(command, InsensitiveCommand::Macro(..)) {
+            // Views in hidden or locked layers cannot be selected
+            let selects = matches!(
+                command,
+                InsensitiveCommand::SelectByDrag(..) | InsensitiveCommand::HighlightAll(true, _)
+            );
+            let layers = &self.layers;
             self.owned_views.event_order_foreach_mut(|v| {
-                v.apply_command(command, undo_accumulator, affected_models);
+                if !(selects && layers.is_inactive(&v.uuid())) {
+                    v.apply_command(command, undo_accumulator, affected_models);
+                }
             });
         }
 
@@ -3768,6 +6092,8 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::PropertyChange(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..)
             | InsensitiveCommand::Macro(..) => false,
         };
 
@@ -3781,7 +6107,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
         new_adapter: DiagramAdapterT,
         models: HashMap<ModelUuid, DomainT::CommonElementT>,
     ) -> ERef<Self> {
-        Self::new(
+        let copy = Self::new(
             ViewUuid::now_v7().into(),
             format!("{} (copy)", self.name).into(),
             new_adapter,
@@ -3796,7 +6122,15 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>>
             .into_iter()
             .map(|e| e.1)
             .collect(),
-        )
+        );
+        copy.write().background = self.background.clone();
+        copy.write().images = self.images.clone();
+        copy.write().grid = self.grid.clone();
+        copy.write().frame = self.frame.clone();
+        copy.write().annotations = self.annotations.clone();
+        copy.write().theme = self.theme;
+        copy.write().line_jumps = self.line_jumps;
+        copy
     }
 }
 
@@ -3901,12 +6235,17 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         self.adapter.refresh_buffers();
     }
 
+    fn set_element_defaults(&mut self, defaults: ElementDefaults) {
+        self.temporaries.element_defaults = defaults;
+    }
     fn new_ui_canvas(
         &mut self,
         context: &GlobalDrawingContext,
         ui: &mut egui::Ui,
         ui_scale: Option<f32>,
     ) -> (Box<dyn NHCanvas>, egui::Response, Option<egui::Pos2>) {
+        self.step_transitions(context.animate_transitions, ui.ctx());
+
         let canvas_pos = ui.next_widget_position();
         let canvas_size = ui.available_size();
         let canvas_rect = egui::Rect::from_min_size(canvas_pos, canvas_size);
@@ -3916,37 +6255,39 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         let ui_canvas = UiCanvas::new(
             painter,
             canvas_rect,
-            self.temporaries.camera_offset,
-            self.temporaries.camera_scale,
+            self.temporaries.camera.offset,
+            self.temporaries.camera.scale,
             ui_scale,
             ui.ctx().pointer_interact_pos().map(|e| {
-                ((e - self.temporaries.camera_offset - painter_response.rect.min.to_vec2())
-                    / self.temporaries.camera_scale)
+                ((e - self.temporaries.camera.offset - painter_response.rect.min.to_vec2())
+                    / self.temporaries.camera.scale)
                     .to_pos2()
             }),
             Highlight::ALL,
             self.adapter.enable_headers(),
         );
-        ui_canvas.clear(self.adapter.background_color(&context.global_colors));
-        ui_canvas.draw_gridlines(
-            Some((50.0, self.adapter.gridlines_color(&context.global_colors))),
-            Some((50.0, self.adapter.gridlines_color(&context.global_colors))),
-        );
+        let (background_color, gridlines_color) = self.canvas_colors(context);
+        ui_canvas.clear(background_color);
+        if self.grid.visible {
+            let gridlines = Some((self.grid.size, gridlines_color));
+            ui_canvas.draw_gridlines(gridlines, gridlines);
+        }
 
         let inner_mouse = ui
             .ctx()
             .pointer_interact_pos()
             .filter(|e| canvas_rect.contains(*e))
             .map(|e| {
-                ((e - self.temporaries.camera_offset - canvas_pos.to_vec2())
-                    / self.temporaries.camera_scale)
+                ((e - self.temporaries.camera.offset - canvas_pos.to_vec2())
+                    / self.temporaries.camera.scale)
                     .to_pos2()
             });
 
-        self.temporaries.last_interactive_canvas_rect = egui::Rect::from_min_size(
-            self.temporaries.camera_offset / -self.temporaries.camera_scale,
-            canvas_size / self.temporaries.camera_scale,
+        self.temporaries.camera.canvas_rect = egui::Rect::from_min_size(
+            self.temporaries.camera.offset / -self.temporaries.camera.scale,
+            canvas_size / self.temporaries.camera.scale,
         );
+        self.temporaries.snap_tolerance = context.snap_tolerance;
 
         (Box::new(ui_canvas), painter_response, inner_mouse)
     }
@@ -3969,11 +6310,13 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         let Some(settings) = (settings as &dyn Any).downcast_ref::<DomainT::SettingsT>() else {
             return;
         };
+        self.step_live_layout(ui.ctx(), commands);
+        self.show_inline_rename(ui, response, commands);
 
         macro_rules! pos_to_abs {
             ($pos:expr) => {
-                (($pos - self.temporaries.camera_offset - response.rect.min.to_vec2())
-                    / self.temporaries.camera_scale)
+                (($pos - self.temporaries.camera.offset - response.rect.min.to_vec2())
+                    / self.temporaries.camera.scale)
                     .to_pos2()
             };
         }
@@ -4005,7 +6348,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
             && ui.input(|i| i.multi_touch().is_none())
             && let Some(old_pos) = self.temporaries.last_unhandled_mouse_pos
         {
-            let delta = response.drag_delta() / self.temporaries.camera_scale;
+            let delta = response.drag_delta() / self.temporaries.camera.scale;
             self.handle_event(
                 InputEvent::Drag {
                     from: old_pos,
@@ -4055,7 +6398,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
 
         // Handle diagram drag
         if response.dragged_by(egui::PointerButton::Middle) {
-            self.temporaries.camera_offset += response.drag_delta();
+            self.temporaries.camera.offset += response.drag_delta();
         }
 
         // Handle diagram zoom
@@ -4064,13 +6407,13 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         {
             macro_rules! apply_zoom {
                 ($factor:expr, $cursor_pos:expr) => {
-                    let old_factor = self.temporaries.camera_scale;
-                    self.temporaries.camera_scale *= $factor;
-                    self.temporaries.camera_offset -= (($cursor_pos
-                        - self.temporaries.camera_offset
+                    let old_factor = self.temporaries.camera.scale;
+                    self.temporaries.camera.scale *= $factor;
+                    self.temporaries.camera.offset -= (($cursor_pos
+                        - self.temporaries.camera.offset
                         - response.rect.min.to_vec2())
                         / old_factor)
-                        * (self.temporaries.camera_scale - old_factor);
+                        * (self.temporaries.camera.scale - old_factor);
                 };
             }
 
@@ -4078,11 +6421,11 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 i.events.iter().for_each(|e| {
                     if let egui::Event::MouseWheel { delta, .. } = e {
                         let factor = if delta.y > 0.0
-                            && self.temporaries.camera_scale < Self::MAX_ZOOM_FACTOR
+                            && self.temporaries.camera.scale < Self::MAX_ZOOM_FACTOR
                         {
                             1.5
                         } else if delta.y < 0.0
-                            && self.temporaries.camera_scale > Self::MIN_ZOOM_FACTOR
+                            && self.temporaries.camera.scale > Self::MIN_ZOOM_FACTOR
                         {
                             0.66
                         } else {
@@ -4099,7 +6442,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
             ui.input(|i| {
                 if let Some(mti) = i.multi_touch() {
                     apply_zoom!(i.zoom_delta(), mti.center_pos);
-                    self.temporaries.camera_offset += i.translation_delta();
+                    self.temporaries.camera.offset += i.translation_delta();
                 }
             });
         }
@@ -4142,44 +6485,12 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 return;
             };
             let local_pos =
-                ((screen_pos - self.temporaries.camera_offset - response.rect.min.to_vec2())
-                    / self.temporaries.camera_scale)
+                ((screen_pos - self.temporaries.camera.offset - response.rect.min.to_vec2())
+                    / self.temporaries.camera.scale)
                     .to_pos2();
-            let (v, m) = {
-                let all_containing_views: Vec<ViewUuid> = self
-                    .temporaries
-                    .flattened_views
-                    .iter()
-                    .filter(|e| e.1.0.min_shape().contains(local_pos))
-                    .map(|e| *e.0)
-                    .collect();
-                let mut parents = HashSet::new();
-                for e in &all_containing_views {
-                    let mut it = *e;
-                    'parents: loop {
-                        let Some((_, parent)) = self.temporaries.flattened_views.get(&it) else {
-                            break 'parents;
-                        };
-                        parents.insert(*parent);
-                        it = *parent;
-                    }
-                }
-                all_containing_views
-                    .into_iter()
-                    .filter(|e| !parents.contains(e))
-                    .map(|e| {
-                        let m = *self
-                            .temporaries
-                            .flattened_views
-                            .get(&e)
-                            .unwrap()
-                            .0
-                            .model_uuid();
-                        (e, m)
-                    })
-                    .next()
-                    .unwrap_or((*self.uuid, *self.adapter.model_uuid()))
-            };
+            let (v, m) = self
+                .topmost_view_at(local_pos)
+                .unwrap_or((*self.uuid, *self.adapter.model_uuid()));
             self.temporaries.context_menu_target = Some((local_pos, v, m));
         }
 
@@ -4237,6 +6548,104 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 }
             }
         }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && ui.button(gdc.translate_0("nh-edit-addtodo")).clicked()
+        {
+            commands.push(ProjectCommand::AddTodo(t.2));
+            commands.push(ProjectCommand::OpenAndFocusTab(crate::NHTab::Tasks, None));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && ui.button(gdc.translate_0("nh-edit-linkfile")).clicked()
+        {
+            commands.push(ProjectCommand::AddExternalLink(t.2));
+            commands.push(ProjectCommand::OpenAndFocusTab(
+                crate::NHTab::ExternalLinks,
+                None,
+            ));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && ui.button(gdc.translate_0("nh-edit-linkissue")).clicked()
+        {
+            commands.push(ProjectCommand::AddIssueLink(t.2));
+            commands.push(ProjectCommand::OpenAndFocusTab(
+                crate::NHTab::IssueLinks,
+                None,
+            ));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && t.1 != *self.uuid
+            && ui
+                .button(gdc.translate_0("nh-edit-linksubdiagram"))
+                .clicked()
+        {
+            commands.push(ProjectCommand::LinkSubDiagram(t.2));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && t.1 != *self.uuid
+            && ui.button(gdc.translate_0("nh-edit-linkelement")).clicked()
+        {
+            commands.push(ProjectCommand::LinkElement(*self.uuid, t.1));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && t.1 != *self.uuid
+        {
+            let locked = self.locked_views.contains(&t.1);
+            let label = if locked {
+                "nh-edit-unlockelement"
+            } else {
+                "nh-edit-lockelement"
+            };
+            if ui.button(gdc.translate_0(label)).clicked() {
+                commands.push(
+                    SimpleProjectCommand::SpecificDiagramCommand(
+                        *self.uuid,
+                        DiagramCommand::SetElementLocked(t.1, !locked),
+                    )
+                    .into(),
+                );
+                ui.close();
+            }
+        }
+        if self
+            .temporaries
+            .context_menu_target
+            .is_some_and(|t| t.1 == *self.uuid)
+            && ui
+                .button(gdc.translate_0("nh-edit-setbackground"))
+                .clicked()
+        {
+            commands.push(ProjectCommand::SetBackgroundImage(*self.uuid));
+            ui.close();
+        }
+        if let Some(t) = &self.temporaries.context_menu_target
+            && t.1 == *self.uuid
+        {
+            if ui.button(gdc.translate_0("nh-edit-addimage")).clicked() {
+                commands.push(ProjectCommand::AddImageElement(*self.uuid, t.0));
+                ui.close();
+            }
+            if cfg!(not(target_arch = "wasm32"))
+                && ui.button(gdc.translate_0("nh-edit-pasteimage")).clicked()
+            {
+                commands.push(ProjectCommand::PasteImageElement(*self.uuid, t.0));
+                ui.close();
+            }
+            if gdc.symbols.first().is_some() {
+                ui.menu_button(gdc.translate_0("nh-edit-placesymbol"), |ui| {
+                    for (k, e) in gdc.symbols.iter() {
+                        if ui.button(&e.name).clicked() {
+                            commands.push(ProjectCommand::PlaceSymbol(*k, Some((*self.uuid, t.0))));
+                            ui.close();
+                        }
+                    }
+                });
+            }
+        }
         ui.separator();
 
         ui.menu_button(gdc.translate_0("nh-edit-delete"), |ui| {
@@ -4303,10 +6712,327 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 ))
             );
         });
+        ui.menu_button(gdc.translate_0("nh-edit-matchsize"), |ui| {
+            ui.set_min_width(crate::MIN_MENU_WIDTH);
+
+            button!(
+                ui,
+                "nh-edit-matchsize-width",
+                SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(SizeMatch::Width))
+            );
+            button!(
+                ui,
+                "nh-edit-matchsize-height",
+                SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(SizeMatch::Height))
+            );
+            button!(
+                ui,
+                "nh-edit-matchsize-both",
+                SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(SizeMatch::Both))
+            );
+        });
     }
     fn unset_context_menu(&mut self) {
         self.temporaries.context_menu_target = None;
     }
+    fn model_at_pointer(&self, ui: &egui::Ui, response: &egui::Response) -> Option<ModelUuid> {
+        let screen_pos = ui.ctx().pointer_interact_pos()?;
+        let local_pos =
+            ((screen_pos - self.temporaries.camera.offset - response.rect.min.to_vec2())
+                / self.temporaries.camera.scale)
+                .to_pos2();
+        self.topmost_view_at(local_pos).map(|e| e.1)
+    }
+    fn element_link_at_pointer(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<ElementLink> {
+        let screen_pos = ui.ctx().pointer_interact_pos()?;
+        let local_pos =
+            ((screen_pos - self.temporaries.camera.offset - response.rect.min.to_vec2())
+                / self.temporaries.camera.scale)
+                .to_pos2();
+        let (view, _) = self.topmost_view_at(local_pos)?;
+        self.element_links.get(&view).cloned()
+    }
+    fn element_link(&self, view: &ViewUuid) -> Option<ElementLink> {
+        self.element_links.get(view).cloned()
+    }
+    fn set_element_link(&mut self, view: ViewUuid, link: Option<ElementLink>) {
+        match link {
+            Some(link) => self.element_links.insert(view, link),
+            None => self.element_links.remove(&view),
+        };
+    }
+    fn add_layer(&mut self, name: String) {
+        self.layers.layers.push(DiagramLayer::new(name));
+    }
+    fn rename_layer(&mut self, layer: &uuid::Uuid, name: String) {
+        if let Some(e) = self.layers.get_mut(layer) {
+            e.name = name;
+        }
+    }
+    fn element_category(&self, model: &ModelUuid) -> Option<ElementCategory> {
+        let view_uuid = self.temporaries.flattened_represented_models.get(model)?;
+        let (view, _) = self.temporaries.flattened_views.get(view_uuid)?;
+        if !view.waypoints().is_empty() {
+            Some(ElementCategory::Connection)
+        } else if self
+            .temporaries
+            .flattened_views
+            .values()
+            .any(|e| e.1 == *view_uuid)
+        {
+            Some(ElementCategory::Container)
+        } else {
+            Some(ElementCategory::Element)
+        }
+    }
+    fn start_inline_rename(&mut self, model: &ModelUuid) -> bool {
+        let Some(view) = self.temporaries.flattened_represented_models.get(model) else {
+            return false;
+        };
+        let Some(name) = self
+            .adapter
+            .find_element(model)
+            .and_then(|e| self.adapter.element_name(&e.0))
+        else {
+            return false;
+        };
+        self.temporaries.inline_rename = Some(InlineRename {
+            view: *view,
+            name: (*name).clone(),
+            focused: false,
+        });
+        true
+    }
+    fn selected_models(&self) -> Vec<ModelUuid> {
+        let mut models: Vec<_> = self
+            .temporaries
+            .flattened_views_status
+            .iter()
+            .filter(|e| e.1.selected())
+            .flat_map(|e| self.temporaries.flattened_views.get(e.0))
+            .map(|e| *e.0.model_uuid())
+            .collect();
+        models.sort();
+        models
+    }
+    fn selection_bounds(&self) -> Option<egui::Rect> {
+        let area = self
+            .temporaries
+            .flattened_views_status
+            .iter()
+            .filter(|e| e.1.selected())
+            .flat_map(|e| self.temporaries.flattened_views.get(e.0))
+            .fold(egui::Rect::NOTHING, |acc, e| acc.union(e.0.bounding_box()));
+        area.is_positive().then_some(area)
+    }
+    fn check_problems(&self) -> Vec<CheckProblem> {
+        let fv = &self.temporaries.flattened_views;
+        let mut views: Vec<_> = fv.iter().collect();
+        views.sort_by_key(|e| *e.0);
+
+        let mut problems = Vec::new();
+        for (k, (v, _)) in views {
+            let mut endpoints: Vec<_> = v
+                .connected_views()
+                .into_iter()
+                .flat_map(|(s, t)| [s, t])
+                .filter(|e| !fv.contains_key(e))
+                .collect();
+            endpoints.sort();
+            endpoints.dedup();
+            for e in endpoints {
+                problems.push(CheckProblem {
+                    view: Some(*k),
+                    model: Some(*v.model_uuid()),
+                    message: format!(
+                        "connection endpoint {} is not present in the diagram",
+                        e.to_string()
+                    ),
+                });
+            }
+        }
+        problems
+    }
+    fn grid(&self) -> DiagramGrid {
+        self.grid.clone()
+    }
+    fn canvas_colors(&self, context: &GlobalDrawingContext) -> (egui::Color32, egui::Color32) {
+        let profile = self.color_profile(context);
+        (
+            profile.map_background(self.adapter.background_color(&context.global_colors)),
+            profile.map_gridlines(self.adapter.gridlines_color(&context.global_colors)),
+        )
+    }
+    fn referenced_images(&self) -> Vec<uuid::Uuid> {
+        self.background
+            .as_ref()
+            .map(|e| e.image)
+            .into_iter()
+            .chain(self.images.iter().flat_map(|e| e.image()))
+            .collect()
+    }
+    fn add_image_element(&mut self, image: &ProjectImage, position: Option<egui::Pos2>) {
+        let element = ImageElement::new(image, self.placement_position(position));
+        self.temporaries.selected_image = Some(element.uuid);
+        self.images.push(element);
+    }
+    fn add_symbol_element(
+        &mut self,
+        symbol: uuid::Uuid,
+        view_box: egui::Rect,
+        position: Option<egui::Pos2>,
+    ) {
+        let element = ImageElement::new_symbol(symbol, view_box, self.placement_position(position));
+        self.temporaries.selected_image = Some(element.uuid);
+        self.images.push(element);
+    }
+    fn layout(&self) -> DiagramLayout {
+        DiagramLayout {
+            elements: self
+                .temporaries
+                .flattened_represented_models
+                .iter()
+                .flat_map(|(m, v)| {
+                    let view = &self.temporaries.flattened_views.get(v)?.0;
+                    Some((
+                        *m,
+                        ElementLayout {
+                            bounds: view.bounding_box(),
+                            waypoints: view.waypoints().into_iter().map(|e| e.1).collect(),
+                        },
+                    ))
+                })
+                .collect(),
+        }
+    }
+    fn animate_layout_from(&mut self, previous: &DiagramLayout) {
+        self.finish_layout_transition();
+
+        let mut element_moves = HashMap::new();
+        let mut vertex_moves = Vec::new();
+        for (m, v) in self.temporaries.flattened_represented_models.iter() {
+            let (Some((view, parent)), Some(before)) = (
+                self.temporaries.flattened_views.get(v),
+                previous.elements.get(m),
+            ) else {
+                continue;
+            };
+            let waypoints = view.waypoints();
+            if waypoints.is_empty() {
+                element_moves.insert(*v, (view.bounding_box().min - before.bounds.min, *parent));
+            } else if waypoints.len() == before.waypoints.len() {
+                for ((vertex, now), then) in waypoints.into_iter().zip(&before.waypoints) {
+                    vertex_moves.push((vertex, now - *then, *parent));
+                }
+            }
+        }
+
+        let parent_move = |p: &ViewUuid| element_moves.get(p).map(|e| e.0).unwrap_or_default();
+        let offsets: Vec<_> = element_moves
+            .iter()
+            .map(|(k, (delta, parent))| (*k, *delta - parent_move(parent)))
+            .chain(
+                vertex_moves
+                    .iter()
+                    .map(|(k, delta, parent)| (*k, *delta - parent_move(parent))),
+            )
+            .filter(|e| e.1 != egui::Vec2::ZERO)
+            .collect();
+        if offsets.is_empty() {
+            return;
+        }
+        for (k, offset) in offsets.iter() {
+            self.move_directly(*k, -*offset);
+        }
+        self.temporaries.layout_transition = Some(LayoutTransition {
+            offsets,
+            shown: 0.0,
+            start: None,
+        });
+    }
+    fn draw_order(&self) -> Vec<(ViewUuid, ModelUuid, Option<ZOrderLock>)> {
+        self.owned_views
+            .iter_draw_order_keys()
+            .flat_map(|k| {
+                let v = self.owned_views.get(&k)?;
+                Some((k, *v.model_uuid(), self.z_order_locks.get(&k).copied()))
+            })
+            .collect()
+    }
+    fn layers(&self) -> LayersOverview {
+        let mut overview = self.layers.overview(
+            self.owned_views.iter_event_order_pairs().map(|e| e.0),
+            self.annotations.visible,
+        );
+        overview.locked_count = self
+            .locked_views
+            .iter()
+            .filter(|e| self.temporaries.flattened_views.contains_key(e))
+            .count();
+        overview
+    }
+    fn prune_view_state(&mut self, restorable: &HashSet<ViewUuid>) {
+        let views = &self.temporaries.flattened_views;
+        let kept = |k: &ViewUuid| views.contains_key(k) || restorable.contains(k);
+        self.z_order_locks.retain(|k, _| kept(k));
+        self.element_links.retain(|k, _| kept(k));
+        self.element_styles.retain(|k, _| kept(k));
+        self.locked_views.retain(|k| kept(k));
+        self.layers.membership.retain(|k, _| kept(k));
+    }
+    fn without_locked(
+        &self,
+        commands: Vec<
+            InsensitiveCommand<
+                DomainT::OrdinalMovementT,
+                DomainT::AddCommandElementT,
+                DomainT::PropChangeT,
+            >,
+        >,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    > {
+        let unlocked = |set: &HashSet<ViewUuid>| -> HashSet<ViewUuid> {
+            set.iter().filter(|e| !self.is_locked(e)).copied().collect()
+        };
+        commands
+            .into_iter()
+            .flat_map(|c| match c {
+                InsensitiveCommand::MovePositional(set, delta) => {
+                    let set = unlocked(&set);
+                    (!set.is_empty()).then(|| InsensitiveCommand::MovePositional(set, delta))
+                }
+                InsensitiveCommand::ResizeElementsBy(set, align, delta) => {
+                    let set = unlocked(&set);
+                    (!set.is_empty())
+                        .then(|| InsensitiveCommand::ResizeElementsBy(set, align, delta))
+                }
+                InsensitiveCommand::ResizeElementTo(view, _) if self.is_locked(&view) => None,
+                InsensitiveCommand::DeleteSpecificElements(set, kind) => {
+                    let set = unlocked(&set);
+                    (!set.is_empty()).then(|| InsensitiveCommand::DeleteSpecificElements(set, kind))
+                }
+                InsensitiveCommand::ArrangeSpecificElements(set, arrangement) => {
+                    let set = unlocked(&set);
+                    (!set.is_empty())
+                        .then(|| InsensitiveCommand::ArrangeSpecificElements(set, arrangement))
+                }
+                InsensitiveCommand::Macro(name, arg, cmds) => {
+                    let cmds = self.without_locked(cmds.to_vec());
+                    (!cmds.is_empty()).then(|| InsensitiveCommand::Macro(name, arg, cmds.into()))
+                }
+                c => Some(c),
+            })
+            .collect()
+    }
 
     fn show_toolbar(
         &mut self,
@@ -4359,7 +7085,9 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         ui.separator();
 
         let (empty_a, empty_b, empty_c) = (HashMap::new(), HashMap::new(), HashMap::new());
-        let empty_q = DomainT::QueryableT::new(ViewUuid::nil(), &empty_a, &empty_b, &empty_c);
+        let notation = self.adapter.notation();
+        let empty_q =
+            DomainT::QueryableT::new(ViewUuid::nil(), &empty_a, &empty_b, &empty_c, &notation);
 
         settings.palette_for_each_mut(|(gid, label, items)| {
             egui::CollapsingHeader::new(&*label)
@@ -4416,27 +7144,35 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                             }
                         }
 
-                        let icon_rect = egui::Rect::from_min_size(
-                            response.rect.min,
-                            egui::Vec2::splat(button_height),
-                        );
-                        let painter = ui.painter().with_clip_rect(icon_rect);
-                        let mut mc = canvas::MeasuringCanvas::new(&painter);
-                        view.draw_in(&empty_q, gdc, settings, &mut mc, &None);
-                        let (scale, offset) =
-                            mc.scale_offset_to_fit(egui::Vec2::splat(button_height));
-                        let mut c = canvas::UiCanvas::new(
-                            painter,
-                            icon_rect,
-                            offset,
-                            scale,
-                            None,
-                            None,
-                            Highlight::NONE,
-                            (false, false),
-                        );
-                        c.clear(egui::Color32::GRAY);
-                        view.draw_in(&empty_q, gdc, settings, &mut c, &None);
+                        if button_height >= TOOL_PALETTE_PREVIEW_MIN_HEIGHT {
+                            let icon_rect = egui::Rect::from_min_size(
+                                response.rect.min,
+                                egui::Vec2::splat(button_height),
+                            );
+                            Self::draw_palette_preview(
+                                view,
+                                &empty_q,
+                                gdc,
+                                settings,
+                                ui.painter(),
+                                icon_rect,
+                            );
+                        }
+                        response.on_hover_ui(|ui| {
+                            ui.label(&*name);
+                            let (rect, _) = ui.allocate_exact_size(
+                                egui::Vec2::splat(TOOL_PALETTE_TOOLTIP_SIZE),
+                                egui::Sense::hover(),
+                            );
+                            Self::draw_palette_preview(
+                                view,
+                                &empty_q,
+                                gdc,
+                                settings,
+                                ui.painter(),
+                                rect,
+                            );
+                        });
                     }
                 });
         });
@@ -4453,12 +7189,14 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
             >,
         >,
     ) -> Option<Box<dyn CustomModal>> {
-        let req = {
+        let (req, child_shown) = {
+            let notation = self.adapter.notation();
             let queryable = DomainT::QueryableT::new(
                 *self.uuid,
                 &self.temporaries.flattened_represented_models,
                 &self.temporaries.flattened_views,
                 &self.temporaries.flattened_views_status,
+                &notation,
             );
 
             let child = self.owned_views.event_order_find_mut(|v| {
@@ -4466,7 +7204,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                     .non_default()
             });
             if let Some(child) = child {
-                child
+                (child, true)
             } else {
                 ui.label("View properties:");
                 if ui
@@ -4475,6 +7213,61 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 {
                     self.name = Arc::new(self.temporaries.name_buffer.clone());
                 }
+                if let Some(grid) = self.grid.show_properties(context, ui) {
+                    commands.push(InsensitiveCommand::GridChange(grid));
+                }
+                let themes = [
+                    (CanvasTheme::FollowApp, "nh-theme-followapp"),
+                    (CanvasTheme::Light, "nh-theme-light"),
+                    (CanvasTheme::Dark, "nh-theme-dark"),
+                ];
+                egui::ComboBox::from_label(context.translate_0("nh-theme"))
+                    .selected_text(
+                        themes
+                            .iter()
+                            .find(|e| e.0 == self.theme)
+                            .map(|e| context.translate_0(e.1))
+                            .unwrap_or_default(),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (t, label) in themes {
+                            ui.selectable_value(&mut self.theme, t, context.translate_0(label));
+                        }
+                    });
+                ui.checkbox(&mut self.line_jumps, context.translate_0("nh-linejumps"));
+                self.annotations.show_properties(
+                    context,
+                    ui,
+                    &mut self.temporaries.annotation_tool,
+                    &mut self.temporaries.annotation_color,
+                );
+                let mut framed = self.frame.is_some();
+                if ui
+                    .checkbox(&mut framed, context.translate_0("nh-frame"))
+                    .changed()
+                {
+                    self.frame = match framed {
+                        true => UFOption::Some(self.adapter.frame_kind().to_owned()),
+                        false => UFOption::None,
+                    };
+                }
+                if let Some(kind) = self.frame.as_mut() {
+                    ui.labeled_text_edit_singleline(context.translate_0("nh-frame-kind"), kind);
+                }
+                if let Some(background) = self.background.as_mut()
+                    && background.show_properties(context, ui)
+                {
+                    self.background = UFOption::None;
+                }
+                if let Some(uuid) = self.temporaries.selected_image
+                    && let Some(image) = self.images.iter_mut().find(|e| e.uuid == uuid)
+                {
+                    ui.separator();
+                    if image.show_properties(context, ui) {
+                        self.images.retain(|e| e.uuid != uuid);
+                        self.temporaries.selected_image = None;
+                    }
+                }
                 self.adapter
                     .show_view_props_fun(&self.uuid, context, ui, commands);
 
@@ -4484,9 +7277,12 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 self.adapter
                     .show_model_props_fun(&self.uuid, context, ui, commands);
 
-                PropertiesStatus::Shown
+                (PropertiesStatus::Shown, false)
             }
         };
+        if child_shown {
+            self.show_style_properties(context, ui, commands);
+        }
 
         match req {
             PropertiesStatus::NotShown | PropertiesStatus::Shown => None,
@@ -4520,7 +7316,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         painter.rect(
             canvas_rect,
             egui::CornerRadius::ZERO,
-            self.adapter.background_color(&context.global_colors),
+            self.canvas_colors(context).0,
             egui::Stroke::NONE,
             egui::StrokeKind::Middle,
         );
@@ -4540,7 +7336,7 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         {
             let padding = 20.0 / camera_scale;
             let mut intersects = true;
-            let licr = self.temporaries.last_interactive_canvas_rect;
+            let licr = self.temporaries.camera.canvas_rect;
             let range_x = if licr.min.x < diagram_bounds.max.x && licr.max.x > diagram_bounds.min.x
             {
                 licr.min.x..=licr.max.x
@@ -4581,11 +7377,13 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         {
             let pos = (hover_pos - painter_response.rect.min.to_vec2()) / camera_scale
                 + diagram_bounds.min.to_vec2();
-            self.temporaries.camera_offset = pos * -self.temporaries.camera_scale
-                + self.temporaries.last_interactive_canvas_rect.size() / 2.0
-                    * self.temporaries.camera_scale;
+            self.temporaries.camera.offset = pos * -self.temporaries.camera.scale
+                + self.temporaries.camera.canvas_rect.size() / 2.0 * self.temporaries.camera.scale;
         }
     }
+    fn swap_camera(&mut self, camera: &mut DiagramCamera) {
+        std::mem::swap(&mut self.temporaries.camera, camera);
+    }
     fn show_menubar_edit_options(
         &mut self,
         gdc: &GlobalDrawingContext,
@@ -4649,6 +7447,68 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 selected_only: true
             })
         );
+        ui.separator();
+        ui.checkbox(
+            &mut self.temporaries.bundle_connections,
+            context.translate_0("nh-view-bundleconnections"),
+        );
+        if self.temporaries.live_layout {
+            button!(
+                ui,
+                "nh-view-livelayout-freeze",
+                SimpleProjectCommand::from(DiagramCommand::LiveLayout(LiveLayoutCommand::Freeze))
+            );
+            button!(
+                ui,
+                "nh-view-livelayout-discard",
+                SimpleProjectCommand::from(DiagramCommand::LiveLayout(LiveLayoutCommand::Discard))
+            );
+        } else {
+            button!(
+                ui,
+                "nh-view-livelayout-play",
+                SimpleProjectCommand::from(DiagramCommand::LiveLayout(LiveLayoutCommand::Start))
+            );
+        }
+        button!(
+            ui,
+            "nh-view-treelayout",
+            SimpleProjectCommand::from(DiagramCommand::LayoutAsTree { radial: false })
+        );
+        button!(
+            ui,
+            "nh-view-radiallayout",
+            SimpleProjectCommand::from(DiagramCommand::LayoutAsTree { radial: true })
+        );
+        ui.menu_button(context.translate_0("nh-view-clusternodes"), |ui| {
+            for mode in ClusterMode::ALL {
+                if ui
+                    .radio_value(
+                        &mut self.temporaries.cluster_mode,
+                        mode,
+                        context.translate_0(mode.message_name()),
+                    )
+                    .changed()
+                {
+                    self.temporaries.expanded_clusters.clear();
+                }
+            }
+            ui.separator();
+            if ui
+                .button(context.translate_0("nh-view-clusternodes-expandall"))
+                .clicked()
+            {
+                self.temporaries
+                    .expanded_clusters
+                    .extend(self.temporaries.element_clusters.iter().map(|c| c.key));
+            }
+            if ui
+                .button(context.translate_0("nh-view-clusternodes-collapseall"))
+                .clicked()
+            {
+                self.temporaries.expanded_clusters.clear();
+            }
+        });
     }
     fn show_menubar_diagram_options(
         &mut self,
@@ -4689,6 +7549,107 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 self.temporaries.last_change_flag = true;
             }
             DiagramCommand::UndoImmediate | DiagramCommand::RedoImmediate => {}
+            // Handled by the project, which has access to the system clipboard
+            DiagramCommand::CopySelectedAsSvg
+            | DiagramCommand::CopySelectedAsPlantUml
+            | DiagramCommand::CopyLayoutAsJson
+            | DiagramCommand::ApplyLayoutFromJson => {}
+            DiagramCommand::MoveInDrawOrder(view, _) | DiagramCommand::LockInDrawOrder(view, _)
+                if self.is_locked(&view) => {}
+            DiagramCommand::MoveInDrawOrder(view, index) => {
+                self.owned_views.move_in_draw_order(&view, index);
+                self.owned_views.apply_locks(&self.z_order_locks);
+            }
+            DiagramCommand::LockInDrawOrder(view, lock) => {
+                match lock {
+                    Some(lock) => self.z_order_locks.insert(view, lock),
+                    None => self.z_order_locks.remove(&view),
+                };
+                self.owned_views.apply_locks(&self.z_order_locks);
+            }
+            DiagramCommand::SetLayerVisible(layer, visible) => {
+                if let Some(e) = self.layers.get_mut(&layer) {
+                    e.visible = visible;
+                }
+                if !visible {
+                    let views = self.layers.views_where(|e| e.uuid == layer);
+                    return vec![InsensitiveCommand::HighlightSpecific(
+                        views,
+                        false,
+                        Highlight::SELECTED,
+                    )];
+                }
+            }
+            DiagramCommand::SetLayerLocked(layer, locked) => {
+                if let Some(e) = self.layers.get_mut(&layer) {
+                    e.locked = locked;
+                }
+                if locked {
+                    let views = self.layers.views_where(|e| e.uuid == layer);
+                    return vec![InsensitiveCommand::HighlightSpecific(
+                        views,
+                        false,
+                        Highlight::SELECTED,
+                    )];
+                }
+            }
+            DiagramCommand::RemoveLayer(layer) => {
+                self.layers.remove(&layer);
+            }
+            DiagramCommand::MoveLayer(layer, index) => {
+                self.layers.move_to(&layer, index);
+            }
+            DiagramCommand::AssignSelectedToLayer(layer) => {
+                let selected: Vec<_> = self
+                    .owned_views
+                    .iter_event_order_pairs()
+                    .map(|e| e.0)
+                    .filter(|k| {
+                        self.temporaries
+                            .flattened_views_status
+                            .get(k)
+                            .is_some_and(|e| e.selected())
+                    })
+                    .collect();
+                self.layers.assign(selected, layer);
+                if layer.is_some_and(|l| self.layers.get(&l).is_some_and(|e| e.locked)) {
+                    return vec![InsensitiveCommand::HighlightAll(false, Highlight::SELECTED)];
+                }
+            }
+            DiagramCommand::SetElementLocked(view, locked) => {
+                if locked {
+                    self.locked_views.insert(view);
+                } else {
+                    self.locked_views.remove(&view);
+                }
+            }
+            DiagramCommand::LockSelectedElements(locked) => {
+                for (k, status) in self.temporaries.flattened_views_status.iter() {
+                    if !status.selected() {
+                        continue;
+                    }
+                    if locked {
+                        self.locked_views.insert(*k);
+                    } else {
+                        self.locked_views.remove(k);
+                    }
+                }
+            }
+            DiagramCommand::SetAnnotationsVisible(visible) => {
+                self.annotations.visible = visible;
+                if !visible {
+                    self.temporaries.annotation_tool = None;
+                }
+            }
+            DiagramCommand::SetBackgroundImage(image) => {
+                self.background = match (image, self.background.as_ref()) {
+                    (None, _) => UFOption::None,
+                    (Some(image), Some(b)) => {
+                        UFOption::Some(BackgroundImage { image, ..b.clone() })
+                    }
+                    (Some(image), None) => UFOption::Some(BackgroundImage::new(image)),
+                };
+            }
             DiagramCommand::InvertSelection => {
                 return vec![
                     InsensitiveCommand::HighlightAll(true, Highlight::SELECTED),
@@ -4751,8 +7712,8 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                             Some((x, y)) => egui::Vec2::new(x as f32, y as f32),
                             None => {
                                 (if target == *self.uuid {
-                                    -self.temporaries.camera_offset.to_vec2()
-                                        / self.temporaries.camera_scale
+                                    -self.temporaries.camera.offset.to_vec2()
+                                        / self.temporaries.camera.scale
                                 } else {
                                     self.temporaries
                                         .flattened_views
@@ -4795,6 +7756,53 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                     _ => unreachable!(),
                 };
             }
+            DiagramCommand::MatchSizeSelected(m) => {
+                let Some(reference) = self
+                    .temporaries
+                    .last_selected
+                    .filter(|e| {
+                        self.temporaries
+                            .flattened_views_status
+                            .get(e)
+                            .is_some_and(|e| e.selected())
+                    })
+                    .and_then(|e| self.temporaries.flattened_views.get(&e))
+                    .filter(|e| e.0.waypoints().is_empty())
+                    .map(|e| e.0.bounding_box().size())
+                else {
+                    return vec![];
+                };
+                let resizes: Vec<_> = self
+                    .temporaries
+                    .flattened_views_status
+                    .iter()
+                    .filter(|e| e.1.selected())
+                    .flat_map(|e| self.temporaries.flattened_views.get(e.0))
+                    .filter(|e| e.0.waypoints().is_empty())
+                    .flat_map(|(v, _)| {
+                        let current = v.bounding_box();
+                        let size = match m {
+                            SizeMatch::Width => egui::Vec2::new(reference.x, current.height()),
+                            SizeMatch::Height => egui::Vec2::new(current.width(), reference.y),
+                            SizeMatch::Both => reference,
+                        };
+                        (size != current.size()).then(|| {
+                            InsensitiveCommand::ResizeElementTo(
+                                *v.uuid(),
+                                egui::Rect::from_min_size(current.min, size),
+                            )
+                        })
+                    })
+                    .collect();
+                if resizes.is_empty() {
+                    return vec![];
+                }
+                return vec![InsensitiveCommand::Macro(
+                    "nh-viewcommand-matchsize".to_owned().into(),
+                    resizes.len(),
+                    resizes.into(),
+                )];
+            }
             DiagramCommand::ColorSelected(slot, color) => {
                 let ccd = ColorChangeData { slot, color };
                 return vec![InsensitiveCommand::PropertyChange(se!(), ccd.into())];
@@ -4837,36 +7845,30 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                     view_uuid.and_then(|e| self.temporaries.flattened_views.get(&e))
                 {
                     let bb = v.bounding_box();
-                    if force
-                        || !self
-                            .temporaries
-                            .last_interactive_canvas_rect
-                            .contains_rect(bb)
-                    {
-                        let lir = self.temporaries.last_interactive_canvas_rect.size() / 2.0
-                            * self.temporaries.camera_scale;
-                        self.temporaries.camera_scale = 1.0;
+                    if force || !self.temporaries.camera.canvas_rect.contains_rect(bb) {
+                        let lir = self.temporaries.camera.canvas_rect.size() / 2.0
+                            * self.temporaries.camera.scale;
                         let lir = egui::Pos2::new(lir.x.max(10.0), lir.y.max(10.0));
-                        self.temporaries.camera_offset = lir - bb.center().to_vec2();
+                        self.start_camera_transition(lir - bb.center().to_vec2(), 1.0);
                     }
                 }
             }
             DiagramCommand::PanCamera(x, y) => {
-                self.temporaries.camera_offset += -10.0 * egui::Vec2::new(x as f32, y as f32);
+                self.temporaries.camera.offset += -10.0 * egui::Vec2::new(x as f32, y as f32);
             }
             DiagramCommand::ResetPosition => {
-                self.temporaries.camera_offset = egui::Pos2::ZERO;
+                self.temporaries.camera.offset = egui::Pos2::ZERO;
             }
             DiagramCommand::AddZoomPercent(d) => {
-                let new_value = self.temporaries.camera_scale + d as f32 / 100.0;
+                let new_value = self.temporaries.camera.scale + d as f32 / 100.0;
                 if (Self::MIN_ZOOM_FACTOR..=Self::MAX_ZOOM_FACTOR).contains(&new_value) {
-                    self.temporaries.camera_scale = new_value;
+                    self.temporaries.camera.scale = new_value;
                 }
             }
             DiagramCommand::ResetScale => {
-                self.temporaries.camera_offset =
-                    self.temporaries.camera_offset / self.temporaries.camera_scale;
-                self.temporaries.camera_scale = 1.0;
+                self.temporaries.camera.offset =
+                    self.temporaries.camera.offset / self.temporaries.camera.scale;
+                self.temporaries.camera.scale = 1.0;
             }
             DiagramCommand::ZoomToFit { selected_only } => {
                 const PADDING: egui::Vec2 = egui::Vec2::splat(10.0);
@@ -4882,12 +7884,11 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 }
 
                 if area.is_positive() {
-                    let ratio = self.temporaries.last_interactive_canvas_rect.size()
-                        * self.temporaries.camera_scale
+                    let ratio = self.temporaries.camera.canvas_rect.size()
+                        * self.temporaries.camera.scale
                         / (area.size() + PADDING);
-                    self.temporaries.camera_scale = ratio.x.min(ratio.y);
-                    self.temporaries.camera_offset =
-                        area.min * -self.temporaries.camera_scale + PADDING / 2.0;
+                    let scale = ratio.x.min(ratio.y);
+                    self.start_camera_transition(area.min * -scale + PADDING / 2.0, scale);
                 }
             }
             DiagramCommand::CreateViewFor(model_uuid) => {
@@ -4920,11 +7921,13 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                             };
 
                             let r = {
+                                let notation = self.adapter.notation();
                                 let q = DomainT::QueryableT::new(
                                     *self.uuid,
                                     &pseudo_frm,
                                     &pseudo_fv,
                                     &pseudo_fvs,
+                                    &notation,
                                 );
                                 self.adapter.create_new_view_for(&q, model.clone())
                             };
@@ -4973,9 +7976,230 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                     )];
                 }
             }
+            DiagramCommand::AddChildElement | DiagramCommand::AddSiblingElement => {
+                let selected: Vec<ViewUuid> = se!();
+                if self.temporaries.inline_rename.is_some() || selected.len() != 1 {
+                    return vec![];
+                }
+                let (_, connections) = self.top_level_graph();
+                let edges: Vec<_> = connections.iter().flat_map(|e| e.1.iter()).collect();
+                let parent = if matches!(command, DiagramCommand::AddChildElement) {
+                    selected[0]
+                } else {
+                    match edges.iter().find(|e| e.1 == selected[0]) {
+                        Some(e) => e.0,
+                        None => return vec![],
+                    }
+                };
+                let Some((parent_view, _)) = self.temporaries.flattened_views.get(&parent) else {
+                    return vec![];
+                };
+
+                // Below the last child, or next to a parent without children
+                let parent_bounds = parent_view.bounding_box();
+                let last_child = edges
+                    .iter()
+                    .filter(|e| e.0 == parent)
+                    .flat_map(|e| self.temporaries.flattened_views.get(&e.1))
+                    .map(|e| e.0.bounding_box())
+                    .max_by(|a, b| a.max.y.total_cmp(&b.max.y));
+                let position = match last_child {
+                    Some(b) => egui::Pos2::new(b.center().x, b.max.y + 20.0 + b.height() / 2.0),
+                    None => parent_bounds.right_center() + egui::Vec2::new(120.0, 0.0),
+                };
+                let Some((element, connection)) =
+                    self.adapter.new_child_element(parent_view, position)
+                else {
+                    return vec![];
+                };
+
+                let element_uuid = *element.uuid();
+                if let Some(name) = self.adapter.element_name(&element.model()) {
+                    self.temporaries.inline_rename = Some(InlineRename {
+                        view: element_uuid,
+                        name: (*name).clone(),
+                        focused: false,
+                    });
+                }
+                return vec![
+                    InsensitiveCommand::HighlightAll(false, canvas::Highlight::SELECTED),
+                    InsensitiveCommand::Macro(
+                        "nh-viewcommand-addelements".to_owned().into(),
+                        1,
+                        vec![element, connection]
+                            .into_iter()
+                            .map(|e| InsensitiveCommand::AddDependency {
+                                target: *self.uuid,
+                                bucket: 0,
+                                position: None,
+                                element: e.into(),
+                                into_model: true,
+                            })
+                            .collect::<Vec<_>>()
+                            .into(),
+                    ),
+                    InsensitiveCommand::HighlightSpecific(
+                        std::iter::once(element_uuid).collect(),
+                        true,
+                        canvas::Highlight::SELECTED,
+                    ),
+                ];
+            }
+            DiagramCommand::LayoutAsTree { radial } => {
+                return self.top_level_layout_to_sensitives(|sizes, edges| {
+                    layout::tree_layout(sizes, edges, radial, egui::Vec2::new(60.0, 20.0))
+                });
+            }
+            DiagramCommand::LiveLayout(c) => match c {
+                LiveLayoutCommand::Start => self.temporaries.live_layout = true,
+                // The offsets are committed on the next frame
+                LiveLayoutCommand::Freeze => self.temporaries.live_layout = false,
+                LiveLayoutCommand::Discard => {
+                    self.temporaries.live_layout = false;
+                    for (k, delta) in std::mem::take(&mut self.temporaries.live_layout_offsets) {
+                        self.move_directly(k, -delta);
+                    }
+                }
+            },
+            DiagramCommand::LayoutLayered { upward } => {
+                return self.top_level_layout_to_sensitives(|sizes, edges| {
+                    layout::layered_layout(sizes, edges, upward, egui::Vec2::new(40.0, 60.0))
+                });
+            }
         };
         vec![]
     }
+    fn named_element_to_sensitives(
+        &mut self,
+        model: ModelUuid,
+        name: &str,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    > {
+        // Placed near the top left corner of the visible area, same as pasted elements
+        let position = (-self.temporaries.camera.offset.to_vec2() / self.temporaries.camera.scale
+            + egui::Vec2::splat(10.0))
+        .to_pos2();
+        let Some(view) = self.adapter.new_named_element(model, name, position) else {
+            return vec![];
+        };
+        let view_uuid = *view.uuid();
+        vec![
+            InsensitiveCommand::HighlightAll(false, canvas::Highlight::SELECTED),
+            InsensitiveCommand::AddDependency {
+                target: *self.uuid,
+                bucket: 0,
+                position: None,
+                element: view.into(),
+                into_model: true,
+            },
+            InsensitiveCommand::HighlightSpecific(
+                std::iter::once(view_uuid).collect(),
+                true,
+                canvas::Highlight::SELECTED,
+            ),
+        ]
+    }
+    fn layout_to_sensitives(
+        &mut self,
+        layout: &DiagramLayout,
+    ) -> Vec<
+        InsensitiveCommand<
+            DomainT::OrdinalMovementT,
+            DomainT::AddCommandElementT,
+            DomainT::PropChangeT,
+        >,
+    > {
+        let depth = |mut uuid: ViewUuid| {
+            let mut depth = 0;
+            while let Some((_, parent)) = self.temporaries.flattened_views.get(&uuid) {
+                depth += 1;
+                uuid = *parent;
+            }
+            depth
+        };
+        // Containers move their contents, so they are placed first, and connections last
+        let mut targets: Vec<_> = self
+            .temporaries
+            .flattened_represented_models
+            .iter()
+            .flat_map(|(m, v)| {
+                let view = self.temporaries.flattened_views.get(v)?.0.clone();
+                let is_connection = !view.waypoints().is_empty();
+                Some((
+                    (is_connection, depth(*v), *v),
+                    view,
+                    layout.elements.get(m)?,
+                ))
+            })
+            .collect();
+        targets.sort_by_key(|e| e.0);
+
+        // Elements are moved directly so that the following ones see the result,
+        // then the movement is reverted and returned as a single command
+        let mut moves = Vec::new();
+        let mut undo = Vec::new();
+        for ((is_connection, _, uuid), view, target) in targets {
+            let mut apply = |s: &mut Self, command| {
+                s.apply_command_inner(&command, &mut undo, &mut HashSet::new());
+                moves.push(command);
+            };
+            if is_connection {
+                let waypoints = view.waypoints();
+                if waypoints.len() != target.waypoints.len() {
+                    continue;
+                }
+                for ((vertex, current), saved) in waypoints.into_iter().zip(&target.waypoints) {
+                    if current != *saved {
+                        apply(
+                            self,
+                            InsensitiveCommand::MovePositional(
+                                std::iter::once(vertex).collect(),
+                                *saved - current,
+                            ),
+                        );
+                    }
+                }
+            } else {
+                let current = view.bounding_box();
+                if current.size() != target.bounds.size() {
+                    apply(
+                        self,
+                        InsensitiveCommand::ResizeElementTo(
+                            uuid,
+                            egui::Rect::from_min_size(current.min, target.bounds.size()),
+                        ),
+                    );
+                }
+                let current = view.bounding_box();
+                if current.min != target.bounds.min {
+                    apply(
+                        self,
+                        InsensitiveCommand::MovePositional(
+                            std::iter::once(uuid).collect(),
+                            target.bounds.min - current.min,
+                        ),
+                    );
+                }
+            }
+        }
+        for e in undo.into_iter().rev() {
+            self.apply_command_inner(&e, &mut Vec::new(), &mut HashSet::new());
+        }
+
+        if moves.is_empty() {
+            return vec![];
+        }
+        vec![InsensitiveCommand::Macro(
+            "nh-viewcommand-applylayout".to_owned().into(),
+            moves.len(),
+            moves.into(),
+        )]
+    }
     fn apply_command(
         &mut self,
         command: &InsensitiveCommand<
@@ -4992,6 +8216,14 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         >,
         affected_models: &mut HashSet<ModelUuid>,
     ) {
+        if !matches!(
+            command,
+            InsensitiveCommand::HighlightAll(..)
+                | InsensitiveCommand::HighlightSpecific(..)
+                | InsensitiveCommand::SelectByDrag(..)
+        ) {
+            self.finish_layout_transition();
+        }
         self.apply_command_inner(command, undo_accumulator, affected_models);
     }
 
@@ -5005,6 +8237,11 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
         let Some(settings) = (settings as &dyn Any).downcast_ref::<DomainT::SettingsT>() else {
             return;
         };
+        let profile = self.color_profile(context);
+        let mut themed_canvas = ThemedCanvas::new(canvas, profile);
+        let canvas: &mut dyn NHCanvas = &mut themed_canvas;
+        self.collect_connection_bundles();
+        self.collect_element_clusters();
 
         let tool =
             if let (Some(pos), Some(stage)) = (mouse_pos, self.temporaries.current_tool.as_ref()) {
@@ -5013,20 +8250,136 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                 None
             };
         let mut drawn_targetting = TargettingStatus::NotDrawn;
+        let notation = self.adapter.notation();
         let queryable = DomainT::QueryableT::new(
             *self.uuid,
             &self.temporaries.flattened_represented_models,
             &self.temporaries.flattened_views,
             &self.temporaries.flattened_views_status,
+            &notation,
         );
 
-        self.owned_views.draw_order_foreach_mut(|v| {
-            if v.draw_in(&queryable, context, settings, canvas, &tool) == TargettingStatus::Drawn {
-                drawn_targetting = TargettingStatus::Drawn;
-            }
-        });
+        let export_area = || {
+            self.temporaries
+                .flattened_views
+                .values()
+                .fold(egui::Rect::NOTHING, |acc, e| acc.union(e.0.bounding_box()))
+                .expand(BackgroundImage::TILED_EXPORT_MARGIN)
+        };
+        // Exports are transparent unless the background differs from the default one
+        let background_color = self.canvas_colors(context).0;
+        if canvas.ui_scale().is_none()
+            && background_color != ColorProfile::LIGHT.background
+            && export_area().is_positive()
+        {
+            canvas.draw_rectangle(
+                export_area(),
+                egui::CornerRadius::ZERO,
+                background_color,
+                canvas::Stroke::NONE,
+                canvas::Highlight::NONE,
+            );
+        }
+        if let Some(background) = self.background.as_ref() {
+            let area = if canvas.ui_scale().is_some() {
+                self.temporaries.camera.canvas_rect
+            } else {
+                export_area()
+            };
+            background.draw_in(&context.images, canvas, area);
+        }
+        for e in &self.images {
+            e.draw_in(
+                context,
+                canvas,
+                self.temporaries.selected_image == Some(e.uuid),
+            );
+        }
+        if let Some(kind) = self.frame.as_ref() {
+            self.draw_frame(kind, canvas);
+        }
+
+        let bundled_connections = &self.temporaries.bundled_connections;
+        let clustered_views = &self.temporaries.clustered_views;
+        let layers = &self.layers;
+        let mut styled_canvas = StyledCanvas::new(canvas, &self.element_styles);
+        let mut jump_canvas = LineJumpCanvas::new(&mut styled_canvas, self.line_jumps);
+        for pass in layers.draw_passes() {
+            self.owned_views.draw_order_foreach_mut(|v| {
+                if layers.layer_of(&v.uuid()).map(|e| e.uuid) != pass {
+                    return;
+                }
+                jump_canvas.begin_element(&v.uuid());
+                if !bundled_connections.contains(&v.uuid())
+                    && !clustered_views.contains(&v.uuid())
+                    && v.draw_in(&queryable, context, settings, &mut jump_canvas, &tool)
+                        == TargettingStatus::Drawn
+                {
+                    drawn_targetting = TargettingStatus::Drawn;
+                }
+                jump_canvas.end_element();
+            });
+        }
+        self.draw_connection_bundles(canvas);
+        self.draw_element_clusters(context, canvas);
+        self.annotations.draw_in(
+            canvas,
+            self.temporaries.drawn_annotation.as_ref().map(|e| &e.0),
+        );
 
         if canvas.ui_scale().is_some() {
+            for (k, (v, _)) in &self.temporaries.flattened_views {
+                if layers.is_hidden(k) {
+                    continue;
+                }
+                if !clustered_views.contains(k) && context.outdated_models.contains(&v.model_uuid())
+                {
+                    let badge = v.bounding_box().right_top();
+                    canvas.draw_ellipse(
+                        badge,
+                        egui::Vec2::splat(7.0),
+                        egui::Color32::ORANGE,
+                        canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                        canvas::Highlight::NONE,
+                    );
+                    canvas.draw_text(
+                        badge,
+                        egui::Align2::CENTER_CENTER,
+                        "!",
+                        canvas::CLASS_TOP_FONT_SIZE,
+                        egui::Color32::BLACK,
+                    );
+                }
+                if !clustered_views.contains(k)
+                    && context.issue_linked_models.contains(&v.model_uuid())
+                {
+                    let badge = v.bounding_box().left_top();
+                    canvas.draw_ellipse(
+                        badge,
+                        egui::Vec2::splat(7.0),
+                        egui::Color32::LIGHT_BLUE,
+                        canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                        canvas::Highlight::NONE,
+                    );
+                    canvas.draw_text(
+                        badge,
+                        egui::Align2::CENTER_CENTER,
+                        "#",
+                        canvas::CLASS_TOP_FONT_SIZE,
+                        egui::Color32::BLACK,
+                    );
+                }
+                if !clustered_views.contains(k) && self.locked_views.contains(k) {
+                    canvas.draw_text(
+                        v.bounding_box().right_bottom(),
+                        egui::Align2::CENTER_CENTER,
+                        "🔒",
+                        canvas::CLASS_TOP_FONT_SIZE,
+                        egui::Color32::BLACK,
+                    );
+                }
+            }
+
             if let Some((pos, tool)) = tool {
                 if drawn_targetting == TargettingStatus::NotDrawn {
                     canvas.draw_rectangle(
@@ -5037,7 +8390,12 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
                         canvas::Highlight::NONE,
                     );
                     self.owned_views.draw_order_foreach_mut(|v| {
-                        v.draw_in(&queryable, context, settings, canvas, &Some((pos, tool)));
+                        if !bundled_connections.contains(&v.uuid())
+                            && !clustered_views.contains(&v.uuid())
+                            && !layers.is_hidden(&v.uuid())
+                        {
+                            v.draw_in(&queryable, context, settings, canvas, &Some((pos, tool)));
+                        }
                     });
                 }
                 tool.draw_status_hint(&queryable, canvas, pos);
@@ -5054,10 +8412,67 @@ impl<DomainT: Domain, DiagramAdapterT: DiagramAdapter<DomainT>> DiagramView2<Dom
             self.temporaries.snap_manager.draw_best(
                 canvas,
                 egui::Color32::BLUE,
-                self.temporaries.last_interactive_canvas_rect,
+                self.temporaries.camera.canvas_rect,
             );
         }
     }
+    fn draw_selected_in(
+        &mut self,
+        context: &GlobalDrawingContext,
+        settings: &dyn DiagramSettings,
+        canvas: &mut dyn NHCanvas,
+    ) {
+        let Some(settings) = (settings as &dyn Any).downcast_ref::<DomainT::SettingsT>() else {
+            return;
+        };
+        let notation = self.adapter.notation();
+        let queryable = DomainT::QueryableT::new(
+            *self.uuid,
+            &self.temporaries.flattened_represented_models,
+            &self.temporaries.flattened_views,
+            &self.temporaries.flattened_views_status,
+            &notation,
+        );
+        let status = &self.temporaries.flattened_views_status;
+        let is_selected = |v: &ViewUuid| status.get(v).is_some_and(|e| e.selected());
+
+        let mut themed_canvas = ThemedCanvas::new(canvas, self.color_profile(context));
+        let mut styled_canvas = StyledCanvas::new(&mut themed_canvas, &self.element_styles);
+        self.owned_views.draw_order_foreach_mut(|v| {
+            if is_selected(&v.uuid()) {
+                styled_canvas.begin_element(&v.uuid());
+                v.draw_in(&queryable, context, settings, &mut styled_canvas, &None);
+                styled_canvas.end_element();
+            }
+        });
+
+        // Nested elements are drawn by their containers, unless none of them is selected
+        let has_selected_ancestor = |mut v: ViewUuid| {
+            while let Some((_, parent)) = self.temporaries.flattened_views.get(&v)
+                && *parent != *self.uuid
+            {
+                if is_selected(parent) {
+                    return true;
+                }
+                v = *parent;
+            }
+            false
+        };
+        let mut nested: Vec<_> = self
+            .temporaries
+            .flattened_views
+            .iter()
+            .filter(|(k, (_, parent))| {
+                *parent != *self.uuid && is_selected(k) && !has_selected_ancestor(**k)
+            })
+            .map(|(_, (v, _))| v.clone())
+            .collect();
+        for v in nested.iter_mut() {
+            styled_canvas.begin_element(&v.uuid());
+            v.draw_in(&queryable, context, settings, &mut styled_canvas, &None);
+            styled_canvas.end_element();
+        }
+    }
 
     fn extend_models_for(&self, views: &HashSet<ViewUuid>, models: &mut HashSet<ModelUuid>) {
         models.extend(
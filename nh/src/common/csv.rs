@@ -0,0 +1,45 @@
+/// Splits a single CSV line into trimmed fields.
+///
+/// Fields may be enclosed in double quotes, in which case they can contain commas
+/// and doubled quotes (`""`) stand for a literal quote.
+pub fn split_line(line: &str) -> Vec<String> {
+    split_line_with(line, ',')
+}
+
+/// Splits a single line into trimmed fields like [`split_line`], using another delimiter
+pub fn split_line_with(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields.into_iter().map(|e| e.trim().to_owned()).collect()
+}
+
+/// Joins fields into a single CSV line, quoting those which would not be read back as they are
+pub fn join_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|e| {
+            if e.contains([',', '"', '\n', '\r']) || e.trim() != *e {
+                format!("\"{}\"", e.replace('"', "\"\""))
+            } else {
+                (*e).to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
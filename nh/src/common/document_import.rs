@@ -0,0 +1,101 @@
+/// Imported documents longer than this are split, so that their editors stay responsive
+pub const SPLIT_THRESHOLD: usize = 64 * 1024;
+
+/// Level of a Markdown ATX heading on the line
+pub fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|e| *e == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])))
+        .then_some(level)
+}
+
+/// Splits the text into parts of at least `max_len` bytes at blank lines,
+/// or at any line break when there is no blank line for too long
+fn split_at_paragraphs(content: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for line in content.split_inclusive('\n') {
+        if (current.len() >= max_len && line.trim().is_empty()) || current.len() >= 2 * max_len {
+            parts.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Splits a Markdown or plain text document into parts starting with its top-level headings,
+/// text before the first heading is the first part.
+///
+/// Documents shorter than `max_len` are kept whole. Parts which are still too long,
+/// as well as texts without headings, are split at paragraphs.
+pub fn split_document(content: &str, max_len: usize) -> Vec<String> {
+    if content.len() <= max_len {
+        return vec![content.to_owned()];
+    }
+
+    let mut in_fence = false;
+    let mut headings = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && let Some(level) = heading_level(line) {
+            headings.push((offset, level));
+        }
+        offset += line.len();
+    }
+
+    let top_level = headings.iter().map(|e| e.1).min();
+    let mut starts: Vec<_> = headings
+        .iter()
+        .filter(|e| Some(e.1) == top_level)
+        .map(|e| e.0)
+        .filter(|e| *e > 0)
+        .collect();
+    starts.insert(0, 0);
+    starts.push(content.len());
+
+    starts
+        .windows(2)
+        .map(|e| &content[e[0]..e[1]])
+        .filter(|e| !e.trim().is_empty())
+        .flat_map(|e| split_at_paragraphs(e, max_len))
+        .collect()
+}
+
+/// Name of the part, its first non-empty line without heading markers
+pub fn part_name(part: &str) -> &str {
+    part.lines()
+        .map(|e| e.trim_start_matches('#').trim())
+        .find(|e| !e.is_empty())
+        .unwrap_or("empty document")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documents_are_split_at_top_level_headings() {
+        let content = "Preface\n\n## Intro\nText\n```\n## not a heading\n```\n### Detail\n\n## Usage\nMore text\n";
+        assert_eq!(split_document(content, 1000), vec![content.to_owned()]);
+
+        let parts = split_document(content, 60);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], "Preface\n\n");
+        assert!(parts[1].starts_with("## Intro\n") && parts[1].ends_with("### Detail\n\n"));
+        assert_eq!(part_name(&parts[2]), "Usage");
+        assert_eq!(parts.concat(), content);
+
+        let plain = "first paragraph\nof text\n\nsecond one\n\nthird\n";
+        let parts = split_document(plain, 10);
+        assert_eq!(
+            parts,
+            vec!["first paragraph\nof text\n", "\nsecond one\n", "\nthird\n"]
+        );
+    }
+}
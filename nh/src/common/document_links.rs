@@ -0,0 +1,51 @@
+use super::uuid::ModelUuid;
+
+/// Target prefix of Markdown links from documents to model elements
+const ELEMENT_LINK_PREFIX: &str = "nh:element/";
+
+/// Markdown link to the model element with the given text
+pub fn element_link(text: &str, model: &ModelUuid) -> String {
+    format!("[{}]({}{})", text, ELEMENT_LINK_PREFIX, model.to_string())
+}
+
+/// Texts and targets of links to model elements in the document
+pub fn element_links(content: &str) -> Vec<(&str, ModelUuid)> {
+    let pattern = format!("]({}", ELEMENT_LINK_PREFIX);
+    let mut links = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find(&pattern) {
+        let text_end = offset + start;
+        let target_start = text_end + pattern.len();
+        offset = target_start;
+        let Some(text_start) = content[..text_end].rfind(['[', '\n']) else {
+            continue;
+        };
+        let Some(target_len) = content[target_start..].find(')') else {
+            continue;
+        };
+        if !content[text_start..].starts_with('[') {
+            continue;
+        }
+        if let Ok(target) = uuid::Uuid::parse_str(&content[target_start..target_start + target_len])
+        {
+            links.push((&content[text_start + 1..text_end], target.into()));
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn element_links_are_found() {
+        let (a, b) = (ModelUuid::now_v7(), ModelUuid::now_v7());
+        let content = format!(
+            "Each {} places\nan {}, see [docs](https://example.org).\n[broken](nh:element/x)",
+            element_link("Customer", &a),
+            element_link("Order", &b),
+        );
+        assert_eq!(element_links(&content), vec![("Customer", a), ("Order", b)]);
+    }
+}
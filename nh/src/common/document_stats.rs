@@ -0,0 +1,95 @@
+use super::document_import::heading_level;
+
+/// Words read per minute used to estimate reading time
+const READING_SPEED: usize = 200;
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct DocumentHeading {
+    pub level: usize,
+    pub title: String,
+    /// Offset of the start of the heading line in characters
+    pub char_offset: usize,
+}
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct DocumentStats {
+    pub words: usize,
+    pub characters: usize,
+    pub headings: Vec<DocumentHeading>,
+}
+
+impl DocumentStats {
+    pub fn of(content: &str) -> Self {
+        let mut stats = Self::default();
+        let mut in_fence = false;
+        for line in content.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            } else if !in_fence && let Some(level) = heading_level(line) {
+                stats.headings.push(DocumentHeading {
+                    level,
+                    title: line.trim_start_matches('#').trim().to_owned(),
+                    char_offset: stats.characters,
+                });
+            }
+            // Markup such as list bullets or heading markers is not counted as words
+            stats.words += line
+                .split_whitespace()
+                .filter(|e| e.chars().any(|c| c.is_alphanumeric()))
+                .count();
+            stats.characters += line.chars().count();
+        }
+        stats
+    }
+
+    pub fn add(&mut self, other: &Self) {
+        self.words += other.words;
+        self.characters += other.characters;
+    }
+
+    /// Heading of the section containing the character offset
+    pub fn heading_at(&self, char_offset: usize) -> Option<&DocumentHeading> {
+        self.headings
+            .iter()
+            .take_while(|e| e.char_offset <= char_offset)
+            .last()
+    }
+
+    /// Estimated reading time in whole minutes, at least one for non-empty documents
+    pub fn reading_minutes(&self) -> usize {
+        self.words.div_ceil(READING_SPEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_and_headings_are_counted() {
+        let stats = DocumentStats::of(
+            "# Orders\n\nEach order has - at most - one invoice.\n\n```\n# comment\n```\n## Invoices\n* paid\n",
+        );
+        assert_eq!(stats.words, 11);
+        assert_eq!(
+            stats.headings,
+            vec![
+                DocumentHeading {
+                    level: 1,
+                    title: "Orders".to_owned(),
+                    char_offset: 0,
+                },
+                DocumentHeading {
+                    level: 2,
+                    title: "Invoices".to_owned(),
+                    char_offset: 69,
+                },
+            ]
+        );
+        assert_eq!(stats.heading_at(68).map(|e| e.level), Some(1));
+        assert_eq!(stats.heading_at(69).map(|e| e.level), Some(2));
+        assert_eq!(stats.reading_minutes(), 1);
+        assert_eq!(DocumentStats::of("").reading_minutes(), 0);
+    }
+}
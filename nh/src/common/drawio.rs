@@ -0,0 +1,430 @@
+use eframe::egui;
+
+use super::canvas::{Highlight, LineType, NHCanvas, Stroke};
+use super::images::ProjectImage;
+
+enum DrawioCell {
+    Vertex {
+        rect: egui::Rect,
+        style: String,
+        value: String,
+        /// Text labels are never connected to
+        connectable: bool,
+    },
+    Edge {
+        points: Vec<egui::Pos2>,
+        style: String,
+        /// Polygon outlines are closed and are not continued by further lines
+        closed: bool,
+    },
+}
+
+/// Records drawn shapes as cells of a draw.io (mxGraph) diagram.
+///
+/// Consecutive lines sharing endpoints become a single edge with waypoints, and edge ends
+/// leaving a rectangle or ellipse from its border are connected to it.
+/// Polygons are kept as closed outlines, as mxGraph has no fillable free-form shape.
+pub struct DrawioCanvas<'a> {
+    camera_offset: egui::Pos2,
+    highlight_filter: Highlight,
+    highlight_colors: [egui::Color32; 4],
+    painter: &'a egui::Painter,
+    cells: Vec<DrawioCell>,
+}
+
+impl<'a> DrawioCanvas<'a> {
+    pub fn new(
+        camera_offset: egui::Pos2,
+        highlight_filter: Highlight,
+        painter: &'a egui::Painter,
+    ) -> Self {
+        Self {
+            camera_offset,
+            highlight_filter,
+            highlight_colors: [
+                egui::Color32::BLUE,
+                egui::Color32::GREEN,
+                egui::Color32::RED,
+                egui::Color32::ORANGE,
+            ],
+            painter,
+            cells: Vec::new(),
+        }
+    }
+
+    fn stroke_style(&self, stroke: Stroke, h: Highlight) -> String {
+        let stroke = stroke.highlighted(&h & self.highlight_filter, &self.highlight_colors);
+        let dashes = match stroke.line_type {
+            LineType::Solid => "",
+            LineType::Dashed => "dashed=1;dashPattern=10 5;",
+            LineType::Dotted => "dashed=1;dashPattern=2 2;",
+        };
+        format!(
+            "strokeColor={};strokeWidth={};{}",
+            color(stroke.color),
+            stroke.width,
+            dashes,
+        )
+    }
+
+    fn push_vertex(&mut self, rect: egui::Rect, style: String, value: String, connectable: bool) {
+        self.cells.push(DrawioCell::Vertex {
+            rect: rect.translate(self.camera_offset.to_vec2()),
+            style,
+            value,
+            connectable,
+        });
+    }
+
+    /// Finds the smallest shape which the edge leaves from `end` towards `next`
+    fn attached_vertex(&self, end: egui::Pos2, next: egui::Pos2) -> Option<usize> {
+        const TOLERANCE: f32 = 2.0;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, c)| match c {
+                DrawioCell::Vertex {
+                    rect,
+                    connectable: true,
+                    ..
+                } if rect.expand(TOLERANCE).contains(end)
+                    && !rect.shrink(TOLERANCE).contains(end)
+                    && !rect.shrink(TOLERANCE).contains(end.lerp(next, 0.5)) =>
+                {
+                    Some((idx, rect.area()))
+                }
+                _ => None,
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|e| e.0)
+    }
+
+    pub fn into_bytes(self, diagram_name: &str) -> Vec<u8> {
+        // Ids 0 and 1 are taken by the root and the default layer
+        let cell_id = |idx: usize| idx + 2;
+
+        let mut cells = String::new();
+        for (idx, c) in self.cells.iter().enumerate() {
+            match c {
+                DrawioCell::Vertex {
+                    rect, style, value, ..
+                } => {
+                    cells += &format!(
+                        r#"<mxCell id="{}" value="{}" style="{}" vertex="1" parent="1"><mxGeometry x="{}" y="{}" width="{}" height="{}" as="geometry"/></mxCell>
+"#,
+                        cell_id(idx),
+                        escape(value),
+                        escape(style),
+                        rect.min.x,
+                        rect.min.y,
+                        rect.width(),
+                        rect.height(),
+                    );
+                }
+                DrawioCell::Edge {
+                    points,
+                    style,
+                    closed,
+                } => {
+                    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                        continue;
+                    };
+                    let mut connections = String::new();
+                    if !closed && points.len() > 1 {
+                        if let Some(source) = self.attached_vertex(*first, points[1]) {
+                            connections += &format!(r#" source="{}""#, cell_id(source));
+                        }
+                        if let Some(target) = self.attached_vertex(*last, points[points.len() - 2])
+                        {
+                            connections += &format!(r#" target="{}""#, cell_id(target));
+                        }
+                    }
+                    let waypoints: String = points[1..points.len() - 1]
+                        .iter()
+                        .map(|p| format!(r#"<mxPoint x="{}" y="{}"/>"#, p.x, p.y))
+                        .collect();
+                    cells += &format!(
+                        r#"<mxCell id="{}" style="{}" edge="1" parent="1"{}><mxGeometry relative="1" as="geometry"><mxPoint x="{}" y="{}" as="sourcePoint"/><mxPoint x="{}" y="{}" as="targetPoint"/><Array as="points">{}</Array></mxGeometry></mxCell>
+"#,
+                        cell_id(idx),
+                        escape(style),
+                        connections,
+                        first.x,
+                        first.y,
+                        last.x,
+                        last.y,
+                        waypoints,
+                    );
+                }
+            }
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<mxfile host="Nihonium">
+<diagram name="{}" id="nihonium-export">
+<mxGraphModel grid="1" gridSize="10" page="0">
+<root>
+<mxCell id="0"/>
+<mxCell id="1" parent="0"/>
+{}</root>
+</mxGraphModel>
+</diagram>
+</mxfile>
+"#,
+            escape(diagram_name),
+            cells,
+        )
+        .into_bytes()
+    }
+}
+
+fn color(c: egui::Color32) -> String {
+    if c.a() == 0 {
+        "none".to_owned()
+    } else {
+        format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b())
+    }
+}
+
+fn fill_style(c: egui::Color32) -> String {
+    if c.a() == 0 || c.a() == 255 {
+        format!("fillColor={};", color(c))
+    } else {
+        format!(
+            "fillColor={};fillOpacity={};",
+            color(c),
+            c.a() as u32 * 100 / 255
+        )
+    }
+}
+
+/// Line breaks of labels are kept as character references, as draw.io expects
+fn escape(s: &str) -> String {
+    super::xml::escape(s).replace('\n', "&#xa;")
+}
+
+impl<'a> NHCanvas for DrawioCanvas<'a> {
+    fn ui_scale(&self) -> Option<f32> {
+        None
+    }
+
+    fn draw_line(&mut self, points: [egui::Pos2; 2], stroke: Stroke, highlight: Highlight) {
+        let style = format!(
+            "endArrow=none;html=1;rounded=0;{}",
+            self.stroke_style(stroke, highlight)
+        );
+        let [a, b] = points.map(|e| e + self.camera_offset.to_vec2());
+
+        if let Some(DrawioCell::Edge {
+            points,
+            style: last_style,
+            closed: false,
+        }) = self.cells.last_mut()
+            && *last_style == style
+            && points.last().is_some_and(|e| e.distance(a) < 0.5)
+        {
+            points.push(b);
+        } else {
+            self.cells.push(DrawioCell::Edge {
+                points: vec![a, b],
+                style,
+                closed: false,
+            });
+        }
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        rect: egui::Rect,
+        corner_radius: egui::CornerRadius,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let rounding = match corner_radius.nw {
+            0 => "rounded=0;".to_owned(),
+            r => format!("rounded=1;absoluteArcSize=1;arcSize={};", 2 * r),
+        };
+        let style = format!(
+            "whiteSpace=wrap;html=1;{}{}{}",
+            rounding,
+            fill_style(color),
+            self.stroke_style(stroke, highlight)
+        );
+        self.push_vertex(rect, style, String::new(), true);
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        position: egui::Pos2,
+        radius: egui::Vec2,
+        color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let style = format!(
+            "ellipse;whiteSpace=wrap;html=1;{}{}",
+            fill_style(color),
+            self.stroke_style(stroke, highlight)
+        );
+        self.push_vertex(
+            egui::Rect::from_center_size(position, 2.0 * radius),
+            style,
+            String::new(),
+            true,
+        );
+    }
+
+    fn draw_polygon(
+        &mut self,
+        vertices: Vec<egui::Pos2>,
+        _color: egui::Color32,
+        stroke: Stroke,
+        highlight: Highlight,
+    ) {
+        let Some(first) = vertices.first().copied() else {
+            return;
+        };
+        let style = format!(
+            "endArrow=none;html=1;rounded=0;{}",
+            self.stroke_style(stroke, highlight)
+        );
+        let points = vertices
+            .into_iter()
+            .chain(std::iter::once(first))
+            .map(|e| e + self.camera_offset.to_vec2())
+            .collect();
+        self.cells.push(DrawioCell::Edge {
+            points,
+            style,
+            closed: true,
+        });
+    }
+
+    fn measure_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+    ) -> egui::Rect {
+        self.painter.text(
+            position,
+            anchor,
+            text,
+            egui::FontId::proportional(font_size),
+            egui::Color32::TRANSPARENT,
+        )
+    }
+    fn draw_text(
+        &mut self,
+        position: egui::Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        font_size: f32,
+        text_color: egui::Color32,
+    ) {
+        let rect = self.measure_text(position, anchor, text, font_size);
+        let align = match anchor.x() {
+            egui::Align::Min => "left",
+            egui::Align::Center => "center",
+            egui::Align::Max => "right",
+        };
+        let style = format!(
+            "text;html=0;strokeColor=none;fillColor=none;align={};verticalAlign=middle;spacing=0;fontSize={};fontColor={};",
+            align,
+            font_size,
+            color(text_color),
+        );
+        self.push_vertex(rect, style, text.to_owned(), false);
+    }
+
+    fn draw_image(
+        &mut self,
+        rect: egui::Rect,
+        image: &ProjectImage,
+        tile: Option<egui::Rect>,
+        opacity: f32,
+    ) {
+        // mxGraph has no repeated images, tiles are placed one by one
+        const MAX_TILES: i32 = 1000;
+
+        // Styles are separated by semicolons, so draw.io expects data URIs without ";base64"
+        let style = format!(
+            "shape=image;html=1;imageAspect=0;opacity={};image=data:{},{};",
+            (opacity * 100.0).round(),
+            image.mime_type(),
+            image.to_base64(),
+        );
+        let Some(tile) = tile else {
+            self.push_vertex(rect, style, String::new(), false);
+            return;
+        };
+
+        let first = ((rect.min - tile.min) / tile.size()).floor();
+        let last = ((rect.max - tile.min) / tile.size()).ceil();
+        let (columns, rows) = ((last.x - first.x) as i32, (last.y - first.y) as i32);
+        if columns.saturating_mul(rows) > MAX_TILES {
+            return;
+        }
+        for row in 0..rows {
+            for column in 0..columns {
+                let offset = (first + egui::Vec2::new(column as f32, row as f32)) * tile.size();
+                self.push_vertex(tile.translate(offset), style.clone(), String::new(), false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_joined_into_edges_connected_to_shapes() {
+        let ctx = egui::Context::default();
+        let painter = egui::Painter::new(ctx, egui::LayerId::background(), egui::Rect::EVERYTHING);
+        let mut canvas = DrawioCanvas::new(egui::Pos2::ZERO, Highlight::NONE, &painter);
+        let stroke = Stroke::new_solid(1.0, egui::Color32::BLACK);
+
+        canvas.draw_rectangle(
+            egui::Rect::from_min_size(egui::Pos2::new(0.0, 0.0), egui::Vec2::splat(100.0)),
+            egui::CornerRadius::ZERO,
+            egui::Color32::WHITE,
+            stroke,
+            Highlight::NONE,
+        );
+        canvas.draw_ellipse(
+            egui::Pos2::new(300.0, 300.0),
+            egui::Vec2::splat(50.0),
+            egui::Color32::WHITE,
+            stroke,
+            Highlight::NONE,
+        );
+        // Compartment separator inside the rectangle
+        canvas.draw_line(
+            [egui::Pos2::new(0.0, 30.0), egui::Pos2::new(100.0, 30.0)],
+            stroke,
+            Highlight::NONE,
+        );
+        canvas.draw_line(
+            [egui::Pos2::new(100.0, 50.0), egui::Pos2::new(300.0, 50.0)],
+            Stroke::new_dashed(1.0, egui::Color32::BLACK),
+            Highlight::NONE,
+        );
+        canvas.draw_line(
+            [egui::Pos2::new(300.0, 50.0), egui::Pos2::new(300.0, 250.0)],
+            Stroke::new_dashed(1.0, egui::Color32::BLACK),
+            Highlight::NONE,
+        );
+
+        assert_eq!(canvas.cells.len(), 4);
+        let xml = String::from_utf8(canvas.into_bytes("A & B")).unwrap();
+        assert!(xml.contains(r#"<diagram name="A &amp; B""#));
+        assert!(xml.contains(r#"style="ellipse;"#));
+        assert!(xml.contains(r#"source="2" target="3""#));
+        assert_eq!(xml.matches("source=").count(), 1);
+        assert!(xml.contains(r#"<Array as="points"><mxPoint x="300" y="50"/></Array>"#));
+    }
+}
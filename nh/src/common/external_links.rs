@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::uuid::ModelUuid;
+
+/// Reference from an element to a file outside of the project, such as a source file
+/// or a specification the element is kept in sync with
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExternalLink {
+    pub element: ModelUuid,
+    /// Absolute, or relative to the folder containing the project
+    pub path: String,
+    /// Seconds since the Unix epoch
+    pub last_edit: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExternalLinkStatus {
+    UpToDate,
+    Outdated,
+    Missing,
+}
+
+impl ExternalLinkStatus {
+    pub fn message_name(&self) -> &'static str {
+        match self {
+            ExternalLinkStatus::UpToDate => "nh-tab-externallinks-uptodate",
+            ExternalLinkStatus::Outdated => "nh-tab-externallinks-outdated",
+            ExternalLinkStatus::Missing => "nh-tab-externallinks-missing",
+        }
+    }
+}
+
+impl ExternalLink {
+    pub fn new(element: ModelUuid) -> Self {
+        Self {
+            element,
+            path: String::new(),
+            last_edit: now(),
+        }
+    }
+
+    pub fn resolve(&self, project_folder: Option<&Path>) -> PathBuf {
+        let path = PathBuf::from(&self.path);
+        match project_folder {
+            Some(folder) if path.is_relative() => folder.join(path),
+            _ => path,
+        }
+    }
+
+    /// The file is outdated when it was modified after the last edit of the element
+    pub fn status(&self, file_modified: Option<u64>) -> ExternalLinkStatus {
+        match file_modified {
+            None => ExternalLinkStatus::Missing,
+            Some(m) if m > self.last_edit => ExternalLinkStatus::Outdated,
+            Some(_) => ExternalLinkStatus::UpToDate,
+        }
+    }
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|e| e.as_secs())
+            .unwrap_or_default()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        0
+    }
+}
+
+/// Time of the last modification of the file in seconds since the Unix epoch,
+/// None if it cannot be read
+#[cfg(not(target_arch = "wasm32"))]
+pub fn file_modified(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .and_then(|e| e.modified())
+        .ok()
+        .and_then(|e| e.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|e| e.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_are_outdated_by_later_file_changes() {
+        let mut link = ExternalLink::new(ModelUuid::nil());
+        link.path = "spec/orders.md".to_owned();
+        link.last_edit = 1000;
+
+        assert_eq!(link.status(None), ExternalLinkStatus::Missing);
+        assert_eq!(link.status(Some(999)), ExternalLinkStatus::UpToDate);
+        assert_eq!(link.status(Some(1000)), ExternalLinkStatus::UpToDate);
+        assert_eq!(link.status(Some(1001)), ExternalLinkStatus::Outdated);
+
+        let folder = PathBuf::from("/projects/shop");
+        assert_eq!(
+            link.resolve(Some(&folder)),
+            PathBuf::from("/projects/shop/spec/orders.md")
+        );
+        link.path = "/srv/orders.md".to_owned();
+        assert_eq!(link.resolve(Some(&folder)), PathBuf::from("/srv/orders.md"));
+    }
+}
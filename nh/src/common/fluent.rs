@@ -22,3 +22,19 @@ pub fn create_fluent_bundle(
 
     Ok(bundle)
 }
+
+/// Translated variants of a piece of model content, keyed by language identifier
+pub type ContentTranslations = std::collections::BTreeMap<String, std::sync::Arc<String>>;
+
+/// Picks the first translation in the order of preference, falling back to the untranslated text
+pub fn localized<'a>(
+    base: &'a str,
+    translations: &'a ContentTranslations,
+    languages: &[LanguageIdentifier],
+) -> &'a str {
+    languages
+        .iter()
+        .find_map(|l| translations.get(&l.to_string()).filter(|e| !e.is_empty()))
+        .map(|e| e.as_str())
+        .unwrap_or(base)
+}
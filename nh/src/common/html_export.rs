@@ -0,0 +1,164 @@
+use eframe::egui;
+
+use super::xml::escape;
+
+/// Element of an exported diagram which reveals its properties when clicked
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct HtmlElement {
+    pub name: String,
+    pub stereotype: String,
+    pub comment: String,
+    /// Area of the element in the coordinates of the image
+    #[serde(skip)]
+    pub bounds: egui::Rect,
+}
+
+const STYLE: &str = r#"
+html, body { margin: 0; height: 100%; overflow: hidden; font-family: sans-serif; }
+#viewport { position: absolute; inset: 0; cursor: grab; background: #fff; }
+#viewport.dragging { cursor: grabbing; }
+#diagram { position: absolute; transform-origin: 0 0; }
+.nh-element { fill: transparent; cursor: pointer; }
+.nh-element:hover, .nh-element.selected { fill: rgba(0, 120, 215, 0.12); stroke: rgb(0, 120, 215); }
+#properties { position: absolute; top: 1em; right: 1em; max-width: 25em; padding: 0.5em 1em;
+  background: #f8f8f8; border: 1px solid #ccc; border-radius: 4px; display: none; }
+#properties h2 { margin: 0.3em 0; font-size: 1.1em; }
+#properties p { white-space: pre-wrap; }
+"#;
+
+const SCRIPT: &str = r#"
+const viewport = document.getElementById("viewport");
+const diagram = document.getElementById("diagram");
+const properties = document.getElementById("properties");
+let x = 0, y = 0, scale = 1, drag = null;
+function update() {
+  diagram.style.transform = `translate(${x}px, ${y}px) scale(${scale})`;
+}
+function select(target) {
+  for (const e of document.querySelectorAll(".nh-element.selected")) {
+    e.classList.remove("selected");
+  }
+  if (!target) {
+    properties.style.display = "none";
+    return;
+  }
+  target.classList.add("selected");
+  const element = ELEMENTS[target.dataset.index];
+  properties.replaceChildren();
+  for (const [tag, text] of [["small", element.stereotype], ["h2", element.name], ["p", element.comment]]) {
+    if (text) {
+      const e = document.createElement(tag);
+      e.textContent = text;
+      properties.appendChild(e);
+    }
+  }
+  properties.style.display = "block";
+}
+viewport.addEventListener("pointerdown", (e) => {
+  drag = { x: e.clientX, y: e.clientY, moved: false };
+  viewport.setPointerCapture(e.pointerId);
+});
+viewport.addEventListener("pointermove", (e) => {
+  if (!drag) return;
+  const dx = e.clientX - drag.x, dy = e.clientY - drag.y;
+  if (drag.moved || Math.abs(dx) + Math.abs(dy) > 3) {
+    drag.moved = true;
+    viewport.classList.add("dragging");
+    x += dx; y += dy;
+    drag.x = e.clientX; drag.y = e.clientY;
+    update();
+  }
+});
+viewport.addEventListener("pointerup", (e) => {
+  viewport.releasePointerCapture(e.pointerId);
+  viewport.classList.remove("dragging");
+  if (drag && !drag.moved) {
+    const hit = document.elementsFromPoint(e.clientX, e.clientY)
+      .find((e) => e.classList && e.classList.contains("nh-element"));
+    select(hit);
+  }
+  drag = null;
+});
+viewport.addEventListener("wheel", (e) => {
+  e.preventDefault();
+  const factor = Math.exp(-e.deltaY * 0.001);
+  const next = Math.min(Math.max(scale * factor, 0.1), 10);
+  x = e.clientX - (e.clientX - x) * next / scale;
+  y = e.clientY - (e.clientY - y) * next / scale;
+  scale = next;
+  update();
+}, { passive: false });
+"#;
+
+/// Standalone HTML page showing the SVG image with pan, zoom and clickable elements
+pub fn interactive_html(title: &str, svg: &str, elements: &[HtmlElement]) -> String {
+    let svg = match svg.find("<svg") {
+        Some(start) => &svg[start..],
+        None => svg,
+    };
+    let svg_end = svg.rfind("</svg>").unwrap_or(svg.len());
+
+    // Smaller elements are placed later so that they are not covered by their containers
+    let mut order: Vec<_> = (0..elements.len()).collect();
+    order.sort_by(|a, b| {
+        let area = |i: &usize| elements[*i].bounds.area();
+        area(b).total_cmp(&area(a))
+    });
+    let mut hotspots = String::new();
+    for i in order {
+        let r = elements[i].bounds;
+        hotspots.push_str(&format!(
+            "<rect class=\"nh-element\" data-index=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>{}</title></rect>\n",
+            i,
+            r.min.x,
+            r.min.y,
+            r.width(),
+            r.height(),
+            escape(&elements[i].name),
+        ));
+    }
+
+    // Closing tags inside the script would end it early
+    let metadata = serde_json::to_string(elements)
+        .unwrap_or_else(|_| "[]".to_owned())
+        .replace("</", "<\\/");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<div id=\"viewport\">\n<div id=\"diagram\">\n{}{}</svg>\n</div>\n</div>\n<div id=\"properties\"></div>\n<script>\nconst ELEMENTS = {};\n{}</script>\n</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        &svg[..svg_end],
+        hotspots,
+        metadata,
+        SCRIPT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_are_clickable() {
+        let element = |name: &str, size: f32| HtmlElement {
+            name: name.to_owned(),
+            stereotype: String::new(),
+            comment: "</script>".to_owned(),
+            bounds: egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::splat(size)),
+        };
+        let html = interactive_html(
+            "A & B",
+            "<?xml version=\"1.0\"?>\n<svg width=\"10\" height=\"10\">\n<rect/>\n</svg>\n",
+            &[element("Part", 5.0), element("Whole", 10.0)],
+        );
+
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(!html.contains("<?xml"));
+        assert_eq!(html.matches("</svg>").count(), 1);
+        let whole = html.find("<title>Whole</title>").unwrap();
+        let part = html.find("<title>Part</title>").unwrap();
+        assert!(whole < part);
+        assert!(html.contains("\"comment\":\"<\\/script>\""));
+        assert_eq!(html.matches("</script>").count(), 1);
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use eframe::egui;
+
+use super::canvas::{Highlight, NHCanvas, Stroke};
+use super::controller::GlobalDrawingContext;
+
+/// Raster image stored in the project, kept both encoded (for saving and vector exports)
+/// and decoded (for drawing).
+#[derive(Clone)]
+pub struct ProjectImage {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// Extension of the encoded file, `png` or `jpg`
+    pub extension: &'static str,
+    pub bytes: Arc<Vec<u8>>,
+    pub image: Arc<egui::ColorImage>,
+}
+
+impl ProjectImage {
+    pub fn decode(uuid: uuid::Uuid, name: String, bytes: Vec<u8>) -> Result<Self, String> {
+        let extension = match image::guess_format(&bytes).map_err(|e| e.to_string())? {
+            image::ImageFormat::Png => "png",
+            image::ImageFormat::Jpeg => "jpg",
+            f => return Err(format!("unsupported image format {:?}", f)),
+        };
+        let decoded = image::load_from_memory(&bytes)
+            .map_err(|e| e.to_string())?
+            .to_rgba8();
+        let size = [decoded.width() as usize, decoded.height() as usize];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, decoded.as_raw());
+
+        Ok(Self {
+            uuid,
+            name,
+            extension,
+            bytes: Arc::new(bytes),
+            image: Arc::new(image),
+        })
+    }
+
+    /// Size in pixels
+    pub fn size(&self) -> egui::Vec2 {
+        egui::Vec2::new(self.image.size[0] as f32, self.image.size[1] as f32)
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self.extension {
+            "png" => "image/png",
+            _ => "image/jpeg",
+        }
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64(&self.bytes)
+    }
+
+    /// Texture of the image, uploaded on first use. It repeats when sampled outside of [0, 1].
+    pub fn texture(&self, ctx: &egui::Context) -> egui::TextureHandle {
+        let id = egui::Id::new(("project image", self.uuid));
+        if let Some(texture) = ctx.data(|d| d.get_temp::<egui::TextureHandle>(id)) {
+            return texture;
+        }
+        let texture = ctx.load_texture(
+            format!("project image {}", self.uuid),
+            (*self.image).clone(),
+            egui::TextureOptions::LINEAR_REPEAT,
+        );
+        ctx.data_mut(|d| d.insert_temp(id, texture.clone()));
+        texture
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ImageLibrary {
+    images: HashMap<uuid::Uuid, ProjectImage>,
+}
+
+impl ImageLibrary {
+    pub fn get(&self, uuid: &uuid::Uuid) -> Option<&ProjectImage> {
+        self.images.get(uuid)
+    }
+
+    pub fn insert(&mut self, image: ProjectImage) {
+        self.images.insert(image.uuid, image);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&uuid::Uuid, &ProjectImage)> {
+        self.images.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+}
+
+/// Image drawn behind all elements of a diagram, such as a floor plan or a map
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BackgroundImage {
+    pub image: uuid::Uuid,
+    /// Top left corner of the image, or of one of the tiles when tiled
+    pub position: egui::Pos2,
+    pub scale: f32,
+    pub opacity: f32,
+    /// Repeat the image over the whole canvas
+    pub tiled: bool,
+    /// Include the image in exported images, otherwise it is only shown while editing
+    pub exported: bool,
+}
+
+impl BackgroundImage {
+    /// Space around the elements covered by a tiled image in exports
+    pub const TILED_EXPORT_MARGIN: f32 = 50.0;
+
+    pub fn new(image: uuid::Uuid) -> Self {
+        Self {
+            image,
+            position: egui::Pos2::ZERO,
+            scale: 1.0,
+            opacity: 0.5,
+            tiled: false,
+            exported: true,
+        }
+    }
+
+    /// Draws the image, tiles cover the `area` when tiled
+    pub fn draw_in(&self, images: &ImageLibrary, canvas: &mut dyn NHCanvas, area: egui::Rect) {
+        if !self.exported && canvas.ui_scale().is_none() {
+            return;
+        }
+        let Some(image) = images.get(&self.image) else {
+            return;
+        };
+        let tile = egui::Rect::from_min_size(self.position, image.size() * self.scale);
+        if !tile.is_positive() {
+            return;
+        }
+
+        if !self.tiled {
+            canvas.draw_image(tile, image, None, self.opacity);
+        } else if area.is_positive() {
+            canvas.draw_image(area, image, Some(tile), self.opacity);
+        }
+    }
+
+    /// Returns true when the image should be removed
+    pub fn show_properties(&mut self, gdc: &GlobalDrawingContext, ui: &mut egui::Ui) -> bool {
+        ui.label(gdc.translate_0("nh-background"));
+        if let Some(image) = gdc.images.get(&self.image) {
+            ui.label(&image.name);
+        }
+        ui.add(
+            egui::Slider::new(&mut self.opacity, 0.0..=1.0)
+                .text(gdc.translate_0("nh-background-opacity")),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.scale, 0.05..=10.0)
+                .logarithmic(true)
+                .text(gdc.translate_0("nh-background-scale")),
+        );
+        ui.horizontal(|ui| {
+            ui.label(gdc.translate_0("nh-background-position"));
+            ui.add(egui::DragValue::new(&mut self.position.x).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut self.position.y).prefix("y: "));
+        });
+        ui.checkbox(&mut self.tiled, gdc.translate_0("nh-background-tiled"));
+        ui.checkbox(
+            &mut self.exported,
+            gdc.translate_0("nh-background-exported"),
+        );
+        ui.button(gdc.translate_0("nh-background-remove")).clicked()
+    }
+}
+
+/// What an [`ImageElement`] shows
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ImageElementSource {
+    Image(uuid::Uuid),
+    /// Symbol of the project library, so that editing the symbol updates all its placements
+    Symbol(uuid::Uuid),
+}
+
+/// Image or symbol placed on the canvas, such as a pasted screenshot. It is drawn
+/// behind the elements and is not part of the model.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ImageElement {
+    pub uuid: uuid::Uuid,
+    pub source: ImageElementSource,
+    pub bounds: egui::Rect,
+    pub opacity: f32,
+}
+
+impl ImageElement {
+    /// Larger images are scaled down when placed
+    const INITIAL_MAX_SIZE: f32 = 400.0;
+    const SYMBOL_INITIAL_SIZE: f32 = 60.0;
+    const HANDLE_SIZE: f32 = 8.0;
+
+    pub fn new(image: &ProjectImage, position: egui::Pos2) -> Self {
+        let size = image.size();
+        let scale = (Self::INITIAL_MAX_SIZE / size.max_elem()).min(1.0);
+        Self {
+            uuid: uuid::Uuid::now_v7(),
+            source: ImageElementSource::Image(image.uuid),
+            bounds: egui::Rect::from_min_size(position, size * scale),
+            opacity: 1.0,
+        }
+    }
+
+    pub fn new_symbol(symbol: uuid::Uuid, view_box: egui::Rect, position: egui::Pos2) -> Self {
+        let size = view_box.size() / view_box.size().max_elem().max(1.0);
+        Self {
+            uuid: uuid::Uuid::now_v7(),
+            source: ImageElementSource::Symbol(symbol),
+            bounds: egui::Rect::from_min_size(position, size * Self::SYMBOL_INITIAL_SIZE),
+            opacity: 1.0,
+        }
+    }
+
+    pub fn image(&self) -> Option<uuid::Uuid> {
+        match self.source {
+            ImageElementSource::Image(e) => Some(e),
+            ImageElementSource::Symbol(_) => None,
+        }
+    }
+
+    /// Handle in the bottom right corner used to resize the image
+    pub fn resize_handle(&self, ui_scale: f32) -> egui::Rect {
+        egui::Rect::from_center_size(
+            self.bounds.max,
+            egui::Vec2::splat(Self::HANDLE_SIZE / ui_scale),
+        )
+    }
+
+    /// Keeps the aspect ratio, so only the horizontal movement is used
+    pub fn resize_by(&mut self, delta: egui::Vec2) {
+        let ratio = self.bounds.height() / self.bounds.width().max(1.0);
+        let width = (self.bounds.width() + delta.x).max(Self::HANDLE_SIZE);
+        self.bounds.set_width(width);
+        self.bounds.set_height(width * ratio);
+    }
+
+    pub fn draw_in(&self, gdc: &GlobalDrawingContext, canvas: &mut dyn NHCanvas, selected: bool) {
+        match self.source {
+            ImageElementSource::Image(e) => {
+                if let Some(image) = gdc.images.get(&e) {
+                    canvas.draw_image(self.bounds, image, None, self.opacity);
+                }
+            }
+            ImageElementSource::Symbol(e) => {
+                if let Some(symbol) = gdc.symbols.get(&e) {
+                    symbol.draw_in(
+                        canvas,
+                        self.bounds,
+                        Stroke::new_solid(1.0, egui::Color32::BLACK.gamma_multiply(self.opacity)),
+                        Highlight::NONE,
+                    );
+                }
+            }
+        }
+        if let Some(ui_scale) = canvas.ui_scale()
+            && selected
+        {
+            canvas.draw_rectangle(
+                self.bounds,
+                egui::CornerRadius::ZERO,
+                egui::Color32::TRANSPARENT,
+                Stroke::new_dashed(1.0, egui::Color32::BLUE),
+                Highlight::NONE,
+            );
+            canvas.draw_rectangle(
+                self.resize_handle(ui_scale),
+                egui::CornerRadius::ZERO,
+                egui::Color32::WHITE,
+                Stroke::new_solid(1.0, egui::Color32::BLUE),
+                Highlight::NONE,
+            );
+        }
+    }
+
+    /// Returns true when the image should be removed
+    pub fn show_properties(&mut self, gdc: &GlobalDrawingContext, ui: &mut egui::Ui) -> bool {
+        match self.source {
+            ImageElementSource::Image(e) => {
+                ui.label(gdc.translate_0("nh-image"));
+                if let Some(image) = gdc.images.get(&e) {
+                    ui.label(&image.name);
+                }
+            }
+            ImageElementSource::Symbol(e) => {
+                ui.label(gdc.translate_0("nh-symbol"));
+                if let Some(symbol) = gdc.symbols.get(&e) {
+                    ui.label(&symbol.name);
+                }
+            }
+        }
+        ui.add(
+            egui::Slider::new(&mut self.opacity, 0.0..=1.0)
+                .text(gdc.translate_0("nh-background-opacity")),
+        );
+        ui.horizontal(|ui| {
+            ui.label(gdc.translate_0("nh-background-position"));
+            let mut position = self.bounds.min;
+            ui.add(egui::DragValue::new(&mut position.x).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut position.y).prefix("y: "));
+            self.bounds = self.bounds.translate(position - self.bounds.min);
+        });
+        ui.horizontal(|ui| {
+            ui.label(gdc.translate_0("nh-image-width"));
+            let mut width = self.bounds.width();
+            if ui
+                .add(egui::DragValue::new(&mut width).range(Self::HANDLE_SIZE..=f32::INFINITY))
+                .changed()
+            {
+                self.resize_by(egui::Vec2::new(width - self.bounds.width(), 0.0));
+            }
+        });
+        ui.button(gdc.translate_0("nh-image-remove")).clicked()
+    }
+}
+
+/// Encodes an image, such as one from the clipboard, so that it can be stored in the project
+pub fn encode_png(size: [usize; 2], rgba: &[u8]) -> Result<Vec<u8>, String> {
+    use image::ImageEncoder;
+
+    let mut bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut bytes)
+        .write_image(
+            rgba,
+            size[0] as u32,
+            size[1] as u32,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::canvas::MeasuringCanvas;
+
+    #[test]
+    fn base64_pads_incomplete_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xfb, 0xff]), "+/8=");
+    }
+
+    #[test]
+    fn images_are_decoded_and_tiled_over_area() {
+        let bytes = encode_png([2, 3], &[255; 2 * 3 * 4]).unwrap();
+        let image = ProjectImage::decode(uuid::Uuid::nil(), "plan.png".to_owned(), bytes).unwrap();
+        assert_eq!(image.extension, "png");
+        assert_eq!(image.size(), egui::Vec2::new(2.0, 3.0));
+        assert!(ProjectImage::decode(uuid::Uuid::nil(), String::new(), vec![1, 2, 3]).is_err());
+
+        let mut images = ImageLibrary::default();
+        images.insert(image);
+        let mut background = BackgroundImage::new(uuid::Uuid::nil());
+        background.scale = 10.0;
+
+        let ctx = egui::Context::default();
+        let painter = egui::Painter::new(ctx, egui::LayerId::background(), egui::Rect::EVERYTHING);
+        let area =
+            egui::Rect::from_min_size(egui::Pos2::new(-100.0, -100.0), egui::Vec2::splat(300.0));
+
+        let mut canvas = MeasuringCanvas::new(&painter);
+        background.draw_in(&images, &mut canvas, area);
+        assert_eq!(
+            canvas.bounds(),
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::new(20.0, 30.0))
+        );
+
+        background.tiled = true;
+        let mut canvas = MeasuringCanvas::new(&painter);
+        background.draw_in(&images, &mut canvas, area);
+        assert_eq!(canvas.bounds(), area);
+
+        background.exported = false;
+        let mut canvas = MeasuringCanvas::new(&painter);
+        background.draw_in(&images, &mut canvas, area);
+        assert_eq!(canvas.bounds(), egui::Rect::NOTHING);
+    }
+
+    #[test]
+    fn image_elements_keep_aspect_ratio() {
+        let bytes = encode_png([800, 200], &[255; 800 * 200 * 4]).unwrap();
+        let image = ProjectImage::decode(uuid::Uuid::nil(), String::new(), bytes).unwrap();
+
+        let mut element = ImageElement::new(&image, egui::Pos2::new(10.0, 20.0));
+        assert_eq!(
+            element.bounds,
+            egui::Rect::from_min_size(egui::Pos2::new(10.0, 20.0), egui::Vec2::new(400.0, 100.0))
+        );
+        element.resize_by(egui::Vec2::new(-200.0, 1000.0));
+        assert_eq!(element.bounds.size(), egui::Vec2::new(200.0, 50.0));
+        assert!(element.resize_handle(2.0).contains(element.bounds.max));
+    }
+
+    #[test]
+    fn symbol_elements_fit_the_view_box() {
+        let view_box = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::new(200.0, 100.0));
+        let element = ImageElement::new_symbol(uuid::Uuid::nil(), view_box, egui::Pos2::ZERO);
+        assert_eq!(element.bounds.size(), egui::Vec2::new(60.0, 30.0));
+        assert_eq!(element.image(), None);
+    }
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+
+use super::uuid::ModelUuid;
+
+/// Reference from an element to an issue tracking its implementation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IssueLink {
+    pub element: ModelUuid,
+    /// Issue URL, or `owner/repository#number` for GitHub
+    pub reference: String,
+}
+
+impl IssueLink {
+    pub fn new(element: ModelUuid) -> Self {
+        Self {
+            element,
+            reference: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IssueReference {
+    GitHub {
+        owner: String,
+        repository: String,
+        number: u64,
+    },
+    GitLab {
+        host: String,
+        /// Path of the project including its groups
+        project: String,
+        number: u64,
+    },
+}
+
+impl IssueReference {
+    pub fn parse(reference: &str) -> Option<Self> {
+        let reference = reference.trim().trim_end_matches('/');
+        let Some(rest) = reference
+            .strip_prefix("https://")
+            .or_else(|| reference.strip_prefix("http://"))
+        else {
+            let (path, number) = reference.split_once('#')?;
+            let (owner, repository) = path.split_once('/')?;
+            return Some(Self::GitHub {
+                owner: owner.to_owned(),
+                repository: repository.to_owned(),
+                number: number.parse().ok()?,
+            });
+        };
+
+        let (host, path) = rest.split_once('/')?;
+        if let Some((project, number)) = path.split_once("/-/issues/") {
+            return Some(Self::GitLab {
+                host: host.to_owned(),
+                project: project.to_owned(),
+                number: number.parse().ok()?,
+            });
+        }
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [owner, repository, "issues" | "pull", number] if host == "github.com" => {
+                Some(Self::GitHub {
+                    owner: owner.to_owned(),
+                    repository: repository.to_owned(),
+                    number: number.parse().ok()?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Short form shown in badges and tooltips
+    pub fn label(&self) -> String {
+        match self {
+            Self::GitHub {
+                owner,
+                repository,
+                number,
+            } => format!("{}/{}#{}", owner, repository, number),
+            Self::GitLab {
+                project, number, ..
+            } => format!("{}#{}", project, number),
+        }
+    }
+
+    pub fn web_url(&self) -> String {
+        match self {
+            Self::GitHub {
+                owner,
+                repository,
+                number,
+            } => format!(
+                "https://github.com/{}/{}/issues/{}",
+                owner, repository, number
+            ),
+            Self::GitLab {
+                host,
+                project,
+                number,
+            } => format!("https://{}/{}/-/issues/{}", host, project, number),
+        }
+    }
+
+    /// REST API endpoint describing the issue, GitHub describes pull requests there too
+    pub fn api_url(&self) -> String {
+        match self {
+            Self::GitHub {
+                owner,
+                repository,
+                number,
+            } => format!(
+                "https://api.github.com/repos/{}/{}/issues/{}",
+                owner, repository, number
+            ),
+            Self::GitLab {
+                host,
+                project,
+                number,
+            } => format!(
+                "https://{}/api/v4/projects/{}/issues/{}",
+                host,
+                project.replace('/', "%2F"),
+                number
+            ),
+        }
+    }
+}
+
+/// Issue details, or the reason they could not be fetched
+pub type IssueFetchResult = Result<IssueInfo, String>;
+/// API URL of the issue along with the fetched result
+pub type IssueFetch = (String, IssueFetchResult);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssueInfo {
+    pub title: String,
+    /// `open` or `closed` on GitHub, `opened` or `closed` on GitLab
+    pub state: String,
+}
+
+impl IssueInfo {
+    /// Reads the response of either API, which both describe issues with a title and a state
+    pub fn from_response(body: &[u8]) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+        Some(Self {
+            title: value.get("title")?.as_str()?.to_owned(),
+            state: value.get("state")?.as_str()?.to_owned(),
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state == "closed"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_parsed() {
+        let github =
+            IssueReference::parse("https://github.com/dolezvo1/nihonium/issues/12").unwrap();
+        assert_eq!(
+            IssueReference::parse("dolezvo1/nihonium#12"),
+            Some(github.clone())
+        );
+        assert_eq!(github.label(), "dolezvo1/nihonium#12");
+        assert_eq!(
+            github.api_url(),
+            "https://api.github.com/repos/dolezvo1/nihonium/issues/12"
+        );
+
+        let gitlab =
+            IssueReference::parse("https://gitlab.example.com/group/sub/project/-/issues/7/")
+                .unwrap();
+        assert_eq!(gitlab.label(), "group/sub/project#7");
+        assert_eq!(
+            gitlab.api_url(),
+            "https://gitlab.example.com/api/v4/projects/group%2Fsub%2Fproject/issues/7"
+        );
+
+        assert_eq!(IssueReference::parse("#12"), None);
+        assert_eq!(IssueReference::parse("https://example.com/issues/12"), None);
+
+        let info =
+            IssueInfo::from_response(br#"{"number": 12, "title": "Crash", "state": "closed"}"#)
+                .unwrap();
+        assert_eq!(info.title, "Crash");
+        assert!(info.is_closed());
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use super::uuid::ViewUuid;
+
+/// User-defined group of top-level views, which can be hidden or locked together
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DiagramLayer {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub visible: bool,
+    /// Views in locked layers are drawn, but cannot be selected
+    pub locked: bool,
+}
+
+impl DiagramLayer {
+    pub fn new(name: String) -> Self {
+        Self {
+            uuid: uuid::Uuid::now_v7(),
+            name,
+            visible: true,
+            locked: false,
+        }
+    }
+}
+
+/// Layers of a diagram. Views not assigned to any of them are in the default layer,
+/// which is always visible, unlocked and behind all the other layers.
+#[derive(Clone, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiagramLayers {
+    /// From the back to the front
+    pub layers: Vec<DiagramLayer>,
+    /// Entries are kept for deleted views, so that undoing the deletion restores them
+    pub membership: HashMap<ViewUuid, uuid::Uuid>,
+}
+
+/// Shown in the Layers tab
+#[derive(Clone, Debug, Default)]
+pub struct LayersOverview {
+    /// From the back to the front, with the number of views in them
+    pub layers: Vec<(DiagramLayer, usize)>,
+    pub default_layer_count: usize,
+    pub annotations_visible: bool,
+    /// Views which cannot be moved, regardless of their layer
+    pub locked_count: usize,
+}
+
+impl DiagramLayers {
+    pub fn get(&self, layer: &uuid::Uuid) -> Option<&DiagramLayer> {
+        self.layers.iter().find(|e| e.uuid == *layer)
+    }
+
+    /// None for the default layer, also when the assigned layer no longer exists
+    pub fn layer_of(&self, view: &ViewUuid) -> Option<&DiagramLayer> {
+        self.membership.get(view).and_then(|e| self.get(e))
+    }
+
+    pub fn is_hidden(&self, view: &ViewUuid) -> bool {
+        self.layer_of(view).is_some_and(|e| !e.visible)
+    }
+
+    pub fn is_locked(&self, view: &ViewUuid) -> bool {
+        self.layer_of(view).is_some_and(|e| e.locked)
+    }
+
+    /// Hidden views are inactive as well, as they cannot be clicked
+    pub fn is_inactive(&self, view: &ViewUuid) -> bool {
+        self.layer_of(view).is_some_and(|e| !e.visible || e.locked)
+    }
+
+    pub fn views_where(&self, f: impl Fn(&DiagramLayer) -> bool) -> HashSet<ViewUuid> {
+        self.membership
+            .iter()
+            .filter(|(_, l)| self.get(l).is_some_and(&f))
+            .map(|(v, _)| *v)
+            .collect()
+    }
+
+    /// Layers to draw from the back to the front, None being the default layer
+    pub fn draw_passes(&self) -> Vec<Option<uuid::Uuid>> {
+        std::iter::once(None)
+            .chain(
+                self.layers
+                    .iter()
+                    .filter(|e| e.visible)
+                    .map(|e| Some(e.uuid)),
+            )
+            .collect()
+    }
+
+    pub fn get_mut(&mut self, layer: &uuid::Uuid) -> Option<&mut DiagramLayer> {
+        self.layers.iter_mut().find(|e| e.uuid == *layer)
+    }
+
+    /// Views of the removed layer return to the default layer
+    pub fn remove(&mut self, layer: &uuid::Uuid) {
+        self.layers.retain(|e| e.uuid != *layer);
+        self.membership.retain(|_, l| *l != *layer);
+    }
+
+    /// Moves the layer to the given position, 0 being the back
+    pub fn move_to(&mut self, layer: &uuid::Uuid, index: usize) {
+        let Some(from) = self.layers.iter().position(|e| e.uuid == *layer) else {
+            return;
+        };
+        let e = self.layers.remove(from);
+        self.layers.insert(index.min(self.layers.len()), e);
+    }
+
+    pub fn assign(&mut self, views: impl IntoIterator<Item = ViewUuid>, layer: Option<uuid::Uuid>) {
+        let layer = layer.filter(|l| self.get(l).is_some());
+        for v in views {
+            match layer {
+                Some(l) => self.membership.insert(v, l),
+                None => self.membership.remove(&v),
+            };
+        }
+    }
+
+    pub fn overview(
+        &self,
+        views: impl IntoIterator<Item = ViewUuid>,
+        annotations_visible: bool,
+    ) -> LayersOverview {
+        let mut counts = HashMap::new();
+        let mut default_layer_count = 0;
+        for v in views {
+            match self.layer_of(&v) {
+                Some(l) => *counts.entry(l.uuid).or_insert(0) += 1,
+                None => default_layer_count += 1,
+            }
+        }
+        LayersOverview {
+            layers: self
+                .layers
+                .iter()
+                .map(|e| (e.clone(), counts.get(&e.uuid).copied().unwrap_or(0)))
+                .collect(),
+            default_layer_count,
+            annotations_visible,
+            locked_count: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_layer_returns_views_to_default() {
+        let mut layers = DiagramLayers::default();
+        let mut hidden = DiagramLayer::new("hidden".to_owned());
+        let uuid = hidden.uuid;
+        layers.layers.push(hidden.clone());
+        let views = [ViewUuid::now_v7(), ViewUuid::now_v7()];
+        layers.assign(views, Some(uuid));
+
+        hidden.visible = false;
+        *layers.get_mut(&uuid).unwrap() = hidden;
+        assert!(views.iter().all(|v| layers.is_hidden(v)));
+        assert_eq!(layers.draw_passes(), vec![None]);
+
+        layers.remove(&uuid);
+        assert!(views.iter().all(|v| layers.layer_of(v).is_none()));
+        assert!(layers.membership.is_empty());
+    }
+
+    #[test]
+    fn locked_views_are_inactive_but_drawn() {
+        let mut layers = DiagramLayers::default();
+        let mut locked = DiagramLayer::new("locked".to_owned());
+        locked.locked = true;
+        let uuid = locked.uuid;
+        layers.layers.push(locked);
+        layers.layers.push(DiagramLayer::new("top".to_owned()));
+        let view = ViewUuid::now_v7();
+        layers.assign([view], Some(uuid));
+
+        assert!(layers.is_inactive(&view) && layers.is_locked(&view) && !layers.is_hidden(&view));
+        assert_eq!(layers.draw_passes().len(), 3);
+
+        layers.move_to(&uuid, 5);
+        assert_eq!(layers.layers.last().map(|e| e.uuid), Some(uuid));
+        assert_eq!(
+            layers
+                .overview([view, ViewUuid::now_v7()], true)
+                .default_layer_count,
+            1
+        );
+    }
+}
@@ -0,0 +1,568 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+use super::uuid::ModelUuid;
+
+/// Geometry of the elements of a diagram keyed by their models, so that it can be
+/// transferred between diagrams showing the same models, such as variants of a diagram
+#[derive(Clone, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct DiagramLayout {
+    pub elements: BTreeMap<ModelUuid, ElementLayout>,
+}
+
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ElementLayout {
+    pub bounds: egui::Rect,
+    /// Vertices of a connection, in the order given by the view
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<egui::Pos2>,
+}
+
+impl DiagramLayout {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        serde_json::from_str(s).map_err(|e| e.to_string())
+    }
+}
+
+/// Computes one step of a force-directed layout.
+///
+/// All nodes repel each other, while edges pull their ends together towards `ideal_distance`.
+/// Returns the displacement of each node, limited to `max_step`. Pinned nodes never move.
+pub fn force_directed_step(
+    positions: &[egui::Pos2],
+    edges: &[(usize, usize)],
+    pinned: &[bool],
+    ideal_distance: f32,
+    max_step: f32,
+) -> Vec<egui::Vec2> {
+    let k = ideal_distance.max(1.0);
+    let mut forces = vec![egui::Vec2::ZERO; positions.len()];
+
+    for i in 0..positions.len() {
+        for j in (i + 1)..positions.len() {
+            let mut delta = positions[i] - positions[j];
+            if delta.length_sq() < 1e-4 {
+                // Separate coincident nodes in a deterministic direction
+                delta = egui::Vec2::angled(i as f32 + j as f32) * 0.01;
+            }
+            let distance = delta.length();
+            let force = delta / distance * (k * k / distance);
+            forces[i] += force;
+            forces[j] -= force;
+        }
+    }
+
+    for (a, b) in edges.iter().filter(|(a, b)| a != b) {
+        let (Some(pa), Some(pb)) = (positions.get(*a), positions.get(*b)) else {
+            continue;
+        };
+        let delta = *pb - *pa;
+        let distance = delta.length();
+        if distance < 1e-4 {
+            continue;
+        }
+        let force = delta / distance * (distance * distance / k);
+        forces[*a] += force;
+        forces[*b] -= force;
+    }
+
+    forces
+        .into_iter()
+        .zip(pinned)
+        .map(|(f, pinned)| {
+            if *pinned || f.length() < 1e-4 {
+                egui::Vec2::ZERO
+            } else {
+                f.normalized() * f.length().min(max_step)
+            }
+        })
+        .collect()
+}
+
+/// Places nodes as trees following the edges, from their sources to their targets.
+///
+/// Nodes without incoming edges are roots, nodes reachable from several parents are placed
+/// under the first one only. Trees grow to the right, or around the first root when `radial`.
+/// Returns the centers of the nodes, with the first root at the origin.
+pub fn tree_layout(
+    sizes: &[egui::Vec2],
+    edges: &[(usize, usize)],
+    radial: bool,
+    spacing: egui::Vec2,
+) -> Vec<egui::Pos2> {
+    let mut has_parent = vec![false; sizes.len()];
+    for (_, b) in edges.iter().filter(|(a, b)| a != b) {
+        if let Some(e) = has_parent.get_mut(*b) {
+            *e = true;
+        }
+    }
+    let mut roots: Vec<_> = (0..sizes.len()).filter(|e| !has_parent[*e]).collect();
+
+    // Spanning forest, nodes only reachable through cycles become roots as well
+    let mut children = vec![Vec::new(); sizes.len()];
+    let mut visited = vec![false; sizes.len()];
+    let mut idx = 0;
+    loop {
+        if idx == roots.len() {
+            match visited.iter().position(|e| !*e) {
+                Some(e) => roots.push(e),
+                None => break,
+            }
+        }
+        let mut queue = std::collections::VecDeque::from([roots[idx]]);
+        visited[roots[idx]] = true;
+        while let Some(n) = queue.pop_front() {
+            for (_, b) in edges.iter().filter(|(a, b)| *a == n && *b < sizes.len()) {
+                if !visited[*b] {
+                    visited[*b] = true;
+                    children[n].push(*b);
+                    queue.push_back(*b);
+                }
+            }
+        }
+        idx += 1;
+    }
+
+    let mut out = vec![egui::Pos2::ZERO; sizes.len()];
+    if radial {
+        fn leaves(n: usize, children: &[Vec<usize>], out: &mut [usize]) -> usize {
+            out[n] = children[n]
+                .iter()
+                .map(|c| leaves(*c, children, out))
+                .sum::<usize>()
+                .max(1);
+            out[n]
+        }
+        fn place(
+            n: usize,
+            depth: usize,
+            angles: std::ops::Range<f32>,
+            children: &[Vec<usize>],
+            leaf_counts: &[usize],
+            ring: f32,
+            out: &mut [egui::Pos2],
+        ) {
+            let angle = (angles.start + angles.end) / 2.0;
+            out[n] = (egui::Vec2::angled(angle) * depth as f32 * ring).to_pos2();
+            let per_leaf = (angles.end - angles.start) / leaf_counts[n] as f32;
+            let mut start = angles.start;
+            for c in &children[n] {
+                let end = start + per_leaf * leaf_counts[*c] as f32;
+                place(*c, depth + 1, start..end, children, leaf_counts, ring, out);
+                start = end;
+            }
+        }
+
+        let mut leaf_counts = vec![0; sizes.len()];
+        for r in &roots {
+            leaves(*r, &children, &mut leaf_counts);
+        }
+        let ring = sizes.iter().map(|e| e.length()).fold(0.0, f32::max) + spacing.x;
+        // Other roots are placed around the first one, as if they were its children
+        let (first, others) = roots.split_first().unwrap_or((&0, &[]));
+        let around: Vec<_> = children
+            .get(*first)
+            .into_iter()
+            .flatten()
+            .chain(others)
+            .copied()
+            .collect();
+        let per_leaf = std::f32::consts::TAU
+            / around.iter().map(|e| leaf_counts[*e]).sum::<usize>().max(1) as f32;
+        let mut start = 0.0;
+        for c in around {
+            let end = start + per_leaf * leaf_counts[c] as f32;
+            place(c, 1, start..end, &children, &leaf_counts, ring, &mut out);
+            start = end;
+        }
+    } else {
+        struct Columns<'a> {
+            children: &'a [Vec<usize>],
+            sizes: &'a [egui::Vec2],
+            x: Vec<f32>,
+            spacing: f32,
+            next_y: f32,
+        }
+        impl Columns<'_> {
+            fn measure(&mut self, n: usize, depth: usize) {
+                if self.x.len() <= depth {
+                    self.x.push(0.0);
+                }
+                self.x[depth] = self.x[depth].max(self.sizes[n].x);
+                for c in self.children[n].iter() {
+                    self.measure(*c, depth + 1);
+                }
+            }
+            fn place(&mut self, n: usize, depth: usize, out: &mut [egui::Pos2]) {
+                let children = self.children;
+                let y = match (children[n].first(), children[n].last()) {
+                    (Some(first), Some(last)) => {
+                        for c in children[n].iter() {
+                            self.place(*c, depth + 1, out);
+                        }
+                        (out[*first].y + out[*last].y) / 2.0
+                    }
+                    _ => self.next_y + self.sizes[n].y / 2.0,
+                };
+                out[n] = egui::Pos2::new(self.x[depth] + self.sizes[n].x / 2.0, y);
+                self.next_y = self.next_y.max(y + self.sizes[n].y / 2.0 + self.spacing);
+            }
+        }
+
+        let mut columns = Columns {
+            children: &children,
+            sizes,
+            x: Vec::new(),
+            spacing: spacing.y,
+            next_y: 0.0,
+        };
+        for r in &roots {
+            columns.measure(*r, 0);
+        }
+        // Widths of the columns become their left edges
+        let mut x = 0.0;
+        for e in columns.x.iter_mut() {
+            (*e, x) = (x, x + *e + spacing.x);
+        }
+        for r in &roots {
+            columns.place(*r, 0, &mut out);
+        }
+    }
+
+    if let Some(first) = roots.first().map(|e| out[*e].to_vec2()) {
+        for e in out.iter_mut() {
+            *e -= first;
+        }
+    }
+    out
+}
+
+/// Places nodes into layers so that edges point from upper layers to lower ones,
+/// or from lower to upper ones when `upward`, such as generalizations to their general classes.
+///
+/// Edges closing cycles are reversed, and edges spanning several layers are routed
+/// through invisible nodes, so that the order within layers can reduce crossings.
+/// Returns the centers of the nodes, with the top left corner of the layout at the origin.
+pub fn layered_layout(
+    sizes: &[egui::Vec2],
+    edges: &[(usize, usize)],
+    upward: bool,
+    spacing: egui::Vec2,
+) -> Vec<egui::Pos2> {
+    const SWEEPS: usize = 8;
+    let n = sizes.len();
+    let mut successors = vec![Vec::new(); n];
+    for (a, b) in edges.iter().filter(|(a, b)| a != b && *a < n && *b < n) {
+        let (a, b) = if upward { (*b, *a) } else { (*a, *b) };
+        if !successors[a].contains(&b) {
+            successors[a].push(b);
+        }
+    }
+
+    // Acyclic graph, reversing the edges leading back to nodes still being visited
+    fn visit(n: usize, successors: &[Vec<usize>], state: &mut [u8], dag: &mut Vec<(usize, usize)>) {
+        state[n] = 1;
+        for s in &successors[n] {
+            match state[*s] {
+                0 => {
+                    dag.push((n, *s));
+                    visit(*s, successors, state, dag);
+                }
+                1 => dag.push((*s, n)),
+                _ => dag.push((n, *s)),
+            }
+        }
+        state[n] = 2;
+    }
+    let mut state = vec![0; n];
+    let mut dag = Vec::new();
+    for e in 0..n {
+        if state[e] == 0 {
+            visit(e, &successors, &mut state, &mut dag);
+        }
+    }
+    dag.sort();
+    dag.dedup();
+
+    // Longest path layering
+    let mut layer_of = vec![0; n];
+    let mut incoming = vec![0; n];
+    for (_, b) in &dag {
+        incoming[*b] += 1;
+    }
+    let mut queue: std::collections::VecDeque<_> = (0..n).filter(|e| incoming[*e] == 0).collect();
+    while let Some(a) = queue.pop_front() {
+        for (_, b) in dag.iter().filter(|e| e.0 == a) {
+            layer_of[*b] = layer_of[*b].max(layer_of[a] + 1);
+            incoming[*b] -= 1;
+            if incoming[*b] == 0 {
+                queue.push_back(*b);
+            }
+        }
+    }
+
+    // Long edges are split into segments between adjacent layers
+    let mut widths: Vec<_> = sizes.iter().map(|e| e.x).collect();
+    let mut segments = Vec::new();
+    for (a, b) in &dag {
+        let mut previous = *a;
+        for l in (layer_of[*a] + 1)..layer_of[*b] {
+            layer_of.push(l);
+            widths.push(0.0);
+            segments.push((previous, layer_of.len() - 1));
+            previous = layer_of.len() - 1;
+        }
+        segments.push((previous, *b));
+    }
+    let mut predecessors = vec![Vec::new(); layer_of.len()];
+    let mut successors = vec![Vec::new(); layer_of.len()];
+    for (a, b) in &segments {
+        predecessors[*b].push(*a);
+        successors[*a].push(*b);
+    }
+    let layer_count = layer_of.iter().max().map(|e| e + 1).unwrap_or(0);
+    let mut layers = vec![Vec::new(); layer_count];
+    for (e, l) in layer_of.iter().enumerate() {
+        layers[*l].push(e);
+    }
+
+    // Order within layers by barycenters of neighbors, keeping the one with fewest crossings
+    fn order_of(layers: &[Vec<usize>], count: usize) -> Vec<usize> {
+        let mut out = vec![0; count];
+        for l in layers {
+            for (i, e) in l.iter().enumerate() {
+                out[*e] = i;
+            }
+        }
+        out
+    }
+    let crossings = |layers: &[Vec<usize>]| {
+        let order = order_of(layers, layer_of.len());
+        let mut count = 0;
+        for (i, (a1, b1)) in segments.iter().enumerate() {
+            for (a2, b2) in &segments[i + 1..] {
+                if layer_of[*a1] == layer_of[*a2]
+                    && (order[*a1] as isize - order[*a2] as isize).signum()
+                        * (order[*b1] as isize - order[*b2] as isize).signum()
+                        < 0
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    };
+    let mut best = (crossings(&layers), layers.clone());
+    for sweep in 0..SWEEPS {
+        let downward = sweep % 2 == 0;
+        let range: Vec<_> = if downward {
+            (1..layer_count).collect()
+        } else {
+            (0..layer_count.saturating_sub(1)).rev().collect()
+        };
+        for l in range {
+            let order = order_of(&layers, layer_of.len());
+            let neighbors = if downward { &predecessors } else { &successors };
+            let mut keyed: Vec<_> = layers[l]
+                .iter()
+                .map(|e| {
+                    let key = if neighbors[*e].is_empty() {
+                        order[*e] as f32
+                    } else {
+                        neighbors[*e].iter().map(|e| order[*e] as f32).sum::<f32>()
+                            / neighbors[*e].len() as f32
+                    };
+                    (key, *e)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[l] = keyed.into_iter().map(|e| e.1).collect();
+        }
+        let c = crossings(&layers);
+        if c < best.0 {
+            best = (c, layers.clone());
+        }
+    }
+    let layers = best.1;
+
+    // Horizontal positions, moved towards the neighbors while keeping the order and gaps
+    let mut x = vec![0.0f32; layer_of.len()];
+    for l in &layers {
+        let mut left = 0.0;
+        for e in l {
+            x[*e] = left + widths[*e] / 2.0;
+            left += widths[*e] + spacing.x;
+        }
+    }
+    let gap = |a: usize, b: usize| (widths[a] + widths[b]) / 2.0 + spacing.x;
+    for sweep in 0..SWEEPS {
+        let neighbors = if sweep % 2 == 0 {
+            &predecessors
+        } else {
+            &successors
+        };
+        for l in &layers {
+            let desired: Vec<_> = l
+                .iter()
+                .map(|e| {
+                    if neighbors[*e].is_empty() {
+                        x[*e]
+                    } else {
+                        neighbors[*e].iter().map(|e| x[*e]).sum::<f32>()
+                            / neighbors[*e].len() as f32
+                    }
+                })
+                .collect();
+            let mut from_left = desired.clone();
+            for i in 1..l.len() {
+                from_left[i] = from_left[i].max(from_left[i - 1] + gap(l[i - 1], l[i]));
+            }
+            let mut from_right = desired;
+            for i in (0..l.len().saturating_sub(1)).rev() {
+                from_right[i] = from_right[i].min(from_right[i + 1] - gap(l[i], l[i + 1]));
+            }
+            for i in 0..l.len() {
+                x[l[i]] = (from_left[i] + from_right[i]) / 2.0;
+                if i > 0 {
+                    x[l[i]] = x[l[i]].max(x[l[i - 1]] + gap(l[i - 1], l[i]));
+                }
+            }
+        }
+    }
+
+    let heights: Vec<_> = layers
+        .iter()
+        .map(|l| {
+            l.iter()
+                .filter(|e| **e < n)
+                .map(|e| sizes[*e].y)
+                .fold(0.0, f32::max)
+        })
+        .collect();
+    let mut y = Vec::with_capacity(layer_count);
+    let mut top = 0.0;
+    for h in heights {
+        y.push(top + h / 2.0);
+        top += h + spacing.y;
+    }
+
+    let min_x = (0..n)
+        .map(|e| x[e] - sizes[e].x / 2.0)
+        .fold(f32::INFINITY, f32::min);
+    (0..n)
+        .map(|e| egui::Pos2::new(x[e] - min_x, y[layer_of[e]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connected_nodes_attract_and_pinned_nodes_stay() {
+        let positions = [egui::Pos2::new(0.0, 0.0), egui::Pos2::new(1000.0, 0.0)];
+        let step = force_directed_step(&positions, &[(0, 1)], &[false, false], 100.0, 10.0);
+        assert!(step[0].x > 0.0 && step[1].x < 0.0);
+        assert_eq!(step[0].length(), 10.0);
+
+        let step = force_directed_step(&positions, &[(0, 1)], &[true, false], 100.0, 10.0);
+        assert_eq!(step[0], egui::Vec2::ZERO);
+        assert!(step[1].x < 0.0);
+    }
+
+    #[test]
+    fn unconnected_nodes_repel() {
+        let positions = [egui::Pos2::new(0.0, 0.0), egui::Pos2::new(0.0, 0.0)];
+        let step = force_directed_step(&positions, &[], &[false, false], 100.0, 10.0);
+        assert!((step[0] - step[1]).length() > 0.0);
+    }
+
+    #[test]
+    fn trees_grow_from_their_roots() {
+        let sizes = [egui::Vec2::new(100.0, 40.0); 4];
+        let edges = [(0, 1), (0, 2), (2, 3), (3, 0)];
+        let spacing = egui::Vec2::new(50.0, 20.0);
+
+        let p = tree_layout(&sizes, &edges, false, spacing);
+        assert_eq!(p[0], egui::Pos2::ZERO);
+        assert_eq!(p[1].x, 150.0);
+        assert_eq!(p[1].x, p[2].x);
+        assert_eq!(p[2].y - p[1].y, 60.0);
+        assert_eq!(p[0].y, (p[1].y + p[2].y) / 2.0);
+        assert_eq!(p[3].x, 300.0);
+
+        let p = tree_layout(&sizes, &edges, true, spacing);
+        assert_eq!(p[0], egui::Pos2::ZERO);
+        assert!((p[1].to_vec2().length() - p[2].to_vec2().length()).abs() < 1e-3);
+        assert!(p[3].to_vec2().length() > p[2].to_vec2().length());
+    }
+
+    #[test]
+    fn generalizations_point_upward_in_layers() {
+        let sizes = [
+            egui::Vec2::new(100.0, 40.0),
+            egui::Vec2::new(80.0, 60.0),
+            egui::Vec2::new(100.0, 40.0),
+            egui::Vec2::new(100.0, 40.0),
+        ];
+        // Specific classes to the general ones, with a redundant shortcut to the root
+        let edges = [(1, 0), (2, 0), (3, 1), (3, 0)];
+        let spacing = egui::Vec2::new(50.0, 30.0);
+
+        let p = layered_layout(&sizes, &edges, true, spacing);
+        assert_eq!(p[0].y, 20.0);
+        assert_eq!(p[1].y, p[2].y);
+        assert_eq!(p[1].y, 40.0 + 30.0 + 30.0);
+        assert_eq!(p[3].y, 40.0 + 30.0 + 60.0 + 30.0 + 20.0);
+        assert!((p[1].x - p[2].x).abs() >= 90.0 + 50.0);
+        let left = (0..4)
+            .map(|e| p[e].x - sizes[e].x / 2.0)
+            .fold(f32::MAX, f32::min);
+        assert!(left.abs() < 1e-3);
+
+        let p = layered_layout(&sizes, &edges, false, spacing);
+        assert!(p[3].y < p[1].y && p[1].y < p[0].y);
+    }
+
+    #[test]
+    fn layers_avoid_crossings_and_cycles() {
+        let sizes = [egui::Vec2::new(100.0, 40.0); 4];
+        let p = layered_layout(&sizes, &[(0, 3), (1, 2)], false, egui::Vec2::splat(20.0));
+        assert_eq!(p[0].y, p[1].y);
+        assert_eq!(p[2].y, p[3].y);
+        assert_eq!(p[0].x < p[1].x, p[3].x < p[2].x);
+
+        let p = layered_layout(
+            &sizes,
+            &[(0, 1), (1, 2), (2, 0)],
+            false,
+            egui::Vec2::splat(20.0),
+        );
+        assert!(p[0].y < p[1].y && p[1].y < p[2].y);
+    }
+
+    #[test]
+    fn layouts_roundtrip_through_json() {
+        let mut layout = DiagramLayout::default();
+        layout.elements.insert(
+            ModelUuid::nil(),
+            ElementLayout {
+                bounds: egui::Rect::from_min_size(
+                    egui::Pos2::new(10.5, -20.0),
+                    egui::Vec2::new(100.0, 50.0),
+                ),
+                waypoints: vec![egui::Pos2::new(1.0, 2.0)],
+            },
+        );
+        let json = layout.to_json();
+        assert!(json.contains("\"00000000-0000-0000-0000-000000000000\""));
+        assert_eq!(DiagramLayout::from_json(&json), Ok(layout));
+
+        assert!(DiagramLayout::from_json("{\"elements\": 1}").is_err());
+    }
+}
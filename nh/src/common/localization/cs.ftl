@@ -17,9 +17,14 @@ nh-project-openproject = Otevřít Projekt
 nh-project-openproject-confirm = Opravdu chcete otevřít jiný projekt?
 nh-project-recentprojects = Nedávné Projekty
 nh-project-addnewdocument = Přidat Nový Dokument
+nh-project-importdocuments = Importovat Dokumenty
 nh-project-addnewdiagram = Přidat Nový Diagram
 nh-project-save = Uložit Projekt
 nh-project-saveas = Uložit Projekt jako
+nh-project-generatedocumentation = Generovat dokumentaci
+nh-project-generatedocumentation-name = Název
+nh-project-generatedocumentation-stereotype = Stereotyp
+nh-project-generatedocumentation-comment = Komentář
 nh-project-closeproject = Zavřít Projekt
 nh-project-closeproject-confirm = Opravdu chcete současný projekt zavřít?
 nh-project-exit = Ukončit
@@ -35,22 +40,80 @@ nh-edit-copy = Kopírovat
 nh-edit-paste = Vložit
 nh-edit-pastehere = Vložit zde
 nh-edit-pasteinto = Vložit do '{ $name }'
+nh-edit-copyassvg = Kopírovat výběr jako SVG
+nh-edit-copyasplantuml = Kopírovat výběr jako PlantUML
+nh-edit-copylayout = Kopírovat rozložení jako JSON
+nh-edit-applylayout = Použít rozložení z JSON
+nh-edit-copyasimage = Kopírovat jako obrázek
+nh-edit-copyselectionasimage = Kopírovat výběr jako obrázek
 nh-edit-delete = Odstranit
+nh-edit-addtodo = Přidat úkol
+nh-edit-linkfile = Propojit externí soubor
+nh-edit-linkissue = Propojit úkol
+nh-edit-linksubdiagram = Propojit poddiagram…
+nh-subdiagram = Diagram otevřený dvojklikem na prvek:
+nh-subdiagram-none = Žádný
+nh-edit-linkelement = Hypertextový odkaz…
+nh-edit-lockelement = Zamknout
+nh-edit-unlockelement = Odemknout
+nh-elementlink = Cíl odkazu, otevřený dvojklikem na prvek s modifikátorem:
+nh-elementlink-none = Žádný
+nh-elementlink-diagram = Diagram
+nh-elementlink-document = Dokument
+nh-elementlink-url = URL
+nh-edit-setbackground = Nastavit obrázek na pozadí
+nh-edit-addimage = Přidat obrázek…
+nh-edit-pasteimage = Vložit obrázek
+nh-edit-placesymbol = Umístit symbol
 nh-edit-clearhighlight = Vyčistit zvýraznění
 nh-edit-arrange = Uspořádat
 nh-edit-arrange-bringtofront = Přenést do popředí
 nh-edit-arrange-forwardone = Krok do popředí
 nh-edit-arrange-backwardone = Krok do pozadí
 nh-edit-arrange-sendtoback = Odsunout do pozadí
+nh-edit-matchsize = Sjednotit velikost
+nh-edit-matchsize-width = Stejná šířka
+nh-edit-matchsize-height = Stejná výška
+nh-edit-matchsize-both = Stejná šířka i výška
+nh-edit-macros = Makra
+nh-edit-macros-record = Spustit nahrávání
+nh-edit-macros-recording = Nahrávání: { $count ->
+   [one] { $count } příkaz
+   [few] { $count } příkazy
+  *[other] { $count } příkazů
+}
+nh-edit-macros-name = Název makra
+nh-edit-macros-stop = Zastavit a uložit
+nh-edit-macros-discard = Zahodit nahrávání
+nh-edit-macros-run = Spustit
+nh-edit-macros-runeach = Spustit pro každý vybraný prvek
 
 nh-view = Pohled
+nh-view-newviewport = Otevřít v novém výřezu
 nh-view-resetposition = Resetovat umístění
 nh-view-resetscale = Resetovat zoom
 nh-view-zoomtofit = Zoom podle obsahu
 nh-view-zoomtofitselected = Zoom podle vybraného obsahu
+nh-view-bundleconnections = Seskupit souběžná spojení
+nh-view-clusternodes = Shlukovat uzly
+nh-view-clusternodes-none = Žádné
+nh-view-clusternodes-stronglyconnected = Silně souvislé komponenty
+nh-view-clusternodes-connected = Souvislé komponenty
+nh-view-clusternodes-packages = Balíčky
+nh-view-clusternodes-expandall = Rozbalit všechny shluky
+nh-view-clusternodes-collapseall = Sbalit všechny shluky
+nh-view-clusternodes-cluster = Shluk
+nh-view-livelayout-play = ▶ Spustit živé rozložení
+nh-view-livelayout-freeze = ❄ Zmrazit živé rozložení
+nh-view-livelayout-discard = ✖ Zahodit živé rozložení
+nh-view-treelayout = Stromové rozložení
+nh-view-radiallayout = Radiální rozložení
 
 nh-diagram = Diagram
 nh-diagram-exportto = Exportovat Diagram { $name } do
+nh-diagram-autolayout = Automatické rozložení
+nh-diagram-autolayout-layered = Vrstvené, spojení směřují dolů
+nh-diagram-autolayout-layeredupward = Vrstvené, spojení směřují nahoru (hierarchie generalizací)
 
 nh-windows = Okna
 nh-windows-recentlyused = Nedávné
@@ -70,6 +133,8 @@ nh-tab-settings = Nastavení
 nh-tab-newdiagram = Nový Diagram
 nh-tab-newdiagram-open = Přidat a otevřít
 nh-tab-newdiagram-background = Přidat na pozadí
+nh-tab-newdiagram-template = Kopie šablony projektu
+nh-tab-newdiagram-setdefault = Předvybrat pro tento typ diagramu
 
 nh-tab-projecthierarchy = Hierarchie Projektu
 nh-tab-projecthierarchy-newfolder = Nová složka
@@ -83,6 +148,10 @@ nh-tab-projecthierarchy-rename = Přejmenovat
 nh-tab-projecthierarchy-delete = Odstranit
 nh-tab-projecthierarchy-duplicate = Duplikovat
 nh-tab-projecthierarchy-duplicateshared = Duplikovat se sdíleným modelem
+nh-tab-projecthierarchy-duplicatevariant = Duplikovat jako variantu
+nh-tab-projecthierarchy-usetemplate = Šablona pro nové diagramy tohoto typu
+nh-tab-projecthierarchy-diffvariant = Porovnat s původním
+nh-tab-projecthierarchy-variantof = { $name } (varianta { $origin })
 nh-tab-projecthierarchy-addnewshareddiagram = Přidat nový diagram se sdíleným modelem
 
 nh-tab-modelhierarchy = Hierarchie Modelu
@@ -101,12 +170,70 @@ nh-tab-toolbar-selectmove = Vybrat/Posunout
 
 nh-tab-properties = Vlastnosti
 nh-tab-diagram = Diagram
+nh-tab-diagramviewport = Výřez
 nh-tab-document = Dokument
 nh-tab-customtab = Zvláštní karta
 nh-tab-outline = Obrys
+nh-tab-documentoutline = Osnova dokumentu
+nh-tab-documentoutline-noheadings = Dokument nemá žádné nadpisy
+
+nh-document-stats = Statistiky
+nh-document-stats-words = Slova
+nh-document-stats-characters = Znaky
+nh-document-stats-readingtime = Doba čtení (min)
+nh-document-stats-outline = Osnova
+nh-document-stats-workspace = Všechny dokumenty
+nh-document-stats-elements = Odkazované prvky
+nh-document-promoteselection = Povýšit na prvek
+nh-document-promoteselection-nodiagram = Nejprve otevřete diagram, do kterého se má prvek přidat
+nh-document-promoteselection-unsupported = Aktivní diagram neumí vytvářet prvky z textu
 
-nh-tab-globalcolors = Globální barvy
+nh-tab-globalcolors = Barvy projektu
 nh-tab-globalcolors-addnew = Přidat novou
+nh-tab-globalcolors-mergeinto = Sloučit do
+nh-tab-globalcolors-mergeinto-tooltip = Odstraní barvu, prvky, které ji používají, použijí místo ní zvolenou barvu
+
+nh-tab-symbols = Knihovna symbolů
+
+nh-tab-abbreviations = Zkratky
+nh-tab-abbreviations-addnew = Přidat novou
+nh-tab-abbreviations-requiredefined = Vyžadovat definici akronymů v názvech
+nh-tab-abbreviations-alldefined = Všechny akronymy jsou definovány
+nh-tab-abbreviations-undefined = Nedefinované akronymy
+nh-tab-tasks = Úkoly
+nh-tab-tasks-filter = Filtr
+nh-tab-tasks-count = Úkoly
+nh-tab-tasks-priority-low = Nízká
+nh-tab-tasks-priority-normal = Normální
+nh-tab-tasks-priority-high = Vysoká
+nh-tab-externallinks = Propojené soubory
+nh-tab-externallinks-path = Cesta k souboru
+nh-tab-externallinks-uptodate = Aktuální
+nh-tab-externallinks-outdated = Zastaralé
+nh-tab-externallinks-missing = Chybí
+nh-tab-externallinks-markuptodate = Označit jako aktuální
+nh-tab-externallinks-notwatched = Soubory jsou sledovány pouze v desktopové verzi
+nh-tab-issuelinks = Propojené úkoly
+nh-tab-issuelinks-reference = URL úkolu nebo vlastník/repozitář#číslo
+nh-tab-issuelinks-invalid = Nejde o úkol z GitHubu ani GitLabu
+nh-tab-issuelinks-loading = Načítání…
+nh-tab-issuelinks-unavailable = Nedostupné
+nh-tab-issuelinks-open = Otevřít
+nh-tab-issuelinks-notfetched = Podrobnosti úkolů jsou načítány pouze v desktopové verzi
+nh-tab-draworder = Pořadí vrstvení
+nh-tab-draworder-lockback = Držet vzadu
+nh-tab-draworder-lockfront = Držet vpředu
+nh-tab-layers = Vrstvy
+nh-tab-layers-annotations = Poznámky
+nh-tab-layers-default = Výchozí vrstva
+nh-tab-layers-layer = Vrstva
+nh-tab-layers-visible = Viditelná
+nh-tab-layers-locked = Zamčená, její prvky nelze vybrat
+nh-tab-layers-assign = Přesunout vybrané sem
+nh-tab-layers-addnew = Přidat vrstvu
+nh-tab-layers-lockedelements = Zamčené prvky
+nh-tab-layers-lockselected = Zamknout vybrané
+nh-tab-layers-unlockselected = Odemknout vybrané
 
 nh-modal-colorpicker-nooveridebrackets = [beze změn]
 nh-modal-colorpicker-notfoundbrackets = [nenalezeno]
@@ -114,7 +241,7 @@ nh-modal-colorpicker-nooverride = Beze změn
 nh-modal-colorpicker-localcolor = Lokální barva
 nh-modal-colorpicker-globalcolor = Globální barva
 
-nh-window-svgexport = Možnosti SVG Exportu
+nh-window-svgexport = Možnosti exportu obrázku
 nh-window-svgexport-solidbackground = Pevné pozadí
 nh-window-svgexport-gridlines = Mřížka
 nh-window-svgexport-select = Výběr
@@ -123,6 +250,55 @@ nh-window-svgexport-warning = Varování
 nh-window-svgexport-invalid = Neplatný
 nh-window-svgexport-horizontalpadding = Horizontální vycpávka
 nh-window-svgexport-verticalpadding = Vertikální vycpávka
+nh-window-svgexport-scale = Měřítko
+nh-window-svgexport-rastersize = { $dpi } DPI, { $width } × { $height } px
+
+nh-grid = Mřížka
+nh-grid-size = Velikost
+nh-grid-visible = Zobrazit mřížku
+nh-grid-snap = Přichytávat k mřížce
+nh-frame = Rámec diagramu
+nh-frame-kind = Druh rámce:
+nh-annotations = Poznámky
+nh-annotations-visible = Zobrazit poznámky
+nh-annotations-none = Výběr
+nh-annotations-freehand = Volná kresba
+nh-annotations-arrow = Šipka
+nh-annotations-highlight = Zvýraznění
+nh-annotations-eraser = Guma
+nh-annotations-color = Barva:
+nh-annotations-clear = Smazat poznámky
+nh-style = Styl
+nh-style-default = Výchozí
+nh-style-line = Styl čáry
+nh-style-line-solid = Plná
+nh-style-line-dashed = Čárkovaná
+nh-style-line-dotted = Tečkovaná
+nh-style-cornerradius = Zaoblení rohů
+nh-style-fill = Výplň
+nh-style-fill-plain = Jednolitá
+nh-style-fill-hatched = Šrafovaná
+nh-style-fill-crosshatched = Křížově šrafovaná
+nh-style-fontscale = Velikost písma
+nh-style-bold = Tučné písmo
+nh-style-reset = Obnovit styl
+nh-theme = Barvy
+nh-theme-followapp = Podle motivu aplikace
+nh-theme-light = Světlé
+nh-theme-dark = Tmavé
+nh-linejumps = Přemostit křížící se spojnice
+nh-background = Obrázek na pozadí
+nh-background-opacity = Krytí
+nh-background-scale = Měřítko
+nh-background-position = Pozice
+nh-background-tiled = Dlaždicově
+nh-background-exported = Zahrnout do exportů
+nh-background-remove = Odstranit pozadí
+
+nh-image = Obrázek
+nh-image-width = Šířka
+nh-image-remove = Odstranit obrázek
+nh-symbol = Symbol
 
 
 pluralize-element = { $count ->
@@ -143,3 +319,7 @@ nh-viewcommand-addelementsinto = Přidat { pluralize-element } do '{ $diagram }'
 nh-viewcommand-removeelements = Odstranit { pluralize-element }
 nh-viewcommand-removeelementsfrom = Odstranit { pluralize-element } z '{ $diagram }'
 nh-viewcommand-modifyelements = Upravit { pluralize-element }
+nh-viewcommand-changegrid = Změnit mřížku v '{ $diagram }'
+nh-viewcommand-livelayout = Živé rozložení: { pluralize-element }
+nh-viewcommand-applylayout = Použití rozložení: { pluralize-element }
+nh-viewcommand-matchsize = Sjednocení velikosti: { pluralize-element }
@@ -17,9 +17,14 @@ nh-project-openproject = Open Project
 nh-project-openproject-confirm = Are you sure you want to open a different project?
 nh-project-recentprojects = Recent Projects
 nh-project-addnewdocument = Add New Document
+nh-project-importdocuments = Import Documents
 nh-project-addnewdiagram = Add New Diagram
 nh-project-save = Save Project
 nh-project-saveas = Save Project as
+nh-project-generatedocumentation = Generate Documentation
+nh-project-generatedocumentation-name = Name
+nh-project-generatedocumentation-stereotype = Stereotype
+nh-project-generatedocumentation-comment = Comment
 nh-project-closeproject = Close Project
 nh-project-closeproject-confirm = Are you sure you want to close the current project?
 nh-project-exit = Exit
@@ -35,22 +40,79 @@ nh-edit-copy = Copy
 nh-edit-paste = Paste
 nh-edit-pastehere = Paste here
 nh-edit-pasteinto = Paste into '{ $name }'
+nh-edit-copyassvg = Copy selection as SVG
+nh-edit-copyasplantuml = Copy selection as PlantUML
+nh-edit-copylayout = Copy layout as JSON
+nh-edit-applylayout = Apply layout from JSON
+nh-edit-copyasimage = Copy as image
+nh-edit-copyselectionasimage = Copy selection as image
 nh-edit-delete = Delete
+nh-edit-addtodo = Add TODO
+nh-edit-linkfile = Link external file
+nh-edit-linkissue = Link issue
+nh-edit-linksubdiagram = Link sub-diagram…
+nh-subdiagram = Diagram double-clicking the element opens:
+nh-subdiagram-none = None
+nh-edit-linkelement = Hyperlink…
+nh-edit-lockelement = Lock
+nh-edit-unlockelement = Unlock
+nh-elementlink = Target of the hyperlink, followed by double-clicking the element with a modifier:
+nh-elementlink-none = None
+nh-elementlink-diagram = Diagram
+nh-elementlink-document = Document
+nh-elementlink-url = URL
+nh-edit-setbackground = Set background image
+nh-edit-addimage = Add image…
+nh-edit-pasteimage = Paste image
+nh-edit-placesymbol = Place symbol
 nh-edit-clearhighlight = Clear highlight
 nh-edit-arrange = Arrange
 nh-edit-arrange-bringtofront = Bring to Front
 nh-edit-arrange-forwardone = Forward One
 nh-edit-arrange-backwardone = Backward One
 nh-edit-arrange-sendtoback = Send to Back
+nh-edit-matchsize = Match Size
+nh-edit-matchsize-width = Same Width
+nh-edit-matchsize-height = Same Height
+nh-edit-matchsize-both = Same Width and Height
+nh-edit-macros = Macros
+nh-edit-macros-record = Start recording
+nh-edit-macros-recording = Recording: { $count ->
+   [one] { $count } command
+  *[other] { $count } commands
+}
+nh-edit-macros-name = Macro name
+nh-edit-macros-stop = Stop and save
+nh-edit-macros-discard = Discard recording
+nh-edit-macros-run = Run
+nh-edit-macros-runeach = Run on each selected element
 
 nh-view = View
+nh-view-newviewport = Open in New Viewport
 nh-view-resetposition = Reset Position
 nh-view-resetscale = Reset Scale
 nh-view-zoomtofit = Zoom to fit
 nh-view-zoomtofitselected = Zoom to fit selected
+nh-view-bundleconnections = Bundle parallel connections
+nh-view-clusternodes = Cluster nodes
+nh-view-clusternodes-none = None
+nh-view-clusternodes-stronglyconnected = Strongly connected components
+nh-view-clusternodes-connected = Connected components
+nh-view-clusternodes-packages = Packages
+nh-view-clusternodes-expandall = Expand all clusters
+nh-view-clusternodes-collapseall = Collapse all clusters
+nh-view-clusternodes-cluster = Cluster
+nh-view-livelayout-play = ▶ Start live layout
+nh-view-livelayout-freeze = ❄ Freeze live layout
+nh-view-livelayout-discard = ✖ Discard live layout
+nh-view-treelayout = Tree layout
+nh-view-radiallayout = Radial layout
 
 nh-diagram = Diagram
 nh-diagram-exportto = Export Diagram { $name } to
+nh-diagram-autolayout = Automatic Layout
+nh-diagram-autolayout-layered = Layered, connections pointing down
+nh-diagram-autolayout-layeredupward = Layered, connections pointing up (generalization hierarchies)
 
 nh-windows = Windows
 nh-windows-recentlyused = Recently Used
@@ -70,6 +132,8 @@ nh-tab-settings = Settings
 nh-tab-newdiagram = New Diagram
 nh-tab-newdiagram-open = Add and Open
 nh-tab-newdiagram-background = Add in Background
+nh-tab-newdiagram-template = Copy of project template
+nh-tab-newdiagram-setdefault = Preselect for this diagram type
 
 nh-tab-projecthierarchy = Project Hierarchy
 nh-tab-projecthierarchy-newfolder = New folder
@@ -83,6 +147,10 @@ nh-tab-projecthierarchy-rename = Rename
 nh-tab-projecthierarchy-delete = Delete
 nh-tab-projecthierarchy-duplicate = Duplicate
 nh-tab-projecthierarchy-duplicateshared = Duplicate with shared model
+nh-tab-projecthierarchy-duplicatevariant = Duplicate as variant
+nh-tab-projecthierarchy-usetemplate = Template for new diagrams of this type
+nh-tab-projecthierarchy-diffvariant = Diff against origin
+nh-tab-projecthierarchy-variantof = { $name } (variant of { $origin })
 nh-tab-projecthierarchy-addnewshareddiagram = Add new diagram with shared model
 
 nh-tab-modelhierarchy = Model Hierarchy
@@ -101,12 +169,70 @@ nh-tab-toolbar-selectmove = Select/Move
 
 nh-tab-properties = Properties
 nh-tab-diagram = Diagram
+nh-tab-diagramviewport = Viewport
 nh-tab-document = Document
 nh-tab-customtab = Custom Tab
 nh-tab-outline = Outline
+nh-tab-documentoutline = Document Outline
+nh-tab-documentoutline-noheadings = The document has no headings
+
+nh-document-stats = Statistics
+nh-document-stats-words = Words
+nh-document-stats-characters = Characters
+nh-document-stats-readingtime = Reading time (min)
+nh-document-stats-outline = Outline
+nh-document-stats-workspace = All documents
+nh-document-stats-elements = Linked elements
+nh-document-promoteselection = Promote to element
+nh-document-promoteselection-nodiagram = Open the diagram the element should be added to first
+nh-document-promoteselection-unsupported = The focused diagram cannot create elements from text
 
-nh-tab-globalcolors = Global Colors
+nh-tab-globalcolors = Project Colors
 nh-tab-globalcolors-addnew = Add new
+nh-tab-globalcolors-mergeinto = Merge into
+nh-tab-globalcolors-mergeinto-tooltip = Removes the color, elements using it will use the chosen color instead
+
+nh-tab-symbols = Symbol Library
+
+nh-tab-abbreviations = Abbreviations
+nh-tab-abbreviations-addnew = Add new
+nh-tab-abbreviations-requiredefined = Require acronyms in names to be defined
+nh-tab-abbreviations-alldefined = All acronyms are defined
+nh-tab-abbreviations-undefined = Undefined acronyms
+nh-tab-tasks = Tasks
+nh-tab-tasks-filter = Filter
+nh-tab-tasks-count = TODOs
+nh-tab-tasks-priority-low = Low
+nh-tab-tasks-priority-normal = Normal
+nh-tab-tasks-priority-high = High
+nh-tab-externallinks = Linked Files
+nh-tab-externallinks-path = Path to the file
+nh-tab-externallinks-uptodate = Up to date
+nh-tab-externallinks-outdated = Outdated
+nh-tab-externallinks-missing = Missing
+nh-tab-externallinks-markuptodate = Mark up to date
+nh-tab-externallinks-notwatched = Files are only watched in the desktop version
+nh-tab-issuelinks = Linked Issues
+nh-tab-issuelinks-reference = Issue URL or owner/repository#number
+nh-tab-issuelinks-invalid = Not a GitHub or GitLab issue
+nh-tab-issuelinks-loading = Loading…
+nh-tab-issuelinks-unavailable = Unavailable
+nh-tab-issuelinks-open = Open
+nh-tab-issuelinks-notfetched = Issue details are only fetched in the desktop version
+nh-tab-draworder = Stacking Order
+nh-tab-draworder-lockback = Keep at back
+nh-tab-draworder-lockfront = Keep in front
+nh-tab-layers = Layers
+nh-tab-layers-annotations = Annotations
+nh-tab-layers-default = Default layer
+nh-tab-layers-layer = Layer
+nh-tab-layers-visible = Visible
+nh-tab-layers-locked = Locked, its elements cannot be selected
+nh-tab-layers-assign = Move selected here
+nh-tab-layers-addnew = Add layer
+nh-tab-layers-lockedelements = Locked elements
+nh-tab-layers-lockselected = Lock selected
+nh-tab-layers-unlockselected = Unlock selected
 
 nh-modal-colorpicker-nooveridebrackets = [no override]
 nh-modal-colorpicker-notfoundbrackets = [not found]
@@ -114,7 +240,7 @@ nh-modal-colorpicker-nooverride = No override
 nh-modal-colorpicker-localcolor = Local color
 nh-modal-colorpicker-globalcolor = Global color
 
-nh-window-svgexport = SVG Export Options
+nh-window-svgexport = Image Export Options
 nh-window-svgexport-solidbackground = Solid background
 nh-window-svgexport-gridlines = Gridlines
 nh-window-svgexport-select = Select
@@ -123,6 +249,55 @@ nh-window-svgexport-warning = Warning
 nh-window-svgexport-invalid = Invalid
 nh-window-svgexport-horizontalpadding = Horizontal padding
 nh-window-svgexport-verticalpadding = Vertical padding
+nh-window-svgexport-scale = Scale
+nh-window-svgexport-rastersize = { $dpi } DPI, { $width } × { $height } px
+
+nh-grid = Grid
+nh-grid-size = Size
+nh-grid-visible = Show grid
+nh-grid-snap = Snap to grid
+nh-frame = Diagram frame
+nh-frame-kind = Frame kind:
+nh-annotations = Annotations
+nh-annotations-visible = Show annotations
+nh-annotations-none = Select
+nh-annotations-freehand = Freehand
+nh-annotations-arrow = Arrow
+nh-annotations-highlight = Highlight
+nh-annotations-eraser = Eraser
+nh-annotations-color = Color:
+nh-annotations-clear = Clear annotations
+nh-style = Style
+nh-style-default = Default
+nh-style-line = Line style
+nh-style-line-solid = Solid
+nh-style-line-dashed = Dashed
+nh-style-line-dotted = Dotted
+nh-style-cornerradius = Corner radius
+nh-style-fill = Fill pattern
+nh-style-fill-plain = Plain
+nh-style-fill-hatched = Hatched
+nh-style-fill-crosshatched = Cross-hatched
+nh-style-fontscale = Font scale
+nh-style-bold = Bold text
+nh-style-reset = Reset style
+nh-theme = Colors
+nh-theme-followapp = Follow application theme
+nh-theme-light = Light
+nh-theme-dark = Dark
+nh-linejumps = Hop over crossing connections
+nh-background = Background image
+nh-background-opacity = Opacity
+nh-background-scale = Scale
+nh-background-position = Position
+nh-background-tiled = Tiled
+nh-background-exported = Include in exports
+nh-background-remove = Remove background
+
+nh-image = Image
+nh-image-width = Width
+nh-image-remove = Remove image
+nh-symbol = Symbol
 
 
 pluralize-element = { $count ->
@@ -142,3 +317,7 @@ nh-viewcommand-addelementsinto = Add { pluralize-element } to '{ $diagram }'
 nh-viewcommand-removeelements = Remove { pluralize-element }
 nh-viewcommand-removeelementsfrom = Remove { pluralize-element } from '{ $diagram }'
 nh-viewcommand-modifyelements = Modify { pluralize-element }
+nh-viewcommand-changegrid = Change grid of '{ $diagram }'
+nh-viewcommand-livelayout = Live layout of { pluralize-element }
+nh-viewcommand-applylayout = Apply layout to { pluralize-element }
+nh-viewcommand-matchsize = Match size of { pluralize-element }
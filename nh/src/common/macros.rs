@@ -0,0 +1,334 @@
+use std::sync::Arc;
+
+use eframe::egui;
+
+use crate::common::{
+    canvas::Highlight,
+    controller::{
+        DiagramCommand, DiagramController, GlobalDrawingContext, ProjectCommand,
+        SimpleProjectCommand,
+    },
+    eref::ERef,
+    uuid::{ModelUuid, ViewUuid},
+};
+
+/// One recorded edit of a macro
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum MacroStep {
+    Command(DiagramCommand),
+    /// Property change made in the properties panel, serialized as JSON.
+    /// It is replayed on the elements selected at that time, in diagrams of the same domain.
+    PropertyChange(String),
+}
+
+impl MacroStep {
+    /// Whether the step only refines the previous one, e.g. a name typed one more character,
+    /// so that only the final value of an edit is kept
+    fn refines(&self, previous: &MacroStep) -> bool {
+        fn variant_and_target(json: &str) -> Option<(String, Vec<serde_json::Value>)> {
+            match serde_json::from_str(json).ok()? {
+                serde_json::Value::Object(o) if o.len() == 1 => {
+                    let (variant, value) = o.into_iter().next()?;
+                    let target = match value {
+                        serde_json::Value::Array(mut a) => {
+                            a.pop();
+                            a
+                        }
+                        _ => vec![],
+                    };
+                    Some((variant, target))
+                }
+                _ => None,
+            }
+        }
+        match (self, previous) {
+            (MacroStep::PropertyChange(a), MacroStep::PropertyChange(b)) => {
+                variant_and_target(a).is_some_and(|a| Some(a) == variant_and_target(b))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Named sequence of recorded diagram commands and property changes, stored in user settings
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CommandMacro {
+    pub name: String,
+    #[serde(default)]
+    pub annotation: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl CommandMacro {
+    /// Whether the command can be recorded, i.e. it is an edit that does not refer to specific elements
+    pub fn is_recordable(command: &DiagramCommand) -> bool {
+        match command {
+            DiagramCommand::DropRedoStackAndLastChangeFlag
+            | DiagramCommand::SetLastChangeFlag
+            | DiagramCommand::UndoImmediate
+            | DiagramCommand::RedoImmediate
+            | DiagramCommand::DeleteSelectedElements(None)
+            | DiagramCommand::CopySelectedAsSvg
+            | DiagramCommand::CopySelectedAsPlantUml
+            | DiagramCommand::CopyLayoutAsJson
+            | DiagramCommand::ApplyLayoutFromJson
+            | DiagramCommand::HighlightElement(..)
+            | DiagramCommand::PanToElement(..)
+            | DiagramCommand::MoveInDrawOrder(..)
+            | DiagramCommand::LockInDrawOrder(..)
+            | DiagramCommand::SetBackgroundImage(..)
+            | DiagramCommand::SetLayerVisible(..)
+            | DiagramCommand::SetLayerLocked(..)
+            | DiagramCommand::RemoveLayer(..)
+            | DiagramCommand::MoveLayer(..)
+            | DiagramCommand::AssignSelectedToLayer(..)
+            | DiagramCommand::SetAnnotationsVisible(..)
+            | DiagramCommand::SetElementLocked(..)
+            | DiagramCommand::CreateViewFor(..)
+            | DiagramCommand::DeleteViewFor(..) => false,
+            DiagramCommand::InvertSelection
+            | DiagramCommand::DeleteSelectedElements(Some(_))
+            | DiagramCommand::CutSelectedElements
+            | DiagramCommand::CopySelectedElements
+            | DiagramCommand::PasteClipboardElements(..)
+            | DiagramCommand::ArrangeSelected(..)
+            | DiagramCommand::MatchSizeSelected(..)
+            | DiagramCommand::ColorSelected(..)
+            | DiagramCommand::LockSelectedElements(..)
+            | DiagramCommand::HighlightAllElements(..)
+            | DiagramCommand::PanCamera(..)
+            | DiagramCommand::ResetPosition
+            | DiagramCommand::AddZoomPercent(..)
+            | DiagramCommand::ResetScale
+            | DiagramCommand::ZoomToFit { .. }
+            | DiagramCommand::AddChildElement
+            | DiagramCommand::AddSiblingElement
+            | DiagramCommand::LayoutAsTree { .. }
+            | DiagramCommand::LayoutLayered { .. }
+            | DiagramCommand::LiveLayout(..) => true,
+        }
+    }
+
+    /// Commands replaying the macro in the given diagram.
+    ///
+    /// When `each_of` is given, the macro is replayed once for each of the elements, with only that element selected.
+    pub fn replay(&self, diagram: ViewUuid, each_of: Option<&[ModelUuid]>) -> Vec<ProjectCommand> {
+        let steps = match each_of {
+            None => self.steps.clone(),
+            Some(elements) => elements
+                .iter()
+                .flat_map(|e| {
+                    [
+                        MacroStep::Command(DiagramCommand::HighlightAllElements(
+                            false,
+                            Highlight::SELECTED,
+                        )),
+                        MacroStep::Command(DiagramCommand::HighlightElement(
+                            (*e).into(),
+                            true,
+                            Highlight::SELECTED,
+                        )),
+                    ]
+                    .into_iter()
+                    .chain(self.steps.iter().cloned())
+                })
+                .collect(),
+        };
+        steps
+            .into_iter()
+            .map(|e| match e {
+                MacroStep::Command(c) => {
+                    SimpleProjectCommand::SpecificDiagramCommand(diagram, c).into()
+                }
+                MacroStep::PropertyChange(p) => {
+                    ProjectCommand::ApplyPropertyChange(diagram, None, Arc::new(p))
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct MacroRecorder {
+    pub macros: Vec<CommandMacro>,
+    /// Steps recorded so far, while a macro is being recorded
+    pub recording: Option<Vec<MacroStep>>,
+    new_macro_name: String,
+}
+
+impl MacroRecorder {
+    pub fn new(macros: Vec<CommandMacro>) -> Self {
+        Self {
+            macros,
+            recording: None,
+            new_macro_name: String::new(),
+        }
+    }
+
+    /// Records the step if a macro is being recorded
+    pub fn record(&mut self, step: MacroStep) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        if let Some(last) = recording.last_mut()
+            && step.refines(last)
+        {
+            *last = step;
+        } else {
+            recording.push(step);
+        }
+    }
+
+    pub fn show_menu(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        focused: Option<(ViewUuid, ERef<dyn DiagramController>)>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        match &self.recording {
+            None => {
+                if ui
+                    .button(gdc.translate_0("nh-edit-macros-record"))
+                    .clicked()
+                {
+                    self.recording = Some(Vec::new());
+                    self.new_macro_name.clear();
+                    ui.close();
+                }
+            }
+            Some(recorded) => {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("count", recorded.len());
+                ui.label(
+                    gdc.fluent_bundle.format_pattern(
+                        gdc.fluent_bundle
+                            .get_message("nh-edit-macros-recording")
+                            .unwrap()
+                            .value()
+                            .unwrap(),
+                        Some(&args),
+                        &mut vec![],
+                    ),
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.new_macro_name)
+                        .hint_text(gdc.translate_0("nh-edit-macros-name")),
+                );
+                let can_save = !recorded.is_empty() && !self.new_macro_name.trim().is_empty();
+                if ui
+                    .add_enabled(
+                        can_save,
+                        egui::Button::new(gdc.translate_0("nh-edit-macros-stop")),
+                    )
+                    .clicked()
+                {
+                    self.macros.push(CommandMacro {
+                        name: self.new_macro_name.trim().to_owned(),
+                        annotation: String::new(),
+                        steps: self.recording.take().unwrap_or_default(),
+                    });
+                    ui.close();
+                }
+                if ui
+                    .button(gdc.translate_0("nh-edit-macros-discard"))
+                    .clicked()
+                {
+                    self.recording = None;
+                    ui.close();
+                }
+            }
+        }
+
+        if self.macros.is_empty() {
+            return;
+        }
+        ui.separator();
+
+        let enabled = self.recording.is_none() && focused.is_some();
+        for m in &self.macros {
+            ui.add_enabled_ui(enabled, |ui| {
+                let r = ui.menu_button(&m.name, |ui| {
+                    let Some((v, c)) = &focused else {
+                        return;
+                    };
+                    if ui.button(gdc.translate_0("nh-edit-macros-run")).clicked() {
+                        commands.extend(m.replay(*v, None));
+                        ui.close();
+                    }
+                    let selected = c.read().selected_models(v);
+                    if ui
+                        .add_enabled(
+                            !selected.is_empty(),
+                            egui::Button::new(gdc.translate_0("nh-edit-macros-runeach")),
+                        )
+                        .clicked()
+                    {
+                        commands.extend(m.replay(*v, Some(&selected)));
+                        ui.close();
+                    }
+                });
+                if !m.annotation.is_empty() {
+                    r.response.on_hover_text(&m.annotation);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_on_each_element_selects_it_first() {
+        let m = CommandMacro {
+            name: "Front".to_owned(),
+            annotation: String::new(),
+            steps: vec![
+                MacroStep::Command(DiagramCommand::ArrangeSelected(
+                    crate::common::controller::Arrangement::BringToFront,
+                )),
+                MacroStep::PropertyChange(r#"{"NameChange":"Task"}"#.to_owned()),
+            ],
+        };
+        let diagram = ViewUuid::now_v7();
+        let (a, b) = (ModelUuid::now_v7(), ModelUuid::now_v7());
+
+        assert_eq!(m.replay(diagram, None).len(), 2);
+        let replayed = m.replay(diagram, Some(&[a, b]));
+        assert_eq!(replayed.len(), 8);
+        assert!(matches!(
+            &replayed[5],
+            ProjectCommand::SimpleProjectCommand(SimpleProjectCommand::SpecificDiagramCommand(d, c))
+                if *d == diagram
+                    && *c == DiagramCommand::HighlightElement(b.into(), true, Highlight::SELECTED)
+        ));
+        assert!(matches!(
+            &replayed[7],
+            ProjectCommand::ApplyPropertyChange(d, None, p) if *d == diagram && p.contains("Task")
+        ));
+    }
+
+    #[test]
+    fn typing_into_a_property_is_recorded_once() {
+        let mut recorder = MacroRecorder::new(vec![]);
+        recorder.recording = Some(vec![]);
+        for e in [
+            r#"{"NameChange":"T"}"#,
+            r#"{"NameChange":"Ta"}"#,
+            r#"{"LinkRoleChange":[false,"a"]}"#,
+            r#"{"LinkRoleChange":[true,"b"]}"#,
+            r#"{"LinkRoleChange":[true,"bc"]}"#,
+        ] {
+            recorder.record(MacroStep::PropertyChange(e.to_owned()));
+        }
+        assert_eq!(
+            recorder.recording.unwrap(),
+            vec![
+                MacroStep::PropertyChange(r#"{"NameChange":"Ta"}"#.to_owned()),
+                MacroStep::PropertyChange(r#"{"LinkRoleChange":[false,"a"]}"#.to_owned()),
+                MacroStep::PropertyChange(r#"{"LinkRoleChange":[true,"bc"]}"#.to_owned()),
+            ]
+        );
+    }
+}
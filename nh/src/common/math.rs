@@ -0,0 +1,396 @@
+//! Lightweight layout of `$...$` math fragments into plain Unicode text.
+//!
+//! Only a small subset of TeX is understood: symbol commands, super- and subscripts,
+//! `\frac`, `\sqrt`, `\mathbb` and text commands. Since the result is ordinary text,
+//! it is drawn and exported by every canvas without further support.
+
+use std::borrow::Cow;
+
+const SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "φ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("nexists", "∄"),
+    ("neg", "¬"),
+    ("lnot", "¬"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("leftrightarrow", "↔"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("Leftrightarrow", "⇔"),
+    ("implies", "⟹"),
+    ("iff", "⟺"),
+    ("mapsto", "↦"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("ni", "∋"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("bigcup", "⋃"),
+    ("bigcap", "⋂"),
+    ("setminus", "∖"),
+    ("emptyset", "∅"),
+    ("varnothing", "∅"),
+    ("times", "×"),
+    ("cdot", "⋅"),
+    ("circ", "∘"),
+    ("div", "÷"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("oplus", "⊕"),
+    ("otimes", "⊗"),
+    ("leq", "≤"),
+    ("le", "≤"),
+    ("geq", "≥"),
+    ("ge", "≥"),
+    ("neq", "≠"),
+    ("ne", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("triangleq", "≜"),
+    ("coloneqq", "≔"),
+    ("prec", "≺"),
+    ("succ", "≻"),
+    ("infty", "∞"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("top", "⊤"),
+    ("bot", "⊥"),
+    ("perp", "⊥"),
+    ("vdash", "⊢"),
+    ("models", "⊨"),
+    ("Box", "□"),
+    ("Diamond", "◇"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("mid", "∣"),
+    ("parallel", "∥"),
+    ("ldots", "…"),
+    ("cdots", "⋯"),
+    ("aleph", "ℵ"),
+    ("ell", "ℓ"),
+    ("prime", "′"),
+    ("star", "⋆"),
+    ("ast", "∗"),
+    ("quad", "  "),
+    ("qquad", "    "),
+];
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('f', 'ᶠ'),
+    ('g', 'ᵍ'),
+    ('h', 'ʰ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('l', 'ˡ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('o', 'ᵒ'),
+    ('p', 'ᵖ'),
+    ('r', 'ʳ'),
+    ('s', 'ˢ'),
+    ('t', 'ᵗ'),
+    ('u', 'ᵘ'),
+    ('v', 'ᵛ'),
+    ('w', 'ʷ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+    ('z', 'ᶻ'),
+    ('′', '′'),
+];
+
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+const DOUBLE_STRUCK: &[(char, char)] = &[
+    ('B', '𝔹'),
+    ('C', 'ℂ'),
+    ('N', 'ℕ'),
+    ('P', 'ℙ'),
+    ('Q', 'ℚ'),
+    ('R', 'ℝ'),
+    ('Z', 'ℤ'),
+];
+
+/// Replaces `$...$` fragments with their Unicode layout. `\$` stands for a literal dollar sign,
+/// an unterminated fragment is kept as written.
+pub fn render_math(text: &str) -> Cow<'_, str> {
+    if !text.contains('$') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(idx) = find_unescaped_dollar(rest) {
+        result.push_str(&rest[..idx].replace("\\$", "$"));
+        let fragment = &rest[idx + 1..];
+        let Some(end) = find_unescaped_dollar(fragment) else {
+            rest = &rest[idx..];
+            break;
+        };
+        result.push_str(&layout(&fragment[..end]));
+        rest = &fragment[end + 1..];
+    }
+    result.push_str(&rest.replace("\\$", "$"));
+
+    Cow::Owned(result)
+}
+
+fn find_unescaped_dollar(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        match c {
+            '$' if !escaped => return Some(idx),
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Lays out a single fragment, without the enclosing dollar signs
+pub fn layout(fragment: &str) -> String {
+    let chars: Vec<char> = fragment.chars().collect();
+    let mut pos = 0;
+    let mut result = layout_group(&chars, &mut pos);
+    // Unbalanced closing braces are kept as written
+    while pos < chars.len() {
+        result.push('}');
+        pos += 1;
+        result.push_str(&layout_group(&chars, &mut pos));
+    }
+    result
+}
+
+fn layout_group(chars: &[char], pos: &mut usize) -> String {
+    let mut result = String::new();
+    while *pos < chars.len() && chars[*pos] != '}' {
+        result.push_str(&layout_atom(chars, pos));
+    }
+    result
+}
+
+fn layout_atom(chars: &[char], pos: &mut usize) -> String {
+    let c = chars[*pos];
+    *pos += 1;
+    match c {
+        '{' => {
+            let group = layout_group(chars, pos);
+            *pos += 1;
+            group
+        }
+        '^' | '_' if *pos < chars.len() => {
+            let argument = layout_argument(chars, pos);
+            let table = if c == '^' { SUPERSCRIPTS } else { SUBSCRIPTS };
+            let scripted: Option<String> = argument
+                .chars()
+                .map(|e| table.iter().find(|t| t.0 == e).map(|t| t.1))
+                .collect();
+            match scripted {
+                Some(s) => s,
+                None if argument.chars().count() == 1 => format!("{}{}", c, argument),
+                None => format!("{}({})", c, argument),
+            }
+        }
+        '\'' => "′".to_owned(),
+        '\\' => layout_command(chars, pos),
+        c => c.to_string(),
+    }
+}
+
+/// Argument of a command or a script: a group, a command or a single character
+fn layout_argument(chars: &[char], pos: &mut usize) -> String {
+    while *pos < chars.len() && chars[*pos] == ' ' {
+        *pos += 1;
+    }
+    if *pos < chars.len() {
+        layout_atom(chars, pos)
+    } else {
+        String::new()
+    }
+}
+
+fn parenthesized(s: String) -> String {
+    if s.chars().count() > 1 {
+        format!("({})", s)
+    } else {
+        s
+    }
+}
+
+fn layout_command(chars: &[char], pos: &mut usize) -> String {
+    let start = *pos;
+    while *pos < chars.len() && chars[*pos].is_ascii_alphabetic() {
+        *pos += 1;
+    }
+    if *pos == start {
+        // Single character commands, such as escapes and spacing
+        let Some(c) = chars.get(*pos) else {
+            return "\\".to_owned();
+        };
+        *pos += 1;
+        return match c {
+            ',' | ';' | ':' | ' ' => " ".to_owned(),
+            '!' => String::new(),
+            c => c.to_string(),
+        };
+    }
+
+    let name: String = chars[start..*pos].iter().collect();
+    match name.as_str() {
+        "frac" => {
+            let numerator = layout_argument(chars, pos);
+            let denominator = layout_argument(chars, pos);
+            format!(
+                "{}/{}",
+                parenthesized(numerator),
+                parenthesized(denominator)
+            )
+        }
+        "sqrt" => format!("√{}", parenthesized(layout_argument(chars, pos))),
+        "mathbb" => layout_argument(chars, pos)
+            .chars()
+            .map(|e| {
+                DOUBLE_STRUCK
+                    .iter()
+                    .find(|t| t.0 == e)
+                    .map(|t| t.1)
+                    .unwrap_or(e)
+            })
+            .collect(),
+        "text" | "mathrm" | "mathit" | "mathbf" | "operatorname" => layout_argument(chars, pos),
+        name => SYMBOLS
+            .iter()
+            .find(|e| e.0 == name)
+            .map(|e| e.1.to_owned())
+            .unwrap_or_else(|| format!("\\{}", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_math;
+
+    #[test]
+    fn fragments_are_laid_out_and_the_rest_is_kept() {
+        assert_eq!(render_math("no math here"), "no math here");
+        assert_eq!(
+            render_math(r"invariant $\forall x \in \mathbb{N}: x^2 \geq x_0$ holds"),
+            "invariant ∀ x ∈ ℕ: x² ≥ x₀ holds"
+        );
+        assert_eq!(
+            render_math(r"$\frac{a+b}{2} \neq \sqrt{y}$"),
+            "(a+b)/2 ≠ √y"
+        );
+        assert_eq!(render_math(r"costs \$5, $\alpha^{q}$"), "costs $5, α^q");
+        assert_eq!(render_math("unterminated $x"), "unterminated $x");
+        assert_eq!(render_math("$x}^{\\prime}$"), "x}′");
+    }
+}
@@ -0,0 +1,120 @@
+/// How generated names, such as of tables or source files, are written
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NamingConvention {
+    /// Names are kept, generated names are joined by underscores
+    AsIs,
+    SnakeCase,
+    ScreamingSnakeCase,
+    CamelCase,
+    PascalCase,
+}
+
+impl NamingConvention {
+    pub const VARIANTS: [Self; 5] = [
+        Self::AsIs,
+        Self::SnakeCase,
+        Self::ScreamingSnakeCase,
+        Self::CamelCase,
+        Self::PascalCase,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AsIs => "As is",
+            Self::SnakeCase => "snake_case",
+            Self::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            Self::CamelCase => "camelCase",
+            Self::PascalCase => "PascalCase",
+        }
+    }
+
+    /// Name made of the given parts, such as a table name and a column name of a foreign key
+    pub fn name(&self, parts: &[&str]) -> String {
+        if *self == Self::AsIs {
+            return parts
+                .iter()
+                .filter(|e| !e.is_empty())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("_");
+        }
+
+        let words: Vec<_> = parts.iter().flat_map(|e| words(e)).collect();
+        match self {
+            Self::AsIs | Self::SnakeCase => words.join("_").to_lowercase(),
+            Self::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            Self::CamelCase | Self::PascalCase => words
+                .iter()
+                .enumerate()
+                .map(|(idx, w)| {
+                    let w = w.to_lowercase();
+                    if idx == 0 && *self == Self::CamelCase {
+                        return w;
+                    }
+                    let mut chars = w.chars();
+                    chars
+                        .next()
+                        .map(|c| c.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Splits a name into words at non-alphanumeric characters and at changes of case
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (idx, c) in chars.iter().copied().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = idx.checked_sub(1).map(|e| chars[e]);
+        let next = chars.get(idx + 1);
+        let is_boundary = c.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_numeric()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if is_boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_conventions_split_words() {
+        let n = NamingConvention::SnakeCase;
+        assert_eq!(n.name(&["OrderLine"]), "order_line");
+        assert_eq!(n.name(&["HTTPRequest", "id"]), "http_request_id");
+        assert_eq!(n.name(&["Order line", "id"]), "order_line_id");
+        assert_eq!(
+            NamingConvention::ScreamingSnakeCase.name(&["orderLine"]),
+            "ORDER_LINE"
+        );
+        assert_eq!(
+            NamingConvention::CamelCase.name(&["order_line", "id"]),
+            "orderLineId"
+        );
+        assert_eq!(
+            NamingConvention::PascalCase.name(&["order line"]),
+            "OrderLine"
+        );
+        assert_eq!(NamingConvention::AsIs.name(&["Order", "id"]), "Order_id");
+    }
+}
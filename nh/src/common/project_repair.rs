@@ -0,0 +1,608 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::EntityUuid;
+
+/// Entity source file, read without instantiating the entities it contains
+#[derive(Serialize, Deserialize)]
+struct EntityChunk {
+    depends_on: Vec<EntityUuid>,
+    main: toml::Table,
+    other: Vec<toml::Table>,
+}
+
+impl EntityChunk {
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let chunk: Self = toml::from_str(str::from_utf8(bytes).map_err(|e| e.to_string())?)
+            .map_err(|e| e.message().to_owned())?;
+        for t in std::iter::once(&chunk.main).chain(chunk.other.iter()) {
+            if table_uuid(t).is_none() {
+                return Err("entity without uuid".to_owned());
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+fn table_uuid(t: &toml::Table) -> Option<&str> {
+    t.get("uuid").and_then(|e| e.as_str())
+}
+
+fn chunk_path(uuid: &EntityUuid) -> String {
+    match uuid {
+        EntityUuid::Model(u) => format!("models/{}.nhe", u.to_string()),
+        EntityUuid::View(u) => format!("views/{}.nhe", u.to_string()),
+        EntityUuid::Controller(u) => format!("controllers/{}.nhe", u.to_string()),
+    }
+}
+
+/// Folder of the chunk, entities of different kinds may share uuids
+fn chunk_folder(path: &str) -> &str {
+    path.split_once('/').map(|e| e.0).unwrap_or("")
+}
+
+#[derive(Debug)]
+pub struct ProjectProblem {
+    /// Source file path relative to the sources folder, or `project manifest`
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ProjectProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Raw contents of a project file and its sources
+pub struct ProjectFiles {
+    manifest: toml::Table,
+    /// Sources by their path relative to the sources folder
+    sources: BTreeMap<String, Vec<u8>>,
+    changed: BTreeSet<String>,
+    removed: BTreeSet<String>,
+}
+
+const MANIFEST_LOCATION: &str = "project manifest";
+const SOURCE_FOLDERS: [&str; 5] = ["models", "views", "controllers", "documents", "images"];
+
+impl ProjectFiles {
+    pub fn new(manifest: toml::Table, sources: BTreeMap<String, Vec<u8>>) -> Self {
+        Self {
+            manifest,
+            sources,
+            changed: BTreeSet::new(),
+            removed: BTreeSet::new(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let parse_manifest = |bytes: &[u8]| -> Result<toml::Table, String> {
+            toml::from_str(str::from_utf8(bytes).map_err(|e| e.to_string())?)
+                .map_err(|e| format!("unreadable project manifest: {}", e.message()))
+        };
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("nhp") => {
+                let manifest = parse_manifest(
+                    &std::fs::read(path).map_err(|e| format!("{:?}: {}", path, e))?,
+                )?;
+                let root = Self::sources_root(path, &manifest);
+                let mut sources = BTreeMap::new();
+                for folder in SOURCE_FOLDERS {
+                    let Ok(entries) = std::fs::read_dir(root.join(folder)) else {
+                        continue;
+                    };
+                    for e in entries.flatten() {
+                        let name = e.file_name().to_string_lossy().into_owned();
+                        if e.path().is_file() && !name.ends_with(".corrupt") {
+                            let bytes = std::fs::read(e.path())
+                                .map_err(|err| format!("{:?}: {}", e.path(), err))?;
+                            sources.insert(format!("{}/{}", folder, name), bytes);
+                        }
+                    }
+                }
+                Ok(Self::new(manifest, sources))
+            }
+            Some("nhpz") => {
+                let bytes = std::fs::read(path).map_err(|e| format!("{:?}: {}", path, e))?;
+                let mut zip =
+                    zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(|e| e.to_string())?;
+                let mut manifest = None;
+                let mut sources = BTreeMap::new();
+                for idx in 0..zip.len() {
+                    let mut file = zip.by_index(idx).map_err(|e| e.to_string())?;
+                    let name = file.name().to_owned();
+                    let mut bytes = Vec::new();
+                    if let Err(e) = file.read_to_end(&mut bytes) {
+                        // Damaged entries are left out, which reports them as missing
+                        eprintln!("{}: {}", name, e);
+                        continue;
+                    }
+                    if name == "project.nhp" {
+                        manifest = Some(parse_manifest(&bytes)?);
+                    } else if let Some(p) = name.strip_prefix("project/") {
+                        sources.insert(p.to_owned(), bytes);
+                    }
+                }
+                Ok(Self::new(
+                    manifest.ok_or_else(|| "project manifest not found".to_owned())?,
+                    sources,
+                ))
+            }
+            _ => Err(format!("Unsupported project file {:?}", path)),
+        }
+    }
+
+    fn sources_root(path: &Path, manifest: &toml::Table) -> PathBuf {
+        let folder = path.parent().unwrap_or(Path::new("."));
+        match manifest.get("sources_root").and_then(|e| e.as_str()) {
+            Some(s) => folder.join(s),
+            None => folder.join(path.file_stem().unwrap_or_default()),
+        }
+    }
+
+    /// Writes back the repaired project.
+    ///
+    /// Removed sources of a `.nhp` project are kept with a `.corrupt` suffix,
+    /// a `.nhpz` project is copied to a `.bak` file before being overwritten.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let manifest = toml::to_string(&self.manifest).map_err(|e| e.to_string())?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("nhp") => {
+                let root = Self::sources_root(path, &self.manifest);
+                for p in &self.removed {
+                    let from = root.join(p);
+                    let to = root.join(format!("{}.corrupt", p));
+                    std::fs::rename(&from, &to).map_err(|e| format!("{:?}: {}", from, e))?;
+                }
+                for p in &self.changed {
+                    let to = root.join(p);
+                    std::fs::write(&to, &self.sources[p])
+                        .map_err(|e| format!("{:?}: {}", to, e))?;
+                }
+                std::fs::write(path, manifest).map_err(|e| format!("{:?}: {}", path, e))
+            }
+            Some("nhpz") => {
+                let mut backup = path.as_os_str().to_owned();
+                backup.push(".bak");
+                std::fs::copy(path, &backup).map_err(|e| format!("{:?}: {}", backup, e))?;
+
+                let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+                let options = zip::write::SimpleFileOptions::default();
+                zip.start_file("project.nhp", options)
+                    .map_err(|e| e.to_string())?;
+                zip.write_all(manifest.as_bytes())
+                    .map_err(|e| e.to_string())?;
+                for (p, bytes) in &self.sources {
+                    zip.start_file(format!("project/{}", p), options)
+                        .map_err(|e| e.to_string())?;
+                    zip.write_all(bytes).map_err(|e| e.to_string())?;
+                }
+                let bytes = zip.finish().map_err(|e| e.to_string())?.into_inner();
+                std::fs::write(path, bytes).map_err(|e| format!("{:?}: {}", path, e))
+            }
+            _ => Err(format!("Unsupported project file {:?}", path)),
+        }
+    }
+
+    fn remove_source(&mut self, path: &str) {
+        self.sources.remove(path);
+        self.changed.remove(path);
+        self.removed.insert(path.to_owned());
+    }
+
+    fn replace_source(&mut self, path: &str, bytes: Vec<u8>) {
+        self.sources.insert(path.to_owned(), bytes);
+        self.changed.insert(path.to_owned());
+    }
+}
+
+/// Scans the project for unreadable sources, duplicate uuids and dangling references.
+///
+/// When fixing, unreadable sources and duplicate entities are dropped, and diagrams
+/// which cannot be loaded are removed from the project so that the rest of it opens.
+pub fn check_project(files: &mut ProjectFiles, fix: bool) -> Vec<ProjectProblem> {
+    let mut problems = Vec::new();
+    let mut problem = |location: &str, message: String| {
+        problems.push(ProjectProblem {
+            location: location.to_owned(),
+            message,
+        })
+    };
+
+    // Unreadable chunks
+    let mut chunks = BTreeMap::new();
+    let mut unreadable = Vec::new();
+    for (path, bytes) in &files.sources {
+        if !path.ends_with(".nhe") {
+            continue;
+        }
+        match EntityChunk::parse(bytes) {
+            Ok(chunk) => {
+                chunks.insert(path.clone(), chunk);
+            }
+            Err(e) => {
+                problem(path, format!("unreadable: {}", e));
+                unreadable.push(path.clone());
+            }
+        }
+    }
+
+    // Duplicate uuids, the first definition in path order is kept
+    let mut defined: HashMap<(String, String), String> = HashMap::new();
+    for (path, chunk) in &chunks {
+        let uuid = table_uuid(&chunk.main).unwrap().to_owned();
+        defined.insert((chunk_folder(path).to_owned(), uuid), path.clone());
+    }
+    let mut deduplicated = Vec::new();
+    for (path, chunk) in chunks.iter_mut() {
+        let folder = chunk_folder(path).to_owned();
+        let before = chunk.other.len();
+        chunk.other.retain(|t| {
+            let key = (folder.clone(), table_uuid(t).unwrap().to_owned());
+            match defined.get(&key) {
+                Some(first) if first != path => {
+                    problem(
+                        path,
+                        format!("duplicate uuid {}, already defined in {}", key.1, first),
+                    );
+                    false
+                }
+                _ => {
+                    defined.insert(key, path.clone());
+                    true
+                }
+            }
+        });
+        if chunk.other.len() != before {
+            deduplicated.push(path.clone());
+        }
+    }
+
+    // Dangling dependencies, which make the dependents impossible to load
+    let mut undepended = Vec::new();
+    for (path, chunk) in &chunks {
+        for d in &chunk.depends_on {
+            let dependency = chunk_path(d);
+            if !chunks.contains_key(&dependency) {
+                problem(
+                    path,
+                    format!("depends on missing or unreadable {}", dependency),
+                );
+                undepended.push((path.clone(), *d));
+            }
+        }
+    }
+    fn is_loadable(
+        path: &str,
+        chunks: &BTreeMap<String, EntityChunk>,
+        memo: &mut HashMap<String, bool>,
+    ) -> bool {
+        if let Some(r) = memo.get(path) {
+            return *r;
+        }
+        // Cycles are assumed to be loadable until proven otherwise
+        memo.insert(path.to_owned(), true);
+        let r = chunks.get(path).is_some_and(|c| {
+            c.depends_on
+                .iter()
+                .all(|d| is_loadable(&chunk_path(d), chunks, memo))
+        });
+        memo.insert(path.to_owned(), r);
+        r
+    }
+    let mut memo = HashMap::new();
+
+    // Controllers and the diagrams they provide
+    let mut removed_controllers = HashSet::new();
+    let mut provided_diagrams = HashSet::new();
+    if let Some(controllers) = files.manifest.get("controllers").and_then(|e| e.as_array()) {
+        for c in controllers {
+            let Some(uuid) = c.get("uuid").and_then(|e| e.as_str()) else {
+                continue;
+            };
+            let path = format!("controllers/{}.nhe", uuid);
+            if is_loadable(&path, &chunks, &mut memo) {
+                provided_diagrams.extend(chunks[&path].depends_on.iter().flat_map(|e| match e {
+                    EntityUuid::View(v) => Some(v.to_string()),
+                    _ => None,
+                }));
+            } else {
+                problem(
+                    MANIFEST_LOCATION,
+                    format!("controller {} cannot be loaded", uuid),
+                );
+                removed_controllers.insert(uuid.to_owned());
+            }
+        }
+    }
+
+    // Hierarchy nodes
+    let mut seen_nodes = HashSet::new();
+    let mut removed_diagrams = HashSet::new();
+    let mut removed_documents = HashSet::new();
+    fn walk(nodes: &[toml::Value], f: &mut dyn FnMut(&toml::Table)) {
+        for n in nodes.iter().flat_map(|e| e.as_table()) {
+            f(n);
+            if let Some(children) = n.get("hierarchy").and_then(|e| e.as_array()) {
+                walk(children, f);
+            }
+        }
+    }
+    if let Some(hierarchy) = files.manifest.get("hierarchy").and_then(|e| e.as_array()) {
+        walk(hierarchy, &mut |n| {
+            let Some(uuid) = table_uuid(n) else {
+                return;
+            };
+            if !seen_nodes.insert(uuid.to_owned()) {
+                problem(
+                    MANIFEST_LOCATION,
+                    format!("hierarchy contains {} more than once", uuid),
+                );
+                return;
+            }
+            match n.get("type").and_then(|e| e.as_str()) {
+                Some("Diagram") if !provided_diagrams.contains(uuid) => {
+                    problem(
+                        MANIFEST_LOCATION,
+                        format!(
+                            "diagram {} is not provided by any loadable controller",
+                            uuid
+                        ),
+                    );
+                    removed_diagrams.insert(uuid.to_owned());
+                }
+                Some("Document") => {
+                    let path = format!("documents/{}.nhd", uuid);
+                    match files.sources.get(&path).map(|e| str::from_utf8(e)) {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            problem(&path, format!("unreadable: {}", e));
+                            removed_documents.insert(uuid.to_owned());
+                        }
+                        None => {
+                            problem(MANIFEST_LOCATION, format!("document {} is missing", uuid));
+                            removed_documents.insert(uuid.to_owned());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        });
+    }
+    let is_removed_diagram = |e: &toml::Value, key: &str| {
+        e.get(key)
+            .and_then(|e| e.as_str())
+            .is_some_and(|e| removed_diagrams.contains(e) || !seen_nodes.contains(e))
+    };
+    for (list, keys) in [
+        ("diagram_variants", &["variant", "origin"][..]),
+        ("diagram_templates", &["template"][..]),
+        ("sub_diagrams", &["diagram"][..]),
+    ] {
+        for e in files
+            .manifest
+            .get(list)
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if keys.iter().any(|k| is_removed_diagram(e, k)) {
+                problem(
+                    MANIFEST_LOCATION,
+                    format!("{} entry refers to a missing diagram", list),
+                );
+            }
+        }
+    }
+    let is_missing_image = |e: &toml::Value| {
+        let uuid = e.get("uuid").and_then(|e| e.as_str()).unwrap_or("");
+        let extension = e.get("extension").and_then(|e| e.as_str()).unwrap_or("");
+        !files
+            .sources
+            .contains_key(&format!("images/{}.{}", uuid, extension))
+    };
+    for e in files
+        .manifest
+        .get("images")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+    {
+        if is_missing_image(e) {
+            problem(
+                MANIFEST_LOCATION,
+                format!(
+                    "image {} is missing",
+                    e.get("uuid").and_then(|e| e.as_str()).unwrap_or("")
+                ),
+            );
+        }
+    }
+
+    if !fix {
+        return problems;
+    }
+
+    let missing_images: Vec<bool> = files
+        .manifest
+        .get("images")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+        .map(is_missing_image)
+        .collect();
+    let m = &mut files.manifest;
+    if let Some(toml::Value::Array(controllers)) = m.get_mut("controllers") {
+        controllers.retain(|c| {
+            c.get("uuid")
+                .and_then(|e| e.as_str())
+                .is_none_or(|e| !removed_controllers.contains(e))
+        });
+    }
+    fn retain_nodes(nodes: &mut Vec<toml::Value>, f: &mut dyn FnMut(&toml::Table) -> bool) {
+        nodes.retain(|n| n.as_table().is_none_or(&mut *f));
+        for n in nodes.iter_mut() {
+            if let Some(toml::Value::Array(children)) = n.get_mut("hierarchy") {
+                retain_nodes(children, f);
+            }
+        }
+    }
+    if let Some(toml::Value::Array(hierarchy)) = m.get_mut("hierarchy") {
+        let mut kept = HashSet::new();
+        retain_nodes(hierarchy, &mut |n| {
+            let Some(uuid) = table_uuid(n) else {
+                return true;
+            };
+            kept.insert(uuid.to_owned())
+                && !removed_diagrams.contains(uuid)
+                && !removed_documents.contains(uuid)
+        });
+    }
+    for (list, keys) in [
+        ("diagram_variants", &["variant", "origin"][..]),
+        ("diagram_templates", &["template"][..]),
+        ("sub_diagrams", &["diagram"][..]),
+    ] {
+        if let Some(toml::Value::Array(entries)) = m.get_mut(list) {
+            entries.retain(|e| !keys.iter().any(|k| is_removed_diagram(e, k)));
+        }
+    }
+    if let Some(toml::Value::Array(images)) = m.get_mut("images") {
+        let mut missing = missing_images.into_iter();
+        images.retain(|_| !missing.next().unwrap_or(false));
+    }
+
+    for (path, d) in undepended {
+        if let Some(chunk) = chunks.get_mut(&path) {
+            chunk.depends_on.retain(|e| *e != d);
+        }
+        if !deduplicated.contains(&path) {
+            deduplicated.push(path);
+        }
+    }
+    for path in deduplicated {
+        if let Ok(s) = toml::to_string(&chunks[&path]) {
+            files.replace_source(&path, s.into_bytes());
+        }
+    }
+    for path in unreadable {
+        files.remove_source(&path);
+    }
+    for uuid in removed_documents {
+        let path = format!("documents/{}.nhd", uuid);
+        if files.sources.contains_key(&path) {
+            files.remove_source(&path);
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::uuid::{ControllerUuid, ModelUuid, ViewUuid};
+
+    fn chunk(uuid: &str, depends_on: Vec<EntityUuid>, other: &[&str]) -> Vec<u8> {
+        let table = |u: &str| toml::Table::from_iter([("uuid".to_owned(), u.into())]);
+        toml::to_string(&EntityChunk {
+            depends_on,
+            main: table(uuid),
+            other: other.iter().map(|e| table(e)).collect(),
+        })
+        .unwrap()
+        .into_bytes()
+    }
+
+    #[test]
+    fn broken_diagrams_are_removed() {
+        let (good_model, broken_model) = (ModelUuid::now_v7(), ModelUuid::now_v7());
+        let (good_view, broken_view) = (ViewUuid::now_v7(), ViewUuid::now_v7());
+        let (good_controller, broken_controller) =
+            (ControllerUuid::now_v7(), ControllerUuid::now_v7());
+        let shared = ViewUuid::now_v7().to_string();
+
+        let manifest: toml::Table = toml::from_str(&format!(
+            r#"
+            project_name = "Test"
+            sources_root = "test"
+            controllers = [
+                {{ uuid = "{gc}", controller_type = "umlclass" }},
+                {{ uuid = "{bc}", controller_type = "umlclass" }},
+            ]
+            hierarchy = [
+                {{ type = "Diagram", uuid = "{gv}" }},
+                {{ type = "Folder", uuid = "{f}", name = "F", hierarchy = [
+                    {{ type = "Diagram", uuid = "{bv}" }},
+                ] }},
+            ]
+            sub_diagrams = [{{ element = "{gm}", diagram = "{bv}" }}]
+            "#,
+            gc = good_controller.to_string(),
+            bc = broken_controller.to_string(),
+            gv = good_view.to_string(),
+            bv = broken_view.to_string(),
+            gm = good_model.to_string(),
+            f = ViewUuid::now_v7().to_string(),
+        ))
+        .unwrap();
+        let sources = BTreeMap::from_iter([
+            (
+                chunk_path(&good_model.into()),
+                chunk(&good_model.to_string(), vec![], &[]),
+            ),
+            (
+                chunk_path(&broken_model.into()),
+                b"main = { uuid = ".to_vec(),
+            ),
+            (
+                chunk_path(&good_view.into()),
+                chunk(&good_view.to_string(), vec![good_model.into()], &[&shared]),
+            ),
+            (
+                chunk_path(&broken_view.into()),
+                chunk(
+                    &broken_view.to_string(),
+                    vec![broken_model.into()],
+                    &[&shared],
+                ),
+            ),
+            (
+                chunk_path(&good_controller.into()),
+                chunk(&good_controller.to_string(), vec![good_view.into()], &[]),
+            ),
+            (
+                chunk_path(&broken_controller.into()),
+                chunk(
+                    &broken_controller.to_string(),
+                    vec![broken_view.into()],
+                    &[],
+                ),
+            ),
+        ]);
+        let mut files = ProjectFiles::new(manifest, sources);
+
+        let problems = check_project(&mut files, false);
+        let messages: Vec<_> = problems.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(problems.len(), 6, "{:#?}", problems);
+        assert!(messages[0].starts_with("unreadable"));
+        assert!(messages[1].starts_with("duplicate uuid"));
+        assert!(messages[2].starts_with("depends on missing"));
+        assert!(messages[3].starts_with("controller"));
+        assert!(messages[4].starts_with("diagram"));
+        assert!(messages[5].starts_with("sub_diagrams"));
+        assert!(files.changed.is_empty() && files.removed.is_empty());
+
+        assert_eq!(check_project(&mut files, true).len(), 6);
+        assert_eq!(files.removed.len(), 1);
+        assert!(check_project(&mut files, false).is_empty());
+        let hierarchy = toml::to_string(&files.manifest).unwrap();
+        assert!(hierarchy.contains(&good_view.to_string()));
+        assert!(!hierarchy.contains(&broken_view.to_string()));
+    }
+}
@@ -10,7 +10,13 @@ use crate::egui;
 use serde::{Deserialize, Serialize};
 
 use crate::DeserializeControllerF;
+use crate::common::abbreviations::AbbreviationDictionary;
 use crate::common::controller::{ColorBundle, HierarchyNode};
+use crate::common::external_links::ExternalLink;
+use crate::common::images::{ImageLibrary, ProjectImage};
+use crate::common::issue_links::IssueLink;
+use crate::common::symbols::{Symbol, SymbolLibrary, SymbolPrimitive};
+use crate::common::todos::TodoMarker;
 
 use super::entity::EntityUuid;
 use super::eref::ERef;
@@ -53,6 +59,9 @@ impl<'a> FSRawWriter<'a> {
         std::fs::DirBuilder::new()
             .recursive(true)
             .create(root.join(sources_folder).join("controllers"))?;
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .create(root.join(sources_folder).join("images"))?;
 
         Ok(Self {
             root,
@@ -218,6 +227,28 @@ struct GlobalColorDTO {
     color: egui::Color32,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct GlobalColorAliasDTO {
+    uuid: uuid::Uuid,
+    target: uuid::Uuid,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SymbolDTO {
+    uuid: uuid::Uuid,
+    name: String,
+    view_box: egui::Rect,
+    primitives: Vec<SymbolPrimitive>,
+}
+
+/// Image contents are stored in `images/{uuid}.{extension}`
+#[derive(Serialize, Deserialize, Debug)]
+struct ImageDTO {
+    uuid: uuid::Uuid,
+    name: String,
+    extension: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NHProjectSerialization {
     format_version: String,
@@ -227,6 +258,46 @@ pub struct NHProjectSerialization {
     hierarchy: Vec<NHProjectHierarchyNodeSerialization>,
     controllers: Vec<NHControllerInfo>,
     global_colors: Vec<GlobalColorDTO>,
+    #[serde(default)]
+    global_color_aliases: Vec<GlobalColorAliasDTO>,
+    #[serde(default)]
+    symbols: Vec<SymbolDTO>,
+    #[serde(default)]
+    images: Vec<ImageDTO>,
+    #[serde(default)]
+    diagram_variants: Vec<DiagramVariantDTO>,
+    #[serde(default)]
+    diagram_templates: Vec<DiagramTemplateDTO>,
+    #[serde(default)]
+    sub_diagrams: Vec<SubDiagramDTO>,
+    #[serde(default)]
+    abbreviations: AbbreviationDictionary,
+    #[serde(default)]
+    todos: Vec<TodoMarker>,
+    #[serde(default)]
+    external_links: Vec<ExternalLink>,
+    #[serde(default)]
+    issue_links: Vec<IssueLink>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct DiagramVariantDTO {
+    variant: ViewUuid,
+    origin: ViewUuid,
+}
+
+/// Diagram which new diagrams of the type are copied from
+#[derive(Serialize, Deserialize, Debug)]
+struct DiagramTemplateDTO {
+    controller_type: String,
+    template: ViewUuid,
+}
+
+/// Diagram double-clicking the element drills down into
+#[derive(Serialize, Deserialize, Debug)]
+struct SubDiagramDTO {
+    element: ModelUuid,
+    diagram: ViewUuid,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -242,17 +313,50 @@ pub struct NHEntitySerialization {
     other: Vec<toml::Value>,
 }
 
+/// Borrowed parts of the open project which get saved
+pub struct ProjectSaveState<'a> {
+    pub project_name: &'a str,
+    pub sources_root: &'a str,
+    pub new_diagram_no_counter: usize,
+    pub hierarchy: &'a [HierarchyNode],
+    pub global_colors: &'a ColorBundle,
+    pub symbols: &'a SymbolLibrary,
+    pub images: &'a ImageLibrary,
+    pub abbreviations: &'a AbbreviationDictionary,
+    pub todos: &'a [TodoMarker],
+    pub external_links: &'a [ExternalLink],
+    pub issue_links: &'a [IssueLink],
+    pub diagram_controllers: &'a HashMap<ViewUuid, ERef<dyn DiagramController>>,
+    pub diagram_variants: &'a HashMap<ViewUuid, ViewUuid>,
+    pub diagram_templates: &'a HashMap<String, ViewUuid>,
+    pub sub_diagrams: &'a HashMap<ModelUuid, ViewUuid>,
+    pub documents: &'a HashMap<ViewUuid, (String, String)>,
+}
+
 impl NHProjectSerialization {
     pub fn write_to<WA: FSWriteAbstraction>(
         wa: &mut WA,
-        project_name: &str,
-        sources_root: &str,
-        new_diagram_no_counter: usize,
-        hierarchy: &Vec<HierarchyNode>,
-        global_colors: &ColorBundle,
-        diagram_controllers: &HashMap<ViewUuid, ERef<dyn DiagramController>>,
-        documents: &HashMap<ViewUuid, (String, String)>,
+        state: &ProjectSaveState<'_>,
     ) -> Result<(), NHSerializeError> {
+        let ProjectSaveState {
+            project_name,
+            sources_root,
+            new_diagram_no_counter,
+            hierarchy,
+            global_colors,
+            symbols,
+            images,
+            abbreviations,
+            todos,
+            external_links,
+            issue_links,
+            diagram_controllers,
+            diagram_variants,
+            diagram_templates,
+            sub_diagrams,
+            documents,
+        } = *state;
+
         fn h(
             e: &HierarchyNode,
             d: &HashMap<ViewUuid, (String, String)>,
@@ -291,6 +395,22 @@ impl NHProjectSerialization {
             )?;
         }
 
+        // Images which are no longer used are left out
+        let referenced_images: HashSet<uuid::Uuid> = diagram_controllers
+            .iter()
+            .flat_map(|(k, c)| c.read().referenced_images(k))
+            .collect();
+        let mut images: Vec<_> = images
+            .iter()
+            .filter(|e| referenced_images.contains(e.0))
+            .map(|e| e.1)
+            .collect();
+        images.sort_by_key(|e| e.uuid);
+        for e in &images {
+            wa.write_source_file(&format!("images/{}.{}", e.uuid, e.extension), &e.bytes)?;
+        }
+
+        let global_colors_bundle = global_colors;
         let global_colors = global_colors
             .colors_order
             .iter()
@@ -321,6 +441,72 @@ impl NHProjectSerialization {
                 controllers
             },
             global_colors,
+            global_color_aliases: {
+                let mut aliases: Vec<_> = global_colors_bundle
+                    .aliases
+                    .iter()
+                    .map(|(k, v)| GlobalColorAliasDTO {
+                        uuid: *k,
+                        target: *v,
+                    })
+                    .collect();
+                aliases.sort_by_key(|e| e.uuid);
+                aliases
+            },
+            symbols: symbols
+                .iter()
+                .map(|(k, e)| SymbolDTO {
+                    uuid: *k,
+                    name: e.name.clone(),
+                    view_box: e.view_box,
+                    primitives: e.primitives.clone(),
+                })
+                .collect(),
+            images: images
+                .into_iter()
+                .map(|e| ImageDTO {
+                    uuid: e.uuid,
+                    name: e.name.clone(),
+                    extension: e.extension.to_owned(),
+                })
+                .collect(),
+            diagram_variants: {
+                let mut variants: Vec<_> = diagram_variants
+                    .iter()
+                    .map(|(k, v)| DiagramVariantDTO {
+                        variant: *k,
+                        origin: *v,
+                    })
+                    .collect();
+                variants.sort_by_key(|e| e.variant);
+                variants
+            },
+            diagram_templates: {
+                let mut templates: Vec<_> = diagram_templates
+                    .iter()
+                    .map(|(k, v)| DiagramTemplateDTO {
+                        controller_type: k.clone(),
+                        template: *v,
+                    })
+                    .collect();
+                templates.sort_by(|a, b| a.controller_type.cmp(&b.controller_type));
+                templates
+            },
+            sub_diagrams: {
+                let mut links: Vec<_> = sub_diagrams
+                    .iter()
+                    .map(|(k, v)| SubDiagramDTO {
+                        element: *k,
+                        diagram: *v,
+                    })
+                    .collect();
+                links.sort_by_key(|e| e.element);
+                links
+            },
+            abbreviations: abbreviations.clone(),
+            todos: todos.to_vec(),
+            external_links: external_links.to_vec(),
+            issue_links: issue_links.to_vec(),
         };
         wa.write_manifest_file(toml::to_string(&project_serialization)?.as_bytes())?;
 
@@ -343,7 +529,85 @@ impl NHProjectSerialization {
         ColorBundle {
             colors_order: o,
             colors: c,
+            aliases: self
+                .global_color_aliases
+                .iter()
+                .map(|e| (e.uuid, e.target))
+                .collect(),
+        }
+    }
+
+    pub fn symbols(&self) -> SymbolLibrary {
+        SymbolLibrary {
+            symbols_order: self.symbols.iter().map(|e| e.uuid).collect(),
+            symbols: self
+                .symbols
+                .iter()
+                .map(|e| {
+                    (
+                        e.uuid,
+                        Symbol {
+                            name: e.name.clone(),
+                            view_box: e.view_box,
+                            primitives: e.primitives.clone(),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads and decodes the images, the source folder has to be set already
+    pub fn images(
+        &self,
+        ra: &mut dyn FSReadAbstraction,
+    ) -> Result<ImageLibrary, NHDeserializeError> {
+        let mut library = ImageLibrary::default();
+        for e in &self.images {
+            let path = format!("images/{}.{}", e.uuid, e.extension);
+            let bytes = ra.read_source_file(&path)?;
+            let image = ProjectImage::decode(e.uuid, e.name.clone(), bytes)
+                .map_err(|err| format!("image '{}' could not be decoded: {}", path, err))?;
+            library.insert(image);
         }
+        Ok(library)
+    }
+
+    pub fn abbreviations(&self) -> AbbreviationDictionary {
+        self.abbreviations.clone()
+    }
+
+    pub fn todos(&self) -> Vec<TodoMarker> {
+        self.todos.clone()
+    }
+
+    pub fn external_links(&self) -> Vec<ExternalLink> {
+        self.external_links.clone()
+    }
+
+    pub fn issue_links(&self) -> Vec<IssueLink> {
+        self.issue_links.clone()
+    }
+
+    pub fn diagram_variants(&self) -> HashMap<ViewUuid, ViewUuid> {
+        self.diagram_variants
+            .iter()
+            .map(|e| (e.variant, e.origin))
+            .collect()
+    }
+
+    pub fn diagram_templates(&self) -> HashMap<String, ViewUuid> {
+        self.diagram_templates
+            .iter()
+            .map(|e| (e.controller_type.clone(), e.template))
+            .collect()
+    }
+
+    pub fn sub_diagrams(&self) -> HashMap<ModelUuid, ViewUuid> {
+        self.sub_diagrams
+            .iter()
+            .map(|e| (e.element, e.diagram))
+            .collect()
     }
 
     pub fn deserialize_all(
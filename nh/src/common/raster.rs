@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use eframe::{egui, epaint};
+use image::ImageEncoder;
+
+use super::canvas::{Highlight, UiCanvas};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageExportFormat {
+    Svg,
+    Png,
+    Jpeg,
+    DrawIo,
+}
+
+impl ImageExportFormat {
+    pub const ALL: [Self; 4] = [Self::Svg, Self::Png, Self::Jpeg, Self::DrawIo];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Svg => "SVG",
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::DrawIo => "draw.io",
+        }
+    }
+
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Svg => &["svg"],
+            Self::Png => &["png"],
+            Self::Jpeg => &["jpg", "jpeg"],
+            Self::DrawIo => &["drawio"],
+        }
+    }
+
+    pub fn is_raster(&self) -> bool {
+        matches!(self, Self::Png | Self::Jpeg)
+    }
+
+    /// Whether the format can store transparent pixels.
+    pub fn supports_transparency(&self) -> bool {
+        !matches!(self, Self::Jpeg)
+    }
+}
+
+/// Draws into an off-screen layer and rasterizes the result into an image of `size` pixels.
+///
+/// The canvas passed to `draw` covers the whole image with the given camera offset and scale.
+pub fn render_to_image(
+    ctx: &egui::Context,
+    size: [usize; 2],
+    camera_offset: egui::Pos2,
+    camera_scale: f32,
+    highlight: Highlight,
+    draw: impl FnOnce(&mut UiCanvas),
+) -> egui::ColorImage {
+    let layer = egui::LayerId::new(egui::Order::Background, egui::Id::new("raster export"));
+    let rect = egui::Rect::from_min_size(
+        egui::Pos2::ZERO,
+        egui::Vec2::new(size[0] as f32, size[1] as f32),
+    );
+
+    let mut canvas = UiCanvas::new(
+        egui::Painter::new(ctx.clone(), layer, rect),
+        rect,
+        camera_offset,
+        camera_scale,
+        None,
+        None,
+        highlight,
+        (false, false),
+    );
+    draw(&mut canvas);
+    let images = canvas.into_used_images();
+
+    let shapes: Vec<_> = ctx.graphics_mut(|g| {
+        std::mem::take(g.entry(layer))
+            .all_entries()
+            .cloned()
+            .collect()
+    });
+    let primitives = ctx.tessellate(shapes, 1.0);
+    let font_image = ctx.fonts(|f| f.image());
+
+    rasterize(&primitives, size, &font_image, &images)
+}
+
+/// Rasterizes tessellated meshes on the CPU into a transparent image.
+///
+/// Meshes using the font texture are sampled from `font_image`, meshes using one of the `images`
+/// repeat that image outside of the [0, 1] range, any other texture is treated as white.
+pub fn rasterize(
+    primitives: &[epaint::ClippedPrimitive],
+    size: [usize; 2],
+    font_image: &egui::ColorImage,
+    images: &HashMap<egui::TextureId, Arc<egui::ColorImage>>,
+) -> egui::ColorImage {
+    let mut target = egui::ColorImage::filled(size, egui::Color32::TRANSPARENT);
+    let image_rect = egui::Rect::from_min_size(
+        egui::Pos2::ZERO,
+        egui::Vec2::new(size[0] as f32, size[1] as f32),
+    );
+
+    for p in primitives {
+        let epaint::Primitive::Mesh(mesh) = &p.primitive else {
+            continue;
+        };
+        let texture = if mesh.texture_id == egui::TextureId::default() {
+            Some((font_image, false))
+        } else {
+            images.get(&mesh.texture_id).map(|e| (&**e, true))
+        };
+        let clip = p.clip_rect.intersect(image_rect);
+        if !clip.is_positive() {
+            continue;
+        }
+
+        for t in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| &mesh.vertices[i as usize]);
+            rasterize_triangle(&mut target, clip, texture, a, b, c);
+        }
+    }
+
+    target
+}
+
+fn rasterize_triangle(
+    target: &mut egui::ColorImage,
+    clip: egui::Rect,
+    texture: Option<(&egui::ColorImage, /*repeat:*/ bool)>,
+    a: &epaint::Vertex,
+    b: &epaint::Vertex,
+    c: &epaint::Vertex,
+) {
+    fn edge(a: egui::Pos2, b: egui::Pos2, p: egui::Pos2) -> f32 {
+        (p.x - a.x) * (b.y - a.y) - (p.y - a.y) * (b.x - a.x)
+    }
+    // Pixels exactly on an edge shared by two triangles belong to only one of them
+    fn owns_edge(a: egui::Pos2, b: egui::Pos2) -> bool {
+        b.y > a.y || (b.y == a.y && b.x < a.x)
+    }
+
+    let (b, c) = match edge(a.pos, b.pos, c.pos) {
+        area if area > 0.0 => (b, c),
+        area if area < 0.0 => (c, b),
+        _ => return,
+    };
+    let area = edge(a.pos, b.pos, c.pos);
+    let owned = [
+        owns_edge(b.pos, c.pos),
+        owns_edge(c.pos, a.pos),
+        owns_edge(a.pos, b.pos),
+    ];
+
+    let bounds = egui::Rect::from_points(&[a.pos, b.pos, c.pos]).intersect(clip);
+    if !bounds.is_positive() {
+        return;
+    }
+    let (x0, x1) = (bounds.min.x.floor() as usize, bounds.max.x.ceil() as usize);
+    let (y0, y1) = (bounds.min.y.floor() as usize, bounds.max.y.ceil() as usize);
+    let width = target.size[0];
+
+    for y in y0..y1.min(target.size[1]) {
+        for x in x0..x1.min(width) {
+            let p = egui::Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            if !clip.contains(p) {
+                continue;
+            }
+            let w = [
+                edge(b.pos, c.pos, p),
+                edge(c.pos, a.pos, p),
+                edge(a.pos, b.pos, p),
+            ];
+            if w.iter()
+                .zip(owned)
+                .any(|(w, owned)| *w < 0.0 || (*w == 0.0 && !owned))
+            {
+                continue;
+            }
+            let w = w.map(|e| e / area);
+
+            let mut color = [0.0f32; 4];
+            for (i, channel) in color.iter_mut().enumerate() {
+                *channel =
+                    w[0] * a.color[i] as f32 + w[1] * b.color[i] as f32 + w[2] * c.color[i] as f32;
+            }
+            if let Some((texture, repeat)) = texture {
+                let mut uv = egui::Pos2::new(
+                    w[0] * a.uv.x + w[1] * b.uv.x + w[2] * c.uv.x,
+                    w[0] * a.uv.y + w[1] * b.uv.y + w[2] * c.uv.y,
+                );
+                if repeat {
+                    uv = egui::Pos2::new(uv.x.rem_euclid(1.0), uv.y.rem_euclid(1.0));
+                }
+                let texel = sample(texture, uv);
+                for (channel, t) in color.iter_mut().zip(texel) {
+                    *channel *= t / 255.0;
+                }
+            }
+
+            let dst = &mut target.pixels[y * width + x];
+            let inverse_alpha = 1.0 - color[3] / 255.0;
+            *dst = egui::Color32::from_rgba_premultiplied(
+                blend(color[0], dst.r(), inverse_alpha),
+                blend(color[1], dst.g(), inverse_alpha),
+                blend(color[2], dst.b(), inverse_alpha),
+                blend(color[3], dst.a(), inverse_alpha),
+            );
+        }
+    }
+}
+
+fn blend(src: f32, dst: u8, inverse_alpha: f32) -> u8 {
+    (src + dst as f32 * inverse_alpha).round().clamp(0.0, 255.0) as u8
+}
+
+/// Bilinear sampling of a premultiplied texture
+fn sample(texture: &egui::ColorImage, uv: egui::Pos2) -> [f32; 4] {
+    let [w, h] = texture.size;
+    if w == 0 || h == 0 {
+        return [255.0; 4];
+    }
+    let x = (uv.x * w as f32 - 0.5).clamp(0.0, (w - 1) as f32);
+    let y = (uv.y * h as f32 - 0.5).clamp(0.0, (h - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let px = |x: usize, y: usize| texture.pixels[y * w + x];
+    let (p00, p10, p01, p11) = (px(x0, y0), px(x1, y0), px(x0, y1), px(x1, y1));
+    std::array::from_fn(|i| {
+        let top = p00[i] as f32 * (1.0 - fx) + p10[i] as f32 * fx;
+        let bottom = p01[i] as f32 * (1.0 - fx) + p11[i] as f32 * fx;
+        top * (1.0 - fy) + bottom * fy
+    })
+}
+
+/// Encodes the image, compositing it over `background` for formats without transparency.
+pub fn encode(
+    image: &egui::ColorImage,
+    format: ImageExportFormat,
+    background: egui::Color32,
+) -> Result<Vec<u8>, String> {
+    let [w, h] = image.size;
+    let mut buffer = Vec::new();
+
+    match format {
+        ImageExportFormat::Svg | ImageExportFormat::DrawIo => {
+            return Err(format!("{} is not a raster format", format.name()));
+        }
+        ImageExportFormat::Png => {
+            let data: Vec<u8> = image
+                .pixels
+                .iter()
+                .flat_map(|e| e.to_srgba_unmultiplied())
+                .collect();
+            image::codecs::png::PngEncoder::new(&mut buffer)
+                .write_image(&data, w as u32, h as u32, image::ExtendedColorType::Rgba8)
+                .map_err(|e| e.to_string())?;
+        }
+        ImageExportFormat::Jpeg => {
+            let data: Vec<u8> = image
+                .pixels
+                .iter()
+                .flat_map(|e| {
+                    let c = background.blend(*e);
+                    [c.r(), c.g(), c.b()]
+                })
+                .collect();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, 90)
+                .write_image(&data, w as u32, h as u32, image::ExtendedColorType::Rgb8)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_triangles_cover_a_square_once() {
+        let mut mesh = epaint::Mesh::default();
+        mesh.add_colored_rect(
+            egui::Rect::from_min_max(egui::Pos2::new(1.0, 1.0), egui::Pos2::new(3.0, 3.0)),
+            egui::Color32::from_black_alpha(128),
+        );
+        let primitive = epaint::ClippedPrimitive {
+            clip_rect: egui::Rect::EVERYTHING,
+            primitive: epaint::Primitive::Mesh(mesh),
+        };
+        let font_image = egui::ColorImage::filled([1, 1], egui::Color32::WHITE);
+
+        let image = rasterize(&[primitive], [4, 4], &font_image, &HashMap::new());
+        for y in 0..4 {
+            for x in 0..4 {
+                let expected = if (1..3).contains(&x) && (1..3).contains(&y) {
+                    128
+                } else {
+                    0
+                };
+                assert_eq!(image.pixels[y * 4 + x].a(), expected, "pixel {x},{y}");
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::document_import::heading_level;
+
+/// Markdown documentation assembled section by section
+#[derive(Default)]
+pub struct MarkdownReport {
+    out: String,
+}
+
+impl MarkdownReport {
+    /// Headings deeper than Markdown supports are kept at the lowest level
+    pub fn heading(&mut self, level: usize, text: &str) {
+        let level = level.clamp(1, 6);
+        self.out.push_str(&"#".repeat(level));
+        self.out.push(' ');
+        self.out
+            .push_str(text.lines().next().unwrap_or_default().trim());
+        self.out.push_str("\n\n");
+    }
+
+    /// Embeds the image as inline HTML, blank lines would end the HTML block
+    pub fn svg(&mut self, svg: &str) {
+        let svg = match svg.find("<svg") {
+            Some(start) => &svg[start..],
+            None => svg,
+        };
+        self.out.push_str("<div>\n");
+        for line in svg.lines().filter(|e| !e.trim().is_empty()) {
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+        self.out.push_str("</div>\n\n");
+    }
+
+    pub fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+        let cell = |e: &str| e.replace('|', "\\|").replace('\n', "<br>");
+        let row = |cells: &mut dyn Iterator<Item = String>| {
+            format!("| {} |\n", cells.collect::<Vec<_>>().join(" | "))
+        };
+        self.out
+            .push_str(&row(&mut headers.iter().map(|e| cell(e))));
+        self.out
+            .push_str(&row(&mut headers.iter().map(|_| "---".to_owned())));
+        for r in rows {
+            self.out.push_str(&row(&mut r.iter().map(|e| cell(e))));
+        }
+        self.out.push('\n');
+    }
+
+    /// Adds Markdown text, its headings are moved below the given level
+    pub fn text(&mut self, content: &str, level: usize) {
+        let mut in_fence = false;
+        for line in content.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            } else if !in_fence && let Some(l) = heading_level(line) {
+                self.heading(level + l, &line[l..]);
+                continue;
+            }
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+        self.out.push('\n');
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_are_assembled() {
+        let mut report = MarkdownReport::default();
+        report.heading(1, "Project");
+        report.text("# Intro\nText\n```\n# code\n```", 1);
+        report.table(
+            &["Name", "Comment"],
+            &[vec!["A|B".to_owned(), "line\nbreak".to_owned()]],
+        );
+        report.svg("<?xml version=\"1.0\"?>\n<svg>\n\n<rect/>\n</svg>\n");
+        assert_eq!(
+            report.finish(),
+            "# Project\n\n## Intro\n\nText\n```\n# code\n```\n\n\
+             | Name | Comment |\n| --- | --- |\n| A\\|B | line<br>break |\n\n\
+             <div>\n<svg>\n<rect/>\n</svg>\n</div>\n\n"
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use eframe::egui;
+
+use super::canvas::{Highlight, NHCanvas, Stroke};
+
+/// Subset of Markdown used in comments: bold text, links and (nested) lists.
+/// The raw text is what gets stored, this only affects how it is shown.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownLine {
+    pub indent: usize,
+    /// Bullet or number of a list item
+    pub marker: Option<String>,
+    pub spans: Vec<MarkdownSpan>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkdownSpan {
+    pub text: String,
+    pub bold: bool,
+    pub link: Option<String>,
+}
+
+const LINK_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 80, 200);
+
+pub fn parse_markdown(text: &str) -> Vec<MarkdownLine> {
+    text.lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> MarkdownLine {
+    let trimmed = line.trim_start();
+    let list_item = if let Some(rest) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|e| trimmed.strip_prefix(e))
+    {
+        Some(("•".to_owned(), rest))
+    } else if let Some((number, rest)) = trimmed.split_once(". ")
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+    {
+        Some((format!("{}.", number), rest))
+    } else {
+        None
+    };
+
+    match list_item {
+        Some((marker, rest)) => MarkdownLine {
+            indent: (line.len() - trimmed.len()) / 2 + 1,
+            marker: Some(marker),
+            spans: parse_spans(rest),
+        },
+        None => MarkdownLine {
+            indent: 0,
+            marker: None,
+            spans: parse_spans(line),
+        },
+    }
+}
+
+fn parse_spans(text: &str) -> Vec<MarkdownSpan> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut bold = false;
+    let mut rest = text;
+
+    macro_rules! flush {
+        () => {
+            if !current.is_empty() {
+                spans.push(MarkdownSpan {
+                    text: std::mem::take(&mut current),
+                    bold,
+                    link: None,
+                });
+            }
+        };
+    }
+
+    while let Some(c) = rest.chars().next() {
+        // Unmatched markers are kept as they are
+        if let Some(after) = rest.strip_prefix("**")
+            && (bold || after.contains("**"))
+        {
+            flush!();
+            bold = !bold;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[')
+            && let Some((label, after)) = after.split_once("](")
+            && !label.contains(']')
+            && let Some((url, after)) = after.split_once(')')
+        {
+            flush!();
+            spans.push(MarkdownSpan {
+                text: label.to_owned(),
+                bold,
+                link: Some(url.to_owned()),
+            });
+            rest = after;
+        } else {
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    flush!();
+
+    spans
+}
+
+/// Horizontal offset, text, and whether the piece is bold and a link
+type LaidOutPiece = (f32, String, bool, bool);
+
+/// Text laid out at the origin, positions are relative to the top left corner
+struct MarkdownLayout {
+    size: egui::Vec2,
+    lines: Vec<(f32, f32, Vec<LaidOutPiece>)>,
+}
+
+fn layout(canvas: &mut dyn NHCanvas, text: &str, font_size: f32) -> MarkdownLayout {
+    let mut measure =
+        |t: &str| canvas.measure_text(egui::Pos2::ZERO, egui::Align2::LEFT_TOP, t, font_size);
+    let line_height = measure("X").height();
+
+    let mut size = egui::Vec2::ZERO;
+    let mut lines = Vec::new();
+    for line in parse_markdown(text) {
+        let mut x = line.indent.saturating_sub(1) as f32 * font_size;
+        let mut items = Vec::new();
+        if let Some(marker) = line.marker {
+            let width = measure(&marker).width();
+            items.push((x, marker, false, false));
+            x += width + font_size / 3.0;
+        }
+        for span in line.spans {
+            let width = measure(&span.text).width();
+            items.push((x, span.text, span.bold, span.link.is_some()));
+            x += width;
+        }
+        size.x = size.x.max(x);
+        lines.push((x, line_height, items));
+        size.y += line_height;
+    }
+
+    MarkdownLayout { size, lines }
+}
+
+fn anchored_rect(position: egui::Pos2, anchor: egui::Align2, size: egui::Vec2) -> egui::Rect {
+    let offset = |align: egui::Align, length: f32| match align {
+        egui::Align::Min => 0.0,
+        egui::Align::Center => length / 2.0,
+        egui::Align::Max => length,
+    };
+    egui::Rect::from_min_size(
+        position - egui::Vec2::new(offset(anchor.x(), size.x), offset(anchor.y(), size.y)),
+        size,
+    )
+}
+
+/// Equivalent of [`NHCanvas::measure_text`] for Markdown text
+pub fn measure_markdown(
+    canvas: &mut dyn NHCanvas,
+    position: egui::Pos2,
+    anchor: egui::Align2,
+    text: &str,
+    font_size: f32,
+) -> egui::Rect {
+    anchored_rect(position, anchor, layout(canvas, text, font_size).size)
+}
+
+/// Equivalent of [`NHCanvas::draw_text`] for Markdown text. Lines are aligned horizontally
+/// according to the anchor, bold text is emulated by drawing it twice.
+pub fn draw_markdown(
+    canvas: &mut dyn NHCanvas,
+    position: egui::Pos2,
+    anchor: egui::Align2,
+    text: &str,
+    font_size: f32,
+    text_color: egui::Color32,
+) {
+    let layout = layout(canvas, text, font_size);
+    let rect = anchored_rect(position, anchor, layout.size);
+
+    let mut y = rect.min.y;
+    for (width, height, items) in layout.lines {
+        let line_x = match anchor.x() {
+            egui::Align::Min => rect.min.x,
+            egui::Align::Center => rect.center().x - width / 2.0,
+            egui::Align::Max => rect.max.x - width,
+        };
+        for (x, text, bold, link) in items {
+            let pos = egui::Pos2::new(line_x + x, y);
+            let color = if link { LINK_COLOR } else { text_color };
+            canvas.draw_text(pos, egui::Align2::LEFT_TOP, &text, font_size, color);
+            if bold {
+                canvas.draw_text(
+                    pos + egui::Vec2::new(font_size / 16.0, 0.0),
+                    egui::Align2::LEFT_TOP,
+                    &text,
+                    font_size,
+                    color,
+                );
+            }
+            if link {
+                let width = canvas
+                    .measure_text(pos, egui::Align2::LEFT_TOP, &text, font_size)
+                    .width();
+                canvas.draw_line(
+                    [
+                        egui::Pos2::new(pos.x, y + height),
+                        egui::Pos2::new(pos.x + width, y + height),
+                    ],
+                    Stroke::new_solid(1.0, LINK_COLOR),
+                    Highlight::NONE,
+                );
+            }
+        }
+        y += height;
+    }
+}
+
+/// Shows the rendered text in the properties panel, with clickable links
+pub fn show_markdown(ui: &mut egui::Ui, text: &str) {
+    for line in parse_markdown(text) {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            ui.add_space(line.indent as f32 * 12.0);
+            if let Some(marker) = line.marker {
+                ui.label(format!("{} ", marker));
+            }
+            if line.spans.is_empty() {
+                ui.label("");
+            }
+            for span in line.spans {
+                let mut text = egui::RichText::new(span.text);
+                if span.bold {
+                    text = text.strong();
+                }
+                match span.link {
+                    Some(url) => {
+                        ui.hyperlink_to(text, url);
+                    }
+                    None => {
+                        ui.label(text);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, bold: bool, link: Option<&str>) -> MarkdownSpan {
+        MarkdownSpan {
+            text: text.to_owned(),
+            bold,
+            link: link.map(|e| e.to_owned()),
+        }
+    }
+
+    #[test]
+    fn parse_inline() {
+        assert_eq!(
+            parse_spans("see **the [docs](https://example.com)** now"),
+            vec![
+                span("see ", false, None),
+                span("the ", true, None),
+                span("docs", true, Some("https://example.com")),
+                span(" now", false, None),
+            ]
+        );
+        assert_eq!(
+            parse_spans("a ** b [c] (d)"),
+            vec![span("a ** b [c] (d)", false, None)]
+        );
+    }
+
+    #[test]
+    fn parse_lists() {
+        let lines = parse_markdown("Steps:\n1. first\n  - nested\n**bold** line");
+        assert_eq!(lines[0].marker, None);
+        assert_eq!(lines[1].marker.as_deref(), Some("1."));
+        assert_eq!(lines[1].indent, 1);
+        assert_eq!(lines[2].marker.as_deref(), Some("•"));
+        assert_eq!(lines[2].indent, 2);
+        assert_eq!(lines[3].marker, None);
+        assert_eq!(lines[3].spans[0], span("bold", true, None));
+    }
+}
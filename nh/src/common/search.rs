@@ -12,6 +12,7 @@ pub struct Searcher {
     current_component: ModelUuid,
     current_found_matches: Vec<ModelUuid>,
     completed_components: Vec<(ModelUuid, Vec<ModelUuid>, Vec<ViewUuid>)>,
+    recorded_fields: Option<Vec<(ModelUuid, Vec<String>)>>,
 }
 
 impl Searcher {
@@ -21,6 +22,15 @@ impl Searcher {
             current_component: ModelUuid::nil(),
             current_found_matches: Vec::new(),
             completed_components: Vec::new(),
+            recorded_fields: None,
+        }
+    }
+
+    /// Creates a searcher which additionally records the fields of all visited elements
+    pub fn new_recording(expr: ast::Expr) -> Self {
+        Self {
+            recorded_fields: Some(Vec::new()),
+            ..Self::new(expr)
         }
     }
 
@@ -36,6 +46,9 @@ impl Searcher {
     }
 
     pub fn check_element(&mut self, uuid: ModelUuid, fields: &[&str]) {
+        if let Some(r) = self.recorded_fields.as_mut() {
+            r.push((uuid, fields.iter().map(|e| (*e).to_owned()).collect()));
+        }
         if check(&self.expr, fields) {
             self.current_found_matches.push(uuid);
         }
@@ -44,6 +57,10 @@ impl Searcher {
     pub fn results(self) -> Vec<(ModelUuid, Vec<ModelUuid>, Vec<ViewUuid>)> {
         self.completed_components
     }
+
+    pub fn recorded_fields(self) -> Vec<(ModelUuid, Vec<String>)> {
+        self.recorded_fields.unwrap_or_default()
+    }
 }
 
 fn check(expr: &ast::Expr, fields: &[&str]) -> bool {
@@ -0,0 +1,559 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::common::{
+    canvas::{self, Highlight, NHCanvas},
+    controller::ProjectCommand,
+};
+
+/// Number of line segments a single curve segment of a path is flattened into
+const CURVE_SEGMENTS: usize = 12;
+
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum SymbolPrimitive {
+    Line {
+        from: egui::Pos2,
+        to: egui::Pos2,
+    },
+    Rectangle {
+        rect: egui::Rect,
+    },
+    Ellipse {
+        center: egui::Pos2,
+        radius: egui::Vec2,
+    },
+    /// SVG path data, supports the M, L, H, V, C, Q and Z commands (and their relative forms)
+    Path {
+        data: String,
+    },
+}
+
+impl SymbolPrimitive {
+    fn kind_str(&self) -> &'static str {
+        match self {
+            SymbolPrimitive::Line { .. } => "Line",
+            SymbolPrimitive::Rectangle { .. } => "Rectangle",
+            SymbolPrimitive::Ellipse { .. } => "Ellipse",
+            SymbolPrimitive::Path { .. } => "Path",
+        }
+    }
+}
+
+/// Named vector drawing, defined in its own coordinate system given by `view_box`.
+#[derive(Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub view_box: egui::Rect,
+    pub primitives: Vec<SymbolPrimitive>,
+}
+
+impl Symbol {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            view_box: egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::splat(100.0)),
+            primitives: vec![],
+        }
+    }
+
+    /// Draws the symbol scaled uniformly to fit and centered in `rect`.
+    pub fn draw_in(
+        &self,
+        canvas: &mut dyn NHCanvas,
+        rect: egui::Rect,
+        stroke: canvas::Stroke,
+        highlight: Highlight,
+    ) {
+        let vb = self.view_box;
+        if vb.width() <= 0.0 || vb.height() <= 0.0 {
+            return;
+        }
+        let scale = (rect.width() / vb.width()).min(rect.height() / vb.height());
+        let offset = rect.center() - vb.center().to_vec2() * scale;
+        let t = |p: egui::Pos2| offset + p.to_vec2() * scale;
+
+        for p in &self.primitives {
+            match p {
+                SymbolPrimitive::Line { from, to } => {
+                    canvas.draw_line([t(*from), t(*to)], stroke, highlight);
+                }
+                SymbolPrimitive::Rectangle { rect } => {
+                    canvas.draw_rectangle(
+                        egui::Rect::from_two_pos(t(rect.min), t(rect.max)),
+                        egui::CornerRadius::ZERO,
+                        egui::Color32::TRANSPARENT,
+                        stroke,
+                        highlight,
+                    );
+                }
+                SymbolPrimitive::Ellipse { center, radius } => {
+                    canvas.draw_ellipse(
+                        t(*center),
+                        *radius * scale,
+                        egui::Color32::TRANSPARENT,
+                        stroke,
+                        highlight,
+                    );
+                }
+                SymbolPrimitive::Path { data } => {
+                    let Ok(polylines) = parse_path(data) else {
+                        continue;
+                    };
+                    for l in polylines {
+                        for w in l.windows(2) {
+                            canvas.draw_line([t(w[0]), t(w[1])], stroke, highlight);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Computes the smallest rectangle containing all primitives.
+    pub fn bounds(&self) -> egui::Rect {
+        let mut r = egui::Rect::NOTHING;
+        for p in &self.primitives {
+            match p {
+                SymbolPrimitive::Line { from, to } => {
+                    r.extend_with(*from);
+                    r.extend_with(*to);
+                }
+                SymbolPrimitive::Rectangle { rect } => r = r.union(*rect),
+                SymbolPrimitive::Ellipse { center, radius } => {
+                    r = r.union(egui::Rect::from_center_size(*center, *radius * 2.0));
+                }
+                SymbolPrimitive::Path { data } => {
+                    for p in parse_path(data).into_iter().flatten().flatten() {
+                        r.extend_with(p);
+                    }
+                }
+            }
+        }
+        r
+    }
+}
+
+/// Parses SVG path data into polylines, flattening curves.
+pub fn parse_path(data: &str) -> Result<Vec<Vec<egui::Pos2>>, String> {
+    let mut tokens = Vec::new();
+    let mut number = String::new();
+    let flush = |number: &mut String, tokens: &mut Vec<PathToken>| -> Result<(), String> {
+        if !number.is_empty() {
+            let v = number
+                .parse::<f32>()
+                .map_err(|_| format!("invalid number '{}'", number))?;
+            tokens.push(PathToken::Number(v));
+            number.clear();
+        }
+        Ok(())
+    };
+    for c in data.chars() {
+        match c {
+            '0'..='9' | '.' => {
+                if c == '.' && number.contains('.') && !number.ends_with(['e', 'E']) {
+                    flush(&mut number, &mut tokens)?;
+                }
+                number.push(c);
+            }
+            '-' | '+' => {
+                if !number.ends_with(['e', 'E']) {
+                    flush(&mut number, &mut tokens)?;
+                }
+                number.push(c);
+            }
+            'e' | 'E' if !number.is_empty() => number.push(c),
+            c if c.is_ascii_alphabetic() => {
+                flush(&mut number, &mut tokens)?;
+                tokens.push(PathToken::Command(c));
+            }
+            c if c.is_whitespace() || c == ',' => flush(&mut number, &mut tokens)?,
+            c => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+    flush(&mut number, &mut tokens)?;
+
+    let mut polylines: Vec<Vec<egui::Pos2>> = Vec::new();
+    let mut current = egui::Pos2::ZERO;
+    let mut start = egui::Pos2::ZERO;
+    let mut command = None;
+    let mut idx = 0;
+    while idx < tokens.len() {
+        if let PathToken::Command(c) = tokens[idx] {
+            command = Some(c);
+            idx += 1;
+            if c.eq_ignore_ascii_case(&'z') {
+                if let Some(l) = polylines.last_mut() {
+                    l.push(start);
+                }
+                current = start;
+                continue;
+            }
+        }
+        let Some(c) = command else {
+            return Err("path data must start with a command".to_owned());
+        };
+        let arity = match c.to_ascii_lowercase() {
+            'm' | 'l' => 2,
+            'h' | 'v' => 1,
+            'c' => 6,
+            'q' => 4,
+            other => return Err(format!("unsupported command '{}'", other)),
+        };
+        let mut args = [0.0f32; 6];
+        for a in args.iter_mut().take(arity) {
+            match tokens.get(idx) {
+                Some(PathToken::Number(v)) => *a = *v,
+                _ => return Err(format!("missing arguments for command '{}'", c)),
+            }
+            idx += 1;
+        }
+
+        let base = if c.is_ascii_lowercase() {
+            current.to_vec2()
+        } else {
+            egui::Vec2::ZERO
+        };
+        let point = |i: usize| egui::Pos2::new(args[i], args[i + 1]) + base;
+        match c.to_ascii_lowercase() {
+            'm' => {
+                current = point(0);
+                start = current;
+                polylines.push(vec![current]);
+                // Subsequent coordinate pairs are implicit line commands
+                command = Some(if c == 'm' { 'l' } else { 'L' });
+                continue;
+            }
+            'l' => current = point(0),
+            'h' => current.x = args[0] + base.x,
+            'v' => current.y = args[0] + base.y,
+            'c' => {
+                let (p0, p1, p2, p3) = (current, point(0), point(2), point(4));
+                let l = current_polyline(&mut polylines, p0);
+                for i in 1..CURVE_SEGMENTS {
+                    let t = i as f32 / CURVE_SEGMENTS as f32;
+                    let u = 1.0 - t;
+                    l.push(
+                        (p0.to_vec2() * u * u * u
+                            + p1.to_vec2() * 3.0 * u * u * t
+                            + p2.to_vec2() * 3.0 * u * t * t
+                            + p3.to_vec2() * t * t * t)
+                            .to_pos2(),
+                    );
+                }
+                current = p3;
+            }
+            'q' => {
+                let (p0, p1, p2) = (current, point(0), point(2));
+                let l = current_polyline(&mut polylines, p0);
+                for i in 1..CURVE_SEGMENTS {
+                    let t = i as f32 / CURVE_SEGMENTS as f32;
+                    let u = 1.0 - t;
+                    l.push(
+                        (p0.to_vec2() * u * u + p1.to_vec2() * 2.0 * u * t + p2.to_vec2() * t * t)
+                            .to_pos2(),
+                    );
+                }
+                current = p2;
+            }
+            _ => unreachable!(),
+        }
+        current_polyline(&mut polylines, current).push(current);
+    }
+
+    Ok(polylines)
+}
+
+enum PathToken {
+    Command(char),
+    Number(f32),
+}
+
+fn current_polyline(
+    polylines: &mut Vec<Vec<egui::Pos2>>,
+    start: egui::Pos2,
+) -> &mut Vec<egui::Pos2> {
+    if polylines.is_empty() {
+        polylines.push(vec![start]);
+    }
+    polylines.last_mut().unwrap()
+}
+
+/// Extracts path data from an SVG document, or accepts bare path data.
+pub fn import_svg(source: &str) -> Result<Vec<SymbolPrimitive>, String> {
+    let source = source.trim();
+    if !source.starts_with('<') {
+        parse_path(source)?;
+        return Ok(vec![SymbolPrimitive::Path {
+            data: source.to_owned(),
+        }]);
+    }
+
+    let mut primitives = Vec::new();
+    let mut rest = source;
+    while let Some(idx) = rest.find(" d=") {
+        rest = &rest[idx + 3..];
+        let Some(quote) = rest.chars().next().filter(|e| *e == '"' || *e == '\'') else {
+            continue;
+        };
+        let Some(end) = rest[1..].find(quote) else {
+            return Err("unterminated path data attribute".to_owned());
+        };
+        let data = &rest[1..end + 1];
+        parse_path(data)?;
+        primitives.push(SymbolPrimitive::Path {
+            data: data.to_owned(),
+        });
+        rest = &rest[end + 1..];
+    }
+    if primitives.is_empty() {
+        return Err("no paths found".to_owned());
+    }
+    Ok(primitives)
+}
+
+/// Symbols of the project, placed in diagrams of any type, and in wireframe diagrams
+/// also as the Symbol widget.
+#[derive(Clone, Default)]
+pub struct SymbolLibrary {
+    pub symbols_order: Vec<uuid::Uuid>,
+    pub symbols: HashMap<uuid::Uuid, Symbol>,
+}
+
+impl SymbolLibrary {
+    pub fn get(&self, uuid: &uuid::Uuid) -> Option<&Symbol> {
+        self.symbols.get(uuid)
+    }
+    /// Symbol placed by tools which do not name any existing one
+    pub fn first(&self) -> Option<uuid::Uuid> {
+        self.iter().next().map(|e| *e.0)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&uuid::Uuid, &Symbol)> {
+        self.symbols_order
+            .iter()
+            .flat_map(|k| self.symbols.get(k).map(|e| (k, e)))
+    }
+    pub fn clear(&mut self) {
+        self.symbols_order.clear();
+        self.symbols.clear();
+    }
+}
+
+#[derive(Default)]
+pub struct SymbolLibraryEditor {
+    new_symbol_name: String,
+    svg_import_buffer: String,
+    svg_import_error: Option<String>,
+}
+
+impl SymbolLibraryEditor {
+    const PREVIEW_SIZE: f32 = 48.0;
+
+    pub fn show(
+        &mut self,
+        library: &mut SymbolLibrary,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        let mut symbol_to_remove = None;
+        for (idx, id) in library.symbols_order.iter().enumerate() {
+            let Some(s) = library.symbols.get_mut(id) else {
+                continue;
+            };
+            ui.horizontal(|ui| {
+                let (rect, _) = ui.allocate_exact_size(
+                    egui::Vec2::splat(Self::PREVIEW_SIZE),
+                    egui::Sense::hover(),
+                );
+                let mut c = canvas::UiCanvas::new(
+                    ui.painter().with_clip_rect(rect),
+                    rect,
+                    egui::Pos2::ZERO,
+                    1.0,
+                    None,
+                    None,
+                    Highlight::NONE,
+                    (false, false),
+                );
+                c.clear(egui::Color32::WHITE);
+                s.draw_in(
+                    &mut c,
+                    egui::Rect::from_min_size(egui::Pos2::ZERO, rect.size()).shrink(4.0),
+                    canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                    Highlight::NONE,
+                );
+
+                ui.text_edit_singleline(&mut s.name);
+                if ui
+                    .button("Place")
+                    .on_hover_text("Place in the last focused diagram")
+                    .clicked()
+                {
+                    commands.push(ProjectCommand::PlaceSymbol(*id, None));
+                }
+                if ui.button("X").clicked() {
+                    symbol_to_remove = Some(idx);
+                }
+            });
+
+            egui::CollapsingHeader::new("Primitives")
+                .id_salt(id)
+                .show(ui, |ui| Self::show_primitives(s, ui));
+            ui.separator();
+        }
+        if let Some(idx) = symbol_to_remove {
+            let id = library.symbols_order.remove(idx);
+            library.symbols.remove(&id);
+        }
+
+        ui.horizontal(|ui| {
+            let r = ui.text_edit_singleline(&mut self.new_symbol_name);
+            if (r.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                || ui.button("Add new").clicked()
+            {
+                let new_uuid = uuid::Uuid::now_v7();
+                library.symbols_order.push(new_uuid);
+                library.symbols.insert(
+                    new_uuid,
+                    Symbol::new(std::mem::take(&mut self.new_symbol_name)),
+                );
+            }
+        });
+
+        ui.label("SVG document or path data:");
+        ui.add(egui::TextEdit::multiline(&mut self.svg_import_buffer).desired_rows(3));
+        if ui.button("Import SVG").clicked() {
+            match import_svg(&self.svg_import_buffer) {
+                Ok(primitives) => {
+                    let mut symbol = Symbol::new(if self.new_symbol_name.is_empty() {
+                        "Imported symbol".to_owned()
+                    } else {
+                        std::mem::take(&mut self.new_symbol_name)
+                    });
+                    symbol.primitives = primitives;
+                    symbol.view_box = symbol.bounds();
+                    let new_uuid = uuid::Uuid::now_v7();
+                    library.symbols_order.push(new_uuid);
+                    library.symbols.insert(new_uuid, symbol);
+                    self.svg_import_buffer.clear();
+                    self.svg_import_error = None;
+                }
+                Err(e) => self.svg_import_error = Some(e),
+            }
+        }
+        if let Some(e) = &self.svg_import_error {
+            ui.colored_label(egui::Color32::RED, e);
+        }
+    }
+
+    fn show_primitives(s: &mut Symbol, ui: &mut egui::Ui) {
+        fn pos_edit(ui: &mut egui::Ui, label: &str, p: &mut egui::Pos2) {
+            ui.label(label);
+            ui.add(egui::DragValue::new(&mut p.x).speed(1.0));
+            ui.add(egui::DragValue::new(&mut p.y).speed(1.0));
+        }
+
+        let mut primitive_to_remove = None;
+        for (idx, p) in s.primitives.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(p.kind_str());
+                match p {
+                    SymbolPrimitive::Line { from, to } => {
+                        pos_edit(ui, "from", from);
+                        pos_edit(ui, "to", to);
+                    }
+                    SymbolPrimitive::Rectangle { rect } => {
+                        pos_edit(ui, "min", &mut rect.min);
+                        pos_edit(ui, "max", &mut rect.max);
+                    }
+                    SymbolPrimitive::Ellipse { center, radius } => {
+                        pos_edit(ui, "center", center);
+                        ui.label("radius");
+                        ui.add(egui::DragValue::new(&mut radius.x).speed(1.0));
+                        ui.add(egui::DragValue::new(&mut radius.y).speed(1.0));
+                    }
+                    SymbolPrimitive::Path { data } => {
+                        ui.text_edit_singleline(data);
+                    }
+                }
+                if ui.button("X").clicked() {
+                    primitive_to_remove = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = primitive_to_remove {
+            s.primitives.remove(idx);
+        }
+
+        ui.horizontal(|ui| {
+            let c = s.view_box.center();
+            let r = s.view_box.size() / 4.0;
+            if ui.button("Add line").clicked() {
+                s.primitives.push(SymbolPrimitive::Line {
+                    from: c - r,
+                    to: c + r,
+                });
+            }
+            if ui.button("Add rectangle").clicked() {
+                s.primitives.push(SymbolPrimitive::Rectangle {
+                    rect: egui::Rect::from_center_size(c, r * 2.0),
+                });
+            }
+            if ui.button("Add ellipse").clicked() {
+                s.primitives.push(SymbolPrimitive::Ellipse {
+                    center: c,
+                    radius: r,
+                });
+            }
+            if ui.button("Add path").clicked() {
+                s.primitives.push(SymbolPrimitive::Path {
+                    data: format!("M {} {} L {} {}", c.x - r.x, c.y, c.x + r.x, c.y),
+                });
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("View box");
+            pos_edit(ui, "min", &mut s.view_box.min);
+            pos_edit(ui, "max", &mut s.view_box.max);
+            if ui.button("Fit").clicked() && !s.primitives.is_empty() {
+                s.view_box = s.bounds();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_commands_are_flattened() {
+        let p = parse_path("M10,10 h20 v20 L10 30z").unwrap();
+        assert_eq!(p.len(), 1);
+        assert_eq!(
+            p[0],
+            vec![
+                egui::Pos2::new(10.0, 10.0),
+                egui::Pos2::new(30.0, 10.0),
+                egui::Pos2::new(30.0, 30.0),
+                egui::Pos2::new(10.0, 30.0),
+                egui::Pos2::new(10.0, 10.0),
+            ]
+        );
+
+        let c = parse_path("m0 0 c0-10 20-10 20 0").unwrap();
+        assert_eq!(c[0].len(), CURVE_SEGMENTS + 1);
+        assert_eq!(*c[0].last().unwrap(), egui::Pos2::new(20.0, 0.0));
+
+        assert!(parse_path("10 10").is_err());
+        assert!(parse_path("M 10").is_err());
+    }
+
+    #[test]
+    fn svg_paths_are_extracted() {
+        let svg = r#"<svg viewBox="0 0 10 10"><path d="M0 0 L10 10"/><path fill="none" d='M10 0 L0 10'/></svg>"#;
+        let primitives = import_svg(svg).unwrap();
+        assert_eq!(primitives.len(), 2);
+        assert!(import_svg("<svg></svg>").is_err());
+    }
+}
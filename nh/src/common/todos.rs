@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use super::uuid::ModelUuid;
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default,
+)]
+pub enum TodoPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl TodoPriority {
+    pub const ALL: [TodoPriority; 3] =
+        [TodoPriority::Low, TodoPriority::Normal, TodoPriority::High];
+
+    pub fn message_name(&self) -> &'static str {
+        match self {
+            TodoPriority::Low => "nh-tab-tasks-priority-low",
+            TodoPriority::Normal => "nh-tab-tasks-priority-normal",
+            TodoPriority::High => "nh-tab-tasks-priority-high",
+        }
+    }
+}
+
+/// Note attached to an element of any diagram, listed in the Tasks tab
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TodoMarker {
+    pub element: ModelUuid,
+    pub text: String,
+    pub priority: TodoPriority,
+}
+
+impl TodoMarker {
+    pub fn new(element: ModelUuid) -> Self {
+        Self {
+            element,
+            text: String::new(),
+            priority: TodoPriority::default(),
+        }
+    }
+
+    /// Whether the marker has at least the given priority and its text or element label
+    /// contain the filter, ignoring case
+    pub fn matches(&self, element_label: &str, filter: &str, min_priority: TodoPriority) -> bool {
+        let filter = filter.to_lowercase();
+        self.priority >= min_priority
+            && (self.text.to_lowercase().contains(&filter)
+                || element_label.to_lowercase().contains(&filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_are_filtered_by_text_label_and_priority() {
+        let mut marker = TodoMarker::new(ModelUuid::nil());
+        marker.text = "Check multiplicities".to_owned();
+
+        assert!(marker.matches("Order", "", TodoPriority::Low));
+        assert!(marker.matches("Order", "MULTIPL", TodoPriority::Normal));
+        assert!(marker.matches("Order", "ord", TodoPriority::Normal));
+        assert!(!marker.matches("Order", "invoice", TodoPriority::Low));
+        assert!(!marker.matches("Order", "", TodoPriority::High));
+
+        marker.priority = TodoPriority::High;
+        assert!(marker.matches("Order", "", TodoPriority::High));
+    }
+}
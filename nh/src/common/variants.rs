@@ -0,0 +1,231 @@
+use std::collections::{HashMap, VecDeque};
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{DiagramController, GlobalDrawingContext, ProjectCommand},
+        eref::ERef,
+        search::{Searcher, ast},
+        uuid::{ModelUuid, ViewUuid},
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariantDiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug)]
+pub struct VariantDiffEntry {
+    pub kind: VariantDiffKind,
+    /// Element of the variant, or of the origin for removed elements
+    pub model: ModelUuid,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Compares the searchable fields of elements of an origin and its variant.
+///
+/// Elements are paired in order by their first field other than their uuid (typically a name),
+/// as duplicated elements never share uuids with their originals.
+pub fn diff_fields(
+    origin: &[(ModelUuid, Vec<String>)],
+    variant: &[(ModelUuid, Vec<String>)],
+) -> Vec<VariantDiffEntry> {
+    fn normalized(uuid: &ModelUuid, fields: &[String]) -> Vec<String> {
+        let uuid = uuid.to_string();
+        fields.iter().filter(|e| **e != uuid).cloned().collect()
+    }
+
+    let origin: Vec<_> = origin.iter().map(|(k, f)| (*k, normalized(k, f))).collect();
+    let mut unpaired: HashMap<Option<String>, VecDeque<usize>> = HashMap::new();
+    for (idx, (_, fields)) in origin.iter().enumerate() {
+        unpaired
+            .entry(fields.first().cloned())
+            .or_default()
+            .push_back(idx);
+    }
+
+    let mut entries = Vec::new();
+    let mut paired = vec![false; origin.len()];
+    for (k, fields) in variant {
+        let fields = normalized(k, fields);
+        match unpaired
+            .get_mut(&fields.first().cloned())
+            .and_then(|e| e.pop_front())
+        {
+            Some(idx) => {
+                paired[idx] = true;
+                if origin[idx].1 != fields {
+                    entries.push(VariantDiffEntry {
+                        kind: VariantDiffKind::Modified,
+                        model: *k,
+                        before: origin[idx].1.clone(),
+                        after: fields,
+                    });
+                }
+            }
+            None => entries.push(VariantDiffEntry {
+                kind: VariantDiffKind::Added,
+                model: *k,
+                before: Vec::new(),
+                after: fields,
+            }),
+        }
+    }
+    for (idx, (k, fields)) in origin.iter().enumerate() {
+        if !paired[idx] {
+            entries.push(VariantDiffEntry {
+                kind: VariantDiffKind::Removed,
+                model: *k,
+                before: fields.clone(),
+                after: Vec::new(),
+            });
+        }
+    }
+
+    entries
+}
+
+fn recorded_fields(controller: &ERef<dyn DiagramController>) -> Vec<(ModelUuid, Vec<String>)> {
+    let mut searcher = Searcher::new_recording(ast::Expr::Literal(String::new()));
+    controller.read().full_text_search(&mut searcher);
+    searcher.recorded_fields()
+}
+
+pub struct VariantDiffTab {
+    origin: (ViewUuid, ERef<dyn DiagramController>),
+    variant: (ViewUuid, ERef<dyn DiagramController>),
+    entries: Vec<VariantDiffEntry>,
+}
+
+impl VariantDiffTab {
+    pub fn new(
+        origin: (ViewUuid, ERef<dyn DiagramController>),
+        variant: (ViewUuid, ERef<dyn DiagramController>),
+    ) -> Self {
+        let mut s = Self {
+            origin,
+            variant,
+            entries: Vec::new(),
+        };
+        s.refresh();
+        s
+    }
+
+    fn refresh(&mut self) {
+        self.entries = diff_fields(
+            &recorded_fields(&self.origin.1),
+            &recorded_fields(&self.variant.1),
+        );
+    }
+}
+
+impl CustomTab for VariantDiffTab {
+    fn title(&self) -> String {
+        format!("Diff: {}", self.variant.1.read().view_name(&self.variant.0))
+    }
+
+    fn show(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "'{}' against its origin '{}'",
+                self.variant.1.read().view_name(&self.variant.0),
+                self.origin.1.read().view_name(&self.origin.0),
+            ));
+            if ui.button("Refresh").clicked() {
+                self.refresh();
+            }
+        });
+        let count = |kind| self.entries.iter().filter(|e| e.kind == kind).count();
+        ui.label(format!(
+            "{} added, {} removed, {} modified",
+            count(VariantDiffKind::Added),
+            count(VariantDiffKind::Removed),
+            count(VariantDiffKind::Modified),
+        ));
+        ui.separator();
+
+        if self.entries.is_empty() {
+            ui.label("No differences");
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("variant diff")
+                .striped(true)
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for e in &self.entries {
+                        let (text, color) = match e.kind {
+                            VariantDiffKind::Added => ("+", egui::Color32::from_rgb(0, 150, 0)),
+                            VariantDiffKind::Removed => ("−", egui::Color32::from_rgb(200, 0, 0)),
+                            VariantDiffKind::Modified => {
+                                ("~", egui::Color32::from_rgb(200, 130, 0))
+                            }
+                        };
+                        ui.colored_label(color, text);
+                        ui.label(&*gdc.model_labels.get(&e.model));
+                        ui.label(match e.kind {
+                            VariantDiffKind::Added => e.after.join(", "),
+                            VariantDiffKind::Removed => e.before.join(", "),
+                            VariantDiffKind::Modified => e
+                                .before
+                                .iter()
+                                .zip(e.after.iter())
+                                .filter(|(b, a)| b != a)
+                                .map(|(b, a)| format!("'{}' → '{}'", b, a))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_are_paired_by_name_ignoring_uuids() {
+        let (a, b, c, d) = (
+            ModelUuid::now_v7(),
+            ModelUuid::now_v7(),
+            ModelUuid::now_v7(),
+            ModelUuid::now_v7(),
+        );
+        let field = |s: &str| s.to_owned();
+        let origin = [
+            (a, vec![a.to_string(), field("Order"), field("")]),
+            (b, vec![b.to_string(), field("Customer"), field("")]),
+        ];
+        let variant = [
+            (c, vec![c.to_string(), field("Order"), field("changed")]),
+            (d, vec![d.to_string(), field("Invoice"), field("")]),
+        ];
+
+        let diff = diff_fields(&origin, &variant);
+        let kinds: Vec<_> = diff.iter().map(|e| (e.kind, e.model)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (VariantDiffKind::Modified, c),
+                (VariantDiffKind::Added, d),
+                (VariantDiffKind::Removed, b),
+            ]
+        );
+        assert!(diff_fields(&origin, &origin).is_empty());
+    }
+}
@@ -145,6 +145,22 @@ pub trait MulticonnectionAdapter<DomainT: Domain>:
     fn remove_target(&mut self, _uuid: &ModelUuid) -> Option<PositionNoT> {
         None
     }
+    /// Change routing the sources as a tree into the shared target,
+    /// if the connection supports it
+    fn tree_style_change(&self, _tree_style: bool) -> Option<DomainT::PropChangeT> {
+        None
+    }
+    fn as_tree_style_change(&self, _change: &DomainT::PropChangeT) -> Option<bool> {
+        None
+    }
+    /// Change drawing the paths as bezier curves, with the vertices as their control points,
+    /// if the connection supports it
+    fn curved_change(&self, _curved: bool) -> Option<DomainT::PropChangeT> {
+        None
+    }
+    fn as_curved_change(&self, _change: &DomainT::PropChangeT) -> Option<bool> {
+        None
+    }
 
     fn show_properties(
         &mut self,
@@ -190,15 +206,45 @@ pub const MULTICONNECTION_SOURCE_BUCKET: BucketNoT = 1;
 pub const MULTICONNECTION_TARGET_BUCKET: BucketNoT = 2;
 pub const MULTICONNECTION_VERTEX_BUCKET: BucketNoT = 3;
 
+/// Points of a bezier curve given by its control points, to be drawn as line segments
+pub fn bezier_points(control: &[egui::Pos2], segments: usize) -> Vec<egui::Pos2> {
+    if control.len() < 3 {
+        return control.to_vec();
+    }
+    (0..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let mut p = control.to_vec();
+            for k in (1..p.len()).rev() {
+                for j in 0..k {
+                    p[j] = p[j].lerp(p[j + 1], t);
+                }
+            }
+            p[0]
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub struct VertexInformation {
     after: ViewUuid,
     id: ViewUuid,
     position: egui::Pos2,
 }
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub struct FlipMulticonnection {}
 
+/// Label of a multiconnection, which can be dragged away from its automatic placement
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ConnectionLabel {
+    /// Name shown at the center point
+    Center,
+    /// Labels at the ending connected to the given view
+    Multiplicity(ViewUuid),
+    Role(ViewUuid),
+    Reading(ViewUuid),
+}
+
 #[derive(
     Clone, serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize,
 )]
@@ -246,8 +292,22 @@ pub struct MulticonnectionView<DomainT: Domain, AdapterT: MulticonnectionAdapter
     #[nh_context_serde(skip_and_default)]
     selected_vertices: HashSet<ViewUuid>,
     center_point: UFOption<(ViewUuid, egui::Pos2)>,
+    /// Sources without vertices bend orthogonally into a bar through the center point
+    #[nh_context_serde(default)]
+    tree_style: bool,
+    /// Paths are bezier curves going through their ending points and the center point only
+    #[nh_context_serde(default)]
+    curved: bool,
     #[nh_context_serde(skip_and_default)]
     point_to_origin: HashMap<ViewUuid, (bool, usize)>,
+
+    /// Offsets of dragged labels from their automatic placement, moved by their own uuid
+    #[nh_context_serde(default)]
+    label_offsets: Vec<(ConnectionLabel, ViewUuid, egui::Vec2)>,
+    #[nh_context_serde(skip_and_default)]
+    label_rects: HashMap<ConnectionLabel, egui::Rect>,
+    #[nh_context_serde(skip_and_default)]
+    dragged_label: Option<ViewUuid>,
 }
 
 impl<DomainT: Domain, AdapterT: MulticonnectionAdapter<DomainT>>
@@ -286,11 +346,51 @@ where
             selected_vertices: HashSet::new(),
 
             center_point: center_point.into(),
+            tree_style: false,
+            curved: false,
             point_to_origin,
+
+            label_offsets: Vec::new(),
+            label_rects: HashMap::new(),
+            dragged_label: None,
+        })
+    }
+
+    fn label_offset(&self, label: ConnectionLabel) -> egui::Vec2 {
+        self.label_offsets
+            .iter()
+            .find(|e| e.0 == label)
+            .map(|e| e.2)
+            .unwrap_or(egui::Vec2::ZERO)
+    }
+
+    /// Bend of a source routed as a tree, on the bar through the center point
+    /// perpendicular to the direction of the target
+    fn tree_elbow(
+        &self,
+        e: &Ending<DomainT::CommonElementViewT>,
+        central_point: egui::Pos2,
+    ) -> Option<egui::Pos2> {
+        if !self.tree_style || e.points.len() > 1 {
+            return None;
+        }
+        let target = self.targets.first()?;
+        let toward = target
+            .points
+            .get(1)
+            .map(|e| e.1)
+            .unwrap_or_else(|| target.element.min_shape().center());
+        let center = e.element.min_shape().center();
+        let d = toward - central_point;
+        Some(if d.y.abs() >= d.x.abs() {
+            egui::Pos2::new(center.x, central_point.y)
+        } else {
+            egui::Pos2::new(central_point.x, center.y)
         })
     }
 
     const VERTEX_RADIUS: f32 = 5.0;
+    const CURVE_SEGMENTS: usize = 24;
     fn all_vertices(&self) -> impl Iterator<Item = &(ViewUuid, egui::Pos2)> {
         self.center_point
             .as_ref()
@@ -307,11 +407,11 @@ where
     ) {
         let sources = self.sources.iter().map(|e| {
             let ad = arrow_data.get(&(false, *e.element.model_uuid())).unwrap();
-            (e, ad)
+            (e, ad, self.tree_elbow(e, central_point.1))
         });
         let destinations = self.targets.iter().map(|e| {
             let ad = arrow_data.get(&(true, *e.element.model_uuid())).unwrap();
-            (e, ad)
+            (e, ad, None)
         });
 
         fn a<'a>(
@@ -319,6 +419,7 @@ where
             central_point: (ViewUuid, egui::Pos2),
             points: &'a Vec<(ViewUuid, egui::Pos2)>,
             ad: &ArrowData,
+            elbow: Option<egui::Pos2>,
         ) -> (
             canvas::ArrowheadType,
             canvas::Stroke,
@@ -326,12 +427,16 @@ where
             impl Iterator<Item = (ViewUuid, egui::Pos2)> + 'a,
         ) {
             let focal_point = points.first().unwrap();
+            let elbow = elbow.map(|e| (ViewUuid::nil(), e));
             let path = std::iter::once((
                 ViewUuid::nil(),
-                ad.arrowhead_type
-                    .get_intersect(focal_point.1, points.get(1).unwrap_or(&central_point).1),
+                ad.arrowhead_type.get_intersect(
+                    focal_point.1,
+                    points.get(1).or(elbow.as_ref()).unwrap_or(&central_point).1,
+                ),
             ))
             .chain(points.iter().skip(1).copied())
+            .chain(elbow)
             .chain(std::iter::once(central_point));
             (
                 ad.arrowhead_type,
@@ -348,10 +453,27 @@ where
         let fg = self.adapter.foreground_color();
         let bg = self.adapter.background_color();
         for (ah, ls, fp, iter) in sources
-            .map(|e| a(fg, central_point, &e.0.points, e.1))
-            .chain(destinations.map(|e| a(fg, central_point, &e.0.points, e.1)))
+            .map(|e| a(fg, central_point, &e.0.points, e.1, e.2))
+            .chain(destinations.map(|e| a(fg, central_point, &e.0.points, e.1, e.2)))
         {
-            let mut iter_peekable = iter.peekable();
+            let path: Vec<_> = iter.collect();
+            if self.curved {
+                let control: Vec<_> = path.iter().map(|e| e.1).collect();
+                for w in bezier_points(&control, Self::CURVE_SEGMENTS).windows(2) {
+                    canvas.draw_connection_line([w[0], w[1]], ls, self.highlight);
+                }
+                if self.highlight.selected && control.len() > 2 {
+                    for w in control.windows(2) {
+                        canvas.draw_line(
+                            [w[0], w[1]],
+                            canvas::Stroke::new_dotted(1.0, egui::Color32::GRAY),
+                            Highlight::NONE,
+                        );
+                    }
+                }
+            }
+
+            let mut iter_peekable = path.into_iter().peekable();
             let mut first = true;
 
             while let Some(u) = iter_peekable.next() {
@@ -366,7 +488,9 @@ where
                     ah.draw_in(canvas, fp, v, (fg, bg), self.highlight);
                 }
 
-                canvas.draw_line([u, v], ls, self.highlight);
+                if !self.curved {
+                    canvas.draw_connection_line([u, v], ls, self.highlight);
+                }
 
                 // Draw drag handle in the middle of a segment
                 if !central_point.0.is_nil() {
@@ -591,6 +715,46 @@ where
             1,
         );
 
+        if let Some(change) = self.adapter.tree_style_change(!self.tree_style) {
+            let mut tree_style = self.tree_style;
+            if ui
+                .checkbox(&mut tree_style, "Tree style (shared target)")
+                .changed()
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    std::iter::once(*self.uuid).collect(),
+                    change,
+                ));
+            }
+        }
+        if let Some(change) = self.adapter.curved_change(!self.curved) {
+            let mut curved = self.curved;
+            if ui.checkbox(&mut curved, "Curved (bezier)").changed() {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    std::iter::once(*self.uuid).collect(),
+                    change,
+                ));
+            }
+        }
+        if ui
+            .add_enabled(
+                self.label_offsets.iter().any(|e| e.2 != egui::Vec2::ZERO),
+                egui::Button::new("Reset label positions"),
+            )
+            .clicked()
+        {
+            for e in self
+                .label_offsets
+                .iter()
+                .filter(|e| e.2 != egui::Vec2::ZERO)
+            {
+                commands.push(InsensitiveCommand::MovePositional(
+                    std::iter::once(e.1).collect(),
+                    -e.2,
+                ));
+            }
+        }
+
         self.adapter.show_properties(q, ui, commands)
     }
 
@@ -602,6 +766,7 @@ where
         canvas: &mut dyn canvas::NHCanvas,
         tool: &Option<(egui::Pos2, &DomainT::ToolT)>,
     ) -> TargettingStatus {
+        self.label_rects.clear();
         let center_point = if let UFOption::Some(center_point) = &self.center_point {
             center_point.1
         } else {
@@ -611,9 +776,21 @@ where
                 .nice_midpoint(&self.targets[0].element.min_shape())
         };
 
-        for e in self.sources.iter_mut().chain(self.targets.iter_mut()) {
+        let elbows: Vec<_> = self
+            .sources
+            .iter()
+            .map(|e| self.tree_elbow(e, center_point))
+            .collect();
+        for (e, elbow) in self
+            .sources
+            .iter_mut()
+            .zip(elbows)
+            .chain(self.targets.iter_mut().map(|e| (e, None)))
+        {
             let shape = e.element.min_shape();
-            let next_point = e.points.iter().nth(1).map(|p| p.1).unwrap_or(center_point);
+            let next_point = elbow
+                .or(e.points.iter().nth(1).map(|p| p.1))
+                .unwrap_or(center_point);
             let intersect = shape
                 .orthogonal_intersect(next_point)
                 .unwrap_or_else(|| shape.center_intersect(next_point));
@@ -643,29 +820,42 @@ where
             Err(label) => {
                 // TODO: Blur the line around center to make the mid label more readable?
                 //       Alternatively labels could have an angle to fit it better.
+                let pos = central_point.1 + self.label_offset(ConnectionLabel::Center);
                 canvas.draw_text(
-                    central_point.1,
+                    pos,
                     egui::Align2::CENTER_CENTER,
                     &label,
                     canvas::CLASS_MIDDLE_FONT_SIZE,
                     egui::Color32::BLACK,
                 );
+                self.label_rects.insert(
+                    ConnectionLabel::Center,
+                    canvas.measure_text(
+                        pos,
+                        egui::Align2::CENTER_CENTER,
+                        &label,
+                        canvas::CLASS_MIDDLE_FONT_SIZE,
+                    ),
+                );
             }
         }
 
+        /// Returns areas of the multiplicity, role and reading labels
         fn draw_arrow_data(
             canvas: &mut dyn canvas::NHCanvas,
             shape: canvas::NHShape,
             shape_intersect: egui::Pos2,
             next_point: egui::Pos2,
             data: &ArrowData,
-        ) {
+            offsets: [egui::Vec2; 3],
+        ) -> [Option<egui::Rect>; 3] {
             fn draw_small_labels(
                 canvas: &mut dyn canvas::NHCanvas,
                 bounds: canvas::NHShape,
                 pos: egui::Pos2,
                 labels: [Option<&str>; 2],
-            ) {
+                offsets: [egui::Vec2; 2],
+            ) -> [Option<egui::Rect>; 2] {
                 let mut m = |e| {
                     canvas
                         .measure_text(
@@ -680,23 +870,23 @@ where
                     labels[0].map(&mut m).unwrap_or(egui::Vec2::ZERO),
                     labels[1].map(&mut m).unwrap_or(egui::Vec2::ZERO),
                 ];
-                for p in bounds
-                    .place_labels(pos, sizes, 10.0)
-                    .iter()
-                    .zip(labels.iter())
-                {
-                    if let Some(l) = p.1 {
+                let mut rects = [None; 2];
+                for (idx, p) in bounds.place_labels(pos, sizes, 10.0).iter().enumerate() {
+                    if let Some(l) = labels[idx] {
+                        let p = *p + offsets[idx];
                         canvas.draw_text(
-                            *p.0,
+                            p,
                             egui::Align2::CENTER_CENTER,
                             l,
                             canvas::CLASS_TOP_FONT_SIZE,
                             egui::Color32::BLACK,
                         );
+                        rects[idx] = Some(egui::Rect::from_center_size(p, sizes[idx]));
                     }
                 }
+                rects
             }
-            draw_small_labels(
+            let [multiplicity, role] = draw_small_labels(
                 canvas,
                 shape,
                 shape_intersect,
@@ -704,6 +894,7 @@ where
                     data.multiplicity.as_ref().map(|e| e.as_str()),
                     data.role.as_ref().map(|e| e.as_str()),
                 ],
+                [offsets[0], offsets[1]],
             );
 
             fn draw_reading(
@@ -711,7 +902,8 @@ where
                 intersect: egui::Pos2,
                 next: egui::Pos2,
                 reading_text: &str,
-            ) {
+                offset: egui::Vec2,
+            ) -> egui::Rect {
                 const PADDING: f32 = 10.0;
                 const TRIANGLE_LONGEST_SIDE: f32 = 10.0;
                 const TRIANGLE_PERPENDICULAR: f32 = 7.0;
@@ -726,11 +918,11 @@ where
                 let mid = (intersect + next.to_vec2()) / 2.0;
                 let (dx, dy) = (next.x - intersect.x, next.y - intersect.y);
                 let angle = f32::atan2(dx, dy);
-                let pos = mid
-                    + egui::Vec2::new(
+                let pos =
+                    mid + egui::Vec2::new(
                         f32::cos(angle) * (size.x / 2.0 + PADDING),
                         -f32::sin(angle) * (size.y / 2.0 + PADDING),
-                    );
+                    ) + offset;
                 canvas.draw_text(
                     pos,
                     egui::Align2::CENTER_CENTER,
@@ -786,11 +978,14 @@ where
                     canvas::Stroke::NONE,
                     canvas::Highlight::NONE,
                 );
+                egui::Rect::from_center_size(pos, size)
             }
-            if let Some(reading) = &data.reading {
-                draw_reading(canvas, shape_intersect, next_point, reading);
-            }
+            let reading = data.reading.as_ref().map(|reading| {
+                draw_reading(canvas, shape_intersect, next_point, reading, offsets[2])
+            });
+            [multiplicity, role, reading]
         }
+        let mut label_rects = Vec::new();
         for (target, e) in self
             .sources
             .iter()
@@ -800,7 +995,13 @@ where
             let Some(data) = ad.get(&(target, *e.element.model_uuid())) else {
                 continue;
             };
-            draw_arrow_data(
+            let uuid = *e.element.uuid();
+            let labels = [
+                ConnectionLabel::Multiplicity(uuid),
+                ConnectionLabel::Role(uuid),
+                ConnectionLabel::Reading(uuid),
+            ];
+            let rects = draw_arrow_data(
                 canvas,
                 e.element.min_shape(),
                 e.points[0].1,
@@ -809,8 +1010,16 @@ where
                     .map(|e| e.1)
                     .unwrap_or_else(|| self.position()),
                 data,
+                labels.map(|e| self.label_offset(e)),
+            );
+            label_rects.extend(
+                labels
+                    .into_iter()
+                    .zip(rects)
+                    .flat_map(|(l, r)| Some((l, r?))),
             );
         }
+        self.label_rects.extend(label_rects);
 
         TargettingStatus::NotDrawn
     }
@@ -964,10 +1173,29 @@ where
                 check_joints!(sources);
                 check_joints!(targets);
 
+                // Check whether over a label, if so drag it
+                if let Some(label) = self
+                    .label_rects
+                    .iter()
+                    .find(|e| e.1.contains(pos))
+                    .map(|e| *e.0)
+                {
+                    let uuid = match self.label_offsets.iter().find(|e| e.0 == label) {
+                        Some(e) => e.1,
+                        None => {
+                            let uuid = ViewUuid::now_v7();
+                            self.label_offsets.push((label, uuid, egui::Vec2::ZERO));
+                            uuid
+                        }
+                    };
+                    self.dragged_label = Some(uuid);
+                    return EventHandlingStatus::HandledByContainer;
+                }
+
                 EventHandlingStatus::NotHandled
             }
             InputEvent::MouseUp(_) => {
-                if self.dragged_node.take().is_some() {
+                if self.dragged_node.take().is_some() || self.dragged_label.take().is_some() {
                     EventHandlingStatus::HandledByElement
                 } else {
                     EventHandlingStatus::NotHandled
@@ -1024,36 +1252,47 @@ where
 
                 // Check segments on paths
                 macro_rules! check_path_segments {
-                    ($v:ident) => {
+                    ($v:ident, $source:expr) => {
                         let p = self.position();
                         for e in &self.$v {
-                            // Iterates over 2-windows
-                            let mut iter = e
+                            let points: Vec<_> = e
                                 .points
                                 .iter()
                                 .map(|e| e.1)
+                                .chain(self.tree_elbow(e, p).filter(|_| $source))
                                 .chain(std::iter::once(p))
-                                .peekable();
-                            while let Some(u) = iter.next() {
-                                let v = if let Some(v) = iter.peek() {
-                                    *v
-                                } else {
-                                    break;
-                                };
+                                .collect();
+                            let points = if self.curved {
+                                bezier_points(&points, Self::CURVE_SEGMENTS)
+                            } else {
+                                points
+                            };
 
-                                if dist_to_line_segment(pos, u, v) <= segment_distance_threshold {
-                                    return EventHandlingStatus::HandledByElement;
-                                }
+                            if points.windows(2).any(|w| {
+                                dist_to_line_segment(pos, w[0], w[1]) <= segment_distance_threshold
+                            }) {
+                                return EventHandlingStatus::HandledByElement;
                             }
                         }
                     };
                 }
-                check_path_segments!(sources);
-                check_path_segments!(targets);
+                check_path_segments!(sources, true);
+                check_path_segments!(targets, false);
+
+                if self.label_rects.values().any(|e| e.contains(pos)) {
+                    return EventHandlingStatus::HandledByElement;
+                }
 
                 EventHandlingStatus::NotHandled
             }
             InputEvent::Drag { delta, .. } => {
+                if let Some(label) = self.dragged_label {
+                    commands.push(InsensitiveCommand::MovePositional(
+                        std::iter::once(label).collect(),
+                        delta,
+                    ));
+                    return EventHandlingStatus::HandledByContainer;
+                }
                 let Some(dragged_node) = self.dragged_node else {
                     return EventHandlingStatus::NotHandled;
                 };
@@ -1173,6 +1412,17 @@ where
                         -*delta,
                     ));
                 }
+                for l in self
+                    .label_offsets
+                    .iter_mut()
+                    .filter(|e| uuids.contains(&e.1))
+                {
+                    l.2 += *delta;
+                    undo_accumulator.push(InsensitiveCommand::MovePositional(
+                        std::iter::once(l.1).collect(),
+                        -*delta,
+                    ));
+                }
             }
             InsensitiveCommand::MovePositional(_, delta)
             | InsensitiveCommand::MovePositionalAll(delta) => {
@@ -1187,7 +1437,9 @@ where
             InsensitiveCommand::ResizeElementsBy(..)
             | InsensitiveCommand::ResizeElementTo(..)
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::DeleteSpecificElements(uuids, _) => {
                 let self_uuid = *self.uuid;
                 if let Some(center_point) =
@@ -1437,6 +1689,24 @@ where
             }
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
+                    if let Some(tree_style) = self.adapter.as_tree_style_change(property)
+                        && let Some(undo) = self.adapter.tree_style_change(self.tree_style)
+                    {
+                        undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                            std::iter::once(*self.uuid).collect(),
+                            undo,
+                        ));
+                        self.tree_style = tree_style;
+                    }
+                    if let Some(curved) = self.adapter.as_curved_change(property)
+                        && let Some(undo) = self.adapter.curved_change(self.curved)
+                    {
+                        undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                            std::iter::once(*self.uuid).collect(),
+                            undo,
+                        ));
+                        self.curved = curved;
+                    }
                     if let Ok(FlipMulticonnection {}) = property.try_into()
                         && self.adapter.flip_multiconnection().is_ok()
                     {
@@ -1479,6 +1749,33 @@ where
             );
         }
     }
+    fn connection_endpoints(&self) -> Option<(ViewUuid, ViewUuid)> {
+        match (self.sources.as_slice(), self.targets.as_slice()) {
+            ([s], [t])
+                if s.points.len() == 1
+                    && t.points.len() == 1
+                    && matches!(self.center_point, UFOption::None) =>
+            {
+                Some((*s.element.uuid(), *t.element.uuid()))
+            }
+            _ => None,
+        }
+    }
+    fn connected_views(&self) -> Vec<(ViewUuid, ViewUuid)> {
+        self.sources
+            .iter()
+            .flat_map(|s| {
+                self.targets
+                    .iter()
+                    .map(|t| (*s.element.uuid(), *t.element.uuid()))
+            })
+            .collect()
+    }
+
+    fn waypoints(&self) -> Vec<(ViewUuid, egui::Pos2)> {
+        self.all_vertices().copied().collect()
+    }
+
     fn delete_when(&self, deleting: &HashSet<ViewUuid>) -> bool {
         self.sources
             .iter()
@@ -1525,9 +1822,19 @@ where
             highlight: self.highlight,
             selected_vertices: self.selected_vertices.clone(),
             center_point,
+            tree_style: self.tree_style,
+            curved: self.curved,
 
             // There is no need to keep it (undo would destroy the whole clone first)
             point_to_origin: HashMap::new(),
+
+            label_offsets: self
+                .label_offsets
+                .iter()
+                .map(|e| (e.0, ViewUuid::now_v7(), e.2))
+                .collect(),
+            label_rects: HashMap::new(),
+            dragged_label: None,
         });
         tlc.insert(view_uuid, cloneish.clone().into());
         c.insert(*self.uuid, cloneish.clone().into());
@@ -1550,5 +1857,14 @@ where
                 e.element = t.clone();
             }
         }
+        for e in self.label_offsets.iter_mut() {
+            let relink = |v: ViewUuid| c.get(&v).map(|e| *e.uuid()).unwrap_or(v);
+            e.0 = match e.0 {
+                ConnectionLabel::Center => ConnectionLabel::Center,
+                ConnectionLabel::Multiplicity(v) => ConnectionLabel::Multiplicity(relink(v)),
+                ConnectionLabel::Role(v) => ConnectionLabel::Role(relink(v)),
+                ConnectionLabel::Reading(v) => ConnectionLabel::Reading(relink(v)),
+            };
+        }
     }
 }
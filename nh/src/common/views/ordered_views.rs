@@ -10,6 +10,13 @@ use crate::common::{
     uuid::ViewUuid,
 };
 
+/// Keeps a view behind or in front of all other unlocked views, regardless of arrangement
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ZOrderLock {
+    Back,
+    Front,
+}
+
 pub struct OrderedViews<T>
 where
     T: View,
@@ -34,6 +41,10 @@ where
         Self { draw_order, views }
     }
 
+    pub fn iter_draw_order_keys(&self) -> impl Iterator<Item = ViewUuid> {
+        self.draw_order.iter().cloned()
+    }
+
     pub fn iter_event_order_keys(&self) -> impl Iterator<Item = ViewUuid> {
         self.draw_order.iter().rev().cloned()
     }
@@ -100,6 +111,27 @@ where
         self.draw_order.retain(|k| keep.contains(k));
     }
 
+    /// Moves the view to `index` in the draw order, 0 being the back
+    pub fn move_in_draw_order(&mut self, uuid: &ViewUuid, index: usize) {
+        let Some(current) = self.draw_order.iter().position(|e| e == uuid) else {
+            return;
+        };
+        let e = self.draw_order.remove(current);
+        self.draw_order.insert(index.min(self.draw_order.len()), e);
+    }
+
+    /// Moves locked views behind or in front of all other views, keeping their relative order
+    pub fn apply_locks(&mut self, locks: &HashMap<ViewUuid, ZOrderLock>) {
+        if locks.is_empty() {
+            return;
+        }
+        self.draw_order.sort_by_key(|e| match locks.get(e) {
+            Some(ZOrderLock::Back) => 0,
+            None => 1,
+            Some(ZOrderLock::Front) => 2,
+        });
+    }
+
     pub fn apply_arrangement(&mut self, uuids: &HashSet<ViewUuid>, arr: Arrangement) {
         match arr {
             Arrangement::BringToFront | Arrangement::SendToBack => {
@@ -398,9 +398,11 @@ where
         let mut drawn_child_targetting = TargettingStatus::NotDrawn;
 
         self.owned_views.draw_order_foreach_mut(|v| {
+            canvas.begin_element(&v.uuid());
             if v.draw_in(q, context, settings, canvas, tool) == TargettingStatus::Drawn {
                 drawn_child_targetting = TargettingStatus::Drawn;
             }
+            canvas.end_element();
         });
 
         if canvas.ui_scale().is_some() {
@@ -434,7 +436,9 @@ where
                     );
 
                     self.owned_views.draw_order_foreach_mut(|v| {
+                        canvas.begin_element(&v.uuid());
                         v.draw_in(q, context, settings, canvas, tool);
+                        canvas.end_element();
                     });
 
                     TargettingStatus::Drawn
@@ -536,7 +540,7 @@ where
                 }
 
                 if let Some(tool) = tool {
-                    tool.add_position(*event.mouse_position());
+                    tool.add_position(ehc.snap_manager.snap_to_grid(*event.mouse_position()));
                     tool.add_section(self.adapter.model_section());
 
                     if let Ok(esm) = tool.try_flush(q, &self.uuid, 0, None, commands)
@@ -905,6 +909,7 @@ where
 
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
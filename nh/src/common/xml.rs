@@ -0,0 +1,240 @@
+/// Element of a parsed XML document, names keep their namespace prefixes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+    /// Text directly inside the element
+    pub text: String,
+}
+
+impl XmlElement {
+    /// Name without the namespace prefix
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|e| e.0 == name)
+            .map(|e| e.1.as_str())
+    }
+
+    /// Children with the given name, compared without namespace prefixes
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |e| e.local_name() == name)
+    }
+}
+
+pub fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Escapes text for use in element content and attribute values
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(s: &str, line: usize) -> Result<String, String> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| format!("unterminated entity on line {}", line))?;
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|e| u32::from_str_radix(e, 16))
+                .or_else(|| entity.strip_prefix('#').map(|e| e.parse()))
+                .and_then(|e| e.ok())
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("unknown entity &{}; on line {}", entity, line))?,
+        };
+        result.push(c);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn line(&self) -> usize {
+        self.source[..self.position].matches('\n').count() + 1
+    }
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+    /// Skips past the terminator, returning the text before it
+    fn take_until(&mut self, terminator: &str) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let end = rest
+            .find(terminator)
+            .ok_or_else(|| format!("expected {:?} after line {}", terminator, self.line()))?;
+        self.position += end + terminator.len();
+        Ok(&rest[..end])
+    }
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(format!("expected a name on line {}", self.line()));
+        }
+        self.position += len;
+        Ok(&rest[..len])
+    }
+
+    /// Skips the prolog, comments, processing instructions and declarations
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.take_until("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if rest.starts_with("<!") && !rest.starts_with("<![CDATA[") {
+                self.take_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<XmlElement, String> {
+        if !self.rest().starts_with('<') {
+            return Err(format!("expected an element on line {}", self.line()));
+        }
+        self.position += 1;
+        let mut element = XmlElement {
+            name: self.name()?.to_owned(),
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            } else if rest.starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let name = self.name()?.to_owned();
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(format!(
+                    "expected '=' after {} on line {}",
+                    name,
+                    self.line()
+                ));
+            }
+            self.position += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(format!("expected a quoted value on line {}", self.line())),
+            };
+            self.position += 1;
+            let line = self.line();
+            let value = self.take_until(&quote.to_string())?;
+            element.attributes.push((name, unescape(value, line)?));
+        }
+
+        loop {
+            let line = self.line();
+            let text = self.rest();
+            let text_len = text.find('<').unwrap_or(text.len());
+            element.text.push_str(&unescape(&text[..text_len], line)?);
+            self.position += text_len;
+
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(format!("unclosed element {}", element.name));
+            } else if rest.starts_with("</") {
+                self.position += 2;
+                let name = self.take_until(">")?.trim();
+                if name != element.name {
+                    return Err(format!(
+                        "expected </{}>, found </{}> on line {}",
+                        element.name,
+                        name,
+                        self.line()
+                    ));
+                }
+                return Ok(element);
+            } else if rest.starts_with("<![CDATA[") {
+                self.position += 9;
+                element.text.push_str(self.take_until("]]>")?);
+            } else if rest.starts_with("<!--") || rest.starts_with("<?") {
+                self.skip_misc()?;
+            } else {
+                element.children.push(self.element()?);
+            }
+        }
+    }
+}
+
+/// Parses the root element of the document
+pub fn parse(source: &str) -> Result<XmlElement, String> {
+    let mut parser = Parser {
+        source: source.strip_prefix('\u{feff}').unwrap_or(source),
+        position: 0,
+    };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if !parser.rest().is_empty() {
+        return Err(format!(
+            "unexpected content after the root element on line {}",
+            parser.line()
+        ));
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documents_are_parsed() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>\n<!-- model -->\n<ns:a x=\"1 &lt; 2\" y='&#x41;'>\n  <b/>text<!-- c --><ns:b><![CDATA[<raw>]]></ns:b>\n</ns:a>\n",
+        )
+        .unwrap();
+        assert_eq!(root.local_name(), "a");
+        assert_eq!(root.attribute("x"), Some("1 < 2"));
+        assert_eq!(root.attribute("y"), Some("A"));
+        assert_eq!(root.children_named("b").count(), 2);
+        assert_eq!(root.children[1].text, "<raw>");
+        assert_eq!(root.text.trim(), "text");
+
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+    }
+}
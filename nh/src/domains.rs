@@ -1,11 +1,28 @@
+pub mod bpmn;
+pub mod causalloop;
+pub mod component;
+pub mod conceptmap;
 pub mod demo;
 pub mod democsd;
 pub mod demoofd;
 pub mod demopsd;
+pub mod deployment;
+pub mod dfd;
+pub mod dmn;
+pub mod er;
+pub mod flowchart;
+pub mod goal;
+pub mod mindmap;
 pub mod network;
 pub mod ontouml;
+pub mod orgchart;
 pub mod rdf;
+pub mod statemachine;
+pub mod sysml;
+pub mod timeline;
 pub mod umlactivity;
 pub mod umlclass;
+pub mod umlobject;
 pub mod umlsequence;
 pub mod usecase;
+pub mod wireframe;
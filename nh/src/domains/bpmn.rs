@@ -0,0 +1,3 @@
+pub mod bpmn_controllers;
+pub mod bpmn_models;
+pub mod bpmn_xml;
@@ -0,0 +1,675 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlClassView, UmlUseCaseView, new_uml_usecase, new_umlclass_class,
+        new_umlclass_dependency, new_umlclass_package,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        canvas,
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, TextExportFormat, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        bpmn::{
+            bpmn_models,
+            bpmn_xml::{BPMN_XML_FORMAT, bpmn_xml},
+        },
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{
+                UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind, UmlUseCase,
+            },
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct BpmnProfile;
+impl UmlClassProfile for BpmnProfile {
+    /// BPMN elements are commonly given colored fills, on which light text would be unreadable
+    fn color_profile(_dark: bool) -> canvas::ColorProfile {
+        canvas::ColorProfile::LIGHT
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct BpmnControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<BpmnProfile>> for BpmnControllerAdapter {
+    type DiagramViewT =
+        DiagramControllerGen2<UmlClassDomain<BpmnProfile>, UmlClassDiagramAdapter<BpmnProfile>>;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-bpmn"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("BPMN Process Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared BPMN Process Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[BPMN_XML_FORMAT]
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        match *format {
+            BPMN_XML_FORMAT => Some(bpmn_xml(&self.model.read())),
+            _ => None,
+        }
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<BpmnProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            BpmnControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<BpmnProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New BPMN process diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (place_model, place_view) = new_activity(
+        "Place order",
+        bpmn_models::TASK,
+        egui::Pos2::new(350.0, 125.0),
+    );
+    let (received_model, received_view) = new_event(
+        "Order received",
+        bpmn_models::START_EVENT,
+        egui::Pos2::new(200.0, 350.0),
+    );
+    let (check_model, check_view) = new_activity(
+        "Check stock",
+        bpmn_models::SERVICE_TASK,
+        egui::Pos2::new(350.0, 350.0),
+    );
+    let (available_model, available_view) = new_event(
+        "Available?",
+        bpmn_models::EXCLUSIVE_GATEWAY,
+        egui::Pos2::new(520.0, 350.0),
+    );
+    let (rejected_model, rejected_view) = new_event(
+        "Order rejected",
+        bpmn_models::END_EVENT,
+        egui::Pos2::new(720.0, 350.0),
+    );
+    let (ship_model, ship_view) = new_activity(
+        "Ship goods",
+        bpmn_models::USER_TASK,
+        egui::Pos2::new(520.0, 550.0),
+    );
+    let (shipped_model, shipped_view) = new_event(
+        "Order shipped",
+        bpmn_models::END_EVENT,
+        egui::Pos2::new(720.0, 550.0),
+    );
+
+    let (order_model, order_view) = new_umlclass_dependency(
+        bpmn_models::MESSAGE_FLOW,
+        "order",
+        false,
+        None,
+        (place_model.clone().into(), place_view.clone().into()),
+        (received_model.clone().into(), received_view.clone().into()),
+    );
+    let (flow1_model, flow1_view) = new_umlclass_dependency(
+        bpmn_models::SEQUENCE_FLOW,
+        "",
+        true,
+        None,
+        (received_model.clone().into(), received_view.clone().into()),
+        (check_model.clone().into(), check_view.clone().into()),
+    );
+    let (flow2_model, flow2_view) = new_umlclass_dependency(
+        bpmn_models::SEQUENCE_FLOW,
+        "",
+        true,
+        None,
+        (check_model.clone().into(), check_view.clone().into()),
+        (
+            available_model.clone().into(),
+            available_view.clone().into(),
+        ),
+    );
+    let (flow3_model, flow3_view) = new_umlclass_dependency(
+        bpmn_models::SEQUENCE_FLOW,
+        "no",
+        true,
+        None,
+        (
+            available_model.clone().into(),
+            available_view.clone().into(),
+        ),
+        (rejected_model.clone().into(), rejected_view.clone().into()),
+    );
+    let (flow4_model, flow4_view) = new_umlclass_dependency(
+        bpmn_models::SEQUENCE_FLOW,
+        "yes",
+        true,
+        None,
+        (
+            available_model.clone().into(),
+            available_view.clone().into(),
+        ),
+        (ship_model.clone().into(), ship_view.clone().into()),
+    );
+    let (flow5_model, flow5_view) = new_umlclass_dependency(
+        bpmn_models::SEQUENCE_FLOW,
+        "",
+        true,
+        None,
+        (ship_model.clone().into(), ship_view.clone().into()),
+        (shipped_model.clone().into(), shipped_view.clone().into()),
+    );
+
+    let (customer, customer_view) = new_umlclass_package(
+        "Customer",
+        bpmn_models::POOL,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(100.0..=850.0, 50.0..=200.0),
+    );
+    add_into(
+        customer_view.clone().into(),
+        vec![place_view.clone().into()],
+    );
+    let (_, sales_view) = new_umlclass_package(
+        "Sales",
+        bpmn_models::LANE,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(130.0..=850.0, 250.0..=450.0),
+    );
+    add_into(
+        sales_view.clone().into(),
+        vec![
+            received_view.clone().into(),
+            check_view.clone().into(),
+            available_view.clone().into(),
+            rejected_view.clone().into(),
+        ],
+    );
+    let (_, warehouse_view) = new_umlclass_package(
+        "Warehouse",
+        bpmn_models::LANE,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(130.0..=850.0, 450.0..=650.0),
+    );
+    add_into(
+        warehouse_view.clone().into(),
+        vec![ship_view.clone().into(), shipped_view.clone().into()],
+    );
+    let (shop, shop_view) = new_umlclass_package(
+        "Shop",
+        bpmn_models::POOL,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(100.0..=850.0, 250.0..=650.0),
+    );
+    add_into(
+        shop_view.clone().into(),
+        vec![sales_view.clone().into(), warehouse_view.clone().into()],
+    );
+
+    let name = format!("Demo BPMN process diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            customer.into(),
+            shop.into(),
+            order_model.into(),
+            flow1_model.into(),
+            flow2_model.into(),
+            flow3_model.into(),
+            flow4_model.into(),
+            flow5_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            customer_view.into(),
+            shop_view.into(),
+            order_view.into(),
+            flow1_view.into(),
+            flow2_view.into(),
+            flow3_view.into(),
+            flow4_view.into(),
+            flow5_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<BpmnProfile>,
+        BpmnControllerAdapter,
+        DiagramControllerGen2<UmlClassDomain<BpmnProfile>, UmlClassDiagramAdapter<BpmnProfile>>,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<BpmnProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<BpmnProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<BpmnProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: bpmn_models::SEQUENCE_FLOW.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<BpmnProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let activity = |name: &str, stereotype: &str, label, key| {
+        (
+            UmlClassToolStage::Class {
+                name: name.to_owned(),
+                stereotype: stereotype.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            label,
+            key,
+        )
+    };
+    let activities = vec![
+        activity(
+            "Task",
+            bpmn_models::TASK,
+            "Task",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        activity("User Task", bpmn_models::USER_TASK, "User Task", None),
+        activity(
+            "Service Task",
+            bpmn_models::SERVICE_TASK,
+            "Service Task",
+            None,
+        ),
+        activity("Sub-Process", bpmn_models::SUB_PROCESS, "Sub-Process", None),
+    ];
+
+    let node = |name: &str, stereotype: &str, label, key| {
+        (
+            UmlClassToolStage::UseCase {
+                name: name.to_owned(),
+                stereotype: stereotype.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            label,
+            key,
+        )
+    };
+    let events = vec![
+        node(
+            "Start",
+            bpmn_models::START_EVENT,
+            "Start Event",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        node(
+            "",
+            bpmn_models::INTERMEDIATE_CATCH_EVENT,
+            "Intermediate Catch Event",
+            None,
+        ),
+        node(
+            "",
+            bpmn_models::INTERMEDIATE_THROW_EVENT,
+            "Intermediate Throw Event",
+            None,
+        ),
+        node(
+            "End",
+            bpmn_models::END_EVENT,
+            "End Event",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+    ];
+    let gateways = vec![
+        node(
+            "",
+            bpmn_models::EXCLUSIVE_GATEWAY,
+            "Exclusive Gateway",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        node("", bpmn_models::PARALLEL_GATEWAY, "Parallel Gateway", None),
+        node(
+            "",
+            bpmn_models::INCLUSIVE_GATEWAY,
+            "Inclusive Gateway",
+            None,
+        ),
+    ];
+
+    let swimlanes = vec![
+        (
+            UmlClassToolStage::PackageStart {
+                name: "Pool".to_owned(),
+                stereotype: bpmn_models::POOL.to_owned(),
+                kind: UmlClassPackageKind::Boundary,
+            },
+            "Pool",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::PackageStart {
+                name: "Lane".to_owned(),
+                stereotype: bpmn_models::LANE.to_owned(),
+                kind: UmlClassPackageKind::Boundary,
+            },
+            "Lane",
+            None,
+        ),
+    ];
+
+    let flows = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: bpmn_models::SEQUENCE_FLOW.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Sequence Flow",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num6,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: false,
+                    stereotype: bpmn_models::MESSAGE_FLOW.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Message Flow",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num7,
+            )),
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Activities", activities),
+        ("Events", events),
+        ("Gateways", gateways),
+        ("Swimlanes", swimlanes),
+        ("Flows", flows),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Text Annotation",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Association", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<BpmnProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<BpmnProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-bpmn",
+    pretty_name: "BPMN process diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "BPMN process diagram (tasks, events, gateways, pools and lanes, sequence and message flows, BPMN 2.0 XML export)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_activity(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<BpmnProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
+
+fn new_event(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlUseCase>, ERef<UmlUseCaseView<BpmnProfile>>) {
+    new_uml_usecase(name, stereotype, false, position, MGlobalColor::None)
+}
+
+/// Moves the views, along with their models, into a pool or a lane
+fn add_into(
+    mut container: UmlClassElementView<BpmnProfile>,
+    elements: Vec<UmlClassElementView<BpmnProfile>>,
+) {
+    let container_uuid = *container.uuid();
+    let (mut u, mut a) = Default::default();
+    for e in elements {
+        container.apply_command(
+            &InsensitiveCommand::AddDependency {
+                target: container_uuid,
+                bucket: 0,
+                position: None,
+                element: UmlClassElementOrVertex::Element(e),
+                into_model: true,
+            },
+            &mut u,
+            &mut a,
+        );
+    }
+}
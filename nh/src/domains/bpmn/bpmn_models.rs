@@ -0,0 +1,35 @@
+// Activities are classes
+pub const TASK: &str = "task";
+pub const USER_TASK: &str = "userTask";
+pub const SERVICE_TASK: &str = "serviceTask";
+pub const SUB_PROCESS: &str = "subProcess";
+// Events are use cases
+pub const START_EVENT: &str = "startEvent";
+pub const INTERMEDIATE_CATCH_EVENT: &str = "intermediateCatchEvent";
+pub const INTERMEDIATE_THROW_EVENT: &str = "intermediateThrowEvent";
+pub const END_EVENT: &str = "endEvent";
+// Gateways are use cases too
+pub const EXCLUSIVE_GATEWAY: &str = "exclusiveGateway";
+pub const PARALLEL_GATEWAY: &str = "parallelGateway";
+pub const INCLUSIVE_GATEWAY: &str = "inclusiveGateway";
+// Pools and lanes are boundary packages, lanes are nested in pools
+pub const POOL: &str = "pool";
+pub const LANE: &str = "lane";
+
+pub const SEQUENCE_FLOW: &str = "";
+pub const MESSAGE_FLOW: &str = "message";
+
+/// Stereotypes of flow nodes, which are also their BPMN 2.0 XML tags
+pub const FLOW_NODES: [&str; 11] = [
+    TASK,
+    USER_TASK,
+    SERVICE_TASK,
+    SUB_PROCESS,
+    START_EVENT,
+    INTERMEDIATE_CATCH_EVENT,
+    INTERMEDIATE_THROW_EVENT,
+    END_EVENT,
+    EXCLUSIVE_GATEWAY,
+    PARALLEL_GATEWAY,
+    INCLUSIVE_GATEWAY,
+];
@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+
+use crate::{
+    common::{
+        controller::{Model, TextExportFormat},
+        uuid::ModelUuid,
+        xml::escape,
+    },
+    domains::{
+        bpmn::bpmn_models,
+        umlclass::umlclass_models::{
+            UmlClass, UmlClassAssociation, UmlClassComment, UmlClassCommentLink,
+            UmlClassDependency, UmlClassDiagram, UmlClassGeneralization, UmlClassInstance,
+            UmlClassPackage, UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+        },
+    },
+};
+
+pub const BPMN_XML_FORMAT: TextExportFormat = TextExportFormat {
+    name: "BPMN 2.0 XML",
+    extensions: &["bpmn", "xml"],
+};
+
+/// Exports the process semantics, without diagram interchange information.
+///
+/// Every pool becomes a participant with its own process, elements outside of pools
+/// belong to a process of the diagram. Sequence flows crossing processes and message
+/// flows within a process are not valid BPMN and are left out.
+pub fn bpmn_xml(diagram: &UmlClassDiagram) -> String {
+    let mut collector = BpmnXmlCollector::new();
+
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+
+    collector.finish(diagram)
+}
+
+struct FlowNode {
+    uuid: ModelUuid,
+    name: String,
+    stereotype: String,
+}
+
+struct Lane {
+    uuid: ModelUuid,
+    name: String,
+    nodes: Vec<ModelUuid>,
+}
+
+struct Process {
+    /// Pool the process is drawn in, None for the diagram process
+    pool: Option<(ModelUuid, String)>,
+    lanes: Vec<Lane>,
+    nodes: Vec<FlowNode>,
+}
+
+struct Flow {
+    uuid: ModelUuid,
+    name: String,
+    stereotype: String,
+    source: ModelUuid,
+    target: ModelUuid,
+}
+
+struct BpmnXmlCollector {
+    processes: Vec<Process>,
+    flows: Vec<Flow>,
+    current_process: usize,
+    current_lane: Option<usize>,
+}
+
+impl BpmnXmlCollector {
+    fn new() -> Self {
+        Self {
+            processes: vec![Process {
+                pool: None,
+                lanes: Vec::new(),
+                nodes: Vec::new(),
+            }],
+            flows: Vec::new(),
+            current_process: 0,
+            current_lane: None,
+        }
+    }
+
+    fn visit_flow_node(&mut self, uuid: ModelUuid, name: &str, stereotype: &str) {
+        if !bpmn_models::FLOW_NODES.contains(&stereotype) {
+            return;
+        }
+        let process = &mut self.processes[self.current_process];
+        if let Some(lane) = self.current_lane {
+            process.lanes[lane].nodes.push(uuid);
+        }
+        process.nodes.push(FlowNode {
+            uuid,
+            name: name.to_owned(),
+            stereotype: stereotype.to_owned(),
+        });
+    }
+
+    fn finish(self, diagram: &UmlClassDiagram) -> String {
+        let process_of: HashMap<ModelUuid, usize> = self
+            .processes
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, p)| p.nodes.iter().map(move |e| (e.uuid, idx)))
+            .collect();
+        let diagram_id = Self::stringify_uuid(&diagram.uuid);
+        let process_id = |p: &Process| match &p.pool {
+            Some((uuid, _)) => format!("{}_process", Self::stringify_uuid(uuid)),
+            None => format!("{}_process", diagram_id),
+        };
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<definitions xmlns=\"http://www.omg.org/spec/BPMN/20100524/MODEL\" id=\"{}\" name=\"{}\" targetNamespace=\"urn:uuid:{}\">\n",
+            diagram_id,
+            escape(&diagram.name),
+            diagram.uuid.to_string(),
+        ));
+
+        let has_pools = self.processes.len() > 1;
+        if has_pools {
+            xml.push_str(&format!(
+                "  <collaboration id=\"{}_collaboration\">\n",
+                diagram_id
+            ));
+            for p in &self.processes {
+                if let Some((uuid, name)) = &p.pool {
+                    xml.push_str(&format!(
+                        "    <participant id=\"{}\" name=\"{}\" processRef=\"{}\"/>\n",
+                        Self::stringify_uuid(uuid),
+                        escape(name),
+                        process_id(p),
+                    ));
+                }
+            }
+            for f in self.flows.iter().filter(|f| {
+                f.stereotype == bpmn_models::MESSAGE_FLOW
+                    && matches!((process_of.get(&f.source), process_of.get(&f.target)),
+                        (Some(s), Some(t)) if s != t)
+            }) {
+                xml.push_str(&format!(
+                    "    <messageFlow id=\"{}\" name=\"{}\" sourceRef=\"{}\" targetRef=\"{}\"/>\n",
+                    Self::stringify_uuid(&f.uuid),
+                    escape(&f.name),
+                    Self::stringify_uuid(&f.source),
+                    Self::stringify_uuid(&f.target),
+                ));
+            }
+            xml.push_str("  </collaboration>\n");
+        }
+
+        for (idx, p) in self.processes.iter().enumerate() {
+            // The diagram process is only listed when something is drawn outside of pools
+            if p.pool.is_none() && p.nodes.is_empty() && has_pools {
+                continue;
+            }
+            let id = process_id(p);
+            xml.push_str(&format!(
+                "  <process id=\"{}\" name=\"{}\" isExecutable=\"false\">\n",
+                id,
+                escape(p.pool.as_ref().map(|e| e.1.as_str()).unwrap_or("")),
+            ));
+
+            if !p.lanes.is_empty() {
+                xml.push_str(&format!("    <laneSet id=\"{}_laneset\">\n", id));
+                for l in &p.lanes {
+                    xml.push_str(&format!(
+                        "      <lane id=\"{}\" name=\"{}\">\n",
+                        Self::stringify_uuid(&l.uuid),
+                        escape(&l.name),
+                    ));
+                    for n in &l.nodes {
+                        xml.push_str(&format!(
+                            "        <flowNodeRef>{}</flowNodeRef>\n",
+                            Self::stringify_uuid(n)
+                        ));
+                    }
+                    xml.push_str("      </lane>\n");
+                }
+                xml.push_str("    </laneSet>\n");
+            }
+
+            for n in &p.nodes {
+                xml.push_str(&format!(
+                    "    <{} id=\"{}\" name=\"{}\"/>\n",
+                    n.stereotype,
+                    Self::stringify_uuid(&n.uuid),
+                    escape(&n.name),
+                ));
+            }
+
+            for f in self.flows.iter().filter(|f| {
+                f.stereotype == bpmn_models::SEQUENCE_FLOW
+                    && process_of.get(&f.source) == Some(&idx)
+                    && process_of.get(&f.target) == Some(&idx)
+            }) {
+                xml.push_str(&format!(
+                    "    <sequenceFlow id=\"{}\" name=\"{}\" sourceRef=\"{}\" targetRef=\"{}\"/>\n",
+                    Self::stringify_uuid(&f.uuid),
+                    escape(&f.name),
+                    Self::stringify_uuid(&f.source),
+                    Self::stringify_uuid(&f.target),
+                ));
+            }
+
+            xml.push_str("  </process>\n");
+        }
+
+        xml.push_str("</definitions>\n");
+        xml
+    }
+
+    fn stringify_uuid(uuid: &ModelUuid) -> String {
+        format!("_{}", uuid.to_string())
+    }
+}
+
+impl UmlClassVisitor for BpmnXmlCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        let (previous_process, previous_lane) = (self.current_process, self.current_lane);
+        match package.stereotype.as_str() {
+            // Pools cannot be nested, pools within pools are treated as lanes
+            bpmn_models::POOL if self.current_process == 0 => {
+                self.processes.push(Process {
+                    pool: Some((*package.uuid, (*package.name).clone())),
+                    lanes: Vec::new(),
+                    nodes: Vec::new(),
+                });
+                self.current_process = self.processes.len() - 1;
+                self.current_lane = None;
+            }
+            bpmn_models::POOL | bpmn_models::LANE => {
+                let lanes = &mut self.processes[self.current_process].lanes;
+                lanes.push(Lane {
+                    uuid: *package.uuid,
+                    name: (*package.name).clone(),
+                    nodes: Vec::new(),
+                });
+                self.current_lane = Some(lanes.len() - 1);
+            }
+            _ => {}
+        }
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+        (self.current_process, self.current_lane) = (previous_process, previous_lane);
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        self.visit_flow_node(*class.uuid, &class.name, &class.stereotype);
+    }
+    fn visit_usecase(&mut self, usecase: &UmlUseCase) {
+        self.visit_flow_node(*usecase.uuid, &usecase.name, &usecase.stereotype);
+    }
+    fn visit_generalization(&mut self, _generalization: &UmlClassGeneralization) {}
+    fn visit_dependency(&mut self, dependency: &UmlClassDependency) {
+        self.flows.push(Flow {
+            uuid: *dependency.uuid,
+            name: (*dependency.name).clone(),
+            stereotype: (*dependency.stereotype).clone(),
+            source: *dependency.source.uuid(),
+            target: *dependency.target.uuid(),
+        });
+    }
+    fn visit_association(&mut self, _association: &UmlClassAssociation) {}
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        common::eref::ERef,
+        domains::umlclass::umlclass_models::{
+            UmlClassAssociable, UmlClassElement, UmlClassPackageKind,
+        },
+    };
+
+    fn generate_modeluuid(id: u32) -> ModelUuid {
+        uuid::Uuid::from_u128(id as u128).into()
+    }
+
+    fn new_task(id: u32, name: &str) -> ERef<UmlClass> {
+        ERef::new(UmlClass::new(
+            generate_modeluuid(id),
+            name.to_owned(),
+            bpmn_models::TASK.to_owned(),
+            "".to_owned(),
+            false,
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
+    fn new_event(id: u32, stereotype: &str) -> ERef<UmlUseCase> {
+        ERef::new(UmlUseCase::new(
+            generate_modeluuid(id),
+            "".to_owned(),
+            stereotype.to_owned(),
+            false,
+        ))
+    }
+
+    fn new_pool(
+        id: u32,
+        name: &str,
+        stereotype: &str,
+        elements: Vec<UmlClassElement>,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassPackage::new(
+            generate_modeluuid(id),
+            name.to_owned(),
+            stereotype.to_owned(),
+            UmlClassPackageKind::Boundary,
+            elements,
+        ))
+        .into()
+    }
+
+    fn new_flow(
+        id: u32,
+        stereotype: &str,
+        source: UmlClassAssociable,
+        target: UmlClassAssociable,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassDependency::new(
+            generate_modeluuid(id),
+            stereotype.to_owned(),
+            "".to_owned(),
+            source,
+            target,
+            true,
+        ))
+        .into()
+    }
+
+    #[test]
+    fn test_pools_and_flows() {
+        let start = new_event(1, bpmn_models::START_EVENT);
+        let check = new_task(2, "Check & ship");
+        let place = new_task(3, "Place order");
+        let d = UmlClassDiagram::new(
+            generate_modeluuid(0),
+            "".to_owned(),
+            vec![
+                new_pool(
+                    10,
+                    "Shop",
+                    bpmn_models::POOL,
+                    vec![new_pool(
+                        11,
+                        "Sales",
+                        bpmn_models::LANE,
+                        vec![start.clone().into(), check.clone().into()],
+                    )],
+                ),
+                new_pool(
+                    12,
+                    "Customer",
+                    bpmn_models::POOL,
+                    vec![place.clone().into()],
+                ),
+                new_flow(
+                    20,
+                    bpmn_models::SEQUENCE_FLOW,
+                    start.clone().into(),
+                    check.clone().into(),
+                ),
+                new_flow(
+                    21,
+                    bpmn_models::MESSAGE_FLOW,
+                    place.clone().into(),
+                    check.clone().into(),
+                ),
+                // Not valid in BPMN, should be left out
+                new_flow(
+                    22,
+                    bpmn_models::SEQUENCE_FLOW,
+                    check.clone().into(),
+                    place.clone().into(),
+                ),
+            ],
+        );
+
+        let xml = bpmn_xml(&d);
+        assert!(xml.contains("<participant id=\"_00000000-0000-0000-0000-00000000000a\" name=\"Shop\" processRef=\"_00000000-0000-0000-0000-00000000000a_process\"/>"));
+        assert!(xml.contains("<lane id=\"_00000000-0000-0000-0000-00000000000b\" name=\"Sales\">\n        <flowNodeRef>_00000000-0000-0000-0000-000000000001</flowNodeRef>"));
+        assert!(xml.contains(
+            "<task id=\"_00000000-0000-0000-0000-000000000002\" name=\"Check &amp; ship\"/>"
+        ));
+        assert!(xml.contains("<sequenceFlow id=\"_00000000-0000-0000-0000-000000000014\" name=\"\" sourceRef=\"_00000000-0000-0000-0000-000000000001\" targetRef=\"_00000000-0000-0000-0000-000000000002\"/>"));
+        assert!(xml.contains("<messageFlow id=\"_00000000-0000-0000-0000-000000000015\""));
+        assert_eq!(xml.matches("<sequenceFlow").count(), 1);
+        assert_eq!(xml.matches("<process").count(), 2);
+    }
+}
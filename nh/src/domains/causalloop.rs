@@ -0,0 +1,3 @@
+pub mod causalloop_analysis;
+pub mod causalloop_controllers;
+pub mod causalloop_models;
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use super::super::umlclass::umlclass_models::{UmlClassDiagram, UmlClassElement};
+use crate::{
+    CustomTab,
+    common::{
+        canvas::Highlight,
+        controller::{DiagramCommand, GlobalDrawingContext, ProjectCommand, SimpleProjectCommand},
+        eref::ERef,
+        uuid::{ModelUuid, ViewUuid},
+    },
+    domains::{causalloop::causalloop_models, umlclass::umlclass_models::UmlClassAssociable},
+};
+
+/// Upper bound on the number of enumerated loops, dense diagrams can have exponentially many.
+const MAX_LOOPS: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoopPolarity {
+    Reinforcing,
+    Balancing,
+    Unknown,
+}
+
+impl LoopPolarity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopPolarity::Reinforcing => "Reinforcing",
+            LoopPolarity::Balancing => "Balancing",
+            LoopPolarity::Unknown => "Unknown",
+        }
+    }
+}
+
+pub struct CausalLoop {
+    pub identifier: String,
+    pub polarity: LoopPolarity,
+    pub variables: Vec<ModelUuid>,
+    pub links: Vec<ModelUuid>,
+}
+
+pub struct CausalLoopAnalysisTab {
+    model: ERef<UmlClassDiagram>,
+    view_uuid: ViewUuid,
+    results: Option<Vec<CausalLoop>>,
+}
+
+impl CausalLoopAnalysisTab {
+    pub fn new(model: ERef<UmlClassDiagram>, view_uuid: ViewUuid) -> Self {
+        Self {
+            model,
+            view_uuid,
+            results: None,
+        }
+    }
+}
+
+impl CustomTab for CausalLoopAnalysisTab {
+    fn title(&self) -> String {
+        "Loop Analysis".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button("Clear highlighting").clicked() {
+                commands.push(
+                    SimpleProjectCommand::SpecificDiagramCommand(
+                        self.view_uuid,
+                        DiagramCommand::HighlightAllElements(false, Highlight::ALL),
+                    )
+                    .into(),
+                );
+            }
+
+            if ui.button("Detect loops").clicked() {
+                self.results = Some(find_loops(&self.model.read()));
+            }
+        });
+
+        let Some(results) = &self.results else {
+            return;
+        };
+        if results.is_empty() {
+            ui.label("No loops found");
+            return;
+        }
+        if results.len() >= MAX_LOOPS {
+            ui.label(format!("Showing only the first {} loops", MAX_LOOPS));
+        }
+
+        let tb = egui_extras::TableBuilder::new(ui)
+            .column(egui_extras::Column::auto().resizable(true))
+            .column(egui_extras::Column::auto().resizable(true))
+            .column(egui_extras::Column::remainder().resizable(true));
+
+        tb.body(|mut body| {
+            for l in results {
+                body.row(30.0, |mut row| {
+                    row.col(|ui| {
+                        ui.label(&l.identifier);
+                    });
+                    row.col(|ui| {
+                        ui.label(l.polarity.as_str());
+                    });
+                    row.col(|ui| {
+                        let text = l
+                            .variables
+                            .iter()
+                            .chain(l.variables.first())
+                            .map(|e| gdc.model_labels.get(e).to_string())
+                            .collect::<Vec<_>>()
+                            .join(" → ");
+                        if ui.label(text).clicked() {
+                            commands.push(
+                                SimpleProjectCommand::SpecificDiagramCommand(
+                                    self.view_uuid,
+                                    DiagramCommand::HighlightAllElements(
+                                        false,
+                                        Highlight::SELECTED,
+                                    ),
+                                )
+                                .into(),
+                            );
+                            for uuid in l.variables.iter().chain(l.links.iter()) {
+                                commands.push(
+                                    SimpleProjectCommand::SpecificDiagramCommand(
+                                        self.view_uuid,
+                                        DiagramCommand::HighlightElement(
+                                            (*uuid).into(),
+                                            true,
+                                            Highlight::SELECTED,
+                                        ),
+                                    )
+                                    .into(),
+                                );
+                            }
+                        }
+                    });
+                });
+            }
+        });
+    }
+}
+
+type RawLink = (ModelUuid, ModelUuid, ModelUuid, Option<bool>);
+/// Variable indices and (variable index, outgoing link index) pairs of a found loop.
+type FoundLoop = (Vec<usize>, Vec<(usize, usize)>);
+
+struct Link {
+    uuid: ModelUuid,
+    target: usize,
+    is_negative: Option<bool>,
+}
+
+fn collect_graph(
+    elements: &[UmlClassElement],
+    variables: &mut Vec<ModelUuid>,
+    raw_links: &mut Vec<RawLink>,
+) {
+    for e in elements {
+        match e {
+            UmlClassElement::Package(inner) => {
+                collect_graph(&inner.read().contained_elements, variables, raw_links);
+            }
+            UmlClassElement::Class(inner) => {
+                variables.push(*inner.read().uuid);
+            }
+            UmlClassElement::Dependency(inner) => {
+                let r = inner.read();
+                let (UmlClassAssociable::Class(s), UmlClassAssociable::Class(t)) =
+                    (&r.source, &r.target)
+                else {
+                    continue;
+                };
+                let is_negative = match r.stereotype.as_str() {
+                    causalloop_models::POSITIVE => Some(false),
+                    causalloop_models::NEGATIVE => Some(true),
+                    _ => None,
+                };
+                raw_links.push((*r.uuid, *s.read().uuid, *t.read().uuid, is_negative));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Enumerates elementary cycles of the causal graph and classifies them by polarity.
+///
+/// A loop with an even number of negative links is reinforcing, with an odd number balancing.
+pub fn find_loops(diagram: &UmlClassDiagram) -> Vec<CausalLoop> {
+    let mut variables = Vec::new();
+    let mut raw_links = Vec::new();
+    collect_graph(&diagram.contained_elements, &mut variables, &mut raw_links);
+
+    let indices: HashMap<ModelUuid, usize> =
+        variables.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+    let mut adjacency: Vec<Vec<Link>> = variables.iter().map(|_| Vec::new()).collect();
+    for (uuid, s, t, is_negative) in raw_links {
+        if let (Some(s), Some(&t)) = (indices.get(&s), indices.get(&t)) {
+            adjacency[*s].push(Link {
+                uuid,
+                target: t,
+                is_negative,
+            });
+        }
+    }
+
+    // Each cycle is found exactly once, from its lowest-indexed variable
+    fn dfs(
+        start: usize,
+        current: usize,
+        adjacency: &[Vec<Link>],
+        on_path: &mut [bool],
+        path_nodes: &mut Vec<usize>,
+        link_path: &mut Vec<(usize, usize)>,
+        found: &mut Vec<FoundLoop>,
+    ) {
+        for (li, l) in adjacency[current].iter().enumerate() {
+            if found.len() >= MAX_LOOPS {
+                return;
+            }
+            if l.target == start {
+                link_path.push((current, li));
+                found.push((path_nodes.clone(), link_path.clone()));
+                link_path.pop();
+            } else if l.target > start && !on_path[l.target] {
+                on_path[l.target] = true;
+                path_nodes.push(l.target);
+                link_path.push((current, li));
+                dfs(
+                    start, l.target, adjacency, on_path, path_nodes, link_path, found,
+                );
+                link_path.pop();
+                path_nodes.pop();
+                on_path[l.target] = false;
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut on_path = vec![false; variables.len()];
+    for start in 0..variables.len() {
+        on_path[start] = true;
+        dfs(
+            start,
+            start,
+            &adjacency,
+            &mut on_path,
+            &mut vec![start],
+            &mut Vec::new(),
+            &mut found,
+        );
+        on_path[start] = false;
+    }
+
+    let (mut reinforcing_no, mut balancing_no, mut unknown_no) = (0, 0, 0);
+    found
+        .into_iter()
+        .map(|(nodes, links)| {
+            let polarity = links
+                .iter()
+                .map(|(n, l)| adjacency[*n][*l].is_negative)
+                .try_fold(false, |acc, e| e.map(|e| acc ^ e))
+                .map(|odd| {
+                    if odd {
+                        LoopPolarity::Balancing
+                    } else {
+                        LoopPolarity::Reinforcing
+                    }
+                })
+                .unwrap_or(LoopPolarity::Unknown);
+            let identifier = match polarity {
+                LoopPolarity::Reinforcing => {
+                    reinforcing_no += 1;
+                    format!("R{}", reinforcing_no)
+                }
+                LoopPolarity::Balancing => {
+                    balancing_no += 1;
+                    format!("B{}", balancing_no)
+                }
+                LoopPolarity::Unknown => {
+                    unknown_no += 1;
+                    format!("?{}", unknown_no)
+                }
+            };
+            CausalLoop {
+                identifier,
+                polarity,
+                variables: nodes.into_iter().map(|e| variables[e]).collect(),
+                links: links
+                    .into_iter()
+                    .map(|(n, l)| adjacency[n][l].uuid)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domains::umlclass::umlclass_models::{UmlClass, UmlClassDependency};
+
+    fn generate_modeluuid(id: u32) -> ModelUuid {
+        uuid::Uuid::from_u128(id as u128).into()
+    }
+
+    fn new_variable(id: u32) -> ERef<UmlClass> {
+        ERef::new(UmlClass::new(
+            generate_modeluuid(id),
+            "".to_owned(),
+            causalloop_models::VARIABLE.to_owned(),
+            "".to_owned(),
+            false,
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
+    fn new_link(
+        id: u32,
+        polarity: &str,
+        source: &ERef<UmlClass>,
+        target: &ERef<UmlClass>,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassDependency::new(
+            generate_modeluuid(id),
+            polarity.to_owned(),
+            "".to_owned(),
+            source.clone().into(),
+            target.clone().into(),
+            true,
+        ))
+        .into()
+    }
+
+    fn new_diagram(elements: Vec<UmlClassElement>) -> UmlClassDiagram {
+        UmlClassDiagram::new(generate_modeluuid(0), "".to_owned(), elements)
+    }
+
+    #[test]
+    fn test_no_loops() {
+        let a = new_variable(1);
+        let b = new_variable(2);
+        let l = new_link(3, causalloop_models::POSITIVE, &a, &b);
+        let d = new_diagram(vec![a.into(), b.into(), l]);
+
+        assert!(find_loops(&d).is_empty());
+    }
+
+    #[test]
+    fn test_reinforcing_and_balancing() {
+        let population = new_variable(1);
+        let births = new_variable(2);
+        let deaths = new_variable(3);
+        let l1 = new_link(4, causalloop_models::POSITIVE, &births, &population);
+        let l2 = new_link(5, causalloop_models::POSITIVE, &population, &births);
+        let l3 = new_link(6, causalloop_models::POSITIVE, &population, &deaths);
+        let l4 = new_link(7, causalloop_models::NEGATIVE, &deaths, &population);
+        let d = new_diagram(vec![
+            population.into(),
+            births.into(),
+            deaths.into(),
+            l1,
+            l2,
+            l3,
+            l4,
+        ]);
+
+        let loops = find_loops(&d);
+        assert_eq!(loops.len(), 2);
+        assert_eq!(
+            loops
+                .iter()
+                .filter(|e| e.polarity == LoopPolarity::Reinforcing)
+                .count(),
+            1
+        );
+        assert_eq!(
+            loops
+                .iter()
+                .filter(|e| e.polarity == LoopPolarity::Balancing)
+                .count(),
+            1
+        );
+        assert!(
+            loops
+                .iter()
+                .all(|e| e.variables.len() == 2 && e.links.len() == 2)
+        );
+    }
+
+    #[test]
+    fn test_double_negative_is_reinforcing() {
+        let a = new_variable(1);
+        let b = new_variable(2);
+        let c = new_variable(3);
+        let l1 = new_link(4, causalloop_models::NEGATIVE, &a, &b);
+        let l2 = new_link(5, causalloop_models::NEGATIVE, &b, &c);
+        let l3 = new_link(6, causalloop_models::POSITIVE, &c, &a);
+        let d = new_diagram(vec![a.into(), b.into(), c.into(), l1, l2, l3]);
+
+        let loops = find_loops(&d);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].polarity, LoopPolarity::Reinforcing);
+        assert_eq!(loops[0].identifier, "R1");
+        assert_eq!(loops[0].variables.len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_polarity() {
+        let a = new_variable(1);
+        let b = new_variable(2);
+        let l1 = new_link(3, causalloop_models::POSITIVE, &a, &b);
+        let l2 = new_link(4, "", &b, &a);
+        let d = new_diagram(vec![a.into(), b.into(), l1, l2]);
+
+        let loops = find_loops(&d);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].polarity, LoopPolarity::Unknown);
+    }
+}
@@ -0,0 +1,502 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlClassView, new_umlclass_class, new_umlclass_comment,
+        new_umlclass_dependency,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT, ProjectCommand,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        causalloop::causalloop_models,
+        umlclass::{
+            umlclass_controllers::{PartialUmlClassElement, UmlClassRenderStyle},
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance},
+        },
+    },
+};
+use eframe::egui;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+#[derive(Clone, Default)]
+pub struct CausalLoopProfile;
+impl UmlClassProfile for CausalLoopProfile {
+    fn menubar_options_fun(
+        model: &ERef<UmlClassDiagram>,
+        view_uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("Loop Analysis").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::causalloop_analysis::CausalLoopAnalysisTab::new(
+                        model.clone(),
+                        *view_uuid,
+                    ),
+                )),
+            ));
+        }
+        ui.separator();
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct CausalLoopControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<CausalLoopProfile>> for CausalLoopControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<CausalLoopProfile>,
+        UmlClassDiagramAdapter<CausalLoopProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-causalloop"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Causal Loop Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Causal Loop Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<CausalLoopProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            CausalLoopControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<CausalLoopProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Causal loop diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (population_model, population_view) =
+        new_causalloop_variable("Population", egui::Pos2::new(300.0, 200.0));
+    let (births_model, births_view) =
+        new_causalloop_variable("Births", egui::Pos2::new(100.0, 200.0));
+    let (deaths_model, deaths_view) =
+        new_causalloop_variable("Deaths", egui::Pos2::new(500.0, 200.0));
+
+    let (b_p_model, b_p_view) = new_umlclass_dependency(
+        causalloop_models::POSITIVE,
+        "",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(200.0, 130.0))),
+        (births_model.clone().into(), births_view.clone().into()),
+        (
+            population_model.clone().into(),
+            population_view.clone().into(),
+        ),
+    );
+    let (p_b_model, p_b_view) = new_umlclass_dependency(
+        causalloop_models::POSITIVE,
+        "",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(200.0, 270.0))),
+        (
+            population_model.clone().into(),
+            population_view.clone().into(),
+        ),
+        (births_model.clone().into(), births_view.clone().into()),
+    );
+    let (p_d_model, p_d_view) = new_umlclass_dependency(
+        causalloop_models::POSITIVE,
+        "",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(400.0, 130.0))),
+        (
+            population_model.clone().into(),
+            population_view.clone().into(),
+        ),
+        (deaths_model.clone().into(), deaths_view.clone().into()),
+    );
+    let (d_p_model, d_p_view) = new_umlclass_dependency(
+        causalloop_models::NEGATIVE,
+        "",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(400.0, 270.0))),
+        (deaths_model.clone().into(), deaths_view.clone().into()),
+        (
+            population_model.clone().into(),
+            population_view.clone().into(),
+        ),
+    );
+
+    let (r_model, r_view) = new_umlclass_comment(
+        "R1",
+        causalloop_models::LOOP_IDENTIFIER,
+        egui::Pos2::new(200.0, 200.0),
+        egui::Align2::CENTER_CENTER,
+    );
+    let (b_model, b_view) = new_umlclass_comment(
+        "B1",
+        causalloop_models::LOOP_IDENTIFIER,
+        egui::Pos2::new(400.0, 200.0),
+        egui::Align2::CENTER_CENTER,
+    );
+
+    let name = format!("Demo Causal loop diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            population_model.into(),
+            births_model.into(),
+            deaths_model.into(),
+            b_p_model.into(),
+            p_b_model.into(),
+            p_d_model.into(),
+            d_p_model.into(),
+            r_model.into(),
+            b_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            population_view.into(),
+            births_view.into(),
+            deaths_view.into(),
+            b_p_view.into(),
+            p_b_view.into(),
+            p_d_view.into(),
+            d_p_view.into(),
+            r_view.into(),
+            b_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<CausalLoopProfile>,
+        CausalLoopControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<CausalLoopProfile>,
+            UmlClassDiagramAdapter<CausalLoopProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<CausalLoopProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<CausalLoopProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<CausalLoopProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: causalloop_models::POSITIVE.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_dep_0_1(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<CausalLoopProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: causalloop_models::NEGATIVE.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<CausalLoopProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] = &[
+        (0, 0, "+", &class_dep_0_0 as &ClassButtonF),
+        (0, 1, "-", &class_dep_0_1 as &ClassButtonF),
+    ];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let classes = vec![(
+        UmlClassToolStage::Class {
+            name: "Variable".to_owned(),
+            stereotype: causalloop_models::VARIABLE.to_owned(),
+            is_abstract: false,
+            render_style: UmlClassRenderStyle::Class,
+            background_color: MGlobalColor::None,
+        },
+        "Variable",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num1,
+        )),
+    )];
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: causalloop_models::POSITIVE.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Positive Link (+)",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: causalloop_models::NEGATIVE.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Negative Link (-)",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num6,
+            )),
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Elements", classes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: causalloop_models::LOOP_IDENTIFIER.to_owned(),
+                        text: "R1".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Loop Identifier",
+                    None,
+                ),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<CausalLoopProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<CausalLoopProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-causalloop",
+    pretty_name: "Causal loop diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Causal loop diagram (variables, polarity-labeled links, loop identifiers) with loop polarity detection",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_causalloop_variable(
+    name: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<CausalLoopProfile>>) {
+    new_umlclass_class(
+        name,
+        causalloop_models::VARIABLE,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
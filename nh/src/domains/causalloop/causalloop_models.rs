@@ -0,0 +1,7 @@
+pub const VARIABLE: &str = "";
+
+// Link polarities
+pub const POSITIVE: &str = "+";
+pub const NEGATIVE: &str = "-";
+
+pub const LOOP_IDENTIFIER: &str = "loop";
@@ -0,0 +1,2 @@
+pub mod component_controllers;
+pub mod component_models;
@@ -0,0 +1,558 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, new_umlclass_association, new_umlclass_class, new_umlclass_dependency,
+        new_umlclass_package,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        component::component_models,
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct ComponentProfile;
+impl UmlClassProfile for ComponentProfile {}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct ComponentControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<ComponentProfile>> for ComponentControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<ComponentProfile>,
+        UmlClassDiagramAdapter<ComponentProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-component"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Component Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Component Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<ComponentProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            ComponentControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<ComponentProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Component diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (webshop_model, webshop_view) = new_umlclass_class(
+        "Web Shop",
+        component_models::COMPONENT,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(150.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (orders_interface_model, orders_interface_view) = new_umlclass_class(
+        "IOrders",
+        component_models::INTERFACE,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(400.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (orders_model, orders_view) = new_umlclass_class(
+        "Order Service",
+        component_models::COMPONENT,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(650.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (database_model, database_view) = new_umlclass_class(
+        "Order Database",
+        component_models::COMPONENT,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(650.0, 400.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+
+    let (required_model, required_view) = new_umlclass_dependency(
+        component_models::REQUIRED,
+        "",
+        true,
+        None,
+        (webshop_model.clone().into(), webshop_view.clone().into()),
+        (
+            orders_interface_model.clone().into(),
+            orders_interface_view.clone().into(),
+        ),
+    );
+    let (provided_model, provided_view) = new_umlclass_dependency(
+        component_models::PROVIDED,
+        "",
+        false,
+        None,
+        (orders_model.clone().into(), orders_view.clone().into()),
+        (
+            orders_interface_model.clone().into(),
+            orders_interface_view.clone().into(),
+        ),
+    );
+    let (connector_model, connector_view) = new_umlclass_association(
+        "",
+        "",
+        "",
+        "",
+        None,
+        (orders_model.clone().into(), orders_view.clone().into()),
+        (database_model.clone().into(), database_view.clone().into()),
+    );
+
+    let (subsystem, subsystem_view) = new_umlclass_package(
+        "Ordering",
+        component_models::SUBSYSTEM,
+        UmlClassPackageKind::Package,
+        egui::Rect::from_x_y_ranges(550.0..=800.0, 100.0..=500.0),
+    );
+    {
+        let mut w = subsystem_view.write();
+        let subsystem_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [orders_view.into(), database_view.into()] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: subsystem_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo Component diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            webshop_model.into(),
+            orders_interface_model.into(),
+            subsystem.into(),
+            required_model.into(),
+            provided_model.into(),
+            connector_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            webshop_view.into(),
+            orders_interface_view.into(),
+            subsystem_view.into(),
+            required_view.into(),
+            provided_view.into(),
+            connector_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<ComponentProfile>,
+        ComponentControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<ComponentProfile>,
+            UmlClassDiagramAdapter<ComponentProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_connector(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_dependency(
+        m: ERef<UmlClass>,
+        stereotype: &str,
+        target_arrow_open: bool,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open,
+            stereotype: stereotype.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_provided(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    ) {
+        class_dependency(m, component_models::PROVIDED, false)
+    }
+    fn class_required(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    ) {
+        class_dependency(m, component_models::REQUIRED, true)
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ComponentProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] = &[
+        (0, 0, "\\", &class_connector as &ClassButtonF),
+        (0, 1, "⊸", &class_provided as &ClassButtonF),
+        (1, 0, "⇢", &class_required as &ClassButtonF),
+    ];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let classes = vec![
+        (
+            UmlClassToolStage::Class {
+                name: "Component".to_owned(),
+                stereotype: component_models::COMPONENT.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Component",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "IInterface".to_owned(),
+                stereotype: component_models::INTERFACE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Interface",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+    ];
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: false,
+                    stereotype: component_models::PROVIDED.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Provided Interface",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: component_models::REQUIRED.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Required Interface",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: "".to_owned(),
+                    source_multiplicity: "".to_owned(),
+                    target_multiplicity: "".to_owned(),
+                },
+            },
+            "Assembly Connector",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num6,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: component_models::DELEGATE.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Delegation Connector",
+            None,
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Components", classes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::PackageStart {
+                        name: "Subsystem".to_owned(),
+                        stereotype: component_models::SUBSYSTEM.to_owned(),
+                        kind: UmlClassPackageKind::Package,
+                    },
+                    "Subsystem",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num8,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<ComponentProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<ComponentProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-component",
+    pretty_name: "Component diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Unified Modeling Language",
+        description: "Component diagram (components, provided/required interfaces, connectors)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
@@ -0,0 +1,7 @@
+pub const COMPONENT: &str = "component";
+pub const INTERFACE: &str = "interface";
+pub const SUBSYSTEM: &str = "subsystem";
+
+pub const PROVIDED: &str = "realize";
+pub const REQUIRED: &str = "use";
+pub const DELEGATE: &str = "delegate";
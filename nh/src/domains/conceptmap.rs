@@ -0,0 +1,5 @@
+pub mod conceptmap_controllers;
+pub mod conceptmap_models;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod conceptmap_rdf;
@@ -0,0 +1,458 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlUseCaseOutline, UmlUseCaseView, new_uml_usecase,
+        new_umlclass_dependency,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        canvas,
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, LiveLayoutCommand, MGlobalColor,
+            MultiDiagramController, PositionNoT, TextExportFormat,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        conceptmap::conceptmap_models,
+        umlclass::{
+            umlclass_controllers::PartialUmlClassElement,
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlUseCase},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct ConceptMapProfile;
+impl UmlClassProfile for ConceptMapProfile {
+    fn menubar_options_fun(
+        _model: &ERef<UmlClassDiagram>,
+        view_uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<crate::common::controller::ProjectCommand>,
+    ) {
+        LiveLayoutCommand::show_menu(view_uuid, ui, commands);
+        ui.separator();
+    }
+
+    fn dependency_line_type(_stereotype: &str) -> canvas::LineType {
+        canvas::LineType::Solid
+    }
+
+    fn usecase_outline(stereotype: &str) -> UmlUseCaseOutline {
+        match stereotype {
+            conceptmap_models::CONCEPT => UmlUseCaseOutline::RoundedRectangle,
+            _ => UmlUseCaseOutline::Ellipse,
+        }
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct ConceptMapControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<ConceptMapProfile>> for ConceptMapControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<ConceptMapProfile>,
+        UmlClassDiagramAdapter<ConceptMapProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-conceptmap"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Concept Map").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Concept Map".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use crate::domains::rdf::rdf_serialization::{NTRIPLES_FORMAT, TURTLE_FORMAT};
+            &[TURTLE_FORMAT, NTRIPLES_FORMAT]
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            &[]
+        }
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use crate::domains::rdf::rdf_serialization::{
+                NTRIPLES_FORMAT, TURTLE_FORMAT, ntriples, turtle,
+            };
+            let rdf = super::conceptmap_rdf::rdf_diagram(&self.model.read());
+            match *format {
+                TURTLE_FORMAT => turtle(&rdf).ok(),
+                NTRIPLES_FORMAT => ntriples(&rdf).ok(),
+                _ => None,
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = format;
+            None
+        }
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<ConceptMapProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            ConceptMapControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<ConceptMapProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Concept Map {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (plants_model, plants_view) = new_concept("Plants", egui::Pos2::new(300.0, 200.0));
+    let (light_model, light_view) = new_concept("Sunlight", egui::Pos2::new(100.0, 80.0));
+    let (oxygen_model, oxygen_view) = new_concept("Oxygen", egui::Pos2::new(500.0, 80.0));
+    let (animals_model, animals_view) = new_concept("Animals", egui::Pos2::new(500.0, 340.0));
+
+    let (need_model, need_view) = new_proposition(
+        "need",
+        (plants_model.clone(), plants_view.clone()),
+        (light_model.clone(), light_view.clone()),
+    );
+    let (produce_model, produce_view) = new_proposition(
+        "produce",
+        (plants_model.clone(), plants_view.clone()),
+        (oxygen_model.clone(), oxygen_view.clone()),
+    );
+    let (breathe_model, breathe_view) = new_proposition(
+        "breathe",
+        (animals_model.clone(), animals_view.clone()),
+        (oxygen_model.clone(), oxygen_view.clone()),
+    );
+    let (eat_model, eat_view) = new_proposition(
+        "eat",
+        (animals_model.clone(), animals_view.clone()),
+        (plants_model.clone(), plants_view.clone()),
+    );
+
+    let name = format!("Demo Concept Map {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            plants_model.into(),
+            light_model.into(),
+            oxygen_model.into(),
+            animals_model.into(),
+            need_model.into(),
+            produce_model.into(),
+            breathe_model.into(),
+            eat_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            plants_view.into(),
+            light_view.into(),
+            oxygen_view.into(),
+            animals_view.into(),
+            need_view.into(),
+            produce_view.into(),
+            breathe_view.into(),
+            eat_view.into(),
+        ],
+    )
+}
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<ConceptMapProfile>,
+        ConceptMapControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<ConceptMapProfile>,
+            UmlClassDiagramAdapter<ConceptMapProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ConceptMapProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ConceptMapProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ConceptMapProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: conceptmap_models::PROPOSITION.to_owned(),
+            name: "relates to".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ConceptMapProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let palette_items = vec![
+        (
+            "Concepts",
+            vec![(
+                UmlClassToolStage::UseCase {
+                    name: "Concept".to_owned(),
+                    stereotype: conceptmap_models::CONCEPT.to_owned(),
+                    is_abstract: false,
+                    background_color: MGlobalColor::None,
+                },
+                "Concept",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Num1,
+                )),
+            )],
+        ),
+        (
+            "Propositions",
+            vec![(
+                UmlClassToolStage::LinkStart {
+                    link_type: LinkType::Dependency {
+                        target_arrow_open: true,
+                        stereotype: conceptmap_models::PROPOSITION.to_owned(),
+                        name: "relates to".to_owned(),
+                    },
+                },
+                "Proposition",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Num2,
+                )),
+            )],
+        ),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a note".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Note",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Note Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<ConceptMapProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<ConceptMapProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-conceptmap",
+    pretty_name: "Concept Map",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Concept map of free text concepts connected by labeled propositions, exportable as RDF",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_concept(
+    name: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlUseCase>, ERef<UmlUseCaseView<ConceptMapProfile>>) {
+    new_uml_usecase(
+        name,
+        conceptmap_models::CONCEPT,
+        false,
+        position,
+        MGlobalColor::None,
+    )
+}
+
+type ConceptRefs = (ERef<UmlUseCase>, ERef<UmlUseCaseView<ConceptMapProfile>>);
+
+fn new_proposition(
+    linking_phrase: &str,
+    source: ConceptRefs,
+    target: ConceptRefs,
+) -> (
+    ERef<super::super::umlclass::umlclass_models::UmlClassDependency>,
+    impl Into<UmlClassElementView<ConceptMapProfile>>,
+) {
+    new_umlclass_dependency(
+        conceptmap_models::PROPOSITION,
+        linking_phrase,
+        true,
+        None,
+        (source.0.into(), source.1.into()),
+        (target.0.into(), target.1.into()),
+    )
+}
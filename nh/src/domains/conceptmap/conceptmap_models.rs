@@ -0,0 +1,5 @@
+// Concepts are use cases, drawn as rounded rectangles
+pub const CONCEPT: &str = "concept";
+
+// Propositions are dependencies named by their linking phrase
+pub const PROPOSITION: &str = "";
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use crate::{
+    common::{eref::ERef, uuid::ModelUuid},
+    domains::{
+        rdf::rdf_models::{RdfDiagram, RdfElement, RdfLiteral, RdfNode, RdfPredicate},
+        umlclass::umlclass_models::{UmlClassAssociable, UmlClassDiagram, UmlClassElement},
+    },
+};
+
+pub const CONCEPT_NAMESPACE: &str = "http://example.org/conceptmap/";
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+/// Predicate of propositions without a linking phrase
+const RELATED_TO: &str = "relatedTo";
+
+/// Local name of an IRI, with whitespace turned into underscores
+/// and characters outside of the unreserved set percent-encoded
+fn local_name(text: &str) -> String {
+    let mut out = String::new();
+    for word in text.split_whitespace() {
+        if !out.is_empty() {
+            out.push('_');
+        }
+        for b in word.bytes() {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                out.push(b as char);
+            } else {
+                out.push_str(&format!("%{:02X}", b));
+            }
+        }
+    }
+    out
+}
+
+/// Converts concepts to nodes labeled with their text, and propositions to predicates.
+///
+/// Concepts with the same text become the same node, so that the triples can be merged
+/// with those of other concept maps. Only top-level elements are converted.
+pub fn rdf_diagram(diagram: &UmlClassDiagram) -> RdfDiagram {
+    let mut nodes = HashMap::<ModelUuid, ERef<RdfNode>>::new();
+    let mut by_iri = HashMap::<String, ERef<RdfNode>>::new();
+    let mut elements = Vec::<RdfElement>::new();
+
+    for e in &diagram.contained_elements {
+        let UmlClassElement::UseCase(inner) = e else {
+            continue;
+        };
+        let concept = inner.read();
+        let iri = format!("{}{}", CONCEPT_NAMESPACE, local_name(&concept.name));
+        let node = match by_iri.get(&iri) {
+            Some(node) => node.clone(),
+            None => {
+                let node = ERef::new(RdfNode::new(ModelUuid::now_v7(), iri.clone()));
+                let label = ERef::new(RdfLiteral::new(
+                    ModelUuid::now_v7(),
+                    (*concept.name).clone(),
+                    XSD_STRING.to_owned(),
+                    String::new(),
+                ));
+                let predicate = ERef::new(RdfPredicate::new(
+                    ModelUuid::now_v7(),
+                    RDFS_LABEL.to_owned(),
+                    node.clone(),
+                    label.clone().into(),
+                ));
+                elements.extend([node.clone().into(), label.into(), predicate.into()]);
+                by_iri.insert(iri, node.clone());
+                node
+            }
+        };
+        nodes.insert(*concept.uuid, node);
+    }
+
+    for e in &diagram.contained_elements {
+        let UmlClassElement::Dependency(inner) = e else {
+            continue;
+        };
+        let proposition = inner.read();
+        let (UmlClassAssociable::UseCase(source), UmlClassAssociable::UseCase(target)) =
+            (&proposition.source, &proposition.target)
+        else {
+            continue;
+        };
+        let (Some(source), Some(target)) = (
+            nodes.get(&*source.read().uuid),
+            nodes.get(&*target.read().uuid),
+        ) else {
+            continue;
+        };
+        let linking_phrase = local_name(&proposition.name);
+        let predicate = ERef::new(RdfPredicate::new(
+            ModelUuid::now_v7(),
+            format!(
+                "{}{}",
+                CONCEPT_NAMESPACE,
+                if linking_phrase.is_empty() {
+                    RELATED_TO
+                } else {
+                    linking_phrase.as_str()
+                }
+            ),
+            source.clone(),
+            target.clone().into(),
+        ));
+        elements.push(predicate.into());
+    }
+
+    let mut rdf = RdfDiagram::new(ModelUuid::now_v7(), (*diagram.name).clone(), elements);
+    rdf.prefixes
+        .push(("cm".to_owned(), CONCEPT_NAMESPACE.to_owned()));
+    rdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{
+        conceptmap::conceptmap_models,
+        rdf::rdf_serialization::ntriples,
+        umlclass::umlclass_models::{UmlClassDependency, UmlUseCase},
+    };
+
+    fn new_concept(name: &str) -> ERef<UmlUseCase> {
+        ERef::new(UmlUseCase::new(
+            ModelUuid::now_v7(),
+            name.to_owned(),
+            conceptmap_models::CONCEPT.to_owned(),
+            false,
+        ))
+    }
+
+    fn new_proposition(
+        name: &str,
+        source: &ERef<UmlUseCase>,
+        target: &ERef<UmlUseCase>,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassDependency::new(
+            ModelUuid::now_v7(),
+            conceptmap_models::PROPOSITION.to_owned(),
+            name.to_owned(),
+            source.clone().into(),
+            target.clone().into(),
+            true,
+        ))
+        .into()
+    }
+
+    #[test]
+    fn propositions_become_triples() {
+        let plants = new_concept("Green plants");
+        let light = new_concept("Sunlight");
+        let light_again = new_concept("Sunlight");
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Photosynthesis".to_owned(),
+            vec![
+                plants.clone().into(),
+                light.clone().into(),
+                light_again.clone().into(),
+                new_proposition("need", &plants, &light),
+                new_proposition("", &light_again, &plants),
+            ],
+        );
+
+        let document = ntriples(&rdf_diagram(&diagram)).unwrap();
+        let lines: Vec<_> = document.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.contains(
+            &"<http://example.org/conceptmap/Green_plants> <http://example.org/conceptmap/need> <http://example.org/conceptmap/Sunlight>."
+        ));
+        assert!(lines.contains(
+            &"<http://example.org/conceptmap/Sunlight> <http://example.org/conceptmap/relatedTo> <http://example.org/conceptmap/Green_plants>."
+        ));
+        assert!(
+            lines
+                .iter()
+                .any(|e| e.starts_with("<http://example.org/conceptmap/Green_plants> <http://www.w3.org/2000/01/rdf-schema#label> \"Green plants\""))
+        );
+    }
+
+    #[test]
+    fn local_names_are_escaped() {
+        assert_eq!(local_name("  is part\tof "), "is_part_of");
+        assert_eq!(local_name("a/b?"), "a%2Fb%3F");
+        assert_eq!(local_name("čaj"), "%C4%8Daj");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::{canvas::ColorProfile, controller::TextExportFormat, csv};
 use crate::egui;
 
 pub const EXTERNAL_ROLE_BACKGROUND: egui::Color32 = egui::Color32::LIGHT_GRAY;
@@ -7,6 +8,12 @@ pub const PERFORMA_DETAIL: egui::Color32 = egui::Color32::RED;
 pub const INFORMA_DETAIL: egui::Color32 = egui::Color32::from_rgb(0, 175, 0);
 pub const FORMA_DETAIL: egui::Color32 = egui::Color32::BLUE;
 
+/// Dark profile of DEMO diagrams, with the external role fill darkened for the light text
+pub const DARK_COLOR_PROFILE: ColorProfile = ColorProfile::DARK.with_fills(&[(
+    EXTERNAL_ROLE_BACKGROUND,
+    egui::Color32::from_rgb(80, 80, 80),
+)]);
+
 #[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, Default)]
 pub enum DemoTransactionKind {
     #[default]
@@ -26,3 +33,118 @@ impl DemoTransactionKind {
         }
     }
 }
+
+pub const TPT_CSV_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Transaction Product Table (CSV)",
+    extensions: &["csv"],
+};
+
+pub const TPT_MARKDOWN_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Transaction Product Table (Markdown)",
+    extensions: &["md"],
+};
+
+/// Row of the Transaction Product Table, pairing a transaction kind with its product kind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionProductRow {
+    pub transaction_kind_id: String,
+    pub transaction_kind: String,
+    pub product_kind_id: String,
+    pub product_kind: String,
+}
+
+impl TransactionProductRow {
+    /// Row for a transaction kind, with the product kind numbered the same way
+    pub fn from_transaction_kind(identifier: &str, name: &str) -> Self {
+        Self {
+            transaction_kind_id: identifier.to_owned(),
+            transaction_kind: name.to_owned(),
+            product_kind_id: format!("PK{}", identifier_number(identifier)),
+            product_kind: String::new(),
+        }
+    }
+
+    /// Row for a product kind, with the transaction kind numbered the same way
+    pub fn from_product_kind(identifier: &str, formulation: &str) -> Self {
+        let number = identifier_number(identifier);
+        Self {
+            transaction_kind_id: format!("TK{}", number),
+            transaction_kind: String::new(),
+            product_kind_id: format!("PK{}", number),
+            product_kind: formulation.to_owned(),
+        }
+    }
+}
+
+/// Number part of identifiers such as `TK01`, `PK01` or just `01`
+fn identifier_number(identifier: &str) -> &str {
+    identifier
+        .trim()
+        .trim_start_matches(|c: char| c.is_alphabetic())
+}
+
+fn sorted_rows(mut rows: Vec<TransactionProductRow>) -> Vec<TransactionProductRow> {
+    rows.sort_by(|a, b| a.transaction_kind_id.cmp(&b.transaction_kind_id));
+    rows
+}
+
+pub fn transaction_product_table_csv(rows: Vec<TransactionProductRow>) -> String {
+    let mut out = csv::join_line(&[
+        "transaction kind id",
+        "transaction kind",
+        "product kind id",
+        "product kind",
+    ]);
+    out.push('\n');
+    for r in sorted_rows(rows) {
+        out.push_str(&csv::join_line(&[
+            &r.transaction_kind_id,
+            &r.transaction_kind,
+            &r.product_kind_id,
+            &r.product_kind,
+        ]));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn transaction_product_table_markdown(rows: Vec<TransactionProductRow>) -> String {
+    fn cell(id: &str, text: &str) -> String {
+        format!("{} {}", id, text).trim().replace('|', "\\|")
+    }
+
+    let mut out = "| transaction kind | product kind |\n|---|---|\n".to_owned();
+    for r in sorted_rows(rows) {
+        out.push_str(&format!(
+            "| {} | {} |\n",
+            cell(&r.transaction_kind_id, &r.transaction_kind),
+            cell(&r.product_kind_id, &r.product_kind),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_product_table_is_listed() {
+        let rows = vec![
+            TransactionProductRow::from_transaction_kind("TK10", "sale payment"),
+            TransactionProductRow::from_product_kind("01", "[SALE] is completed"),
+        ];
+        assert_eq!(
+            transaction_product_table_markdown(rows.clone()),
+            "| transaction kind | product kind |\n|---|---|\n\
+             | TK01 | PK01 [SALE] is completed |\n\
+             | TK10 sale payment | PK10 |\n"
+        );
+        assert_eq!(
+            transaction_product_table_csv(rows),
+            "transaction kind id,transaction kind,product kind id,product kind\n\
+             TK01,,PK01,[SALE] is completed\n\
+             TK10,sale payment,PK10,\n"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use super::super::demo::{
     DemoTransactionKind, EXTERNAL_ROLE_BACKGROUND, FORMA_DETAIL, INFORMA_DETAIL,
-    INTERNAL_ROLE_BACKGROUND, PERFORMA_DETAIL,
+    INTERNAL_ROLE_BACKGROUND, PERFORMA_DETAIL, TPT_CSV_FORMAT, TPT_MARKDOWN_FORMAT,
+    transaction_product_table_csv, transaction_product_table_markdown,
 };
 use super::democsd_models::{
     DemoCsdDiagram, DemoCsdElement, DemoCsdLink, DemoCsdLinkType, DemoCsdPackage,
@@ -13,8 +14,8 @@ use crate::common::controller::{
     ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
     GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, MGlobalColor, Model,
     MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand, PropertiesStatus,
-    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus, Tool,
-    ToolPalette, TryMerge, View,
+    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus,
+    TextExportFormat, Tool, ToolPalette, TryMerge, View,
 };
 use crate::common::entity::{Entity, EntityUuid};
 use crate::common::eref::ERef;
@@ -60,13 +61,14 @@ type LinkViewT = MulticonnectionView<DemoCsdDomain, DemoCsdLinkAdapter>;
 #[derive(Clone, Copy, Debug)]
 pub enum DemoCsdOrdinalMovement {}
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum DemoCsdPropChange {
     NameChange(Arc<String>),
     IdentifierChange(Arc<String>),
     TransactorSelfactivatingChange(bool),
     TransactorInternalChange(bool),
     TransactorCompositeChange(bool),
+    TransactorResponsibilitiesChange(Arc<String>),
 
     TransactionKindChange(DemoTransactionKind),
     TransactionMultipleChange(bool),
@@ -117,6 +119,10 @@ impl TryMerge for DemoCsdPropChange {
             (Self::NameChange(_), newer @ Self::NameChange(_))
             | (Self::IdentifierChange(_), newer @ Self::IdentifierChange(_))
             | (Self::LinkMultiplicityChange(_), newer @ Self::LinkMultiplicityChange(_))
+            | (
+                Self::TransactorResponsibilitiesChange(_),
+                newer @ Self::TransactorResponsibilitiesChange(_),
+            )
             | (Self::CommentChange(_), newer @ Self::CommentChange(_)) => Some(newer.clone()),
             _ => None,
         }
@@ -254,6 +260,22 @@ impl ControllerAdapter<DemoCsdDomain> for DemoCsdControllerAdapter {
         }
         None
     }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[TPT_CSV_FORMAT, TPT_MARKDOWN_FORMAT]
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        let rows = super::democsd_models::transaction_product_rows(&self.model.read());
+        match *format {
+            TPT_CSV_FORMAT => Some(transaction_product_table_csv(rows)),
+            TPT_MARKDOWN_FORMAT => Some(transaction_product_table_markdown(rows)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(
@@ -384,6 +406,13 @@ impl DiagramAdapter<DemoCsdDomain> for DemoCsdDiagramAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        if dark {
+            super::super::demo::DARK_COLOR_PROFILE
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -1070,7 +1099,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(DemoCsdSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
     }))
@@ -1782,6 +1811,7 @@ fn new_democsd_transactor_view(
         internal_buffer: m.internal,
         composite_buffer: m.composite,
         transaction_selfactivating_buffer: m.transaction_selfactivating,
+        responsibilities_buffer: (*m.responsibilities).clone(),
         comment_buffer: (*m.comment).clone(),
 
         dragged_shape: None,
@@ -1865,6 +1895,8 @@ pub struct DemoCsdTransactorView {
     #[nh_context_serde(skip_and_default)]
     transaction_selfactivating_buffer: bool,
     #[nh_context_serde(skip_and_default)]
+    responsibilities_buffer: String,
+    #[nh_context_serde(skip_and_default)]
     comment_buffer: String,
 
     #[nh_context_serde(skip_and_default)]
@@ -2014,6 +2046,18 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
             ));
         }
 
+        if ui
+            .labeled_text_edit_multiline("Responsibilities:", &mut self.responsibilities_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                DemoCsdPropChange::TransactorResponsibilitiesChange(Arc::new(
+                    self.responsibilities_buffer.clone(),
+                )),
+            ));
+        }
+
         if ui
             .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
             .changed()
@@ -2054,6 +2098,7 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
         self.internal_buffer = model.internal;
         self.composite_buffer = model.composite;
         self.transaction_selfactivating_buffer = model.transaction_selfactivating;
+        self.responsibilities_buffer = (*model.responsibilities).clone();
         self.comment_buffer = (*model.comment).clone();
     }
 
@@ -2098,10 +2143,26 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
             )
         });
 
+        // Responsibilities compartment below the name, separated by a line
+        let responsibilities_size = if read.responsibilities.is_empty() {
+            egui::Vec2::ZERO
+        } else {
+            canvas
+                .measure_text(
+                    self.position,
+                    egui::Align2::LEFT_TOP,
+                    &read.responsibilities,
+                    canvas::CLASS_ITEM_FONT_SIZE,
+                )
+                .size()
+                + egui::Vec2::new(0.0, 5.0)
+        };
+
         let max_row = tx_name_bounds
             .width()
             .max(identifier_bounds.width())
             .max(name_bounds.width())
+            .max(responsibilities_size.x)
             .max(2.0 * radius);
         let box_y_offset = if self.transaction_view.is_some() {
             if read.transaction_selfactivating {
@@ -2127,7 +2188,8 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
                 } * canvas::CLASS_MIDDLE_FONT_SIZE
                     + tx_name_bounds.height()
                     + identifier_bounds.height()
-                    + name_bounds.height(),
+                    + name_bounds.height()
+                    + responsibilities_size.y,
             ),
         )
         .expand(5.0);
@@ -2169,6 +2231,25 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
             egui::Color32::BLACK,
         );
 
+        if !read.responsibilities.is_empty() {
+            let y = self.position.y + name_offset.y + name_bounds.height() + 2.5;
+            canvas.draw_line(
+                [
+                    egui::Pos2::new(self.bounds_rect.min.x, y),
+                    egui::Pos2::new(self.bounds_rect.max.x, y),
+                ],
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            canvas.draw_text(
+                egui::Pos2::new(self.bounds_rect.min.x + 5.0, y + 2.5),
+                egui::Align2::LEFT_TOP,
+                &read.responsibilities,
+                canvas::CLASS_ITEM_FONT_SIZE,
+                egui::Color32::BLACK,
+            );
+        }
+
         // If tx is present, draw it 4 rows above the position
         if let UFOption::Some(t) = &self.transaction_view {
             let res = t
@@ -2411,7 +2492,7 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
                     tool.add_section(self.model());
 
                     if self.transaction_view.as_ref().is_none() {
-                        tool.add_position(*event.mouse_position());
+                        tool.add_position(ehc.snap_manager.snap_to_grid(*event.mouse_position()));
 
                         if let Ok(esm) = tool.try_flush(q, &self.uuid, 0, None, commands)
                             && ehc
@@ -2531,7 +2612,9 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
             }
             InsensitiveCommand::ResizeElementsBy(..)
             | InsensitiveCommand::ResizeElementTo(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::AddDependency {
                 target,
                 bucket,
@@ -2663,6 +2746,15 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
                             ));
                             model.composite = *value;
                         }
+                        DemoCsdPropChange::TransactorResponsibilitiesChange(responsibilities) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                DemoCsdPropChange::TransactorResponsibilitiesChange(
+                                    model.responsibilities.clone(),
+                                ),
+                            ));
+                            model.responsibilities = responsibilities.clone();
+                        }
                         DemoCsdPropChange::CommentChange(comment) => {
                             undo_accumulator.push(InsensitiveCommand::PropertyChange(
                                 std::iter::once(*self.uuid).collect(),
@@ -2768,6 +2860,7 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactorView {
             internal_buffer: self.internal_buffer,
             composite_buffer: self.composite_buffer,
             transaction_selfactivating_buffer: self.transaction_selfactivating_buffer,
+            responsibilities_buffer: self.responsibilities_buffer.clone(),
             comment_buffer: self.comment_buffer.clone(),
             dragged_shape: None,
             highlight: self.highlight,
@@ -3296,7 +3389,9 @@ impl ElementControllerGen2<DemoCsdDomain> for DemoCsdTransactionView {
             | InsensitiveCommand::DeleteSpecificElements(..)
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
-            | InsensitiveCommand::ArrangeSpecificElements(..) => {}
+            | InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
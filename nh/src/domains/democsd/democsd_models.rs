@@ -8,7 +8,7 @@ use crate::common::eref::ERef;
 use crate::common::search::FullTextSearchable;
 use crate::common::ufoption::UFOption;
 use crate::common::uuid::ModelUuid;
-use crate::domains::demo::DemoTransactionKind;
+use crate::domains::demo::{DemoTransactionKind, TransactionProductRow};
 use std::collections::HashSet;
 use std::{collections::HashMap, sync::Arc};
 
@@ -190,6 +190,23 @@ fn enumerate_elements(e: &DemoCsdElement, into: &mut HashMap<ModelUuid, DemoCsdE
     }
 }
 
+/// Transaction kinds of the diagram, their product kinds are left to be formulated
+pub fn transaction_product_rows(d: &DemoCsdDiagram) -> Vec<TransactionProductRow> {
+    enumerate_diagram(d)
+        .values()
+        .filter_map(|e| match e {
+            DemoCsdElement::DemoCsdTransaction(inner) => {
+                let r = inner.read();
+                Some(TransactionProductRow::from_transaction_kind(
+                    &r.identifier,
+                    &r.name,
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn transitive_closure(
     d: &DemoCsdDiagram,
     mut when_deleting: HashSet<ModelUuid>,
@@ -505,6 +522,9 @@ pub struct DemoCsdTransactor {
     #[nh_context_serde(entity)]
     pub transaction: UFOption<ERef<DemoCsdTransaction>>,
     pub transaction_selfactivating: bool,
+    /// Responsibilities of the actor role, one per line
+    #[nh_context_serde(default)]
+    pub responsibilities: Arc<String>,
 
     pub comment: Arc<String>,
 }
@@ -528,6 +548,7 @@ impl DemoCsdTransactor {
             composite,
             transaction: transaction.into(),
             transaction_selfactivating,
+            responsibilities: Arc::new("".to_owned()),
 
             comment: Arc::new("".to_owned()),
         }
@@ -541,6 +562,7 @@ impl DemoCsdTransactor {
             composite: self.composite,
             transaction: self.transaction.clone(),
             transaction_selfactivating: self.transaction_selfactivating,
+            responsibilities: self.responsibilities.clone(),
             comment: self.comment.clone(),
         })
     }
@@ -614,6 +636,7 @@ impl FullTextSearchable for DemoCsdTransactor {
                 &self.uuid.to_string(),
                 &self.identifier,
                 &self.name,
+                &self.responsibilities,
                 &self.comment,
             ],
         );
@@ -1,6 +1,7 @@
 use super::super::demo::{
     DemoTransactionKind, EXTERNAL_ROLE_BACKGROUND, FORMA_DETAIL, INFORMA_DETAIL,
-    INTERNAL_ROLE_BACKGROUND, PERFORMA_DETAIL,
+    INTERNAL_ROLE_BACKGROUND, PERFORMA_DETAIL, TPT_CSV_FORMAT, TPT_MARKDOWN_FORMAT,
+    transaction_product_table_csv, transaction_product_table_markdown,
 };
 use super::demoofd_models::{
     DemoOfdDiagram, DemoOfdElement, DemoOfdEntityType, DemoOfdEventType, DemoOfdPackage,
@@ -13,8 +14,8 @@ use crate::common::controller::{
     ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
     GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, MGlobalColor, Model,
     MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand, PropertiesStatus,
-    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus, Tool,
-    ToolPalette, TryMerge, View,
+    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus,
+    TextExportFormat, Tool, ToolPalette, TryMerge, View,
 };
 use crate::common::entity::{Entity, EntityUuid};
 use crate::common::eref::ERef;
@@ -67,7 +68,7 @@ type ExclusionViewT = MulticonnectionView<DemoOfdDomain, DemoOfdExclusionAdapter
 #[derive(Clone, Copy, Debug)]
 pub enum DemoOfdOrdinalMovement {}
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum DemoOfdPropChange {
     NameChange(Arc<String>),
 
@@ -294,6 +295,22 @@ impl ControllerAdapter<DemoOfdDomain> for DemoOfdControllerAdapter {
         }
         None
     }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[TPT_CSV_FORMAT, TPT_MARKDOWN_FORMAT]
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        let rows = super::demoofd_models::transaction_product_rows(&self.model.read());
+        match *format {
+            TPT_CSV_FORMAT => Some(transaction_product_table_csv(rows)),
+            TPT_MARKDOWN_FORMAT => Some(transaction_product_table_markdown(rows)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(
@@ -489,6 +506,13 @@ impl DiagramAdapter<DemoOfdDomain> for DemoOfdDiagramAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        if dark {
+            super::super::demo::DARK_COLOR_PROFILE
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -1176,7 +1200,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(DemoOfdSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
     }))
@@ -2818,7 +2842,9 @@ impl ElementControllerGen2<DemoOfdDomain> for DemoOfdEntityView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -3594,7 +3620,9 @@ impl ElementControllerGen2<DemoOfdDomain> for DemoOfdEventView {
                 recurse!();
             }
             InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::DeleteSpecificElements(uuids, _) => {
                 if let Some(e) = self.specialization_view.as_ref()
                     && uuids.contains(&*e.read().uuid)
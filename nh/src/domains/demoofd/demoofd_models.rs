@@ -16,7 +16,7 @@ use crate::{
         uuid::ModelUuid,
         views::multiconnection_view::MULTICONNECTION_SOURCE_BUCKET,
     },
-    domains::demo::DemoTransactionKind,
+    domains::demo::{DemoTransactionKind, TransactionProductRow},
 };
 
 #[derive(
@@ -277,6 +277,23 @@ fn enumerate_elements(e: &DemoOfdElement, into: &mut HashMap<ModelUuid, DemoOfdE
     }
 }
 
+/// Product kinds of the event types, formulated over their base entity types
+pub fn transaction_product_rows(d: &DemoOfdDiagram) -> Vec<TransactionProductRow> {
+    enumerate_diagram(d)
+        .values()
+        .filter_map(|e| match e {
+            DemoOfdElement::DemoOfdEventType(inner) => {
+                let r = inner.read();
+                Some(TransactionProductRow::from_product_kind(
+                    &r.identifier,
+                    &format!("[{}] {}", r.base_entity_type.read().name, r.name),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn transitive_closure(
     d: &DemoOfdDiagram,
     mut when_deleting: HashSet<ModelUuid>,
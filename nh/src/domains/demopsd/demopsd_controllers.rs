@@ -77,7 +77,7 @@ impl DemoPsdOrdinalMovement {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum DemoPsdPropChange {
     NameChange(Arc<String>),
     IdentifierChange(Arc<String>),
@@ -566,6 +566,13 @@ impl DiagramAdapter<DemoPsdDomain> for DemoPsdDiagramAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        if dark {
+            super::super::demo::DARK_COLOR_PROFILE
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -1157,7 +1164,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(DemoPsdSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
     }))
@@ -2546,7 +2553,7 @@ impl ElementControllerGen2<DemoPsdDomain> for DemoPsdTransactionView {
                         && !matches!(tool.initial_stage, DemoPsdToolStage::Fact { .. }))
                         || quadrant != egui::Align2::CENTER_CENTER
                     {
-                        tool.add_position(pos);
+                        tool.add_position(ehc.snap_manager.snap_to_grid(pos));
                         let quadrant_no = match quadrant {
                             egui::Align2::CENTER_CENTER => DemoPsdTransaction::CENTER_BUCKET,
                             egui::Align2::LEFT_TOP => DemoPsdTransaction::BEFORE_INITIATOR_BUCKET,
@@ -2960,7 +2967,9 @@ impl ElementControllerGen2<DemoPsdDomain> for DemoPsdTransactionView {
                 }
                 recurse!();
             }
-            InsensitiveCommand::ArrangeSpecificElements(..) => {}
+            InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::MoveOrdinal(uuids, direction) => {
                 let mut undo_uuids = HashSet::new();
                 match direction {
@@ -3747,7 +3756,9 @@ impl ElementControllerGen2<DemoPsdDomain> for DemoPsdFactView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -4220,7 +4231,9 @@ impl ElementControllerGen2<DemoPsdDomain> for DemoPsdActView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -0,0 +1,2 @@
+pub mod deployment_controllers;
+pub mod deployment_models;
@@ -0,0 +1,528 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, new_umlclass_association, new_umlclass_class, new_umlclass_dependency,
+        new_umlclass_package,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        deployment::deployment_models,
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct DeploymentProfile;
+impl UmlClassProfile for DeploymentProfile {}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct DeploymentControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<DeploymentProfile>> for DeploymentControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<DeploymentProfile>,
+        UmlClassDiagramAdapter<DeploymentProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-deployment"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Deployment Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Deployment Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<DeploymentProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            DeploymentControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<DeploymentProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Deployment diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (client_model, client_view) = new_umlclass_class(
+        "Client PC",
+        deployment_models::DEVICE,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(150.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (server_model, server_view) = new_umlclass_class(
+        "Application Server",
+        deployment_models::EXECUTION_ENVIRONMENT,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(450.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (artifact_model, artifact_view) = new_umlclass_class(
+        "shop.war",
+        deployment_models::ARTIFACT,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(450.0, 400.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+
+    let (path_model, path_view) = new_umlclass_association(
+        "",
+        "HTTPS",
+        "",
+        "",
+        None,
+        (client_model.clone().into(), client_view.clone().into()),
+        (server_model.clone().into(), server_view.clone().into()),
+    );
+    let (deploy_model, deploy_view) = new_umlclass_dependency(
+        deployment_models::DEPLOY,
+        "",
+        true,
+        None,
+        (artifact_model.clone().into(), artifact_view.clone().into()),
+        (server_model.clone().into(), server_view.clone().into()),
+    );
+
+    let (datacenter, datacenter_view) = new_umlclass_package(
+        "Datacenter",
+        deployment_models::DEVICE,
+        UmlClassPackageKind::Package,
+        egui::Rect::from_x_y_ranges(350.0..=600.0, 100.0..=500.0),
+    );
+    {
+        let mut w = datacenter_view.write();
+        let datacenter_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [server_view.clone().into(), artifact_view.clone().into()] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: datacenter_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo Deployment diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            client_model.into(),
+            datacenter.into(),
+            path_model.into(),
+            deploy_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            client_view.into(),
+            datacenter_view.into(),
+            path_view.into(),
+            deploy_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<DeploymentProfile>,
+        DeploymentControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<DeploymentProfile>,
+            UmlClassDiagramAdapter<DeploymentProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_communication_path(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_dependency(
+        m: ERef<UmlClass>,
+        stereotype: &str,
+        target_arrow_open: bool,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open,
+            stereotype: stereotype.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_deploy(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    ) {
+        class_dependency(m, deployment_models::DEPLOY, true)
+    }
+    fn class_manifest(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    ) {
+        class_dependency(m, deployment_models::MANIFEST, true)
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DeploymentProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] = &[
+        (0, 0, "\\", &class_communication_path as &ClassButtonF),
+        (0, 1, "⇢", &class_deploy as &ClassButtonF),
+        (1, 0, "⤏", &class_manifest as &ClassButtonF),
+    ];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let mut nodes = Vec::new();
+    for (stereotype, label, name, ksc) in [
+        (
+            deployment_models::DEVICE,
+            "Device Node",
+            "Server",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            deployment_models::EXECUTION_ENVIRONMENT,
+            "Execution Environment",
+            "Runtime",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            deployment_models::ARTIFACT,
+            "Artifact",
+            "app.jar",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+    ] {
+        nodes.push((
+            UmlClassToolStage::Class {
+                name: name.to_owned(),
+                stereotype: stereotype.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            label,
+            ksc,
+        ));
+    }
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: "".to_owned(),
+                    source_multiplicity: "".to_owned(),
+                    target_multiplicity: "".to_owned(),
+                },
+            },
+            "Communication Path",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: deployment_models::DEPLOY.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Deployment",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: deployment_models::MANIFEST.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Manifestation",
+            None,
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Nodes", nodes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::PackageStart {
+                        name: "Node".to_owned(),
+                        stereotype: deployment_models::DEVICE.to_owned(),
+                        kind: UmlClassPackageKind::Package,
+                    },
+                    "Node Container",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num8,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<DeploymentProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<DeploymentProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-deployment",
+    pretty_name: "Deployment diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Unified Modeling Language",
+        description: "Deployment diagram (nodes, artifacts, communication paths)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
@@ -0,0 +1,6 @@
+pub const DEVICE: &str = "device";
+pub const EXECUTION_ENVIRONMENT: &str = "executionEnvironment";
+pub const ARTIFACT: &str = "artifact";
+
+pub const DEPLOY: &str = "deploy";
+pub const MANIFEST: &str = "manifest";
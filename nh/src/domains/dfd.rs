@@ -0,0 +1,3 @@
+pub mod dfd_controllers;
+pub mod dfd_models;
+pub mod dfd_validations;
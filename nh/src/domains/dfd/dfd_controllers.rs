@@ -0,0 +1,499 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, new_umlclass_class, new_umlclass_dependency,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT, ProjectCommand,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        dfd::{dfd_models, dfd_validations},
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassRenderStyle, new_uml_usecase,
+                new_umlclass_associable_view,
+            },
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance},
+        },
+    },
+};
+use eframe::egui;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+#[derive(Clone, Default)]
+pub struct DfdProfile;
+impl UmlClassProfile for DfdProfile {}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct DfdControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+    /// Processes and the views of their lower level
+    decompositions: Vec<(ModelUuid, ViewUuid)>,
+}
+
+impl ControllerAdapter<UmlClassDomain<DfdProfile>> for DfdControllerAdapter {
+    type DiagramViewT =
+        DiagramControllerGen2<UmlClassDomain<DfdProfile>, UmlClassDiagramAdapter<DfdProfile>>;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self {
+            model: new_model,
+            decompositions: Vec::new(),
+        }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-dfd"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Data Flow Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Data Flow Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+
+    fn decomposition_view(
+        &mut self,
+        element: &ModelUuid,
+        view_exists: &dyn Fn(&ViewUuid) -> bool,
+    ) -> Option<(ViewUuid, Option<ERef<Self::DiagramViewT>>)> {
+        let (process_name, neighbours) = {
+            let m = self.model.read();
+            let process = dfd_validations::find_process(&m, element)?;
+            let process_name = (*process.read().name).clone();
+            (
+                process_name,
+                dfd_validations::process_neighbours(&m, element),
+            )
+        };
+
+        if let Some((_, v)) = self
+            .decompositions
+            .iter()
+            .find(|e| e.0 == *element && view_exists(&e.1))
+        {
+            return Some((*v, None));
+        }
+        self.decompositions.retain(|e| e.0 != *element);
+
+        // Lower level starts out with the elements the process exchanges data with
+        let elements = neighbours
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                new_umlclass_associable_view(e, egui::Pos2::new(100.0, 100.0 + 120.0 * i as f32))
+            })
+            .collect();
+        let view_uuid = ViewUuid::now_v7();
+        self.decompositions.push((*element, view_uuid));
+
+        Some((
+            view_uuid,
+            Some(Self::DiagramViewT::new(
+                view_uuid.into(),
+                format!("{} (decomposition)", process_name).into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                elements,
+            )),
+        ))
+    }
+
+    fn show_menubar_diagram_options(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        view_uuid: &ViewUuid,
+        represented_models: &dyn Fn(&ViewUuid) -> Option<HashSet<ModelUuid>>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("Check Balancing").clicked() {
+            let levels = self
+                .decompositions
+                .iter()
+                .flat_map(|(p, v)| represented_models(v).map(|r| (*p, *v, r)))
+                .collect();
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(dfd_validations::DfdBalancingTab::new(
+                    self.model.clone(),
+                    *view_uuid,
+                    levels,
+                ))),
+            ));
+        }
+        ui.separator();
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<DfdProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            DfdControllerAdapter {
+                model: model.clone(),
+                decompositions: Vec::new(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<DfdProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Data flow diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (customer_model, customer_view) = new_umlclass_class(
+        "Customer",
+        dfd_models::EXTERNAL_ENTITY,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(100.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+    let (process_model, process_view) = new_uml_usecase(
+        "Handle Order",
+        dfd_models::PROCESS,
+        false,
+        egui::Pos2::new(350.0, 200.0),
+        MGlobalColor::None,
+    );
+    let (store_model, store_view) = new_umlclass_class(
+        "Orders",
+        dfd_models::DATA_STORE,
+        false,
+        Vec::new(),
+        Vec::new(),
+        egui::Pos2::new(600.0, 200.0),
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    );
+
+    let (order_model, order_view) = new_umlclass_dependency(
+        dfd_models::FLOW,
+        "order",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(225.0, 160.0))),
+        (customer_model.clone().into(), customer_view.clone().into()),
+        (process_model.clone().into(), process_view.clone().into()),
+    );
+    let (receipt_model, receipt_view) = new_umlclass_dependency(
+        dfd_models::FLOW,
+        "receipt",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(225.0, 240.0))),
+        (process_model.clone().into(), process_view.clone().into()),
+        (customer_model.clone().into(), customer_view.clone().into()),
+    );
+    let (record_model, record_view) = new_umlclass_dependency(
+        dfd_models::FLOW,
+        "order record",
+        true,
+        None,
+        (process_model.clone().into(), process_view.clone().into()),
+        (store_model.clone().into(), store_view.clone().into()),
+    );
+
+    let name = format!("Demo Data flow diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            customer_model.into(),
+            process_model.into(),
+            store_model.into(),
+            order_model.into(),
+            receipt_model.into(),
+            record_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            customer_view.into(),
+            process_view.into(),
+            store_view.into(),
+            order_view.into(),
+            receipt_view.into(),
+            record_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<DfdProfile>,
+        DfdControllerAdapter,
+        DiagramControllerGen2<UmlClassDomain<DfdProfile>, UmlClassDiagramAdapter<DfdProfile>>,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DfdProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DfdProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DfdProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: dfd_models::FLOW.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DfdProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let classes = vec![
+        (
+            UmlClassToolStage::UseCase {
+                name: "Process".to_owned(),
+                stereotype: dfd_models::PROCESS.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            "Process",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Entity".to_owned(),
+                stereotype: dfd_models::EXTERNAL_ENTITY.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "External Entity",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Store".to_owned(),
+                stereotype: dfd_models::DATA_STORE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Data Store",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+    ];
+
+    let relationships = vec![(
+        UmlClassToolStage::LinkStart {
+            link_type: LinkType::Dependency {
+                target_arrow_open: true,
+                stereotype: dfd_models::FLOW.to_owned(),
+                name: "".to_owned(),
+            },
+        },
+        "Data Flow",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num5,
+        )),
+    )];
+
+    let palette_items = vec![
+        ("Elements", classes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<DfdProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<DfdProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-dfd",
+    pretty_name: "Data flow diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Data flow diagram (processes, data stores, external entities, data flows) with level decomposition and balancing check",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
@@ -0,0 +1,5 @@
+pub const PROCESS: &str = "";
+pub const EXTERNAL_ENTITY: &str = "external";
+pub const DATA_STORE: &str = "datastore";
+
+pub const FLOW: &str = "";
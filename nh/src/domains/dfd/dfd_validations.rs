@@ -0,0 +1,459 @@
+use std::collections::HashSet;
+
+use eframe::egui;
+
+use super::super::umlclass::umlclass_models::{
+    UmlClassAssociable, UmlClassDiagram, UmlClassElement, UmlUseCase,
+};
+use crate::{
+    CustomTab,
+    common::{
+        canvas::Highlight,
+        controller::{
+            DiagramCommand, GlobalDrawingContext, Model, ProjectCommand, SimpleProjectCommand,
+        },
+        eref::ERef,
+        uuid::{ModelUuid, ViewUuid},
+    },
+    domains::dfd::dfd_models,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BalancingProblemKind {
+    /// A flow of the process is not present in its decomposition
+    MissingInLevel,
+    /// A flow crossing the boundary of the decomposition is not a flow of the process
+    MissingInParent,
+}
+
+pub struct BalancingProblem {
+    pub kind: BalancingProblemKind,
+    pub process: ModelUuid,
+    pub level_view: ViewUuid,
+    pub flow: ModelUuid,
+    pub neighbour: ModelUuid,
+    pub is_input: bool,
+    pub name: String,
+}
+
+/// A decomposed process, the view of its lower level and the models represented in that view.
+pub type DecompositionLevel = (ModelUuid, ViewUuid, HashSet<ModelUuid>);
+
+pub struct DfdBalancingTab {
+    model: ERef<UmlClassDiagram>,
+    view_uuid: ViewUuid,
+    levels: Vec<DecompositionLevel>,
+    results: Vec<BalancingProblem>,
+}
+
+impl DfdBalancingTab {
+    pub fn new(
+        model: ERef<UmlClassDiagram>,
+        view_uuid: ViewUuid,
+        levels: Vec<DecompositionLevel>,
+    ) -> Self {
+        let results = check_balancing(&model.read(), &levels);
+        Self {
+            model,
+            view_uuid,
+            levels,
+            results,
+        }
+    }
+}
+
+impl CustomTab for DfdBalancingTab {
+    fn title(&self) -> String {
+        "Balancing".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button("Recheck").clicked() {
+                self.results = check_balancing(&self.model.read(), &self.levels);
+            }
+            ui.label(format!("{} decomposed processes", self.levels.len()));
+        });
+
+        if self.results.is_empty() {
+            ui.label("All decompositions are balanced");
+            return;
+        }
+
+        let tb = egui_extras::TableBuilder::new(ui)
+            .column(egui_extras::Column::auto().resizable(true))
+            .column(egui_extras::Column::remainder().resizable(true));
+
+        tb.body(|mut body| {
+            for p in &self.results {
+                body.row(30.0, |mut row| {
+                    row.col(|ui| {
+                        ui.label(&*gdc.model_labels.get(&p.process));
+                    });
+                    row.col(|ui| {
+                        let neighbour = gdc.model_labels.get(&p.neighbour);
+                        let direction = match p.is_input {
+                            true => format!("Input flow \"{}\" from {}", p.name, neighbour),
+                            false => format!("Output flow \"{}\" to {}", p.name, neighbour),
+                        };
+                        let (text, view_uuid) = match p.kind {
+                            BalancingProblemKind::MissingInLevel => (
+                                format!("{} is missing in the decomposition", direction),
+                                self.view_uuid,
+                            ),
+                            BalancingProblemKind::MissingInParent => (
+                                format!("{} has no counterpart in the parent level", direction),
+                                p.level_view,
+                            ),
+                        };
+                        if ui.label(text).clicked() {
+                            commands.push(
+                                SimpleProjectCommand::SpecificDiagramCommand(
+                                    view_uuid,
+                                    DiagramCommand::HighlightAllElements(
+                                        false,
+                                        Highlight::SELECTED,
+                                    ),
+                                )
+                                .into(),
+                            );
+                            commands.push(
+                                SimpleProjectCommand::SpecificDiagramCommand(
+                                    view_uuid,
+                                    DiagramCommand::HighlightElement(
+                                        p.flow.into(),
+                                        true,
+                                        Highlight::SELECTED,
+                                    ),
+                                )
+                                .into(),
+                            );
+                            commands.push(
+                                SimpleProjectCommand::SpecificDiagramCommand(
+                                    view_uuid,
+                                    DiagramCommand::PanToElement(p.flow.into(), false),
+                                )
+                                .into(),
+                            );
+                        }
+                    });
+                });
+            }
+        });
+    }
+}
+
+struct Flow {
+    uuid: ModelUuid,
+    source: UmlClassAssociable,
+    target: UmlClassAssociable,
+    name: String,
+}
+
+fn collect_flows(elements: &[UmlClassElement], flows: &mut Vec<Flow>) {
+    for e in elements {
+        match e {
+            UmlClassElement::Package(inner) => {
+                collect_flows(&inner.read().contained_elements, flows);
+            }
+            UmlClassElement::Dependency(inner) => {
+                let r = inner.read();
+                if *r.stereotype == dfd_models::FLOW {
+                    flows.push(Flow {
+                        uuid: *r.uuid,
+                        source: r.source.clone(),
+                        target: r.target.clone(),
+                        name: r.name.trim().to_owned(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn find_process(diagram: &UmlClassDiagram, uuid: &ModelUuid) -> Option<ERef<UmlUseCase>> {
+    fn find(elements: &[UmlClassElement], uuid: &ModelUuid) -> Option<ERef<UmlUseCase>> {
+        elements.iter().find_map(|e| match e {
+            UmlClassElement::Package(inner) => find(&inner.read().contained_elements, uuid),
+            UmlClassElement::UseCase(inner)
+                if *inner.read().uuid == *uuid
+                    && *inner.read().stereotype == dfd_models::PROCESS =>
+            {
+                Some(inner.clone())
+            }
+            _ => None,
+        })
+    }
+    find(&diagram.contained_elements, uuid)
+}
+
+/// Elements the process exchanges data flows with, in order of first appearance.
+pub fn process_neighbours(
+    diagram: &UmlClassDiagram,
+    process: &ModelUuid,
+) -> Vec<UmlClassAssociable> {
+    let mut flows = Vec::new();
+    collect_flows(&diagram.contained_elements, &mut flows);
+
+    let mut seen = HashSet::new();
+    let mut neighbours = Vec::new();
+    for f in flows {
+        let neighbour = if *f.source.uuid() == *process {
+            f.target
+        } else if *f.target.uuid() == *process {
+            f.source
+        } else {
+            continue;
+        };
+        if *neighbour.uuid() != *process && seen.insert(*neighbour.uuid()) {
+            neighbours.push(neighbour);
+        }
+    }
+    neighbours
+}
+
+/// Compares the flows of each decomposed process with the flows crossing the boundary of its lower level.
+///
+/// Flows are matched by the element on the other side, direction and name.
+pub fn check_balancing(
+    diagram: &UmlClassDiagram,
+    levels: &[DecompositionLevel],
+) -> Vec<BalancingProblem> {
+    let mut flows = Vec::new();
+    collect_flows(&diagram.contained_elements, &mut flows);
+
+    let mut problems = Vec::new();
+    for (process, level_view, represented) in levels {
+        // (flow, neighbour, is_input, name)
+        let mut parent_flows = Vec::new();
+        for f in &flows {
+            let (s, t) = (*f.source.uuid(), *f.target.uuid());
+            if s == *process && t != *process {
+                parent_flows.push((f.uuid, t, false, f.name.as_str()));
+            } else if t == *process && s != *process {
+                parent_flows.push((f.uuid, s, true, f.name.as_str()));
+            }
+        }
+        let neighbours: HashSet<ModelUuid> = parent_flows.iter().map(|e| e.1).collect();
+
+        let mut child_flows = Vec::new();
+        for f in flows.iter().filter(|e| represented.contains(&e.uuid)) {
+            let (s, t) = (*f.source.uuid(), *f.target.uuid());
+            if s == *process || t == *process {
+                continue;
+            }
+            let is_inner = |e: &ModelUuid| represented.contains(e) && !neighbours.contains(e);
+            if neighbours.contains(&s) && is_inner(&t) {
+                child_flows.push((f.uuid, s, true, f.name.as_str()));
+            } else if neighbours.contains(&t) && is_inner(&s) {
+                child_flows.push((f.uuid, t, false, f.name.as_str()));
+            }
+        }
+
+        let mut unmatched_children: Vec<Option<_>> = child_flows.into_iter().map(Some).collect();
+        for (flow, neighbour, is_input, name) in parent_flows {
+            let matching = unmatched_children.iter_mut().find(|e| {
+                e.is_some_and(|(_, n, i, nm)| n == neighbour && i == is_input && nm == name)
+            });
+            if let Some(m) = matching {
+                *m = None;
+            } else {
+                problems.push(BalancingProblem {
+                    kind: BalancingProblemKind::MissingInLevel,
+                    process: *process,
+                    level_view: *level_view,
+                    flow,
+                    neighbour,
+                    is_input,
+                    name: name.to_owned(),
+                });
+            }
+        }
+        for (flow, neighbour, is_input, name) in unmatched_children.into_iter().flatten() {
+            problems.push(BalancingProblem {
+                kind: BalancingProblemKind::MissingInParent,
+                process: *process,
+                level_view: *level_view,
+                flow,
+                neighbour,
+                is_input,
+                name: name.to_owned(),
+            });
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domains::umlclass::umlclass_models::{UmlClass, UmlClassDependency};
+
+    fn generate_modeluuid(id: u32) -> ModelUuid {
+        uuid::Uuid::from_u128(id as u128).into()
+    }
+
+    fn new_process(id: u32) -> ERef<UmlUseCase> {
+        ERef::new(UmlUseCase::new(
+            generate_modeluuid(id),
+            "".to_owned(),
+            dfd_models::PROCESS.to_owned(),
+            false,
+        ))
+    }
+
+    fn new_external(id: u32) -> ERef<UmlClass> {
+        ERef::new(UmlClass::new(
+            generate_modeluuid(id),
+            "".to_owned(),
+            dfd_models::EXTERNAL_ENTITY.to_owned(),
+            "".to_owned(),
+            false,
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
+    fn new_flow(
+        id: u32,
+        name: &str,
+        source: UmlClassAssociable,
+        target: UmlClassAssociable,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassDependency::new(
+            generate_modeluuid(id),
+            dfd_models::FLOW.to_owned(),
+            name.to_owned(),
+            source,
+            target,
+            true,
+        ))
+        .into()
+    }
+
+    fn new_diagram(elements: Vec<UmlClassElement>) -> UmlClassDiagram {
+        UmlClassDiagram::new(generate_modeluuid(0), "".to_owned(), elements)
+    }
+
+    fn level(process: u32, represented: &[u32]) -> DecompositionLevel {
+        (
+            generate_modeluuid(process),
+            ViewUuid::nil(),
+            represented.iter().map(|e| generate_modeluuid(*e)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let customer = new_external(1);
+        let process = new_process(2);
+        let other = new_process(3);
+        let d = new_diagram(vec![
+            customer.clone().into(),
+            process.clone().into(),
+            other.clone().into(),
+            new_flow(4, "order", customer.clone().into(), process.clone().into()),
+            new_flow(
+                5,
+                "receipt",
+                process.clone().into(),
+                customer.clone().into(),
+            ),
+            new_flow(
+                6,
+                "unrelated",
+                customer.clone().into(),
+                other.clone().into(),
+            ),
+        ]);
+
+        let n = process_neighbours(&d, &generate_modeluuid(2));
+        assert_eq!(n.len(), 1);
+        assert_eq!(*n[0].uuid(), generate_modeluuid(1));
+        assert!(find_process(&d, &generate_modeluuid(2)).is_some());
+        assert!(find_process(&d, &generate_modeluuid(1)).is_none());
+    }
+
+    #[test]
+    fn test_balanced() {
+        let customer = new_external(1);
+        let process = new_process(2);
+        let inner_a = new_process(3);
+        let inner_b = new_process(4);
+        let d = new_diagram(vec![
+            customer.clone().into(),
+            process.clone().into(),
+            inner_a.clone().into(),
+            inner_b.clone().into(),
+            new_flow(5, "order", customer.clone().into(), process.clone().into()),
+            new_flow(
+                6,
+                "receipt",
+                process.clone().into(),
+                customer.clone().into(),
+            ),
+            new_flow(7, "order", customer.clone().into(), inner_a.clone().into()),
+            new_flow(
+                8,
+                "receipt",
+                inner_b.clone().into(),
+                customer.clone().into(),
+            ),
+            new_flow(
+                9,
+                "internal",
+                inner_a.clone().into(),
+                inner_b.clone().into(),
+            ),
+        ]);
+
+        let problems = check_balancing(&d, &[level(2, &[1, 3, 4, 7, 8, 9])]);
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn test_unbalanced() {
+        let customer = new_external(1);
+        let process = new_process(2);
+        let inner = new_process(3);
+        let d = new_diagram(vec![
+            customer.clone().into(),
+            process.clone().into(),
+            inner.clone().into(),
+            new_flow(5, "order", customer.clone().into(), process.clone().into()),
+            new_flow(
+                6,
+                "receipt",
+                process.clone().into(),
+                customer.clone().into(),
+            ),
+            new_flow(
+                7,
+                "complaint",
+                customer.clone().into(),
+                inner.clone().into(),
+            ),
+        ]);
+
+        let problems = check_balancing(&d, &[level(2, &[1, 3, 7])]);
+        assert_eq!(problems.len(), 3);
+        assert_eq!(
+            problems
+                .iter()
+                .filter(|e| e.kind == BalancingProblemKind::MissingInLevel)
+                .count(),
+            2
+        );
+        assert!(problems.iter().any(|e| {
+            e.kind == BalancingProblemKind::MissingInParent
+                && e.flow == generate_modeluuid(7)
+                && e.is_input
+        }));
+    }
+}
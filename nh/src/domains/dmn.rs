@@ -0,0 +1,4 @@
+pub mod dmn_controllers;
+pub mod dmn_decision_tables;
+pub mod dmn_models;
+pub mod dmn_xml;
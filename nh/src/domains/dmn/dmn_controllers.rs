@@ -0,0 +1,545 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlClassView, new_umlclass_class, new_umlclass_dependency,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT, ProjectCommand,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        dmn::{
+            dmn_decision_tables,
+            dmn_models::{self, DmnDecisionTable, DmnHitPolicy, DmnRule},
+            dmn_xml,
+        },
+        umlclass::{
+            umlclass_controllers::{PartialUmlClassElement, UmlClassRenderStyle},
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance},
+        },
+    },
+};
+use eframe::egui;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+#[derive(Clone, Default)]
+pub struct DmnProfile;
+impl UmlClassProfile for DmnProfile {}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct DmnControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+    decision_tables: Arc<RwLock<Vec<(ModelUuid, DmnDecisionTable)>>>,
+}
+
+impl ControllerAdapter<UmlClassDomain<DmnProfile>> for DmnControllerAdapter {
+    type DiagramViewT =
+        DiagramControllerGen2<UmlClassDomain<DmnProfile>, UmlClassDiagramAdapter<DmnProfile>>;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self {
+            model: new_model,
+            decision_tables: Default::default(),
+        }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-dmn"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("DMN Decision Requirements Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared DMN Decision Requirements Diagram"
+                    .to_owned()
+                    .into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+
+    fn show_menubar_diagram_options(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        _view_uuid: &ViewUuid,
+        _represented_models: &dyn Fn(&ViewUuid) -> Option<HashSet<ModelUuid>>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("Decision Tables").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(dmn_decision_tables::DmnDecisionTablesTab::new(
+                    self.model.clone(),
+                    self.decision_tables.clone(),
+                ))),
+            ));
+        }
+        if ui.button("DMN XML").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(dmn_xml::DmnXmlTab::new(
+                    self.model.clone(),
+                    self.decision_tables.clone(),
+                ))),
+            ));
+        }
+        ui.separator();
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<DmnProfile>>,
+    decision_tables: Vec<(ModelUuid, DmnDecisionTable)>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            DmnControllerAdapter {
+                model: model.clone(),
+                decision_tables: Arc::new(RwLock::new(decision_tables)),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<DmnProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New DMN decision requirements diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![], vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (approval_model, approval_view) = new_dmn_element(
+        "Loan Approval",
+        dmn_models::DECISION,
+        egui::Pos2::new(300.0, 100.0),
+    );
+    let (applicant_model, applicant_view) = new_dmn_element(
+        "Applicant",
+        dmn_models::INPUT_DATA,
+        egui::Pos2::new(150.0, 300.0),
+    );
+    let (rules_model, rules_view) = new_dmn_element(
+        "Eligibility Rules",
+        dmn_models::BUSINESS_KNOWLEDGE_MODEL,
+        egui::Pos2::new(450.0, 300.0),
+    );
+    let (policy_model, policy_view) = new_dmn_element(
+        "Lending Policy",
+        dmn_models::KNOWLEDGE_SOURCE,
+        egui::Pos2::new(600.0, 100.0),
+    );
+
+    let (information_model, information_view) = new_umlclass_dependency(
+        dmn_models::INFORMATION_REQUIREMENT,
+        "",
+        false,
+        None,
+        (
+            applicant_model.clone().into(),
+            applicant_view.clone().into(),
+        ),
+        (approval_model.clone().into(), approval_view.clone().into()),
+    );
+    let (knowledge_model, knowledge_view) = new_umlclass_dependency(
+        dmn_models::KNOWLEDGE_REQUIREMENT,
+        "",
+        true,
+        None,
+        (rules_model.clone().into(), rules_view.clone().into()),
+        (approval_model.clone().into(), approval_view.clone().into()),
+    );
+    let (authority_model, authority_view) = new_umlclass_dependency(
+        dmn_models::AUTHORITY_REQUIREMENT,
+        "",
+        true,
+        None,
+        (policy_model.clone().into(), policy_view.clone().into()),
+        (approval_model.clone().into(), approval_view.clone().into()),
+    );
+
+    let decision_table = DmnDecisionTable {
+        hit_policy: DmnHitPolicy::First,
+        inputs: vec!["Applicant.age".to_owned(), "Applicant.income".to_owned()],
+        outputs: vec!["Approved".to_owned()],
+        rules: vec![
+            DmnRule {
+                input_entries: vec!["< 18".to_owned(), "-".to_owned()],
+                output_entries: vec!["false".to_owned()],
+                annotation: "Applicant must be an adult".to_owned(),
+            },
+            DmnRule {
+                input_entries: vec!["-".to_owned(), ">= 2000".to_owned()],
+                output_entries: vec!["true".to_owned()],
+                annotation: "".to_owned(),
+            },
+            DmnRule {
+                input_entries: vec!["-".to_owned(), "-".to_owned()],
+                output_entries: vec!["false".to_owned()],
+                annotation: "".to_owned(),
+            },
+        ],
+    };
+    let decision_tables = vec![(*approval_model.read().uuid, decision_table)];
+
+    let name = format!("Demo DMN decision requirements diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            approval_model.into(),
+            applicant_model.into(),
+            rules_model.into(),
+            policy_model.into(),
+            information_model.into(),
+            knowledge_model.into(),
+            authority_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            approval_view.into(),
+            applicant_view.into(),
+            rules_view.into(),
+            policy_view.into(),
+            information_view.into(),
+            knowledge_view.into(),
+            authority_view.into(),
+        ],
+        decision_tables,
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<DmnProfile>,
+        DmnControllerAdapter,
+        DiagramControllerGen2<UmlClassDomain<DmnProfile>, UmlClassDiagramAdapter<DmnProfile>>,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DmnProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DmnProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DmnProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: false,
+            stereotype: dmn_models::INFORMATION_REQUIREMENT.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<DmnProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let classes = vec![
+        (
+            UmlClassToolStage::Class {
+                name: "Decision".to_owned(),
+                stereotype: dmn_models::DECISION.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Decision",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Input".to_owned(),
+                stereotype: dmn_models::INPUT_DATA.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Input Data",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Knowledge".to_owned(),
+                stereotype: dmn_models::BUSINESS_KNOWLEDGE_MODEL.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Business Knowledge Model",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Source".to_owned(),
+                stereotype: dmn_models::KNOWLEDGE_SOURCE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Knowledge Source",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+    ];
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: false,
+                    stereotype: dmn_models::INFORMATION_REQUIREMENT.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Information Requirement",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: dmn_models::KNOWLEDGE_REQUIREMENT.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Knowledge Requirement",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num6,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: dmn_models::AUTHORITY_REQUIREMENT.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Authority Requirement",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num7,
+            )),
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Elements", classes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<DmnProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<DmnProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-dmn",
+    pretty_name: "DMN decision requirements diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Decision requirements diagram (decisions, input data, business knowledge models, knowledge sources) with decision tables and DMN XML export",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_dmn_element(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<DmnProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
@@ -0,0 +1,192 @@
+use std::sync::{Arc, RwLock};
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        eref::ERef,
+        uuid::ModelUuid,
+    },
+    domains::{
+        dmn::dmn_models::{self, DmnDecisionTable, DmnHitPolicy},
+        umlclass::umlclass_models::{UmlClassDiagram, UmlClassElement},
+    },
+};
+
+pub struct DmnDecisionTablesTab {
+    diagram: ERef<UmlClassDiagram>,
+    decision_tables: Arc<RwLock<Vec<(ModelUuid, DmnDecisionTable)>>>,
+    selected_decision: Option<ModelUuid>,
+}
+
+impl DmnDecisionTablesTab {
+    pub fn new(
+        diagram: ERef<UmlClassDiagram>,
+        decision_tables: Arc<RwLock<Vec<(ModelUuid, DmnDecisionTable)>>>,
+    ) -> Self {
+        Self {
+            diagram,
+            decision_tables,
+            selected_decision: None,
+        }
+    }
+}
+
+fn collect_decisions(elements: &[UmlClassElement], decisions: &mut Vec<(ModelUuid, String)>) {
+    for e in elements {
+        match e {
+            UmlClassElement::Package(inner) => {
+                collect_decisions(&inner.read().contained_elements, decisions);
+            }
+            UmlClassElement::Class(inner) => {
+                let r = inner.read();
+                if *r.stereotype == dmn_models::DECISION {
+                    decisions.push((*r.uuid, (*r.name).clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+enum TableRemoval {
+    Input(usize),
+    Output(usize),
+    Rule(usize),
+}
+
+impl CustomTab for DmnDecisionTablesTab {
+    fn title(&self) -> String {
+        "Decision Tables".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+        let mut decisions = Vec::new();
+        collect_decisions(&self.diagram.read().contained_elements, &mut decisions);
+        self.selected_decision
+            .take_if(|s| !decisions.iter().any(|e| e.0 == *s));
+
+        let selected_name = self
+            .selected_decision
+            .and_then(|s| decisions.iter().find(|e| e.0 == s))
+            .map(|e| e.1.as_str())
+            .unwrap_or("");
+        egui::ComboBox::from_label("Decision")
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                for (uuid, name) in &decisions {
+                    ui.selectable_value(&mut self.selected_decision, Some(*uuid), name);
+                }
+            });
+
+        let Some(selected) = self.selected_decision else {
+            if decisions.is_empty() {
+                ui.label("The diagram contains no decisions");
+            }
+            return;
+        };
+
+        let mut decision_tables = self.decision_tables.write().unwrap();
+        let Some((_, table)) = decision_tables.iter_mut().find(|e| e.0 == selected) else {
+            if ui.button("Create decision table").clicked() {
+                let mut table = DmnDecisionTable::default();
+                table.add_input();
+                table.add_output();
+                table.add_rule();
+                decision_tables.push((selected, table));
+            }
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Hit policy")
+                .selected_text(table.hit_policy.as_str())
+                .show_ui(ui, |ui| {
+                    for e in DmnHitPolicy::ALL {
+                        ui.selectable_value(&mut table.hit_policy, e, e.as_str());
+                    }
+                });
+            if ui.button("Add input").clicked() {
+                table.add_input();
+            }
+            if ui.button("Add output").clicked() {
+                table.add_output();
+            }
+            if ui.button("Add rule").clicked() {
+                table.add_rule();
+            }
+        });
+        ui.separator();
+
+        let mut removal = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("dmn-decision-table")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("#");
+                    for (i, e) in table.inputs.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(e)
+                                    .hint_text("Input")
+                                    .desired_width(120.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                removal = Some(TableRemoval::Input(i));
+                            }
+                        });
+                    }
+                    for (i, e) in table.outputs.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(e)
+                                    .hint_text("Output")
+                                    .desired_width(120.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                removal = Some(TableRemoval::Output(i));
+                            }
+                        });
+                    }
+                    ui.label("Annotation");
+                    ui.end_row();
+
+                    for (i, r) in table.rules.iter_mut().enumerate() {
+                        ui.label(format!("{}", i + 1));
+                        for e in r
+                            .input_entries
+                            .iter_mut()
+                            .chain(r.output_entries.iter_mut())
+                        {
+                            ui.add(egui::TextEdit::singleline(e).desired_width(120.0));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut r.annotation).desired_width(160.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                removal = Some(TableRemoval::Rule(i));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+
+        match removal {
+            Some(TableRemoval::Input(i)) => table.remove_input(i),
+            Some(TableRemoval::Output(i)) => table.remove_output(i),
+            Some(TableRemoval::Rule(i)) => {
+                table.rules.remove(i);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,97 @@
+pub const DECISION: &str = "decision";
+pub const INPUT_DATA: &str = "inputData";
+pub const BUSINESS_KNOWLEDGE_MODEL: &str = "businessKnowledgeModel";
+pub const KNOWLEDGE_SOURCE: &str = "knowledgeSource";
+
+// Requirements
+pub const INFORMATION_REQUIREMENT: &str = "information";
+pub const KNOWLEDGE_REQUIREMENT: &str = "knowledge";
+pub const AUTHORITY_REQUIREMENT: &str = "authority";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum DmnHitPolicy {
+    #[default]
+    Unique,
+    First,
+    Priority,
+    Any,
+    Collect,
+    RuleOrder,
+    OutputOrder,
+}
+
+impl DmnHitPolicy {
+    pub const ALL: [DmnHitPolicy; 7] = [
+        DmnHitPolicy::Unique,
+        DmnHitPolicy::First,
+        DmnHitPolicy::Priority,
+        DmnHitPolicy::Any,
+        DmnHitPolicy::Collect,
+        DmnHitPolicy::RuleOrder,
+        DmnHitPolicy::OutputOrder,
+    ];
+
+    /// Value of the `hitPolicy` attribute in DMN XML
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DmnHitPolicy::Unique => "UNIQUE",
+            DmnHitPolicy::First => "FIRST",
+            DmnHitPolicy::Priority => "PRIORITY",
+            DmnHitPolicy::Any => "ANY",
+            DmnHitPolicy::Collect => "COLLECT",
+            DmnHitPolicy::RuleOrder => "RULE ORDER",
+            DmnHitPolicy::OutputOrder => "OUTPUT ORDER",
+        }
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DmnRule {
+    pub input_entries: Vec<String>,
+    pub output_entries: Vec<String>,
+    pub annotation: String,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DmnDecisionTable {
+    pub hit_policy: DmnHitPolicy,
+    /// Input expressions
+    pub inputs: Vec<String>,
+    /// Output names
+    pub outputs: Vec<String>,
+    pub rules: Vec<DmnRule>,
+}
+
+impl DmnDecisionTable {
+    pub fn add_input(&mut self) {
+        self.inputs.push("".to_owned());
+        for r in &mut self.rules {
+            r.input_entries.push("-".to_owned());
+        }
+    }
+    pub fn remove_input(&mut self, idx: usize) {
+        self.inputs.remove(idx);
+        for r in &mut self.rules {
+            r.input_entries.remove(idx);
+        }
+    }
+    pub fn add_output(&mut self) {
+        self.outputs.push("".to_owned());
+        for r in &mut self.rules {
+            r.output_entries.push("".to_owned());
+        }
+    }
+    pub fn remove_output(&mut self, idx: usize) {
+        self.outputs.remove(idx);
+        for r in &mut self.rules {
+            r.output_entries.remove(idx);
+        }
+    }
+    pub fn add_rule(&mut self) {
+        self.rules.push(DmnRule {
+            input_entries: self.inputs.iter().map(|_| "-".to_owned()).collect(),
+            output_entries: self.outputs.iter().map(|_| "".to_owned()).collect(),
+            annotation: "".to_owned(),
+        });
+    }
+}
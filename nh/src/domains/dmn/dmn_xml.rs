@@ -0,0 +1,413 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, Model, ProjectCommand},
+        eref::ERef,
+        uuid::ModelUuid,
+        xml::escape,
+    },
+    domains::{
+        dmn::dmn_models::{self, DmnDecisionTable},
+        umlclass::umlclass_models::{
+            UmlClass, UmlClassAssociation, UmlClassComment, UmlClassCommentLink,
+            UmlClassDependency, UmlClassDiagram, UmlClassGeneralization, UmlClassInstance,
+            UmlClassPackage, UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+        },
+    },
+};
+
+pub struct DmnXmlTab {
+    diagram: ERef<UmlClassDiagram>,
+    decision_tables: Arc<RwLock<Vec<(ModelUuid, DmnDecisionTable)>>>,
+    dmn_xml: String,
+}
+
+impl DmnXmlTab {
+    pub fn new(
+        diagram: ERef<UmlClassDiagram>,
+        decision_tables: Arc<RwLock<Vec<(ModelUuid, DmnDecisionTable)>>>,
+    ) -> Self {
+        let dmn_xml = dmn_xml(&diagram.read(), &decision_tables.read().unwrap());
+        Self {
+            diagram,
+            decision_tables,
+            dmn_xml,
+        }
+    }
+}
+
+impl CustomTab for DmnXmlTab {
+    fn title(&self) -> String {
+        "DMN XML".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button("Refresh").clicked() {
+                self.dmn_xml = dmn_xml(&self.diagram.read(), &self.decision_tables.read().unwrap());
+            }
+            if ui.button("Copy").clicked() {
+                ui.ctx().copy_text(self.dmn_xml.clone());
+            }
+        });
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.dmn_xml.as_str()).code_editor(),
+            );
+        });
+    }
+}
+
+pub fn dmn_xml(
+    diagram: &UmlClassDiagram,
+    decision_tables: &[(ModelUuid, DmnDecisionTable)],
+) -> String {
+    let mut collector = DmnXmlCollector::new();
+
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+
+    collector.finish(diagram, decision_tables)
+}
+
+struct DrdElement {
+    uuid: ModelUuid,
+    name: String,
+    stereotype: String,
+}
+
+struct DrdRequirement {
+    uuid: ModelUuid,
+    stereotype: String,
+    source: ModelUuid,
+    target: ModelUuid,
+}
+
+struct DmnXmlCollector {
+    elements: Vec<DrdElement>,
+    requirements: Vec<DrdRequirement>,
+}
+
+impl DmnXmlCollector {
+    fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+            requirements: Vec::new(),
+        }
+    }
+
+    fn finish(
+        self,
+        diagram: &UmlClassDiagram,
+        decision_tables: &[(ModelUuid, DmnDecisionTable)],
+    ) -> String {
+        let stereotypes: HashMap<ModelUuid, &str> = self
+            .elements
+            .iter()
+            .map(|e| (e.uuid, e.stereotype.as_str()))
+            .collect();
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<definitions xmlns=\"https://www.omg.org/spec/DMN/20191111/MODEL/\" id=\"{}\" name=\"{}\" namespace=\"urn:uuid:{}\">\n",
+            Self::stringify_uuid(&diagram.uuid),
+            escape(&diagram.name),
+            diagram.uuid.to_string(),
+        ));
+
+        for e in &self.elements {
+            let tag = match e.stereotype.as_str() {
+                dmn_models::DECISION => "decision",
+                dmn_models::INPUT_DATA => "inputData",
+                dmn_models::BUSINESS_KNOWLEDGE_MODEL => "businessKnowledgeModel",
+                dmn_models::KNOWLEDGE_SOURCE => "knowledgeSource",
+                _ => continue,
+            };
+            xml.push_str(&format!(
+                "  <{} id=\"{}\" name=\"{}\">\n",
+                tag,
+                Self::stringify_uuid(&e.uuid),
+                escape(&e.name),
+            ));
+
+            // Requirements are owned by the requiring element, in the order given by the schema
+            for kind in [
+                dmn_models::INFORMATION_REQUIREMENT,
+                dmn_models::KNOWLEDGE_REQUIREMENT,
+                dmn_models::AUTHORITY_REQUIREMENT,
+            ] {
+                for r in self
+                    .requirements
+                    .iter()
+                    .filter(|r| r.target == e.uuid && r.stereotype == kind)
+                {
+                    let Some(source_stereotype) = stereotypes.get(&r.source) else {
+                        continue;
+                    };
+                    let Some((requirement_tag, required_tag)) =
+                        Self::requirement_tags(kind, &e.stereotype, source_stereotype)
+                    else {
+                        continue;
+                    };
+                    xml.push_str(&format!(
+                        "    <{} id=\"{}\">\n      <{} href=\"#{}\"/>\n    </{}>\n",
+                        requirement_tag,
+                        Self::stringify_uuid(&r.uuid),
+                        required_tag,
+                        Self::stringify_uuid(&r.source),
+                        requirement_tag,
+                    ));
+                }
+            }
+
+            if e.stereotype == dmn_models::DECISION
+                && let Some((_, t)) = decision_tables.iter().find(|t| t.0 == e.uuid)
+            {
+                Self::push_decision_table(&mut xml, &e.uuid, t);
+            }
+
+            xml.push_str(&format!("  </{}>\n", tag));
+        }
+
+        xml.push_str("</definitions>\n");
+        xml
+    }
+
+    fn push_decision_table(xml: &mut String, uuid: &ModelUuid, table: &DmnDecisionTable) {
+        let id = Self::stringify_uuid(uuid);
+        xml.push_str(&format!(
+            "    <decisionTable id=\"{}_table\" hitPolicy=\"{}\">\n",
+            id,
+            table.hit_policy.as_str(),
+        ));
+        for (i, e) in table.inputs.iter().enumerate() {
+            xml.push_str(&format!(
+                "      <input id=\"{}_input{}\" label=\"{}\">\n        <inputExpression id=\"{}_inputexpression{}\">\n          <text>{}</text>\n        </inputExpression>\n      </input>\n",
+                id,
+                i,
+                escape(e),
+                id,
+                i,
+                escape(e),
+            ));
+        }
+        for (i, e) in table.outputs.iter().enumerate() {
+            xml.push_str(&format!(
+                "      <output id=\"{}_output{}\" name=\"{}\"/>\n",
+                id,
+                i,
+                escape(e),
+            ));
+        }
+        xml.push_str("      <annotation name=\"Annotation\"/>\n");
+        for (i, r) in table.rules.iter().enumerate() {
+            xml.push_str(&format!("      <rule id=\"{}_rule{}\">\n", id, i));
+            for (j, e) in r.input_entries.iter().enumerate() {
+                xml.push_str(&format!(
+                    "        <inputEntry id=\"{}_rule{}_input{}\">\n          <text>{}</text>\n        </inputEntry>\n",
+                    id,
+                    i,
+                    j,
+                    escape(e),
+                ));
+            }
+            for (j, e) in r.output_entries.iter().enumerate() {
+                xml.push_str(&format!(
+                    "        <outputEntry id=\"{}_rule{}_output{}\">\n          <text>{}</text>\n        </outputEntry>\n",
+                    id,
+                    i,
+                    j,
+                    escape(e),
+                ));
+            }
+            xml.push_str(&format!(
+                "        <annotationEntry>\n          <text>{}</text>\n        </annotationEntry>\n",
+                escape(&r.annotation),
+            ));
+            xml.push_str("      </rule>\n");
+        }
+        xml.push_str("    </decisionTable>\n");
+    }
+
+    /// Tags of the requirement and of its reference, if the requirement is valid between the elements
+    fn requirement_tags(
+        kind: &str,
+        target_stereotype: &str,
+        source_stereotype: &str,
+    ) -> Option<(&'static str, &'static str)> {
+        match (kind, target_stereotype, source_stereotype) {
+            (dmn_models::INFORMATION_REQUIREMENT, dmn_models::DECISION, dmn_models::DECISION) => {
+                Some(("informationRequirement", "requiredDecision"))
+            }
+            (dmn_models::INFORMATION_REQUIREMENT, dmn_models::DECISION, dmn_models::INPUT_DATA) => {
+                Some(("informationRequirement", "requiredInput"))
+            }
+            (
+                dmn_models::KNOWLEDGE_REQUIREMENT,
+                dmn_models::DECISION | dmn_models::BUSINESS_KNOWLEDGE_MODEL,
+                dmn_models::BUSINESS_KNOWLEDGE_MODEL,
+            ) => Some(("knowledgeRequirement", "requiredKnowledge")),
+            (
+                dmn_models::AUTHORITY_REQUIREMENT,
+                dmn_models::DECISION
+                | dmn_models::BUSINESS_KNOWLEDGE_MODEL
+                | dmn_models::KNOWLEDGE_SOURCE,
+                source,
+            ) => match source {
+                dmn_models::DECISION => Some(("authorityRequirement", "requiredDecision")),
+                dmn_models::INPUT_DATA => Some(("authorityRequirement", "requiredInput")),
+                dmn_models::KNOWLEDGE_SOURCE => Some(("authorityRequirement", "requiredAuthority")),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn stringify_uuid(uuid: &ModelUuid) -> String {
+        format!("_{}", uuid.to_string())
+    }
+}
+
+impl UmlClassVisitor for DmnXmlCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        self.elements.push(DrdElement {
+            uuid: *class.uuid,
+            name: (*class.name).clone(),
+            stereotype: (*class.stereotype).clone(),
+        });
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, _generalization: &UmlClassGeneralization) {}
+    fn visit_dependency(&mut self, dependency: &UmlClassDependency) {
+        self.requirements.push(DrdRequirement {
+            uuid: *dependency.uuid,
+            stereotype: (*dependency.stereotype).clone(),
+            source: *dependency.source.uuid(),
+            target: *dependency.target.uuid(),
+        });
+    }
+    fn visit_association(&mut self, _association: &UmlClassAssociation) {}
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domains::{
+        dmn::dmn_models::{DmnHitPolicy, DmnRule},
+        umlclass::umlclass_models::UmlClassElement,
+    };
+
+    fn generate_modeluuid(id: u32) -> ModelUuid {
+        uuid::Uuid::from_u128(id as u128).into()
+    }
+
+    fn new_element(id: u32, name: &str, stereotype: &str) -> ERef<UmlClass> {
+        ERef::new(UmlClass::new(
+            generate_modeluuid(id),
+            name.to_owned(),
+            stereotype.to_owned(),
+            "".to_owned(),
+            false,
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
+    fn new_requirement(
+        id: u32,
+        stereotype: &str,
+        source: &ERef<UmlClass>,
+        target: &ERef<UmlClass>,
+    ) -> UmlClassElement {
+        ERef::new(UmlClassDependency::new(
+            generate_modeluuid(id),
+            stereotype.to_owned(),
+            "".to_owned(),
+            source.clone().into(),
+            target.clone().into(),
+            true,
+        ))
+        .into()
+    }
+
+    #[test]
+    fn test_requirements() {
+        let decision = new_element(1, "Approve", dmn_models::DECISION);
+        let input = new_element(2, "Applicant", dmn_models::INPUT_DATA);
+        let bkm = new_element(3, "Rules", dmn_models::BUSINESS_KNOWLEDGE_MODEL);
+        let d = UmlClassDiagram::new(
+            generate_modeluuid(0),
+            "".to_owned(),
+            vec![
+                decision.clone().into(),
+                input.clone().into(),
+                bkm.clone().into(),
+                new_requirement(4, dmn_models::INFORMATION_REQUIREMENT, &input, &decision),
+                new_requirement(5, dmn_models::KNOWLEDGE_REQUIREMENT, &bkm, &decision),
+                // Not valid in DMN, should be left out
+                new_requirement(6, dmn_models::INFORMATION_REQUIREMENT, &decision, &input),
+            ],
+        );
+
+        let xml = dmn_xml(&d, &[]);
+        assert!(xml.contains(
+            "<inputData id=\"_00000000-0000-0000-0000-000000000002\" name=\"Applicant\">"
+        ));
+        assert!(xml.contains("<requiredInput href=\"#_00000000-0000-0000-0000-000000000002\"/>"));
+        assert!(
+            xml.contains("<requiredKnowledge href=\"#_00000000-0000-0000-0000-000000000003\"/>")
+        );
+        assert_eq!(xml.matches("<informationRequirement").count(), 1);
+    }
+
+    #[test]
+    fn test_decision_table() {
+        let decision = new_element(1, "Risk & <Score>", dmn_models::DECISION);
+        let d = UmlClassDiagram::new(
+            generate_modeluuid(0),
+            "".to_owned(),
+            vec![decision.clone().into()],
+        );
+        let table = DmnDecisionTable {
+            hit_policy: DmnHitPolicy::RuleOrder,
+            inputs: vec!["age".to_owned()],
+            outputs: vec!["risk".to_owned()],
+            rules: vec![DmnRule {
+                input_entries: vec!["< 18".to_owned()],
+                output_entries: vec!["\"high\"".to_owned()],
+                annotation: "".to_owned(),
+            }],
+        };
+
+        let xml = dmn_xml(&d, &[(generate_modeluuid(1), table)]);
+        assert!(xml.contains("name=\"Risk &amp; &lt;Score&gt;\""));
+        assert!(xml.contains("hitPolicy=\"RULE ORDER\""));
+        assert!(xml.contains("<text>&lt; 18</text>"));
+        assert!(xml.contains("<text>&quot;high&quot;</text>"));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod er_controllers;
+pub mod er_models;
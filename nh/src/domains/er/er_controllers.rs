@@ -0,0 +1,527 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, TextDescriptionTab, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView,
+        UmlClassProfile, UmlClassToolStage, UmlClassView, new_umlclass_association,
+        new_umlclass_class, new_umlclass_property,
+    },
+    umlclass_models::{
+        UmlClassAssociationAggregation, UmlClassAssociationNavigability, UmlClassDiagram,
+    },
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        canvas,
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT, ProjectCommand,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        ufoption::UFOption,
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        er::er_models,
+        umlclass::{
+            umlclass_controllers::{PartialUmlClassElement, UmlClassRenderStyle},
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance},
+        },
+    },
+};
+use eframe::egui;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+#[derive(Clone, Default)]
+pub struct ErProfile;
+impl UmlClassProfile for ErProfile {
+    fn menubar_options_fun(
+        model: &ERef<UmlClassDiagram>,
+        _view_uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("PlantUML description").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(TextDescriptionTab::plantuml(model.clone()))),
+            ));
+        }
+        if ui.button("SQL DDL").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::super::umlclass::umlclass_sql_ddl::SqlDdlTab::new(model.clone()),
+                )),
+            ));
+        }
+        if ui.button("Import PlantUML").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::super::umlclass::umlclass_plantuml_import::PlantUmlImportTab::default(),
+                )),
+            ));
+        }
+        ui.separator();
+    }
+
+    fn class_compartments(stereotype: &str) -> &'static [&'static str] {
+        match stereotype {
+            er_models::ENTITY => &["constraints"],
+            _ => &[],
+        }
+    }
+
+    fn association_ending(
+        multiplicity: &str,
+        _navigability: UmlClassAssociationNavigability,
+        _aggregation: UmlClassAssociationAggregation,
+    ) -> (canvas::ArrowheadType, bool) {
+        match er_models::crows_foot(multiplicity) {
+            Some((optional, many)) => (canvas::ArrowheadType::CrowsFoot { optional, many }, false),
+            None => (canvas::ArrowheadType::None, true),
+        }
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct ErControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<ErProfile>> for ErControllerAdapter {
+    type DiagramViewT =
+        DiagramControllerGen2<UmlClassDomain<ErProfile>, UmlClassDiagramAdapter<ErProfile>>;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-er"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Entity-Relationship Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Entity-Relationship Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<ErProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            ErControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<ErProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Entity-relationship diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (customer_model, customer_view) = new_entity(
+        "Customer",
+        &[
+            ("id", "INTEGER", er_models::PRIMARY_KEY),
+            ("name", "VARCHAR(100)", er_models::ATTRIBUTE),
+            ("email", "VARCHAR(255)", er_models::ATTRIBUTE),
+        ],
+        egui::Pos2::new(150.0, 150.0),
+    );
+    let (order_model, order_view) = new_entity(
+        "Order",
+        &[
+            ("id", "INTEGER", er_models::PRIMARY_KEY),
+            ("customer_id", "INTEGER", er_models::FOREIGN_KEY),
+            ("created", "DATE", er_models::ATTRIBUTE),
+        ],
+        egui::Pos2::new(450.0, 150.0),
+    );
+    let (line_model, line_view) = new_entity(
+        "Order Line",
+        &[
+            ("order_id", "INTEGER", er_models::FOREIGN_KEY),
+            ("product_id", "INTEGER", er_models::FOREIGN_KEY),
+            ("quantity", "INTEGER", er_models::ATTRIBUTE),
+        ],
+        egui::Pos2::new(450.0, 400.0),
+    );
+    let (product_model, product_view) = new_entity(
+        "Product",
+        &[
+            ("id", "INTEGER", er_models::PRIMARY_KEY),
+            ("name", "VARCHAR(100)", er_models::ATTRIBUTE),
+            ("price", "DECIMAL(10, 2)", er_models::ATTRIBUTE),
+        ],
+        egui::Pos2::new(750.0, 400.0),
+    );
+
+    let (places_model, places_view) = new_umlclass_association(
+        er_models::RELATIONSHIP,
+        "places",
+        "1",
+        "0..*",
+        None,
+        (customer_model.clone().into(), customer_view.clone().into()),
+        (order_model.clone().into(), order_view.clone().into()),
+    );
+    let (contains_model, contains_view) = new_umlclass_association(
+        er_models::RELATIONSHIP,
+        "contains",
+        "1",
+        "1..*",
+        None,
+        (order_model.clone().into(), order_view.clone().into()),
+        (line_model.clone().into(), line_view.clone().into()),
+    );
+    let (ordered_model, ordered_view) = new_umlclass_association(
+        er_models::RELATIONSHIP,
+        "ordered in",
+        "1",
+        "0..*",
+        None,
+        (product_model.clone().into(), product_view.clone().into()),
+        (line_model.clone().into(), line_view.clone().into()),
+    );
+
+    let name = format!("Demo Entity-relationship diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            customer_model.into(),
+            order_model.into(),
+            line_model.into(),
+            product_model.into(),
+            places_model.into(),
+            contains_model.into(),
+            ordered_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            customer_view.into(),
+            order_view.into(),
+            line_view.into(),
+            product_view.into(),
+            places_view.into(),
+            contains_view.into(),
+            ordered_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<ErProfile>,
+        ErControllerAdapter,
+        DiagramControllerGen2<UmlClassDomain<ErProfile>, UmlClassDiagramAdapter<ErProfile>>,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ErProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ErProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_assoc_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ErProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: er_models::RELATIONSHIP.to_owned(),
+            source_multiplicity: "1".to_owned(),
+            target_multiplicity: "0..*".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<ErProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "\\", &class_assoc_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let entities = vec![(
+        UmlClassToolStage::Class {
+            name: "Entity".to_owned(),
+            stereotype: er_models::ENTITY.to_owned(),
+            is_abstract: false,
+            render_style: UmlClassRenderStyle::Class,
+            background_color: MGlobalColor::None,
+        },
+        "Entity",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num1,
+        )),
+    )];
+
+    let attributes = vec![
+        (
+            UmlClassToolStage::ClassProperty {
+                name: "attribute".to_owned(),
+                property_type: "VARCHAR(255)".to_owned(),
+                stereotype: er_models::ATTRIBUTE.to_owned(),
+            },
+            "Attribute",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            UmlClassToolStage::ClassProperty {
+                name: "id".to_owned(),
+                property_type: "INTEGER".to_owned(),
+                stereotype: er_models::PRIMARY_KEY.to_owned(),
+            },
+            "Primary Key",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+        (
+            UmlClassToolStage::ClassProperty {
+                name: "other_id".to_owned(),
+                property_type: "INTEGER".to_owned(),
+                stereotype: er_models::FOREIGN_KEY.to_owned(),
+            },
+            "Foreign Key",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+    ];
+
+    let relationships = [
+        ("1", "0..*", "One to Many", Some(egui::Key::Num5)),
+        ("1", "0..1", "One to One", Some(egui::Key::Num6)),
+        ("0..*", "0..*", "Many to Many", Some(egui::Key::Num7)),
+    ]
+    .into_iter()
+    .map(|(source, target, label, key)| {
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: er_models::RELATIONSHIP.to_owned(),
+                    source_multiplicity: source.to_owned(),
+                    target_multiplicity: target.to_owned(),
+                },
+            },
+            label,
+            key.map(|k| egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, k)),
+        )
+    })
+    .collect();
+
+    let palette_items = vec![
+        ("Entities", entities),
+        ("Attributes", attributes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<ErProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<ErProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-er",
+    pretty_name: "Entity-relationship diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Entity-relationship diagram (entities, PK/FK attributes, relationships in crow's foot notation)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_entity(
+    name: &str,
+    attributes: &[(&str, &str, &str)],
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<ErProfile>>) {
+    new_umlclass_class(
+        name,
+        er_models::ENTITY,
+        false,
+        attributes
+            .iter()
+            .map(|(name, value_type, flag)| {
+                new_umlclass_property(UFOption::None, name, value_type, "", "", flag)
+            })
+            .collect(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
@@ -0,0 +1,35 @@
+pub const ENTITY: &str = "";
+
+// Attribute flags
+pub const ATTRIBUTE: &str = "";
+pub const PRIMARY_KEY: &str = "PK";
+pub const FOREIGN_KEY: &str = "FK";
+
+pub const RELATIONSHIP: &str = "";
+
+/// Crow's foot end of a relationship as `(optional, many)`, `None` for multiplicities
+/// which the notation cannot express
+pub fn crows_foot(multiplicity: &str) -> Option<(bool, bool)> {
+    match multiplicity.trim() {
+        "1" | "1..1" => Some((false, false)),
+        "0..1" => Some((true, false)),
+        "*" | "0..*" | "n" | "0..n" => Some((true, true)),
+        "1..*" | "1..n" => Some((false, true)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplicities_map_to_crows_feet() {
+        assert_eq!(crows_foot("1"), Some((false, false)));
+        assert_eq!(crows_foot(" 0..1 "), Some((true, false)));
+        assert_eq!(crows_foot("*"), Some((true, true)));
+        assert_eq!(crows_foot("1..n"), Some((false, true)));
+        assert_eq!(crows_foot("2..5"), None);
+        assert_eq!(crows_foot(""), None);
+    }
+}
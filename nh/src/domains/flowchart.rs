@@ -0,0 +1,2 @@
+pub mod flowchart_controllers;
+pub mod flowchart_models;
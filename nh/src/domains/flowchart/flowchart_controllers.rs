@@ -0,0 +1,498 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlUseCaseOutline, UmlUseCaseView, new_uml_usecase,
+        new_umlclass_comment, new_umlclass_dependency,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        canvas,
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        flowchart::flowchart_models,
+        umlclass::{
+            umlclass_controllers::PartialUmlClassElement,
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlUseCase},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct FlowchartProfile;
+impl UmlClassProfile for FlowchartProfile {
+    fn menubar_options_fun(
+        _model: &ERef<UmlClassDiagram>,
+        _view_uuid: &ViewUuid,
+        _ui: &mut egui::Ui,
+        _commands: &mut Vec<crate::common::controller::ProjectCommand>,
+    ) {
+    }
+
+    fn dependency_line_type(_stereotype: &str) -> canvas::LineType {
+        canvas::LineType::Solid
+    }
+
+    fn usecase_outline(stereotype: &str) -> UmlUseCaseOutline {
+        match stereotype {
+            flowchart_models::PROCESS => UmlUseCaseOutline::Rectangle,
+            flowchart_models::DECISION => UmlUseCaseOutline::Rhombus,
+            flowchart_models::TERMINATOR => UmlUseCaseOutline::RoundedRectangle,
+            flowchart_models::INPUT_OUTPUT => UmlUseCaseOutline::Parallelogram,
+            flowchart_models::CONNECTOR => UmlUseCaseOutline::Circle,
+            _ => UmlUseCaseOutline::Ellipse,
+        }
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct FlowchartControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<FlowchartProfile>> for FlowchartControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<FlowchartProfile>,
+        UmlClassDiagramAdapter<FlowchartProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-flowchart"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Flowchart").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Flowchart".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<FlowchartProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            FlowchartControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<FlowchartProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Flowchart {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (start_model, start_view) = new_node(
+        "Start",
+        flowchart_models::TERMINATOR,
+        egui::Pos2::new(300.0, 80.0),
+    );
+    let (read_model, read_view) = new_node(
+        "Read temperature",
+        flowchart_models::INPUT_OUTPUT,
+        egui::Pos2::new(300.0, 180.0),
+    );
+    let (check_model, check_view) = new_node(
+        "Too cold?",
+        flowchart_models::DECISION,
+        egui::Pos2::new(300.0, 300.0),
+    );
+    let (heat_model, heat_view) = new_node(
+        "Turn heating on",
+        flowchart_models::PROCESS,
+        egui::Pos2::new(520.0, 300.0),
+    );
+    let (wait_model, wait_view) = new_node(
+        "Wait a minute",
+        flowchart_models::PROCESS,
+        egui::Pos2::new(300.0, 430.0),
+    );
+    let (loop_model, loop_view) = new_node(
+        "A",
+        flowchart_models::CONNECTOR,
+        egui::Pos2::new(300.0, 530.0),
+    );
+
+    let (flow1_model, flow1_view) = new_flow(
+        "",
+        (start_model.clone(), start_view.clone()),
+        (read_model.clone(), read_view.clone()),
+    );
+    let (flow2_model, flow2_view) = new_flow(
+        "",
+        (read_model.clone(), read_view.clone()),
+        (check_model.clone(), check_view.clone()),
+    );
+    let (flow3_model, flow3_view) = new_flow(
+        "yes",
+        (check_model.clone(), check_view.clone()),
+        (heat_model.clone(), heat_view.clone()),
+    );
+    let (flow4_model, flow4_view) = new_flow(
+        "no",
+        (check_model.clone(), check_view.clone()),
+        (wait_model.clone(), wait_view.clone()),
+    );
+    let (flow5_model, flow5_view) = new_flow(
+        "",
+        (heat_model.clone(), heat_view.clone()),
+        (wait_model.clone(), wait_view.clone()),
+    );
+    let (flow6_model, flow6_view) = new_flow(
+        "",
+        (wait_model.clone(), wait_view.clone()),
+        (loop_model.clone(), loop_view.clone()),
+    );
+    let (note_model, note_view) = new_umlclass_comment(
+        "Connector A continues\nat \"Read temperature\"",
+        "",
+        egui::Pos2::new(520.0, 530.0),
+        egui::Align2::CENTER_CENTER,
+    );
+
+    let name = format!("Demo Flowchart {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            start_model.into(),
+            read_model.into(),
+            check_model.into(),
+            heat_model.into(),
+            wait_model.into(),
+            loop_model.into(),
+            flow1_model.into(),
+            flow2_model.into(),
+            flow3_model.into(),
+            flow4_model.into(),
+            flow5_model.into(),
+            flow6_model.into(),
+            note_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            start_view.into(),
+            read_view.into(),
+            check_view.into(),
+            heat_view.into(),
+            wait_view.into(),
+            loop_view.into(),
+            flow1_view.into(),
+            flow2_view.into(),
+            flow3_view.into(),
+            flow4_view.into(),
+            flow5_view.into(),
+            flow6_view.into(),
+            note_view.into(),
+        ],
+    )
+}
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<FlowchartProfile>,
+        FlowchartControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<FlowchartProfile>,
+            UmlClassDiagramAdapter<FlowchartProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<FlowchartProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<FlowchartProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<FlowchartProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: flowchart_models::FLOW.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<FlowchartProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let nodes = [
+        (
+            "Step",
+            flowchart_models::PROCESS,
+            "Process",
+            Some(egui::Key::Num1),
+        ),
+        (
+            "Condition?",
+            flowchart_models::DECISION,
+            "Decision",
+            Some(egui::Key::Num2),
+        ),
+        (
+            "Start",
+            flowchart_models::TERMINATOR,
+            "Terminator",
+            Some(egui::Key::Num3),
+        ),
+        (
+            "Data",
+            flowchart_models::INPUT_OUTPUT,
+            "Input / Output",
+            Some(egui::Key::Num4),
+        ),
+        ("A", flowchart_models::CONNECTOR, "Connector", None),
+    ]
+    .into_iter()
+    .map(|(name, stereotype, label, key)| {
+        (
+            UmlClassToolStage::UseCase {
+                name: name.to_owned(),
+                stereotype: stereotype.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            label,
+            key.map(|k| egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, k)),
+        )
+    })
+    .collect();
+
+    let flows = vec![(
+        UmlClassToolStage::LinkStart {
+            link_type: LinkType::Dependency {
+                target_arrow_open: true,
+                stereotype: flowchart_models::FLOW.to_owned(),
+                name: "".to_owned(),
+            },
+        },
+        "Flow",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num5,
+        )),
+    )];
+
+    let palette_items = vec![
+        ("Shapes", nodes),
+        ("Flows", flows),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a note".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Note",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Note Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<FlowchartProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<FlowchartProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-flowchart",
+    pretty_name: "Flowchart",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Flowchart (processes, decisions, terminators, input/output, connectors) without UML semantics",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_node(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlUseCase>, ERef<UmlUseCaseView<FlowchartProfile>>) {
+    new_uml_usecase(name, stereotype, false, position, MGlobalColor::None)
+}
+
+type NodeRefs = (ERef<UmlUseCase>, ERef<UmlUseCaseView<FlowchartProfile>>);
+
+fn new_flow(
+    name: &str,
+    source: NodeRefs,
+    target: NodeRefs,
+) -> (
+    ERef<super::super::umlclass::umlclass_models::UmlClassDependency>,
+    impl Into<UmlClassElementView<FlowchartProfile>>,
+) {
+    new_umlclass_dependency(
+        flowchart_models::FLOW,
+        name,
+        true,
+        None,
+        (source.0.into(), source.1.into()),
+        (target.0.into(), target.1.into()),
+    )
+}
@@ -0,0 +1,8 @@
+// Nodes are use cases, drawn with the outline of their kind
+pub const PROCESS: &str = "process";
+pub const DECISION: &str = "decision";
+pub const TERMINATOR: &str = "terminator";
+pub const INPUT_OUTPUT: &str = "data";
+pub const CONNECTOR: &str = "connector";
+
+pub const FLOW: &str = "";
@@ -0,0 +1,2 @@
+pub mod goal_controllers;
+pub mod goal_models;
@@ -0,0 +1,642 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlClassView, new_umlclass_class, new_umlclass_dependency,
+        new_umlclass_package,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        canvas,
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        goal::goal_models,
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct GoalProfile;
+impl UmlClassProfile for GoalProfile {
+    /// Keeps dark text, as goal models tend to tell softgoals, tasks and resources apart by fill color
+    fn color_profile(_dark: bool) -> canvas::ColorProfile {
+        canvas::ColorProfile::LIGHT
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct GoalControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<GoalProfile>> for GoalControllerAdapter {
+    type DiagramViewT =
+        DiagramControllerGen2<UmlClassDomain<GoalProfile>, UmlClassDiagramAdapter<GoalProfile>>;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-goal"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Goal Model").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Goal Model".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<GoalProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            GoalControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<GoalProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Goal model {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (buy_model, buy_view) = new_goal_element(
+        "Product bought",
+        goal_models::GOAL,
+        egui::Pos2::new(300.0, 150.0),
+    );
+    let (select_model, select_view) = new_goal_element(
+        "Select product",
+        goal_models::TASK,
+        egui::Pos2::new(200.0, 300.0),
+    );
+    let (pay_model, pay_view) = new_goal_element(
+        "Pay for order",
+        goal_models::TASK,
+        egui::Pos2::new(400.0, 300.0),
+    );
+    let (fast_model, fast_view) = new_goal_element(
+        "Fast checkout",
+        goal_models::SOFTGOAL,
+        egui::Pos2::new(600.0, 150.0),
+    );
+    let (card_model, card_view) = new_goal_element(
+        "Credit card",
+        goal_models::RESOURCE,
+        egui::Pos2::new(400.0, 450.0),
+    );
+
+    let (select_and_model, select_and_view) = new_umlclass_dependency(
+        goal_models::AND_DECOMPOSITION,
+        "",
+        false,
+        None,
+        (select_model.clone().into(), select_view.clone().into()),
+        (buy_model.clone().into(), buy_view.clone().into()),
+    );
+    let (pay_and_model, pay_and_view) = new_umlclass_dependency(
+        goal_models::AND_DECOMPOSITION,
+        "",
+        false,
+        None,
+        (pay_model.clone().into(), pay_view.clone().into()),
+        (buy_model.clone().into(), buy_view.clone().into()),
+    );
+    let (help_model, help_view) = new_umlclass_dependency(
+        goal_models::HELP,
+        "",
+        true,
+        None,
+        (pay_model.clone().into(), pay_view.clone().into()),
+        (fast_model.clone().into(), fast_view.clone().into()),
+    );
+    let (needs_model, needs_view) = new_umlclass_dependency(
+        goal_models::NEEDS,
+        "",
+        true,
+        None,
+        (pay_model.clone().into(), pay_view.clone().into()),
+        (card_model.clone().into(), card_view.clone().into()),
+    );
+
+    let (actor, actor_view) = new_umlclass_package(
+        "Customer",
+        goal_models::ACTOR,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(100.0..=750.0, 50.0..=550.0),
+    );
+    {
+        let mut w = actor_view.write();
+        let actor_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [
+            buy_view.clone().into(),
+            select_view.clone().into(),
+            pay_view.clone().into(),
+            fast_view.clone().into(),
+            card_view.clone().into(),
+        ] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: actor_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo Goal model {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            actor.into(),
+            select_and_model.into(),
+            pay_and_model.into(),
+            help_model.into(),
+            needs_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            actor_view.into(),
+            select_and_view.into(),
+            pay_and_view.into(),
+            help_view.into(),
+            needs_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<GoalProfile>,
+        GoalControllerAdapter,
+        DiagramControllerGen2<UmlClassDomain<GoalProfile>, UmlClassDiagramAdapter<GoalProfile>>,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: false,
+            stereotype: goal_models::AND_DECOMPOSITION.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_dep_0_1(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: goal_models::HELP.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    fn class_dep_1_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: goal_models::HURT.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<GoalProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] = &[
+        (0, 0, "⋀", &class_dep_0_0 as &ClassButtonF),
+        (0, 1, "+", &class_dep_0_1 as &ClassButtonF),
+        (1, 0, "−", &class_dep_1_0 as &ClassButtonF),
+    ];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let classes = vec![
+        (
+            UmlClassToolStage::Class {
+                name: "Goal".to_owned(),
+                stereotype: goal_models::GOAL.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Goal",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Softgoal".to_owned(),
+                stereotype: goal_models::SOFTGOAL.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Softgoal",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Task".to_owned(),
+                stereotype: goal_models::TASK.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Task",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Resource".to_owned(),
+                stereotype: goal_models::RESOURCE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Resource",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+    ];
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: false,
+                    stereotype: goal_models::AND_DECOMPOSITION.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "AND Decomposition",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: false,
+                    stereotype: goal_models::OR_DECOMPOSITION.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "OR Decomposition",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num6,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::MAKE.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Contribution (make)",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num7,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::HELP.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Contribution (help)",
+            None,
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::HURT.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Contribution (hurt)",
+            None,
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::BREAK.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Contribution (break)",
+            None,
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::NEEDS.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Needed-by",
+            None,
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: goal_models::DEPENDS.to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Actor Dependency",
+            None,
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Elements", classes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::PackageStart {
+                        name: "Actor".to_owned(),
+                        stereotype: goal_models::ACTOR.to_owned(),
+                        kind: UmlClassPackageKind::Boundary,
+                    },
+                    "Actor Boundary",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num8,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<GoalProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<GoalProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-goal",
+    pretty_name: "Goal model",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Goal-oriented requirements model (i*/KAOS goals, softgoals, tasks, resources)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_goal_element(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<GoalProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
@@ -0,0 +1,19 @@
+// Intentional elements
+pub const GOAL: &str = "goal";
+pub const SOFTGOAL: &str = "softgoal";
+pub const TASK: &str = "task";
+pub const RESOURCE: &str = "resource";
+// Actor boundaries
+pub const ACTOR: &str = "actor";
+
+// Decomposition links
+pub const AND_DECOMPOSITION: &str = "and";
+pub const OR_DECOMPOSITION: &str = "or";
+// Contribution links
+pub const MAKE: &str = "make";
+pub const HELP: &str = "help";
+pub const HURT: &str = "hurt";
+pub const BREAK: &str = "break";
+// Other links
+pub const NEEDS: &str = "needs";
+pub const DEPENDS: &str = "depends";
@@ -0,0 +1,2 @@
+pub mod mindmap_controllers;
+pub mod mindmap_models;
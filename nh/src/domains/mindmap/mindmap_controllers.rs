@@ -0,0 +1,425 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        AssociationViewT, LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView,
+        UmlClassProfile, UmlClassToolStage, UmlUseCaseOutline, UmlUseCaseView, new_uml_usecase,
+        new_umlclass_association,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        mindmap::mindmap_models,
+        umlclass::{
+            umlclass_controllers::PartialUmlClassElement,
+            umlclass_models::{
+                UmlClass, UmlClassAssociation, UmlClassElement, UmlClassInstance, UmlUseCase,
+            },
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct MindMapProfile;
+impl UmlClassProfile for MindMapProfile {
+    fn menubar_options_fun(
+        _model: &ERef<UmlClassDiagram>,
+        _view_uuid: &ViewUuid,
+        _ui: &mut egui::Ui,
+        _commands: &mut Vec<crate::common::controller::ProjectCommand>,
+    ) {
+    }
+
+    fn usecase_outline(stereotype: &str) -> UmlUseCaseOutline {
+        match stereotype {
+            mindmap_models::TOPIC => UmlUseCaseOutline::RoundedRectangle,
+            _ => UmlUseCaseOutline::Ellipse,
+        }
+    }
+
+    fn child_usecase_stereotypes() -> Option<(&'static str, &'static str)> {
+        Some((mindmap_models::TOPIC, mindmap_models::BRANCH))
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct MindMapControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<MindMapProfile>> for MindMapControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<MindMapProfile>,
+        UmlClassDiagramAdapter<MindMapProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-mindmap"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Mind Map").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Mind Map".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<MindMapProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            MindMapControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<MindMapProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Mind Map {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let mut models = Vec::<UmlClassElement>::new();
+    let mut views = Vec::<UmlClassElementView<MindMapProfile>>::new();
+
+    let (central_model, central_view) = new_topic(
+        "Trip to Japan",
+        mindmap_models::CENTRAL_TOPIC,
+        egui::Pos2::new(300.0, 250.0),
+    );
+    models.push(central_model.clone().into());
+    views.push(central_view.clone().into());
+
+    let branches: [(&str, &[&str]); 3] = [
+        ("Transport", &["Flights", "Rail pass"]),
+        ("Accommodation", &["Tokyo", "Kyoto", "Onsen"]),
+        ("Budget", &[]),
+    ];
+    let mut y = 100.0;
+    for (topic, subtopics) in branches {
+        let topic_y = y + 40.0 * subtopics.len().saturating_sub(1) as f32 / 2.0;
+        let (topic_model, topic_view) = new_topic(
+            topic,
+            mindmap_models::TOPIC,
+            egui::Pos2::new(500.0, topic_y),
+        );
+        let (branch_model, branch_view) = new_branch(
+            (central_model.clone(), central_view.clone()),
+            (topic_model.clone(), topic_view.clone()),
+        );
+        models.extend([topic_model.clone().into(), branch_model.into()]);
+        views.extend([topic_view.clone().into(), branch_view.into()]);
+
+        for subtopic in subtopics {
+            let (subtopic_model, subtopic_view) =
+                new_topic(subtopic, mindmap_models::TOPIC, egui::Pos2::new(680.0, y));
+            let (branch_model, branch_view) = new_branch(
+                (topic_model.clone(), topic_view.clone()),
+                (subtopic_model.clone(), subtopic_view.clone()),
+            );
+            models.extend([subtopic_model.into(), branch_model.into()]);
+            views.extend([subtopic_view.into(), branch_view.into()]);
+            y += 40.0;
+        }
+        y += 40.0;
+    }
+
+    let name = format!("Demo Mind Map {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        models,
+    ));
+    new_controlller(diagram, name, views)
+}
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<MindMapProfile>,
+        MindMapControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<MindMapProfile>,
+            UmlClassDiagramAdapter<MindMapProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<MindMapProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<MindMapProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<MindMapProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: mindmap_models::BRANCH.to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<MindMapProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let palette_items = vec![
+        (
+            "Topics",
+            vec![
+                (
+                    UmlClassToolStage::UseCase {
+                        name: "Central topic".to_owned(),
+                        stereotype: mindmap_models::CENTRAL_TOPIC.to_owned(),
+                        is_abstract: false,
+                        background_color: MGlobalColor::None,
+                    },
+                    "Central Topic",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num1,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::UseCase {
+                        name: "Topic".to_owned(),
+                        stereotype: mindmap_models::TOPIC.to_owned(),
+                        is_abstract: false,
+                        background_color: MGlobalColor::None,
+                    },
+                    "Topic",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num2,
+                    )),
+                ),
+            ],
+        ),
+        (
+            "Branches",
+            vec![(
+                UmlClassToolStage::LinkStart {
+                    link_type: LinkType::Association {
+                        stereotype: mindmap_models::BRANCH.to_owned(),
+                        source_multiplicity: "".to_owned(),
+                        target_multiplicity: "".to_owned(),
+                    },
+                },
+                "Branch",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Num3,
+                )),
+            )],
+        ),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a note".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Note",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Note Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<MindMapProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<MindMapProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-mindmap",
+    pretty_name: "Mind Map",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Mind map with a central topic and branches, children are added with Tab and siblings with Enter",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+type TopicRefs = (ERef<UmlUseCase>, ERef<UmlUseCaseView<MindMapProfile>>);
+
+fn new_topic(name: &str, stereotype: &str, position: egui::Pos2) -> TopicRefs {
+    new_uml_usecase(name, stereotype, false, position, MGlobalColor::None)
+}
+
+fn new_branch(
+    parent: TopicRefs,
+    child: TopicRefs,
+) -> (
+    ERef<UmlClassAssociation>,
+    ERef<AssociationViewT<MindMapProfile>>,
+) {
+    new_umlclass_association(
+        mindmap_models::BRANCH,
+        "",
+        "",
+        "",
+        None,
+        (parent.0.into(), parent.1.into()),
+        (child.0.into(), child.1.into()),
+    )
+}
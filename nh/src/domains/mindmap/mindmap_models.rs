@@ -0,0 +1,6 @@
+// Topics are use cases, the central one keeps the plain ellipse
+pub const CENTRAL_TOPIC: &str = "";
+pub const TOPIC: &str = "topic";
+
+// Branches are associations from the parent topic to the child
+pub const BRANCH: &str = "";
@@ -1,2 +1,3 @@
 pub mod network_controllers;
+pub mod network_inventory;
 pub mod network_models;
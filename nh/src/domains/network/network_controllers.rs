@@ -18,6 +18,7 @@ use crate::common::views::multiconnection_view::{
     VertexInformation,
 };
 use crate::common::views::package_view::{PackageAdapter, PackageView};
+use crate::domains::network::network_inventory::{InventoryHost, NetworkInventory};
 use crate::domains::network::network_models::{
     NetworkAssociation, NetworkAssociationArrowheadType, NetworkAssociationLineType,
     NetworkComment, NetworkContainer, NetworkDiagram, NetworkElement, NetworkFile, NetworkFileKind,
@@ -55,7 +56,7 @@ type LinkViewT = MulticonnectionView<NetworkDomain, NetworkAssociationAdapter>;
 #[derive(Clone, Copy, Debug)]
 pub struct NetworkOrdinalMovement {}
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum NetworkPropChange {
     NameChange(Arc<String>),
 
@@ -299,6 +300,7 @@ impl DiagramAdapter<NetworkDomain> for NetworkDiagramAdapter {
                     min: egui::Pos2::ZERO,
                     max: egui::Pos2::new(100.0, 100.0),
                 },
+                Vec::new(),
             )
             .into(),
             NetworkElement::Node(inner) => new_network_node_view(inner, egui::Pos2::ZERO).into(),
@@ -472,9 +474,19 @@ impl DiagramAdapter<NetworkDomain> for NetworkDiagramAdapter {
     fn menubar_options_fun(
         &self,
         _view_uuid: &ViewUuid,
-        _ui: &mut egui::Ui,
-        _commands: &mut Vec<ProjectCommand>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
     ) {
+        if ui.button("Import Inventory").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::network_inventory::NetworkInventoryImportTab::default(),
+                )),
+            ));
+        }
+        ui.separator();
     }
     fn try_handle_custom_shortcut(
         &mut self,
@@ -659,6 +671,102 @@ pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
     )
 }
 
+/// Creates a new diagram with one container per subnet, placing the remaining hosts below them.
+///
+/// Link bandwidths are shown as the role label at the source end.
+pub fn from_inventory(inventory: &NetworkInventory) -> (ViewUuid, ERef<dyn DiagramController>) {
+    const COLUMNS: usize = 3;
+    const SPACING: egui::Vec2 = egui::Vec2::new(140.0, 110.0);
+    const CONTAINER_PADDING: egui::Vec2 = egui::Vec2::new(70.0, 80.0);
+
+    let mut nodes = HashMap::new();
+    let mut new_node = |host: &InventoryHost, position: egui::Pos2| {
+        let (model, view) = new_network_node(&host.name, host.kind, position);
+        let r = (NetworkElement::from(model), NetworkElementView::from(view));
+        nodes.insert(host.name.clone(), r.clone());
+        r
+    };
+
+    let mut models = Vec::new();
+    let mut views = Vec::new();
+    let mut x = 40.0;
+    let mut bottom: f32 = 40.0;
+    for subnet in inventory.subnets() {
+        let hosts: Vec<_> = inventory
+            .hosts
+            .iter()
+            .filter(|e| e.subnet.as_deref() == Some(subnet))
+            .collect();
+        let columns = hosts.len().clamp(1, COLUMNS);
+        let rows = hosts.len().div_ceil(COLUMNS).max(1);
+        let bounds_rect = egui::Rect::from_min_size(
+            egui::Pos2::new(x, 40.0),
+            egui::Vec2::new(columns as f32, rows as f32) * SPACING
+                + egui::Vec2::new(0.0, CONTAINER_PADDING.y - SPACING.y / 2.0),
+        );
+
+        let (contained_models, contained_views) = hosts
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let offset = egui::Vec2::new((i % COLUMNS) as f32, (i / COLUMNS) as f32) * SPACING;
+                new_node(h, bounds_rect.min + CONTAINER_PADDING + offset)
+            })
+            .unzip();
+        let (container, container_view) =
+            new_network_container(subnet, bounds_rect, contained_models, contained_views);
+        models.push(container.into());
+        views.push(container_view.into());
+
+        x = bounds_rect.max.x + 40.0;
+        bottom = bottom.max(bounds_rect.max.y);
+    }
+
+    for (i, h) in inventory
+        .hosts
+        .iter()
+        .filter(|e| e.subnet.is_none())
+        .enumerate()
+    {
+        let (model, view) = new_node(
+            h,
+            egui::Pos2::new(
+                40.0 + SPACING.x / 2.0 + i as f32 * SPACING.x,
+                bottom + 100.0,
+            ),
+        );
+        models.push(model);
+        views.push(view);
+    }
+
+    for l in &inventory.links {
+        let (Some(source), Some(target)) = (nodes.get(&l.from), nodes.get(&l.to)) else {
+            continue;
+        };
+        let (link, link_view) = new_network_association(
+            NetworkAssociationLineType::Solid,
+            source.clone(),
+            NetworkAssociationArrowheadType::None,
+            target.clone(),
+            NetworkAssociationArrowheadType::None,
+        );
+        if !l.bandwidth.is_empty() {
+            link.write().source_label_role = Arc::new(l.bandwidth.clone());
+            link_view.write().refresh_buffers();
+        }
+        models.push(link.into());
+        views.push(link_view.into());
+    }
+
+    let name = "Imported Network diagram".to_owned();
+    let diagram = ERef::new(NetworkDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        models,
+    ));
+    new_controlller(diagram, name, views)
+}
+
 pub fn deserializer(
     uuid: ControllerUuid,
     d: &mut NHDeserializer,
@@ -1327,6 +1435,8 @@ fn view_for_stage(s: &NetworkToolStage) -> NetworkElementView {
                     min: egui::Pos2::ZERO,
                     max: egui::Pos2::new(100.0, 50.0),
                 },
+                Vec::new(),
+                Vec::new(),
             )
             .1;
             container_view.into()
@@ -1344,7 +1454,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(NetworkSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
         element_buttons: buttons::ELEMENT_BUTTONS.clone(),
@@ -1768,8 +1878,13 @@ impl Tool<NetworkDomain> for NaiveNetworkTool {
             } => {
                 self.current_stage = self.initial_stage.clone();
 
-                let container_view =
-                    new_network_container(name, egui::Rect::from_two_pos(*a, *b)).1;
+                let container_view = new_network_container(
+                    name,
+                    egui::Rect::from_two_pos(*a, *b),
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .1;
 
                 self.try_spend();
                 commands.push(InsensitiveCommand::AddDependency {
@@ -1793,19 +1908,23 @@ impl Tool<NetworkDomain> for NaiveNetworkTool {
 fn new_network_container(
     name: &str,
     bounds_rect: egui::Rect,
+    contained_models: Vec<NetworkElement>,
+    contained_views: Vec<NetworkElementView>,
 ) -> (ERef<NetworkContainer>, ERef<PackageViewT>) {
     let container_model = ERef::new(NetworkContainer::new(
         ModelUuid::now_v7(),
         name.to_owned(),
-        Vec::new(),
+        contained_models,
     ));
-    let container_view = new_network_container_view(container_model.clone(), bounds_rect);
+    let container_view =
+        new_network_container_view(container_model.clone(), bounds_rect, contained_views);
 
     (container_model, container_view)
 }
 fn new_network_container_view(
     model: ERef<NetworkContainer>,
     bounds_rect: egui::Rect,
+    contained_views: Vec<NetworkElementView>,
 ) -> ERef<PackageViewT> {
     let m = model.read();
     PackageView::new(
@@ -1816,7 +1935,7 @@ fn new_network_container_view(
             name_buffer: (*m.name).clone(),
             comment_buffer: (*m.comment).clone(),
         },
-        Vec::new(),
+        contained_views,
         bounds_rect,
     )
 }
@@ -2699,7 +2818,9 @@ impl ElementControllerGen2<NetworkDomain> for NetworkNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -3328,7 +3449,9 @@ impl ElementControllerGen2<NetworkDomain> for NetworkUserView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -3860,7 +3983,9 @@ impl ElementControllerGen2<NetworkDomain> for NetworkFileView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -4596,6 +4721,12 @@ impl ElementControllerGen2<NetworkDomain> for NetworkCommentView {
             ));
         }
 
+        egui::CollapsingHeader::new("Preview")
+            .default_open(true)
+            .show(ui, |ui| {
+                crate::common::rich_text::show_markdown(ui, &self.text_buffer);
+            });
+
         ui.label("View properties");
 
         ui.horizontal(|ui| {
@@ -4685,18 +4816,18 @@ impl ElementControllerGen2<NetworkDomain> for NetworkCommentView {
                 egui::Align::Max => -Self::CORNER_SIZE,
             },
         };
-        self.bounds_rect = canvas
-            .measure_text(
-                self.position,
-                self.align,
-                &read.text,
-                canvas::CLASS_MIDDLE_FONT_SIZE,
-            )
-            .expand2(egui::Vec2 {
-                x: Self::CORNER_SIZE,
-                y: Self::CORNER_SIZE,
-            })
-            .translate(align_offset);
+        self.bounds_rect = crate::common::rich_text::measure_markdown(
+            canvas,
+            self.position,
+            self.align,
+            &read.text,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        )
+        .expand2(egui::Vec2 {
+            x: Self::CORNER_SIZE,
+            y: Self::CORNER_SIZE,
+        })
+        .translate(align_offset);
 
         canvas.draw_polygon(
             [
@@ -4745,7 +4876,8 @@ impl ElementControllerGen2<NetworkDomain> for NetworkCommentView {
             canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
             self.highlight,
         );
-        canvas.draw_text(
+        crate::common::rich_text::draw_markdown(
+            canvas,
             self.position + align_offset,
             self.align,
             &read.text,
@@ -4926,7 +5058,9 @@ impl ElementControllerGen2<NetworkDomain> for NetworkCommentView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
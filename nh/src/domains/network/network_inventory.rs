@@ -0,0 +1,398 @@
+use std::collections::HashSet;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab, NHTab,
+    common::controller::{GlobalDrawingContext, ProjectCommand},
+    common::csv,
+    common::uuid::ViewUuid,
+    domains::network::network_models::NetworkNodeKind,
+};
+
+pub struct InventoryHost {
+    pub name: String,
+    pub kind: NetworkNodeKind,
+    pub subnet: Option<String>,
+}
+
+pub struct InventoryLink {
+    pub from: String,
+    pub to: String,
+    pub bandwidth: String,
+}
+
+/// Flat list of hosts (optionally grouped into subnets) and the links between them.
+#[derive(Default)]
+pub struct NetworkInventory {
+    pub hosts: Vec<InventoryHost>,
+    pub links: Vec<InventoryLink>,
+}
+
+impl NetworkInventory {
+    /// Subnet names in order of their first appearance.
+    pub fn subnets(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.hosts
+            .iter()
+            .flat_map(|e| e.subnet.as_deref())
+            .filter(|e| seen.insert(*e))
+            .collect()
+    }
+
+    fn validate(self) -> Result<Self, String> {
+        let mut names = HashSet::new();
+        for h in &self.hosts {
+            if h.name.is_empty() {
+                return Err("host with an empty name".to_owned());
+            }
+            if !names.insert(h.name.as_str()) {
+                return Err(format!("duplicate host '{}'", h.name));
+            }
+        }
+        for l in &self.links {
+            for e in [&l.from, &l.to] {
+                if !names.contains(e.as_str()) {
+                    return Err(format!("link refers to unknown host '{}'", e));
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn parse_kind(s: &str) -> Result<NetworkNodeKind, String> {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let key = normalize(s);
+    if key.is_empty() || key == "host" {
+        return Ok(NetworkNodeKind::default());
+    }
+    NetworkNodeKind::VARIANTS
+        .into_iter()
+        .find(|e| normalize(e.as_str()) == key)
+        .ok_or_else(|| format!("unknown node kind '{}'", s))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum InventoryFormat {
+    Yaml,
+    Csv,
+}
+
+impl InventoryFormat {
+    pub const VARIANTS: [Self; 2] = [Self::Yaml, Self::Csv];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InventoryFormat::Yaml => "YAML",
+            InventoryFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn parse(&self, source: &str) -> Result<NetworkInventory, String> {
+        match self {
+            InventoryFormat::Yaml => parse_yaml_inventory(source),
+            InventoryFormat::Csv => parse_csv_inventory(source),
+        }
+    }
+
+    fn example(&self) -> &'static str {
+        match self {
+            InventoryFormat::Yaml => {
+                "hosts:\n  - name: gateway\n    kind: router\n  - name: core\n    kind: switch\n    subnet: Office LAN\n  - name: web01\n    kind: server\n    subnet: Office LAN\nlinks:\n  - from: gateway\n    to: core\n    bandwidth: 10 Gbps\n  - from: core\n    to: web01\n    bandwidth: 1 Gbps\n"
+            }
+            InventoryFormat::Csv => {
+                "# host,name,kind,subnet\n# link,from,to,bandwidth\nhost,gateway,router,\nhost,core,switch,Office LAN\nhost,web01,server,Office LAN\nlink,gateway,core,10 Gbps\nlink,core,web01,1 Gbps\n"
+            }
+        }
+    }
+}
+
+/// Parses the following subset of YAML:
+///
+/// ```yaml
+/// hosts:
+///   - name: core
+///     kind: switch
+///     subnet: Office LAN
+/// links:
+///   - from: gateway
+///     to: core
+///     bandwidth: 10 Gbps
+/// ```
+pub fn parse_yaml_inventory(source: &str) -> Result<NetworkInventory, String> {
+    enum Section {
+        None,
+        Hosts,
+        Links,
+    }
+    type Item = Vec<(String, String)>;
+
+    fn unquote(s: &str) -> &str {
+        let s = s.trim();
+        for q in ['"', '\''] {
+            if let Some(inner) = s.strip_prefix(q).and_then(|e| e.strip_suffix(q)) {
+                return inner;
+            }
+        }
+        s
+    }
+    fn get<'a>(item: &'a Item, key: &str) -> Option<&'a str> {
+        item.iter()
+            .find(|e| e.0 == key)
+            .map(|e| e.1.as_str())
+            .filter(|e| !e.is_empty())
+    }
+
+    let mut section = Section::None;
+    let mut hosts: Vec<(usize, Item)> = Vec::new();
+    let mut links: Vec<(usize, Item)> = Vec::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let content = match line.find(" #") {
+            Some(i) => &line[..i],
+            None => line,
+        };
+        if content.trim().is_empty() || content.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if !content.starts_with(' ') && !content.starts_with('-') {
+            section = match content.trim() {
+                "hosts:" => Section::Hosts,
+                "links:" => Section::Links,
+                other => return Err(format!("line {}: unknown section '{}'", line_no, other)),
+            };
+            continue;
+        }
+
+        let items = match section {
+            Section::None => {
+                return Err(format!("line {}: entry outside of a section", line_no));
+            }
+            Section::Hosts => &mut hosts,
+            Section::Links => &mut links,
+        };
+        let trimmed = content.trim_start();
+        let pair = if let Some(rest) = trimmed.strip_prefix('-') {
+            items.push((line_no, Vec::new()));
+            rest.trim_start()
+        } else {
+            trimmed
+        };
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = pair.split_once(':') else {
+            return Err(format!("line {}: expected 'key: value'", line_no));
+        };
+        let Some(item) = items.last_mut() else {
+            return Err(format!("line {}: expected '- ' to start an entry", line_no));
+        };
+        item.1
+            .push((key.trim().to_owned(), unquote(value).to_owned()));
+    }
+
+    let mut inventory = NetworkInventory::default();
+    for (line_no, item) in hosts {
+        inventory.hosts.push(InventoryHost {
+            name: get(&item, "name")
+                .ok_or_else(|| format!("line {}: host without a name", line_no))?
+                .to_owned(),
+            kind: parse_kind(get(&item, "kind").unwrap_or(""))
+                .map_err(|e| format!("line {}: {}", line_no, e))?,
+            subnet: get(&item, "subnet").map(|e| e.to_owned()),
+        });
+    }
+    for (line_no, item) in links {
+        let endpoint = |key| {
+            get(&item, key)
+                .map(|e| e.to_owned())
+                .ok_or_else(|| format!("line {}: link without '{}'", line_no, key))
+        };
+        inventory.links.push(InventoryLink {
+            from: endpoint("from")?,
+            to: endpoint("to")?,
+            bandwidth: get(&item, "bandwidth").unwrap_or("").to_owned(),
+        });
+    }
+
+    inventory.validate()
+}
+
+/// Parses rows of either `host,name,kind,subnet` or `link,from,to,bandwidth`.
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_csv_inventory(source: &str) -> Result<NetworkInventory, String> {
+    let mut inventory = NetworkInventory::default();
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let fields = csv::split_line(line);
+        let field = |i: usize| fields.get(i).map(|e| e.as_str()).unwrap_or("");
+
+        match field(0).to_lowercase().as_str() {
+            "host" => inventory.hosts.push(InventoryHost {
+                name: field(1).to_owned(),
+                kind: parse_kind(field(2)).map_err(|e| format!("line {}: {}", line_no, e))?,
+                subnet: Some(field(3).to_owned()).filter(|e| !e.is_empty()),
+            }),
+            "link" => {
+                if field(1).is_empty() || field(2).is_empty() {
+                    return Err(format!("line {}: link without both endpoints", line_no));
+                }
+                inventory.links.push(InventoryLink {
+                    from: field(1).to_owned(),
+                    to: field(2).to_owned(),
+                    bandwidth: field(3).to_owned(),
+                });
+            }
+            other => {
+                return Err(format!("line {}: unknown record type '{}'", line_no, other));
+            }
+        }
+    }
+
+    inventory.validate()
+}
+
+pub struct NetworkInventoryImportTab {
+    format: InventoryFormat,
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for NetworkInventoryImportTab {
+    fn default() -> Self {
+        Self {
+            format: InventoryFormat::Yaml,
+            source: InventoryFormat::Yaml.example().to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for NetworkInventoryImportTab {
+    fn title(&self) -> String {
+        "Inventory Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            let previous_format = self.format;
+            egui::ComboBox::from_label("Format")
+                .selected_text(self.format.as_str())
+                .show_ui(ui, |ui| {
+                    for e in InventoryFormat::VARIANTS {
+                        ui.selectable_value(&mut self.format, e, e.as_str());
+                    }
+                });
+            if self.format != previous_format && self.source == previous_format.example() {
+                self.source = self.format.example().to_owned();
+            }
+
+            if ui.button("Import").clicked() {
+                self.status = Some(match self.format.parse(&self.source) {
+                    Ok(inventory) => {
+                        let (uuid, controller) =
+                            super::network_controllers::from_inventory(&inventory);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        Ok(format!(
+                            "Imported {} hosts and {} links",
+                            inventory.hosts.len(),
+                            inventory.links.len()
+                        ))
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_and_csv_examples_agree() {
+        let yaml = InventoryFormat::Yaml
+            .parse(InventoryFormat::Yaml.example())
+            .unwrap();
+        let csv = InventoryFormat::Csv
+            .parse(InventoryFormat::Csv.example())
+            .unwrap();
+
+        for inventory in [&yaml, &csv] {
+            assert_eq!(inventory.hosts.len(), 3);
+            assert_eq!(inventory.links.len(), 2);
+            assert_eq!(inventory.subnets(), vec!["Office LAN"]);
+            assert!(inventory.hosts[0].kind == NetworkNodeKind::Router);
+            assert!(inventory.hosts[0].subnet.is_none());
+            assert_eq!(inventory.links[0].bandwidth, "10 Gbps");
+        }
+    }
+
+    #[test]
+    fn csv_quoted_fields() {
+        let inventory = parse_csv_inventory(
+            "host,\"db, primary\",server,\"Data \"\"center\"\"\"\nhost,db2,Cellular Phone,\n",
+        )
+        .unwrap();
+        assert_eq!(inventory.hosts[0].name, "db, primary");
+        assert_eq!(
+            inventory.hosts[0].subnet.as_deref(),
+            Some("Data \"center\"")
+        );
+        assert!(inventory.hosts[1].kind == NetworkNodeKind::CellularPhone);
+    }
+
+    #[test]
+    fn invalid_inventories() {
+        assert!(parse_csv_inventory("host,a,server,\nlink,a,b,\n").is_err());
+        assert!(parse_csv_inventory("host,a,server,\nhost,a,router,\n").is_err());
+        assert!(parse_csv_inventory("host,a,toaster,\n").is_err());
+        assert!(parse_yaml_inventory("nodes:\n  - name: a\n").is_err());
+        assert!(parse_yaml_inventory("hosts:\n  - kind: server\n").is_err());
+    }
+}
@@ -1,3 +1,8 @@
 pub mod ontouml_controllers;
+pub mod ontouml_json;
 pub mod ontouml_models;
+pub mod ontouml_patterns;
 pub mod ontouml_validations;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ontouml_owl_import;
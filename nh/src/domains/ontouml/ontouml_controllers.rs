@@ -1,8 +1,9 @@
 use super::super::umlclass::{
     umlclass_controllers::{
-        LinkType, PlantUmlTab, StereotypeController, UmlClassDiagramAdapter, UmlClassDomain,
+        LinkType, StereotypeController, TextDescriptionTab, UmlClassDiagramAdapter, UmlClassDomain,
         UmlClassElementOrVertex, UmlClassElementView, UmlClassProfile, UmlClassToolStage,
         new_umlclass_association, new_umlclass_class, new_umlclass_generalization,
+        plantuml_elements,
     },
     umlclass_models::UmlClassDiagram,
 };
@@ -11,9 +12,10 @@ use crate::{
     DiagramCreationData, DiagramInfo,
     common::{
         controller::{
-            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            BucketNoT, CheckProblem, ControllerAdapter, DiagramController, DiagramControllerGen2,
             DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
-            MGlobalColor, MultiDiagramController, PositionNoT, ProjectCommand, View,
+            MGlobalColor, MultiDiagramController, PositionNoT, ProjectCommand, TextExportFormat,
+            View,
         },
         eref::ERef,
         project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
@@ -21,10 +23,15 @@ use crate::{
         views::multiconnection_view::MULTICONNECTION_SOURCE_BUCKET,
     },
     domains::{
-        ontouml::ontouml_models,
+        ontouml::{
+            ontouml_json::{ONTOUML_JSON_FORMAT, ontouml_json_document},
+            ontouml_models,
+        },
         umlclass::{
             umlclass_controllers::{PartialUmlClassElement, UmlClassRenderStyle, UmlClassView},
+            umlclass_mermaid::MERMAID_FORMAT,
             umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind},
+            umlclass_plantuml::PLANTUML_FORMAT,
         },
     },
 };
@@ -50,7 +57,14 @@ impl UmlClassProfile for OntoUmlProfile {
             let uuid = uuid::Uuid::now_v7();
             commands.push(ProjectCommand::AddCustomTab(
                 uuid,
-                Arc::new(RwLock::new(PlantUmlTab::new(model.clone()))),
+                Arc::new(RwLock::new(TextDescriptionTab::plantuml(model.clone()))),
+            ));
+        }
+        if ui.button("Mermaid description").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(TextDescriptionTab::mermaid(model.clone()))),
             ));
         }
         if ui.button("OntoUML Validations").clicked() {
@@ -65,8 +79,22 @@ impl UmlClassProfile for OntoUmlProfile {
                 )),
             ));
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Import OWL").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::ontouml_owl_import::OwlImportTab::default(),
+                )),
+            ));
+        }
         ui.separator();
     }
+
+    fn pattern_elements(pattern: &str, position: egui::Pos2) -> Vec<UmlClassElementView<Self>> {
+        super::ontouml_patterns::pattern_elements(pattern, position)
+    }
 }
 
 #[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
@@ -133,6 +161,36 @@ impl ControllerAdapter<UmlClassDomain<OntoUmlProfile>> for OntoUmlControllerAdap
         }
         None
     }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[PLANTUML_FORMAT, MERMAID_FORMAT, ONTOUML_JSON_FORMAT]
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        match *format {
+            PLANTUML_FORMAT => Some(self.model.read().plantuml_document(languages)),
+            MERMAID_FORMAT => Some(self.model.read().mermaid_document(languages)),
+            ONTOUML_JSON_FORMAT => Some(ontouml_json_document(&self.model.read())),
+            _ => None,
+        }
+    }
+    fn export_selected_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+        selection: &HashSet<ModelUuid>,
+    ) -> Option<String> {
+        match *format {
+            PLANTUML_FORMAT => Some(self.model.read().plantuml_document_of(languages, selection)),
+            _ => None,
+        }
+    }
+    fn check_problems(&self) -> Vec<CheckProblem> {
+        super::ontouml_validations::check_problems(&self.model)
+    }
 }
 
 fn new_controlller(
@@ -306,6 +364,22 @@ pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
     )
 }
 
+pub fn from_plantuml(
+    source: &super::super::umlclass::umlclass_plantuml_import::PlantUmlClassDiagram,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (root_models, root_views) = plantuml_elements::<OntoUmlProfile>(source);
+    let name = source
+        .title
+        .clone()
+        .unwrap_or_else(|| "Imported OntoUML diagram".to_owned());
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        root_models,
+    ));
+    new_controlller(diagram, name, root_views)
+}
+
 pub fn deserializer(
     uuid: ControllerUuid,
     d: &mut NHDeserializer,
@@ -569,9 +643,23 @@ pub fn default_settings() -> Box<dyn DiagramSettings> {
         ));
     }
 
+    let patterns = super::ontouml_patterns::PATTERNS
+        .iter()
+        .map(|(pattern, label)| {
+            (
+                UmlClassToolStage::Pattern {
+                    pattern: (*pattern).to_owned(),
+                },
+                *label,
+                None,
+            )
+        })
+        .collect();
+
     let palette_items = vec![
         ("Classes", classes),
         ("Relationships", relationships),
+        ("Patterns", patterns),
         (
             "Other",
             vec![
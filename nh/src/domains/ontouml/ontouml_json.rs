@@ -0,0 +1,376 @@
+use serde_json::{Value, json};
+
+use super::ontouml_models;
+use crate::{
+    common::{controller::TextExportFormat, uuid::ModelUuid},
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassAssociationAggregation,
+        UmlClassComment, UmlClassCommentLink, UmlClassDependency, UmlClassDiagram,
+        UmlClassGeneralization, UmlClassInstance, UmlClassPackage, UmlClassVisitor, UmlUseCase,
+        UmlUseCaseGeneralization,
+    },
+};
+
+pub const ONTOUML_JSON_FORMAT: TextExportFormat = TextExportFormat {
+    name: "OntoUML JSON",
+    extensions: &["json"],
+};
+
+/// Serializes the diagram as a project following the ontouml-schema, as used by ontouml-js
+/// and the OntoUML services
+pub fn ontouml_json_document(diagram: &UmlClassDiagram) -> String {
+    let mut collector = OntoUmlJsonCollector {
+        contents: vec![vec![]],
+    };
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+    let contents = collector.contents.pop().unwrap_or_default();
+
+    let project = json!({
+        "id": diagram.uuid.to_string(),
+        "name": *diagram.name,
+        "description": description(&diagram.comment),
+        "type": "Project",
+        "model": {
+            "id": format!("{}-model", diagram.uuid.to_string()),
+            "name": *diagram.name,
+            "description": null,
+            "type": "Package",
+            "propertyAssignments": null,
+            "contents": contents,
+        },
+        "diagrams": [],
+    });
+    serde_json::to_string_pretty(&project).unwrap_or_default()
+}
+
+fn description(comment: &str) -> Value {
+    if comment.is_empty() {
+        Value::Null
+    } else {
+        Value::from(comment)
+    }
+}
+
+/// Ultimate sortals, sortals and non-sortals are all restricted to their usual nature
+fn restricted_to(stereotype: &str) -> Value {
+    let nature = match stereotype {
+        ontouml_models::KIND
+        | ontouml_models::SUBKIND
+        | ontouml_models::PHASE
+        | ontouml_models::ROLE
+        | ontouml_models::CATEGORY
+        | ontouml_models::PHASE_MIXIN
+        | ontouml_models::ROLE_MIXIN
+        | ontouml_models::MIXIN => "functional-complex",
+        ontouml_models::COLLECTIVE => "collective",
+        ontouml_models::QUANTITY => "quantity",
+        ontouml_models::RELATOR => "relator",
+        ontouml_models::MODE => "intrinsic-mode",
+        ontouml_models::QUALITY => "quality",
+        _ => return Value::Null,
+    };
+    json!([nature])
+}
+
+fn reference(uuid: &ModelUuid, element_type: &str) -> Value {
+    json!({ "id": uuid.to_string(), "type": element_type })
+}
+
+struct OntoUmlJsonCollector {
+    /// Contents of the currently visited packages, innermost last
+    contents: Vec<Vec<Value>>,
+}
+
+impl OntoUmlJsonCollector {
+    fn push(&mut self, e: Value) {
+        if let Some(c) = self.contents.last_mut() {
+            c.push(e);
+        }
+    }
+
+    fn association_end(
+        id: String,
+        class: &UmlClassAssociable,
+        role: &str,
+        multiplicity: &str,
+        aggregation: UmlClassAssociationAggregation,
+    ) -> Value {
+        let property_type = match class {
+            UmlClassAssociable::Class(inner) => reference(&inner.read().uuid, "Class"),
+            UmlClassAssociable::Instance(..) | UmlClassAssociable::UseCase(..) => Value::Null,
+        };
+        json!({
+            "id": id,
+            "name": if role.is_empty() { Value::Null } else { Value::from(role) },
+            "description": null,
+            "type": "Property",
+            "propertyAssignments": null,
+            "stereotype": null,
+            "isDerived": false,
+            "isReadOnly": false,
+            "isOrdered": false,
+            "cardinality": if multiplicity.is_empty() { "0..*" } else { multiplicity },
+            "propertyType": property_type,
+            "subsettedProperties": null,
+            "redefinedProperties": null,
+            // The aggregation is kept at the end where the diamond is drawn, i.e. the whole
+            "aggregationKind": match aggregation {
+                UmlClassAssociationAggregation::None => "NONE",
+                UmlClassAssociationAggregation::Shared => "SHARED",
+                UmlClassAssociationAggregation::Composite => "COMPOSITE",
+            },
+        })
+    }
+}
+
+impl UmlClassVisitor for OntoUmlJsonCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        self.contents.push(vec![]);
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+        let contents = self.contents.pop().unwrap_or_default();
+
+        self.push(json!({
+            "id": package.uuid.to_string(),
+            "name": *package.name,
+            "description": description(&package.comment),
+            "type": "Package",
+            "propertyAssignments": null,
+            "contents": contents,
+        }));
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let properties: Vec<Value> = class
+            .properties
+            .iter()
+            .map(|e| {
+                let r = e.read();
+                json!({
+                    "id": r.uuid.to_string(),
+                    "name": *r.name,
+                    "description": null,
+                    "type": "Property",
+                    "propertyAssignments": null,
+                    "stereotype": null,
+                    "isDerived": r.is_derived,
+                    "isReadOnly": r.is_read_only,
+                    "isOrdered": r.is_ordered,
+                    "cardinality": if r.multiplicity.is_empty() { "1" } else { r.multiplicity.as_str() },
+                    "propertyType": null,
+                    "subsettedProperties": null,
+                    "redefinedProperties": null,
+                    "aggregationKind": "NONE",
+                })
+            })
+            .collect();
+
+        self.push(json!({
+            "id": class.uuid.to_string(),
+            "name": *class.name,
+            "description": description(&class.comment),
+            "type": "Class",
+            "propertyAssignments": null,
+            "stereotype": if class.stereotype.is_empty() {
+                Value::Null
+            } else {
+                Value::from(class.stereotype.as_str())
+            },
+            "isAbstract": class.is_abstract,
+            "isDerived": false,
+            "properties": properties,
+            "isExtensional": if *class.stereotype == ontouml_models::COLLECTIVE {
+                Value::from(false)
+            } else {
+                Value::Null
+            },
+            "isPowertype": false,
+            "order": null,
+            "literals": null,
+            "restrictedTo": restricted_to(&class.stereotype),
+        }));
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, generalization: &UmlClassGeneralization) {
+        let pairs: Vec<_> = generalization
+            .sources
+            .iter()
+            .flat_map(|s| generalization.targets.iter().map(move |t| (s, t)))
+            .collect();
+        let mut ids = Vec::new();
+        for (idx, (specific, general)) in pairs.iter().enumerate() {
+            let id = if pairs.len() == 1 {
+                generalization.uuid.to_string()
+            } else {
+                format!("{}-{}", generalization.uuid.to_string(), idx)
+            };
+            self.push(json!({
+                "id": id,
+                "name": null,
+                "description": null,
+                "type": "Generalization",
+                "propertyAssignments": null,
+                "general": reference(&general.read().uuid, "Class"),
+                "specific": reference(&specific.read().uuid, "Class"),
+            }));
+            ids.push(id);
+        }
+
+        // Generalizations only form a set when there is something to say about it
+        if pairs.len() > 1
+            || !generalization.set_name.is_empty()
+            || generalization.set_is_covering
+            || generalization.set_is_disjoint
+        {
+            self.push(json!({
+                "id": format!("{}-set", generalization.uuid.to_string()),
+                "name": if generalization.set_name.is_empty() {
+                    Value::Null
+                } else {
+                    Value::from(generalization.set_name.as_str())
+                },
+                "description": description(&generalization.comment),
+                "type": "GeneralizationSet",
+                "propertyAssignments": null,
+                "isDisjoint": generalization.set_is_disjoint,
+                "isComplete": generalization.set_is_covering,
+                "categorizer": null,
+                "generalizations": ids
+                    .iter()
+                    .map(|e| json!({ "id": e, "type": "Generalization" }))
+                    .collect::<Vec<_>>(),
+            }));
+        }
+    }
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, association: &UmlClassAssociation) {
+        self.push(json!({
+            "id": association.uuid.to_string(),
+            "name": if association.name.is_empty() {
+                Value::Null
+            } else {
+                Value::from(association.name.as_str())
+            },
+            "description": description(&association.comment),
+            "type": "Relation",
+            "propertyAssignments": null,
+            "stereotype": if association.stereotype.is_empty() {
+                Value::Null
+            } else {
+                Value::from(association.stereotype.as_str())
+            },
+            "isAbstract": false,
+            "isDerived": false,
+            "properties": [
+                Self::association_end(
+                    format!("{}-source", association.uuid.to_string()),
+                    &association.source,
+                    &association.source_label_role,
+                    &association.source_label_multiplicity,
+                    association.source_aggregation,
+                ),
+                Self::association_end(
+                    format!("{}-target", association.uuid.to_string()),
+                    &association.target,
+                    &association.target_label_role,
+                    &association.target_label_multiplicity,
+                    association.target_aggregation,
+                ),
+            ],
+        }));
+    }
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::eref::ERef,
+        domains::umlclass::umlclass_models::{UmlClassAssociation, UmlClassElement},
+    };
+
+    #[test]
+    fn classes_relations_and_generalization_sets_follow_the_schema() {
+        let class = |name: &str, stereotype: &str| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                stereotype.to_owned(),
+                String::new(),
+                false,
+                vec![],
+                vec![],
+            ))
+        };
+        let (person, child, adult, enrollment) = (
+            class("Person", ontouml_models::KIND),
+            class("Child", ontouml_models::PHASE),
+            class("Adult", ontouml_models::PHASE),
+            class("Enrollment", ontouml_models::RELATOR),
+        );
+        let mut generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            "age".to_owned(),
+            vec![child.clone(), adult.clone()],
+            vec![person.clone()],
+        );
+        generalization.set_is_disjoint = true;
+        generalization.set_is_covering = true;
+        let mediation = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            ontouml_models::MEDIATION.to_owned(),
+            String::new(),
+            enrollment.clone().into(),
+            "1..*".to_owned(),
+            person.clone().into(),
+            "1".to_owned(),
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "School".to_owned(),
+            vec![
+                UmlClassElement::Class(person),
+                UmlClassElement::Class(child),
+                UmlClassElement::Class(adult),
+                UmlClassElement::Class(enrollment),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+                UmlClassElement::Association(ERef::new(mediation)),
+            ],
+        );
+
+        let document: Value = serde_json::from_str(&ontouml_json_document(&diagram)).unwrap();
+        assert_eq!(document["type"], "Project");
+        let contents = document["model"]["contents"].as_array().unwrap();
+        let of_type = |t: &str| {
+            contents
+                .iter()
+                .filter(|e| e["type"] == t)
+                .collect::<Vec<_>>()
+        };
+
+        let classes = of_type("Class");
+        assert_eq!(classes[0]["stereotype"], "kind");
+        assert_eq!(classes[0]["restrictedTo"], json!(["functional-complex"]));
+        assert_eq!(classes[3]["restrictedTo"], json!(["relator"]));
+
+        assert_eq!(of_type("Generalization").len(), 2);
+        let sets = of_type("GeneralizationSet");
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0]["isComplete"], true);
+        assert_eq!(sets[0]["generalizations"].as_array().unwrap().len(), 2);
+
+        let relation = &of_type("Relation")[0];
+        assert_eq!(relation["stereotype"], "mediation");
+        assert_eq!(relation["properties"][0]["cardinality"], "1..*");
+        assert_eq!(
+            relation["properties"][1]["propertyType"]["id"],
+            classes[0]["id"]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use super::ontouml_models;
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        uuid::ViewUuid,
+    },
+    domains::{
+        rdf::rdf_import::{ImportedObject, RdfImportFormat, parse_rdf},
+        umlclass::{
+            umlclass_models::UmlClassAssociationNavigability,
+            umlclass_plantuml_import::{
+                PlantUmlAssociationEnd, PlantUmlClass, PlantUmlClassDiagram, PlantUmlLink,
+                PlantUmlLinkKind, PlantUmlMember,
+            },
+        },
+    },
+};
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const RDFS_SUBCLASSOF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+const RDFS_SUBPROPERTYOF: &str = "http://www.w3.org/2000/01/rdf-schema#subPropertyOf";
+const RDFS_DOMAIN: &str = "http://www.w3.org/2000/01/rdf-schema#domain";
+const RDFS_RANGE: &str = "http://www.w3.org/2000/01/rdf-schema#range";
+const OWL_ONTOLOGY: &str = "http://www.w3.org/2002/07/owl#Ontology";
+const OWL_CLASS: &str = "http://www.w3.org/2002/07/owl#Class";
+const OWL_OBJECT_PROPERTY: &str = "http://www.w3.org/2002/07/owl#ObjectProperty";
+const OWL_DATATYPE_PROPERTY: &str = "http://www.w3.org/2002/07/owl#DatatypeProperty";
+const OWL_FUNCTIONAL_PROPERTY: &str = "http://www.w3.org/2002/07/owl#FunctionalProperty";
+/// Namespace of gUFO, the OWL implementation of UFO
+const GUFO: &str = "http://purl.org/nemo/gufo#";
+
+/// Part of the IRI after the last `#` or `/`
+fn local_name(iri: &str) -> &str {
+    iri.rsplit(['#', '/']).next().unwrap_or(iri)
+}
+
+/// Class stereotype corresponding to a gUFO class the class instantiates or specializes
+fn gufo_class_stereotype(iri: &str) -> Option<&'static str> {
+    let e = match iri.strip_prefix(GUFO)? {
+        "Kind" => ontouml_models::KIND,
+        "SubKind" => ontouml_models::SUBKIND,
+        "Phase" => ontouml_models::PHASE,
+        "Role" => ontouml_models::ROLE,
+        "Category" => ontouml_models::CATEGORY,
+        "Mixin" => ontouml_models::MIXIN,
+        "RoleMixin" => ontouml_models::ROLE_MIXIN,
+        "PhaseMixin" => ontouml_models::PHASE_MIXIN,
+        "Relator" => ontouml_models::RELATOR,
+        "Quality" => ontouml_models::QUALITY,
+        "IntrinsicMode" | "ExtrinsicMode" => ontouml_models::MODE,
+        "FixedCollection" | "VariableCollection" => ontouml_models::COLLECTIVE,
+        "Quantity" => ontouml_models::QUANTITY,
+        _ => return None,
+    };
+    Some(e)
+}
+
+/// Association stereotype corresponding to a gUFO property the property specializes
+fn gufo_association_stereotype(iri: &str) -> Option<&'static str> {
+    let e = match iri.strip_prefix(GUFO)? {
+        "mediates" => ontouml_models::MEDIATION,
+        "inheresIn" | "bears" => ontouml_models::CHARACTERIZATION,
+        "isComponentOf" => ontouml_models::COMPONENT_OF,
+        "isCollectionMemberOf" => ontouml_models::MEMBER_OF,
+        "isSubCollectionOf" => ontouml_models::SUBCOLLECTION_OF,
+        "isSubQuantityOf" => ontouml_models::SUBQUANTITY_OF,
+        _ => return None,
+    };
+    Some(e)
+}
+
+#[derive(Default)]
+struct Description<'a> {
+    types: Vec<&'a str>,
+    label: Option<&'a str>,
+    supertypes: Vec<usize>,
+    domain: Option<usize>,
+    range: Option<usize>,
+}
+
+/// Reads classes and properties of an OWL ontology.
+///
+/// Classes become OntoUML classes, object properties between them associations and
+/// datatype properties attributes. Stereotypes are taken from gUFO classes and properties
+/// where used, otherwise they are guessed from the specialization hierarchy.
+pub fn parse_owl(source: &str, format: RdfImportFormat) -> Result<PlantUmlClassDiagram, String> {
+    let rdf = parse_rdf(source, format)?;
+
+    let mut descriptions: Vec<Description> = rdf.nodes.iter().map(|_| Default::default()).collect();
+    for (subject, predicate, object) in &rdf.statements {
+        let d = &mut descriptions[*subject];
+        match (predicate.as_str(), object) {
+            (RDF_TYPE, ImportedObject::Node(o)) => d.types.push(&rdf.nodes[*o]),
+            (RDFS_LABEL, ImportedObject::Literal { content, .. }) => {
+                d.label.get_or_insert(content);
+            }
+            (RDFS_SUBCLASSOF | RDFS_SUBPROPERTYOF, ImportedObject::Node(o)) => {
+                d.supertypes.push(*o)
+            }
+            (RDFS_DOMAIN, ImportedObject::Node(o)) => d.domain = Some(*o),
+            (RDFS_RANGE, ImportedObject::Node(o)) => d.range = Some(*o),
+            _ => {}
+        }
+    }
+    let is_a = |node: usize, t: &str| descriptions[node].types.contains(&t);
+    let name = |node: usize| {
+        descriptions[node]
+            .label
+            .unwrap_or_else(|| local_name(&rdf.nodes[node]))
+            .to_owned()
+    };
+
+    let mut diagram = PlantUmlClassDiagram {
+        title: (0..rdf.nodes.len())
+            .find(|e| is_a(*e, OWL_ONTOLOGY))
+            .and_then(|e| descriptions[e].label.map(|e| e.to_owned())),
+        ..Default::default()
+    };
+    let mut class_indices = HashMap::new();
+    for (node, iri) in rdf.nodes.iter().enumerate() {
+        if is_a(node, OWL_CLASS) && !iri.starts_with("_:") && !iri.starts_with(GUFO) {
+            class_indices.insert(node, diagram.classes.len());
+            diagram.classes.push(PlantUmlClass {
+                name: name(node),
+                stereotype: String::new(),
+                is_abstract: false,
+                members: Vec::new(),
+                package: None,
+            });
+        }
+    }
+    if diagram.classes.is_empty() {
+        return Err("no OWL classes found".to_owned());
+    }
+
+    for (&node, &class) in &class_indices {
+        let d = &descriptions[node];
+        let specialized = d
+            .supertypes
+            .iter()
+            .find_map(|e| gufo_class_stereotype(&rdf.nodes[*e]));
+        let instantiated = d.types.iter().find_map(|e| gufo_class_stereotype(e));
+        let has_superclass = d.supertypes.iter().any(|e| class_indices.contains_key(e));
+        // gUFO kinds of relators and aspects specialize the respective gUFO class
+        let stereotype = match (specialized, instantiated) {
+            (Some(s), _) if s != ontouml_models::KIND => s,
+            (_, Some(s)) => s,
+            _ if has_superclass => ontouml_models::SUBKIND,
+            _ => ontouml_models::KIND,
+        };
+        diagram.classes[class].stereotype = stereotype.to_owned();
+    }
+
+    let class_of = |node: Option<usize>| node.and_then(|e| class_indices.get(&e).copied());
+    for (node, d) in descriptions.iter().enumerate() {
+        let multiplicity = if is_a(node, OWL_FUNCTIONAL_PROPERTY) {
+            "0..1"
+        } else {
+            ""
+        };
+        if let Some(class) = class_indices.get(&node) {
+            for superclass in d.supertypes.iter().flat_map(|e| class_indices.get(e)) {
+                diagram.links.push(PlantUmlLink {
+                    kind: PlantUmlLinkKind::Generalization,
+                    source: *class,
+                    target: *superclass,
+                    source_multiplicity: String::new(),
+                    target_multiplicity: String::new(),
+                    label: String::new(),
+                    stereotype: String::new(),
+                });
+            }
+        } else if is_a(node, OWL_OBJECT_PROPERTY)
+            && let (Some(source), Some(target)) = (class_of(d.domain), class_of(d.range))
+        {
+            let is_one_of = |class: usize, stereotypes: &[&str]| {
+                stereotypes.contains(&diagram.classes[class].stereotype.as_str())
+            };
+            let aspects = [ontouml_models::QUALITY, ontouml_models::MODE];
+            let stereotype = d
+                .supertypes
+                .iter()
+                .find_map(|e| gufo_association_stereotype(&rdf.nodes[*e]))
+                .unwrap_or(
+                    if is_one_of(source, &[ontouml_models::RELATOR])
+                        || is_one_of(target, &[ontouml_models::RELATOR])
+                    {
+                        ontouml_models::MEDIATION
+                    } else if is_one_of(source, &aspects) || is_one_of(target, &aspects) {
+                        ontouml_models::CHARACTERIZATION
+                    } else {
+                        ontouml_models::NONE
+                    },
+                );
+            diagram.links.push(PlantUmlLink {
+                kind: PlantUmlLinkKind::Association(
+                    PlantUmlAssociationEnd::default(),
+                    PlantUmlAssociationEnd {
+                        navigability: UmlClassAssociationNavigability::Navigable,
+                        ..Default::default()
+                    },
+                ),
+                source,
+                target,
+                source_multiplicity: String::new(),
+                target_multiplicity: multiplicity.to_owned(),
+                label: name(node),
+                stereotype: stereotype.to_owned(),
+            });
+        } else if is_a(node, OWL_DATATYPE_PROPERTY)
+            && let Some(class) = class_of(d.domain)
+        {
+            diagram.classes[class].members.push(PlantUmlMember {
+                name: name(node),
+                value_type: d
+                    .range
+                    .map(|e| local_name(&rdf.nodes[e]).to_owned())
+                    .unwrap_or_default(),
+                multiplicity: multiplicity.to_owned(),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(diagram)
+}
+
+pub struct OwlImportTab {
+    format: RdfImportFormat,
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for OwlImportTab {
+    fn default() -> Self {
+        Self {
+            format: RdfImportFormat::Turtle,
+            source: "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .\n@prefix gufo: <http://purl.org/nemo/gufo#> .\n@prefix ex: <http://example.org/> .\n\nex:Person a owl:Class, gufo:Kind .\nex:Student a owl:Class ; rdfs:subClassOf ex:Person .\nex:Enrollment a owl:Class, gufo:Kind ; rdfs:subClassOf gufo:Relator .\nex:enrolls a owl:ObjectProperty ; rdfs:domain ex:Enrollment ; rdfs:range ex:Student .\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for OwlImportTab {
+    fn title(&self) -> String {
+        "OWL Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            for f in [RdfImportFormat::Turtle, RdfImportFormat::RdfXml] {
+                ui.radio_value(&mut self.format, f, f.name());
+            }
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_owl(&self.source, self.format) {
+                    Ok(diagram) => {
+                        let (uuid, controller) =
+                            super::ontouml_controllers::from_plantuml(&diagram);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        Ok(format!(
+                            "Imported {} classes and {} relations",
+                            diagram.classes.len(),
+                            diagram.links.len(),
+                        ))
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_and_properties_are_mapped() {
+        let d = parse_owl(
+            "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n\
+             @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .\n\
+             @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+             @prefix gufo: <http://purl.org/nemo/gufo#> .\n\
+             @prefix ex: <http://example.org/> .\n\
+             ex:Person a owl:Class ; rdfs:label \"Person\"@en .\n\
+             ex:Student a owl:Class, gufo:Role ; rdfs:subClassOf ex:Person .\n\
+             ex:Enrollment a owl:Class, gufo:Kind ; rdfs:subClassOf gufo:Relator .\n\
+             ex:enrolls a owl:ObjectProperty ; rdfs:domain ex:Enrollment ; rdfs:range ex:Student .\n\
+             ex:name a owl:DatatypeProperty, owl:FunctionalProperty ;\n\
+                 rdfs:domain ex:Person ; rdfs:range xsd:string .\n",
+            RdfImportFormat::Turtle,
+        )
+        .unwrap();
+
+        let classes: Vec<_> = d
+            .classes
+            .iter()
+            .map(|e| (e.name.as_str(), e.stereotype.as_str()))
+            .collect();
+        assert_eq!(
+            classes,
+            [
+                ("Person", ontouml_models::KIND),
+                ("Student", ontouml_models::ROLE),
+                ("Enrollment", ontouml_models::RELATOR),
+            ]
+        );
+        assert_eq!(d.classes[0].members[0].name, "name");
+        assert_eq!(d.classes[0].members[0].value_type, "string");
+        assert_eq!(d.classes[0].members[0].multiplicity, "0..1");
+
+        assert_eq!(d.links.len(), 2);
+        assert!(
+            d.links
+                .iter()
+                .any(|e| matches!(e.kind, PlantUmlLinkKind::Generalization)
+                    && (e.source, e.target) == (1, 0))
+        );
+        assert!(d.links.iter().any(|e| e.label == "enrolls"
+            && e.stereotype == ontouml_models::MEDIATION
+            && (e.source, e.target) == (2, 1)));
+
+        assert!(
+            parse_owl(
+                "@prefix ex: <http://example.org/> .\nex:a ex:b ex:c .\n",
+                RdfImportFormat::Turtle
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use eframe::egui;
+
+use super::{
+    ontouml_controllers::{OntoUmlProfile, new_ontouml_class},
+    ontouml_models,
+};
+use crate::{
+    common::{eref::ERef, uuid::ModelUuid},
+    domains::umlclass::{
+        umlclass_controllers::{
+            UmlClassElementView, UmlClassView, new_umlclass_association,
+            new_umlclass_generalization, new_umlclass_generalization_view,
+        },
+        umlclass_models::{UmlClass, UmlClassGeneralization},
+    },
+};
+
+pub const SUBKIND_PARTITION: &str = "subkindPartition";
+pub const PHASE_PARTITION: &str = "phasePartition";
+pub const RELATOR_ROLES: &str = "relatorRoles";
+
+/// Pattern names and palette labels
+pub const PATTERNS: [(&str, &str); 3] = [
+    (SUBKIND_PARTITION, "Subkind Partition"),
+    (PHASE_PARTITION, "Phase Partition"),
+    (RELATOR_ROLES, "Role + Relator + Mediation"),
+];
+
+type ClassRefs = (ERef<UmlClass>, ERef<UmlClassView<OntoUmlProfile>>);
+
+/// Disjoint and complete generalization set of the given classes under the general one
+fn partition(
+    set_name: &str,
+    specifics: &[&ClassRefs],
+    general: &ClassRefs,
+) -> UmlClassElementView<OntoUmlProfile> {
+    let mut model = UmlClassGeneralization::new(
+        ModelUuid::now_v7(),
+        set_name.to_owned(),
+        specifics.iter().map(|e| e.0.clone()).collect(),
+        vec![general.0.clone()],
+    );
+    model.set_is_disjoint = true;
+    model.set_is_covering = true;
+    new_umlclass_generalization_view(
+        ERef::new(model),
+        None,
+        specifics.iter().map(|e| e.1.clone().into()).collect(),
+        vec![general.1.clone().into()],
+    )
+    .into()
+}
+
+/// Kind partitioned into two specializations of the given stereotype
+fn kind_partition(
+    stereotype: &str,
+    set_name: &str,
+    names: [&str; 3],
+    position: egui::Pos2,
+) -> Vec<UmlClassElementView<OntoUmlProfile>> {
+    let kind = new_ontouml_class(names[0], ontouml_models::KIND, false, position);
+    let a = new_ontouml_class(
+        names[1],
+        stereotype,
+        false,
+        position + egui::Vec2::new(-100.0, 150.0),
+    );
+    let b = new_ontouml_class(
+        names[2],
+        stereotype,
+        false,
+        position + egui::Vec2::new(100.0, 150.0),
+    );
+    let generalization = partition(set_name, &[&a, &b], &kind);
+    vec![kind.1.into(), a.1.into(), b.1.into(), generalization]
+}
+
+/// Relator mediating two roles, each specializing its own kind
+fn relator_roles(position: egui::Pos2) -> Vec<UmlClassElementView<OntoUmlProfile>> {
+    let employment = new_ontouml_class("Employment", ontouml_models::RELATOR, false, position);
+    let mut elements = vec![employment.1.clone().into()];
+    for (kind_name, role_name, dx) in [
+        ("Person", "Employee", -200.0),
+        ("Organization", "Employer", 200.0),
+    ] {
+        let kind = new_ontouml_class(
+            kind_name,
+            ontouml_models::KIND,
+            false,
+            position + egui::Vec2::new(dx, -150.0),
+        );
+        let role = new_ontouml_class(
+            role_name,
+            ontouml_models::ROLE,
+            false,
+            position + egui::Vec2::new(dx, 0.0),
+        );
+        let generalization = new_umlclass_generalization(
+            "",
+            None,
+            (role.0.clone(), role.1.clone().into()),
+            (kind.0.clone(), kind.1.clone().into()),
+        );
+        let mediation = new_umlclass_association(
+            ontouml_models::MEDIATION,
+            "",
+            "1..1",
+            "1..*",
+            None,
+            (role.0.into(), role.1.clone().into()),
+            (employment.0.clone().into(), employment.1.clone().into()),
+        );
+        elements.extend([
+            kind.1.into(),
+            role.1.into(),
+            generalization.1.into(),
+            mediation.1.into(),
+        ]);
+    }
+    elements
+}
+
+pub fn pattern_elements(
+    pattern: &str,
+    position: egui::Pos2,
+) -> Vec<UmlClassElementView<OntoUmlProfile>> {
+    match pattern {
+        SUBKIND_PARTITION => kind_partition(
+            ontouml_models::SUBKIND,
+            "",
+            ["Organization", "Company", "Nonprofit"],
+            position,
+        ),
+        PHASE_PARTITION => kind_partition(
+            ontouml_models::PHASE,
+            "age",
+            ["Person", "Child", "Adult"],
+            position,
+        ),
+        RELATOR_ROLES => relator_roles(position),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::controller::{ElementController, Model},
+        domains::umlclass::umlclass_models::UmlClassElement,
+    };
+
+    #[test]
+    fn patterns_connect_their_own_classes() {
+        for (pattern, _) in PATTERNS {
+            let models: Vec<_> = pattern_elements(pattern, egui::Pos2::ZERO)
+                .iter()
+                .map(|e| e.model())
+                .collect();
+            let classes: Vec<_> = models
+                .iter()
+                .flat_map(|e| match e {
+                    UmlClassElement::Class(inner) => Some(*inner.read().uuid),
+                    _ => None,
+                })
+                .collect();
+            assert!(classes.len() >= 3, "{}", pattern);
+
+            for e in &models {
+                let ends = match e {
+                    UmlClassElement::Generalization(inner) => {
+                        let r = inner.read();
+                        assert_eq!(r.set_is_disjoint, r.sources.len() > 1);
+                        r.sources
+                            .iter()
+                            .chain(r.targets.iter())
+                            .map(|e| *e.read().uuid)
+                            .collect()
+                    }
+                    UmlClassElement::Association(inner) => {
+                        let r = inner.read();
+                        vec![*r.source.uuid(), *r.target.uuid()]
+                    }
+                    _ => Vec::new(),
+                };
+                assert!(ends.iter().all(|e| classes.contains(e)), "{}", pattern);
+            }
+        }
+        assert!(pattern_elements("unknown", egui::Pos2::ZERO).is_empty());
+    }
+}
@@ -11,7 +11,8 @@ use crate::{
     common::{
         canvas::Highlight,
         controller::{
-            DiagramCommand, GlobalDrawingContext, Model, ProjectCommand, SimpleProjectCommand,
+            CheckProblem, DiagramCommand, GlobalDrawingContext, Model, ProjectCommand,
+            SimpleProjectCommand,
         },
         eref::ERef,
         uuid::{ModelUuid, ViewUuid},
@@ -194,6 +195,21 @@ impl CustomTab for OntoUMLValidationTab {
     }
 }
 
+/// Structural errors of the model, as reported when checking the project
+pub fn check_problems(model: &ERef<UmlClassDiagram>) -> Vec<CheckProblem> {
+    validate_structure(model)
+        .into_iter()
+        .flat_map(|e| match e {
+            ValidationProblem::Error { uuid, text, .. } => Some(CheckProblem {
+                view: None,
+                model: Some(uuid),
+                message: text,
+            }),
+            ValidationProblem::AntiPattern { .. } => None,
+        })
+        .collect()
+}
+
 fn validate(
     model: &ERef<UmlClassDiagram>,
     check_errors: bool,
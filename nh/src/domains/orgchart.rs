@@ -0,0 +1,3 @@
+pub mod orgchart_controllers;
+pub mod orgchart_import;
+pub mod orgchart_models;
@@ -0,0 +1,565 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        AssociationViewT, LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView,
+        UmlClassProfile, UmlClassToolStage, UmlClassView, new_umlclass_association,
+        new_umlclass_class, new_umlclass_package, new_umlclass_property,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, ProjectCommand, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        ufoption::UFOption,
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        orgchart::{orgchart_import::OrgChartRecord, orgchart_models},
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{
+                UmlClass, UmlClassAssociation, UmlClassElement, UmlClassInstance,
+                UmlClassPackageKind,
+            },
+        },
+    },
+};
+use eframe::egui;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+#[derive(Clone, Default)]
+pub struct OrgChartProfile;
+impl UmlClassProfile for OrgChartProfile {
+    fn menubar_options_fun(
+        _model: &ERef<UmlClassDiagram>,
+        _view_uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("Import CSV").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::orgchart_import::OrgChartImportTab::default(),
+                )),
+            ));
+        }
+        ui.separator();
+    }
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct OrgChartControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<OrgChartProfile>> for OrgChartControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<OrgChartProfile>,
+        UmlClassDiagramAdapter<OrgChartProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-orgchart"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Organizational Chart").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Organizational Chart".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<OrgChartProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            OrgChartControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<OrgChartProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Organizational chart {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (ceo_model, ceo_view) = new_orgchart_person(
+        "Alice Novak",
+        "Chief Executive Officer",
+        egui::Pos2::new(400.0, 100.0),
+    );
+    let (cto_model, cto_view) = new_orgchart_person(
+        "Bob Svoboda",
+        "Chief Technology Officer",
+        egui::Pos2::new(250.0, 250.0),
+    );
+    let (cfo_model, cfo_view) = new_orgchart_person(
+        "Carol Dvořák",
+        "Chief Financial Officer",
+        egui::Pos2::new(600.0, 250.0),
+    );
+    let (lead_model, lead_view) = new_orgchart_role(
+        "Team Lead",
+        orgchart_models::ROLE,
+        egui::Pos2::new(150.0, 420.0),
+    );
+    let (dev_model, dev_view) = new_orgchart_role(
+        "Developer",
+        orgchart_models::ROLE,
+        egui::Pos2::new(350.0, 420.0),
+    );
+
+    let mut links = Vec::new();
+    for (manager, report) in [
+        ((&ceo_model, &ceo_view), (&cto_model, &cto_view)),
+        ((&ceo_model, &ceo_view), (&cfo_model, &cfo_view)),
+        ((&cto_model, &cto_view), (&lead_model, &lead_view)),
+        ((&cto_model, &cto_view), (&dev_model, &dev_view)),
+    ] {
+        links.push(new_orgchart_reporting_line(
+            (manager.0.clone(), manager.1.clone()),
+            (report.0.clone(), report.1.clone()),
+        ));
+    }
+
+    let (department, department_view) = new_umlclass_package(
+        "Engineering",
+        orgchart_models::DEPARTMENT,
+        UmlClassPackageKind::Package,
+        egui::Rect::from_x_y_ranges(50.0..=450.0, 180.0..=490.0),
+    );
+    {
+        let mut w = department_view.write();
+        let department_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [
+            cto_view.clone().into(),
+            lead_view.clone().into(),
+            dev_view.clone().into(),
+        ] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: department_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo Organizational chart {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        [ceo_model.into(), cfo_model.into(), department.into()]
+            .into_iter()
+            .chain(links.iter().map(|e| e.0.clone().into()))
+            .collect(),
+    ));
+    new_controlller(
+        diagram,
+        name,
+        [ceo_view.into(), cfo_view.into(), department_view.into()]
+            .into_iter()
+            .chain(links.iter().map(|e| e.1.clone().into()))
+            .collect(),
+    )
+}
+
+/// Creates a new chart from imported records, laid out as a top-down tree.
+pub fn from_records(records: &[OrgChartRecord]) -> (ViewUuid, ERef<dyn DiagramController>) {
+    const ORIGIN: egui::Pos2 = egui::Pos2::new(120.0, 80.0);
+    const SPACING: egui::Vec2 = egui::Vec2::new(200.0, 140.0);
+
+    let layout = super::orgchart_import::tree_layout(records);
+    let persons: Vec<_> = records
+        .iter()
+        .zip(layout)
+        .map(|(r, (depth, slot))| {
+            new_orgchart_person(
+                &r.name,
+                &r.role,
+                ORIGIN + egui::Vec2::new(slot * SPACING.x, depth as f32 * SPACING.y),
+            )
+        })
+        .collect();
+    let indices: HashMap<_, _> = records
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.name.as_str(), i))
+        .collect();
+    let links: Vec<_> = records
+        .iter()
+        .enumerate()
+        .flat_map(|(i, r)| {
+            let m = *indices.get(r.manager.as_deref()?)?;
+            Some(new_orgchart_reporting_line(
+                persons[m].clone(),
+                persons[i].clone(),
+            ))
+        })
+        .collect();
+
+    let name = "Imported Organizational chart".to_owned();
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        persons
+            .iter()
+            .map(|e| e.0.clone().into())
+            .chain(links.iter().map(|e| e.0.clone().into()))
+            .collect(),
+    ));
+    new_controlller(
+        diagram,
+        name,
+        persons
+            .iter()
+            .map(|e| e.1.clone().into())
+            .chain(links.iter().map(|e| e.1.clone().into()))
+            .collect(),
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<OrgChartProfile>,
+        OrgChartControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<OrgChartProfile>,
+            UmlClassDiagramAdapter<OrgChartProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn reporting_line() -> LinkType {
+        LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        }
+    }
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<OrgChartProfile>,
+        bool,
+    ) {
+        let link_type = reporting_line();
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<OrgChartProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_reporting_line(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<OrgChartProfile>,
+        bool,
+    ) {
+        let link_type = reporting_line();
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<OrgChartProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "\\", &class_reporting_line as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let nodes = vec![
+        (
+            UmlClassToolStage::Class {
+                name: "Person".to_owned(),
+                stereotype: orgchart_models::PERSON.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Person",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::Class {
+                name: "Role".to_owned(),
+                stereotype: orgchart_models::ROLE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "Role",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+    ];
+
+    let relationships = vec![(
+        UmlClassToolStage::LinkStart {
+            link_type: LinkType::Association {
+                stereotype: "".to_owned(),
+                source_multiplicity: "".to_owned(),
+                target_multiplicity: "".to_owned(),
+            },
+        },
+        "Reporting Line",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num3,
+        )),
+    )];
+
+    let palette_items = vec![
+        ("Nodes", nodes),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::PackageStart {
+                        name: "Department".to_owned(),
+                        stereotype: orgchart_models::DEPARTMENT.to_owned(),
+                        kind: UmlClassPackageKind::Package,
+                    },
+                    "Department",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num4,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num5,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<OrgChartProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<OrgChartProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-orgchart",
+    pretty_name: "Organizational chart",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Organizational chart (people, roles, departments and reporting lines) with CSV import",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_orgchart_person(
+    name: &str,
+    role: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<OrgChartProfile>>) {
+    let properties = if role.is_empty() {
+        Vec::new()
+    } else {
+        vec![new_umlclass_property(UFOption::None, role, "", "", "", "")]
+    };
+    new_umlclass_class(
+        name,
+        orgchart_models::PERSON,
+        false,
+        properties,
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
+
+fn new_orgchart_role(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<OrgChartProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
+
+fn new_orgchart_reporting_line(
+    manager: (ERef<UmlClass>, ERef<UmlClassView<OrgChartProfile>>),
+    report: (ERef<UmlClass>, ERef<UmlClassView<OrgChartProfile>>),
+) -> (
+    ERef<UmlClassAssociation>,
+    ERef<AssociationViewT<OrgChartProfile>>,
+) {
+    new_umlclass_association(
+        "",
+        "",
+        "",
+        "",
+        None,
+        (manager.0.into(), manager.1.into()),
+        (report.0.into(), report.1.into()),
+    )
+}
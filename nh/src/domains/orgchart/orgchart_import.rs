@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        csv,
+        uuid::ViewUuid,
+    },
+};
+
+pub struct OrgChartRecord {
+    pub name: String,
+    pub manager: Option<String>,
+    pub role: String,
+}
+
+/// Parses rows of `person,manager[,role]`, where an empty manager marks the top of a hierarchy.
+///
+/// Managers that are not listed as persons themselves are added as additional roots.
+/// Empty lines, lines starting with `#` and an optional `person,manager,...` header are ignored.
+pub fn parse_org_csv(source: &str) -> Result<Vec<OrgChartRecord>, String> {
+    let mut records: Vec<OrgChartRecord> = Vec::new();
+    let mut indices = HashMap::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let fields = csv::split_line(line);
+        let field = |i: usize| fields.get(i).map(|e| e.as_str()).unwrap_or("");
+
+        if records.is_empty()
+            && ["person", "name"].contains(&field(0).to_lowercase().as_str())
+            && field(1).eq_ignore_ascii_case("manager")
+        {
+            continue;
+        }
+        if field(0).is_empty() {
+            return Err(format!("line {}: person without a name", line_no));
+        }
+        if indices.insert(field(0).to_owned(), records.len()).is_some() {
+            return Err(format!("line {}: duplicate person '{}'", line_no, field(0)));
+        }
+        records.push(OrgChartRecord {
+            name: field(0).to_owned(),
+            manager: Some(field(1).to_owned()).filter(|e| !e.is_empty()),
+            role: field(2).to_owned(),
+        });
+    }
+
+    let missing_managers: Vec<_> = records
+        .iter()
+        .flat_map(|e| e.manager.clone())
+        .filter(|e| !indices.contains_key(e))
+        .collect();
+    for m in missing_managers {
+        if !indices.contains_key(&m) {
+            indices.insert(m.clone(), records.len());
+            records.push(OrgChartRecord {
+                name: m,
+                manager: None,
+                role: String::new(),
+            });
+        }
+    }
+
+    for r in &records {
+        let mut current = r;
+        for _ in 0..records.len() {
+            match &current.manager {
+                None => break,
+                Some(m) => current = &records[indices[m]],
+            }
+        }
+        if current.manager.is_some() {
+            return Err(format!("'{}' is part of a reporting cycle", r.name));
+        }
+    }
+
+    Ok(records)
+}
+
+/// Places every record of an acyclic hierarchy on a grid.
+///
+/// Returns the depth and the horizontal slot of each record. Leaves occupy consecutive
+/// slots and every manager is centered above its direct reports.
+pub fn tree_layout(records: &[OrgChartRecord]) -> Vec<(usize, f32)> {
+    fn place(
+        idx: usize,
+        depth: usize,
+        children: &[Vec<usize>],
+        next_leaf: &mut f32,
+        out: &mut [(usize, f32)],
+    ) {
+        match (children[idx].first(), children[idx].last()) {
+            (Some(first), Some(last)) => {
+                for c in &children[idx] {
+                    place(*c, depth + 1, children, next_leaf, out);
+                }
+                out[idx] = (depth, (out[*first].1 + out[*last].1) / 2.0);
+            }
+            _ => {
+                out[idx] = (depth, *next_leaf);
+                *next_leaf += 1.0;
+            }
+        }
+    }
+
+    let indices: HashMap<_, _> = records
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.name.as_str(), i))
+        .collect();
+    let mut children = vec![Vec::new(); records.len()];
+    let mut roots = Vec::new();
+    for (i, r) in records.iter().enumerate() {
+        match r.manager.as_deref().and_then(|e| indices.get(e)) {
+            Some(m) => children[*m].push(i),
+            None => roots.push(i),
+        }
+    }
+
+    let mut out = vec![(0, 0.0); records.len()];
+    let mut next_leaf = 0.0;
+    for r in roots {
+        place(r, 0, &children, &mut next_leaf, &mut out);
+    }
+    out
+}
+
+pub struct OrgChartImportTab {
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for OrgChartImportTab {
+    fn default() -> Self {
+        Self {
+            source: "person,manager,role\nAlice,,CEO\nBob,Alice,CTO\nCarol,Alice,CFO\nDave,Bob,Developer\nErin,Bob,Developer\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for OrgChartImportTab {
+    fn title(&self) -> String {
+        "Org Chart Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("CSV rows of person, manager and optional role");
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_org_csv(&self.source) {
+                    Ok(records) => {
+                        let (uuid, controller) =
+                            super::orgchart_controllers::from_records(&records);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        Ok(format!("Imported {} persons", records.len()))
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implicit_managers_become_roots() {
+        let records = parse_org_csv("Person,Manager\nBob,Alice\nCarol,Alice\n").unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].name, "Alice");
+        assert!(records[2].manager.is_none());
+    }
+
+    #[test]
+    fn cycles_and_duplicates_are_rejected() {
+        assert!(parse_org_csv("a,b\nb,c\nc,a\n").is_err());
+        assert!(parse_org_csv("a,\na,\n").is_err());
+    }
+
+    #[test]
+    fn managers_are_centered_above_reports() {
+        let records = parse_org_csv("a,\nb,a\nc,a\nd,b\ne,b\nf,\n").unwrap();
+        let layout = tree_layout(&records);
+        assert_eq!(layout[3], (2, 0.0));
+        assert_eq!(layout[4], (2, 1.0));
+        assert_eq!(layout[1], (1, 0.5));
+        assert_eq!(layout[2], (1, 2.0));
+        assert_eq!(layout[0], (0, 1.25));
+        assert_eq!(layout[5], (0, 3.0));
+    }
+}
@@ -0,0 +1,5 @@
+// Nodes
+pub const PERSON: &str = "person";
+pub const ROLE: &str = "role";
+// Containers
+pub const DEPARTMENT: &str = "department";
@@ -1,5 +1,10 @@
 pub mod rdf_controllers;
+pub mod rdf_dot;
 pub mod rdf_models;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rdf_import;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod rdf_queries;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rdf_serialization;
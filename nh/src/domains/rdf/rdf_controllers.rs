@@ -1,3 +1,4 @@
+use super::rdf_dot::DOT_FORMAT;
 use super::rdf_models::{
     RdfDiagram, RdfElement, RdfGraph, RdfLiteral, RdfNode, RdfPredicate, RdfTargettableElement,
 };
@@ -6,10 +7,10 @@ use crate::common::controller::{
     BucketNoT, ColorBundle, ColorChangeData, ContainerModel, ControllerAdapter, DiagramAdapter,
     DiagramController, DiagramControllerGen2, DiagramSettings, DiagramSettings2, Domain,
     ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
-    GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, MGlobalColor, Model,
-    MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand, PropertiesStatus,
-    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus, Tool,
-    ToolPalette, TryMerge, View,
+    GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, LiveLayoutCommand,
+    MGlobalColor, Model, MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand,
+    PropertiesStatus, Queryable, SelectionStatus, ShowSettingsResult, SnapManager,
+    TargettingStatus, TextExportFormat, Tool, ToolPalette, TryMerge, View,
 };
 use crate::common::entity::{Entity, EntityUuid};
 use crate::common::eref::ERef;
@@ -41,19 +42,26 @@ impl Domain for RdfDomain {
     type CommonElementViewT = RdfElementView;
     type ViewTargettingSectionT = RdfElement;
     type QueryableT<'a> = GenericQueryable<'a, Self>;
+    type NotationT = RdfNotation;
     type ToolT = NaiveRdfTool;
     type OrdinalMovementT = RdfOrdinalMovement;
     type AddCommandElementT = RdfElementOrVertex;
     type PropChangeT = RdfPropChange;
 }
 
+#[derive(Clone, Copy, Default, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RdfNotation {
+    /// Literals are shown as rounded rectangles with their datatype or language as a superscript
+    pub compact_literals: bool,
+}
+
 type PackageViewT = PackageView<RdfDomain, RdfGraphAdapter>;
 type LinkViewT = MulticonnectionView<RdfDomain, RdfPredicateAdapter>;
 
 #[derive(Clone, Copy, Debug)]
 pub struct RdfOrdinalMovement {}
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum RdfPropChange {
     NameChange(Arc<String>),
     IriChange(Arc<String>),
@@ -65,6 +73,8 @@ pub enum RdfPropChange {
     ColorChange(ColorChangeData),
     CommentChange(Arc<String>),
     FlipMulticonnection(FlipMulticonnection),
+    CurvedChange(bool),
+    NotationChange(RdfNotation),
 }
 
 impl Debug for RdfPropChange {
@@ -83,6 +93,8 @@ impl Debug for RdfPropChange {
                 Self::ColorChange(_color) => "ColorChange(..)".to_string(),
                 Self::CommentChange(comment) => format!("CommentChange({})", comment),
                 Self::FlipMulticonnection(_) => "FlipMulticonnection".to_string(),
+                Self::CurvedChange(curved) => format!("CurvedChange({})", curved),
+                Self::NotationChange(notation) => format!("NotationChange({:?})", notation),
             }
         )
     }
@@ -212,6 +224,41 @@ impl ControllerAdapter<RdfDomain> for RdfControllerAdapter {
         }
         None
     }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use super::rdf_serialization::{NTRIPLES_FORMAT, TURTLE_FORMAT};
+            &[TURTLE_FORMAT, NTRIPLES_FORMAT, DOT_FORMAT]
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            &[DOT_FORMAT]
+        }
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        _languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use super::rdf_serialization::{NTRIPLES_FORMAT, TURTLE_FORMAT};
+            match *format {
+                TURTLE_FORMAT => {
+                    return super::rdf_serialization::turtle(&self.model.read()).ok();
+                }
+                NTRIPLES_FORMAT => {
+                    return super::rdf_serialization::ntriples(&self.model.read()).ok();
+                }
+                _ => {}
+            }
+        }
+        match *format {
+            DOT_FORMAT => Some(super::rdf_dot::dot_document(&self.model.read())),
+            _ => None,
+        }
+    }
 }
 
 #[derive(
@@ -221,6 +268,8 @@ pub struct RdfDiagramAdapter {
     #[nh_context_serde(entity)]
     model: ERef<RdfDiagram>,
     background_color: MGlobalColor,
+    #[nh_context_serde(default)]
+    notation: RdfNotation,
     #[serde(skip)]
     #[nh_context_serde(skip_and_default)]
     buffer: RdfDiagramBuffer,
@@ -238,6 +287,7 @@ impl RdfDiagramAdapter {
         Self {
             model: model.clone(),
             background_color: MGlobalColor::None,
+            notation: RdfNotation::default(),
             buffer: RdfDiagramBuffer {
                 name: (*m.name).clone(),
                 comment: (*m.comment).clone(),
@@ -319,6 +369,9 @@ impl DiagramAdapter<RdfDomain> for RdfDiagramAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn notation(&self) -> RdfNotation {
+        self.notation
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -339,6 +392,17 @@ impl DiagramAdapter<RdfDomain> for RdfDiagramAdapter {
                 RdfPropChange::ColorChange((0, new_color).into()),
             ));
         }
+
+        let mut notation = self.notation;
+        if ui
+            .checkbox(&mut notation.compact_literals, "Compact literals")
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                RdfPropChange::NotationChange(notation),
+            ));
+        }
     }
     fn show_model_props_fun(
         &mut self,
@@ -405,6 +469,13 @@ impl DiagramAdapter<RdfDomain> for RdfDiagramAdapter {
                     ));
                     model.comment = comment.clone();
                 }
+                RdfPropChange::NotationChange(notation) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        RdfPropChange::NotationChange(self.notation),
+                    ));
+                    self.notation = *notation;
+                }
                 _ => {}
             }
         }
@@ -417,16 +488,21 @@ impl DiagramAdapter<RdfDomain> for RdfDiagramAdapter {
 
     fn menubar_options_fun(
         &self,
-        _view_uuid: &ViewUuid,
+        view_uuid: &ViewUuid,
         ui: &mut egui::Ui,
         commands: &mut Vec<ProjectCommand>,
     ) {
+        LiveLayoutCommand::show_menu(view_uuid, ui, commands);
+        ui.separator();
         // TODO: re-enable when sophia's getrandom dependency gets updated
         #[cfg(not(target_arch = "wasm32"))]
         {
-            /* TODO: RDF import & export
-            if ui.button("Import RDF data").clicked() {}
-            */
+            if ui.button("Import RDF data").clicked() {
+                commands.push(ProjectCommand::AddCustomTab(
+                    uuid::Uuid::now_v7(),
+                    Arc::new(RwLock::new(super::rdf_import::RdfImportTab::default())),
+                ));
+            }
             if ui.button("SPARQL Queries").clicked() {
                 let uuid = uuid::Uuid::now_v7();
                 commands.push(ProjectCommand::AddCustomTab(
@@ -436,6 +512,15 @@ impl DiagramAdapter<RdfDomain> for RdfDiagramAdapter {
                     ))),
                 ));
             }
+            if ui.button("RDF Export").clicked() {
+                let uuid = uuid::Uuid::now_v7();
+                commands.push(ProjectCommand::AddCustomTab(
+                    uuid,
+                    Arc::new(RwLock::new(super::rdf_serialization::RdfExportTab::new(
+                        self.model.clone(),
+                    ))),
+                ));
+            }
             ui.separator();
         }
     }
@@ -558,6 +643,56 @@ pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
     )
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub fn from_imported(
+    name: String,
+    imported: &super::rdf_import::ImportedRdf,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    use super::rdf_import::ImportedObject;
+
+    let mut positions = super::rdf_import::force_layout(imported).into_iter();
+    let nodes: Vec<_> = imported
+        .nodes
+        .iter()
+        .zip(&mut positions)
+        .map(|(iri, position)| new_rdf_node(iri, position))
+        .collect();
+    let (mut models, mut views): (Vec<RdfElement>, Vec<RdfElementView>) = nodes
+        .iter()
+        .map(|(m, v)| (m.clone().into(), v.clone().into()))
+        .unzip();
+
+    for (subject, iri, object) in &imported.statements {
+        let source = (nodes[*subject].0.clone(), nodes[*subject].1.clone().into());
+        let target = match object {
+            ImportedObject::Node(idx) => {
+                (nodes[*idx].0.clone().into(), nodes[*idx].1.clone().into())
+            }
+            ImportedObject::Literal {
+                content,
+                datatype,
+                langtag,
+            } => {
+                let (literal, literal_view) = new_rdf_literal(
+                    content,
+                    datatype,
+                    langtag,
+                    positions.next().unwrap_or(egui::Pos2::ZERO),
+                );
+                models.push(literal.clone().into());
+                views.push(literal_view.clone().into());
+                (literal.into(), literal_view.into())
+            }
+        };
+        let (predicate, predicate_view) = new_rdf_predicate(iri, source, target);
+        models.push(predicate.into());
+        views.push(predicate_view.into());
+    }
+
+    let diagram = ERef::new(RdfDiagram::new(ModelUuid::now_v7(), name.clone(), models));
+    new_controlller(diagram, name, views)
+}
+
 pub fn stress_test<const N1: usize, const DX: u32, const DY: u32>(
     no: u32,
 ) -> (ViewUuid, ERef<dyn DiagramController>) {
@@ -844,7 +979,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(RdfSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
     }))
@@ -1771,7 +1906,9 @@ impl ElementControllerGen2<RdfDomain> for RdfNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -1969,6 +2106,67 @@ pub struct RdfLiteralView {
     pub bounds_rect: egui::Rect,
 }
 
+impl RdfLiteralView {
+    /// Draws the content in a rounded rectangle, followed by the language tag
+    /// or the local name of the datatype as a superscript
+    fn draw_compact(&mut self, canvas: &mut dyn NHCanvas) {
+        let model = self.model.read();
+        let superscript = if !model.langtag.is_empty() {
+            format!("@{}", model.langtag)
+        } else {
+            model
+                .datatype
+                .rsplit(['#', '/', ':'])
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        };
+
+        let content_rect = canvas.measure_text(
+            self.position,
+            egui::Align2::CENTER_CENTER,
+            &model.content,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        );
+        let superscript_anchor =
+            egui::Pos2::new(content_rect.right() + 2.0, content_rect.center().y);
+        let mut text_rect = content_rect;
+        if !superscript.is_empty() {
+            text_rect = text_rect.union(canvas.measure_text(
+                superscript_anchor,
+                egui::Align2::LEFT_BOTTOM,
+                &superscript,
+                canvas::CLASS_ITEM_FONT_SIZE,
+            ));
+        }
+        self.bounds_rect = text_rect.expand2(egui::Vec2::new(10.0, 5.0));
+
+        canvas.draw_rectangle(
+            self.bounds_rect,
+            egui::CornerRadius::same(8),
+            egui::Color32::WHITE,
+            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+            self.highlight,
+        );
+        canvas.draw_text(
+            self.position,
+            egui::Align2::CENTER_CENTER,
+            &model.content,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            egui::Color32::BLACK,
+        );
+        if !superscript.is_empty() {
+            canvas.draw_text(
+                superscript_anchor,
+                egui::Align2::LEFT_BOTTOM,
+                &superscript,
+                canvas::CLASS_ITEM_FONT_SIZE,
+                egui::Color32::BLACK,
+            );
+        }
+    }
+}
+
 impl Entity for RdfLiteralView {
     fn tagged_uuid(&self) -> EntityUuid {
         (*self.uuid).into()
@@ -2081,25 +2279,29 @@ impl ElementControllerGen2<RdfDomain> for RdfLiteralView {
 
     fn draw_in(
         &mut self,
-        _q: &<RdfDomain as Domain>::QueryableT<'_>,
+        q: &<RdfDomain as Domain>::QueryableT<'_>,
         _gdc: &GlobalDrawingContext,
         _settings: &RdfSettings,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveRdfTool)>,
     ) -> TargettingStatus {
         // Draw shape and text
-        self.bounds_rect = crate::domains::umlclass::umlclass_controllers::draw_uml_class(
-            canvas,
-            self.position,
-            None,
-            &self.model.read().content,
-            None,
-            false,
-            &[],
-            egui::Color32::WHITE,
-            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
-            self.highlight,
-        );
+        if q.notation().compact_literals {
+            self.draw_compact(canvas);
+        } else {
+            self.bounds_rect = crate::domains::umlclass::umlclass_controllers::draw_uml_class(
+                canvas,
+                self.position,
+                None,
+                &self.model.read().content,
+                None,
+                false,
+                &[],
+                egui::Color32::WHITE,
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                self.highlight,
+            );
+        }
 
         // Draw targetting rectangle
         if canvas.ui_scale().is_some()
@@ -2225,7 +2427,9 @@ impl ElementControllerGen2<RdfDomain> for RdfLiteralView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -2431,6 +2635,16 @@ impl MulticonnectionAdapter<RdfDomain> for RdfPredicateAdapter {
         }
     }
 
+    fn curved_change(&self, curved: bool) -> Option<RdfPropChange> {
+        Some(RdfPropChange::CurvedChange(curved))
+    }
+    fn as_curved_change(&self, change: &RdfPropChange) -> Option<bool> {
+        match change {
+            RdfPropChange::CurvedChange(curved) => Some(*curved),
+            _ => None,
+        }
+    }
+
     fn show_properties(
         &mut self,
         q: &<RdfDomain as Domain>::QueryableT<'_>,
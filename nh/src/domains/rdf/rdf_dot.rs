@@ -0,0 +1,147 @@
+use crate::{
+    common::{
+        controller::{Model, TextExportFormat},
+        uuid::ModelUuid,
+    },
+    domains::rdf::rdf_models::{RdfDiagram, RdfElement},
+};
+
+pub const DOT_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Graphviz DOT",
+    extensions: &["dot", "gv"],
+};
+
+fn node_id(uuid: &ModelUuid) -> String {
+    "n".chars()
+        .chain(uuid.to_string().chars().filter(|e| *e != '-'))
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn literal_label(content: &str, datatype: &str, langtag: &str) -> String {
+    let mut label = format!("\"{}\"", content);
+    if !langtag.is_empty() {
+        label.push_str(&format!("@{}", langtag));
+    } else if !datatype.is_empty() {
+        label.push_str(&format!("^^{}", datatype));
+    }
+    label
+}
+
+/// Writes node declarations of the elements, with named graphs as clusters.
+/// Edges are collected separately, so that they do not pull their endpoints into clusters.
+fn collect(elements: &[RdfElement], indent: usize, nodes: &mut String, edges: &mut String) {
+    let pad = "  ".repeat(indent);
+    for e in elements {
+        match e {
+            RdfElement::RdfGraph(inner) => {
+                let r = inner.read();
+                nodes.push_str(&format!(
+                    "{}subgraph \"cluster_{}\" {{\n{}  label=\"{}\";\n",
+                    pad,
+                    node_id(&r.uuid),
+                    pad,
+                    escape(&r.iri)
+                ));
+                collect(&r.contained_elements, indent + 1, nodes, edges);
+                nodes.push_str(&format!("{}}}\n", pad));
+            }
+            RdfElement::RdfNode(inner) => {
+                let r = inner.read();
+                nodes.push_str(&format!(
+                    "{}{} [label=\"{}\"];\n",
+                    pad,
+                    node_id(&r.uuid),
+                    escape(&r.iri)
+                ));
+            }
+            RdfElement::RdfLiteral(inner) => {
+                let r = inner.read();
+                nodes.push_str(&format!(
+                    "{}{} [label=\"{}\", shape=box];\n",
+                    pad,
+                    node_id(&r.uuid),
+                    escape(&literal_label(&r.content, &r.datatype, &r.langtag))
+                ));
+            }
+            RdfElement::RdfPredicate(inner) => {
+                let r = inner.read();
+                edges.push_str(&format!(
+                    "  {} -> {} [label=\"{}\"];\n",
+                    node_id(&r.source.read().uuid),
+                    node_id(&r.target.uuid()),
+                    escape(&r.iri)
+                ));
+            }
+        }
+    }
+}
+
+/// Graphviz document with IRI nodes as ellipses, literals as boxes and predicates as labeled edges
+pub fn dot_document(diagram: &RdfDiagram) -> String {
+    let (mut nodes, mut edges) = (String::new(), String::new());
+    collect(&diagram.contained_elements, 1, &mut nodes, &mut edges);
+    format!(
+        "digraph \"{}\" {{\n  node [shape=ellipse];\n{}{}}}\n",
+        escape(&diagram.name),
+        nodes,
+        edges
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::eref::ERef,
+        domains::rdf::rdf_models::{RdfGraph, RdfLiteral, RdfNode, RdfPredicate},
+    };
+
+    #[test]
+    fn graphs_become_clusters_and_predicates_edges() {
+        let alice = ERef::new(RdfNode::new(
+            ModelUuid::now_v7(),
+            "http://example.org/alice".to_owned(),
+        ));
+        let name = ERef::new(RdfLiteral::new(
+            ModelUuid::now_v7(),
+            "Alice \"Al\"".to_owned(),
+            String::new(),
+            "en".to_owned(),
+        ));
+        let predicate = RdfPredicate::new(
+            ModelUuid::now_v7(),
+            "http://xmlns.com/foaf/0.1/name".to_owned(),
+            alice.clone(),
+            name.clone().into(),
+        );
+        let graph = RdfGraph::new(
+            ModelUuid::now_v7(),
+            "http://example.org/people".to_owned(),
+            vec![alice.into()],
+        );
+        let diagram = RdfDiagram::new(
+            ModelUuid::now_v7(),
+            "People".to_owned(),
+            vec![
+                ERef::new(graph).into(),
+                name.into(),
+                ERef::new(predicate).into(),
+            ],
+        );
+
+        let document = dot_document(&diagram);
+        assert!(document.starts_with("digraph \"People\" {\n"));
+        assert!(document.contains("  subgraph \"cluster_"));
+        assert!(document.contains("label=\"http://example.org/people\";"));
+        assert!(document.contains(r#"[label="\"Alice \"Al\"\"@en", shape=box]"#));
+        let edge = document.lines().find(|e| e.contains(" -> ")).unwrap();
+        assert!(edge.ends_with("[label=\"http://xmlns.com/foaf/0.1/name\"];"));
+        assert!(document.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,266 @@
+use eframe::egui;
+use std::collections::HashMap;
+
+use sophia::api::{source::TripleSource, term::SimpleTerm};
+
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        uuid::ViewUuid,
+    },
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RdfImportFormat {
+    Turtle,
+    RdfXml,
+}
+
+impl RdfImportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RdfImportFormat::Turtle => "Turtle",
+            RdfImportFormat::RdfXml => "RDF/XML",
+        }
+    }
+}
+
+pub enum ImportedObject {
+    Node(usize),
+    Literal {
+        content: String,
+        datatype: String,
+        langtag: String,
+    },
+}
+
+/// Parsed document, with each subject and object resource appearing only once in `nodes`
+pub struct ImportedRdf {
+    /// IRIs of resources, blank nodes are prefixed with `_:`
+    pub nodes: Vec<String>,
+    /// Subject node index, predicate IRI and object
+    pub statements: Vec<(usize, String, ImportedObject)>,
+}
+
+pub fn parse_rdf(source: &str, format: RdfImportFormat) -> Result<ImportedRdf, String> {
+    let triples: Vec<[SimpleTerm<'static>; 3]> = match format {
+        RdfImportFormat::Turtle => sophia::turtle::parser::turtle::parse_str(source)
+            .collect_triples()
+            .map_err(|e| e.to_string())?,
+        RdfImportFormat::RdfXml => sophia::xml::parser::parse_str(source)
+            .collect_triples()
+            .map_err(|e| e.to_string())?,
+    };
+
+    let mut imported = ImportedRdf {
+        nodes: Vec::new(),
+        statements: Vec::new(),
+    };
+    let mut indices = HashMap::new();
+    let mut node_index = |iri: String, nodes: &mut Vec<String>| {
+        *indices.entry(iri.clone()).or_insert_with(|| {
+            nodes.push(iri);
+            nodes.len() - 1
+        })
+    };
+    for [subject, predicate, object] in triples {
+        let resource = |t: &SimpleTerm| match t {
+            SimpleTerm::Iri(iri) => Ok(iri.as_str().to_owned()),
+            SimpleTerm::BlankNode(id) => Ok(format!("_:{}", id.as_str())),
+            _ => Err("Quoted triples and variables are not supported".to_owned()),
+        };
+
+        let subject = node_index(resource(&subject)?, &mut imported.nodes);
+        let SimpleTerm::Iri(predicate) = predicate else {
+            return Err("Predicates must be IRIs".to_owned());
+        };
+        let object = match object {
+            SimpleTerm::LiteralDatatype(content, datatype) => ImportedObject::Literal {
+                content: content.to_string(),
+                datatype: datatype.as_str().to_owned(),
+                langtag: String::new(),
+            },
+            SimpleTerm::LiteralLanguage(content, langtag) => ImportedObject::Literal {
+                content: content.to_string(),
+                datatype: String::new(),
+                langtag: langtag.as_str().to_owned(),
+            },
+            t => ImportedObject::Node(node_index(resource(&t)?, &mut imported.nodes)),
+        };
+        imported
+            .statements
+            .push((subject, predicate.as_str().to_owned(), object));
+    }
+
+    Ok(imported)
+}
+
+/// Places resources and literals using a force-directed layout started from a circle.
+///
+/// Returns positions of nodes followed by positions of literals, in order of their statements.
+pub fn force_layout(imported: &ImportedRdf) -> Vec<egui::Pos2> {
+    const IDEAL_DISTANCE: f32 = 150.0;
+    const ITERATIONS: usize = 200;
+
+    let mut edges = Vec::new();
+    let mut vertex_count = imported.nodes.len();
+    for (subject, _, object) in &imported.statements {
+        match object {
+            ImportedObject::Node(target) => edges.push((*subject, *target)),
+            ImportedObject::Literal { .. } => {
+                edges.push((*subject, vertex_count));
+                vertex_count += 1;
+            }
+        }
+    }
+
+    let radius = IDEAL_DISTANCE * (vertex_count as f32).sqrt();
+    let mut positions: Vec<_> = (0..vertex_count)
+        .map(|i| {
+            egui::Pos2::ZERO
+                + egui::Vec2::angled(i as f32 * std::f32::consts::TAU / vertex_count as f32)
+                    * radius
+        })
+        .collect();
+    let pinned = vec![false; vertex_count];
+    for i in 0..ITERATIONS {
+        // Cool down, so that the layout settles
+        let max_step = 50.0 * (1.0 - i as f32 / ITERATIONS as f32) + 1.0;
+        let steps = crate::common::layout::force_directed_step(
+            &positions,
+            &edges,
+            &pinned,
+            IDEAL_DISTANCE,
+            max_step,
+        );
+        for (p, s) in positions.iter_mut().zip(steps) {
+            *p += s;
+        }
+    }
+
+    let min = positions
+        .iter()
+        .fold(egui::Pos2::new(f32::MAX, f32::MAX), |acc, e| acc.min(*e));
+    positions
+        .into_iter()
+        .map(|e| e - min.to_vec2() + egui::Vec2::splat(100.0))
+        .collect()
+}
+
+pub struct RdfImportTab {
+    format: RdfImportFormat,
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for RdfImportTab {
+    fn default() -> Self {
+        Self {
+            format: RdfImportFormat::Turtle,
+            source: "@prefix foaf: <http://xmlns.com/foaf/0.1/> .\n@prefix ex: <http://example.org/> .\n\nex:alice foaf:name \"Alice\"@en ;\n    foaf:knows ex:bob .\nex:bob foaf:name \"Bob\"@en .\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for RdfImportTab {
+    fn title(&self) -> String {
+        "RDF Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            for f in [RdfImportFormat::Turtle, RdfImportFormat::RdfXml] {
+                ui.radio_value(&mut self.format, f, f.name());
+            }
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_rdf(&self.source, self.format) {
+                    Ok(imported) => {
+                        let (uuid, controller) = super::rdf_controllers::from_imported(
+                            format!("Imported {} data", self.format.name()),
+                            &imported,
+                        );
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        Ok(format!(
+                            "Imported {} resources and {} statements",
+                            imported.nodes.len(),
+                            imported.statements.len(),
+                        ))
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resources_are_shared_and_literals_are_not() {
+        let imported = parse_rdf(
+            "@prefix ex: <http://example.org/> .\n\
+             ex:alice ex:knows ex:bob, [ ex:name \"Carol\" ] ;\n\
+                 ex:name \"Alice\"@en .\n\
+             ex:bob ex:age 42 .\n",
+            RdfImportFormat::Turtle,
+        )
+        .unwrap();
+
+        assert_eq!(imported.nodes.len(), 3);
+        assert_eq!(imported.nodes[0], "http://example.org/alice");
+        assert!(imported.nodes.iter().any(|e| e.starts_with("_:")));
+        assert_eq!(imported.statements.len(), 5);
+        assert!(imported.statements.iter().any(|e| matches!(
+            &e.2,
+            ImportedObject::Literal { content, datatype, .. }
+                if content == "42" && datatype.ends_with("#integer")
+        )));
+
+        let positions = force_layout(&imported);
+        assert_eq!(positions.len(), 3 + 3);
+        assert!(positions.iter().all(|e| e.x >= 99.0 && e.y >= 99.0));
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                assert!(a.distance(*b) > 10.0);
+            }
+        }
+
+        assert!(parse_rdf("ex:a ex:b", RdfImportFormat::Turtle).is_err());
+    }
+}
@@ -12,13 +12,19 @@ use std::{collections::HashMap, sync::Arc};
 #[cfg(not(target_arch = "wasm32"))]
 use sophia::api::{
     MownStr,
-    term::{GraphName, IriRef, LanguageTag, SimpleTerm},
+    term::{BnodeId, GraphName, IriRef, LanguageTag, SimpleTerm},
 };
 
+/// Triple along with the graph it belongs to
+#[cfg(not(target_arch = "wasm32"))]
+pub type RdfQuad<'a> = ([SimpleTerm<'a>; 3], GraphName<SimpleTerm<'a>>);
+
 #[cfg(not(target_arch = "wasm32"))]
 pub struct RdfCollector<'a> {
-    data: Vec<([SimpleTerm<'a>; 3], GraphName<SimpleTerm<'a>>)>,
+    data: Vec<RdfQuad<'a>>,
     current_graph: GraphName<SimpleTerm<'a>>,
+    /// Nodes which cannot be represented as terms, reported instead of their triples
+    errors: Vec<String>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -83,14 +89,14 @@ impl RdfElement {
             RdfElement::RdfLiteral(_) | RdfElement::RdfNode(_) => {}
             RdfElement::RdfPredicate(inner) => {
                 let model = inner.read();
-                let subject = model.source.read().term_repr();
-                let object = model.target.term_repr();
-
-                collector.add_triple([
-                    subject,
-                    SimpleTerm::Iri(IriRef::new(MownStr::from((*model.iri).clone())).unwrap()),
-                    object,
-                ]);
+                match (model.source.read().term_repr(), model.target.term_repr()) {
+                    (Ok(subject), Ok(object)) => collector.add_triple([
+                        subject,
+                        SimpleTerm::Iri(IriRef::new(MownStr::from((*model.iri).clone())).unwrap()),
+                        object,
+                    ]),
+                    (Err(e), _) | (_, Err(e)) => collector.errors.push(e),
+                }
             }
         }
     }
@@ -98,9 +104,9 @@ impl RdfElement {
 
 impl RdfTargettableElement {
     #[cfg(not(target_arch = "wasm32"))]
-    fn term_repr(&self) -> SimpleTerm<'static> {
+    fn term_repr(&self) -> Result<SimpleTerm<'static>, String> {
         match self {
-            RdfTargettableElement::RdfLiteral(inner) => inner.read().term_repr(),
+            RdfTargettableElement::RdfLiteral(inner) => Ok(inner.read().term_repr()),
             RdfTargettableElement::RdfNode(inner) => inner.read().term_repr(),
         }
     }
@@ -232,6 +238,7 @@ pub fn deep_copy_diagram(d: &RdfDiagram) -> (ERef<RdfDiagram>, HashMap<ModelUuid
             .iter()
             .map(|e| (uuid::Uuid::now_v7(), e.1.clone()))
             .collect(),
+        prefixes: d.prefixes.clone(),
     };
     (ERef::new(new_diagram), all_models)
 }
@@ -327,6 +334,9 @@ pub struct RdfDiagram {
     #[nh_context_serde(entity)]
     pub contained_elements: Vec<RdfElement>,
     pub stored_queries: HashMap<uuid::Uuid, (String, String)>,
+    /// Prefix name and namespace IRI pairs used when serializing to Turtle
+    #[nh_context_serde(default)]
+    pub prefixes: Vec<(String, String)>,
 
     pub comment: Arc<String>,
 }
@@ -348,22 +358,41 @@ impl RdfDiagram {
                 );
                 hm
             },
+            prefixes: vec![
+                (
+                    "rdf".to_owned(),
+                    "http://www.w3.org/1999/02/22-rdf-syntax-ns#".to_owned(),
+                ),
+                (
+                    "rdfs".to_owned(),
+                    "http://www.w3.org/2000/01/rdf-schema#".to_owned(),
+                ),
+                (
+                    "xsd".to_owned(),
+                    "http://www.w3.org/2001/XMLSchema#".to_owned(),
+                ),
+            ],
             comment: Arc::new("".to_owned()),
         }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn graph(&self) -> Vec<([SimpleTerm<'_>; 3], GraphName<SimpleTerm<'_>>)> {
+    pub fn graph(&self) -> Result<Vec<RdfQuad<'_>>, String> {
         let mut collector = RdfCollector {
             data: Vec::new(),
             current_graph: None,
+            errors: Vec::new(),
         };
 
         for c in &self.contained_elements {
             c.accept_collector(&mut collector);
         }
 
-        collector.data
+        if collector.errors.is_empty() {
+            Ok(collector.data)
+        } else {
+            Err(collector.errors.join("\n"))
+        }
     }
 
     pub fn get_element_pos_in(
@@ -724,8 +753,16 @@ impl RdfNode {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn term_repr(&self) -> SimpleTerm<'static> {
-        SimpleTerm::Iri(IriRef::new(MownStr::from((*self.iri).clone())).unwrap())
+    fn term_repr(&self) -> Result<SimpleTerm<'static>, String> {
+        if let Some(id) = self.iri.strip_prefix("_:") {
+            BnodeId::new(MownStr::from(id.to_owned()))
+                .map(SimpleTerm::BlankNode)
+                .map_err(|_| format!("Invalid blank node \"{}\"", self.iri))
+        } else {
+            IriRef::new(MownStr::from((*self.iri).clone()))
+                .map(SimpleTerm::Iri)
+                .map_err(|_| format!("Invalid node IRI \"{}\"", self.iri))
+        }
     }
 }
 
@@ -58,11 +58,19 @@ impl SparqlQueriesTab {
     fn execute(&mut self) {
         let model = self.model.write();
 
+        let graph = match model.graph() {
+            Ok(graph) => graph,
+            Err(e) => {
+                self.debug_message = Some(e);
+                return;
+            }
+        };
+
         match SparqlQuery::parse(&self.query_value_buffer) {
             Err(e) => {
                 self.debug_message = Some(format!("{:?}", e));
             }
-            Ok(query) => match SparqlWrapper(&model.graph())
+            Ok(query) => match SparqlWrapper(&graph)
                 .query(&query)
                 .map(|e| e.into_bindings())
             {
@@ -0,0 +1,206 @@
+use eframe::egui;
+
+use sophia::api::{
+    prefix::Prefix,
+    serializer::{Stringifier, TripleSerializer},
+    term::SimpleTerm,
+};
+use sophia::iri::Iri;
+use sophia::turtle::serializer::{
+    nt::NtSerializer,
+    turtle::{TurtleConfig, TurtleSerializer},
+};
+
+use super::rdf_models::RdfDiagram;
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand, TextExportFormat},
+        eref::ERef,
+    },
+};
+
+pub const TURTLE_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Turtle",
+    extensions: &["ttl"],
+};
+pub const NTRIPLES_FORMAT: TextExportFormat = TextExportFormat {
+    name: "N-Triples",
+    extensions: &["nt"],
+};
+
+/// Triples of all graphs, as neither format can express graph names
+fn triples(diagram: &RdfDiagram) -> Result<Vec<[SimpleTerm<'_>; 3]>, String> {
+    Ok(diagram.graph()?.into_iter().map(|e| e.0).collect())
+}
+
+pub fn turtle(diagram: &RdfDiagram) -> Result<String, String> {
+    let prefix_map = diagram
+        .prefixes
+        .iter()
+        .map(|(prefix, namespace)| {
+            Ok((
+                Prefix::new(prefix.clone().into_boxed_str())
+                    .map_err(|_| format!("Invalid prefix name \"{}\"", prefix))?,
+                Iri::new(namespace.clone().into_boxed_str())
+                    .map_err(|_| format!("Invalid namespace IRI \"{}\"", namespace))?,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let config = TurtleConfig::new()
+        .with_pretty(true)
+        .with_own_prefix_map(prefix_map);
+
+    TurtleSerializer::new_stringifier_with_config(config)
+        .serialize_graph(&triples(diagram)?)
+        .map(|e| e.as_str().to_owned())
+        .map_err(|e| e.to_string())
+}
+
+pub fn ntriples(diagram: &RdfDiagram) -> Result<String, String> {
+    NtSerializer::new_stringifier()
+        .serialize_graph(&triples(diagram)?)
+        .map(|e| e.as_str().to_owned())
+        .map_err(|e| e.to_string())
+}
+
+pub struct RdfExportTab {
+    model: ERef<RdfDiagram>,
+    format: TextExportFormat,
+    output: String,
+}
+
+impl RdfExportTab {
+    pub fn new(model: ERef<RdfDiagram>) -> Self {
+        Self {
+            model,
+            format: TURTLE_FORMAT,
+            output: "".to_owned(),
+        }
+    }
+}
+
+impl CustomTab for RdfExportTab {
+    fn title(&self) -> String {
+        "RDF Export".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+        let mut model = self.model.write();
+
+        ui.label("Prefixes");
+        let mut removed = None;
+        egui::Grid::new("rdf export prefixes").show(ui, |ui| {
+            for (idx, (prefix, namespace)) in model.prefixes.iter_mut().enumerate() {
+                ui.text_edit_singleline(prefix);
+                ui.text_edit_singleline(namespace);
+                if ui.button("Remove").clicked() {
+                    removed = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = removed {
+            model.prefixes.remove(idx);
+        }
+        if ui.button("Add prefix").clicked() {
+            model.prefixes.push(("".to_owned(), "".to_owned()));
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.format, TURTLE_FORMAT, TURTLE_FORMAT.name);
+            ui.radio_value(&mut self.format, NTRIPLES_FORMAT, NTRIPLES_FORMAT.name);
+
+            if ui.button("Generate").clicked() {
+                let result = match self.format {
+                    TURTLE_FORMAT => turtle(&model),
+                    _ => ntriples(&model),
+                };
+                self.output = result.unwrap_or_else(|e| format!("Error: {}", e));
+            }
+        });
+
+        ui.add_sized(
+            (ui.available_width(), 20.0),
+            egui::TextEdit::multiline(&mut self.output.as_str()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::uuid::ModelUuid,
+        domains::rdf::rdf_models::{RdfLiteral, RdfNode, RdfPredicate},
+    };
+
+    #[test]
+    fn prefixes_apply_to_turtle_only() {
+        let alice = ERef::new(RdfNode::new(
+            ModelUuid::now_v7(),
+            "http://example.org/alice".to_owned(),
+        ));
+        let name = ERef::new(RdfLiteral::new(
+            ModelUuid::now_v7(),
+            "Alice".to_owned(),
+            String::new(),
+            "en".to_owned(),
+        ));
+        let predicate = RdfPredicate::new(
+            ModelUuid::now_v7(),
+            "http://xmlns.com/foaf/0.1/name".to_owned(),
+            alice.clone(),
+            name.clone().into(),
+        );
+        let mut diagram = RdfDiagram::new(
+            ModelUuid::now_v7(),
+            "People".to_owned(),
+            vec![alice.into(), name.into(), ERef::new(predicate).into()],
+        );
+        diagram
+            .prefixes
+            .push(("ex".to_owned(), "http://example.org/".to_owned()));
+
+        let document = turtle(&diagram).unwrap();
+        assert!(document.contains("PREFIX ex: <http://example.org/>\n"));
+        assert!(document.contains("ex:alice"));
+
+        let document = ntriples(&diagram).unwrap();
+        assert_eq!(
+            document.trim_end(),
+            "<http://example.org/alice> <http://xmlns.com/foaf/0.1/name> \"Alice\"@en."
+        );
+
+        diagram.prefixes.push(("e x".to_owned(), "".to_owned()));
+        assert!(turtle(&diagram).is_err());
+    }
+
+    #[test]
+    fn invalid_blank_nodes_are_reported() {
+        let node = ERef::new(RdfNode::new(ModelUuid::now_v7(), "_:a b".to_owned()));
+        let predicate = RdfPredicate::new(
+            ModelUuid::now_v7(),
+            "http://example.org/knows".to_owned(),
+            node.clone(),
+            node.clone().into(),
+        );
+        let diagram = RdfDiagram::new(
+            ModelUuid::now_v7(),
+            "People".to_owned(),
+            vec![node.into(), ERef::new(predicate).into()],
+        );
+
+        assert_eq!(
+            ntriples(&diagram).unwrap_err(),
+            "Invalid blank node \"_:a b\""
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod statemachine_controllers;
+pub mod statemachine_models;
@@ -0,0 +1,522 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView, UmlClassProfile,
+        UmlClassToolStage, UmlClassView, new_uml_usecase, new_umlclass_class,
+        new_umlclass_dependency, new_umlclass_package,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        statemachine::statemachine_models,
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance, UmlClassPackageKind},
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct StateMachineProfile;
+impl UmlClassProfile for StateMachineProfile {}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct StateMachineControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<StateMachineProfile>> for StateMachineControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<
+        UmlClassDomain<StateMachineProfile>,
+        UmlClassDiagramAdapter<StateMachineProfile>,
+    >;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-statemachine"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("State Machine Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared State Machine Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<StateMachineProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            StateMachineControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<StateMachineProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New State machine diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (initial_model, initial_view) = new_uml_usecase(
+        "",
+        statemachine_models::INITIAL,
+        false,
+        egui::Pos2::new(100.0, 200.0),
+        MGlobalColor::None,
+    );
+    let (idle_model, idle_view) = new_state(
+        "Idle",
+        statemachine_models::STATE,
+        egui::Pos2::new(250.0, 200.0),
+    );
+    let (running_model, running_view) = new_state(
+        "Running",
+        statemachine_models::STATE,
+        egui::Pos2::new(500.0, 170.0),
+    );
+    let (paused_model, paused_view) = new_state(
+        "Paused",
+        statemachine_models::STATE,
+        egui::Pos2::new(700.0, 270.0),
+    );
+    let (final_model, final_view) = new_uml_usecase(
+        "",
+        statemachine_models::FINAL,
+        false,
+        egui::Pos2::new(500.0, 450.0),
+        MGlobalColor::None,
+    );
+
+    let (init_model, init_view) = new_umlclass_dependency(
+        statemachine_models::TRANSITION,
+        "",
+        true,
+        None,
+        (initial_model.clone().into(), initial_view.clone().into()),
+        (idle_model.clone().into(), idle_view.clone().into()),
+    );
+    let (start_model, start_view) = new_umlclass_dependency(
+        statemachine_models::TRANSITION,
+        &statemachine_models::transition_label("start", "ready", "reset timer"),
+        true,
+        None,
+        (idle_model.clone().into(), idle_view.clone().into()),
+        (running_model.clone().into(), running_view.clone().into()),
+    );
+    let (pause_model, pause_view) = new_umlclass_dependency(
+        statemachine_models::TRANSITION,
+        &statemachine_models::transition_label("pause", "", ""),
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(620.0, 170.0))),
+        (running_model.clone().into(), running_view.clone().into()),
+        (paused_model.clone().into(), paused_view.clone().into()),
+    );
+    let (resume_model, resume_view) = new_umlclass_dependency(
+        statemachine_models::TRANSITION,
+        &statemachine_models::transition_label("resume", "", ""),
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(580.0, 270.0))),
+        (paused_model.clone().into(), paused_view.clone().into()),
+        (running_model.clone().into(), running_view.clone().into()),
+    );
+    let (stop_model, stop_view) = new_umlclass_dependency(
+        statemachine_models::TRANSITION,
+        &statemachine_models::transition_label("stop", "", "save state"),
+        true,
+        None,
+        (running_model.clone().into(), running_view.clone().into()),
+        (final_model.clone().into(), final_view.clone().into()),
+    );
+
+    let (active, active_view) = new_umlclass_package(
+        "Active",
+        statemachine_models::COMPOSITE_STATE,
+        UmlClassPackageKind::Package,
+        egui::Rect::from_x_y_ranges(400.0..=800.0, 100.0..=350.0),
+    );
+    {
+        let mut w = active_view.write();
+        let active_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [running_view.clone().into(), paused_view.clone().into()] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: active_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo State machine diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            initial_model.into(),
+            idle_model.into(),
+            active.into(),
+            final_model.into(),
+            init_model.into(),
+            start_model.into(),
+            pause_model.into(),
+            resume_model.into(),
+            stop_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            initial_view.into(),
+            idle_view.into(),
+            active_view.into(),
+            final_view.into(),
+            init_view.into(),
+            start_view.into(),
+            pause_view.into(),
+            resume_view.into(),
+            stop_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<StateMachineProfile>,
+        StateMachineControllerAdapter,
+        DiagramControllerGen2<
+            UmlClassDomain<StateMachineProfile>,
+            UmlClassDiagramAdapter<StateMachineProfile>,
+        >,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_association(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<StateMachineProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<StateMachineProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_association as &InstanceButtonF)];
+    fn class_dep_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<StateMachineProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Dependency {
+            target_arrow_open: true,
+            stereotype: statemachine_models::TRANSITION.to_owned(),
+            name: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<StateMachineProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "→", &class_dep_0_0 as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let states = vec![
+        (
+            UmlClassToolStage::Class {
+                name: "State".to_owned(),
+                stereotype: statemachine_models::STATE.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            "State",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num1,
+            )),
+        ),
+        (
+            UmlClassToolStage::PackageStart {
+                name: "Composite State".to_owned(),
+                stereotype: statemachine_models::COMPOSITE_STATE.to_owned(),
+                kind: UmlClassPackageKind::Package,
+            },
+            "Composite State",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num2,
+            )),
+        ),
+        (
+            UmlClassToolStage::ClassOperation {
+                name: "entry / action".to_owned(),
+                return_type: "".to_owned(),
+                stereotype: "".to_owned(),
+            },
+            "Internal Activity",
+            None,
+        ),
+    ];
+
+    let pseudostates = vec![
+        (
+            UmlClassToolStage::UseCase {
+                name: "".to_owned(),
+                stereotype: statemachine_models::INITIAL.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            "Initial",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+        (
+            UmlClassToolStage::UseCase {
+                name: "".to_owned(),
+                stereotype: statemachine_models::FINAL.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            "Final",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        (
+            UmlClassToolStage::UseCase {
+                name: "".to_owned(),
+                stereotype: statemachine_models::CHOICE.to_owned(),
+                is_abstract: false,
+                background_color: MGlobalColor::None,
+            },
+            "Choice",
+            None,
+        ),
+    ];
+
+    let relationships = vec![(
+        UmlClassToolStage::LinkStart {
+            link_type: LinkType::Dependency {
+                target_arrow_open: true,
+                stereotype: statemachine_models::TRANSITION.to_owned(),
+                name: statemachine_models::transition_label("trigger", "guard", "effect"),
+            },
+        },
+        "Transition",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num5,
+        )),
+    )];
+
+    let palette_items = vec![
+        ("States", states),
+        ("Pseudostates", pseudostates),
+        ("Relationships", relationships),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<StateMachineProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<StateMachineProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-statemachine",
+    pretty_name: "State machine diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Unified Modeling Language",
+        description: "State machine diagram (states, composite states, pseudostates, transitions with trigger [guard] / effect labels)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_state(
+    name: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<StateMachineProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        Vec::new(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
@@ -0,0 +1,44 @@
+// States, composite states are packages
+pub const STATE: &str = "";
+pub const COMPOSITE_STATE: &str = "";
+// Pseudostates
+pub const INITIAL: &str = "initial";
+pub const FINAL: &str = "final";
+pub const CHOICE: &str = "choice";
+
+pub const TRANSITION: &str = "";
+
+/// Transition label in the `trigger [guard] / effect` notation, leaving out empty parts
+pub fn transition_label(trigger: &str, guard: &str, effect: &str) -> String {
+    let mut label = trigger.trim().to_owned();
+    if !guard.trim().is_empty() {
+        if !label.is_empty() {
+            label.push(' ');
+        }
+        label.push_str(&format!("[{}]", guard.trim()));
+    }
+    if !effect.trim().is_empty() {
+        if !label.is_empty() {
+            label.push(' ');
+        }
+        label.push_str(&format!("/ {}", effect.trim()));
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_labels_leave_out_empty_parts() {
+        assert_eq!(
+            transition_label("start", "ready", "reset()"),
+            "start [ready] / reset()"
+        );
+        assert_eq!(transition_label("pause", "", ""), "pause");
+        assert_eq!(transition_label("", "done", ""), "[done]");
+        assert_eq!(transition_label("", "", "log()"), "/ log()");
+        assert_eq!(transition_label("", "", ""), "");
+    }
+}
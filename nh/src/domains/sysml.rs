@@ -0,0 +1,2 @@
+pub mod sysml_controllers;
+pub mod sysml_models;
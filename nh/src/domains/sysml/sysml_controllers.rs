@@ -0,0 +1,1021 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, StereotypeController, UmlClassDiagramAdapter, UmlClassDomain,
+        UmlClassElementView, UmlClassProfile, UmlClassToolStage, UmlClassView,
+        new_umlclass_association, new_umlclass_class, new_umlclass_composition,
+        new_umlclass_dependency, new_umlclass_instance, new_umlclass_package,
+        new_umlclass_property,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, ElementControllerGen2, GlobalDrawingContext, InsensitiveCommand,
+            MGlobalColor, MultiDiagramController, PositionNoT, View,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        ufoption::UFOption,
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::{
+        sysml::sysml_models,
+        umlclass::{
+            umlclass_controllers::{
+                PartialUmlClassElement, UmlClassElementOrVertex, UmlClassRenderStyle,
+            },
+            umlclass_models::{
+                UmlClass, UmlClassAssociationNavigability, UmlClassElement, UmlClassInstance,
+                UmlClassPackageKind,
+            },
+        },
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+#[derive(Clone, Default)]
+pub struct SysmlProfile;
+impl UmlClassProfile for SysmlProfile {
+    type ClassStereotypeController = SysmlClassStereotypeController;
+    type InstanceStereotypeController = SysmlInstanceStereotypeController;
+
+    fn class_compartments(stereotype: &str) -> &'static [&'static str] {
+        match stereotype {
+            sysml_models::BLOCK => &["constraints"],
+            sysml_models::CONSTRAINT_BLOCK => &["constraints", "parameters"],
+            _ => &[],
+        }
+    }
+}
+
+type SysmlDiagramView =
+    DiagramControllerGen2<UmlClassDomain<SysmlProfile>, UmlClassDiagramAdapter<SysmlProfile>>;
+
+/// Block definition diagram
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct SysmlBddControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<SysmlProfile>> for SysmlBddControllerAdapter {
+    type DiagramViewT = SysmlDiagramView;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-sysml-bdd"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Block Definition Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Block Definition Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+/// Internal block diagram
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct SysmlIbdControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<SysmlProfile>> for SysmlIbdControllerAdapter {
+    type DiagramViewT = SysmlDiagramView;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-sysml-ibd"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Internal Block Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared Internal Block Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+fn new_bdd_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<SysmlProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            SysmlBddControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<SysmlProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+fn new_ibd_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<SysmlProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            SysmlIbdControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<SysmlProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new_bdd(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Block definition diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_bdd_controlller(diagram, name, vec![])
+}
+
+pub fn new_ibd(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Internal block diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_ibd_controlller(diagram, name, vec![])
+}
+
+pub fn demo_bdd(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (vehicle_model, vehicle_view) = new_block(
+        "Vehicle",
+        sysml_models::BLOCK,
+        &[("mass", "Mass"), ("maxSpeed", "Speed")],
+        egui::Pos2::new(400.0, 100.0),
+    );
+    let (engine_model, engine_view) = new_block(
+        "Engine",
+        sysml_models::BLOCK,
+        &[("power", "Power")],
+        egui::Pos2::new(200.0, 350.0),
+    );
+    let (wheel_model, wheel_view) = new_block(
+        "Wheel",
+        sysml_models::BLOCK,
+        &[("diameter", "Length")],
+        egui::Pos2::new(450.0, 350.0),
+    );
+    let (driver_model, driver_view) = new_block(
+        "Driver",
+        sysml_models::BLOCK,
+        &[],
+        egui::Pos2::new(700.0, 100.0),
+    );
+    let (mass_model, mass_view) = new_block(
+        "Mass",
+        sysml_models::VALUE_TYPE,
+        &[("unit", "kg")],
+        egui::Pos2::new(700.0, 350.0),
+    );
+
+    let (engine_part_model, engine_part_view) = new_umlclass_composition(
+        "",
+        "",
+        "1",
+        "1",
+        None,
+        (vehicle_model.clone().into(), vehicle_view.clone().into()),
+        (engine_model.clone().into(), engine_view.clone().into()),
+    );
+    engine_part_model.write().target_label_role = "engine".to_owned().into();
+    let (wheel_part_model, wheel_part_view) = new_umlclass_composition(
+        "",
+        "",
+        "1",
+        "4",
+        None,
+        (vehicle_model.clone().into(), vehicle_view.clone().into()),
+        (wheel_model.clone().into(), wheel_view.clone().into()),
+    );
+    wheel_part_model.write().target_label_role = "wheels".to_owned().into();
+    let (driver_ref_model, driver_ref_view) = new_umlclass_association(
+        "",
+        "",
+        "0..1",
+        "0..1",
+        None,
+        (vehicle_model.clone().into(), vehicle_view.clone().into()),
+        (driver_model.clone().into(), driver_view.clone().into()),
+    );
+    {
+        let mut w = driver_ref_model.write();
+        w.target_label_role = "driver".to_owned().into();
+        w.target_navigability = UmlClassAssociationNavigability::Navigable;
+    }
+
+    let name = format!("Demo Block definition diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            vehicle_model.into(),
+            engine_model.into(),
+            wheel_model.into(),
+            driver_model.into(),
+            mass_model.into(),
+            engine_part_model.into(),
+            wheel_part_model.into(),
+            driver_ref_model.into(),
+        ],
+    ));
+    new_bdd_controlller(
+        diagram,
+        name,
+        vec![
+            vehicle_view.into(),
+            engine_view.into(),
+            wheel_view.into(),
+            driver_view.into(),
+            mass_view.into(),
+            engine_part_view.into(),
+            wheel_part_view.into(),
+            driver_ref_view.into(),
+        ],
+    )
+}
+
+pub fn demo_ibd(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (engine_model, engine_view) = new_usage(
+        "engine",
+        "Engine",
+        sysml_models::PART_USAGE,
+        egui::Pos2::new(250.0, 250.0),
+    );
+    let (engine_out_model, engine_out_view) = new_usage(
+        "torqueOut",
+        "TorquePort",
+        sysml_models::PROXY_PORT,
+        egui::Pos2::new(400.0, 250.0),
+    );
+    let (transmission_in_model, transmission_in_view) = new_usage(
+        "torqueIn",
+        "~TorquePort",
+        sysml_models::PROXY_PORT,
+        egui::Pos2::new(550.0, 250.0),
+    );
+    let (transmission_model, transmission_view) = new_usage(
+        "transmission",
+        "Transmission",
+        sysml_models::PART_USAGE,
+        egui::Pos2::new(700.0, 250.0),
+    );
+    let (driver_model, driver_view) = new_usage(
+        "driver",
+        "Driver",
+        sysml_models::REFERENCE_USAGE,
+        egui::Pos2::new(250.0, 450.0),
+    );
+
+    let (_, engine_port_view) = new_umlclass_association(
+        sysml_models::CONNECTOR,
+        "",
+        "",
+        "",
+        None,
+        (engine_model.clone().into(), engine_view.clone().into()),
+        (
+            engine_out_model.clone().into(),
+            engine_out_view.clone().into(),
+        ),
+    );
+    let (_, shaft_view) = new_umlclass_association(
+        sysml_models::CONNECTOR,
+        "shaft",
+        "",
+        "",
+        None,
+        (
+            engine_out_model.clone().into(),
+            engine_out_view.clone().into(),
+        ),
+        (
+            transmission_in_model.clone().into(),
+            transmission_in_view.clone().into(),
+        ),
+    );
+    let (_, transmission_port_view) = new_umlclass_association(
+        sysml_models::CONNECTOR,
+        "",
+        "",
+        "",
+        None,
+        (
+            transmission_in_model.clone().into(),
+            transmission_in_view.clone().into(),
+        ),
+        (
+            transmission_model.clone().into(),
+            transmission_view.clone().into(),
+        ),
+    );
+    let (_, torque_view) = new_umlclass_dependency(
+        sysml_models::ITEM_FLOW,
+        "torque: Torque",
+        true,
+        Some((ViewUuid::now_v7(), egui::Pos2::new(475.0, 200.0))),
+        (
+            engine_out_model.clone().into(),
+            engine_out_view.clone().into(),
+        ),
+        (
+            transmission_in_model.clone().into(),
+            transmission_in_view.clone().into(),
+        ),
+    );
+    let (_, throttle_view) = new_umlclass_dependency(
+        sysml_models::ITEM_FLOW,
+        "throttle: Signal",
+        true,
+        None,
+        (driver_model.clone().into(), driver_view.clone().into()),
+        (engine_model.clone().into(), engine_view.clone().into()),
+    );
+
+    let (vehicle, vehicle_view) = new_umlclass_package(
+        "Vehicle",
+        sysml_models::BLOCK,
+        UmlClassPackageKind::Boundary,
+        egui::Rect::from_x_y_ranges(100.0..=850.0, 100.0..=550.0),
+    );
+    {
+        let mut w = vehicle_view.write();
+        let vehicle_uuid = *w.uuid();
+        let (mut u, mut a) = Default::default();
+        for e in [
+            engine_view.clone().into(),
+            engine_out_view.clone().into(),
+            transmission_in_view.clone().into(),
+            transmission_view.clone().into(),
+            driver_view.clone().into(),
+            engine_port_view.clone().into(),
+            shaft_view.clone().into(),
+            transmission_port_view.clone().into(),
+            torque_view.clone().into(),
+            throttle_view.clone().into(),
+        ] {
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target: vehicle_uuid,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(e),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    }
+
+    let name = format!("Demo Internal block diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![vehicle.into()],
+    ));
+    new_ibd_controlller(diagram, name, vec![vehicle_view.into()])
+}
+
+pub fn deserializer_bdd(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<SysmlProfile>,
+        SysmlBddControllerAdapter,
+        SysmlDiagramView,
+    >>(&uuid)?)
+}
+
+pub fn deserializer_ibd(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<SysmlProfile>,
+        SysmlIbdControllerAdapter,
+        SysmlDiagramView,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_connector(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<SysmlProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: sysml_models::CONNECTOR.to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<SysmlProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_connector as &InstanceButtonF)];
+    fn class_part_0_0(
+        m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<SysmlProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Composition {
+            stereotype: "".to_owned(),
+            source_multiplicity: "1".to_owned(),
+            target_multiplicity: "1".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<SysmlProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "◆", &class_part_0_0 as &ClassButtonF)];
+}
+
+fn other_palette_items() -> Vec<(
+    UmlClassToolStage,
+    &'static str,
+    Option<egui::KeyboardShortcut>,
+)> {
+    vec![
+        (
+            UmlClassToolStage::Comment {
+                stereotype: "".to_owned(),
+                text: "a comment".to_owned(),
+                align: egui::Align2::CENTER_CENTER,
+            },
+            "Comment",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num9,
+            )),
+        ),
+        (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+    ]
+}
+
+pub fn default_settings_bdd() -> Box<dyn DiagramSettings> {
+    let classifiers = [
+        ("Block", sysml_models::BLOCK, Some(egui::Key::Num1)),
+        ("ValueType", sysml_models::VALUE_TYPE, Some(egui::Key::Num2)),
+        ("InterfaceBlock", sysml_models::INTERFACE_BLOCK, None),
+        ("ConstraintBlock", sysml_models::CONSTRAINT_BLOCK, None),
+    ]
+    .into_iter()
+    .map(|(label, stereotype, key)| {
+        (
+            UmlClassToolStage::Class {
+                name: label.to_owned(),
+                stereotype: stereotype.to_owned(),
+                is_abstract: false,
+                render_style: UmlClassRenderStyle::Class,
+                background_color: MGlobalColor::None,
+            },
+            label,
+            key.map(|k| egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, k)),
+        )
+    })
+    .collect();
+
+    let features = [
+        ("value", "Real", sysml_models::VALUE, "Value Property"),
+        ("part", "Block", sysml_models::PART, "Part Property"),
+        (
+            "ref",
+            "Block",
+            sysml_models::REFERENCE,
+            "Reference Property",
+        ),
+        ("flow", "Item", sysml_models::FLOW_PROPERTY, "Flow Property"),
+    ]
+    .into_iter()
+    .map(|(name, property_type, stereotype, label)| {
+        (
+            UmlClassToolStage::ClassProperty {
+                name: name.to_owned(),
+                property_type: property_type.to_owned(),
+                stereotype: stereotype.to_owned(),
+            },
+            label,
+            None,
+        )
+    })
+    .chain(std::iter::once((
+        UmlClassToolStage::ClassOperation {
+            name: "operation".to_owned(),
+            return_type: "".to_owned(),
+            stereotype: "".to_owned(),
+        },
+        "Operation",
+        None,
+    )))
+    .collect();
+
+    let relationships = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Composition {
+                    stereotype: "".to_owned(),
+                    source_multiplicity: "1".to_owned(),
+                    target_multiplicity: "1".to_owned(),
+                },
+            },
+            "Part Association",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num3,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: "".to_owned(),
+                    source_multiplicity: "".to_owned(),
+                    target_multiplicity: "0..1".to_owned(),
+                },
+            },
+            "Reference Association",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Generalization {
+                    set_name: "".to_owned(),
+                },
+            },
+            "Generalization",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: "".to_owned(),
+                    name: "".to_owned(),
+                },
+            },
+            "Dependency",
+            None,
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Blocks", classifiers),
+        ("Features", features),
+        ("Relationships", relationships),
+        ("Other", other_palette_items()),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<SysmlProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn default_settings_ibd() -> Box<dyn DiagramSettings> {
+    let usages = [
+        (
+            "part",
+            "Block",
+            sysml_models::PART_USAGE,
+            "Part",
+            Some(egui::Key::Num1),
+        ),
+        (
+            "ref",
+            "Block",
+            sysml_models::REFERENCE_USAGE,
+            "Reference",
+            None,
+        ),
+        (
+            "p",
+            "InterfaceBlock",
+            sysml_models::PROXY_PORT,
+            "Proxy Port",
+            Some(egui::Key::Num2),
+        ),
+        ("p", "Block", sysml_models::FULL_PORT, "Full Port", None),
+    ]
+    .into_iter()
+    .map(|(name, instance_type, stereotype, label, key)| {
+        (
+            UmlClassToolStage::Instance {
+                instance_name: name.to_owned(),
+                instance_type: instance_type.to_owned(),
+                stereotype: stereotype.to_owned(),
+                background_color: MGlobalColor::None,
+            },
+            label,
+            key.map(|k| egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, k)),
+        )
+    })
+    .chain(std::iter::once((
+        UmlClassToolStage::PackageStart {
+            name: "Block".to_owned(),
+            stereotype: sysml_models::BLOCK.to_owned(),
+            kind: UmlClassPackageKind::Boundary,
+        },
+        "Enclosing Block",
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::Num3,
+        )),
+    )))
+    .collect();
+
+    let connectors = vec![
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: sysml_models::CONNECTOR.to_owned(),
+                    source_multiplicity: "".to_owned(),
+                    target_multiplicity: "".to_owned(),
+                },
+            },
+            "Connector",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num4,
+            )),
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Association {
+                    stereotype: sysml_models::BINDING_CONNECTOR.to_owned(),
+                    source_multiplicity: "".to_owned(),
+                    target_multiplicity: "".to_owned(),
+                },
+            },
+            "Binding Connector",
+            None,
+        ),
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: LinkType::Dependency {
+                    target_arrow_open: true,
+                    stereotype: sysml_models::ITEM_FLOW.to_owned(),
+                    name: "item: Item".to_owned(),
+                },
+            },
+            "Item Flow",
+            Some(egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::Num5,
+            )),
+        ),
+    ];
+
+    let palette_items = vec![
+        ("Parts and Ports", usages),
+        ("Connectors", connectors),
+        ("Other", other_palette_items()),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<SysmlProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<SysmlProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-sysml-bdd",
+    pretty_name: "SysML block definition diagram",
+    default_settings: &(default_settings_bdd as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Systems Modeling Language",
+        description: "Block definition diagram (blocks, value types, part and reference associations)",
+        constructors: &[
+            ("empty", &(new_bdd as DiagramConstructorF)),
+            ("demo", &(demo_bdd as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer_bdd as DeserializeControllerF),
+}}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-sysml-ibd",
+    pretty_name: "SysML internal block diagram",
+    default_settings: &(default_settings_ibd as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Systems Modeling Language",
+        description: "Internal block diagram (parts, ports, connectors and item flows within an enclosing block)",
+        constructors: &[
+            ("empty", &(new_ibd as DiagramConstructorF)),
+            ("demo", &(demo_ibd as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer_ibd as DeserializeControllerF),
+}}
+
+#[derive(Clone, Default)]
+pub struct SysmlClassStereotypeController {
+    display_string: String,
+    buffer: &'static str,
+}
+
+impl StereotypeController for SysmlClassStereotypeController {
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label("Stereotype:");
+        let mut changed = false;
+        egui::ComboBox::from_id_salt("Class stereotype:")
+            .selected_text(&self.display_string)
+            .show_ui(ui, |ui| {
+                for e in [
+                    (sysml_models::NONE, "None"),
+                    (sysml_models::BLOCK, "Block"),
+                    (sysml_models::VALUE_TYPE, "ValueType"),
+                    (sysml_models::INTERFACE_BLOCK, "InterfaceBlock"),
+                    (sysml_models::CONSTRAINT_BLOCK, "ConstraintBlock"),
+                ] {
+                    if ui.selectable_value(&mut self.buffer, e.0, e.1).changed() {
+                        changed = true;
+                        self.refresh(e.0);
+                    }
+                }
+            });
+        changed
+    }
+    fn get_raw(&self) -> String {
+        self.buffer.to_owned()
+    }
+    fn is_valid(&self, value: &str) -> bool {
+        sysml_models::sysml_class_stereotype_literal(value).is_some()
+    }
+    fn refresh(&mut self, new_value: &str) {
+        if let Some(new_value) = sysml_models::sysml_class_stereotype_literal(new_value) {
+            self.buffer = new_value;
+        }
+        self.display_string = if self.buffer.is_empty() {
+            "None".to_owned()
+        } else {
+            format!("«{}»", self.buffer)
+        };
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SysmlInstanceStereotypeController {
+    display_string: String,
+    buffer: &'static str,
+}
+
+impl StereotypeController for SysmlInstanceStereotypeController {
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label("Stereotype:");
+        let mut changed = false;
+        egui::ComboBox::from_id_salt("Instance stereotype:")
+            .selected_text(&self.display_string)
+            .show_ui(ui, |ui| {
+                for e in [
+                    (sysml_models::PART_USAGE, "Part"),
+                    (sysml_models::REFERENCE_USAGE, "Reference"),
+                    (sysml_models::PORT, "Port"),
+                    (sysml_models::PROXY_PORT, "Proxy Port"),
+                    (sysml_models::FULL_PORT, "Full Port"),
+                ] {
+                    if ui.selectable_value(&mut self.buffer, e.0, e.1).changed() {
+                        changed = true;
+                        self.refresh(e.0);
+                    }
+                }
+            });
+        changed
+    }
+    fn get_raw(&self) -> String {
+        self.buffer.to_owned()
+    }
+    fn is_valid(&self, value: &str) -> bool {
+        sysml_models::sysml_instance_stereotype_literal(value).is_some()
+    }
+    fn refresh(&mut self, new_value: &str) {
+        if let Some(new_value) = sysml_models::sysml_instance_stereotype_literal(new_value) {
+            self.buffer = new_value;
+        }
+        self.display_string = format!("«{}»", self.buffer);
+    }
+}
+
+fn new_block(
+    name: &str,
+    stereotype: &str,
+    values: &[(&str, &str)],
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<SysmlProfile>>) {
+    new_umlclass_class(
+        name,
+        stereotype,
+        false,
+        values
+            .iter()
+            .map(|(name, value_type)| {
+                new_umlclass_property(
+                    UFOption::None,
+                    name,
+                    value_type,
+                    "",
+                    "",
+                    sysml_models::VALUE,
+                )
+            })
+            .collect(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
+
+fn new_usage(
+    name: &str,
+    usage_type: &str,
+    stereotype: &str,
+    position: egui::Pos2,
+) -> (
+    ERef<UmlClassInstance>,
+    ERef<super::super::umlclass::umlclass_controllers::UmlClassInstanceView<SysmlProfile>>,
+) {
+    new_umlclass_instance(
+        name,
+        usage_type,
+        stereotype,
+        "",
+        position,
+        MGlobalColor::None,
+    )
+}
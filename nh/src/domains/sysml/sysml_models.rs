@@ -0,0 +1,48 @@
+// Block definition diagram classifiers
+pub const NONE: &str = "";
+pub const BLOCK: &str = "block";
+pub const VALUE_TYPE: &str = "valueType";
+pub const INTERFACE_BLOCK: &str = "interfaceBlock";
+pub const CONSTRAINT_BLOCK: &str = "constraint";
+
+pub fn sysml_class_stereotype_literal(e: &str) -> Option<&'static str> {
+    let e = match e {
+        NONE => NONE,
+        BLOCK => BLOCK,
+        VALUE_TYPE => VALUE_TYPE,
+        INTERFACE_BLOCK => INTERFACE_BLOCK,
+        CONSTRAINT_BLOCK => CONSTRAINT_BLOCK,
+        _ => return None,
+    };
+    Some(e)
+}
+
+// Properties of blocks, value properties are left without a stereotype
+pub const VALUE: &str = "";
+pub const PART: &str = "part";
+pub const REFERENCE: &str = "reference";
+pub const FLOW_PROPERTY: &str = "flowProperty";
+
+// Internal block diagram usages
+pub const PART_USAGE: &str = "part";
+pub const REFERENCE_USAGE: &str = "ref";
+pub const PORT: &str = "port";
+pub const PROXY_PORT: &str = "proxy";
+pub const FULL_PORT: &str = "full";
+
+pub fn sysml_instance_stereotype_literal(e: &str) -> Option<&'static str> {
+    let e = match e {
+        PART_USAGE => PART_USAGE,
+        REFERENCE_USAGE => REFERENCE_USAGE,
+        PORT => PORT,
+        PROXY_PORT => PROXY_PORT,
+        FULL_PORT => FULL_PORT,
+        _ => return None,
+    };
+    Some(e)
+}
+
+// Relationships
+pub const CONNECTOR: &str = "";
+pub const ITEM_FLOW: &str = "flow";
+pub const BINDING_CONNECTOR: &str = "equal";
@@ -0,0 +1,2 @@
+pub mod timeline_controllers;
+pub mod timeline_models;
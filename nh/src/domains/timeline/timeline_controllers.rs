@@ -0,0 +1,2251 @@
+use super::timeline_models::{
+    TimelineBar, TimelineDate, TimelineDiagram, TimelineElement, TimelineLane, TimelineMilestone,
+};
+use crate::common::canvas::{self, NHCanvas, NHShape};
+use crate::common::controller::{
+    BucketNoT, ColorBundle, ColorChangeData, ContainerModel, ControllerAdapter, DiagramAdapter,
+    DiagramController, DiagramControllerGen2, DiagramSettings, DiagramSettings2, Domain,
+    ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
+    GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, MGlobalColor, Model,
+    MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand, PropertiesStatus,
+    Queryable, SelectionStatus, ShowSettingsResult, SnapManager, TargettingStatus, Tool,
+    ToolPalette, TryMerge, View,
+};
+use crate::common::entity::{Entity, EntityUuid};
+use crate::common::eref::ERef;
+use crate::common::project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer};
+use crate::common::ui_ext::UiExt;
+use crate::common::uuid::{ControllerUuid, ModelUuid, ViewUuid};
+use crate::common::views::multiconnection_view::VertexInformation;
+use crate::common::views::package_view::{PackageAdapter, PackageView};
+use crate::{
+    CustomModal, CustomModalResult, DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF,
+    DiagramConstructorF, DiagramCreationData, DiagramInfo, SetShortcut,
+};
+use eframe::egui;
+use std::collections::HashSet;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::{Arc, RwLock},
+};
+
+/// Date placed at x = 0 (2026-01-01)
+const AXIS_ORIGIN: TimelineDate = TimelineDate::from_day_number(20454);
+/// Horizontal distance between two consecutive days on the time axis
+const DAY_WIDTH: f32 = 8.0;
+const BAR_HEIGHT: f32 = 24.0;
+const MILESTONE_RADIUS: f32 = 8.0;
+const DEFAULT_BAR_DAYS: i64 = 14;
+
+fn date_to_x(date: TimelineDate) -> f32 {
+    AXIS_ORIGIN.days_until(&date) as f32 * DAY_WIDTH
+}
+fn x_to_date(x: f32) -> TimelineDate {
+    AXIS_ORIGIN.add_days((x / DAY_WIDTH).round() as i64)
+}
+
+pub struct TimelineDomain;
+impl Domain for TimelineDomain {
+    type SettingsT = TimelineSettings;
+    type CommonElementT = TimelineElement;
+    type DiagramModelT = TimelineDiagram;
+    type CommonElementViewT = TimelineElementView;
+    type ViewTargettingSectionT = TimelineElement;
+    type QueryableT<'a> = GenericQueryable<'a, Self>;
+    type ToolT = NaiveTimelineTool;
+    type OrdinalMovementT = TimelineOrdinalMovement;
+    type AddCommandElementT = TimelineElementOrVertex;
+    type PropChangeT = TimelinePropChange;
+}
+
+type PackageViewT = PackageView<TimelineDomain, TimelineLaneAdapter>;
+
+/// Dark profile with the default light blue of bars darkened for the light text
+const DARK_COLOR_PROFILE: canvas::ColorProfile = canvas::ColorProfile::DARK.with_fills(&[(
+    egui::Color32::from_rgb(173, 216, 230),
+    egui::Color32::from_rgb(35, 75, 90),
+)]);
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimelineOrdinalMovement {}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum TimelinePropChange {
+    Name(Arc<String>),
+    StartDate(TimelineDate),
+    EndDate(TimelineDate),
+    Date(TimelineDate),
+
+    Color(ColorChangeData),
+    Comment(Arc<String>),
+}
+
+impl Debug for TimelinePropChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "TimelinePropChange::{}",
+            match self {
+                Self::Name(name) => format!("Name({})", name),
+                Self::StartDate(date) => format!("StartDate({})", date),
+                Self::EndDate(date) => format!("EndDate({})", date),
+                Self::Date(date) => format!("Date({})", date),
+
+                Self::Color(_color) => "Color(..)".to_string(),
+                Self::Comment(comment) => format!("Comment({})", comment),
+            }
+        )
+    }
+}
+
+impl From<ColorChangeData> for TimelinePropChange {
+    fn from(value: ColorChangeData) -> Self {
+        TimelinePropChange::Color(value)
+    }
+}
+impl TryFrom<TimelinePropChange> for ColorChangeData {
+    type Error = ();
+
+    fn try_from(value: TimelinePropChange) -> Result<Self, Self::Error> {
+        match value {
+            TimelinePropChange::Color(v) => Ok(v),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryMerge for TimelinePropChange {
+    fn try_merge(&self, newer: &Self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match (self, newer) {
+            (Self::Name(_), newer @ Self::Name(_))
+            | (Self::Comment(_), newer @ Self::Comment(_)) => Some(newer.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, derive_more::From, derive_more::TryInto)]
+pub enum TimelineElementOrVertex {
+    Element(TimelineElementView),
+    Vertex(VertexInformation),
+}
+
+impl Debug for TimelineElementOrVertex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "TimelineElementOrVertex::???")
+    }
+}
+
+#[derive(Clone, derive_more::From, nh_derive::View, nh_derive::NHContextSerDeTag)]
+#[view(default_passthrough = "eref", domain = "TimelineDomain")]
+#[nh_context_serde(uuid_type = ViewUuid)]
+pub enum TimelineElementView {
+    Lane(ERef<PackageViewT>),
+    Bar(ERef<TimelineBarView>),
+    Milestone(ERef<TimelineMilestoneView>),
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct TimelineControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<TimelineDiagram>,
+}
+
+impl ControllerAdapter<TimelineDomain> for TimelineControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<TimelineDomain, TimelineDiagramAdapter>;
+
+    fn model(&self) -> ERef<TimelineDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<TimelineDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "timeline"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::timeline_models::transitive_closure(&self.model.read(), when_deleting)
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: TimelineElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, TimelineElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Timeline Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Timeline Diagram".to_owned().into(),
+                TimelineDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+#[derive(
+    Clone, serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize,
+)]
+pub struct TimelineDiagramAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<TimelineDiagram>,
+    background_color: MGlobalColor,
+    #[serde(skip)]
+    #[nh_context_serde(skip_and_default)]
+    buffer: TimelineDiagramBuffer,
+}
+
+#[derive(Clone, Default)]
+struct TimelineDiagramBuffer {
+    name: String,
+    comment: String,
+}
+
+impl TimelineDiagramAdapter {
+    fn new(model: ERef<TimelineDiagram>) -> Self {
+        let m = model.read();
+        Self {
+            model: model.clone(),
+            background_color: MGlobalColor::None,
+            buffer: TimelineDiagramBuffer {
+                name: (*m.name).clone(),
+                comment: (*m.comment).clone(),
+            },
+        }
+    }
+}
+
+impl DiagramAdapter<TimelineDomain> for TimelineDiagramAdapter {
+    fn model(&self) -> ERef<TimelineDiagram> {
+        self.model.clone()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid()
+    }
+    fn model_name(&self) -> Arc<String> {
+        self.model.read().name.clone()
+    }
+
+    fn get_element_pos_in(
+        &self,
+        parent: &ModelUuid,
+        model_uuid: &ModelUuid,
+    ) -> Option<(BucketNoT, PositionNoT)> {
+        self.model.read().get_element_pos_in(parent, model_uuid)
+    }
+
+    fn create_new_view_for(
+        &self,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        element: TimelineElement,
+    ) -> Result<TimelineElementView, HashSet<ModelUuid>> {
+        let v = match element {
+            TimelineElement::Lane(rw_lock) => TimelineElementView::from(new_timeline_lane_view(
+                rw_lock,
+                egui::Rect {
+                    min: egui::Pos2::ZERO,
+                    max: egui::Pos2::new(400.0, 100.0),
+                },
+            )),
+            TimelineElement::Bar(rw_lock) => {
+                TimelineElementView::from(new_timeline_bar_view(rw_lock, 0.0))
+            }
+            TimelineElement::Milestone(rw_lock) => {
+                TimelineElementView::from(new_timeline_milestone_view(rw_lock, 0.0))
+            }
+        };
+
+        Ok(v)
+    }
+    fn label_for(&self, e: &TimelineElement) -> Arc<String> {
+        match e {
+            TimelineElement::Lane(inner) => inner.read().name.clone(),
+            TimelineElement::Bar(inner) => inner.read().name.clone(),
+            TimelineElement::Milestone(inner) => inner.read().name.clone(),
+        }
+    }
+
+    fn background_color(&self, global_colors: &ColorBundle) -> egui::Color32 {
+        global_colors
+            .get(&self.background_color)
+            .unwrap_or(egui::Color32::WHITE)
+    }
+    fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
+        egui::Color32::from_rgb(220, 220, 220)
+    }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        if dark {
+            DARK_COLOR_PROFILE
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
+    fn enable_headers(&self) -> (bool, bool) {
+        (true, true)
+    }
+    fn show_view_props_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        drawing_context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) {
+        ui.label("Background color:");
+        if let Some(new_color) = crate::common::controller::mglobalcolor_edit_button(
+            drawing_context,
+            ui,
+            &self.background_color,
+        ) {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                TimelinePropChange::Color((0, new_color).into()),
+            ));
+        }
+    }
+    fn show_model_props_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        _drawing_context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) {
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.buffer.name)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                TimelinePropChange::Name(Arc::new(self.buffer.name.clone())),
+            ));
+        };
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.buffer.comment)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                TimelinePropChange::Comment(Arc::new(self.buffer.comment.clone())),
+            ));
+        }
+    }
+
+    fn apply_property_change_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        command: &InsensitiveCommand<
+            TimelineOrdinalMovement,
+            TimelineElementOrVertex,
+            TimelinePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) {
+        if let InsensitiveCommand::PropertyChange(_, property) = command {
+            let mut model = self.model.write();
+            match property {
+                TimelinePropChange::Name(name) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Name(model.name.clone()),
+                    ));
+                    model.name = name.clone();
+                }
+                TimelinePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Color(ColorChangeData {
+                            slot: 0,
+                            color: self.background_color,
+                        }),
+                    ));
+                    self.background_color = *color;
+                }
+                TimelinePropChange::Comment(comment) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Comment(model.comment.clone()),
+                    ));
+                    model.comment = comment.clone();
+                }
+                _ => {}
+            }
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.buffer.name = (*model.name).clone();
+        self.buffer.comment = (*model.comment).clone();
+    }
+
+    fn menubar_options_fun(
+        &self,
+        _view_uuid: &ViewUuid,
+        _ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+    }
+    fn try_handle_custom_shortcut(
+        &mut self,
+        settings: &TimelineSettings,
+        modifiers: egui::Modifiers,
+        key: egui::Key,
+    ) -> PropertiesStatus<TimelineDomain> {
+        if let Some((uuid, ts)) = settings
+            .palette
+            .read()
+            .unwrap()
+            .find_matching_tool_stage(modifiers, key)
+        {
+            PropertiesStatus::ToolRequest(Some(NaiveTimelineTool {
+                uuid,
+                initial_stage: ts.clone(),
+                current_stage: ts,
+                result: PartialTimelineElement::None,
+                event_lock: false,
+                is_spent: None,
+            }))
+        } else {
+            PropertiesStatus::Shown
+        }
+    }
+
+    fn deep_copy(&self) -> (Self, HashMap<ModelUuid, TimelineElement>) {
+        let (new_model, models) = super::timeline_models::deep_copy_diagram(&self.model.read());
+        (
+            Self {
+                model: new_model,
+                ..self.clone()
+            },
+            models,
+        )
+    }
+
+    fn enumerate_models(&self) -> (Self, HashMap<ModelUuid, TimelineElement>) {
+        let models = super::timeline_models::enumerate_diagram(&self.model.read());
+        (self.clone(), models)
+    }
+}
+
+fn new_controlller(
+    model: ERef<TimelineDiagram>,
+    name: String,
+    elements: Vec<TimelineElementView>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            TimelineControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                TimelineDiagramAdapter::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Timeline diagram {}", no);
+
+    let diagram = ERef::new(TimelineDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let date = |s: &str| s.parse::<TimelineDate>().unwrap();
+
+    let (design, design_view) =
+        new_timeline_bar("Design", date("2026-01-05"), date("2026-01-30"), 60.0);
+    let (implementation, implementation_view) = new_timeline_bar(
+        "Implementation",
+        date("2026-02-02"),
+        date("2026-03-27"),
+        100.0,
+    );
+    let (development, development_view) = new_timeline_lane(
+        "Development",
+        egui::Rect::from_x_y_ranges(0.0..=date_to_x(date("2026-04-30")), 20.0..=140.0),
+        vec![design.into(), implementation.into()],
+        vec![design_view.into(), implementation_view.into()],
+    );
+
+    let (beta, beta_view) = new_timeline_milestone("Beta", date("2026-03-02"), 190.0);
+    let (release, release_view) = new_timeline_milestone("Release 1.0", date("2026-04-01"), 190.0);
+    let (marketing, marketing_view) =
+        new_timeline_bar("Marketing", date("2026-03-02"), date("2026-04-10"), 220.0);
+    let (delivery, delivery_view) = new_timeline_lane(
+        "Delivery",
+        egui::Rect::from_x_y_ranges(0.0..=date_to_x(date("2026-04-30")), 160.0..=280.0),
+        vec![beta.into(), release.into(), marketing.into()],
+        vec![beta_view.into(), release_view.into(), marketing_view.into()],
+    );
+
+    let name = format!("Demo Timeline diagram {}", no);
+    let diagram = ERef::new(TimelineDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![development.into(), delivery.into()],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![development_view.into(), delivery_view.into()],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        TimelineDomain,
+        TimelineControllerAdapter,
+        DiagramControllerGen2<TimelineDomain, TimelineDiagramAdapter>,
+    >>(&uuid)?)
+}
+
+pub struct TimelineSettings {
+    palette: RwLock<ToolPalette<TimelineToolStage, TimelineDomain>>,
+    palette_edit_buffer: RwLock<PaletteEditBuffer<TimelineToolStage, TimelineElementView>>,
+}
+impl DiagramSettings for TimelineSettings {
+    fn show(
+        &mut self,
+        gdc: &mut GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        shortcut_being_set: &Option<SetShortcut>,
+    ) -> ShowSettingsResult {
+        let mut w = self.palette.write().unwrap();
+        let mut buffer = self.palette_edit_buffer.write().unwrap();
+        let mut ret = ShowSettingsResult::None;
+
+        ui.columns(2, |columns| {
+            w.show_treeview(gdc, &mut columns[0]);
+
+            let selected = w.get_selected();
+            if selected.uuid() != buffer.uuid() {
+                *buffer = w.get_buffer(selected.uuid().cloned());
+            }
+            match &mut *buffer {
+                PaletteEditBuffer::None => {}
+                PaletteEditBuffer::Group(_uuid, name) => {
+                    if columns[1]
+                        .labeled_text_edit_singleline("Label", name)
+                        .changed()
+                    {
+                        w.set_from_buffer(buffer.clone());
+                    }
+                }
+                PaletteEditBuffer::Tool(uuid, name, tool, view, ksc) => {
+                    let mut modified = false;
+                    modified |= columns[1]
+                        .labeled_text_edit_singleline("Label", name)
+                        .changed();
+
+                    match crate::common::controller::show_shortcut(
+                        &mut columns[1],
+                        ksc,
+                        shortcut_being_set
+                            .as_ref()
+                            .is_some_and(|e| e.is_diagram(uuid)),
+                    ) {
+                        crate::common::controller::ShortCutStatus::NoChange => {}
+                        crate::common::controller::ShortCutStatus::Cleared => modified = true,
+                        crate::common::controller::ShortCutStatus::Set => {
+                            ret = ShowSettingsResult::SetShortcut(*uuid);
+                        }
+                        crate::common::controller::ShortCutStatus::CancelSet => {
+                            ret = ShowSettingsResult::CancelShortcutSetting;
+                        }
+                    }
+
+                    match tool {
+                        TimelineToolStage::Bar { name }
+                        | TimelineToolStage::Milestone { name }
+                        | TimelineToolStage::LaneStart { name } => {
+                            modified |= columns[1]
+                                .labeled_text_edit_singleline("Name", name)
+                                .changed();
+                        }
+                        TimelineToolStage::LaneEnd => unreachable!(),
+                    }
+
+                    if modified {
+                        *view = view_for_stage(tool);
+                        w.set_from_buffer(buffer.clone());
+                    }
+                }
+            }
+        });
+
+        ret
+    }
+
+    fn try_set_shortcut(&mut self, tool: uuid::Uuid, shortcut: egui::KeyboardShortcut) {
+        let mut wp = self.palette.write().unwrap();
+        wp.set_shortcut(tool, Some(shortcut));
+        let mut wb = self.palette_edit_buffer.write().unwrap();
+        *wb = wp.get_buffer(wb.uuid().cloned());
+    }
+
+    fn serialize(&self) -> Result<toml::Value, ()> {
+        let mut table = toml::Table::new();
+        table.insert(
+            "palette".to_owned(),
+            self.palette.read().unwrap().serialize()?,
+        );
+        Ok(table.into())
+    }
+}
+impl DiagramSettings2<TimelineDomain> for TimelineSettings {
+    fn palette_for_each_mut<F>(&self, f: F)
+    where
+        F: FnMut(
+            &mut (
+                uuid::Uuid,
+                String,
+                Vec<(
+                    uuid::Uuid,
+                    TimelineToolStage,
+                    String,
+                    TimelineElementView,
+                    Option<egui::KeyboardShortcut>,
+                )>,
+            ),
+        ),
+    {
+        self.palette.write().unwrap().for_each_mut(f);
+    }
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let palette_items = vec![
+        (
+            "Elements",
+            vec![
+                (
+                    TimelineToolStage::Bar {
+                        name: "Activity".to_owned(),
+                    },
+                    "Bar",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num1,
+                    )),
+                ),
+                (
+                    TimelineToolStage::Milestone {
+                        name: "Milestone".to_owned(),
+                    },
+                    "Milestone",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num2,
+                    )),
+                ),
+            ],
+        ),
+        (
+            "Other",
+            vec![(
+                TimelineToolStage::LaneStart {
+                    name: "Lane".to_owned(),
+                },
+                "Lane",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Num3,
+                )),
+            )],
+        ),
+    ]
+    .into_iter()
+    .map(|e| {
+        (
+            e.0,
+            e.1.into_iter()
+                .map(|e| {
+                    let v = view_for_stage(&e.0);
+                    (e.0, e.1, v, e.2)
+                })
+                .collect(),
+        )
+    })
+    .collect();
+
+    Box::new(TimelineSettings {
+        palette: RwLock::new(ToolPalette::new(palette_items)),
+        palette_edit_buffer: RwLock::new(PaletteEditBuffer::None),
+    })
+}
+
+fn view_for_stage(s: &TimelineToolStage) -> TimelineElementView {
+    match s {
+        TimelineToolStage::Bar { name } => {
+            let bar_view = new_timeline_bar(
+                name,
+                AXIS_ORIGIN,
+                AXIS_ORIGIN.add_days(DEFAULT_BAR_DAYS - 1),
+                0.0,
+            )
+            .1;
+            bar_view.into()
+        }
+        TimelineToolStage::Milestone { name } => {
+            let milestone_view = new_timeline_milestone(name, AXIS_ORIGIN, 0.0).1;
+            milestone_view.into()
+        }
+        TimelineToolStage::LaneStart { name } => {
+            let lane_view = new_timeline_lane(
+                name,
+                egui::Rect {
+                    min: egui::Pos2::ZERO,
+                    max: egui::Pos2::new(150.0, 50.0),
+                },
+                Vec::new(),
+                Vec::new(),
+            )
+            .1;
+            lane_view.into()
+        }
+        TimelineToolStage::LaneEnd => unreachable!(),
+    }
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    let toml::Value::Table(value) = value else {
+        return Err(());
+    };
+    Ok(Box::new(TimelineSettings {
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
+            .into(),
+        palette_edit_buffer: PaletteEditBuffer::None.into(),
+    }))
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "timeline",
+    pretty_name: "Timeline",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Timeline / roadmap diagram",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TimelineToolStage {
+    Bar { name: String },
+    Milestone { name: String },
+    LaneStart { name: String },
+    LaneEnd,
+}
+
+enum PartialTimelineElement {
+    None,
+    Some(TimelineElementView),
+    Lane {
+        name: String,
+        a: egui::Pos2,
+        b: Option<egui::Pos2>,
+    },
+}
+
+pub struct NaiveTimelineTool {
+    uuid: uuid::Uuid,
+    initial_stage: TimelineToolStage,
+    current_stage: TimelineToolStage,
+    result: PartialTimelineElement,
+    event_lock: bool,
+    is_spent: Option<bool>,
+}
+
+impl NaiveTimelineTool {
+    fn try_spend(&mut self) {
+        self.result = PartialTimelineElement::None;
+        self.is_spent = self.is_spent.map(|_| true);
+    }
+}
+
+const TARGETTABLE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(0, 255, 0, 31);
+const NON_TARGETTABLE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(255, 0, 0, 31);
+
+impl Tool<TimelineDomain> for NaiveTimelineTool {
+    type Stage = TimelineToolStage;
+
+    fn new(uuid: uuid::Uuid, initial_stage: TimelineToolStage, repeat: bool) -> Self {
+        Self {
+            uuid,
+            current_stage: initial_stage.clone(),
+            initial_stage,
+            result: PartialTimelineElement::None,
+            event_lock: false,
+            is_spent: if repeat { None } else { Some(false) },
+        }
+    }
+    fn initial_stage_uuid(&self) -> &uuid::Uuid {
+        &self.uuid
+    }
+    fn repeats(&self) -> bool {
+        self.is_spent.is_none()
+    }
+    fn is_spent(&self) -> bool {
+        self.is_spent.is_some_and(|e| e)
+    }
+
+    fn targetting_for_section(&self, element: Option<TimelineElement>) -> egui::Color32 {
+        match element {
+            None => TARGETTABLE_COLOR,
+            Some(TimelineElement::Lane(..)) => match self.current_stage {
+                TimelineToolStage::Bar { .. } | TimelineToolStage::Milestone { .. } => {
+                    TARGETTABLE_COLOR
+                }
+                TimelineToolStage::LaneStart { .. } | TimelineToolStage::LaneEnd => {
+                    NON_TARGETTABLE_COLOR
+                }
+            },
+            Some(TimelineElement::Bar(..) | TimelineElement::Milestone(..)) => {
+                NON_TARGETTABLE_COLOR
+            }
+        }
+    }
+    fn draw_status_hint(
+        &self,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        canvas: &mut dyn NHCanvas,
+        pos: egui::Pos2,
+    ) {
+        if let PartialTimelineElement::Lane { a, .. } = &self.result {
+            canvas.draw_rectangle(
+                egui::Rect::from_two_pos(*a, pos),
+                egui::CornerRadius::ZERO,
+                egui::Color32::TRANSPARENT,
+                canvas::Stroke::new_dashed(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+        }
+    }
+
+    fn add_position(&mut self, pos: egui::Pos2) {
+        if self.event_lock {
+            return;
+        }
+
+        match (&self.current_stage, &mut self.result) {
+            (TimelineToolStage::Bar { name }, _) => {
+                let start = x_to_date(pos.x);
+                let (_bar, bar_view) = new_timeline_bar(
+                    name,
+                    start,
+                    start.add_days(DEFAULT_BAR_DAYS - 1),
+                    pos.y - BAR_HEIGHT / 2.0,
+                );
+                self.result = PartialTimelineElement::Some(bar_view.into());
+                self.event_lock = true;
+            }
+            (TimelineToolStage::Milestone { name }, _) => {
+                let (_milestone, milestone_view) =
+                    new_timeline_milestone(name, x_to_date(pos.x), pos.y);
+                self.result = PartialTimelineElement::Some(milestone_view.into());
+                self.event_lock = true;
+            }
+            (TimelineToolStage::LaneStart { name }, _) => {
+                self.result = PartialTimelineElement::Lane {
+                    name: name.clone(),
+                    a: pos,
+                    b: None,
+                };
+                self.current_stage = TimelineToolStage::LaneEnd;
+                self.event_lock = true;
+            }
+            (TimelineToolStage::LaneEnd, PartialTimelineElement::Lane { b, .. }) => *b = Some(pos),
+            _ => {}
+        }
+    }
+    fn add_section(&mut self, _controller: TimelineElement) {}
+
+    fn try_flush(
+        &mut self,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        preferred_container: &ViewUuid,
+        preferred_bucket: BucketNoT,
+        preferred_position: Option<PositionNoT>,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                <TimelineDomain as Domain>::OrdinalMovementT,
+                <TimelineDomain as Domain>::AddCommandElementT,
+                <TimelineDomain as Domain>::PropChangeT,
+            >,
+        >,
+    ) -> Result<Option<Box<dyn CustomModal>>, ()> {
+        match &self.result {
+            PartialTimelineElement::Some(element) => {
+                let element = element.clone();
+                let esm: Option<Box<dyn CustomModal>> = match &element {
+                    TimelineElementView::Bar(inner) => {
+                        Some(Box::new(TimelineNameSetupModal::from(
+                            TimelineElement::from(inner.read().model.clone()),
+                        )))
+                    }
+                    TimelineElementView::Milestone(inner) => {
+                        Some(Box::new(TimelineNameSetupModal::from(
+                            TimelineElement::from(inner.read().model.clone()),
+                        )))
+                    }
+                    TimelineElementView::Lane(..) => unreachable!(),
+                };
+                self.try_spend();
+                commands.push(InsensitiveCommand::AddDependency {
+                    target: *preferred_container,
+                    bucket: preferred_bucket,
+                    position: preferred_position,
+                    element: element.into(),
+                    into_model: true,
+                });
+                Ok(esm)
+            }
+            PartialTimelineElement::Lane {
+                name,
+                a,
+                b: Some(b),
+                ..
+            } => {
+                self.current_stage = self.initial_stage.clone();
+
+                let (lane_model, lane_view) = new_timeline_lane(
+                    name,
+                    egui::Rect::from_two_pos(*a, *b),
+                    Vec::new(),
+                    Vec::new(),
+                );
+
+                self.try_spend();
+                commands.push(InsensitiveCommand::AddDependency {
+                    target: *preferred_container,
+                    bucket: preferred_bucket,
+                    position: preferred_position,
+                    element: TimelineElementView::from(lane_view).into(),
+                    into_model: true,
+                });
+                Ok(Some(Box::new(TimelineNameSetupModal::from(
+                    TimelineElement::from(lane_model),
+                ))))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn reset_event_lock(&mut self) {
+        self.event_lock = false;
+    }
+}
+
+struct TimelineNameSetupModal {
+    model: TimelineElement,
+    first_frame: bool,
+    name_buffer: String,
+}
+
+impl From<TimelineElement> for TimelineNameSetupModal {
+    fn from(model: TimelineElement) -> Self {
+        let name_buffer = match &model {
+            TimelineElement::Lane(eref) => (*eref.read().name).clone(),
+            TimelineElement::Bar(eref) => (*eref.read().name).clone(),
+            TimelineElement::Milestone(eref) => (*eref.read().name).clone(),
+        };
+        Self {
+            model,
+            first_frame: true,
+            name_buffer,
+        }
+    }
+}
+
+impl CustomModal for TimelineNameSetupModal {
+    fn show(
+        &mut self,
+        gdc: &mut GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) -> CustomModalResult {
+        ui.label("Name:");
+        let r = ui.text_edit_singleline(&mut self.name_buffer);
+        ui.separator();
+
+        if self.first_frame {
+            r.request_focus();
+            self.first_frame = false;
+        }
+
+        let mut result = CustomModalResult::KeepOpen;
+        ui.horizontal(|ui| {
+            if ui.button(gdc.translate_0("nh-generic-ok")).clicked() {
+                let name = Arc::new(self.name_buffer.clone());
+                match &self.model {
+                    TimelineElement::Lane(inner) => inner.write().name = name,
+                    TimelineElement::Bar(inner) => inner.write().name = name,
+                    TimelineElement::Milestone(inner) => inner.write().name = name,
+                }
+                result = CustomModalResult::CloseModified(*self.model.uuid());
+            }
+            if ui.button(gdc.translate_0("nh-generic-cancel")).clicked() {
+                result = CustomModalResult::CloseUnmodified;
+            }
+        });
+
+        result
+    }
+}
+
+fn new_timeline_lane(
+    name: &str,
+    bounds_rect: egui::Rect,
+    contained_models: Vec<TimelineElement>,
+    contained_views: Vec<TimelineElementView>,
+) -> (ERef<TimelineLane>, ERef<PackageViewT>) {
+    let lane_model = ERef::new(TimelineLane::new(
+        ModelUuid::now_v7(),
+        name.to_owned(),
+        contained_models,
+    ));
+    let lane_view = PackageView::new(
+        ViewUuid::now_v7().into(),
+        new_timeline_lane_adapter(lane_model.clone()),
+        contained_views,
+        bounds_rect,
+    );
+
+    (lane_model, lane_view)
+}
+fn new_timeline_lane_view(
+    model: ERef<TimelineLane>,
+    bounds_rect: egui::Rect,
+) -> ERef<PackageViewT> {
+    PackageView::new(
+        ViewUuid::now_v7().into(),
+        new_timeline_lane_adapter(model),
+        Vec::new(),
+        bounds_rect,
+    )
+}
+fn new_timeline_lane_adapter(model: ERef<TimelineLane>) -> TimelineLaneAdapter {
+    let m = model.read();
+    TimelineLaneAdapter {
+        model: model.clone(),
+        background_color: MGlobalColor::None,
+        name_buffer: (*m.name).clone(),
+        comment_buffer: (*m.comment).clone(),
+    }
+}
+
+#[derive(
+    Clone, serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize,
+)]
+pub struct TimelineLaneAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<TimelineLane>,
+    background_color: MGlobalColor,
+
+    #[nh_context_serde(skip_and_default)]
+    name_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_buffer: String,
+}
+
+impl PackageAdapter<TimelineDomain> for TimelineLaneAdapter {
+    fn model_section(&self) -> TimelineElement {
+        self.model.clone().into()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid.clone()
+    }
+    fn model_name(&self) -> Arc<String> {
+        self.model.read().name.clone()
+    }
+
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        self.model.read().get_element_pos(uuid)
+    }
+    fn insert_element(
+        &mut self,
+        position: Option<PositionNoT>,
+        element: TimelineElement,
+    ) -> Result<PositionNoT, ()> {
+        self.model
+            .write()
+            .insert_element(0, position, element)
+            .map_err(|_| ())
+    }
+    fn delete_element(&mut self, uuid: &ModelUuid) -> Option<PositionNoT> {
+        self.model.write().remove_element(uuid).map(|e| e.1)
+    }
+
+    fn background_color(&self, global_colors: &ColorBundle) -> egui::Color32 {
+        global_colors
+            .get(&self.background_color)
+            .unwrap_or(egui::Color32::WHITE)
+    }
+    fn draw_label_or_get_text(
+        &self,
+        bounds_rect: egui::Rect,
+        _highlight: canvas::Highlight,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        context: &GlobalDrawingContext,
+        _settings: &TimelineSettings,
+        canvas: &mut dyn canvas::NHCanvas,
+        _tool: &Option<(egui::Pos2, &NaiveTimelineTool)>,
+    ) -> Result<egui::Rect, Arc<String>> {
+        const PADDING: f32 = 4.0;
+        let text_color = self.text_color(&context.global_colors);
+
+        // Draw month boundaries of the time axis
+        let mut month = x_to_date(bounds_rect.min.x).next_month_start();
+        loop {
+            let x = date_to_x(month);
+            if x >= bounds_rect.max.x {
+                break;
+            }
+            canvas.draw_line(
+                [
+                    egui::Pos2::new(x, bounds_rect.min.y),
+                    egui::Pos2::new(x, bounds_rect.max.y),
+                ],
+                canvas::Stroke::new_dashed(1.0, egui::Color32::GRAY),
+                canvas::Highlight::NONE,
+            );
+            let (y, m, _) = month.ymd();
+            canvas.draw_text(
+                egui::Pos2::new(x + PADDING, bounds_rect.max.y - PADDING),
+                egui::Align2::LEFT_BOTTOM,
+                &format!("{:04}-{:02}", y, m),
+                canvas::CLASS_BOTTOM_FONT_SIZE,
+                egui::Color32::GRAY,
+            );
+            month = month.next_month_start();
+        }
+
+        let name = self.model_name();
+        let r = canvas.measure_text(
+            bounds_rect.left_top() + egui::Vec2::splat(PADDING),
+            egui::Align2::LEFT_TOP,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        );
+        canvas.draw_text(
+            bounds_rect.left_top() + egui::Vec2::splat(PADDING),
+            egui::Align2::LEFT_TOP,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            text_color,
+        );
+        canvas.draw_header_text(
+            canvas::HeaderLocation::Vertical(bounds_rect.min.y..=bounds_rect.max.y),
+            &name,
+        );
+
+        Ok(r.expand(PADDING))
+    }
+
+    fn show_model_properties(
+        &mut self,
+        q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) {
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.name_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Name(Arc::new(self.name_buffer.clone())),
+            ));
+        }
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Comment(Arc::new(self.comment_buffer.clone())),
+            ));
+        }
+    }
+    fn show_color_property(
+        &mut self,
+        context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ColorChangeData> {
+        ui.label("Background color:");
+        crate::common::controller::mglobalcolor_edit_button(context, ui, &self.background_color)
+            .map(|e| (0, e).into())
+    }
+    fn apply_change(
+        &mut self,
+        view_uuid: &ViewUuid,
+        command: &InsensitiveCommand<
+            TimelineOrdinalMovement,
+            TimelineElementOrVertex,
+            TimelinePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) {
+        if let InsensitiveCommand::PropertyChange(_, property) = command {
+            let mut model = self.model.write();
+            match property {
+                TimelinePropChange::Name(name) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Name(model.name.clone()),
+                    ));
+                    model.name = name.clone();
+                }
+                TimelinePropChange::Comment(comment) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Comment(model.comment.clone()),
+                    ));
+                    model.comment = comment.clone();
+                }
+                TimelinePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        TimelinePropChange::Color(ColorChangeData {
+                            slot: 0,
+                            color: self.background_color,
+                        }),
+                    ));
+                    self.background_color = *color;
+                }
+                _ => {}
+            }
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.name_buffer = (*model.name).clone();
+        self.comment_buffer = (*model.comment).clone();
+    }
+
+    fn deep_copy_init(
+        &self,
+        new_uuid: ModelUuid,
+        m: &mut HashMap<ModelUuid, TimelineElement>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let old_model = self.model.read();
+
+        let model = if let Some(TimelineElement::Lane(m)) = m.get(&old_model.uuid) {
+            m.clone()
+        } else {
+            let modelish = old_model.clone_with(new_uuid);
+            m.insert(*old_model.uuid, modelish.clone().into());
+            modelish
+        };
+        Self {
+            model,
+            background_color: self.background_color,
+            name_buffer: self.name_buffer.clone(),
+            comment_buffer: self.comment_buffer.clone(),
+        }
+    }
+
+    fn deep_copy_finish(&mut self, m: &HashMap<ModelUuid, TimelineElement>) {
+        let mut w = self.model.write();
+        for e in w.contained_elements.iter_mut() {
+            if let Some(new_model) = m.get(&*e.uuid()) {
+                *e = new_model.clone();
+            }
+        }
+    }
+}
+
+fn new_timeline_bar(
+    name: &str,
+    start: TimelineDate,
+    end: TimelineDate,
+    y: f32,
+) -> (ERef<TimelineBar>, ERef<TimelineBarView>) {
+    let bar_model = ERef::new(TimelineBar::new(
+        ModelUuid::now_v7(),
+        name.to_owned(),
+        start,
+        end,
+    ));
+    let bar_view = new_timeline_bar_view(bar_model.clone(), y);
+    (bar_model, bar_view)
+}
+fn new_timeline_bar_view(model: ERef<TimelineBar>, y: f32) -> ERef<TimelineBarView> {
+    let m = model.read();
+
+    ERef::new(TimelineBarView {
+        uuid: ViewUuid::now_v7().into(),
+        model: model.clone(),
+
+        name_buffer: (*m.name).clone(),
+        start_buffer: m.start.to_string(),
+        end_buffer: m.end.to_string(),
+        comment_buffer: (*m.comment).clone(),
+
+        dragged_shape: None,
+        highlight: canvas::Highlight::NONE,
+        position: egui::Pos2::new(date_to_x(m.start), y),
+        background_color: MGlobalColor::None,
+    })
+}
+
+/// Bar spanning the days between its start and end date (both inclusive).
+///
+/// The horizontal position of the view determines the start date, which is always
+/// rounded to the nearest day when drawn.
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct TimelineBarView {
+    uuid: Arc<ViewUuid>,
+    #[nh_context_serde(entity)]
+    pub model: ERef<TimelineBar>,
+
+    #[nh_context_serde(skip_and_default)]
+    name_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    start_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    end_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_buffer: String,
+
+    #[nh_context_serde(skip_and_default)]
+    dragged_shape: Option<NHShape>,
+    #[nh_context_serde(skip_and_default)]
+    highlight: canvas::Highlight,
+    pub position: egui::Pos2,
+    background_color: MGlobalColor,
+}
+
+impl TimelineBarView {
+    fn bounds_rect(&self) -> egui::Rect {
+        let m = self.model.read();
+        let end = m.end.max(m.start).add_days(1);
+        egui::Rect::from_min_max(
+            egui::Pos2::new(date_to_x(m.start), self.position.y),
+            egui::Pos2::new(date_to_x(end), self.position.y + BAR_HEIGHT),
+        )
+    }
+}
+
+impl Entity for TimelineBarView {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl View for TimelineBarView {
+    fn uuid(&self) -> Arc<ViewUuid> {
+        self.uuid.clone()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid.clone()
+    }
+}
+
+impl ElementController<TimelineElement> for TimelineBarView {
+    fn model(&self) -> TimelineElement {
+        self.model.clone().into()
+    }
+
+    fn min_shape(&self) -> NHShape {
+        NHShape::Rect {
+            inner: self.bounds_rect(),
+        }
+    }
+
+    fn position(&self) -> egui::Pos2 {
+        self.bounds_rect().center()
+    }
+}
+
+impl ElementControllerGen2<TimelineDomain> for TimelineBarView {
+    fn show_properties(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) -> PropertiesStatus<TimelineDomain> {
+        if !self.highlight.selected {
+            return PropertiesStatus::NotShown;
+        }
+
+        ui.label("Model properties");
+
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.name_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Name(Arc::new(self.name_buffer.clone())),
+            ));
+        }
+
+        let (start, end) = {
+            let m = self.model.read();
+            (m.start, m.end)
+        };
+        if ui
+            .labeled_text_edit_singleline("Start (YYYY-MM-DD):", &mut self.start_buffer)
+            .changed()
+            && let Ok(new_start) = self.start_buffer.parse::<TimelineDate>()
+            && new_start != start
+            && new_start <= end
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::StartDate(new_start),
+            ));
+        }
+        if ui
+            .labeled_text_edit_singleline("End (YYYY-MM-DD):", &mut self.end_buffer)
+            .changed()
+            && let Ok(new_end) = self.end_buffer.parse::<TimelineDate>()
+            && new_end != end
+            && new_end >= start
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::EndDate(new_end),
+            ));
+        }
+        ui.label(format!("Duration: {} days", start.days_until(&end) + 1));
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Comment(Arc::new(self.comment_buffer.clone())),
+            ));
+        }
+
+        ui.label("View properties");
+
+        ui.horizontal(|ui| {
+            let mut y = self.position.y;
+
+            ui.label("y");
+            if ui.add(egui::DragValue::new(&mut y).speed(1.0)).changed() {
+                commands.push(InsensitiveCommand::MovePositional(
+                    q.selected_views(),
+                    egui::Vec2::new(0.0, y - self.position.y),
+                ));
+            }
+        });
+
+        ui.label("Background color:");
+        if let Some(new_color) =
+            crate::common::controller::mglobalcolor_edit_button(gdc, ui, &self.background_color)
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Color((0, new_color).into()),
+            ));
+        }
+
+        PropertiesStatus::Shown
+    }
+    fn draw_in(
+        &mut self,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        gdc: &GlobalDrawingContext,
+        _settings: &TimelineSettings,
+        canvas: &mut dyn NHCanvas,
+        tool: &Option<(egui::Pos2, &NaiveTimelineTool)>,
+    ) -> TargettingStatus {
+        let bounds_rect = self.bounds_rect();
+        let (name, start) = {
+            let m = self.model.read();
+            (m.name.clone(), m.start)
+        };
+
+        canvas.draw_rectangle(
+            bounds_rect,
+            egui::CornerRadius::same(4),
+            gdc.global_colors
+                .get(&self.background_color)
+                .unwrap_or(egui::Color32::from_rgb(173, 216, 230)),
+            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+            self.highlight,
+        );
+        canvas.draw_text(
+            bounds_rect.left_center() + egui::Vec2::new(4.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            egui::Color32::BLACK,
+        );
+        canvas.draw_header_text(
+            canvas::HeaderLocation::Horizontal(bounds_rect.min.x..=bounds_rect.max.x),
+            &start.to_string(),
+        );
+
+        if canvas.ui_scale().is_some()
+            && let Some(t) = tool
+                .as_ref()
+                .filter(|e| self.min_shape().contains(e.0))
+                .map(|e| e.1)
+        {
+            canvas.draw_rectangle(
+                bounds_rect,
+                egui::CornerRadius::same(4),
+                t.targetting_for_section(Some(self.model())),
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            TargettingStatus::Drawn
+        } else {
+            TargettingStatus::NotDrawn
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        event: InputEvent,
+        _ehc: &EventHandlingContext,
+        _settings: &<TimelineDomain as Domain>::SettingsT,
+        q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        tool: &mut Option<NaiveTimelineTool>,
+        _element_setup_modal: &mut Option<Box<dyn CustomModal>>,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) -> EventHandlingStatus {
+        match event {
+            InputEvent::MouseDown(pos) => {
+                if !self.min_shape().contains(pos) {
+                    return EventHandlingStatus::NotHandled;
+                }
+                self.dragged_shape = Some(self.min_shape());
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::MouseUp(_) => {
+                if self.dragged_shape.is_some() {
+                    self.dragged_shape = None;
+                    EventHandlingStatus::HandledByElement
+                } else {
+                    EventHandlingStatus::NotHandled
+                }
+            }
+            InputEvent::Click(pos) if self.min_shape().contains(pos) => {
+                if let Some(tool) = tool {
+                    tool.add_section(self.model());
+                }
+
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::Drag { delta, .. } if self.dragged_shape.is_some() => {
+                let translated_real_shape = self.dragged_shape.unwrap().translate(delta);
+                self.dragged_shape = Some(translated_real_shape);
+
+                // Only whole days are allowed horizontally
+                let bounds_rect = self.bounds_rect();
+                let desired_left = translated_real_shape.center().x - bounds_rect.width() / 2.0;
+                let snapped_delta = egui::Vec2::new(
+                    date_to_x(x_to_date(desired_left)) - bounds_rect.min.x,
+                    translated_real_shape.center().y - bounds_rect.center().y,
+                );
+
+                if self.highlight.selected {
+                    commands.push(InsensitiveCommand::MovePositional(
+                        q.selected_views(),
+                        snapped_delta,
+                    ));
+                } else {
+                    commands.push(InsensitiveCommand::MovePositional(
+                        std::iter::once(*self.uuid).collect(),
+                        snapped_delta,
+                    ));
+                }
+                EventHandlingStatus::HandledByElement
+            }
+            _ => EventHandlingStatus::NotHandled,
+        }
+    }
+
+    fn apply_command(
+        &mut self,
+        command: &InsensitiveCommand<
+            TimelineOrdinalMovement,
+            TimelineElementOrVertex,
+            TimelinePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) {
+        match command {
+            InsensitiveCommand::HighlightAll(set, h) => {
+                self.highlight = self.highlight.combine(*set, *h);
+            }
+            InsensitiveCommand::HighlightSpecific(uuids, set, h) => {
+                if uuids.contains(&*self.uuid) {
+                    self.highlight = self.highlight.combine(*set, *h);
+                }
+            }
+            InsensitiveCommand::SelectByDrag(rect, retain) => {
+                self.highlight.selected = (self.highlight.selected && *retain)
+                    || self.min_shape().contained_within(*rect);
+            }
+            InsensitiveCommand::MovePositional(uuids, _) if !uuids.contains(&*self.uuid) => {}
+            InsensitiveCommand::MovePositional(_, delta)
+            | InsensitiveCommand::MovePositionalAll(delta) => {
+                self.position += *delta;
+                undo_accumulator.push(InsensitiveCommand::MovePositional(
+                    std::iter::once(*self.uuid).collect(),
+                    -*delta,
+                ));
+
+                let mut model = self.model.write();
+                let shift = model.start.days_until(&x_to_date(self.position.x));
+                if shift != 0 {
+                    affected_models.insert(*model.uuid);
+                    model.start = model.start.add_days(shift);
+                    model.end = model.end.add_days(shift);
+                }
+            }
+            InsensitiveCommand::ResizeElementsBy(..)
+            | InsensitiveCommand::ResizeElementTo(..)
+            | InsensitiveCommand::DeleteSpecificElements(..)
+            | InsensitiveCommand::AddDependency { .. }
+            | InsensitiveCommand::RemoveDependency { .. }
+            | InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
+            InsensitiveCommand::PropertyChange(uuids, property) => {
+                if uuids.contains(&*self.uuid) {
+                    affected_models.insert(*self.model.read().uuid);
+                    let mut model = self.model.write();
+                    match property {
+                        TimelinePropChange::Name(name) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Name(model.name.clone()),
+                            ));
+                            model.name = name.clone();
+                        }
+                        TimelinePropChange::StartDate(start) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::StartDate(model.start),
+                            ));
+                            model.start = *start;
+                            self.position.x = date_to_x(*start);
+                        }
+                        TimelinePropChange::EndDate(end) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::EndDate(model.end),
+                            ));
+                            model.end = *end;
+                        }
+                        TimelinePropChange::Comment(comment) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Comment(model.comment.clone()),
+                            ));
+                            model.comment = comment.clone();
+                        }
+                        TimelinePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Color(ColorChangeData {
+                                    slot: 0,
+                                    color: self.background_color,
+                                }),
+                            ));
+                            self.background_color = *color;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            InsensitiveCommand::Macro(..) => unreachable!(),
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.name_buffer = (*model.name).clone();
+        self.start_buffer = model.start.to_string();
+        self.end_buffer = model.end.to_string();
+        self.comment_buffer = (*model.comment).clone();
+    }
+
+    fn head_count(
+        &mut self,
+        _flattened_views: &mut HashMap<ViewUuid, (TimelineElementView, ViewUuid)>,
+        flattened_views_status: &mut HashMap<ViewUuid, SelectionStatus>,
+        flattened_represented_models: &mut HashMap<ModelUuid, ViewUuid>,
+    ) {
+        flattened_views_status.insert(*self.uuid(), self.highlight.selected.into());
+        flattened_represented_models.insert(*self.model_uuid(), *self.uuid);
+    }
+
+    fn deep_copy_clone(
+        &self,
+        uuid_present: &dyn Fn(&ViewUuid) -> bool,
+        tlc: &mut HashMap<ViewUuid, TimelineElementView>,
+        c: &mut HashMap<ViewUuid, TimelineElementView>,
+        m: &mut HashMap<ModelUuid, TimelineElement>,
+    ) {
+        let old_model = self.model.read();
+
+        let (view_uuid, model_uuid) = if uuid_present(&self.uuid) {
+            (ViewUuid::now_v7(), ModelUuid::now_v7())
+        } else {
+            (*self.uuid, *old_model.uuid)
+        };
+
+        let modelish = if let Some(TimelineElement::Bar(m)) = m.get(&old_model.uuid) {
+            m.clone()
+        } else {
+            let modelish = old_model.clone_with(model_uuid);
+            m.insert(*old_model.uuid, modelish.clone().into());
+            modelish
+        };
+
+        let cloneish = ERef::new(Self {
+            uuid: view_uuid.into(),
+            model: modelish,
+            name_buffer: self.name_buffer.clone(),
+            start_buffer: self.start_buffer.clone(),
+            end_buffer: self.end_buffer.clone(),
+            comment_buffer: self.comment_buffer.clone(),
+            dragged_shape: None,
+            highlight: self.highlight,
+            position: self.position,
+            background_color: self.background_color,
+        });
+        tlc.insert(view_uuid, cloneish.clone().into());
+        c.insert(*self.uuid, cloneish.clone().into());
+    }
+}
+
+fn new_timeline_milestone(
+    name: &str,
+    date: TimelineDate,
+    y: f32,
+) -> (ERef<TimelineMilestone>, ERef<TimelineMilestoneView>) {
+    let milestone_model = ERef::new(TimelineMilestone::new(
+        ModelUuid::now_v7(),
+        name.to_owned(),
+        date,
+    ));
+    let milestone_view = new_timeline_milestone_view(milestone_model.clone(), y);
+    (milestone_model, milestone_view)
+}
+fn new_timeline_milestone_view(
+    model: ERef<TimelineMilestone>,
+    y: f32,
+) -> ERef<TimelineMilestoneView> {
+    let m = model.read();
+
+    ERef::new(TimelineMilestoneView {
+        uuid: ViewUuid::now_v7().into(),
+        model: model.clone(),
+
+        name_buffer: (*m.name).clone(),
+        date_buffer: m.date.to_string(),
+        comment_buffer: (*m.comment).clone(),
+
+        dragged_shape: None,
+        highlight: canvas::Highlight::NONE,
+        position: egui::Pos2::new(date_to_x(m.date), y),
+    })
+}
+
+/// Diamond marking a single date, placed at the start of the day.
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct TimelineMilestoneView {
+    uuid: Arc<ViewUuid>,
+    #[nh_context_serde(entity)]
+    pub model: ERef<TimelineMilestone>,
+
+    #[nh_context_serde(skip_and_default)]
+    name_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    date_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_buffer: String,
+
+    #[nh_context_serde(skip_and_default)]
+    dragged_shape: Option<NHShape>,
+    #[nh_context_serde(skip_and_default)]
+    highlight: canvas::Highlight,
+    pub position: egui::Pos2,
+}
+
+impl TimelineMilestoneView {
+    fn snapped_position(&self) -> egui::Pos2 {
+        egui::Pos2::new(date_to_x(self.model.read().date), self.position.y)
+    }
+}
+
+impl Entity for TimelineMilestoneView {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl View for TimelineMilestoneView {
+    fn uuid(&self) -> Arc<ViewUuid> {
+        self.uuid.clone()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid.clone()
+    }
+}
+
+impl ElementController<TimelineElement> for TimelineMilestoneView {
+    fn model(&self) -> TimelineElement {
+        self.model.clone().into()
+    }
+
+    fn min_shape(&self) -> NHShape {
+        NHShape::Rhombus {
+            position: self.snapped_position(),
+            bounds_radius: egui::Vec2::splat(MILESTONE_RADIUS),
+        }
+    }
+
+    fn position(&self) -> egui::Pos2 {
+        self.snapped_position()
+    }
+}
+
+impl ElementControllerGen2<TimelineDomain> for TimelineMilestoneView {
+    fn show_properties(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) -> PropertiesStatus<TimelineDomain> {
+        if !self.highlight.selected {
+            return PropertiesStatus::NotShown;
+        }
+
+        ui.label("Model properties");
+
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.name_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Name(Arc::new(self.name_buffer.clone())),
+            ));
+        }
+
+        if ui
+            .labeled_text_edit_singleline("Date (YYYY-MM-DD):", &mut self.date_buffer)
+            .changed()
+            && let Ok(new_date) = self.date_buffer.parse::<TimelineDate>()
+            && new_date != self.model.read().date
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Date(new_date),
+            ));
+        }
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                TimelinePropChange::Comment(Arc::new(self.comment_buffer.clone())),
+            ));
+        }
+
+        ui.label("View properties");
+
+        ui.horizontal(|ui| {
+            let mut y = self.position.y;
+
+            ui.label("y");
+            if ui.add(egui::DragValue::new(&mut y).speed(1.0)).changed() {
+                commands.push(InsensitiveCommand::MovePositional(
+                    q.selected_views(),
+                    egui::Vec2::new(0.0, y - self.position.y),
+                ));
+            }
+        });
+
+        PropertiesStatus::Shown
+    }
+    fn draw_in(
+        &mut self,
+        _q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        _gdc: &GlobalDrawingContext,
+        _settings: &TimelineSettings,
+        canvas: &mut dyn NHCanvas,
+        tool: &Option<(egui::Pos2, &NaiveTimelineTool)>,
+    ) -> TargettingStatus {
+        let pos = self.snapped_position();
+        let (name, date) = {
+            let m = self.model.read();
+            (m.name.clone(), m.date)
+        };
+        let diamond = |r: f32| {
+            vec![
+                pos + egui::Vec2::new(0.0, -r),
+                pos + egui::Vec2::new(r, 0.0),
+                pos + egui::Vec2::new(0.0, r),
+                pos + egui::Vec2::new(-r, 0.0),
+            ]
+        };
+
+        canvas.draw_polygon(
+            diamond(MILESTONE_RADIUS),
+            egui::Color32::BLACK,
+            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+            self.highlight,
+        );
+        canvas.draw_text(
+            pos + egui::Vec2::new(MILESTONE_RADIUS + 4.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            egui::Color32::BLACK,
+        );
+        canvas.draw_header_text(
+            canvas::HeaderLocation::Horizontal(pos.x..=pos.x),
+            &date.to_string(),
+        );
+
+        if canvas.ui_scale().is_some()
+            && let Some(t) = tool
+                .as_ref()
+                .filter(|e| self.min_shape().contains(e.0))
+                .map(|e| e.1)
+        {
+            canvas.draw_polygon(
+                diamond(MILESTONE_RADIUS),
+                t.targetting_for_section(Some(self.model())),
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            TargettingStatus::Drawn
+        } else {
+            TargettingStatus::NotDrawn
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        event: InputEvent,
+        _ehc: &EventHandlingContext,
+        _settings: &<TimelineDomain as Domain>::SettingsT,
+        q: &<TimelineDomain as Domain>::QueryableT<'_>,
+        tool: &mut Option<NaiveTimelineTool>,
+        _element_setup_modal: &mut Option<Box<dyn CustomModal>>,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+    ) -> EventHandlingStatus {
+        match event {
+            InputEvent::MouseDown(pos) => {
+                if !self.min_shape().contains(pos) {
+                    return EventHandlingStatus::NotHandled;
+                }
+                self.dragged_shape = Some(self.min_shape());
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::MouseUp(_) => {
+                if self.dragged_shape.is_some() {
+                    self.dragged_shape = None;
+                    EventHandlingStatus::HandledByElement
+                } else {
+                    EventHandlingStatus::NotHandled
+                }
+            }
+            InputEvent::Click(pos) if self.min_shape().contains(pos) => {
+                if let Some(tool) = tool {
+                    tool.add_section(self.model());
+                }
+
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::Drag { delta, .. } if self.dragged_shape.is_some() => {
+                let translated_real_shape = self.dragged_shape.unwrap().translate(delta);
+                self.dragged_shape = Some(translated_real_shape);
+
+                // Only whole days are allowed horizontally
+                let desired = translated_real_shape.center();
+                let current = self.snapped_position();
+                let snapped_delta = egui::Vec2::new(
+                    date_to_x(x_to_date(desired.x)) - current.x,
+                    desired.y - current.y,
+                );
+
+                if self.highlight.selected {
+                    commands.push(InsensitiveCommand::MovePositional(
+                        q.selected_views(),
+                        snapped_delta,
+                    ));
+                } else {
+                    commands.push(InsensitiveCommand::MovePositional(
+                        std::iter::once(*self.uuid).collect(),
+                        snapped_delta,
+                    ));
+                }
+                EventHandlingStatus::HandledByElement
+            }
+            _ => EventHandlingStatus::NotHandled,
+        }
+    }
+
+    fn apply_command(
+        &mut self,
+        command: &InsensitiveCommand<
+            TimelineOrdinalMovement,
+            TimelineElementOrVertex,
+            TimelinePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                TimelineOrdinalMovement,
+                TimelineElementOrVertex,
+                TimelinePropChange,
+            >,
+        >,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) {
+        match command {
+            InsensitiveCommand::HighlightAll(set, h) => {
+                self.highlight = self.highlight.combine(*set, *h);
+            }
+            InsensitiveCommand::HighlightSpecific(uuids, set, h) => {
+                if uuids.contains(&*self.uuid) {
+                    self.highlight = self.highlight.combine(*set, *h);
+                }
+            }
+            InsensitiveCommand::SelectByDrag(rect, retain) => {
+                self.highlight.selected = (self.highlight.selected && *retain)
+                    || self.min_shape().contained_within(*rect);
+            }
+            InsensitiveCommand::MovePositional(uuids, _) if !uuids.contains(&*self.uuid) => {}
+            InsensitiveCommand::MovePositional(_, delta)
+            | InsensitiveCommand::MovePositionalAll(delta) => {
+                self.position += *delta;
+                undo_accumulator.push(InsensitiveCommand::MovePositional(
+                    std::iter::once(*self.uuid).collect(),
+                    -*delta,
+                ));
+
+                let mut model = self.model.write();
+                let date = x_to_date(self.position.x);
+                if model.date != date {
+                    affected_models.insert(*model.uuid);
+                    model.date = date;
+                }
+            }
+            InsensitiveCommand::ResizeElementsBy(..)
+            | InsensitiveCommand::ResizeElementTo(..)
+            | InsensitiveCommand::DeleteSpecificElements(..)
+            | InsensitiveCommand::AddDependency { .. }
+            | InsensitiveCommand::RemoveDependency { .. }
+            | InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
+            InsensitiveCommand::PropertyChange(uuids, property) => {
+                if uuids.contains(&*self.uuid) {
+                    affected_models.insert(*self.model.read().uuid);
+                    let mut model = self.model.write();
+                    match property {
+                        TimelinePropChange::Name(name) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Name(model.name.clone()),
+                            ));
+                            model.name = name.clone();
+                        }
+                        TimelinePropChange::Date(date) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Date(model.date),
+                            ));
+                            model.date = *date;
+                            self.position.x = date_to_x(*date);
+                        }
+                        TimelinePropChange::Comment(comment) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                TimelinePropChange::Comment(model.comment.clone()),
+                            ));
+                            model.comment = comment.clone();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            InsensitiveCommand::Macro(..) => unreachable!(),
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.name_buffer = (*model.name).clone();
+        self.date_buffer = model.date.to_string();
+        self.comment_buffer = (*model.comment).clone();
+    }
+
+    fn head_count(
+        &mut self,
+        _flattened_views: &mut HashMap<ViewUuid, (TimelineElementView, ViewUuid)>,
+        flattened_views_status: &mut HashMap<ViewUuid, SelectionStatus>,
+        flattened_represented_models: &mut HashMap<ModelUuid, ViewUuid>,
+    ) {
+        flattened_views_status.insert(*self.uuid(), self.highlight.selected.into());
+        flattened_represented_models.insert(*self.model_uuid(), *self.uuid);
+    }
+
+    fn deep_copy_clone(
+        &self,
+        uuid_present: &dyn Fn(&ViewUuid) -> bool,
+        tlc: &mut HashMap<ViewUuid, TimelineElementView>,
+        c: &mut HashMap<ViewUuid, TimelineElementView>,
+        m: &mut HashMap<ModelUuid, TimelineElement>,
+    ) {
+        let old_model = self.model.read();
+
+        let (view_uuid, model_uuid) = if uuid_present(&self.uuid) {
+            (ViewUuid::now_v7(), ModelUuid::now_v7())
+        } else {
+            (*self.uuid, *old_model.uuid)
+        };
+
+        let modelish = if let Some(TimelineElement::Milestone(m)) = m.get(&old_model.uuid) {
+            m.clone()
+        } else {
+            let modelish = old_model.clone_with(model_uuid);
+            m.insert(*old_model.uuid, modelish.clone().into());
+            modelish
+        };
+
+        let cloneish = ERef::new(Self {
+            uuid: view_uuid.into(),
+            model: modelish,
+            name_buffer: self.name_buffer.clone(),
+            date_buffer: self.date_buffer.clone(),
+            comment_buffer: self.comment_buffer.clone(),
+            dragged_shape: None,
+            highlight: self.highlight,
+            position: self.position,
+        });
+        tlc.insert(view_uuid, cloneish.clone().into());
+        c.insert(*self.uuid, cloneish.clone().into());
+    }
+}
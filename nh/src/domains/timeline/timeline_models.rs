@@ -0,0 +1,690 @@
+use crate::common::controller::{
+    BucketNoT, ContainerModel, DiagramVisitor, ElementVisitor, Model, PositionNoT,
+    VisitableDiagram, VisitableElement,
+};
+use crate::common::entity::{Entity, EntityUuid};
+use crate::common::eref::ERef;
+use crate::common::search::FullTextSearchable;
+use crate::common::uuid::ModelUuid;
+use std::collections::HashSet;
+use std::{collections::HashMap, sync::Arc};
+
+/// Calendar date, stored as the number of days since 1970-01-01
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimelineDate(i64);
+
+impl TimelineDate {
+    pub const fn from_day_number(day: i64) -> Self {
+        Self(day)
+    }
+    /// Returns None if the given day does not exist in the given month
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self(days_from_civil(year, month, day)))
+    }
+
+    pub fn ymd(&self) -> (i64, u32, u32) {
+        civil_from_days(self.0)
+    }
+    pub fn add_days(&self, days: i64) -> Self {
+        Self(self.0 + days)
+    }
+    pub fn days_until(&self, other: &Self) -> i64 {
+        other.0 - self.0
+    }
+    /// Returns the first day of the month following this date
+    pub fn next_month_start(&self) -> Self {
+        let (y, m, _) = self.ymd();
+        if m == 12 {
+            Self(days_from_civil(y + 1, 1, 1))
+        } else {
+            Self(days_from_civil(y, m + 1, 1))
+        }
+    }
+}
+
+impl std::fmt::Display for TimelineDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (y, m, d) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", y, m, d)
+    }
+}
+
+impl std::str::FromStr for TimelineDate {
+    type Err = ();
+
+    /// Parses dates in the `YYYY-MM-DD` format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or(());
+        let year = next()?.parse::<i64>().map_err(|_| ())?;
+        let month = next()?.parse::<u32>().map_err(|_| ())?;
+        let day = next()?.parse::<u32>().map_err(|_| ())?;
+        Self::from_ymd(year, month, day).ok_or(())
+    }
+}
+
+impl serde::Serialize for TimelineDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TimelineDate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid date: {}", s)))
+    }
+}
+
+// Conversions between the proleptic Gregorian calendar and day numbers,
+// see http://howardhinnant.github.io/date_algorithms.html
+const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    let next = if month == 12 {
+        days_from_civil(year + 1, 1, 1)
+    } else {
+        days_from_civil(year, month + 1, 1)
+    };
+    (next - days_from_civil(year, month, 1)) as u32
+}
+
+#[derive(
+    Clone,
+    derive_more::From,
+    nh_derive::Model,
+    nh_derive::ContainerModel,
+    nh_derive::FullTextSearchable,
+    nh_derive::NHContextSerDeTag,
+)]
+#[model(default_passthrough = "eref")]
+#[container_model(element_type = TimelineElement, default_passthrough = "none")]
+#[full_text_searchable(default_passthrough = "eref")]
+#[nh_context_serde(uuid_type = ModelUuid)]
+pub enum TimelineElement {
+    #[container_model(passthrough = "eref")]
+    Lane(ERef<TimelineLane>),
+    Bar(ERef<TimelineBar>),
+    Milestone(ERef<TimelineMilestone>),
+}
+
+impl VisitableElement for TimelineElement {
+    fn accept(&self, v: &mut dyn ElementVisitor<Self>)
+    where
+        Self: Sized,
+    {
+        match self {
+            TimelineElement::Lane(inner) => {
+                v.open_complex(self);
+                for e in &inner.read().contained_elements {
+                    e.accept(v);
+                }
+                v.close_complex(self);
+            }
+            e => v.visit_simple(e),
+        }
+    }
+}
+
+pub fn deep_copy_diagram(
+    d: &TimelineDiagram,
+) -> (ERef<TimelineDiagram>, HashMap<ModelUuid, TimelineElement>) {
+    fn walk(
+        e: &TimelineElement,
+        into: &mut HashMap<ModelUuid, TimelineElement>,
+    ) -> TimelineElement {
+        let new_uuid = ModelUuid::now_v7();
+        match e {
+            TimelineElement::Lane(inner) => {
+                let model = inner.read();
+
+                let new_model = TimelineLane {
+                    uuid: new_uuid.into(),
+                    name: model.name.clone(),
+                    contained_elements: model
+                        .contained_elements
+                        .iter()
+                        .map(|e| {
+                            let new_model = walk(e, into);
+                            into.insert(*e.uuid(), new_model.clone());
+                            new_model
+                        })
+                        .collect(),
+                    comment: model.comment.clone(),
+                };
+                TimelineElement::Lane(ERef::new(new_model))
+            }
+            TimelineElement::Bar(inner) => TimelineElement::Bar(inner.read().clone_with(new_uuid)),
+            TimelineElement::Milestone(inner) => {
+                TimelineElement::Milestone(inner.read().clone_with(new_uuid))
+            }
+        }
+    }
+
+    let mut all_models = HashMap::new();
+    let mut new_contained_elements = Vec::new();
+    for e in &d.contained_elements {
+        let new_model = walk(e, &mut all_models);
+        all_models.insert(*e.uuid(), new_model.clone());
+        new_contained_elements.push(new_model);
+    }
+
+    let new_diagram = TimelineDiagram {
+        uuid: ModelUuid::now_v7().into(),
+        name: d.name.clone(),
+        contained_elements: new_contained_elements,
+        comment: d.comment.clone(),
+    };
+    (ERef::new(new_diagram), all_models)
+}
+
+pub fn enumerate_diagram(d: &TimelineDiagram) -> HashMap<ModelUuid, TimelineElement> {
+    let mut all_models = HashMap::new();
+    for e in &d.contained_elements {
+        enumerate_elements(e, &mut all_models);
+    }
+    all_models
+}
+fn enumerate_elements(e: &TimelineElement, into: &mut HashMap<ModelUuid, TimelineElement>) {
+    into.insert(*e.uuid(), e.clone());
+    match e {
+        TimelineElement::Lane(inner) => {
+            for e in &inner.read().contained_elements {
+                enumerate_elements(e, into);
+            }
+        }
+        TimelineElement::Bar(..) | TimelineElement::Milestone(..) => {}
+    }
+}
+
+pub fn transitive_closure(
+    d: &TimelineDiagram,
+    mut when_deleting: HashSet<ModelUuid>,
+) -> HashSet<ModelUuid> {
+    fn walk(e: &TimelineElement, when_deleting: &mut HashSet<ModelUuid>) {
+        match e {
+            TimelineElement::Lane(inner) => {
+                let r = inner.read();
+                if when_deleting.contains(&r.uuid) {
+                    let mut c = Default::default();
+                    enumerate_elements(e, &mut c);
+                    when_deleting.extend(c.into_keys());
+                } else {
+                    for e in &r.contained_elements {
+                        walk(e, when_deleting);
+                    }
+                }
+            }
+            TimelineElement::Bar(..) | TimelineElement::Milestone(..) => {}
+        }
+    }
+    for e in &d.contained_elements {
+        walk(e, &mut when_deleting);
+    }
+
+    when_deleting
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity, is_subset_with = crate::common::project_serde::no_dependencies)]
+pub struct TimelineDiagram {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    #[nh_context_serde(entity)]
+    pub contained_elements: Vec<TimelineElement>,
+
+    pub comment: Arc<String>,
+}
+
+impl TimelineDiagram {
+    pub fn new(uuid: ModelUuid, name: String, contained_elements: Vec<TimelineElement>) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            contained_elements,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+
+    pub fn get_element_pos_in(
+        &self,
+        parent: &ModelUuid,
+        uuid: &ModelUuid,
+    ) -> Option<(BucketNoT, PositionNoT)> {
+        if *parent == *self.uuid {
+            self.get_element_pos(uuid)
+        } else {
+            self.find_element(parent)
+                .and_then(|e| e.0.get_element_pos(uuid))
+        }
+    }
+
+    pub fn insert_element_into(
+        &mut self,
+        parent: ModelUuid,
+        element: TimelineElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        if *self.uuid == parent {
+            self.insert_element(b, p, element)
+                .map(|_| ())
+                .map_err(|_| ())
+        } else {
+            self.find_element(&parent).ok_or(()).and_then(|mut e| {
+                e.0.insert_element(b, p, element)
+                    .map(|_| ())
+                    .map_err(|_| ())
+            })
+        }
+    }
+
+    pub fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, TimelineElement, BucketNoT, PositionNoT)>,
+    ) {
+        fn r(
+            e: &TimelineElement,
+            uuids: &HashSet<ModelUuid>,
+            undo: &mut Vec<(ModelUuid, TimelineElement, BucketNoT, PositionNoT)>,
+        ) {
+            match e {
+                TimelineElement::Lane(inner) => {
+                    let mut w = inner.write();
+                    for (idx, e) in w.contained_elements.iter().enumerate() {
+                        if uuids.contains(&e.uuid()) {
+                            undo.push((*w.uuid, e.clone(), 0, idx));
+                        } else {
+                            r(e, uuids, undo);
+                        }
+                    }
+                    w.contained_elements.retain(|e| !uuids.contains(&e.uuid()));
+                }
+                TimelineElement::Bar(_) | TimelineElement::Milestone(_) => {}
+            }
+        }
+
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if uuids.contains(&e.uuid()) {
+                undo.push((*self.uuid, e.clone(), 0, idx));
+            } else {
+                r(e, uuids, undo);
+            }
+        }
+        self.contained_elements
+            .retain(|e| !uuids.contains(&e.uuid()));
+    }
+}
+
+impl Entity for TimelineDiagram {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for TimelineDiagram {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl VisitableDiagram for TimelineDiagram {
+    fn accept(&self, v: &mut dyn DiagramVisitor<Self>) {
+        v.open_diagram(self);
+        for e in &self.contained_elements {
+            e.accept(v);
+        }
+        v.close_diagram(self);
+    }
+}
+
+impl ContainerModel for TimelineDiagram {
+    type ElementT = TimelineElement;
+
+    fn find_element(&self, uuid: &ModelUuid) -> Option<(TimelineElement, ModelUuid)> {
+        for e in &self.contained_elements {
+            if *e.uuid() == *uuid {
+                return Some((e.clone(), *self.uuid));
+            }
+            if let Some(e) = e.find_element(uuid) {
+                return Some(e);
+            }
+        }
+        None
+    }
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+    fn insert_element(
+        &mut self,
+        bucket: BucketNoT,
+        position: Option<PositionNoT>,
+        element: TimelineElement,
+    ) -> Result<PositionNoT, TimelineElement> {
+        if bucket != 0 {
+            return Err(element);
+        }
+
+        let pos = position.unwrap_or(self.contained_elements.len());
+        self.contained_elements.insert(pos, element);
+        Ok(pos)
+    }
+    fn remove_element(&mut self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                self.contained_elements.remove(idx);
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+}
+
+impl FullTextSearchable for TimelineDiagram {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[&self.uuid.to_string(), &self.name, &self.comment],
+        );
+
+        for e in &self.contained_elements {
+            e.full_text_search(acc);
+        }
+    }
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct TimelineLane {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    #[nh_context_serde(entity)]
+    pub contained_elements: Vec<TimelineElement>,
+
+    pub comment: Arc<String>,
+}
+
+impl TimelineLane {
+    pub fn new(uuid: ModelUuid, name: String, contained_elements: Vec<TimelineElement>) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            contained_elements,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+    pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
+        ERef::new(Self {
+            uuid: Arc::new(new_uuid),
+            name: self.name.clone(),
+            contained_elements: self.contained_elements.clone(),
+            comment: self.comment.clone(),
+        })
+    }
+}
+
+impl Entity for TimelineLane {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for TimelineLane {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl ContainerModel for TimelineLane {
+    type ElementT = TimelineElement;
+
+    fn find_element(&self, uuid: &ModelUuid) -> Option<(TimelineElement, ModelUuid)> {
+        for e in &self.contained_elements {
+            if *e.uuid() == *uuid {
+                return Some((e.clone(), *self.uuid));
+            }
+            if let Some(e) = e.find_element(uuid) {
+                return Some(e);
+            }
+        }
+        None
+    }
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+    fn insert_element(
+        &mut self,
+        bucket: BucketNoT,
+        position: Option<PositionNoT>,
+        element: TimelineElement,
+    ) -> Result<PositionNoT, TimelineElement> {
+        if bucket != 0 {
+            return Err(element);
+        }
+
+        let pos = position.unwrap_or(self.contained_elements.len());
+        self.contained_elements.insert(pos, element);
+        Ok(pos)
+    }
+    fn remove_element(&mut self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                self.contained_elements.remove(idx);
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+}
+
+impl FullTextSearchable for TimelineLane {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[&self.uuid.to_string(), &self.name, &self.comment],
+        );
+
+        for e in &self.contained_elements {
+            e.full_text_search(acc);
+        }
+    }
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct TimelineBar {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    /// First day covered by the bar
+    pub start: TimelineDate,
+    /// Last day covered by the bar
+    pub end: TimelineDate,
+
+    pub comment: Arc<String>,
+}
+
+impl TimelineBar {
+    pub fn new(uuid: ModelUuid, name: String, start: TimelineDate, end: TimelineDate) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            start,
+            end,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+    pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
+        ERef::new(Self {
+            uuid: Arc::new(new_uuid),
+            name: self.name.clone(),
+            start: self.start,
+            end: self.end,
+            comment: self.comment.clone(),
+        })
+    }
+}
+
+impl Entity for TimelineBar {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for TimelineBar {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl FullTextSearchable for TimelineBar {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[
+                &self.uuid.to_string(),
+                &self.name,
+                &self.start.to_string(),
+                &self.end.to_string(),
+                &self.comment,
+            ],
+        );
+    }
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct TimelineMilestone {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    pub date: TimelineDate,
+
+    pub comment: Arc<String>,
+}
+
+impl TimelineMilestone {
+    pub fn new(uuid: ModelUuid, name: String, date: TimelineDate) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            date,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+    pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
+        ERef::new(Self {
+            uuid: Arc::new(new_uuid),
+            name: self.name.clone(),
+            date: self.date,
+            comment: self.comment.clone(),
+        })
+    }
+}
+
+impl Entity for TimelineMilestone {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for TimelineMilestone {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl FullTextSearchable for TimelineMilestone {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[
+                &self.uuid.to_string(),
+                &self.name,
+                &self.date.to_string(),
+                &self.comment,
+            ],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimelineDate;
+
+    #[test]
+    fn date_roundtrip() {
+        for s in ["1970-01-01", "2000-02-29", "2024-12-31", "2026-03-01"] {
+            let d: TimelineDate = s.parse().unwrap();
+            assert_eq!(d.to_string(), s);
+        }
+        assert_eq!(
+            TimelineDate::from_ymd(1970, 1, 1),
+            Some(TimelineDate::from_day_number(0))
+        );
+        let feb28 = TimelineDate::from_ymd(2000, 2, 28).unwrap();
+        let mar1 = TimelineDate::from_ymd(2000, 3, 1).unwrap();
+        assert_eq!(feb28.days_until(&mar1), 2);
+    }
+
+    #[test]
+    fn invalid_dates_rejected() {
+        for s in [
+            "2023-02-29",
+            "2026-13-01",
+            "2026-04-31",
+            "2026-01",
+            "tomorrow",
+        ] {
+            assert!(s.parse::<TimelineDate>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn next_month_start() {
+        let d: TimelineDate = "2025-12-15".parse().unwrap();
+        assert_eq!(d.next_month_start().to_string(), "2026-01-01");
+        assert_eq!(
+            d.next_month_start().next_month_start().to_string(),
+            "2026-02-01"
+        );
+    }
+}
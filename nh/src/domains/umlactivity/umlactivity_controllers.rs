@@ -74,7 +74,7 @@ impl UmlActivityOrdinalMovement {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum UmlActivityPropChange {
     NameChange(Arc<String>),
     StereotypeChange(Arc<String>),
@@ -505,6 +505,9 @@ impl DiagramAdapter<UmlActivityDomain> for UmlActivityDiagramAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn frame_kind(&self) -> &'static str {
+        "act"
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -1704,7 +1707,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(UmlActivitySettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
         nonfinal_buttons: buttons::NONFINAL_BUTTONS.clone(),
@@ -3784,6 +3787,7 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityPartitionView {
             InsensitiveCommand::PropertyChange(..) => {
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
@@ -4240,8 +4244,10 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityPartitionSectionVie
             () => {{
                 let mut targetting_drawn = false;
                 self.contained_elements.draw_order_foreach_mut(|e| {
+                    canvas.begin_element(&e.uuid());
                     targetting_drawn |=
                         e.draw_in(q, context, settings, canvas, tool) != TargettingStatus::NotDrawn;
+                    canvas.end_element();
                 });
                 targetting_drawn
             }};
@@ -4352,7 +4358,7 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityPartitionSectionVie
             }
             InputEvent::Click(pos) if self.bounds_rect.contains(pos) => {
                 if let Some(tool) = tool {
-                    tool.add_position(*event.mouse_position());
+                    tool.add_position(ehc.snap_manager.snap_to_grid(*event.mouse_position()));
                     tool.add_section(self.model.clone().into());
 
                     if let Ok(esm) = tool.try_flush(q, &self.uuid, 0, None, commands)
@@ -4704,6 +4710,7 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityPartitionSectionVie
                 }
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
@@ -5425,7 +5432,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityActionNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -5839,7 +5848,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityInitialNodeView {
             InsensitiveCommand::AddDependency { .. } => {}
             InsensitiveCommand::RemoveDependency { .. } => {}
             InsensitiveCommand::ArrangeSpecificElements(..) => {}
-            InsensitiveCommand::MoveOrdinal(..) => {}
+            InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
@@ -6235,7 +6246,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityFinalNodeView {
             InsensitiveCommand::AddDependency { .. } => {}
             InsensitiveCommand::RemoveDependency { .. } => {}
             InsensitiveCommand::ArrangeSpecificElements(..) => {}
-            InsensitiveCommand::MoveOrdinal(..) => {}
+            InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -6659,7 +6672,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityDecisionNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -7065,7 +7080,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityForkNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -7528,7 +7545,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityObjectNodeView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -8047,6 +8066,12 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityCommentView {
             ));
         }
 
+        egui::CollapsingHeader::new("Preview")
+            .default_open(true)
+            .show(ui, |ui| {
+                crate::common::rich_text::show_markdown(ui, &self.text_buffer);
+            });
+
         ui.label("View properties");
 
         ui.horizontal(|ui| {
@@ -8134,18 +8159,18 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityCommentView {
                 egui::Align::Max => -Self::CORNER_SIZE,
             },
         };
-        self.bounds_rect = canvas
-            .measure_text(
-                self.position,
-                self.align,
-                &self.display_text,
-                canvas::CLASS_MIDDLE_FONT_SIZE,
-            )
-            .expand2(egui::Vec2 {
-                x: Self::CORNER_SIZE,
-                y: Self::CORNER_SIZE,
-            })
-            .translate(align_offset);
+        self.bounds_rect = crate::common::rich_text::measure_markdown(
+            canvas,
+            self.position,
+            self.align,
+            &self.display_text,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        )
+        .expand2(egui::Vec2 {
+            x: Self::CORNER_SIZE,
+            y: Self::CORNER_SIZE,
+        })
+        .translate(align_offset);
 
         canvas.draw_polygon(
             [
@@ -8194,7 +8219,8 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityCommentView {
             canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
             self.highlight,
         );
-        canvas.draw_text(
+        crate::common::rich_text::draw_markdown(
+            canvas,
             self.position + align_offset,
             self.align,
             &self.display_text,
@@ -8417,7 +8443,9 @@ impl ElementControllerGen2<UmlActivityDomain> for UmlActivityCommentView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
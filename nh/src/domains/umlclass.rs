@@ -1,3 +1,13 @@
+pub mod umlclass_board;
+pub mod umlclass_codegen;
 pub mod umlclass_controllers;
+pub mod umlclass_csv_export;
+pub mod umlclass_csv_import;
+pub mod umlclass_ecore;
+pub mod umlclass_json_schema;
+pub mod umlclass_mermaid;
 pub mod umlclass_models;
 pub mod umlclass_plantuml;
+pub mod umlclass_plantuml_import;
+pub mod umlclass_sql_ddl;
+pub mod umlclass_sql_import;
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        eref::ERef,
+        uuid::{ModelUuid, ViewUuid},
+    },
+    domains::umlclass::{
+        umlclass_controllers::UmlClassPropChange,
+        umlclass_models::{UmlClassDiagram, UmlClassElement},
+    },
+};
+
+/// Attribute of a class typed by an enumeration of the diagram, whose literals are the board columns
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BoardGrouping {
+    pub class: String,
+    pub attribute: String,
+    pub enumeration: String,
+}
+
+impl BoardGrouping {
+    fn label(&self) -> String {
+        format!("{}.{}: {}", self.class, self.attribute, self.enumeration)
+    }
+}
+
+pub struct BoardCard {
+    pub instance: ModelUuid,
+    pub name: String,
+    pub slots: Arc<String>,
+    pub value: String,
+}
+
+#[derive(Default)]
+struct Collected {
+    /// Attributes of classes, with their types
+    classes: Vec<(String, Vec<(String, String)>)>,
+    /// Literals of enumerations
+    enumerations: Vec<(String, Vec<String>)>,
+    /// Instances with their types
+    instances: Vec<(ModelUuid, String, String, Arc<String>)>,
+}
+
+fn collect(elements: &[UmlClassElement], c: &mut Collected) {
+    for e in elements {
+        match e {
+            UmlClassElement::Package(inner) => collect(&inner.read().contained_elements, c),
+            UmlClassElement::Class(inner) => {
+                let r = inner.read();
+                let members: Vec<(String, String)> = r
+                    .properties
+                    .iter()
+                    .map(|e| {
+                        let e = e.read();
+                        ((*e.name).clone(), (*e.value_type).clone())
+                    })
+                    .collect();
+                if r.stereotype.eq_ignore_ascii_case("enumeration") {
+                    c.enumerations.push((
+                        (*r.name).clone(),
+                        members.into_iter().map(|e| e.0).collect(),
+                    ));
+                } else {
+                    c.classes.push(((*r.name).clone(), members));
+                }
+            }
+            UmlClassElement::Instance(inner) => {
+                let r = inner.read();
+                c.instances.push((
+                    *r.uuid,
+                    format!("{}: {}", r.instance_name, r.instance_type),
+                    r.instance_type.trim().to_owned(),
+                    r.instance_slots.clone(),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Attributes of classes typed by an enumeration of the diagram
+fn collect_groupings(c: &Collected) -> Vec<BoardGrouping> {
+    c.classes
+        .iter()
+        .flat_map(|(class, attributes)| {
+            attributes.iter().filter_map(|(attribute, value_type)| {
+                let enumeration = c.enumerations.iter().find(|e| e.0 == value_type.trim())?;
+                Some(BoardGrouping {
+                    class: class.clone(),
+                    attribute: attribute.clone(),
+                    enumeration: enumeration.0.clone(),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Splits a slot line such as `status: Status = Done` into its name and value
+fn slot_parts(line: &str) -> (&str, Option<&str>) {
+    let (declaration, value) = match line.split_once('=') {
+        Some((d, v)) => (d, Some(v.trim())),
+        None => (line, None),
+    };
+    let name = declaration.split(':').next().unwrap_or_default().trim();
+    (name, value)
+}
+
+/// Finds the value of the slot, without the enumeration name qualifying a literal
+pub fn slot_value<'a>(slots: &'a str, slot: &str, enumeration: &str) -> Option<&'a str> {
+    let value = slots.lines().find_map(|l| match slot_parts(l) {
+        (name, value) if name == slot => value,
+        _ => None,
+    })?;
+    Some(
+        value
+            .strip_prefix(enumeration)
+            .and_then(|e| e.strip_prefix("::").or_else(|| e.strip_prefix('.')))
+            .unwrap_or(value),
+    )
+}
+
+/// Replaces the value of the slot, appending the slot if missing, or removes the slot if the value is empty
+pub fn set_slot_value(slots: &str, slot: &str, value: &str) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = slots
+        .lines()
+        .filter_map(|l| {
+            if found || slot_parts(l).0 != slot {
+                return Some(l.to_owned());
+            }
+            found = true;
+            let declaration = l.split_once('=').map(|e| e.0).unwrap_or(l);
+            (!value.is_empty()).then(|| format!("{} = {}", declaration.trim_end(), value))
+        })
+        .collect();
+    if !found && !value.is_empty() {
+        lines.push(format!("{} = {}", slot, value));
+    }
+    lines.join("\n")
+}
+
+fn collect_cards(c: &Collected, grouping: &BoardGrouping) -> Vec<BoardCard> {
+    c.instances
+        .iter()
+        .filter(|e| e.2 == grouping.class)
+        .map(|(uuid, name, _, slots)| BoardCard {
+            instance: *uuid,
+            name: name.clone(),
+            slots: slots.clone(),
+            value: slot_value(slots, &grouping.attribute, &grouping.enumeration)
+                .unwrap_or_default()
+                .to_owned(),
+        })
+        .collect()
+}
+
+/// Shows instances of a class as cards in columns by the literal of an enumeration-typed
+/// attribute, read from and written into the slots of the instances.
+///
+/// Only instances are shown, as other elements have no typed values.
+pub struct UmlClassBoardTab {
+    diagram: ERef<UmlClassDiagram>,
+    view_uuid: ViewUuid,
+    grouping: Option<BoardGrouping>,
+}
+
+impl UmlClassBoardTab {
+    pub fn new(diagram: ERef<UmlClassDiagram>, view_uuid: ViewUuid) -> Self {
+        Self {
+            diagram,
+            view_uuid,
+            grouping: None,
+        }
+    }
+}
+
+impl CustomTab for UmlClassBoardTab {
+    fn title(&self) -> String {
+        "Board".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        let mut collected = Collected::default();
+        collect(&self.diagram.read().contained_elements, &mut collected);
+        let groupings = collect_groupings(&collected);
+        self.grouping.take_if(|e| !groupings.contains(e));
+        if self.grouping.is_none() {
+            self.grouping = groupings.first().cloned();
+        }
+
+        egui::ComboBox::from_label("Group by")
+            .selected_text(
+                self.grouping
+                    .as_ref()
+                    .map(|e| e.label())
+                    .unwrap_or_default(),
+            )
+            .show_ui(ui, |ui| {
+                for e in &groupings {
+                    ui.selectable_value(&mut self.grouping, Some(e.clone()), e.label());
+                }
+            });
+        ui.separator();
+
+        let Some(grouping) = &self.grouping else {
+            ui.label("The diagram contains no class attributes typed by an «enumeration»");
+            return;
+        };
+
+        let cards = collect_cards(&collected, grouping);
+        let mut columns = vec![String::new()];
+        if let Some((_, literals)) = collected
+            .enumerations
+            .iter()
+            .find(|e| e.0 == grouping.enumeration)
+        {
+            columns.extend(literals.iter().cloned());
+        }
+        // Values which are not literals of the enumeration are kept visible
+        for e in &cards {
+            if !columns.contains(&e.value) {
+                columns.push(e.value.clone());
+            }
+        }
+
+        let mut dropped = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                for column in &columns {
+                    let frame = egui::Frame::group(ui.style()).inner_margin(4.0);
+                    let (_, payload) = ui.dnd_drop_zone::<usize, ()>(frame, |ui| {
+                        ui.set_width(160.0);
+                        ui.vertical(|ui| {
+                            ui.strong(if column.is_empty() {
+                                "(unset)"
+                            } else {
+                                column.as_str()
+                            });
+                            ui.separator();
+                            for (idx, card) in cards.iter().enumerate() {
+                                if card.value != *column {
+                                    continue;
+                                }
+                                let id = egui::Id::new(("nh-board-card", idx));
+                                ui.dnd_drag_source(id, idx, |ui| {
+                                    egui::Frame::new()
+                                        .fill(ui.visuals().faint_bg_color)
+                                        .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+                                        .inner_margin(6.0)
+                                        .show(ui, |ui| {
+                                            ui.set_width(ui.available_width());
+                                            ui.label(&card.name);
+                                        });
+                                });
+                            }
+                        });
+                    });
+                    if let Some(idx) = payload {
+                        dropped = Some((*idx, column.clone()));
+                    }
+                }
+            });
+        });
+
+        if let Some((idx, column)) = dropped
+            && let Some(card) = cards.get(idx)
+            && card.value != column
+        {
+            let slots = set_slot_value(&card.slots, &grouping.attribute, &column);
+            let change = UmlClassPropChange::InstanceSlots(Arc::new(slots));
+            if let Ok(change) = serde_json::to_string(&change) {
+                commands.push(ProjectCommand::ApplyPropertyChange(
+                    self.view_uuid,
+                    Some(vec![card.instance]),
+                    Arc::new(change),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_values_are_replaced_or_appended() {
+        assert_eq!(
+            slot_value(
+                "name = \"a\"\nstatus: Status = Status::Done",
+                "status",
+                "Status"
+            ),
+            Some("Done")
+        );
+        assert_eq!(slot_value("status", "status", "Status"), None);
+        assert_eq!(
+            set_slot_value("name = \"a\"\nstatus: Status = Todo", "status", "Done"),
+            "name = \"a\"\nstatus: Status = Done"
+        );
+        assert_eq!(set_slot_value("status", "status", "Done"), "status = Done");
+        assert_eq!(set_slot_value("", "status", "Done"), "status = Done");
+        assert_eq!(
+            set_slot_value("a = 1\nstatus = Done", "status", ""),
+            "a = 1"
+        );
+    }
+}
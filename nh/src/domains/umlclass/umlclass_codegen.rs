@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        eref::ERef,
+        naming::NamingConvention,
+        ufoption::UFOption,
+        uuid::ModelUuid,
+    },
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociation, UmlClassComment, UmlClassCommentLink, UmlClassDependency,
+        UmlClassDiagram, UmlClassGeneralization, UmlClassInstance, UmlClassPackage,
+        UmlClassVisibilityKind, UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+        multiplicity_bounds,
+    },
+};
+
+/// Templates of source files of one language.
+///
+/// Templates contain `{{value}}` placeholders, `{{#value}}..{{/value}}` sections shown
+/// when the value is not empty and `{{^value}}..{{/value}}` sections shown when it is.
+pub struct CodeTemplate {
+    pub language: &'static str,
+    pub extension: &'static str,
+    pub file_naming: NamingConvention,
+    pub type_naming: NamingConvention,
+    pub member_naming: NamingConvention,
+    /// One file per class, with values `name`, `abstract`, `supertypes`, `uses_many`,
+    /// `fields` and `methods`
+    pub class: &'static str,
+    /// Values `name`, `type`, `visibility` and `static`
+    pub field: &'static str,
+    /// Values `name`, `parameters`, `return_type`, `visibility`, `static` and `abstract`
+    pub method: &'static str,
+    /// Types of UML types, types without a mapping are used as they are
+    pub types: &'static [(&'static str, &'static str)],
+    /// Type of properties without one
+    pub any_type: &'static str,
+    /// Type of multi-valued properties, with value `type`
+    pub many: &'static str,
+    /// Type of optional properties, with value `type`
+    pub optional: &'static str,
+    /// Keywords of public, package, protected, private and unspecified visibility
+    pub visibilities: [&'static str; 5],
+}
+
+pub const CODE_TEMPLATES: [CodeTemplate; 3] = [
+    CodeTemplate {
+        language: "Rust",
+        extension: "rs",
+        file_naming: NamingConvention::SnakeCase,
+        type_naming: NamingConvention::PascalCase,
+        member_naming: NamingConvention::SnakeCase,
+        class: "{{#supertypes}}/// Specializes {{supertypes}}\n{{/supertypes}}pub struct {{name}} {\n{{fields}}}\n{{#methods}}\nimpl {{name}} {\n{{methods}}}\n{{/methods}}",
+        field: "    {{visibility}}{{name}}: {{type}},\n",
+        method: "    {{visibility}}fn {{name}}({{^static}}&self{{#parameters}}, {{/parameters}}{{/static}}{{parameters}}){{#return_type}} -> {{return_type}}{{/return_type}} {\n        todo!()\n    }\n",
+        types: &[
+            ("String", "String"),
+            ("Integer", "i64"),
+            ("int", "i32"),
+            ("Long", "i64"),
+            ("UnlimitedNatural", "u64"),
+            ("Real", "f64"),
+            ("Double", "f64"),
+            ("Float", "f32"),
+            ("Boolean", "bool"),
+        ],
+        any_type: "()",
+        many: "Vec<{{type}}>",
+        optional: "Option<{{type}}>",
+        visibilities: ["pub ", "pub(crate) ", "pub(crate) ", "", ""],
+    },
+    CodeTemplate {
+        language: "Java",
+        extension: "java",
+        file_naming: NamingConvention::PascalCase,
+        type_naming: NamingConvention::PascalCase,
+        member_naming: NamingConvention::CamelCase,
+        class: "{{#uses_many}}import java.util.List;\n\n{{/uses_many}}public {{#abstract}}abstract {{/abstract}}class {{name}}{{#supertypes}} extends {{supertypes}}{{/supertypes}} {\n{{fields}}{{#methods}}{{#fields}}\n{{/fields}}{{methods}}{{/methods}}}\n",
+        field: "    {{visibility}}{{#static}}static {{/static}}{{type}} {{name}};\n",
+        method: "    {{visibility}}{{#static}}static {{/static}}{{#abstract}}abstract {{/abstract}}{{return_type}}{{^return_type}}void{{/return_type}} {{name}}({{parameters}}){{#abstract}};{{/abstract}}{{^abstract}} {\n        throw new UnsupportedOperationException();\n    }{{/abstract}}\n",
+        types: &[
+            ("String", "String"),
+            ("Integer", "Integer"),
+            ("int", "int"),
+            ("Long", "Long"),
+            ("UnlimitedNatural", "Long"),
+            ("Real", "Double"),
+            ("Double", "Double"),
+            ("Float", "Float"),
+            ("Boolean", "Boolean"),
+            ("bool", "boolean"),
+        ],
+        any_type: "Object",
+        many: "List<{{type}}>",
+        optional: "{{type}}",
+        visibilities: ["public ", "", "protected ", "private ", ""],
+    },
+    CodeTemplate {
+        language: "TypeScript",
+        extension: "ts",
+        file_naming: NamingConvention::PascalCase,
+        type_naming: NamingConvention::PascalCase,
+        member_naming: NamingConvention::CamelCase,
+        class: "export {{#abstract}}abstract {{/abstract}}class {{name}}{{#supertypes}} extends {{supertypes}}{{/supertypes}} {\n{{fields}}{{#methods}}{{#fields}}\n{{/fields}}{{methods}}{{/methods}}}\n",
+        field: "    {{visibility}}{{#static}}static {{/static}}{{name}}: {{type}};\n",
+        method: "    {{visibility}}{{#static}}static {{/static}}{{#abstract}}abstract {{/abstract}}{{name}}({{parameters}}): {{return_type}}{{^return_type}}void{{/return_type}}{{#abstract}};{{/abstract}}{{^abstract}} {\n        throw new Error(\"Not implemented\");\n    }{{/abstract}}\n",
+        types: &[
+            ("String", "string"),
+            ("Integer", "number"),
+            ("int", "number"),
+            ("Long", "number"),
+            ("UnlimitedNatural", "number"),
+            ("Real", "number"),
+            ("Double", "number"),
+            ("Float", "number"),
+            ("Boolean", "boolean"),
+            ("bool", "boolean"),
+        ],
+        any_type: "unknown",
+        many: "{{type}}[]",
+        optional: "{{type}} | undefined",
+        visibilities: ["public ", "", "protected ", "private ", ""],
+    },
+];
+
+/// Expands placeholders and sections of the template, unknown values are empty
+fn render(template: &str, values: &HashMap<&str, String>) -> String {
+    let value = |key: &str| values.get(key).map(|e| e.as_str()).unwrap_or("");
+
+    let mut result = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}").map(|e| start + e) else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 2..end];
+        rest = &rest[end + 2..];
+
+        let (inverted, key) = match tag.as_bytes().first() {
+            Some(b'#') => (false, &tag[1..]),
+            Some(b'^') => (true, &tag[1..]),
+            _ => {
+                result.push_str(value(tag));
+                continue;
+            }
+        };
+        let closing = format!("{{{{/{}}}}}", key);
+        let (section, after) = rest.split_at(rest.find(&closing).unwrap_or(rest.len()));
+        if value(key).is_empty() == inverted {
+            result.push_str(&render(section, values));
+        }
+        rest = after.strip_prefix(closing.as_str()).unwrap_or(after);
+    }
+    result.push_str(rest);
+    result
+}
+
+struct FieldInfo {
+    name: String,
+    value_type: String,
+    multiplicity: String,
+    visibility: UFOption<UmlClassVisibilityKind>,
+    is_static: bool,
+}
+
+struct MethodInfo {
+    name: String,
+    parameters: String,
+    return_type: String,
+    visibility: UFOption<UmlClassVisibilityKind>,
+    is_static: bool,
+    is_abstract: bool,
+}
+
+struct ClassInfo {
+    uuid: ModelUuid,
+    name: String,
+    is_abstract: bool,
+    fields: Vec<FieldInfo>,
+    methods: Vec<MethodInfo>,
+}
+
+#[derive(Default)]
+struct CodeCollector {
+    classes: Vec<ClassInfo>,
+    supertypes: HashMap<ModelUuid, Vec<String>>,
+}
+
+impl UmlClassVisitor for CodeCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let fields = class
+            .properties
+            .iter()
+            .map(|e| e.read())
+            .filter(|e| !e.is_derived)
+            .map(|e| FieldInfo {
+                name: (*e.name).clone(),
+                value_type: (*e.value_type).clone(),
+                multiplicity: (*e.multiplicity).clone(),
+                visibility: e.visibility,
+                is_static: e.is_static,
+            })
+            .collect();
+        let methods = class
+            .operations
+            .iter()
+            .map(|e| {
+                let e = e.read();
+                MethodInfo {
+                    name: (*e.name).clone(),
+                    parameters: (*e.parameters).clone(),
+                    return_type: (*e.return_type).clone(),
+                    visibility: e.visibility,
+                    is_static: e.is_static,
+                    is_abstract: e.is_abstract,
+                }
+            })
+            .collect();
+        self.classes.push(ClassInfo {
+            uuid: *class.uuid,
+            name: (*class.name).clone(),
+            is_abstract: class.is_abstract,
+            fields,
+            methods,
+        });
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, generalization: &UmlClassGeneralization) {
+        for source in &generalization.sources {
+            self.supertypes
+                .entry(*source.read().uuid)
+                .or_default()
+                .extend(
+                    generalization
+                        .targets
+                        .iter()
+                        .map(|e| (*e.read().name).clone()),
+                );
+        }
+    }
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, _association: &UmlClassAssociation) {}
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+impl CodeTemplate {
+    fn visibility(&self, visibility: &UFOption<UmlClassVisibilityKind>) -> &'static str {
+        match visibility {
+            UFOption::Some(UmlClassVisibilityKind::Public) => self.visibilities[0],
+            UFOption::Some(UmlClassVisibilityKind::Package) => self.visibilities[1],
+            UFOption::Some(UmlClassVisibilityKind::Protected) => self.visibilities[2],
+            UFOption::Some(UmlClassVisibilityKind::Private) => self.visibilities[3],
+            UFOption::None => self.visibilities[4],
+        }
+    }
+
+    /// Maps the UML type, names of classes of the diagram follow the type naming
+    fn type_name(&self, uml_type: &str, class_names: &[&str]) -> String {
+        let uml_type = uml_type.trim();
+        if let Some((_, t)) = self.types.iter().find(|e| e.0 == uml_type) {
+            (*t).to_owned()
+        } else if class_names.contains(&uml_type) {
+            self.type_naming.name(&[uml_type])
+        } else {
+            uml_type.to_owned()
+        }
+    }
+
+    /// Source files with skeletons of the classes of the diagram, by file names
+    pub fn generate(&self, diagram: &UmlClassDiagram) -> Vec<(String, String)> {
+        let mut collector = CodeCollector::default();
+        for e in &diagram.contained_elements {
+            e.accept_uml(&mut collector);
+        }
+        let class_names: Vec<_> = collector.classes.iter().map(|e| e.name.as_str()).collect();
+        let flag = |b: bool| if b { "true" } else { "" }.to_owned();
+
+        let mut files = Vec::new();
+        for c in &collector.classes {
+            let mut uses_many = false;
+            let mut fields = String::new();
+            for f in &c.fields {
+                let (lower, many) = multiplicity_bounds(&f.multiplicity);
+                let mut value_type = if f.value_type.trim().is_empty() {
+                    self.any_type.to_owned()
+                } else {
+                    self.type_name(&f.value_type, &class_names)
+                };
+                if many {
+                    uses_many = true;
+                    value_type = render(self.many, &HashMap::from([("type", value_type)]));
+                } else if lower == 0 {
+                    value_type = render(self.optional, &HashMap::from([("type", value_type)]));
+                }
+                fields.push_str(&render(
+                    self.field,
+                    &HashMap::from([
+                        ("name", self.member_naming.name(&[&f.name])),
+                        ("type", value_type),
+                        ("visibility", self.visibility(&f.visibility).to_owned()),
+                        ("static", flag(f.is_static)),
+                    ]),
+                ));
+            }
+
+            let mut methods = String::new();
+            for m in &c.methods {
+                let return_type = if m.return_type.trim().is_empty() {
+                    String::new()
+                } else {
+                    self.type_name(&m.return_type, &class_names)
+                };
+                methods.push_str(&render(
+                    self.method,
+                    &HashMap::from([
+                        ("name", self.member_naming.name(&[&m.name])),
+                        ("parameters", m.parameters.trim().to_owned()),
+                        ("return_type", return_type),
+                        ("visibility", self.visibility(&m.visibility).to_owned()),
+                        ("static", flag(m.is_static)),
+                        ("abstract", flag(m.is_abstract)),
+                    ]),
+                ));
+            }
+
+            let supertypes = collector
+                .supertypes
+                .get(&c.uuid)
+                .into_iter()
+                .flatten()
+                .map(|e| self.type_naming.name(&[e]))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let source = render(
+                self.class,
+                &HashMap::from([
+                    ("name", self.type_naming.name(&[&c.name])),
+                    ("abstract", flag(c.is_abstract)),
+                    ("supertypes", supertypes),
+                    ("uses_many", flag(uses_many)),
+                    ("fields", fields),
+                    ("methods", methods),
+                ]),
+            );
+            files.push((
+                format!("{}.{}", self.file_naming.name(&[&c.name]), self.extension),
+                source,
+            ));
+        }
+        files
+    }
+}
+
+/// Lets the user choose a language, preview the generated files and write them into a folder
+pub struct CodeGenerationTab {
+    diagram: ERef<UmlClassDiagram>,
+    template: usize,
+    files: Vec<(String, String)>,
+    selected_file: usize,
+}
+
+impl CodeGenerationTab {
+    pub fn new(diagram: ERef<UmlClassDiagram>) -> Self {
+        let files = CODE_TEMPLATES[0].generate(&diagram.read());
+        Self {
+            diagram,
+            template: 0,
+            files,
+            selected_file: 0,
+        }
+    }
+}
+
+impl CustomTab for CodeGenerationTab {
+    fn title(&self) -> String {
+        "Code Generation".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            let mut modified = false;
+            egui::ComboBox::new("code template", "Language")
+                .selected_text(CODE_TEMPLATES[self.template].language)
+                .show_ui(ui, |ui| {
+                    for (idx, t) in CODE_TEMPLATES.iter().enumerate() {
+                        modified |= ui
+                            .selectable_value(&mut self.template, idx, t.language)
+                            .clicked();
+                    }
+                });
+            if ui.button("Refresh").clicked() || modified {
+                self.files = CODE_TEMPLATES[self.template].generate(&self.diagram.read());
+                self.selected_file = self.selected_file.min(self.files.len().saturating_sub(1));
+            }
+            if ui
+                .add_enabled(
+                    !self.files.is_empty(),
+                    egui::Button::new("Write to folder..."),
+                )
+                .clicked()
+            {
+                commands.push(ProjectCommand::WriteFiles(self.files.clone()));
+            }
+        });
+        ui.separator();
+
+        ui.horizontal_wrapped(|ui| {
+            for (idx, (name, _)) in self.files.iter().enumerate() {
+                ui.selectable_value(&mut self.selected_file, idx, name);
+            }
+        });
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if let Some((_, source)) = self.files.get(self.selected_file) {
+                ui.add_sized(
+                    (ui.available_width(), 20.0),
+                    egui::TextEdit::multiline(&mut source.as_str()).code_editor(),
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::umlclass::umlclass_models::{
+        UmlClassElement, UmlClassOperation, UmlClassProperty,
+    };
+
+    #[test]
+    fn templates_render_sections() {
+        let values = HashMap::from([("name", "x".to_owned()), ("empty", String::new())]);
+        assert_eq!(
+            render(
+                "a{{name}}{{#name}}[{{name}}]{{/name}}{{#empty}}no{{/empty}}{{^empty}}!{{/empty}}",
+                &values
+            ),
+            "ax[x]!"
+        );
+        assert_eq!(render("{{unknown}}{{unclosed", &values), "{{unclosed");
+    }
+
+    #[test]
+    fn class_skeletons_are_generated() {
+        let shape = ERef::new(UmlClass::new(
+            ModelUuid::now_v7(),
+            "shape".to_owned(),
+            String::new(),
+            String::new(),
+            true,
+            vec![],
+            vec![ERef::new(UmlClassOperation::new(
+                ModelUuid::now_v7(),
+                UFOption::Some(UmlClassVisibilityKind::Public),
+                "area".to_owned(),
+                String::new(),
+                "Real".to_owned(),
+                String::new(),
+            ))],
+        ));
+        let polygon = ERef::new(UmlClass::new(
+            ModelUuid::now_v7(),
+            "Polygon".to_owned(),
+            String::new(),
+            String::new(),
+            false,
+            vec![ERef::new(UmlClassProperty::new(
+                ModelUuid::now_v7(),
+                UFOption::Some(UmlClassVisibilityKind::Private),
+                "cornerPoints".to_owned(),
+                "Integer".to_owned(),
+                "3..*".to_owned(),
+                String::new(),
+                String::new(),
+            ))],
+            vec![],
+        ));
+        let generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            vec![polygon.clone()],
+            vec![shape.clone()],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Shapes".to_owned(),
+            vec![
+                UmlClassElement::Class(shape),
+                UmlClassElement::Class(polygon),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+            ],
+        );
+
+        let rust = CODE_TEMPLATES[0].generate(&diagram);
+        assert_eq!(rust[0].0, "shape.rs");
+        assert_eq!(
+            rust[0].1,
+            "pub struct Shape {\n}\n\nimpl Shape {\n    pub fn area(&self) -> f64 {\n        todo!()\n    }\n}\n"
+        );
+        assert_eq!(
+            rust[1].1,
+            "/// Specializes Shape\npub struct Polygon {\n    corner_points: Vec<i64>,\n}\n"
+        );
+
+        let java = CODE_TEMPLATES[1].generate(&diagram);
+        assert_eq!(java[1].0, "Polygon.java");
+        assert_eq!(
+            java[1].1,
+            "import java.util.List;\n\npublic class Polygon extends Shape {\n    private List<Integer> cornerPoints;\n}\n"
+        );
+
+        let typescript = CODE_TEMPLATES[2].generate(&diagram);
+        assert!(
+            typescript[0]
+                .1
+                .starts_with("export abstract class Shape {\n    public area(): number {\n")
+        );
+    }
+}
@@ -9,12 +9,14 @@ use crate::common::controller::{
     DiagramAdapter, DiagramController, DiagramControllerGen2, DiagramSettings, DiagramSettings2,
     Domain, ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
     GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, LabelProvider,
-    MGlobalColor, Model, MultiDiagramController, PaletteEditBuffer, PositionNoT, ProjectCommand,
-    PropertiesStatus, Queryable, SelectionStatus, ShowSettingsResult, SnapManager,
-    TargettingStatus, Tool, ToolPalette, TryMerge, View,
+    MGlobalColor, Model, MultiDiagramController, NAME_PATTERN_HINT, PaletteEditBuffer, PositionNoT,
+    ProjectCommand, PropertiesStatus, Queryable, SelectionStatus, ShowSettingsResult, SnapManager,
+    TargettingStatus, TextExportFormat, Tool, ToolPalette, TryMerge, View, expand_name_pattern,
 };
 use crate::common::entity::{Entity, EntityUuid};
 use crate::common::eref::ERef;
+use crate::common::fluent::{ContentTranslations, localized};
+use crate::common::math::render_math;
 use crate::common::project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer};
 use crate::common::ufoption::UFOption;
 use crate::common::ui_ext::UiExt;
@@ -24,16 +26,22 @@ use crate::common::views::multiconnection_view::{
     MULTICONNECTION_TARGET_BUCKET, MulticonnectionAdapter, MulticonnectionView, VertexInformation,
 };
 use crate::common::views::package_view::{PackageAdapter, PackageView};
+use crate::domains::umlclass::umlclass_csv_export::{CSV_ASSOCIATIONS_FORMAT, CSV_CLASSES_FORMAT};
+use crate::domains::umlclass::umlclass_ecore::ECORE_FORMAT;
+use crate::domains::umlclass::umlclass_json_schema::JSON_SCHEMA_FORMAT;
+use crate::domains::umlclass::umlclass_mermaid::MERMAID_FORMAT;
 use crate::domains::umlclass::umlclass_models::{
     UmlClassOperation, UmlClassPackageKind, UmlClassProperty, UmlClassVisibilityKind,
     UmlGeneralization, UmlUseCase, UmlUseCaseGeneralization,
 };
+use crate::domains::umlclass::umlclass_plantuml::PLANTUML_FORMAT;
+use crate::domains::umlclass::umlclass_sql_ddl::SQL_DDL_FORMAT;
 use crate::{
     CustomModal, CustomModalResult, CustomTab, DefaultSettingsF, DeserializeControllerF,
     DeserializeSettingsF, DiagramConstructorF, DiagramCreationData, DiagramInfo, SetShortcut,
 };
 use eframe::egui;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::marker::PhantomData;
 use std::{
     collections::HashMap,
@@ -65,6 +73,24 @@ pub trait UmlClassProfile: Default + Clone + Send + Sync + 'static {
     type PackageStereotypeController: StereotypeController = UnrestrictedStereotypeController;
     type CommentStereotypeController: StereotypeController = UnrestrictedStereotypeController;
 
+    /// Arrowhead at an association end, and whether its multiplicity is also shown as a label
+    fn association_ending(
+        _multiplicity: &str,
+        navigability: UmlClassAssociationNavigability,
+        aggregation: UmlClassAssociationAggregation,
+    ) -> (canvas::ArrowheadType, bool) {
+        let arrowhead = match aggregation {
+            UmlClassAssociationAggregation::None => match navigability {
+                UmlClassAssociationNavigability::Unspecified
+                | UmlClassAssociationNavigability::NonNavigable => canvas::ArrowheadType::None,
+                UmlClassAssociationNavigability::Navigable => canvas::ArrowheadType::OpenTriangle,
+            },
+            UmlClassAssociationAggregation::Shared => canvas::ArrowheadType::EmptyRhombus,
+            UmlClassAssociationAggregation::Composite => canvas::ArrowheadType::FullRhombus,
+        };
+        (arrowhead, true)
+    }
+
     fn menubar_options_fun(
         model: &ERef<UmlClassDiagram>,
         _view_uuid: &ViewUuid,
@@ -75,7 +101,16 @@ pub trait UmlClassProfile: Default + Clone + Send + Sync + 'static {
             let uuid = uuid::Uuid::now_v7();
             commands.push(ProjectCommand::AddCustomTab(
                 uuid,
-                Arc::new(RwLock::new(PlantUmlTab::new(model.clone()))),
+                Arc::new(RwLock::new(TextDescriptionTab::plantuml(model.clone()))),
+            ));
+        }
+        if ui.button("Import PlantUML").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::umlclass_plantuml_import::PlantUmlImportTab::default(),
+                )),
             ));
         }
         ui.separator();
@@ -84,6 +119,43 @@ pub trait UmlClassProfile: Default + Clone + Send + Sync + 'static {
     fn allows_class_rendering_as_stick_figure() -> bool {
         false
     }
+
+    /// Colors the views are drawn with, see [`DiagramAdapter::color_profile`]
+    fn color_profile(dark: bool) -> canvas::ColorProfile {
+        if dark {
+            canvas::ColorProfile::DARK
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
+
+    /// Names of additional compartments of classes with the given stereotype,
+    /// shown below the standard compartments
+    fn class_compartments(_stereotype: &str) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Line of dependencies with the given stereotype
+    fn dependency_line_type(_stereotype: &str) -> canvas::LineType {
+        canvas::LineType::Dashed
+    }
+
+    /// Outline of use cases with the given stereotype
+    fn usecase_outline(_stereotype: &str) -> UmlUseCaseOutline {
+        UmlUseCaseOutline::Ellipse
+    }
+
+    /// Stereotypes of the use case and of the association connecting it, when use cases
+    /// can be added as children of other use cases from the keyboard
+    fn child_usecase_stereotypes() -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    /// Elements created at once by the pattern palette item of the given name,
+    /// with the first one placed at `position`
+    fn pattern_elements(_pattern: &str, _position: egui::Pos2) -> Vec<UmlClassElementView<Self>> {
+        Vec::new()
+    }
 }
 
 #[derive(Clone, Default)]
@@ -105,7 +177,99 @@ impl StereotypeController for UnrestrictedStereotypeController {
 
 #[derive(Clone, Default)]
 pub struct UmlClassNullProfile;
-impl UmlClassProfile for UmlClassNullProfile {}
+impl UmlClassProfile for UmlClassNullProfile {
+    fn menubar_options_fun(
+        model: &ERef<UmlClassDiagram>,
+        view_uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        if ui.button("PlantUML description").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(TextDescriptionTab::plantuml(model.clone()))),
+            ));
+        }
+        if ui.button("Mermaid description").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(TextDescriptionTab::mermaid(model.clone()))),
+            ));
+        }
+        if ui.button("JSON Schema").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(TextDescriptionTab::json_schema(model.clone()))),
+            ));
+        }
+        if ui.button("SQL DDL").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(super::umlclass_sql_ddl::SqlDdlTab::new(
+                    model.clone(),
+                ))),
+            ));
+        }
+        if ui.button("Code skeletons").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::umlclass_codegen::CodeGenerationTab::new(model.clone()),
+                )),
+            ));
+        }
+        if ui.button("Import PlantUML").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::umlclass_plantuml_import::PlantUmlImportTab::default(),
+                )),
+            ));
+        }
+        if ui.button("Import SQL schema").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::umlclass_sql_import::SqlSchemaImportTab::default(),
+                )),
+            ));
+        }
+        if ui.button("Import Ecore").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(super::umlclass_ecore::EcoreImportTab::default())),
+            ));
+        }
+        if ui.button("Import CSV").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(
+                    super::umlclass_csv_import::CsvImportTab::default(),
+                )),
+            ));
+        }
+        if ui.button("Board").clicked() {
+            let uuid = uuid::Uuid::now_v7();
+            commands.push(ProjectCommand::AddCustomTab(
+                uuid,
+                Arc::new(RwLock::new(super::umlclass_board::UmlClassBoardTab::new(
+                    model.clone(),
+                    *view_uuid,
+                ))),
+            ));
+        }
+        ui.separator();
+    }
+}
 
 pub struct UmlClassDomain<P: UmlClassProfile> {
     _profile: PhantomData<P>,
@@ -117,16 +281,87 @@ impl<P: UmlClassProfile> Domain for UmlClassDomain<P> {
     type CommonElementViewT = UmlClassElementView<P>;
     type ViewTargettingSectionT = UmlClassElement;
     type QueryableT<'a> = GenericQueryable<'a, Self>;
+    type NotationT = UmlClassNotation;
     type ToolT = NaiveUmlClassTool<P>;
     type OrdinalMovementT = UmlClassOrdinalMovement;
     type AddCommandElementT = UmlClassElementOrVertex<P>;
     type PropChangeT = UmlClassPropChange;
 }
 
+#[derive(Clone, Copy, Default, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UmlClassNotation {
+    /// Instances are shown only as `name: Type`, without stereotypes and slots
+    pub compact_instances: bool,
+    /// Default for class views which do not show or hide the compartment themselves
+    #[serde(default)]
+    pub hide_properties: bool,
+    #[serde(default)]
+    pub hide_operations: bool,
+    /// Stereotypes in guillemets are not drawn, only their effect on the shapes
+    #[serde(default)]
+    pub hide_stereotypes: bool,
+    /// Compartments defined by the stereotypes of classes, holding their tagged values
+    #[serde(default)]
+    pub hide_tagged_values: bool,
+}
+
+/// Display text without its leading stereotype in guillemets, if there is one
+fn strip_stereotype(text: &str) -> &str {
+    text.strip_prefix('«')
+        .and_then(|e| e.split_once('»'))
+        .map(|(_, rest)| rest.trim_start())
+        .unwrap_or(text)
+}
+
+fn translation_buffers(translations: &ContentTranslations) -> BTreeMap<String, String> {
+    translations
+        .iter()
+        .map(|(k, v)| (k.clone(), (**v).clone()))
+        .collect()
+}
+
+/// Shows edits of the text in all available languages, returning the edited translation
+fn show_translations(
+    ui: &mut egui::Ui,
+    label: &str,
+    multiline: bool,
+    buffers: &mut BTreeMap<String, String>,
+) -> Option<(Arc<String>, Arc<String>)> {
+    let mut changed = None;
+    for (l, _) in crate::common::fluent::AVAILABLE_LANGUAGES {
+        let language = l.to_string();
+        let buffer = buffers.entry(language.clone()).or_default();
+        let label = format!("{} ({}):", label, language);
+        let response = if multiline {
+            ui.labeled_text_edit_multiline(label, buffer)
+        } else {
+            ui.labeled_text_edit_singleline(label, buffer)
+        };
+        if response.changed() {
+            changed = Some((Arc::new(language), Arc::new(buffer.clone())));
+        }
+    }
+    changed
+}
+
+/// Sets the translation, or removes it when empty, returning the previous one
+fn set_translation(
+    translations: &mut ContentTranslations,
+    language: &str,
+    text: &Arc<String>,
+) -> Arc<String> {
+    let previous = if text.is_empty() {
+        translations.remove(language)
+    } else {
+        translations.insert(language.to_owned(), text.clone())
+    };
+    previous.unwrap_or_default()
+}
+
 type PackageViewT<P> = PackageView<UmlClassDomain<P>, UmlClassPackageAdapter<P>>;
 type GeneralizationViewT<P> = MulticonnectionView<UmlClassDomain<P>, UmlClassGeneralizationAdapter>;
 type DependencyViewT<P> = MulticonnectionView<UmlClassDomain<P>, UmlClassDependencyAdapter<P>>;
-type AssociationViewT<P> = MulticonnectionView<UmlClassDomain<P>, UmlClassAssocationAdapter<P>>;
+pub type AssociationViewT<P> = MulticonnectionView<UmlClassDomain<P>, UmlClassAssocationAdapter<P>>;
 type UseCaseGeneralizationViewT<P> =
     MulticonnectionView<UmlClassDomain<P>, UmlUseCaseGeneralizationAdapter>;
 type CommentLinkViewT<P> = MulticonnectionView<UmlClassDomain<P>, UmlClassCommentLinkAdapter>;
@@ -146,7 +381,7 @@ impl UmlClassOrdinalMovement {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum UmlClassPropChange {
     StereotypeChange(Arc<String>),
 
@@ -176,6 +411,8 @@ pub enum UmlClassPropChange {
     SetNameChange(Arc<String>),
     SetCoveringChange(bool),
     SetDisjointChange(bool),
+    TreeStyleChange(bool),
+    CurvedChange(bool),
 
     DependencyArrowOpenChange(bool),
 
@@ -188,9 +425,14 @@ pub enum UmlClassPropChange {
 
     PackageKindChange(UmlClassPackageKind),
 
+    CompartmentChange(/*compartment*/ Arc<String>, Arc<String>),
+    NameTranslationChange(/*language*/ Arc<String>, Arc<String>),
+    CommentTranslationChange(/*language*/ Arc<String>, Arc<String>),
+
     ColorChange(ColorChangeData),
     CommentChange(Arc<String>),
     CommentAlignChange(Option<egui::Align>, Option<egui::Align>),
+    NotationChange(UmlClassNotation),
 }
 
 impl Debug for UmlClassPropChange {
@@ -252,6 +494,12 @@ impl TryMerge for UmlClassPropChange {
             {
                 Some(newer.clone())
             }
+            (Self::CompartmentChange(c1, _), newer @ Self::CompartmentChange(c2, _))
+            | (Self::NameTranslationChange(c1, _), newer @ Self::NameTranslationChange(c2, _))
+            | (
+                Self::CommentTranslationChange(c1, _),
+                newer @ Self::CommentTranslationChange(c2, _),
+            ) if c1 == c2 => Some(newer.clone()),
             _ => None,
         }
     }
@@ -370,6 +618,108 @@ impl ControllerAdapter<UmlClassDomain<UmlClassNullProfile>> for UmlClassControll
         }
         None
     }
+
+    fn show_menubar_diagram_options(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        _view_uuid: &ViewUuid,
+        _represented_models: &dyn Fn(&ViewUuid) -> Option<HashSet<ModelUuid>>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        // Object diagram sharing the model, so that its instances are bound to these classes
+        if ui.button("New Object Diagram").clicked() {
+            let name = format!("{} objects", self.model.read().name);
+            let (uuid, controller) =
+                crate::domains::umlobject::umlobject_controllers::new_controlller(
+                    self.model.clone(),
+                    name,
+                    vec![],
+                );
+            commands.push(ProjectCommand::AddNewDiagram(
+                ViewUuid::nil(),
+                uuid,
+                controller,
+            ));
+            commands.push(ProjectCommand::OpenAndFocusTab(
+                crate::NHTab::Diagram { uuid },
+                None,
+            ));
+        }
+        ui.separator();
+    }
+
+    fn text_export_formats(&self) -> &'static [TextExportFormat] {
+        &[
+            PLANTUML_FORMAT,
+            MERMAID_FORMAT,
+            JSON_SCHEMA_FORMAT,
+            SQL_DDL_FORMAT,
+            ECORE_FORMAT,
+            CSV_CLASSES_FORMAT,
+            CSV_ASSOCIATIONS_FORMAT,
+        ]
+    }
+    fn export_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+    ) -> Option<String> {
+        match *format {
+            PLANTUML_FORMAT => Some(self.model.read().plantuml_document(languages)),
+            MERMAID_FORMAT => Some(self.model.read().mermaid_document(languages)),
+            JSON_SCHEMA_FORMAT => Some(super::umlclass_json_schema::json_schema_document(
+                &self.model.read(),
+                languages,
+            )),
+            SQL_DDL_FORMAT => Some(super::umlclass_sql_ddl::sql_ddl(
+                &self.model.read(),
+                &Default::default(),
+            )),
+            ECORE_FORMAT => Some(super::umlclass_ecore::ecore_document(&self.model.read())),
+            CSV_CLASSES_FORMAT => Some(super::umlclass_csv_export::class_table(&self.model.read())),
+            CSV_ASSOCIATIONS_FORMAT => Some(super::umlclass_csv_export::association_table(
+                &self.model.read(),
+            )),
+            _ => None,
+        }
+    }
+    fn export_selected_text(
+        &self,
+        format: &TextExportFormat,
+        languages: &[unic_langid::LanguageIdentifier],
+        selection: &HashSet<ModelUuid>,
+    ) -> Option<String> {
+        match *format {
+            PLANTUML_FORMAT => Some(self.model.read().plantuml_document_of(languages, selection)),
+            _ => None,
+        }
+    }
+    fn stereotype_and_comment(&self, element: &UmlClassElement) -> (Arc<String>, Arc<String>) {
+        macro_rules! both {
+            ($e:expr) => {{
+                let r = $e.read();
+                (r.stereotype.clone(), r.comment.clone())
+            }};
+        }
+        match element {
+            UmlClassElement::Package(inner) => both!(inner),
+            UmlClassElement::Instance(inner) => both!(inner),
+            UmlClassElement::Class(inner) => both!(inner),
+            UmlClassElement::UseCase(inner) => both!(inner),
+            UmlClassElement::Dependency(inner) => both!(inner),
+            UmlClassElement::Association(inner) => both!(inner),
+            UmlClassElement::Property(inner) => (inner.read().stereotype.clone(), Arc::default()),
+            UmlClassElement::Operation(inner) => (inner.read().stereotype.clone(), Arc::default()),
+            UmlClassElement::Generalization(inner) => {
+                (Arc::default(), inner.read().comment.clone())
+            }
+            UmlClassElement::UseCaseGeneralization(inner) => {
+                (Arc::default(), inner.read().comment.clone())
+            }
+            UmlClassElement::Comment(..) | UmlClassElement::CommentLink(..) => Default::default(),
+        }
+    }
 }
 
 #[derive(
@@ -379,6 +729,8 @@ pub struct UmlClassDiagramAdapter<P: UmlClassProfile> {
     #[nh_context_serde(entity)]
     model: ERef<UmlClassDiagram>,
     background_color: MGlobalColor,
+    #[nh_context_serde(default)]
+    notation: UmlClassNotation,
     #[serde(skip)]
     #[nh_context_serde(skip_and_default)]
     buffer: UmlClassDiagramBuffer,
@@ -399,6 +751,7 @@ impl<P: UmlClassProfile> UmlClassDiagramAdapter<P> {
         Self {
             model: model.clone(),
             background_color: MGlobalColor::None,
+            notation: UmlClassNotation::default(),
             buffer: UmlClassDiagramBuffer {
                 name: (*m.name).clone(),
                 comment: (*m.comment).clone(),
@@ -581,6 +934,65 @@ impl<P: UmlClassProfile> DiagramAdapter<UmlClassDomain<P>> for UmlClassDiagramAd
 
         Ok(v)
     }
+    fn new_named_element(
+        &self,
+        uuid: ModelUuid,
+        name: &str,
+        position: egui::Pos2,
+    ) -> Option<UmlClassElementView<P>> {
+        let model = ERef::new(UmlClass::new(
+            uuid,
+            name.to_owned(),
+            String::new(),
+            String::new(),
+            false,
+            vec![],
+            vec![],
+        ));
+        Some(UmlClassElementView::from(new_umlclass_class_view(
+            model,
+            vec![],
+            vec![],
+            position,
+            UmlClassRenderStyle::Class,
+            MGlobalColor::None,
+        )))
+    }
+    fn new_child_element(
+        &self,
+        parent: &UmlClassElementView<P>,
+        position: egui::Pos2,
+    ) -> Option<(UmlClassElementView<P>, UmlClassElementView<P>)> {
+        let (stereotype, association_stereotype) = P::child_usecase_stereotypes()?;
+        let UmlClassElementView::UseCase(parent) = parent else {
+            return None;
+        };
+        let parent_model = parent.read().model.clone();
+        let (child_model, child_view) =
+            new_uml_usecase::<P>("", stereotype, false, position, MGlobalColor::None);
+        let (_, association_view) = new_umlclass_association(
+            association_stereotype,
+            "",
+            "",
+            "",
+            None,
+            (parent_model.into(), parent.clone().into()),
+            (child_model.into(), child_view.clone().into()),
+        );
+        Some((child_view.into(), association_view.into()))
+    }
+    fn element_name(&self, e: &UmlClassElement) -> Option<Arc<String>> {
+        match e {
+            UmlClassElement::Package(inner) => Some(inner.read().name.clone()),
+            UmlClassElement::Class(inner) => Some(inner.read().name.clone()),
+            UmlClassElement::Property(inner) => Some(inner.read().name.clone()),
+            UmlClassElement::Operation(inner) => Some(inner.read().name.clone()),
+            _ => None,
+        }
+    }
+    fn name_change(&self, name: Arc<String>) -> Option<UmlClassPropChange> {
+        Some(UmlClassPropChange::NameChange(name))
+    }
     fn label_for(&self, e: &UmlClassElement) -> Arc<String> {
         match e {
             UmlClassElement::Package(inner) => inner.read().name.clone(),
@@ -668,6 +1080,15 @@ impl<P: UmlClassProfile> DiagramAdapter<UmlClassDomain<P>> for UmlClassDiagramAd
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        P::color_profile(dark)
+    }
+    fn frame_kind(&self) -> &'static str {
+        "class"
+    }
+    fn notation(&self) -> UmlClassNotation {
+        self.notation
+    }
     fn show_view_props_fun(
         &mut self,
         view_uuid: &ViewUuid,
@@ -692,6 +1113,25 @@ impl<P: UmlClassProfile> DiagramAdapter<UmlClassDomain<P>> for UmlClassDiagramAd
                 UmlClassPropChange::ColorChange((0, new_color).into()),
             ));
         }
+
+        let mut notation = self.notation;
+        if ui
+            .checkbox(&mut notation.compact_instances, "Compact instances")
+            .changed()
+            | ui.checkbox(&mut notation.hide_properties, "Hide properties")
+                .changed()
+            | ui.checkbox(&mut notation.hide_operations, "Hide operations")
+                .changed()
+            | ui.checkbox(&mut notation.hide_stereotypes, "Hide stereotypes")
+                .changed()
+            | ui.checkbox(&mut notation.hide_tagged_values, "Hide tagged values")
+                .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                UmlClassPropChange::NotationChange(notation),
+            ));
+        }
     }
     fn show_model_props_fun(
         &mut self,
@@ -770,6 +1210,13 @@ impl<P: UmlClassProfile> DiagramAdapter<UmlClassDomain<P>> for UmlClassDiagramAd
                     ));
                     model.comment = comment.clone();
                 }
+                UmlClassPropChange::NotationChange(notation) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        UmlClassPropChange::NotationChange(self.notation),
+                    ));
+                    self.notation = *notation;
+                }
                 _ => {}
             }
         }
@@ -830,38 +1277,59 @@ impl<P: UmlClassProfile> DiagramAdapter<UmlClassDomain<P>> for UmlClassDiagramAd
     }
 }
 
-pub struct PlantUmlTab {
+/// Read-only textual description of the diagram in one of the supported text formats
+pub struct TextDescriptionTab {
     diagram: ERef<UmlClassDiagram>,
-    plantuml_description: String,
+    title: &'static str,
+    generate: fn(&UmlClassDiagram, &[unic_langid::LanguageIdentifier]) -> String,
+    description: String,
 }
 
-impl PlantUmlTab {
-    pub fn new(diagram: ERef<UmlClassDiagram>) -> Self {
+impl TextDescriptionTab {
+    pub fn plantuml(diagram: ERef<UmlClassDiagram>) -> Self {
+        Self {
+            diagram,
+            title: "PlantUML description",
+            generate: UmlClassDiagram::plantuml,
+            description: String::new(),
+        }
+    }
+    pub fn mermaid(diagram: ERef<UmlClassDiagram>) -> Self {
+        Self {
+            diagram,
+            title: "Mermaid description",
+            generate: UmlClassDiagram::mermaid_document,
+            description: String::new(),
+        }
+    }
+    pub fn json_schema(diagram: ERef<UmlClassDiagram>) -> Self {
         Self {
             diagram,
-            plantuml_description: String::new(),
+            title: "JSON Schema",
+            generate: super::umlclass_json_schema::json_schema_document,
+            description: String::new(),
         }
     }
 }
 
-impl CustomTab for PlantUmlTab {
+impl CustomTab for TextDescriptionTab {
     fn title(&self) -> String {
-        "PlantUML description".to_owned()
+        self.title.to_owned()
     }
 
     fn show(
         &mut self,
-        _gdc: &GlobalDrawingContext,
+        gdc: &GlobalDrawingContext,
         ui: &mut egui::Ui,
         _commands: &mut Vec<ProjectCommand>,
     ) {
         if ui.button("Refresh").clicked() {
-            self.plantuml_description = self.diagram.read().plantuml();
+            self.description = (self.generate)(&self.diagram.read(), &gdc.languages_order);
         }
 
         ui.add_sized(
             (ui.available_width(), 20.0),
-            egui::TextEdit::multiline(&mut self.plantuml_description.as_str()),
+            egui::TextEdit::multiline(&mut self.description.as_str()),
         );
     }
 }
@@ -1290,6 +1758,176 @@ pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
     )
 }
 
+pub fn from_plantuml(
+    source: &super::umlclass_plantuml_import::PlantUmlClassDiagram,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (root_models, root_views) = plantuml_elements::<UmlClassNullProfile>(source);
+    let name = source
+        .title
+        .clone()
+        .unwrap_or_else(|| "Imported UML class diagram".to_owned());
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        root_models,
+    ));
+    new_controlller(diagram, name, root_views)
+}
+
+/// Top level models and views of an imported diagram, placed in a grid
+pub fn plantuml_elements<P: UmlClassProfile>(
+    source: &super::umlclass_plantuml_import::PlantUmlClassDiagram,
+) -> (Vec<UmlClassElement>, Vec<UmlClassElementView<P>>) {
+    use super::umlclass_plantuml_import::PlantUmlLinkKind;
+
+    let (class_positions, package_rects) = super::umlclass_plantuml_import::grid_layout(source);
+    let classes: Vec<_> = source
+        .classes
+        .iter()
+        .zip(class_positions)
+        .map(|(c, position)| {
+            let (properties, operations): (Vec<_>, Vec<_>) =
+                c.members.iter().partition(|e| e.parameters.is_none());
+            let visibility = |e: &super::umlclass_plantuml_import::PlantUmlMember| {
+                e.visibility.map_or(UFOption::None, UFOption::Some)
+            };
+            new_umlclass_class(
+                &c.name,
+                &c.stereotype,
+                c.is_abstract,
+                properties
+                    .into_iter()
+                    .map(|e| {
+                        new_umlclass_property(
+                            visibility(e),
+                            &e.name,
+                            &e.value_type,
+                            &e.multiplicity,
+                            &e.default_value,
+                            &e.stereotype,
+                        )
+                    })
+                    .collect(),
+                operations
+                    .into_iter()
+                    .map(|e| {
+                        new_umlclass_operation(
+                            visibility(e),
+                            &e.name,
+                            e.parameters.as_deref().unwrap_or_default(),
+                            &e.value_type,
+                            &e.stereotype,
+                        )
+                    })
+                    .collect(),
+                position,
+                UmlClassRenderStyle::Class,
+                MGlobalColor::None,
+            )
+        })
+        .collect();
+    let packages: Vec<_> = source
+        .packages
+        .iter()
+        .zip(package_rects)
+        .map(|(p, rect)| {
+            new_umlclass_package(&p.name, &p.stereotype, UmlClassPackageKind::Package, rect)
+        })
+        .collect();
+
+    let mut root_models: Vec<UmlClassElement> = Vec::new();
+    let mut root_views: Vec<UmlClassElementView<P>> = Vec::new();
+    let mut add_to = |parent: Option<usize>, model: UmlClassElement, view| match parent {
+        None => {
+            root_models.push(model);
+            root_views.push(view);
+        }
+        Some(parent) => {
+            let mut w = packages[parent].1.write();
+            let target = *w.uuid();
+            let (mut u, mut a) = Default::default();
+            w.apply_command(
+                &InsensitiveCommand::AddDependency {
+                    target,
+                    bucket: 0,
+                    position: None,
+                    element: UmlClassElementOrVertex::Element(view),
+                    into_model: true,
+                },
+                &mut u,
+                &mut a,
+            );
+        }
+    };
+    for (p, (model, view)) in source.packages.iter().zip(packages.iter()) {
+        add_to(p.parent, model.clone().into(), view.clone().into());
+    }
+    for (c, (model, view)) in source.classes.iter().zip(classes.iter()) {
+        add_to(c.package, model.clone().into(), view.clone().into());
+    }
+
+    for l in &source.links {
+        let (source_model, source_view) = classes[l.source].clone();
+        let (target_model, target_view) = classes[l.target].clone();
+        let (model, view): (UmlClassElement, UmlClassElementView<P>) = match &l.kind {
+            PlantUmlLinkKind::Generalization => {
+                let (m, v) = new_umlclass_generalization(
+                    "",
+                    None,
+                    (source_model, source_view.into()),
+                    (target_model, target_view.into()),
+                );
+                (m.into(), v.into())
+            }
+            PlantUmlLinkKind::Realization | PlantUmlLinkKind::Dependency => {
+                let is_dependency = matches!(l.kind, PlantUmlLinkKind::Dependency);
+                let stereotype = l
+                    .label
+                    .strip_prefix("<<")
+                    .and_then(|e| e.strip_suffix(">>"));
+                let (m, v) = new_umlclass_dependency(
+                    stereotype.unwrap_or_default(),
+                    if stereotype.is_some() { "" } else { &l.label },
+                    is_dependency,
+                    None,
+                    (source_model.into(), source_view.into()),
+                    (target_model.into(), target_view.into()),
+                );
+                (m.into(), v.into())
+            }
+            PlantUmlLinkKind::Association(source_end, target_end) => {
+                let mut m = UmlClassAssociation::new(
+                    ModelUuid::now_v7(),
+                    l.stereotype.clone(),
+                    l.label.clone(),
+                    source_model.into(),
+                    l.source_multiplicity.clone(),
+                    target_model.into(),
+                    l.target_multiplicity.clone(),
+                );
+                m.source_navigability = source_end.navigability;
+                m.source_aggregation = source_end.aggregation;
+                m.source_label_role = Arc::new(source_end.role.clone());
+                m.target_navigability = target_end.navigability;
+                m.target_aggregation = target_end.aggregation;
+                m.target_label_role = Arc::new(target_end.role.clone());
+                let m = ERef::new(m);
+                let v = new_umlclass_association_view(
+                    m.clone(),
+                    None,
+                    source_view.into(),
+                    target_view.into(),
+                );
+                (m.into(), v.into())
+            }
+        };
+        root_models.push(model);
+        root_views.push(view);
+    }
+
+    (root_models, root_views)
+}
+
 pub fn deserializer(
     uuid: ControllerUuid,
     d: &mut NHDeserializer,
@@ -1398,6 +2036,7 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                             }
                             modified |= columns[1]
                                 .labeled_text_edit_singleline("Instance name", instance_name)
+                                .on_hover_text(NAME_PATTERN_HINT)
                                 .changed();
                             modified |= columns[1]
                                 .labeled_text_edit_singleline("Instance type", instance_type)
@@ -1429,6 +2068,7 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                             }
                             modified |= columns[1]
                                 .labeled_text_edit_singleline("Name", name)
+                                .on_hover_text(NAME_PATTERN_HINT)
                                 .changed();
                             modified |= columns[1].checkbox(is_abstract, "isAbstract").changed();
 
@@ -1493,6 +2133,7 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                             }
                             modified |= columns[1]
                                 .labeled_text_edit_singleline("Name", name)
+                                .on_hover_text(NAME_PATTERN_HINT)
                                 .changed();
                             modified |= columns[1].checkbox(is_abstract, "isAbstract").changed();
 
@@ -1535,6 +2176,11 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                                 stereotype,
                                 source_multiplicity,
                                 target_multiplicity,
+                            }
+                            | LinkType::Composition {
+                                stereotype,
+                                source_multiplicity,
+                                target_multiplicity,
                             } => {
                                 let mut sc = P::AssociationStereotypeController::default();
                                 sc.refresh(stereotype);
@@ -1569,6 +2215,7 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                             }
                             modified |= columns[1]
                                 .labeled_text_edit_singleline("Name", name)
+                                .on_hover_text(NAME_PATTERN_HINT)
                                 .changed();
 
                             columns[1].label("Package kind");
@@ -1627,7 +2274,8 @@ impl<P: UmlClassProfile> DiagramSettings for UmlClassSettings<P> {
                                     }
                                 });
                         }
-                        UmlClassToolStage::CommentLinkStart => {}
+                        UmlClassToolStage::CommentLinkStart | UmlClassToolStage::Pattern { .. } => {
+                        }
                         UmlClassToolStage::LinkEnd
                         | UmlClassToolStage::LinkAddEnding { .. }
                         | UmlClassToolStage::PackageEnd
@@ -1764,7 +2412,7 @@ fn view_for_stage<P: UmlClassProfile>(s: &UmlClassToolStage) -> UmlClassElementV
             background_color: color,
         } => {
             let instance_view = new_umlclass_instance(
-                instance_name,
+                &expand_name_pattern(instance_name, |_| false),
                 instance_type,
                 stereotype,
                 "",
@@ -1783,7 +2431,7 @@ fn view_for_stage<P: UmlClassProfile>(s: &UmlClassToolStage) -> UmlClassElementV
             background_color: color,
         } => {
             let class_view = new_umlclass_class(
-                name,
+                &expand_name_pattern(name, |_| false),
                 stereotype,
                 *is_abstract,
                 Vec::new(),
@@ -1822,8 +2470,14 @@ fn view_for_stage<P: UmlClassProfile>(s: &UmlClassToolStage) -> UmlClassElementV
             is_abstract,
             background_color: color,
         } => {
-            let uc_view =
-                new_uml_usecase(name, stereotype, *is_abstract, egui::Pos2::ZERO, *color).1;
+            let uc_view = new_uml_usecase(
+                &expand_name_pattern(name, |_| false),
+                stereotype,
+                *is_abstract,
+                egui::Pos2::ZERO,
+                *color,
+            )
+            .1;
             uc_view.write().refresh_buffers();
             uc_view.into()
         }
@@ -1893,6 +2547,23 @@ fn view_for_stage<P: UmlClassProfile>(s: &UmlClassToolStage) -> UmlClassElementV
                     .1;
                     a.into()
                 }
+                LinkType::Composition {
+                    stereotype,
+                    source_multiplicity,
+                    target_multiplicity,
+                } => {
+                    let a = new_umlclass_composition(
+                        stereotype,
+                        "",
+                        source_multiplicity,
+                        target_multiplicity,
+                        None,
+                        (d1.0.into(), d1.1.into()),
+                        (d2.0.into(), d2.1.into()),
+                    )
+                    .1;
+                    a.into()
+                }
             }
         }
         UmlClassToolStage::PackageStart {
@@ -1938,6 +2609,29 @@ fn view_for_stage<P: UmlClassProfile>(s: &UmlClassToolStage) -> UmlClassElementV
                 new_umlclass_commentlink(None, (d1.0, d1.1.into()), (d2.0.into(), d2.1.into())).1;
             commentlink.into()
         }
+        // The first element stands for the whole pattern
+        UmlClassToolStage::Pattern { pattern } => {
+            match P::pattern_elements(pattern, egui::Pos2::ZERO)
+                .into_iter()
+                .next()
+            {
+                Some(mut view) => {
+                    view.refresh_buffers();
+                    view
+                }
+                None => {
+                    let comment_view = new_umlclass_comment(
+                        pattern,
+                        "",
+                        egui::Pos2::ZERO,
+                        egui::Align2::CENTER_CENTER,
+                    )
+                    .1;
+                    comment_view.write().refresh_buffers();
+                    comment_view.into()
+                }
+            }
+        }
         UmlClassToolStage::LinkEnd
         | UmlClassToolStage::LinkAddEnding { .. }
         | UmlClassToolStage::PackageEnd
@@ -2107,7 +2801,7 @@ pub fn default_settings() -> Box<dyn DiagramSettings> {
                 ),
                 (
                     UmlClassToolStage::Class {
-                        name: "ClassName".to_owned(),
+                        name: "Class{n}".to_owned(),
                         stereotype: "class".to_owned(),
                         is_abstract: false,
                         render_style: UmlClassRenderStyle::Class,
@@ -2267,15 +2961,15 @@ pub fn settings_deserializer_helper<P: UmlClassProfile>(
         return Err(());
     };
     Ok(Box::new(UmlClassSettings::<P> {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
         comment_indication: value
             .get("comment_indication")
-            .unwrap()
+            .ok_or(())?
             .clone()
             .try_into()
-            .unwrap(),
+            .map_err(|_| ())?,
         instance_buttons,
         class_buttons,
     }))
@@ -2320,6 +3014,12 @@ pub enum LinkType {
         source_multiplicity: String,
         target_multiplicity: String,
     },
+    /// Association whose source end is the composite owner of the target
+    Composition {
+        stereotype: String,
+        source_multiplicity: String,
+        target_multiplicity: String,
+    },
 }
 
 #[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -2373,11 +3073,16 @@ pub enum UmlClassToolStage {
     },
     CommentLinkStart,
     CommentLinkEnd,
+    /// Several connected elements at once, as given by `UmlClassProfile::pattern_elements`
+    Pattern {
+        pattern: String,
+    },
 }
 
 pub enum PartialUmlClassElement<P: UmlClassProfile> {
     None,
     Some(UmlClassElementView<P>),
+    Pattern(Vec<UmlClassElementView<P>>),
     Link {
         link_type: LinkType,
         source: UmlClassAssociable,
@@ -2453,7 +3158,8 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 | UmlClassToolStage::PackageStart { .. }
                 | UmlClassToolStage::PackageEnd
                 | UmlClassToolStage::Comment { .. }
-                | UmlClassToolStage::CommentLinkEnd => TARGETTABLE_COLOR,
+                | UmlClassToolStage::CommentLinkEnd
+                | UmlClassToolStage::Pattern { .. } => TARGETTABLE_COLOR,
 
                 UmlClassToolStage::ClassProperty { .. }
                 | UmlClassToolStage::ClassOperation { .. }
@@ -2472,6 +3178,7 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 | UmlClassToolStage::PackageEnd
                 | UmlClassToolStage::Comment { .. }
                 | UmlClassToolStage::CommentLinkStart
+                | UmlClassToolStage::Pattern { .. }
                 | UmlClassToolStage::LinkStart {
                     link_type: LinkType::Generalization { .. },
                 }
@@ -2500,7 +3207,8 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 | UmlClassToolStage::PackageStart { .. }
                 | UmlClassToolStage::PackageEnd
                 | UmlClassToolStage::Comment { .. }
-                | UmlClassToolStage::CommentLinkStart => NON_TARGETTABLE_COLOR,
+                | UmlClassToolStage::CommentLinkStart
+                | UmlClassToolStage::Pattern { .. } => NON_TARGETTABLE_COLOR,
 
                 UmlClassToolStage::LinkAddEnding { .. } | UmlClassToolStage::LinkEnd => {
                     match &self.result {
@@ -2534,7 +3242,8 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 | UmlClassToolStage::PackageStart { .. }
                 | UmlClassToolStage::PackageEnd
                 | UmlClassToolStage::Comment { .. }
-                | UmlClassToolStage::CommentLinkStart => NON_TARGETTABLE_COLOR,
+                | UmlClassToolStage::CommentLinkStart
+                | UmlClassToolStage::Pattern { .. } => NON_TARGETTABLE_COLOR,
 
                 UmlClassToolStage::LinkStart { .. } | UmlClassToolStage::CommentLinkEnd => {
                     TARGETTABLE_COLOR
@@ -2573,7 +3282,8 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 | UmlClassToolStage::PackageStart { .. }
                 | UmlClassToolStage::PackageEnd
                 | UmlClassToolStage::Comment { .. }
-                | UmlClassToolStage::CommentLinkEnd => NON_TARGETTABLE_COLOR,
+                | UmlClassToolStage::CommentLinkEnd
+                | UmlClassToolStage::Pattern { .. } => NON_TARGETTABLE_COLOR,
             },
             Some(
                 UmlClassElement::Generalization(..)
@@ -2729,6 +3439,13 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 self.result = PartialUmlClassElement::Some(comment_view.into());
                 self.event_lock = true;
             }
+            (UmlClassToolStage::Pattern { pattern }, _) => {
+                let elements = P::pattern_elements(pattern, pos);
+                if !elements.is_empty() {
+                    self.result = PartialUmlClassElement::Pattern(elements);
+                    self.event_lock = true;
+                }
+            }
             _ => {}
         }
     }
@@ -2955,6 +3672,24 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
             >,
         >,
     ) -> Result<Option<Box<dyn CustomModal>>, ()> {
+        let mut taken_names = HashSet::new();
+        q.for_each_view(|e| match e.model() {
+            UmlClassElement::Package(inner) => {
+                taken_names.insert((*inner.read().name).clone());
+            }
+            UmlClassElement::Instance(inner) => {
+                taken_names.insert((*inner.read().instance_name).clone());
+            }
+            UmlClassElement::Class(inner) => {
+                taken_names.insert((*inner.read().name).clone());
+            }
+            UmlClassElement::UseCase(inner) => {
+                taken_names.insert((*inner.read().name).clone());
+            }
+            _ => {}
+        });
+        let expand = |pattern: &str| expand_name_pattern(pattern, |e| taken_names.contains(e));
+
         match &mut self.result {
             PartialUmlClassElement::LinkEnding {
                 source,
@@ -2980,6 +3715,27 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
             }
             PartialUmlClassElement::Some(element) => {
                 let element = element.clone();
+                match &element {
+                    UmlClassElementView::Instance(inner) => {
+                        let model = inner.read().model.clone();
+                        let name = expand(&model.read().instance_name);
+                        model.write().instance_name = Arc::new(name);
+                        inner.write().refresh_buffers();
+                    }
+                    UmlClassElementView::Class(inner) => {
+                        let model = inner.read().model.clone();
+                        let name = expand(&model.read().name);
+                        model.write().name = Arc::new(name);
+                        inner.write().refresh_buffers();
+                    }
+                    UmlClassElementView::UseCase(inner) => {
+                        let model = inner.read().model.clone();
+                        let name = expand(&model.read().name);
+                        model.write().name = Arc::new(name);
+                        inner.write().refresh_buffers();
+                    }
+                    _ => {}
+                }
                 let esm: Option<Box<dyn CustomModal>> = match &element {
                     UmlClassElementView::Instance(inner) => {
                         Some(Box::new(UmlClassInstanceSetupModal::<
@@ -3019,6 +3775,36 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                 });
                 Ok(esm)
             }
+            PartialUmlClassElement::Pattern(elements) => {
+                let elements = std::mem::take(elements);
+                for e in &elements {
+                    if let UmlClassElementView::Class(inner) = e {
+                        let model = inner.read().model.clone();
+                        let name = expand(&model.read().name);
+                        model.write().name = Arc::new(name);
+                        inner.write().refresh_buffers();
+                    }
+                }
+                self.try_spend();
+                // All elements are added by a single undoable command
+                commands.push(InsensitiveCommand::Macro(
+                    "nh-viewcommand-addelements".to_owned().into(),
+                    elements.len(),
+                    elements
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, e)| InsensitiveCommand::AddDependency {
+                            target: *preferred_container,
+                            bucket: preferred_bucket,
+                            position: preferred_position.map(|p| p + idx),
+                            element: e.into(),
+                            into_model: true,
+                        })
+                        .collect::<Vec<_>>()
+                        .into(),
+                ));
+                Ok(None)
+            }
             PartialUmlClassElement::Link {
                 link_type,
                 source,
@@ -3095,6 +3881,21 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
                         )
                         .1
                         .into(),
+                        LinkType::Composition {
+                            stereotype,
+                            source_multiplicity,
+                            target_multiplicity,
+                        } => new_umlclass_composition(
+                            stereotype,
+                            "",
+                            source_multiplicity,
+                            target_multiplicity,
+                            None,
+                            (source.clone(), source_view),
+                            (dest.clone(), target_view),
+                        )
+                        .1
+                        .into(),
                     };
 
                     self.try_spend();
@@ -3151,8 +3952,12 @@ impl<P: UmlClassProfile> Tool<UmlClassDomain<P>> for NaiveUmlClassTool<P> {
             } => {
                 self.current_stage = self.initial_stage.clone();
 
-                let (_package_model, package_view) =
-                    new_umlclass_package(name, stereotype, *kind, egui::Rect::from_two_pos(*a, *b));
+                let (_package_model, package_view) = new_umlclass_package(
+                    &expand(name),
+                    stereotype,
+                    *kind,
+                    egui::Rect::from_two_pos(*a, *b),
+                );
 
                 self.try_spend();
                 commands.push(InsensitiveCommand::AddDependency {
@@ -3200,11 +4005,12 @@ pub fn new_umlclass_package_view<P: UmlClassProfile>(
         UmlClassPackageAdapter {
             model: model.clone(),
             background_color: MGlobalColor::None,
-            display_text: Arc::new("".to_owned()),
             name_buffer: (*m.name).clone(),
+            name_translation_buffers: translation_buffers(&m.name_translations),
             stereotype_controller: Default::default(),
             kind_buffer: m.kind,
             comment_buffer: (*m.comment).clone(),
+            comment_translation_buffers: translation_buffers(&m.comment_translations),
             _profile: PhantomData,
         },
         Vec::new(),
@@ -3220,10 +4026,10 @@ pub struct UmlClassPackageAdapter<P: UmlClassProfile> {
     model: ERef<UmlClassPackage>,
     background_color: MGlobalColor,
 
-    #[nh_context_serde(skip_and_default)]
-    display_text: Arc<String>,
     #[nh_context_serde(skip_and_default)]
     name_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    name_translation_buffers: BTreeMap<String, String>,
     #[serde(skip)]
     #[nh_context_serde(skip_and_default)]
     stereotype_controller: P::PackageStereotypeController,
@@ -3231,6 +4037,8 @@ pub struct UmlClassPackageAdapter<P: UmlClassProfile> {
     kind_buffer: UmlClassPackageKind,
     #[nh_context_serde(skip_and_default)]
     comment_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_translation_buffers: BTreeMap<String, String>,
 
     #[nh_context_serde(skip_and_default)]
     _profile: PhantomData<P>,
@@ -3273,12 +4081,25 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
         &self,
         bounds_rect: egui::Rect,
         highlight: canvas::Highlight,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         context: &GlobalDrawingContext,
         _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn canvas::NHCanvas,
         _tool: &Option<(egui::Pos2, &<UmlClassDomain<P> as Domain>::ToolT)>,
     ) -> Result<egui::Rect, Arc<String>> {
+        let display_text = {
+            let model = self.model.read();
+            let name = localized(
+                &model.name,
+                &model.name_translations,
+                &context.languages_order,
+            );
+            if q.notation().hide_stereotypes || model.stereotype.is_empty() {
+                Arc::new(name.to_owned())
+            } else {
+                Arc::new(format!("«{}» {}", model.stereotype, name))
+            }
+        };
         match self.kind_buffer {
             UmlClassPackageKind::Package => {
                 const PADDING: f32 = 4.0;
@@ -3287,7 +4108,7 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
                 let r = canvas.measure_text(
                     bounds_rect.left_top() + egui::Vec2::new(PADDING, -PADDING),
                     egui::Align2::LEFT_BOTTOM,
-                    &self.display_text,
+                    &display_text,
                     canvas::CLASS_MIDDLE_FONT_SIZE,
                 );
                 canvas.draw_rectangle(
@@ -3300,13 +4121,13 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
                 canvas.draw_text(
                     bounds_rect.left_top() + egui::Vec2::new(PADDING, -PADDING),
                     egui::Align2::LEFT_BOTTOM,
-                    &self.display_text,
+                    &display_text,
                     canvas::CLASS_MIDDLE_FONT_SIZE,
                     foreground_color,
                 );
                 Ok(r.expand(PADDING))
             }
-            UmlClassPackageKind::Boundary => Err(self.display_text.clone()),
+            UmlClassPackageKind::Boundary => Err(display_text),
         }
     }
 
@@ -3365,6 +4186,25 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
                 UmlClassPropChange::CommentChange(Arc::new(self.comment_buffer.clone())),
             ));
         }
+
+        ui.collapsing("Translations", |ui| {
+            if let Some((language, text)) =
+                show_translations(ui, "Name", false, &mut self.name_translation_buffers)
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::NameTranslationChange(language, text),
+                ));
+            }
+            if let Some((language, text)) =
+                show_translations(ui, "Comment", true, &mut self.comment_translation_buffers)
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::CommentTranslationChange(language, text),
+                ));
+            }
+        });
     }
     fn show_color_property(
         &mut self,
@@ -3426,6 +4266,20 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
                     ));
                     model.comment = comment.clone();
                 }
+                UmlClassPropChange::NameTranslationChange(language, text) => {
+                    let previous = set_translation(&mut model.name_translations, language, text);
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        UmlClassPropChange::NameTranslationChange(language.clone(), previous),
+                    ));
+                }
+                UmlClassPropChange::CommentTranslationChange(language, text) => {
+                    let previous = set_translation(&mut model.comment_translations, language, text);
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        UmlClassPropChange::CommentTranslationChange(language.clone(), previous),
+                    ));
+                }
                 UmlClassPropChange::ColorChange(ColorChangeData { slot: 0, color }) => {
                     undo_accumulator.push(InsensitiveCommand::PropertyChange(
                         std::iter::once(*view_uuid).collect(),
@@ -3443,15 +4297,12 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
     fn refresh_buffers(&mut self) {
         let model = self.model.read();
 
-        self.display_text = if model.stereotype.is_empty() {
-            model.name.clone()
-        } else {
-            format!("«{}» {}", model.stereotype, model.name).into()
-        };
         self.stereotype_controller.refresh(&model.stereotype);
         self.name_buffer = (*model.name).clone();
+        self.name_translation_buffers = translation_buffers(&model.name_translations);
         self.kind_buffer = model.kind;
         self.comment_buffer = (*model.comment).clone();
+        self.comment_translation_buffers = translation_buffers(&model.comment_translations);
     }
 
     fn deep_copy_init(
@@ -3475,11 +4326,12 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
         Self {
             model,
             background_color: self.background_color,
-            display_text: self.display_text.clone(),
             stereotype_controller: self.stereotype_controller.clone(),
             name_buffer: self.name_buffer.clone(),
+            name_translation_buffers: self.name_translation_buffers.clone(),
             kind_buffer: self.kind_buffer,
             comment_buffer: self.comment_buffer.clone(),
+            comment_translation_buffers: self.comment_translation_buffers.clone(),
             _profile: PhantomData,
         }
     }
@@ -3494,7 +4346,7 @@ impl<P: UmlClassProfile> PackageAdapter<UmlClassDomain<P>> for UmlClassPackageAd
     }
 }
 
-fn new_umlclass_instance<P: UmlClassProfile>(
+pub fn new_umlclass_instance<P: UmlClassProfile>(
     instance_name: &str,
     instance_type: &str,
     stereotype: &str,
@@ -3782,13 +4634,18 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
 
     fn draw_in(
         &mut self,
-        _: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         context: &GlobalDrawingContext,
         settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveUmlClassTool<P>)>,
     ) -> TargettingStatus {
         let read = self.model.read();
+        let notation = q.notation();
+        let compact = notation.compact_instances;
+        let show_stereotype =
+            !compact && !notation.hide_stereotypes && !self.stereotype_in_guillemets.is_empty();
+        let show_slots = !compact && !read.instance_slots.is_empty();
 
         let mut min = canvas.measure_text(
             self.position,
@@ -3797,7 +4654,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
             canvas::CLASS_MIDDLE_FONT_SIZE,
         );
         let stereotype_botton = min.center_top();
-        if !self.stereotype_in_guillemets.is_empty() {
+        if show_stereotype {
             min = min.union(canvas.measure_text(
                 stereotype_botton,
                 egui::Align2::CENTER_BOTTOM,
@@ -3806,7 +4663,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
             ));
         }
         let slots_top = min.center_bottom();
-        if !read.instance_slots.is_empty() {
+        if show_slots {
             min = min.union(canvas.measure_text(
                 slots_top,
                 egui::Align2::CENTER_TOP,
@@ -3833,7 +4690,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
             canvas::CLASS_MIDDLE_FONT_SIZE,
             egui::Color32::BLACK,
         );
-        if !self.stereotype_in_guillemets.is_empty() {
+        if show_stereotype {
             canvas.draw_text(
                 stereotype_botton,
                 egui::Align2::CENTER_BOTTOM,
@@ -3842,7 +4699,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
                 egui::Color32::BLACK,
             );
         }
-        if !read.instance_slots.is_empty() {
+        if show_slots {
             canvas.draw_line(
                 [self.bounds_rect.left(), self.bounds_rect.right()]
                     .map(|e| egui::Pos2::new(e, slots_top.y)),
@@ -4070,7 +4927,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -4208,7 +5067,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassIn
     }
 }
 
-fn new_umlclass_property<P: UmlClassProfile>(
+pub fn new_umlclass_property<P: UmlClassProfile>(
     visibility_modifier: UFOption<UmlClassVisibilityKind>,
     name: &str,
     value_type: &str,
@@ -4435,16 +5294,25 @@ impl<P: UmlClassProfile> UmlClassPropertyView<P> {
     fn draw_inner(
         &mut self,
         at: egui::Pos2,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
-        _gdc: &GlobalDrawingContext,
-        _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveUmlClassTool<P>)>,
+        fit: UmlClassMemberFit,
     ) -> (egui::Rect, TargettingStatus) {
+        let text = if q.notation().hide_stereotypes {
+            strip_stereotype(&self.display_text)
+        } else {
+            self.display_text.as_str()
+        };
+        let text = canvas::fit_text_width(text, fit.max_width, fit.wrap, |t| {
+            canvas
+                .measure_text(at, egui::Align2::LEFT_TOP, t, canvas::CLASS_ITEM_FONT_SIZE)
+                .width()
+        });
         self.bounds_rect = canvas.measure_text(
             at,
             egui::Align2::LEFT_TOP,
-            &self.display_text,
+            &text,
             canvas::CLASS_ITEM_FONT_SIZE,
         );
         canvas.draw_rectangle(
@@ -4457,7 +5325,7 @@ impl<P: UmlClassProfile> UmlClassPropertyView<P> {
         canvas.draw_text(
             at,
             egui::Align2::LEFT_TOP,
-            &self.display_text,
+            &text,
             canvas::CLASS_ITEM_FONT_SIZE,
             egui::Color32::BLACK,
         );
@@ -4696,18 +5564,17 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassPr
     fn draw_in(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
-        context: &GlobalDrawingContext,
-        settings: &<UmlClassDomain<P> as Domain>::SettingsT,
+        _context: &GlobalDrawingContext,
+        _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &<UmlClassDomain<P> as Domain>::ToolT)>,
     ) -> TargettingStatus {
         self.draw_inner(
             self.bounds_rect.left_top(),
             q,
-            context,
-            settings,
             canvas,
             tool,
+            UmlClassMemberFit::default(),
         )
         .1
     }
@@ -4783,7 +5650,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassPr
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -5242,16 +6111,25 @@ impl<P: UmlClassProfile> UmlClassOperationView<P> {
     fn draw_inner(
         &mut self,
         at: egui::Pos2,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
-        _gdc: &GlobalDrawingContext,
-        _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveUmlClassTool<P>)>,
+        fit: UmlClassMemberFit,
     ) -> (egui::Rect, TargettingStatus) {
+        let text = if q.notation().hide_stereotypes {
+            strip_stereotype(&self.display_text)
+        } else {
+            self.display_text.as_str()
+        };
+        let text = canvas::fit_text_width(text, fit.max_width, fit.wrap, |t| {
+            canvas
+                .measure_text(at, egui::Align2::LEFT_TOP, t, canvas::CLASS_ITEM_FONT_SIZE)
+                .width()
+        });
         self.bounds_rect = canvas.measure_text(
             at,
             egui::Align2::LEFT_TOP,
-            &self.display_text,
+            &text,
             canvas::CLASS_ITEM_FONT_SIZE,
         );
         canvas.draw_rectangle(
@@ -5269,7 +6147,7 @@ impl<P: UmlClassProfile> UmlClassOperationView<P> {
         canvas.draw_text(
             at,
             egui::Align2::LEFT_TOP,
-            &self.display_text,
+            &text,
             canvas::CLASS_ITEM_FONT_SIZE,
             text_color,
         );
@@ -5489,18 +6367,17 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassOp
     fn draw_in(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
-        context: &GlobalDrawingContext,
-        settings: &<UmlClassDomain<P> as Domain>::SettingsT,
+        _context: &GlobalDrawingContext,
+        _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &<UmlClassDomain<P> as Domain>::ToolT)>,
     ) -> TargettingStatus {
         self.draw_inner(
             self.bounds_rect.left_top(),
             q,
-            context,
-            settings,
             canvas,
             tool,
+            UmlClassMemberFit::default(),
         )
         .1
     }
@@ -5576,7 +6453,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassOp
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -5715,7 +6594,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassOp
                 t.push('}');
             }
 
-            t
+            render_math(&t).into_owned()
         };
 
         self.visibility_buffer = m.visibility;
@@ -5842,7 +6721,14 @@ pub fn new_umlclass_class_view<P: UmlClassProfile>(
         name_buffer: (*m.name).clone(),
         template_parameters_buffer: (*m.template_parameters).clone(),
         is_abstract_buffer: m.is_abstract,
+        compartment_buffers: m
+            .compartments
+            .iter()
+            .map(|(k, v)| (k.clone(), (**v).clone()))
+            .collect(),
+        name_translation_buffers: translation_buffers(&m.name_translations),
         comment_buffer: (*m.comment).clone(),
+        comment_translation_buffers: translation_buffers(&m.comment_translations),
 
         dragged_shape: None,
         highlight: canvas::Highlight::NONE,
@@ -5854,6 +6740,10 @@ pub fn new_umlclass_class_view<P: UmlClassProfile>(
         suppress_template_parameters: false,
         suppress_properties: false,
         suppress_operations: false,
+        force_properties: false,
+        force_operations: false,
+        member_max_width: 0.0,
+        wrap_members: false,
 
         _profile: PhantomData,
     })
@@ -5912,19 +6802,72 @@ impl<SC: StereotypeController> CustomModal for UmlClassSetupModal<SC> {
 
         result
     }
-}
-
-#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
-pub enum UmlClassRenderStyle {
-    Class,
-    StickFigure,
-}
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum UmlClassRenderStyle {
+    Class,
+    StickFigure,
+}
+
+impl UmlClassRenderStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UmlClassRenderStyle::Class => "Class",
+            UmlClassRenderStyle::StickFigure => "Stick Figure",
+        }
+    }
+}
+
+/// How a class view shows properties and operations wider than its limit
+#[derive(Clone, Copy, Default)]
+struct UmlClassMemberFit {
+    /// No limit when not positive
+    max_width: f32,
+    /// Breaks the members into lines instead of cutting them off
+    wrap: bool,
+}
+
+/// Whether a class view shows a compartment, stored as the suppress and force flags
+#[derive(Clone, Copy, PartialEq)]
+enum UmlClassCompartmentVisibility {
+    DiagramDefault,
+    Shown,
+    Hidden,
+}
+
+impl UmlClassCompartmentVisibility {
+    const VARIANTS: [Self; 3] = [Self::DiagramDefault, Self::Shown, Self::Hidden];
+
+    fn from_flags(suppress: bool, force: bool) -> Self {
+        match (suppress, force) {
+            (true, _) => Self::Hidden,
+            (false, true) => Self::Shown,
+            (false, false) => Self::DiagramDefault,
+        }
+    }
+
+    fn flags(&self) -> (bool, bool) {
+        match self {
+            Self::DiagramDefault => (false, false),
+            Self::Shown => (false, true),
+            Self::Hidden => (true, false),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::DiagramDefault => "Diagram default",
+            Self::Shown => "Shown",
+            Self::Hidden => "Hidden",
+        }
+    }
 
-impl UmlClassRenderStyle {
-    pub fn as_str(&self) -> &'static str {
+    fn is_shown(&self, hidden_by_default: bool) -> bool {
         match self {
-            UmlClassRenderStyle::Class => "Class",
-            UmlClassRenderStyle::StickFigure => "Stick Figure",
+            Self::DiagramDefault => !hidden_by_default,
+            Self::Shown => true,
+            Self::Hidden => false,
         }
     }
 }
@@ -5951,7 +6894,13 @@ pub struct UmlClassView<P: UmlClassProfile> {
     #[nh_context_serde(skip_and_default)]
     is_abstract_buffer: bool,
     #[nh_context_serde(skip_and_default)]
+    compartment_buffers: BTreeMap<String, String>,
+    #[nh_context_serde(skip_and_default)]
+    name_translation_buffers: BTreeMap<String, String>,
+    #[nh_context_serde(skip_and_default)]
     comment_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_translation_buffers: BTreeMap<String, String>,
 
     #[nh_context_serde(skip_and_default)]
     dragged_shape: Option<NHShape>,
@@ -5965,6 +6914,16 @@ pub struct UmlClassView<P: UmlClassProfile> {
     suppress_template_parameters: bool,
     suppress_properties: bool,
     suppress_operations: bool,
+    /// Shows the compartment even when the diagram hides it by default
+    #[nh_context_serde(default)]
+    force_properties: bool,
+    #[nh_context_serde(default)]
+    force_operations: bool,
+    /// Properties and operations wider than this are truncated or wrapped, 0 for no limit
+    #[nh_context_serde(default)]
+    member_max_width: f32,
+    #[nh_context_serde(default)]
+    wrap_members: bool,
 
     #[nh_context_serde(skip_and_default)]
     _profile: PhantomData<P>,
@@ -5972,6 +6931,13 @@ pub struct UmlClassView<P: UmlClassProfile> {
 
 impl<P: UmlClassProfile> UmlClassView<P> {
     const BUTTON_RADIUS: f32 = 8.0;
+
+    fn member_fit(&self) -> UmlClassMemberFit {
+        UmlClassMemberFit {
+            max_width: self.member_max_width,
+            wrap: self.wrap_members,
+        }
+    }
     fn button_rect(&self, ui_scale: f32, row_index: usize, column_index: usize) -> egui::Rect {
         let b_center = self.bounds_rect.right_top()
             + egui::Vec2::new(
@@ -6250,6 +7216,22 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             ));
         }
 
+        for c in P::class_compartments(&self.model.read().stereotype) {
+            let buffer = self.compartment_buffers.entry((*c).to_owned()).or_default();
+            if ui
+                .labeled_text_edit_multiline(format!("{}:", c), buffer)
+                .changed()
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::CompartmentChange(
+                        Arc::new((*c).to_owned()),
+                        Arc::new(buffer.clone()),
+                    ),
+                ));
+            }
+        }
+
         if ui
             .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
             .changed()
@@ -6260,6 +7242,25 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             ));
         }
 
+        ui.collapsing("Translations", |ui| {
+            if let Some((language, text)) =
+                show_translations(ui, "Name", false, &mut self.name_translation_buffers)
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::NameTranslationChange(language, text),
+                ));
+            }
+            if let Some((language, text)) =
+                show_translations(ui, "Comment", true, &mut self.comment_translation_buffers)
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::CommentTranslationChange(language, text),
+                ));
+            }
+        });
+
         ui.label("View properties");
 
         ui.horizontal(|ui| {
@@ -6313,8 +7314,36 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             &mut self.suppress_template_parameters,
             "suppress template parameters",
         );
-        ui.checkbox(&mut self.suppress_properties, "suppress properties");
-        ui.checkbox(&mut self.suppress_operations, "suppress operations");
+        for (label, suppress, force) in [
+            (
+                "Properties",
+                &mut self.suppress_properties,
+                &mut self.force_properties,
+            ),
+            (
+                "Operations",
+                &mut self.suppress_operations,
+                &mut self.force_operations,
+            ),
+        ] {
+            let mut visibility = UmlClassCompartmentVisibility::from_flags(*suppress, *force);
+            egui::ComboBox::from_label(label)
+                .selected_text(visibility.as_str())
+                .show_ui(ui, |ui| {
+                    for e in UmlClassCompartmentVisibility::VARIANTS {
+                        ui.selectable_value(&mut visibility, e, e.as_str());
+                    }
+                });
+            (*suppress, *force) = visibility.flags();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Member max width");
+            ui.add(egui::DragValue::new(&mut self.member_max_width).range(0.0..=1000.0));
+        });
+        ui.add_enabled(
+            self.member_max_width > 0.0,
+            egui::Checkbox::new(&mut self.wrap_members, "Wrap long members"),
+        );
 
         PropertiesStatus::Shown
     }
@@ -6342,6 +7371,16 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
         }
 
         let read = self.model.read();
+        let name = localized(
+            &read.name,
+            &read.name_translations,
+            &context.languages_order,
+        );
+        let comment = localized(
+            &read.comment,
+            &read.comment_translations,
+            &context.languages_order,
+        );
         let child_status = RwLock::new(TargettingStatus::NotDrawn);
         let body_color = context
             .global_colors
@@ -6398,16 +7437,24 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             canvas.draw_text(
                 p - egui::Vec2::new(0.0, -28.0),
                 egui::Align2::CENTER_TOP,
-                &read.name,
+                name,
                 canvas::CLASS_MIDDLE_FONT_SIZE,
                 egui::Color32::BLACK,
             );
         } else {
+            let fit = self.member_fit();
             let mut body = Vec::<(
                 egui::Vec2,
                 Box<dyn Fn(&mut dyn canvas::NHCanvas, egui::Pos2)>,
             )>::new();
-            if !self.suppress_properties && !self.properties_views.is_empty() {
+            let notation = q.notation();
+            if UmlClassCompartmentVisibility::from_flags(
+                self.suppress_properties,
+                self.force_properties,
+            )
+            .is_shown(notation.hide_properties)
+                && !self.properties_views.is_empty()
+            {
                 body.push((
                     rect_union_fold(
                         self.properties_views
@@ -6417,7 +7464,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                     .size(),
                     Box::new(|c, at| {
                         self.properties_views.iter().fold(at, |s, e| {
-                            let r = e.write().draw_inner(s, q, context, settings, c, tool);
+                            let r = e.write().draw_inner(s, q, c, tool, fit);
                             if r.1 != TargettingStatus::NotDrawn {
                                 *child_status.write().unwrap() = r.1;
                             }
@@ -6426,7 +7473,13 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                     }),
                 ));
             }
-            if !self.suppress_operations && !self.operations_views.is_empty() {
+            if UmlClassCompartmentVisibility::from_flags(
+                self.suppress_operations,
+                self.force_operations,
+            )
+            .is_shown(notation.hide_operations)
+                && !self.operations_views.is_empty()
+            {
                 body.push((
                     rect_union_fold(
                         self.operations_views
@@ -6436,7 +7489,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                     .size(),
                     Box::new(|c, at| {
                         self.operations_views.iter().fold(at, |s, e| {
-                            let r = e.write().draw_inner(s, q, context, settings, c, tool);
+                            let r = e.write().draw_inner(s, q, c, tool, fit);
                             if r.1 != TargettingStatus::NotDrawn {
                                 *child_status.write().unwrap() = r.1;
                             }
@@ -6445,16 +7498,59 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                     }),
                 ));
             }
+            for name in P::class_compartments(&read.stereotype)
+                .iter()
+                .filter(|_| !notation.hide_tagged_values)
+            {
+                let Some(text) = read.compartments.get(*name).cloned() else {
+                    continue;
+                };
+                let name_size = canvas
+                    .measure_text(
+                        self.position,
+                        egui::Align2::LEFT_TOP,
+                        name,
+                        canvas::CLASS_TOP_FONT_SIZE,
+                    )
+                    .size();
+                let text_size = canvas
+                    .measure_text(
+                        self.position,
+                        egui::Align2::LEFT_TOP,
+                        &text,
+                        canvas::CLASS_ITEM_FONT_SIZE,
+                    )
+                    .size();
+                body.push((
+                    egui::Vec2::new(name_size.x.max(text_size.x), name_size.y + text_size.y),
+                    Box::new(move |c, at| {
+                        c.draw_text(
+                            at,
+                            egui::Align2::LEFT_TOP,
+                            name,
+                            canvas::CLASS_TOP_FONT_SIZE,
+                            egui::Color32::DARK_GRAY,
+                        );
+                        c.draw_text(
+                            at + egui::Vec2::new(0.0, name_size.y),
+                            egui::Align2::LEFT_TOP,
+                            &text,
+                            canvas::CLASS_ITEM_FONT_SIZE,
+                            egui::Color32::BLACK,
+                        );
+                    }),
+                ));
+            }
             if settings.comment_indication == CommentIndication::TextCompartment
-                && !read.comment.is_empty()
+                && !comment.is_empty()
             {
-                let comment = read.comment.clone();
+                let comment = render_math(comment).into_owned();
                 body.push((
                     canvas
                         .measure_text(
                             self.position,
                             egui::Align2::LEFT_TOP,
-                            &read.comment,
+                            &comment,
                             canvas::CLASS_ITEM_FONT_SIZE,
                         )
                         .size(),
@@ -6473,8 +7569,10 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             self.bounds_rect = draw_uml_class(
                 canvas,
                 self.position,
-                self.stereotype_in_guillemets.clone(),
-                &read.name,
+                self.stereotype_in_guillemets
+                    .clone()
+                    .filter(|_| !notation.hide_stereotypes),
+                name,
                 None,
                 read.is_abstract,
                 &body,
@@ -7035,7 +8133,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                     }
                 }
             }
-            InsensitiveCommand::ArrangeSpecificElements(..) => {}
+            InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::MoveOrdinal(uuids, direction) => {
                 let mut undo_uuids = HashSet::new();
                 {
@@ -7139,6 +8239,48 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
                             ));
                             model.template_parameters = template_parameters.clone();
                         }
+                        UmlClassPropChange::CompartmentChange(compartment, text) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                UmlClassPropChange::CompartmentChange(
+                                    compartment.clone(),
+                                    model
+                                        .compartments
+                                        .get(&**compartment)
+                                        .cloned()
+                                        .unwrap_or_default(),
+                                ),
+                            ));
+                            if text.is_empty() {
+                                model.compartments.remove(&**compartment);
+                            } else {
+                                model
+                                    .compartments
+                                    .insert((**compartment).clone(), text.clone());
+                            }
+                        }
+                        UmlClassPropChange::NameTranslationChange(language, text) => {
+                            let previous =
+                                set_translation(&mut model.name_translations, language, text);
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                UmlClassPropChange::NameTranslationChange(
+                                    language.clone(),
+                                    previous,
+                                ),
+                            ));
+                        }
+                        UmlClassPropChange::CommentTranslationChange(language, text) => {
+                            let previous =
+                                set_translation(&mut model.comment_translations, language, text);
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                UmlClassPropChange::CommentTranslationChange(
+                                    language.clone(),
+                                    previous,
+                                ),
+                            ));
+                        }
                         UmlClassPropChange::ClassAbstractChange(is_abstract) => {
                             undo_accumulator.push(InsensitiveCommand::PropertyChange(
                                 std::iter::once(*self.uuid).collect(),
@@ -7185,7 +8327,14 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
         self.name_buffer = (*model.name).clone();
         self.template_parameters_buffer = (*model.template_parameters).clone();
         self.is_abstract_buffer = model.is_abstract;
+        self.compartment_buffers = model
+            .compartments
+            .iter()
+            .map(|(k, v)| (k.clone(), (**v).clone()))
+            .collect();
+        self.name_translation_buffers = translation_buffers(&model.name_translations);
         self.comment_buffer = (*model.comment).clone();
+        self.comment_translation_buffers = translation_buffers(&model.comment_translations);
 
         for e in &self.properties_views {
             e.write().refresh_buffers();
@@ -7303,7 +8452,10 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             name_buffer: self.name_buffer.clone(),
             template_parameters_buffer: self.template_parameters_buffer.clone(),
             is_abstract_buffer: self.is_abstract_buffer,
+            compartment_buffers: self.compartment_buffers.clone(),
+            name_translation_buffers: self.name_translation_buffers.clone(),
             comment_buffer: self.comment_buffer.clone(),
+            comment_translation_buffers: self.comment_translation_buffers.clone(),
             dragged_shape: None,
             highlight: self.highlight,
             position: self.position,
@@ -7313,6 +8465,10 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
             suppress_template_parameters: self.suppress_template_parameters,
             suppress_properties: self.suppress_properties,
             suppress_operations: self.suppress_operations,
+            force_properties: self.force_properties,
+            force_operations: self.force_operations,
+            member_max_width: self.member_max_width,
+            wrap_members: self.wrap_members,
             _profile: PhantomData,
         });
         tlc.insert(view_uuid, cloneish.clone().into());
@@ -7339,6 +8495,45 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassVi
     }
 }
 
+pub fn new_umlclass_associable_view<P: UmlClassProfile>(
+    model: &UmlClassAssociable,
+    position: egui::Pos2,
+) -> UmlClassElementView<P> {
+    match model {
+        UmlClassAssociable::Instance(inner) => UmlClassElementView::from(
+            new_umlclass_instance_view(inner.clone(), position, MGlobalColor::None),
+        ),
+        UmlClassAssociable::Class(inner) => {
+            let (properties_views, operations_views) = {
+                let r = inner.read();
+                (
+                    r.properties
+                        .iter()
+                        .map(|e| new_umlclass_property_view(e.clone()))
+                        .collect(),
+                    r.operations
+                        .iter()
+                        .map(|e| new_umlclass_operation_view(e.clone()))
+                        .collect(),
+                )
+            };
+            UmlClassElementView::from(new_umlclass_class_view(
+                inner.clone(),
+                properties_views,
+                operations_views,
+                position,
+                UmlClassRenderStyle::Class,
+                MGlobalColor::None,
+            ))
+        }
+        UmlClassAssociable::UseCase(inner) => UmlClassElementView::from(new_uml_usecase_view(
+            inner.clone(),
+            position,
+            MGlobalColor::None,
+        )),
+    }
+}
+
 pub fn new_uml_usecase<P: UmlClassProfile>(
     name: &str,
     stereotype: &str,
@@ -7382,6 +8577,16 @@ pub fn new_uml_usecase_view<P: UmlClassProfile>(
     })
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum UmlUseCaseOutline {
+    Ellipse,
+    Circle,
+    Rectangle,
+    RoundedRectangle,
+    Rhombus,
+    Parallelogram,
+}
+
 #[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
 #[nh_context_serde(is_entity)]
 pub struct UmlUseCaseView<P: UmlClassProfile> {
@@ -7433,9 +8638,20 @@ impl<P: UmlClassProfile> ElementController<UmlClassElement> for UmlUseCaseView<P
     }
 
     fn min_shape(&self) -> NHShape {
-        NHShape::Ellipse {
-            position: self.position,
-            bounds_radius: self.bounds_radius,
+        match P::usecase_outline(&self.model.read().stereotype) {
+            UmlUseCaseOutline::Ellipse | UmlUseCaseOutline::Circle => NHShape::Ellipse {
+                position: self.position,
+                bounds_radius: self.bounds_radius,
+            },
+            UmlUseCaseOutline::Rectangle
+            | UmlUseCaseOutline::RoundedRectangle
+            | UmlUseCaseOutline::Parallelogram => NHShape::Rect {
+                inner: egui::Rect::from_center_size(self.position, 2.0 * self.bounds_radius),
+            },
+            UmlUseCaseOutline::Rhombus => NHShape::Rhombus {
+                position: self.position,
+                bounds_radius: self.bounds_radius,
+            },
         }
     }
 
@@ -7444,6 +8660,59 @@ impl<P: UmlClassProfile> ElementController<UmlClassElement> for UmlUseCaseView<P
     }
 }
 
+impl<P: UmlClassProfile> UmlUseCaseView<P> {
+    fn draw_outline(
+        &self,
+        canvas: &mut dyn NHCanvas,
+        outline: UmlUseCaseOutline,
+        color: egui::Color32,
+        highlight: canvas::Highlight,
+    ) {
+        let stroke = canvas::Stroke::new_solid(1.0, egui::Color32::BLACK);
+        let rect = egui::Rect::from_center_size(self.position, 2.0 * self.bounds_radius);
+        match outline {
+            UmlUseCaseOutline::Ellipse | UmlUseCaseOutline::Circle => {
+                canvas.draw_ellipse(self.position, self.bounds_radius, color, stroke, highlight)
+            }
+            UmlUseCaseOutline::Rectangle => {
+                canvas.draw_rectangle(rect, egui::CornerRadius::ZERO, color, stroke, highlight)
+            }
+            UmlUseCaseOutline::RoundedRectangle => canvas.draw_rectangle(
+                rect,
+                egui::CornerRadius::same(rect.height().min(255.0) as u8 / 2),
+                color,
+                stroke,
+                highlight,
+            ),
+            UmlUseCaseOutline::Rhombus => canvas.draw_polygon(
+                vec![
+                    rect.center_top(),
+                    rect.right_center(),
+                    rect.center_bottom(),
+                    rect.left_center(),
+                ],
+                color,
+                stroke,
+                highlight,
+            ),
+            UmlUseCaseOutline::Parallelogram => {
+                let slant = egui::Vec2::new(rect.height() / 4.0, 0.0);
+                canvas.draw_polygon(
+                    vec![
+                        rect.left_top() + slant,
+                        rect.right_top() + slant,
+                        rect.right_bottom() - slant,
+                        rect.left_bottom() - slant,
+                    ],
+                    color,
+                    stroke,
+                    highlight,
+                )
+            }
+        }
+    }
+}
+
 impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCaseView<P> {
     fn show_properties(
         &mut self,
@@ -7537,12 +8806,16 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
 
     fn draw_in(
         &mut self,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         context: &GlobalDrawingContext,
         _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveUmlClassTool<P>)>,
     ) -> TargettingStatus {
+        let stereotype = self
+            .stereotype_in_guillemets
+            .clone()
+            .filter(|_| !q.notation().hide_stereotypes);
         // Draw shape and text
         let name_bounds = canvas.measure_text(
             self.position,
@@ -7551,7 +8824,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
             canvas::CLASS_MIDDLE_FONT_SIZE,
         );
         let mut text_bounds = name_bounds;
-        if let Some(s) = &self.stereotype_in_guillemets {
+        if let Some(s) = &stereotype {
             let stereotype_bounds = canvas.measure_text(
                 name_bounds.center_top(),
                 egui::Align2::CENTER_BOTTOM,
@@ -7561,16 +8834,28 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
             text_bounds = text_bounds.union(stereotype_bounds);
         }
 
-        self.bounds_radius = text_bounds.size() / 1.5;
+        let outline = P::usecase_outline(&self.model.read().stereotype);
+        let text_radius = text_bounds.size() / 2.0;
+        self.bounds_radius = match outline {
+            UmlUseCaseOutline::Ellipse => text_bounds.size() / 1.5,
+            UmlUseCaseOutline::Circle => egui::Vec2::splat(text_radius.length().max(10.0) + 2.0),
+            UmlUseCaseOutline::Rectangle | UmlUseCaseOutline::RoundedRectangle => {
+                text_radius + egui::Vec2::new(10.0, 8.0)
+            }
+            // Fits the text rectangle inside of the rhombus
+            UmlUseCaseOutline::Rhombus => 2.0 * text_radius + egui::Vec2::splat(8.0),
+            UmlUseCaseOutline::Parallelogram => {
+                text_radius + egui::Vec2::new(10.0 + text_radius.y / 2.0, 8.0)
+            }
+        };
 
-        canvas.draw_ellipse(
-            self.position,
-            self.bounds_radius,
+        self.draw_outline(
+            canvas,
+            outline,
             context
                 .global_colors
                 .get(&self.background_color)
                 .unwrap_or(egui::Color32::WHITE),
-            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
             self.highlight,
         );
 
@@ -7585,7 +8870,7 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
                 IS_ABSTRACT_COLOR
             },
         );
-        if let Some(s) = &self.stereotype_in_guillemets {
+        if let Some(s) = &stereotype {
             canvas.draw_text(
                 name_bounds.center_top(),
                 egui::Align2::CENTER_BOTTOM,
@@ -7602,11 +8887,10 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
                 .filter(|e| self.min_shape().contains(e.0))
                 .map(|e| e.1)
         {
-            canvas.draw_ellipse(
-                self.position,
-                self.bounds_radius,
+            self.draw_outline(
+                canvas,
+                outline,
                 t.targetting_for_section(Some(self.model())),
-                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
                 canvas::Highlight::NONE,
             );
             TargettingStatus::Drawn
@@ -7732,7 +9016,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -7790,7 +9076,10 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlUseCase
     fn refresh_buffers(&mut self) {
         let model = self.model.read();
 
-        self.stereotype_in_guillemets = if model.stereotype.is_empty() {
+        // Stereotypes with their own outline are not repeated in guillemets
+        self.stereotype_in_guillemets = if model.stereotype.is_empty()
+            || P::usecase_outline(&model.stereotype) != UmlUseCaseOutline::Ellipse
+        {
             None
         } else {
             Some(format!("«{}»", model.stereotype).into())
@@ -8023,6 +9312,16 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         None
     }
 
+    fn tree_style_change(&self, tree_style: bool) -> Option<UmlClassPropChange> {
+        Some(UmlClassPropChange::TreeStyleChange(tree_style))
+    }
+    fn as_tree_style_change(&self, change: &UmlClassPropChange) -> Option<bool> {
+        match change {
+            UmlClassPropChange::TreeStyleChange(tree_style) => Some(*tree_style),
+            _ => None,
+        }
+    }
+
     fn show_properties(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
@@ -8390,7 +9689,7 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         &self,
         _center: egui::Pos2,
         _highlight: canvas::Highlight,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         _context: &GlobalDrawingContext,
         _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         _canvas: &mut dyn canvas::NHCanvas,
@@ -8398,6 +9697,10 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
     ) -> Result<(), Arc<String>> {
         match self.temporaries.midpoint_label.clone() {
             None => Ok(()),
+            Some(label) if q.notation().hide_stereotypes => match strip_stereotype(&label) {
+                "" => Ok(()),
+                e => Err(Arc::new(e.to_owned())),
+            },
             Some(label) => Err(label),
         }
     }
@@ -8419,6 +9722,16 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         Ok(())
     }
 
+    fn curved_change(&self, curved: bool) -> Option<UmlClassPropChange> {
+        Some(UmlClassPropChange::CurvedChange(curved))
+    }
+    fn as_curved_change(&self, change: &UmlClassPropChange) -> Option<bool> {
+        match change {
+            UmlClassPropChange::CurvedChange(curved) => Some(*curved),
+            _ => None,
+        }
+    }
+
     fn show_properties(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
@@ -8545,15 +9858,16 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
     fn refresh_buffers(&mut self) {
         let model = self.model.read();
 
+        let line_type = P::dependency_line_type(&model.stereotype);
         self.temporaries.arrow_data.clear();
         self.temporaries.arrow_data.insert(
             (false, *model.source.uuid()),
-            ArrowData::new_labelless(canvas::LineType::Dashed, canvas::ArrowheadType::None),
+            ArrowData::new_labelless(line_type, canvas::ArrowheadType::None),
         );
         self.temporaries.arrow_data.insert(
             (true, *model.target.uuid()),
             ArrowData::new_labelless(
-                canvas::LineType::Dashed,
+                line_type,
                 if model.target_arrow_open {
                     canvas::ArrowheadType::OpenTriangle
                 } else {
@@ -8636,6 +9950,30 @@ pub fn new_umlclass_association<P: UmlClassProfile>(
         new_umlclass_association_view(link_model.clone(), center_point, source.1, target.1);
     (link_model, link_view)
 }
+pub fn new_umlclass_composition<P: UmlClassProfile>(
+    stereotype: &str,
+    name: &str,
+    source_label_multiplicity: &str,
+    target_label_multiplicity: &str,
+    center_point: Option<(ViewUuid, egui::Pos2)>,
+    source: (UmlClassAssociable, UmlClassElementView<P>),
+    target: (UmlClassAssociable, UmlClassElementView<P>),
+) -> (ERef<UmlClassAssociation>, ERef<AssociationViewT<P>>) {
+    let mut association = UmlClassAssociation::new(
+        ModelUuid::now_v7(),
+        stereotype.to_owned(),
+        name.to_owned(),
+        source.0,
+        source_label_multiplicity.to_owned(),
+        target.0,
+        target_label_multiplicity.to_owned(),
+    );
+    association.source_aggregation = UmlClassAssociationAggregation::Composite;
+    let link_model = ERef::new(association);
+    let link_view =
+        new_umlclass_association_view(link_model.clone(), center_point, source.1, target.1);
+    (link_model, link_view)
+}
 pub fn new_umlclass_association_view<P: UmlClassProfile>(
     model: ERef<UmlClassAssociation>,
     center_point: Option<(ViewUuid, egui::Pos2)>,
@@ -8710,7 +10048,7 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         &self,
         _center: egui::Pos2,
         _highlight: canvas::Highlight,
-        _q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         _context: &GlobalDrawingContext,
         _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         _canvas: &mut dyn canvas::NHCanvas,
@@ -8718,6 +10056,10 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
     ) -> Result<(), Arc<String>> {
         match self.temporaries.midpoint_label.clone() {
             None => Ok(()),
+            Some(label) if q.notation().hide_stereotypes => match strip_stereotype(&label) {
+                "" => Ok(()),
+                e => Err(Arc::new(e.to_owned())),
+            },
             Some(label) => Err(label),
         }
     }
@@ -8739,6 +10081,16 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         Ok(())
     }
 
+    fn curved_change(&self, curved: bool) -> Option<UmlClassPropChange> {
+        Some(UmlClassPropChange::CurvedChange(curved))
+    }
+    fn as_curved_change(&self, change: &UmlClassPropChange) -> Option<bool> {
+        match change {
+            UmlClassPropChange::CurvedChange(curved) => Some(*curved),
+            _ => None,
+        }
+    }
+
     fn show_properties(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
@@ -9130,29 +10482,20 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
     fn refresh_buffers(&mut self) {
         let model = self.model.read();
 
-        fn ah(
-            n: UmlClassAssociationNavigability,
-            a: UmlClassAssociationAggregation,
-        ) -> canvas::ArrowheadType {
-            match a {
-                UmlClassAssociationAggregation::None => match n {
-                    UmlClassAssociationNavigability::Unspecified => canvas::ArrowheadType::None,
-                    UmlClassAssociationNavigability::NonNavigable => canvas::ArrowheadType::None,
-                    UmlClassAssociationNavigability::Navigable => {
-                        canvas::ArrowheadType::OpenTriangle
-                    }
-                },
-                UmlClassAssociationAggregation::Shared => canvas::ArrowheadType::EmptyRhombus,
-                UmlClassAssociationAggregation::Composite => canvas::ArrowheadType::FullRhombus,
-            }
-        }
         self.temporaries.arrow_data.clear();
+        let (source_arrowhead, source_multiplicity_shown) = P::association_ending(
+            &model.source_label_multiplicity,
+            model.source_navigability,
+            model.source_aggregation,
+        );
         self.temporaries.arrow_data.insert(
             (false, *model.source.uuid()),
             ArrowData {
                 line_type: canvas::LineType::Solid,
-                arrowhead_type: ah(model.source_navigability, model.source_aggregation),
-                multiplicity: if !model.source_label_multiplicity.is_empty() {
+                arrowhead_type: source_arrowhead,
+                multiplicity: if source_multiplicity_shown
+                    && !model.source_label_multiplicity.is_empty()
+                {
                     Some(model.source_label_multiplicity.clone())
                 } else {
                     None
@@ -9169,12 +10512,19 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
                 },
             },
         );
+        let (target_arrowhead, target_multiplicity_shown) = P::association_ending(
+            &model.target_label_multiplicity,
+            model.target_navigability,
+            model.target_aggregation,
+        );
         self.temporaries.arrow_data.insert(
             (true, *model.target.uuid()),
             ArrowData {
                 line_type: canvas::LineType::Solid,
-                arrowhead_type: ah(model.target_navigability, model.target_aggregation),
-                multiplicity: if !model.target_label_multiplicity.is_empty() {
+                arrowhead_type: target_arrowhead,
+                multiplicity: if target_multiplicity_shown
+                    && !model.target_label_multiplicity.is_empty()
+                {
                     Some(model.target_label_multiplicity.clone())
                 } else {
                     None
@@ -9422,6 +10772,16 @@ impl<P: UmlClassProfile> MulticonnectionAdapter<UmlClassDomain<P>>
         None
     }
 
+    fn tree_style_change(&self, tree_style: bool) -> Option<UmlClassPropChange> {
+        Some(UmlClassPropChange::TreeStyleChange(tree_style))
+    }
+    fn as_tree_style_change(&self, change: &UmlClassPropChange) -> Option<bool> {
+        match change {
+            UmlClassPropChange::TreeStyleChange(tree_style) => Some(*tree_style),
+            _ => None,
+        }
+    }
+
     fn show_properties(
         &mut self,
         q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
@@ -9710,9 +11070,9 @@ pub fn new_umlclass_comment_view<P: UmlClassProfile>(
         uuid: ViewUuid::now_v7().into(),
         model: model.clone(),
 
-        display_text: String::new(),
         stereotype_controller: Default::default(),
         text_buffer: (*m.text).clone(),
+        text_translation_buffers: translation_buffers(&m.text_translations),
 
         dragged_shape: None,
         highlight: canvas::Highlight::NONE,
@@ -9731,12 +11091,12 @@ pub struct UmlClassCommentView<P: UmlClassProfile> {
     #[nh_context_serde(entity)]
     pub model: ERef<UmlClassComment>,
 
-    #[nh_context_serde(skip_and_default)]
-    display_text: String,
     #[nh_context_serde(skip_and_default)]
     stereotype_controller: P::CommentStereotypeController,
     #[nh_context_serde(skip_and_default)]
     text_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    text_translation_buffers: BTreeMap<String, String>,
 
     #[nh_context_serde(skip_and_default)]
     dragged_shape: Option<NHShape>,
@@ -9829,6 +11189,23 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
             ));
         }
 
+        egui::CollapsingHeader::new("Preview")
+            .default_open(true)
+            .show(ui, |ui| {
+                crate::common::rich_text::show_markdown(ui, &self.text_buffer);
+            });
+
+        ui.collapsing("Translations", |ui| {
+            if let Some((language, text)) =
+                show_translations(ui, "Text", true, &mut self.text_translation_buffers)
+            {
+                commands.push(InsensitiveCommand::PropertyChange(
+                    q.selected_views(),
+                    UmlClassPropChange::NameTranslationChange(language, text),
+                ));
+            }
+        });
+
         ui.label("View properties");
 
         ui.horizontal(|ui| {
@@ -9898,12 +11275,28 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
 
     fn draw_in(
         &mut self,
-        _: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
+        q: &<UmlClassDomain<P> as Domain>::QueryableT<'_>,
         context: &GlobalDrawingContext,
         _settings: &<UmlClassDomain<P> as Domain>::SettingsT,
         canvas: &mut dyn NHCanvas,
         tool: &Option<(egui::Pos2, &NaiveUmlClassTool<P>)>,
     ) -> TargettingStatus {
+        let display_text = {
+            let model = self.model.read();
+            let mut s = String::new();
+            if !q.notation().hide_stereotypes && !model.stereotype.is_empty() {
+                s.push('«');
+                s.push_str(&model.stereotype);
+                s.push_str("»\n");
+            }
+            s.push_str(&render_math(localized(
+                &model.text,
+                &model.text_translations,
+                &context.languages_order,
+            )));
+            s
+        };
+        let text = display_text.as_str();
         let align_offset = egui::Vec2 {
             x: match self.align.x() {
                 egui::Align::Min => -Self::CORNER_SIZE,
@@ -9916,18 +11309,18 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
                 egui::Align::Max => -Self::CORNER_SIZE,
             },
         };
-        self.bounds_rect = canvas
-            .measure_text(
-                self.position,
-                self.align,
-                &self.display_text,
-                canvas::CLASS_MIDDLE_FONT_SIZE,
-            )
-            .expand2(egui::Vec2 {
-                x: Self::CORNER_SIZE,
-                y: Self::CORNER_SIZE,
-            })
-            .translate(align_offset);
+        self.bounds_rect = crate::common::rich_text::measure_markdown(
+            canvas,
+            self.position,
+            self.align,
+            text,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        )
+        .expand2(egui::Vec2 {
+            x: Self::CORNER_SIZE,
+            y: Self::CORNER_SIZE,
+        })
+        .translate(align_offset);
 
         canvas.draw_polygon(
             [
@@ -9976,10 +11369,11 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
             canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
             self.highlight,
         );
-        canvas.draw_text(
+        crate::common::rich_text::draw_markdown(
+            canvas,
             self.position + align_offset,
             self.align,
-            &self.display_text,
+            text,
             canvas::CLASS_MIDDLE_FONT_SIZE,
             egui::Color32::BLACK,
         );
@@ -10199,7 +11593,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -10223,6 +11619,17 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
                             ));
                             model.text = text.clone();
                         }
+                        UmlClassPropChange::NameTranslationChange(language, text) => {
+                            let previous =
+                                set_translation(&mut model.text_translations, language, text);
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                UmlClassPropChange::NameTranslationChange(
+                                    language.clone(),
+                                    previous,
+                                ),
+                            ));
+                        }
                         UmlClassPropChange::ColorChange(ColorChangeData { slot: 0, color }) => {
                             undo_accumulator.push(InsensitiveCommand::PropertyChange(
                                 std::iter::once(*self.uuid).collect(),
@@ -10258,18 +11665,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
     fn refresh_buffers(&mut self) {
         let model = self.model.read();
 
-        self.display_text = {
-            let mut s = String::new();
-            if !model.stereotype.is_empty() {
-                s.push('«');
-                s.push_str(&model.stereotype);
-                s.push_str("»\n");
-            }
-            s.push_str(&model.text);
-            s
-        };
         self.stereotype_controller.refresh(&model.stereotype);
         self.text_buffer = (*model.text).clone();
+        self.text_translation_buffers = translation_buffers(&model.text_translations);
     }
 
     fn head_count(
@@ -10308,9 +11706,9 @@ impl<P: UmlClassProfile> ElementControllerGen2<UmlClassDomain<P>> for UmlClassCo
         let cloneish = ERef::new(Self {
             uuid: view_uuid.into(),
             model: modelish,
-            display_text: self.display_text.clone(),
             stereotype_controller: self.stereotype_controller.clone(),
             text_buffer: self.text_buffer.clone(),
+            text_translation_buffers: self.text_translation_buffers.clone(),
             dragged_shape: None,
             highlight: self.highlight,
             position: self.position,
@@ -0,0 +1,252 @@
+use crate::{
+    common::{controller::TextExportFormat, csv},
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassComment, UmlClassCommentLink,
+        UmlClassDependency, UmlClassDiagram, UmlClassGeneralization, UmlClassInstance,
+        UmlClassPackage, UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+    },
+};
+
+pub const CSV_CLASSES_FORMAT: TextExportFormat = TextExportFormat {
+    name: "CSV class table",
+    extensions: &["csv"],
+};
+
+pub const CSV_ASSOCIATIONS_FORMAT: TextExportFormat = TextExportFormat {
+    name: "CSV association table",
+    extensions: &["csv"],
+};
+
+const CLASS_COLUMNS: [&str; 9] = [
+    "package",
+    "class",
+    "stereotype",
+    "abstract",
+    "attribute",
+    "visibility",
+    "type",
+    "multiplicity",
+    "default",
+];
+
+const ASSOCIATION_COLUMNS: [&str; 13] = [
+    "package",
+    "association",
+    "stereotype",
+    "source",
+    "source role",
+    "source multiplicity",
+    "source navigability",
+    "source aggregation",
+    "target",
+    "target role",
+    "target multiplicity",
+    "target navigability",
+    "target aggregation",
+];
+
+fn associable_name(e: &UmlClassAssociable) -> String {
+    match e {
+        UmlClassAssociable::Instance(inner) => {
+            let r = inner.read();
+            format!("{}: {}", r.instance_name, r.instance_type)
+        }
+        UmlClassAssociable::Class(inner) => (*inner.read().name).clone(),
+        UmlClassAssociable::UseCase(inner) => (*inner.read().name).clone(),
+    }
+}
+
+#[derive(Default)]
+struct CsvTableCollector {
+    /// Names of the packages being visited, joined with `::` in the package column
+    packages: Vec<String>,
+    classes: Vec<String>,
+    associations: Vec<String>,
+}
+
+impl CsvTableCollector {
+    fn package(&self) -> String {
+        self.packages.join("::")
+    }
+}
+
+impl UmlClassVisitor for CsvTableCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        self.packages.push((*package.name).clone());
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+        self.packages.pop();
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let package = self.package();
+        let is_abstract = if class.is_abstract { "yes" } else { "" };
+        let class_columns = [
+            package.as_str(),
+            &class.name,
+            &class.stereotype,
+            is_abstract,
+        ];
+        if class.properties.is_empty() {
+            self.classes.push(csv::join_line(&class_columns));
+        }
+        for p in &class.properties {
+            let p = p.read();
+            let visibility = p.visibility.as_ref().map(|e| e.as_str()).unwrap_or("");
+            let mut row = class_columns.to_vec();
+            row.extend([
+                p.name.as_str(),
+                visibility,
+                &p.value_type,
+                &p.multiplicity,
+                &p.default_value,
+            ]);
+            self.classes.push(csv::join_line(&row));
+        }
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, _generalization: &UmlClassGeneralization) {}
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, a: &UmlClassAssociation) {
+        let package = self.package();
+        let (source, target) = (associable_name(&a.source), associable_name(&a.target));
+        self.associations.push(csv::join_line(&[
+            &package,
+            &a.name,
+            &a.stereotype,
+            &source,
+            &a.source_label_role,
+            &a.source_label_multiplicity,
+            a.source_navigability.name(),
+            a.source_aggregation.name(),
+            &target,
+            &a.target_label_role,
+            &a.target_label_multiplicity,
+            a.target_navigability.name(),
+            a.target_aggregation.name(),
+        ]));
+    }
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+fn collect(diagram: &UmlClassDiagram) -> CsvTableCollector {
+    let mut collector = CsvTableCollector::default();
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+    collector
+}
+
+fn table(columns: &[&str], rows: Vec<String>) -> String {
+    let mut out = csv::join_line(columns);
+    out.push('\n');
+    for r in rows {
+        out.push_str(&r);
+        out.push('\n');
+    }
+    out
+}
+
+/// Listing of classes with one row per attribute, classes without attributes have a single row
+pub fn class_table(diagram: &UmlClassDiagram) -> String {
+    table(&CLASS_COLUMNS, collect(diagram).classes)
+}
+
+/// Listing of associations with one row per association, describing both of its ends
+pub fn association_table(diagram: &UmlClassDiagram) -> String {
+    table(&ASSOCIATION_COLUMNS, collect(diagram).associations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::{eref::ERef, ufoption::UFOption, uuid::ModelUuid},
+        domains::umlclass::umlclass_models::{
+            UmlClassElement, UmlClassPackageKind, UmlClassProperty, UmlClassVisibilityKind,
+        },
+    };
+
+    #[test]
+    fn tables_are_listed() {
+        let property = ERef::new(UmlClassProperty::new(
+            ModelUuid::now_v7(),
+            UFOption::Some(UmlClassVisibilityKind::Private),
+            "note".to_owned(),
+            "String".to_owned(),
+            "0..1".to_owned(),
+            "\"n/a\", none".to_owned(),
+            String::new(),
+        ));
+        let class = |name: &str, properties| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                false,
+                properties,
+                vec![],
+            ))
+        };
+        let order = class("Order", vec![property]);
+        let customer = class("Customer", vec![]);
+        let mut places = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            "places".to_owned(),
+            customer.clone().into(),
+            "1".to_owned(),
+            order.clone().into(),
+            "*".to_owned(),
+        );
+        places.target_label_role = "orders".to_owned().into();
+        let package = UmlClassPackage::new(
+            ModelUuid::now_v7(),
+            "sales".to_owned(),
+            String::new(),
+            UmlClassPackageKind::default(),
+            vec![UmlClassElement::Class(order)],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Sales".to_owned(),
+            vec![
+                UmlClassElement::Package(ERef::new(package)),
+                UmlClassElement::Class(customer),
+                UmlClassElement::Association(ERef::new(places)),
+            ],
+        );
+
+        assert_eq!(
+            class_table(&diagram),
+            "package,class,stereotype,abstract,attribute,visibility,type,multiplicity,default\n\
+             sales,Order,,,note,Private,String,0..1,\"\"\"n/a\"\", none\"\n\
+             ,Customer,,\n"
+        );
+        let associations = association_table(&diagram);
+        let rows: Vec<_> = associations.lines().map(csv::split_line).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[1],
+            [
+                "",
+                "places",
+                "",
+                "Customer",
+                "",
+                "1",
+                "Unspecified",
+                "None",
+                "Order",
+                "orders",
+                "*",
+                "Unspecified",
+                "None",
+            ]
+        );
+    }
+}
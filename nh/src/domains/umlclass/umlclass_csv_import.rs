@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use super::umlclass_plantuml_import::{PlantUmlClass, PlantUmlClassDiagram, parse_member};
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        csv,
+        uuid::ViewUuid,
+    },
+};
+
+/// Separator of attributes within the attribute column
+const ATTRIBUTE_SEPARATOR: char = ';';
+
+/// Reads classes from rows of name, stereotype and attribute list.
+///
+/// Rows are tab separated when the first row contains a tab, comma separated otherwise.
+/// Attributes are written as in PlantUML (`+name: Type`) and separated by semicolons.
+/// Rows repeating a class name add further attributes to the class.
+pub fn parse_class_csv(source: &str) -> Result<PlantUmlClassDiagram, String> {
+    let mut diagram = PlantUmlClassDiagram::default();
+    let mut indices = HashMap::new();
+    let mut delimiter = None;
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let delimiter = *delimiter.get_or_insert(if line.contains('\t') { '\t' } else { ',' });
+        let fields = csv::split_line_with(line, delimiter);
+        let field = |i: usize| fields.get(i).map(|e| e.as_str()).unwrap_or("");
+
+        if diagram.classes.is_empty()
+            && ["name", "class"].contains(&field(0).to_lowercase().as_str())
+            && field(1).eq_ignore_ascii_case("stereotype")
+        {
+            continue;
+        }
+        if field(0).is_empty() {
+            return Err(format!("line {}: class without a name", line_no));
+        }
+
+        let class_idx = *indices.entry(field(0).to_owned()).or_insert_with(|| {
+            diagram.classes.push(PlantUmlClass {
+                name: field(0).to_owned(),
+                stereotype: String::new(),
+                is_abstract: false,
+                members: Vec::new(),
+                package: None,
+            });
+            diagram.classes.len() - 1
+        });
+        let class = &mut diagram.classes[class_idx];
+        if class.stereotype.is_empty() {
+            class.stereotype = field(1)
+                .trim_start_matches("<<")
+                .trim_end_matches(">>")
+                .trim()
+                .to_owned();
+        }
+        for attribute in field(2)
+            .split(ATTRIBUTE_SEPARATOR)
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            match parse_member(attribute) {
+                Some(member) => class.members.push(member),
+                None => diagram.skipped_lines.push(line_no),
+            }
+        }
+    }
+
+    if diagram.classes.is_empty() {
+        return Err("no classes found".to_owned());
+    }
+    Ok(diagram)
+}
+
+pub struct CsvImportTab {
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for CsvImportTab {
+    fn default() -> Self {
+        Self {
+            source: "name,stereotype,attributes\nCustomer,entity,\"name: String; email: String\"\nOrder,entity,\"number: Integer; created: Date\"\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for CsvImportTab {
+    fn title(&self) -> String {
+        "CSV Class Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("CSV or TSV with name, stereotype and attributes separated by semicolons");
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_class_csv(&self.source) {
+                    Ok(diagram) => {
+                        let (uuid, controller) =
+                            super::umlclass_controllers::from_plantuml(&diagram);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        let mut message = format!(
+                            "Imported {} classes with {} attributes",
+                            diagram.classes.len(),
+                            diagram
+                                .classes
+                                .iter()
+                                .map(|e| e.members.len())
+                                .sum::<usize>(),
+                        );
+                        if !diagram.skipped_lines.is_empty() {
+                            message.push_str(&format!(
+                                ", skipped attributes on lines {}",
+                                diagram
+                                    .skipped_lines
+                                    .iter()
+                                    .map(|e| e.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ));
+                        }
+                        Ok(message)
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_are_read_from_csv_and_tsv() {
+        let d = parse_class_csv(
+            "Name,Stereotype,Attributes\n\
+             # vocabulary\n\
+             Customer,<<entity>>,\"+name: String; -email: String\"\n\
+             Order,,number: Integer\n\
+             Customer,,int age\n",
+        )
+        .unwrap();
+        let names: Vec<_> = d.classes.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Customer", "Order"]);
+        assert_eq!(d.classes[0].stereotype, "entity");
+        let attributes: Vec<_> = d.classes[0]
+            .members
+            .iter()
+            .map(|e| (e.name.as_str(), e.value_type.as_str()))
+            .collect();
+        assert_eq!(
+            attributes,
+            [("name", "String"), ("email", "String"), ("age", "int")]
+        );
+
+        let d = parse_class_csv("Invoice\tdocument\ttotal: Decimal, in EUR\n").unwrap();
+        assert_eq!(d.classes[0].stereotype, "document");
+        assert_eq!(d.classes[0].members[0].value_type, "Decimal, in EUR");
+
+        assert!(parse_class_csv(",entity,\n").is_err());
+    }
+}
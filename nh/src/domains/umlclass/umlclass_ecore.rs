@@ -0,0 +1,1092 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use eframe::egui;
+
+use super::umlclass_plantuml_import::{
+    PlantUmlAssociationEnd, PlantUmlClass, PlantUmlClassDiagram, PlantUmlLink, PlantUmlLinkKind,
+    PlantUmlMember, PlantUmlPackage,
+};
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand, TextExportFormat},
+        uuid::{ModelUuid, ViewUuid},
+        xml::{self, XmlElement, escape},
+    },
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassAssociationAggregation,
+        UmlClassAssociationNavigability, UmlClassComment, UmlClassCommentLink, UmlClassDependency,
+        UmlClassDiagram, UmlClassGeneralization, UmlClassInstance, UmlClassPackage,
+        UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+    },
+};
+
+pub const ECORE_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Ecore",
+    extensions: &["ecore"],
+};
+
+const ECORE_URI: &str = "http://www.eclipse.org/emf/2002/Ecore";
+
+/// Ecore data types of UML primitive types, the first entry of each data type is used on import
+const ECORE_TYPES: [(&str, &str); 14] = [
+    ("String", "EString"),
+    ("Integer", "EInt"),
+    ("int", "EInt"),
+    ("UnlimitedNatural", "EInt"),
+    ("Long", "ELong"),
+    ("Real", "EDouble"),
+    ("Double", "EDouble"),
+    ("Float", "EFloat"),
+    ("Decimal", "EBigDecimal"),
+    ("Boolean", "EBoolean"),
+    ("bool", "EBoolean"),
+    ("Date", "EDate"),
+    ("DateTime", "EDate"),
+    ("Time", "EDate"),
+];
+
+/// Bounds of a multiplicity, the upper bound of unbounded multiplicities is -1
+fn multiplicity_to_bounds(multiplicity: &str) -> (i64, i64) {
+    let upper = |e: &str| match e.trim() {
+        "*" => -1,
+        e => e.parse().unwrap_or(-1),
+    };
+    match multiplicity.trim() {
+        "" => (1, 1),
+        "*" => (0, -1),
+        m => match m.split_once("..") {
+            Some((lower, u)) => (lower.trim().parse().unwrap_or(0), upper(u)),
+            None => m.parse().map(|e| (e, e)).unwrap_or((0, -1)),
+        },
+    }
+}
+
+fn bounds_to_multiplicity(lower: i64, upper: i64) -> String {
+    match (lower, upper) {
+        (0, -1) => "*".to_owned(),
+        (l, -1 | -2) => format!("{}..*", l),
+        (l, u) if l == u => l.to_string(),
+        (l, u) => format!("{}..{}", l, u),
+    }
+}
+
+/// Ecore names must be identifiers, other characters are replaced
+fn identifier(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        "model".to_owned()
+    } else {
+        name
+    }
+}
+
+struct EcoreFeature {
+    name: String,
+    value_type: String,
+    multiplicity: String,
+    default_value: String,
+    is_id: bool,
+    is_read_only: bool,
+    is_derived: bool,
+}
+
+struct EcoreOperation {
+    name: String,
+    parameters: String,
+    return_type: String,
+}
+
+struct EcoreClass {
+    uuid: ModelUuid,
+    name: String,
+    is_abstract: bool,
+    is_interface: bool,
+    is_enumeration: bool,
+    features: Vec<EcoreFeature>,
+    operations: Vec<EcoreOperation>,
+}
+
+#[derive(Default)]
+struct EcorePackage {
+    name: String,
+    classes: Vec<EcoreClass>,
+    subpackages: Vec<EcorePackage>,
+}
+
+struct EcoreReference {
+    name: String,
+    target: ModelUuid,
+    multiplicity: String,
+    containment: bool,
+    /// Owner and name of the opposite reference
+    opposite: Option<(ModelUuid, String)>,
+}
+
+struct EcoreCollector {
+    /// Packages being visited, the first one is the root package
+    packages: Vec<EcorePackage>,
+    supertypes: HashMap<ModelUuid, Vec<ModelUuid>>,
+    references: HashMap<ModelUuid, Vec<EcoreReference>>,
+}
+
+fn reference_name(role: &str, target: &UmlClass) -> String {
+    if !role.is_empty() {
+        return identifier(role);
+    }
+    let mut chars = target.name.chars();
+    chars
+        .next()
+        .map(|c| c.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+impl UmlClassVisitor for EcoreCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        self.packages.push(EcorePackage {
+            name: identifier(&package.name),
+            ..Default::default()
+        });
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+        if let Some(p) = self.packages.pop()
+            && let Some(parent) = self.packages.last_mut()
+        {
+            parent.subpackages.push(p);
+        }
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let is_enumeration = class.stereotype.eq_ignore_ascii_case("enumeration");
+        let features = class
+            .properties
+            .iter()
+            .map(|e| e.read())
+            .map(|e| EcoreFeature {
+                name: (*e.name).clone(),
+                value_type: e.value_type.trim().to_owned(),
+                multiplicity: (*e.multiplicity).clone(),
+                default_value: (*e.default_value).clone(),
+                is_id: e.is_id,
+                is_read_only: e.is_read_only,
+                is_derived: e.is_derived,
+            })
+            .collect();
+        let operations = class
+            .operations
+            .iter()
+            .map(|e| e.read())
+            .map(|e| EcoreOperation {
+                name: (*e.name).clone(),
+                parameters: (*e.parameters).clone(),
+                return_type: e.return_type.trim().to_owned(),
+            })
+            .collect();
+        if let Some(p) = self.packages.last_mut() {
+            p.classes.push(EcoreClass {
+                uuid: *class.uuid,
+                name: identifier(&class.name),
+                is_abstract: class.is_abstract,
+                is_interface: class.stereotype.eq_ignore_ascii_case("interface"),
+                is_enumeration,
+                features,
+                operations,
+            });
+        }
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, generalization: &UmlClassGeneralization) {
+        for source in &generalization.sources {
+            self.supertypes
+                .entry(*source.read().uuid)
+                .or_default()
+                .extend(generalization.targets.iter().map(|e| *e.read().uuid));
+        }
+    }
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, a: &UmlClassAssociation) {
+        let (UmlClassAssociable::Class(source), UmlClassAssociable::Class(target)) =
+            (&a.source, &a.target)
+        else {
+            return;
+        };
+        let (source, target) = (source.read(), target.read());
+        // Associations without any navigability are read from source to target
+        let to_target = a.target_navigability == UmlClassAssociationNavigability::Navigable
+            || (a.target_navigability == UmlClassAssociationNavigability::Unspecified
+                && a.source_navigability == UmlClassAssociationNavigability::Unspecified);
+        let to_source = a.source_navigability == UmlClassAssociationNavigability::Navigable;
+        let target_name = reference_name(&a.target_label_role, &target);
+        let source_name = reference_name(&a.source_label_role, &source);
+
+        if to_target {
+            self.references
+                .entry(*source.uuid)
+                .or_default()
+                .push(EcoreReference {
+                    name: target_name.clone(),
+                    target: *target.uuid,
+                    multiplicity: (*a.target_label_multiplicity).clone(),
+                    containment: a.source_aggregation == UmlClassAssociationAggregation::Composite,
+                    opposite: to_source.then(|| (*target.uuid, source_name.clone())),
+                });
+        }
+        if to_source {
+            self.references
+                .entry(*target.uuid)
+                .or_default()
+                .push(EcoreReference {
+                    name: source_name,
+                    target: *source.uuid,
+                    multiplicity: (*a.source_label_multiplicity).clone(),
+                    containment: a.target_aggregation == UmlClassAssociationAggregation::Composite,
+                    opposite: to_target.then(|| (*source.uuid, target_name)),
+                });
+        }
+    }
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+struct EcoreWriter<'a> {
+    out: String,
+    /// Paths of classes, such as `#//subpackage/Class`
+    class_paths: HashMap<ModelUuid, String>,
+    class_names: HashMap<&'a str, String>,
+    /// Data types which are neither Ecore types nor classes, by their names
+    data_types: BTreeMap<String, String>,
+    supertypes: &'a HashMap<ModelUuid, Vec<ModelUuid>>,
+    references: &'a HashMap<ModelUuid, Vec<EcoreReference>>,
+}
+
+impl<'a> EcoreWriter<'a> {
+    fn collect_paths(&mut self, package: &'a EcorePackage, prefix: &str) {
+        for c in &package.classes {
+            let path = format!("{}{}", prefix, c.name);
+            self.class_names.entry(&c.name).or_insert(path.clone());
+            self.class_paths.insert(c.uuid, path);
+        }
+        for p in &package.subpackages {
+            self.collect_paths(p, &format!("{}{}/", prefix, p.name));
+        }
+    }
+
+    fn collect_data_types(&mut self, package: &'a EcorePackage) {
+        for c in &package.classes {
+            let types = c.features.iter().map(|e| e.value_type.as_str()).chain(
+                c.operations.iter().flat_map(|o| {
+                    parameters(&o.parameters)
+                        .map(|e| e.1)
+                        .chain([o.return_type.as_str()])
+                }),
+            );
+            for t in types {
+                if !t.is_empty()
+                    && !self.class_names.contains_key(t)
+                    && !ECORE_TYPES.iter().any(|e| e.0 == t)
+                {
+                    self.data_types.insert(identifier(t), t.to_owned());
+                }
+            }
+        }
+        for p in &package.subpackages {
+            self.collect_data_types(p);
+        }
+    }
+
+    /// Reference to the type, and whether it is a class
+    fn type_reference(&self, value_type: &str) -> (String, bool) {
+        if let Some(path) = self.class_names.get(value_type) {
+            (path.clone(), true)
+        } else if let Some((_, t)) = ECORE_TYPES.iter().find(|e| e.0 == value_type) {
+            (format!("ecore:EDataType {}#//{}", ECORE_URI, t), false)
+        } else {
+            (format!("#//{}", identifier(value_type)), false)
+        }
+    }
+
+    fn line(&mut self, depth: usize, tag: &str, attributes: &[(&str, String)], has_content: bool) {
+        let _ = write!(self.out, "{:1$}<{2}", "", depth * 2, tag);
+        for (name, value) in attributes {
+            let _ = write!(self.out, " {}=\"{}\"", name, escape(value));
+        }
+        self.out.push_str(if has_content { ">\n" } else { "/>\n" });
+    }
+
+    fn close(&mut self, depth: usize, tag: &str) {
+        let _ = writeln!(self.out, "{:1$}</{2}>", "", depth * 2, tag);
+    }
+
+    fn typed(&self, attributes: &mut Vec<(&str, String)>, value_type: &str) {
+        if !value_type.is_empty() {
+            attributes.push(("eType", self.type_reference(value_type).0));
+        }
+    }
+
+    fn bounds(attributes: &mut Vec<(&str, String)>, multiplicity: &str) {
+        let (lower, upper) = multiplicity_to_bounds(multiplicity);
+        if lower != 0 {
+            attributes.push(("lowerBound", lower.to_string()));
+        }
+        if upper != 1 {
+            attributes.push(("upperBound", upper.to_string()));
+        }
+    }
+
+    fn write_class(&mut self, c: &EcoreClass, depth: usize) {
+        if c.is_enumeration {
+            let attributes = [
+                ("xsi:type", "ecore:EEnum".to_owned()),
+                ("name", c.name.clone()),
+            ];
+            self.line(depth, "eClassifiers", &attributes, !c.features.is_empty());
+            for (i, l) in c.features.iter().enumerate() {
+                let mut attributes = vec![("name", identifier(&l.name))];
+                if i > 0 {
+                    attributes.push(("value", i.to_string()));
+                }
+                self.line(depth + 1, "eLiterals", &attributes, false);
+            }
+            if !c.features.is_empty() {
+                self.close(depth, "eClassifiers");
+            }
+            return;
+        }
+
+        let mut attributes = vec![
+            ("xsi:type", "ecore:EClass".to_owned()),
+            ("name", c.name.clone()),
+        ];
+        if c.is_abstract || c.is_interface {
+            attributes.push(("abstract", "true".to_owned()));
+        }
+        if c.is_interface {
+            attributes.push(("interface", "true".to_owned()));
+        }
+        let supertypes: Vec<_> = self
+            .supertypes
+            .get(&c.uuid)
+            .into_iter()
+            .flatten()
+            .flat_map(|e| self.class_paths.get(e))
+            .map(|e| e.as_str())
+            .collect();
+        if !supertypes.is_empty() {
+            attributes.push(("eSuperTypes", supertypes.join(" ")));
+        }
+        let references = self.references.get(&c.uuid).map_or(&[][..], |e| e);
+        let has_content =
+            !c.features.is_empty() || !c.operations.is_empty() || !references.is_empty();
+        self.line(depth, "eClassifiers", &attributes, has_content);
+
+        for o in &c.operations {
+            let parameters: Vec<_> = parameters(&o.parameters).collect();
+            let mut attributes = vec![("name", identifier(&o.name))];
+            self.typed(&mut attributes, &o.return_type);
+            self.line(
+                depth + 1,
+                "eOperations",
+                &attributes,
+                !parameters.is_empty(),
+            );
+            for (name, value_type) in &parameters {
+                let mut attributes = vec![("name", identifier(name))];
+                self.typed(&mut attributes, value_type);
+                self.line(depth + 2, "eParameters", &attributes, false);
+            }
+            if !parameters.is_empty() {
+                self.close(depth + 1, "eOperations");
+            }
+        }
+        for f in &c.features {
+            let (type_reference, is_class) = self.type_reference(&f.value_type);
+            let kind = if is_class {
+                "ecore:EReference"
+            } else {
+                "ecore:EAttribute"
+            };
+            let mut attributes = vec![("xsi:type", kind.to_owned()), ("name", identifier(&f.name))];
+            Self::bounds(&mut attributes, &f.multiplicity);
+            if !f.value_type.is_empty() {
+                attributes.push(("eType", type_reference));
+            }
+            if !f.default_value.is_empty() && !is_class {
+                attributes.push(("defaultValueLiteral", f.default_value.clone()));
+            }
+            if f.is_id && !is_class {
+                attributes.push(("iD", "true".to_owned()));
+            }
+            if f.is_read_only {
+                attributes.push(("changeable", "false".to_owned()));
+            }
+            if f.is_derived {
+                attributes.push(("volatile", "true".to_owned()));
+                attributes.push(("transient", "true".to_owned()));
+                attributes.push(("derived", "true".to_owned()));
+            }
+            self.line(depth + 1, "eStructuralFeatures", &attributes, false);
+        }
+        for r in references {
+            let Some(target) = self.class_paths.get(&r.target) else {
+                continue;
+            };
+            let mut attributes = vec![
+                ("xsi:type", "ecore:EReference".to_owned()),
+                ("name", r.name.clone()),
+            ];
+            Self::bounds(&mut attributes, &r.multiplicity);
+            attributes.push(("eType", target.clone()));
+            if r.containment {
+                attributes.push(("containment", "true".to_owned()));
+            }
+            if let Some((owner, name)) = &r.opposite
+                && let Some(owner) = self.class_paths.get(owner)
+            {
+                attributes.push(("eOpposite", format!("{}/{}", owner, name)));
+            }
+            self.line(depth + 1, "eStructuralFeatures", &attributes, false);
+        }
+        if has_content {
+            self.close(depth, "eClassifiers");
+        }
+    }
+
+    fn write_package(&mut self, package: &EcorePackage, depth: usize) {
+        let tag = if depth == 0 {
+            "ecore:EPackage"
+        } else {
+            "eSubpackages"
+        };
+        let mut attributes = Vec::new();
+        if depth == 0 {
+            attributes.extend([
+                ("xmi:version", "2.0".to_owned()),
+                ("xmlns:xmi", "http://www.omg.org/XMI".to_owned()),
+                (
+                    "xmlns:xsi",
+                    "http://www.w3.org/2001/XMLSchema-instance".to_owned(),
+                ),
+                ("xmlns:ecore", ECORE_URI.to_owned()),
+            ]);
+        }
+        attributes.extend([
+            ("name", package.name.clone()),
+            (
+                "nsURI",
+                format!("http://www.example.org/{}", package.name.to_lowercase()),
+            ),
+            ("nsPrefix", package.name.to_lowercase()),
+        ]);
+        self.line(depth, tag, &attributes, true);
+
+        for c in &package.classes {
+            self.write_class(c, depth + 1);
+        }
+        if depth == 0 {
+            for (name, instance_class_name) in std::mem::take(&mut self.data_types) {
+                let attributes = [
+                    ("xsi:type", "ecore:EDataType".to_owned()),
+                    ("name", name),
+                    ("instanceClassName", instance_class_name),
+                ];
+                self.line(depth + 1, "eClassifiers", &attributes, false);
+            }
+        }
+        for p in &package.subpackages {
+            self.write_package(p, depth + 1);
+        }
+        self.close(depth, tag);
+    }
+}
+
+/// Names and types of operation parameters written as `name: Type, ...`
+fn parameters(parameters: &str) -> impl Iterator<Item = (&str, &str)> {
+    parameters
+        .split(',')
+        .map(|e| e.split('=').next().unwrap_or(e))
+        .filter(|e| !e.trim().is_empty())
+        .map(|e| match e.split_once(':') {
+            Some((name, value_type)) => (name.trim(), value_type.trim()),
+            None => (e.trim(), ""),
+        })
+}
+
+/// Ecore model of the diagram, with packages as subpackages of a package named after the diagram.
+///
+/// Navigable association ends become references, opposite of each other when both ends
+/// are navigable, composite aggregations become containment references.
+pub fn ecore_document(diagram: &UmlClassDiagram) -> String {
+    let mut collector = EcoreCollector {
+        packages: vec![EcorePackage {
+            name: identifier(&diagram.name),
+            ..Default::default()
+        }],
+        supertypes: HashMap::new(),
+        references: HashMap::new(),
+    };
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+    let root = collector.packages.pop().unwrap_or_default();
+
+    let mut writer = EcoreWriter {
+        out: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_owned(),
+        class_paths: HashMap::new(),
+        class_names: HashMap::new(),
+        data_types: BTreeMap::new(),
+        supertypes: &collector.supertypes,
+        references: &collector.references,
+    };
+    writer.collect_paths(&root, "#//");
+    writer.collect_data_types(&root);
+    writer.write_package(&root, 0);
+    writer.out
+}
+
+enum EcoreType {
+    Class(usize),
+    Data(String),
+}
+
+struct EcoreImportReference {
+    owner: usize,
+    /// Path of the reference, such as `subpackage/Class/reference`
+    path: String,
+    name: String,
+    target: usize,
+    multiplicity: String,
+    containment: bool,
+    opposite: Option<String>,
+}
+
+struct EcoreReader<'a> {
+    diagram: PlantUmlClassDiagram,
+    /// Class indices by paths such as `subpackage/Class`
+    classes: HashMap<String, usize>,
+    /// Instance class names of data types declared in the model by their paths
+    data_types: HashMap<String, String>,
+    /// Classes whose features are read once all classifiers are known, with their paths
+    pending: Vec<(usize, &'a XmlElement, String)>,
+}
+
+fn xsi_type(e: &XmlElement) -> &str {
+    e.attribute("xsi:type").map_or("", xml::local_name)
+}
+
+impl<'a> EcoreReader<'a> {
+    fn read_package(&mut self, element: &'a XmlElement, prefix: &str, package: Option<usize>) {
+        for c in element.children_named("eClassifiers") {
+            let name = c.attribute("name").unwrap_or_default();
+            let path = format!("{}{}", prefix, name);
+            match xsi_type(c) {
+                "EClass" | "" => {
+                    let is_interface = c.attribute("interface") == Some("true");
+                    self.diagram.classes.push(PlantUmlClass {
+                        name: name.to_owned(),
+                        stereotype: if is_interface { "interface" } else { "" }.to_owned(),
+                        is_abstract: !is_interface && c.attribute("abstract") == Some("true"),
+                        members: vec![],
+                        package,
+                    });
+                    let index = self.diagram.classes.len() - 1;
+                    self.classes.insert(path.clone(), index);
+                    self.pending.push((index, c, path));
+                }
+                "EEnum" => {
+                    self.diagram.classes.push(PlantUmlClass {
+                        name: name.to_owned(),
+                        stereotype: "enumeration".to_owned(),
+                        is_abstract: false,
+                        members: c
+                            .children_named("eLiterals")
+                            .map(|e| PlantUmlMember {
+                                name: e.attribute("name").unwrap_or_default().to_owned(),
+                                ..Default::default()
+                            })
+                            .collect(),
+                        package,
+                    });
+                    self.classes.insert(path, self.diagram.classes.len() - 1);
+                }
+                _ => {
+                    let instance_class_name = c.attribute("instanceClassName").unwrap_or(name);
+                    self.data_types.insert(path, instance_class_name.to_owned());
+                }
+            }
+        }
+        for p in element.children_named("eSubpackages") {
+            let name = p.attribute("name").unwrap_or_default();
+            self.diagram.packages.push(PlantUmlPackage {
+                name: name.to_owned(),
+                stereotype: String::new(),
+                parent: package,
+            });
+            let index = self.diagram.packages.len() - 1;
+            self.read_package(p, &format!("{}{}/", prefix, name), Some(index));
+        }
+    }
+
+    /// Resolves references such as `#//Class` or `ecore:EDataType http://...Ecore#//EString`
+    fn resolve(&self, reference: &str) -> EcoreType {
+        let reference = reference.rsplit(' ').next().unwrap_or(reference);
+        let (document, path) = reference.split_once("#//").unwrap_or(("", reference));
+        if document == ECORE_URI {
+            let name = ECORE_TYPES
+                .iter()
+                .find(|e| e.1 == path)
+                .map_or(path, |e| e.0);
+            EcoreType::Data(name.to_owned())
+        } else if let Some(index) = self.classes.get(path) {
+            EcoreType::Class(*index)
+        } else if let Some(name) = self.data_types.get(path) {
+            EcoreType::Data(name.clone())
+        } else {
+            EcoreType::Data(path.rsplit('/').next().unwrap_or(path).to_owned())
+        }
+    }
+
+    fn element_type(&self, element: &XmlElement) -> Option<EcoreType> {
+        let reference = element.attribute("eType").or_else(|| {
+            element
+                .children_named("eGenericType")
+                .next()
+                .and_then(|e| e.attribute("eClassifier"))
+        })?;
+        Some(self.resolve(reference))
+    }
+
+    fn type_name(&self, element: &XmlElement) -> String {
+        match self.element_type(element) {
+            None => String::new(),
+            Some(EcoreType::Class(index)) => self.diagram.classes[index].name.clone(),
+            Some(EcoreType::Data(name)) => name,
+        }
+    }
+
+    fn read_features(&mut self) -> Vec<EcoreImportReference> {
+        let mut references = Vec::new();
+        for (index, class, path) in std::mem::take(&mut self.pending) {
+            for s in class
+                .attribute("eSuperTypes")
+                .unwrap_or_default()
+                .split_whitespace()
+            {
+                if let EcoreType::Class(target) = self.resolve(s) {
+                    self.diagram.links.push(PlantUmlLink {
+                        kind: PlantUmlLinkKind::Generalization,
+                        source: index,
+                        target,
+                        source_multiplicity: String::new(),
+                        target_multiplicity: String::new(),
+                        label: String::new(),
+                        stereotype: String::new(),
+                    });
+                }
+            }
+
+            let mut members = Vec::new();
+            for o in class.children_named("eOperations") {
+                let parameters: Vec<_> = o
+                    .children_named("eParameters")
+                    .map(|e| {
+                        let name = e.attribute("name").unwrap_or_default();
+                        match self.type_name(e) {
+                            t if t.is_empty() => name.to_owned(),
+                            t => format!("{}: {}", name, t),
+                        }
+                    })
+                    .collect();
+                members.push(PlantUmlMember {
+                    name: o.attribute("name").unwrap_or_default().to_owned(),
+                    value_type: self.type_name(o),
+                    parameters: Some(parameters.join(", ")),
+                    ..Default::default()
+                });
+            }
+            for f in class.children_named("eStructuralFeatures") {
+                let name = f.attribute("name").unwrap_or_default();
+                let bound = |name: &str, default: i64| {
+                    f.attribute(name)
+                        .and_then(|e| e.parse().ok())
+                        .unwrap_or(default)
+                };
+                let multiplicity =
+                    bounds_to_multiplicity(bound("lowerBound", 0), bound("upperBound", 1));
+                if xsi_type(f) == "EReference"
+                    && let Some(EcoreType::Class(target)) = self.element_type(f)
+                {
+                    references.push(EcoreImportReference {
+                        owner: index,
+                        path: format!("{}/{}", path, name),
+                        name: name.to_owned(),
+                        target,
+                        multiplicity,
+                        containment: f.attribute("containment") == Some("true"),
+                        opposite: f
+                            .attribute("eOpposite")
+                            .map(|e| e.split_once("#//").map_or(e, |e| e.1).to_owned()),
+                    });
+                    continue;
+                }
+                members.push(PlantUmlMember {
+                    name: name.to_owned(),
+                    value_type: self.type_name(f),
+                    multiplicity: if multiplicity == "1" {
+                        String::new()
+                    } else {
+                        multiplicity
+                    },
+                    default_value: f
+                        .attribute("defaultValueLiteral")
+                        .unwrap_or_default()
+                        .to_owned(),
+                    ..Default::default()
+                });
+            }
+            self.diagram.classes[index].members.extend(members);
+        }
+        references
+    }
+}
+
+/// Reads classes of an `.ecore` file, references which are opposites of each other are merged
+/// into a single association. References to classes of other files are read as properties.
+pub fn parse_ecore(source: &str) -> Result<PlantUmlClassDiagram, String> {
+    let root = xml::parse(source)?;
+    let package = if root.local_name() == "XMI" {
+        root.children_named("EPackage").next()
+    } else {
+        Some(&root).filter(|e| e.local_name() == "EPackage")
+    }
+    .ok_or_else(|| "the document does not contain an Ecore package".to_owned())?;
+
+    let mut reader = EcoreReader {
+        diagram: PlantUmlClassDiagram {
+            title: package.attribute("name").map(|e| e.to_owned()),
+            ..Default::default()
+        },
+        classes: HashMap::new(),
+        data_types: HashMap::new(),
+        pending: Vec::new(),
+    };
+    reader.read_package(package, "", None);
+    let references = reader.read_features();
+
+    let mut merged = vec![false; references.len()];
+    for (i, r) in references.iter().enumerate() {
+        if merged[i] {
+            continue;
+        }
+        merged[i] = true;
+        let opposite = r
+            .opposite
+            .as_ref()
+            .and_then(|o| (0..references.len()).find(|j| !merged[*j] && references[*j].path == *o));
+        let mut source_end = PlantUmlAssociationEnd::default();
+        let mut source_multiplicity = String::new();
+        if let Some(j) = opposite {
+            merged[j] = true;
+            let o = &references[j];
+            source_end.navigability = UmlClassAssociationNavigability::Navigable;
+            source_end.role = o.name.clone();
+            source_multiplicity = o.multiplicity.clone();
+        }
+        if r.containment {
+            source_end.aggregation = UmlClassAssociationAggregation::Composite;
+        }
+        let target_end = PlantUmlAssociationEnd {
+            navigability: UmlClassAssociationNavigability::Navigable,
+            aggregation: if opposite.is_some_and(|j| references[j].containment) {
+                UmlClassAssociationAggregation::Composite
+            } else {
+                UmlClassAssociationAggregation::None
+            },
+            role: r.name.clone(),
+        };
+        reader.diagram.links.push(PlantUmlLink {
+            kind: PlantUmlLinkKind::Association(source_end, target_end),
+            source: r.owner,
+            target: r.target,
+            source_multiplicity,
+            target_multiplicity: r.multiplicity.clone(),
+            label: String::new(),
+            stereotype: String::new(),
+        });
+    }
+
+    Ok(reader.diagram)
+}
+
+pub struct EcoreImportTab {
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for EcoreImportTab {
+    fn default() -> Self {
+        Self {
+            source: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ecore:EPackage xmi:version=\"2.0\" xmlns:xmi=\"http://www.omg.org/XMI\"\n    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"\n    xmlns:ecore=\"http://www.eclipse.org/emf/2002/Ecore\" name=\"library\">\n  <eClassifiers xsi:type=\"ecore:EClass\" name=\"Library\">\n    <eStructuralFeatures xsi:type=\"ecore:EReference\" name=\"books\" upperBound=\"-1\"\n        eType=\"#//Book\" containment=\"true\"/>\n  </eClassifiers>\n  <eClassifiers xsi:type=\"ecore:EClass\" name=\"Book\">\n    <eStructuralFeatures xsi:type=\"ecore:EAttribute\" name=\"title\"\n        eType=\"ecore:EDataType http://www.eclipse.org/emf/2002/Ecore#//EString\"/>\n  </eClassifiers>\n</ecore:EPackage>\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for EcoreImportTab {
+    fn title(&self) -> String {
+        "Ecore Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("Contents of an .ecore file");
+            if ui.button("Import").clicked() {
+                self.status = Some(parse_ecore(&self.source).map(|diagram| {
+                    let (uuid, controller) = super::umlclass_controllers::from_plantuml(&diagram);
+                    commands.push(ProjectCommand::AddNewDiagram(
+                        ViewUuid::nil(),
+                        uuid,
+                        controller,
+                    ));
+                    commands.push(ProjectCommand::OpenAndFocusTab(
+                        NHTab::Diagram { uuid },
+                        None,
+                    ));
+                    format!(
+                        "Imported {} classifiers and {} relationships",
+                        diagram.classes.len(),
+                        diagram.links.len(),
+                    )
+                }));
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        common::{eref::ERef, ufoption::UFOption},
+        domains::umlclass::umlclass_models::{
+            UmlClassElement, UmlClassOperation, UmlClassPackageKind, UmlClassProperty,
+        },
+    };
+
+    fn find<'a>(d: &'a PlantUmlClassDiagram, name: &str) -> (usize, &'a PlantUmlClass) {
+        d.classes
+            .iter()
+            .enumerate()
+            .find(|e| e.1.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn exported_models_are_imported_back() {
+        let property = |name: &str, value_type: &str, multiplicity: &str| {
+            ERef::new(UmlClassProperty::new(
+                ModelUuid::now_v7(),
+                UFOption::None,
+                name.to_owned(),
+                value_type.to_owned(),
+                multiplicity.to_owned(),
+                String::new(),
+                String::new(),
+            ))
+        };
+        let class = |name: &str, stereotype: &str, properties, operations| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                stereotype.to_owned(),
+                String::new(),
+                false,
+                properties,
+                operations,
+            ))
+        };
+        let party = class("Party", "", vec![property("name", "String", "")], vec![]);
+        let person = class(
+            "Person",
+            "",
+            vec![
+                property("nicknames", "String", "0..*"),
+                property("photo", "Image", "0..1"),
+            ],
+            vec![ERef::new(UmlClassOperation::new(
+                ModelUuid::now_v7(),
+                UFOption::None,
+                "rename".to_owned(),
+                "first: String, last: String".to_owned(),
+                "Boolean".to_owned(),
+                String::new(),
+            ))],
+        );
+        let order = class("Order", "", vec![], vec![]);
+        let state = class(
+            "State",
+            "enumeration",
+            vec![property("open", "", ""), property("closed", "", "")],
+            vec![],
+        );
+        let generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            vec![person.clone()],
+            vec![party.clone()],
+        );
+        let mut placed = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            String::new(),
+            party.clone().into(),
+            "1".to_owned(),
+            order.clone().into(),
+            "*".to_owned(),
+        );
+        placed.source_navigability = UmlClassAssociationNavigability::Navigable;
+        placed.target_navigability = UmlClassAssociationNavigability::Navigable;
+        placed.source_aggregation = UmlClassAssociationAggregation::Composite;
+        placed.target_label_role = Arc::new("orders".to_owned());
+        let sales = UmlClassPackage::new(
+            ModelUuid::now_v7(),
+            "sales".to_owned(),
+            String::new(),
+            UmlClassPackageKind::Package,
+            vec![UmlClassElement::Class(order), UmlClassElement::Class(state)],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Parties".to_owned(),
+            vec![
+                UmlClassElement::Class(party),
+                UmlClassElement::Class(person),
+                UmlClassElement::Package(ERef::new(sales)),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+                UmlClassElement::Association(ERef::new(placed)),
+            ],
+        );
+
+        let document = ecore_document(&diagram);
+        assert!(document.contains(
+            "eType=\"#//sales/Order\" containment=\"true\" eOpposite=\"#//sales/Order/party\""
+        ));
+        assert!(document.contains("name=\"Image\" instanceClassName=\"Image\""));
+
+        let d = parse_ecore(&document).unwrap();
+        assert_eq!(d.title.as_deref(), Some("Parties"));
+        assert_eq!(d.packages.len(), 1);
+        let (party, _) = find(&d, "Party");
+        let (person, p) = find(&d, "Person");
+        assert_eq!(p.members.len(), 3);
+        assert_eq!(
+            p.members[0].parameters.as_deref(),
+            Some("first: String, last: String")
+        );
+        assert_eq!(p.members[0].value_type, "Boolean");
+        assert_eq!(p.members[1].multiplicity, "*");
+        assert_eq!(p.members[2].value_type, "Image");
+        let (order, o) = find(&d, "Order");
+        assert_eq!(o.package, Some(0));
+        let (_, s) = find(&d, "State");
+        assert_eq!(s.stereotype, "enumeration");
+        assert_eq!(s.members.len(), 2);
+
+        assert_eq!(d.links.len(), 2);
+        assert!(
+            d.links
+                .iter()
+                .any(|e| matches!(e.kind, PlantUmlLinkKind::Generalization)
+                    && e.source == person
+                    && e.target == party)
+        );
+        let association = d
+            .links
+            .iter()
+            .find(|e| matches!(e.kind, PlantUmlLinkKind::Association(..)))
+            .unwrap();
+        let PlantUmlLinkKind::Association(source_end, target_end) = &association.kind else {
+            unreachable!()
+        };
+        assert_eq!((association.source, association.target), (party, order));
+        assert!(source_end.aggregation == UmlClassAssociationAggregation::Composite);
+        assert_eq!(
+            (source_end.role.as_str(), target_end.role.as_str()),
+            ("party", "orders")
+        );
+        assert_eq!(
+            (
+                association.source_multiplicity.as_str(),
+                association.target_multiplicity.as_str()
+            ),
+            ("1", "*")
+        );
+    }
+
+    #[test]
+    fn xmi_documents_are_read() {
+        let d = parse_ecore(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmi:version="2.0" xmlns:xmi="http://www.omg.org/XMI"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:ecore="http://www.eclipse.org/emf/2002/Ecore">
+  <ecore:EPackage name="library" nsURI="http://library" nsPrefix="library">
+    <eClassifiers xsi:type="ecore:EClass" name="Writer">
+      <eStructuralFeatures xsi:type="ecore:EReference" name="books" upperBound="-1"
+          eType="#//Book" eOpposite="#//Book/authors"/>
+      <eStructuralFeatures xsi:type="ecore:EAttribute" name="born" lowerBound="1">
+        <eGenericType eClassifier="ecore:EDataType http://www.eclipse.org/emf/2002/Ecore#//EDate"/>
+      </eStructuralFeatures>
+    </eClassifiers>
+    <eClassifiers xsi:type="ecore:EClass" name="Book">
+      <eStructuralFeatures xsi:type="ecore:EReference" name="authors" lowerBound="1"
+          upperBound="-1" eType="#//Writer" eOpposite="#//Writer/books"/>
+      <eStructuralFeatures xsi:type="ecore:EReference" name="publisher"
+          eType="ecore:EClass other.ecore#//Publisher"/>
+    </eClassifiers>
+  </ecore:EPackage>
+</xmi:XMI>"##,
+        )
+        .unwrap();
+        assert_eq!(d.classes.len(), 2);
+        assert_eq!(d.classes[0].members[0].value_type, "Date");
+        assert_eq!(d.classes[0].members[0].multiplicity, "");
+        assert_eq!(d.classes[1].members[0].value_type, "Publisher");
+        assert_eq!(d.links.len(), 1);
+        assert_eq!(d.links[0].source_multiplicity, "1..*");
+        assert_eq!(d.links[0].target_multiplicity, "*");
+
+        assert!(parse_ecore("<uml:Model/>").is_err());
+    }
+}
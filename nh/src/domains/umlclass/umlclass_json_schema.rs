@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    common::{controller::TextExportFormat, fluent::localized, uuid::ModelUuid},
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassAssociationNavigability,
+        UmlClassComment, UmlClassCommentLink, UmlClassDependency, UmlClassDiagram,
+        UmlClassGeneralization, UmlClassInstance, UmlClassPackage, UmlClassVisitor, UmlUseCase,
+        UmlUseCaseGeneralization, multiplicity_bounds,
+    },
+};
+
+pub const JSON_SCHEMA_FORMAT: TextExportFormat = TextExportFormat {
+    name: "JSON Schema",
+    extensions: &["json"],
+};
+
+/// Schemas of UML primitive types, other types which are not classes of the diagram allow anything
+const PRIMITIVE_TYPES: [(&str, &str, Option<&str>); 14] = [
+    ("String", "string", None),
+    ("Integer", "integer", None),
+    ("int", "integer", None),
+    ("Long", "integer", None),
+    ("UnlimitedNatural", "integer", None),
+    ("Real", "number", None),
+    ("Double", "number", None),
+    ("Float", "number", None),
+    ("Decimal", "number", None),
+    ("Boolean", "boolean", None),
+    ("bool", "boolean", None),
+    ("Date", "string", Some("date")),
+    ("DateTime", "string", Some("date-time")),
+    ("Time", "string", Some("time")),
+];
+
+struct PropertySchema {
+    name: String,
+    value_type: String,
+    multiplicity: String,
+    default_value: String,
+    is_read_only: bool,
+    description: String,
+}
+
+struct ClassSchema {
+    uuid: ModelUuid,
+    name: String,
+    title: String,
+    description: String,
+    is_enumeration: bool,
+    properties: Vec<PropertySchema>,
+}
+
+struct JsonSchemaCollector {
+    languages: Vec<unic_langid::LanguageIdentifier>,
+    classes: Vec<ClassSchema>,
+    supertypes: HashMap<ModelUuid, Vec<String>>,
+    /// Properties of navigable association ends, by the class owning them
+    association_ends: HashMap<ModelUuid, Vec<PropertySchema>>,
+}
+
+impl JsonSchemaCollector {
+    fn association_end(
+        &mut self,
+        owner: &UmlClassAssociable,
+        target: &UmlClassAssociable,
+        role: &str,
+        multiplicity: &str,
+        description: &str,
+    ) {
+        let (UmlClassAssociable::Class(owner), UmlClassAssociable::Class(target)) = (owner, target)
+        else {
+            return;
+        };
+        let target = target.read();
+        let name = if role.is_empty() {
+            let mut chars = target.name.chars();
+            chars
+                .next()
+                .map(|c| c.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        } else {
+            role.to_owned()
+        };
+        self.association_ends
+            .entry(*owner.read().uuid)
+            .or_default()
+            .push(PropertySchema {
+                name,
+                value_type: (*target.name).clone(),
+                multiplicity: multiplicity.to_owned(),
+                default_value: String::new(),
+                is_read_only: false,
+                description: description.to_owned(),
+            });
+    }
+}
+
+impl UmlClassVisitor for JsonSchemaCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let properties = class
+            .properties
+            .iter()
+            .map(|e| e.read())
+            .filter(|e| !e.is_static)
+            .map(|e| PropertySchema {
+                name: (*e.name).clone(),
+                value_type: (*e.value_type).clone(),
+                multiplicity: (*e.multiplicity).clone(),
+                default_value: (*e.default_value).clone(),
+                is_read_only: e.is_read_only || e.is_derived,
+                description: String::new(),
+            })
+            .collect();
+        self.classes.push(ClassSchema {
+            uuid: *class.uuid,
+            name: (*class.name).clone(),
+            title: localized(&class.name, &class.name_translations, &self.languages).to_owned(),
+            description: (*class.comment).clone(),
+            is_enumeration: class.stereotype.eq_ignore_ascii_case("enumeration"),
+            properties,
+        });
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, generalization: &UmlClassGeneralization) {
+        for source in &generalization.sources {
+            self.supertypes
+                .entry(*source.read().uuid)
+                .or_default()
+                .extend(
+                    generalization
+                        .targets
+                        .iter()
+                        .map(|e| (*e.read().name).clone()),
+                );
+        }
+    }
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, a: &UmlClassAssociation) {
+        if a.target_navigability == UmlClassAssociationNavigability::Navigable {
+            self.association_end(
+                &a.source,
+                &a.target,
+                &a.target_label_role,
+                &a.target_label_multiplicity,
+                &a.name,
+            );
+        }
+        if a.source_navigability == UmlClassAssociationNavigability::Navigable {
+            self.association_end(
+                &a.target,
+                &a.source,
+                &a.source_label_role,
+                &a.source_label_multiplicity,
+                &a.name,
+            );
+        }
+    }
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+fn property_schema(p: &PropertySchema, class_names: &[&str]) -> Value {
+    let value_type = p.value_type.trim();
+    let mut schema =
+        if let Some((_, t, format)) = PRIMITIVE_TYPES.iter().find(|e| e.0 == value_type) {
+            match format {
+                Some(f) => json!({ "type": t, "format": f }),
+                None => json!({ "type": t }),
+            }
+        } else if class_names.contains(&value_type) {
+            reference(value_type)
+        } else {
+            json!({})
+        };
+
+    let (lower, many) = multiplicity_bounds(&p.multiplicity);
+    if many {
+        let mut array = json!({ "type": "array", "items": schema });
+        if lower > 0 {
+            array["minItems"] = lower.into();
+        }
+        let upper = p.multiplicity.rsplit("..").next().unwrap_or("");
+        if let Ok(upper) = upper.trim().parse::<u32>() {
+            array["maxItems"] = upper.into();
+        }
+        schema = array;
+    }
+
+    if !p.default_value.is_empty() {
+        schema["default"] = serde_json::from_str(&p.default_value)
+            .unwrap_or_else(|_| Value::String(p.default_value.clone()));
+    }
+    if p.is_read_only {
+        schema["readOnly"] = true.into();
+    }
+    if !p.description.is_empty() {
+        schema["description"] = p.description.clone().into();
+    }
+    schema
+}
+
+/// JSON Schema with definitions of the classes of the diagram.
+///
+/// Properties and navigable association ends become object properties, required when their
+/// lower bound is not zero, generalizations are expressed with `allOf`.
+pub fn json_schema_document(
+    diagram: &UmlClassDiagram,
+    languages: &[unic_langid::LanguageIdentifier],
+) -> String {
+    let mut collector = JsonSchemaCollector {
+        languages: languages.to_vec(),
+        classes: vec![],
+        supertypes: HashMap::new(),
+        association_ends: HashMap::new(),
+    };
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+    let class_names: Vec<_> = collector.classes.iter().map(|e| e.name.as_str()).collect();
+
+    let mut definitions = Map::new();
+    for c in &collector.classes {
+        let mut schema = Map::new();
+        if c.title != c.name {
+            schema.insert("title".to_owned(), c.title.clone().into());
+        }
+        if !c.description.is_empty() {
+            schema.insert("description".to_owned(), c.description.clone().into());
+        }
+
+        if c.is_enumeration {
+            let literals: Vec<_> = c.properties.iter().map(|e| e.name.clone()).collect();
+            schema.insert("enum".to_owned(), literals.into());
+            definitions.insert(c.name.clone(), Value::Object(schema));
+            continue;
+        }
+
+        schema.insert("type".to_owned(), "object".into());
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for p in c.properties.iter().chain(
+            collector
+                .association_ends
+                .get(&c.uuid)
+                .into_iter()
+                .flatten(),
+        ) {
+            properties.insert(p.name.clone(), property_schema(p, &class_names));
+            if multiplicity_bounds(&p.multiplicity).0 > 0 {
+                required.push(Value::String(p.name.clone()));
+            }
+        }
+        schema.insert("properties".to_owned(), properties.into());
+        if !required.is_empty() {
+            schema.insert("required".to_owned(), required.into());
+        }
+        if let Some(supertypes) = collector.supertypes.get(&c.uuid) {
+            let all_of: Vec<_> = supertypes.iter().map(|e| reference(e)).collect();
+            schema.insert("allOf".to_owned(), all_of.into());
+        }
+        definitions.insert(c.name.clone(), Value::Object(schema));
+    }
+
+    let mut document = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$defs": definitions,
+    });
+    if !diagram.name.is_empty() {
+        document["title"] = (*diagram.name).clone().into();
+    }
+    if !diagram.comment.is_empty() {
+        document["description"] = (*diagram.comment).clone().into();
+    }
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::{eref::ERef, ufoption::UFOption},
+        domains::umlclass::umlclass_models::{UmlClassElement, UmlClassProperty},
+    };
+
+    #[test]
+    fn classes_become_definitions() {
+        let property = |name: &str, value_type: &str, multiplicity: &str| {
+            ERef::new(UmlClassProperty::new(
+                ModelUuid::now_v7(),
+                UFOption::None,
+                name.to_owned(),
+                value_type.to_owned(),
+                multiplicity.to_owned(),
+                String::new(),
+                String::new(),
+            ))
+        };
+        let class = |name: &str, properties| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                false,
+                properties,
+                vec![],
+            ))
+        };
+        let party = class("Party", vec![property("name", "String", "")]);
+        let person = class(
+            "Person",
+            vec![
+                property("born", "Date", "0..1"),
+                property("nicknames", "String", "0..3"),
+            ],
+        );
+        let order = class("Order", vec![]);
+        let generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            vec![person.clone()],
+            vec![party.clone()],
+        );
+        let mut placed = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            String::new(),
+            order.clone().into(),
+            "*".to_owned(),
+            party.clone().into(),
+            "1".to_owned(),
+        );
+        placed.target_navigability = UmlClassAssociationNavigability::Navigable;
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Parties".to_owned(),
+            vec![
+                UmlClassElement::Class(party),
+                UmlClassElement::Class(person),
+                UmlClassElement::Class(order),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+                UmlClassElement::Association(ERef::new(placed)),
+            ],
+        );
+
+        let schema: Value = serde_json::from_str(&json_schema_document(&diagram, &[])).unwrap();
+        let defs = &schema["$defs"];
+        assert_eq!(schema["title"], "Parties");
+        assert_eq!(defs["Party"]["required"], json!(["name"]));
+        assert_eq!(
+            defs["Person"]["allOf"],
+            json!([{ "$ref": "#/$defs/Party" }])
+        );
+        assert_eq!(
+            defs["Person"]["properties"]["born"],
+            json!({ "type": "string", "format": "date" })
+        );
+        assert_eq!(
+            defs["Person"]["properties"]["nicknames"],
+            json!({ "type": "array", "items": { "type": "string" }, "maxItems": 3 })
+        );
+        assert_eq!(defs["Person"].get("required"), None);
+        assert_eq!(
+            defs["Order"]["properties"]["party"],
+            json!({ "$ref": "#/$defs/Party" })
+        );
+        assert_eq!(defs["Order"]["required"], json!(["party"]));
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    common::{
+        controller::{Model, TextExportFormat},
+        fluent::localized,
+        uuid::ModelUuid,
+    },
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociation, UmlClassAssociationAggregation,
+        UmlClassAssociationNavigability, UmlClassComment, UmlClassCommentLink, UmlClassDependency,
+        UmlClassElement, UmlClassGeneralization, UmlClassInstance, UmlClassPackage,
+        UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+    },
+};
+
+pub const MERMAID_FORMAT: TextExportFormat = TextExportFormat {
+    name: "Mermaid",
+    extensions: &["mmd", "mermaid"],
+};
+
+/// Collects a Mermaid `classDiagram` body.
+///
+/// Mermaid namespaces cannot be nested, so nested packages are emitted as separate namespaces.
+pub struct UmlClassMermaidCollector {
+    languages: Vec<unic_langid::LanguageIdentifier>,
+    mermaid_structures: String,
+    mermaid_links: String,
+}
+
+impl UmlClassMermaidCollector {
+    pub fn new(languages: &[unic_langid::LanguageIdentifier]) -> Self {
+        Self {
+            languages: languages.to_vec(),
+            mermaid_structures: "".to_owned(),
+            mermaid_links: "".to_owned(),
+        }
+    }
+    pub fn finish(mut self) -> String {
+        self.mermaid_structures.push_str(&self.mermaid_links);
+        self.mermaid_structures
+    }
+
+    fn stringify_uuid(uuid: &ModelUuid) -> String {
+        "m".chars()
+            .chain(uuid.to_string().chars().filter(|e| *e != '-'))
+            .collect()
+    }
+    fn label(s: &str) -> String {
+        s.replace('"', "'").replace('\n', " ")
+    }
+    fn identifier(s: &str) -> String {
+        let id: String = s
+            .chars()
+            .map(|e| if e.is_alphanumeric() { e } else { '_' })
+            .collect();
+        if id.is_empty() { "_".to_owned() } else { id }
+    }
+
+    fn push_classifier(
+        &mut self,
+        uuid: &ModelUuid,
+        label: &str,
+        annotations: &[&str],
+        members: &[String],
+    ) {
+        self.mermaid_structures.push_str(&format!(
+            "  class {}[\"{}\"]",
+            Self::stringify_uuid(uuid),
+            Self::label(label),
+        ));
+        let annotations: Vec<_> = annotations.iter().filter(|e| !e.is_empty()).collect();
+        if annotations.is_empty() && members.is_empty() {
+            self.mermaid_structures.push('\n');
+            return;
+        }
+        self.mermaid_structures.push_str(" {\n");
+        for e in annotations {
+            self.mermaid_structures
+                .push_str(&format!("    <<{}>>\n", Self::label(e)));
+        }
+        for e in members {
+            self.mermaid_structures.push_str(&format!("    {}\n", e));
+        }
+        self.mermaid_structures.push_str("  }\n");
+    }
+    fn push_link(&mut self, source: &ModelUuid, arrow: &str, target: &ModelUuid, label: &str) {
+        self.mermaid_links.push_str(&format!(
+            "  {} {} {}",
+            Self::stringify_uuid(source),
+            arrow,
+            Self::stringify_uuid(target),
+        ));
+        if !label.is_empty() {
+            self.mermaid_links
+                .push_str(&format!(" : {}", Self::label(label)));
+        }
+        self.mermaid_links.push('\n');
+    }
+}
+
+impl UmlClassVisitor for UmlClassMermaidCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        let outer = std::mem::take(&mut self.mermaid_structures);
+        for e in package
+            .contained_elements
+            .iter()
+            .filter(|e| !matches!(e, UmlClassElement::Package(_)))
+        {
+            e.accept_uml(self);
+        }
+        let contents = std::mem::replace(&mut self.mermaid_structures, outer);
+
+        if !contents.is_empty() {
+            self.mermaid_structures.push_str(&format!(
+                "namespace {} {{\n{}}}\n",
+                Self::identifier(localized(
+                    &package.name,
+                    &package.name_translations,
+                    &self.languages
+                )),
+                contents
+            ));
+        }
+
+        for e in package
+            .contained_elements
+            .iter()
+            .filter(|e| matches!(e, UmlClassElement::Package(_)))
+        {
+            e.accept_uml(self);
+        }
+    }
+    fn visit_instance(&mut self, instance: &UmlClassInstance) {
+        let label = if instance.instance_name.is_empty() {
+            format!(":{}", instance.instance_type)
+        } else {
+            format!("{}: {}", instance.instance_name, instance.instance_type)
+        };
+        let slots: Vec<_> = instance
+            .instance_slots
+            .lines()
+            .filter(|e| !e.is_empty())
+            .map(|e| e.to_owned())
+            .collect();
+        self.push_classifier(&instance.uuid, &label, &[&instance.stereotype], &slots);
+    }
+    fn visit_class(&mut self, class: &UmlClass) {
+        let mut label =
+            localized(&class.name, &class.name_translations, &self.languages).to_owned();
+        if !class.template_parameters.is_empty() {
+            label.push_str(&format!("<{}>", class.template_parameters));
+        }
+
+        let mut members = Vec::new();
+        for e in &class.properties {
+            let r = e.read();
+            let visibility = r.visibility.as_ref().map(|e| e.as_char()).unwrap_or("");
+            let value_type = if !r.value_type.is_empty() {
+                format!("{} ", r.value_type)
+            } else {
+                "".to_owned()
+            };
+            let classifier = if r.is_static { "$" } else { "" };
+            members.push(format!(
+                "{}{}{}{}",
+                visibility, value_type, r.name, classifier
+            ));
+        }
+        for e in &class.operations {
+            let r = e.read();
+            let visibility = r.visibility.as_ref().map(|e| e.as_char()).unwrap_or("");
+            let classifier = if r.is_abstract {
+                "*"
+            } else if r.is_static {
+                "$"
+            } else {
+                ""
+            };
+            let return_type = if !r.return_type.is_empty() {
+                format!(" {}", r.return_type)
+            } else {
+                "".to_owned()
+            };
+            members.push(format!(
+                "{}{}({}){}{}",
+                visibility, r.name, r.parameters, classifier, return_type
+            ));
+        }
+
+        self.push_classifier(
+            &class.uuid,
+            &label,
+            &[
+                &class.stereotype,
+                if class.is_abstract { "abstract" } else { "" },
+            ],
+            &members,
+        );
+    }
+    fn visit_generalization(&mut self, link: &UmlClassGeneralization) {
+        for source in link.sources.iter().map(|e| *e.read().uuid) {
+            for target in link.targets.iter().map(|e| *e.read().uuid) {
+                self.push_link(&source, "--|>", &target, "");
+            }
+        }
+    }
+    fn visit_dependency(&mut self, link: &UmlClassDependency) {
+        let label = if link.stereotype.is_empty() {
+            (*link.name).clone()
+        } else {
+            format!("«{}» {}", link.stereotype, link.name)
+        };
+        self.push_link(
+            &link.source.uuid(),
+            if link.target_arrow_open {
+                "..>"
+            } else {
+                "..|>"
+            },
+            &link.target.uuid(),
+            label.trim(),
+        );
+    }
+    fn visit_association(&mut self, link: &UmlClassAssociation) {
+        fn ah(
+            target: bool,
+            n: UmlClassAssociationNavigability,
+            a: UmlClassAssociationAggregation,
+        ) -> &'static str {
+            match a {
+                UmlClassAssociationAggregation::None => match n {
+                    UmlClassAssociationNavigability::Unspecified
+                    | UmlClassAssociationNavigability::NonNavigable => "",
+                    UmlClassAssociationNavigability::Navigable => {
+                        if !target {
+                            "<"
+                        } else {
+                            ">"
+                        }
+                    }
+                },
+                UmlClassAssociationAggregation::Shared => "o",
+                UmlClassAssociationAggregation::Composite => "*",
+            }
+        }
+        let multiplicity = |m: &str| {
+            if m.is_empty() {
+                " ".to_owned()
+            } else {
+                format!(" \"{}\" ", Self::label(m))
+            }
+        };
+        let arrow = format!(
+            "{}{}--{}{}",
+            multiplicity(&link.source_label_multiplicity).trim_start(),
+            ah(false, link.source_navigability, link.source_aggregation),
+            ah(true, link.target_navigability, link.target_aggregation),
+            multiplicity(&link.target_label_multiplicity).trim_end(),
+        );
+        let label = if link.stereotype.is_empty() {
+            (*link.name).clone()
+        } else {
+            format!("«{}» {}", link.stereotype, link.name)
+        };
+        self.push_link(
+            &link.source.uuid(),
+            &arrow,
+            &link.target.uuid(),
+            label.trim(),
+        );
+    }
+    fn visit_comment(&mut self, comment: &UmlClassComment) {
+        let text = localized(&comment.text, &comment.text_translations, &self.languages);
+        self.mermaid_structures
+            .push_str(&format!("  note \"{}\"\n", Self::label(text)));
+    }
+    fn visit_commentlink(&mut self, _comment_link: &UmlClassCommentLink) {}
+
+    fn visit_usecase(&mut self, usecase: &UmlUseCase) {
+        self.push_classifier(
+            &usecase.uuid,
+            &usecase.name,
+            &["usecase", &usecase.stereotype],
+            &[],
+        );
+    }
+    fn visit_usecasegeneralization(&mut self, g: &UmlUseCaseGeneralization) {
+        for source in g.sources.iter().map(|e| *e.read().uuid) {
+            for target in g.targets.iter().map(|e| *e.read().uuid) {
+                self.push_link(&source, "--|>", &target, "");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::{eref::ERef, uuid::ModelUuid},
+        domains::umlclass::umlclass_models::{
+            UmlClass, UmlClassAssociation, UmlClassAssociationAggregation, UmlClassDiagram,
+            UmlClassElement, UmlClassPackage, UmlClassPackageKind,
+        },
+    };
+
+    #[test]
+    fn packages_become_namespaces_and_links_follow() {
+        let class = |name: &str| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                false,
+                vec![],
+                vec![],
+            ))
+        };
+        let (order, line) = (class("Order"), class("Order line"));
+        let mut association = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            "contains".to_owned(),
+            order.clone().into(),
+            "1".to_owned(),
+            line.clone().into(),
+            "*".to_owned(),
+        );
+        association.source_aggregation = UmlClassAssociationAggregation::Composite;
+        let package = UmlClassPackage::new(
+            ModelUuid::now_v7(),
+            "sales orders".to_owned(),
+            String::new(),
+            UmlClassPackageKind::Package,
+            vec![UmlClassElement::Class(order), UmlClassElement::Class(line)],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Sales".to_owned(),
+            vec![
+                UmlClassElement::Package(ERef::new(package)),
+                UmlClassElement::Association(ERef::new(association)),
+            ],
+        );
+
+        let document = diagram.mermaid_document(&[]);
+        assert!(
+            document
+                .starts_with("---\ntitle: Sales\n---\nclassDiagram\nnamespace sales_orders {\n")
+        );
+        assert!(document.contains("[\"Order line\"]"));
+        assert!(document.contains(" \"1\" *-- \"*\" "));
+        assert!(document.trim_end().ends_with(" : contains"));
+    }
+}
@@ -4,16 +4,35 @@ use crate::common::controller::{
 };
 use crate::common::entity::{Entity, EntityUuid};
 use crate::common::eref::ERef;
+use crate::common::fluent::ContentTranslations;
 use crate::common::search::FullTextSearchable;
 use crate::common::ufoption::UFOption;
 use crate::common::uuid::ModelUuid;
 use crate::common::views::multiconnection_view::{
     MULTICONNECTION_SOURCE_BUCKET, MULTICONNECTION_TARGET_BUCKET,
 };
+use crate::domains::umlclass::umlclass_mermaid::UmlClassMermaidCollector;
 use crate::domains::umlclass::umlclass_plantuml::UmlClassPlantUmlCollector;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::{collections::HashMap, sync::Arc};
 
+/// Lower bound and whether the upper bound is more than one, a missing multiplicity is exactly one
+pub fn multiplicity_bounds(multiplicity: &str) -> (u32, bool) {
+    let multiplicity = multiplicity.trim();
+    if multiplicity.is_empty() {
+        return (1, false);
+    }
+    let (lower, upper) = multiplicity
+        .split_once("..")
+        .unwrap_or((multiplicity, multiplicity));
+    let lower = lower.trim().parse().unwrap_or(0);
+    let upper = upper.trim();
+    (
+        lower,
+        upper == "*" || upper.parse::<u32>().is_ok_and(|e| e > 1),
+    )
+}
+
 pub trait UmlClassVisitor {
     fn visit_package(&mut self, package: &UmlClassPackage);
     fn visit_instance(&mut self, instance: &UmlClassInstance);
@@ -165,7 +184,9 @@ pub fn deep_copy_diagram(
                             new_model
                         })
                         .collect(),
+                    name_translations: model.name_translations.clone(),
                     comment: model.comment.clone(),
+                    comment_translations: model.comment_translations.clone(),
                 };
                 UmlClassElement::Package(ERef::new(new_model))
             }
@@ -486,8 +507,45 @@ impl UmlClassDiagram {
         }
     }
 
-    pub fn plantuml(&self) -> String {
-        let mut collector = UmlClassPlantUmlCollector::new();
+    pub fn plantuml(&self, languages: &[unic_langid::LanguageIdentifier]) -> String {
+        let mut collector = UmlClassPlantUmlCollector::new(languages);
+
+        for e in &self.contained_elements {
+            e.accept_uml(&mut collector);
+        }
+
+        collector.finish()
+    }
+
+    /// Complete PlantUML document, as written to exported files
+    pub fn plantuml_document(&self, languages: &[unic_langid::LanguageIdentifier]) -> String {
+        let mut document = "@startuml\n".to_owned();
+        if !self.name.is_empty() {
+            document.push_str(&format!("title {}\n", self.name));
+        }
+        document.push_str(&self.plantuml(languages));
+        document.push_str("@enduml\n");
+        document
+    }
+
+    /// PlantUML document of only the selected elements and relationships among them
+    pub fn plantuml_document_of(
+        &self,
+        languages: &[unic_langid::LanguageIdentifier],
+        selection: &HashSet<ModelUuid>,
+    ) -> String {
+        let mut collector =
+            UmlClassPlantUmlCollector::new(languages).with_selection(selection.clone());
+
+        for e in &self.contained_elements {
+            e.accept_uml(&mut collector);
+        }
+
+        format!("@startuml\n{}@enduml\n", collector.finish())
+    }
+
+    pub fn mermaid(&self, languages: &[unic_langid::LanguageIdentifier]) -> String {
+        let mut collector = UmlClassMermaidCollector::new(languages);
 
         for e in &self.contained_elements {
             e.accept_uml(&mut collector);
@@ -496,6 +554,17 @@ impl UmlClassDiagram {
         collector.finish()
     }
 
+    /// Complete Mermaid document, as written to exported files
+    pub fn mermaid_document(&self, languages: &[unic_langid::LanguageIdentifier]) -> String {
+        let mut document = String::new();
+        if !self.name.is_empty() {
+            document.push_str(&format!("---\ntitle: {}\n---\n", self.name));
+        }
+        document.push_str("classDiagram\n");
+        document.push_str(&self.mermaid(languages));
+        document
+    }
+
     pub fn get_element_pos_in(
         &self,
         parent: &ModelUuid,
@@ -716,8 +785,13 @@ pub struct UmlClassPackage {
     pub kind: UmlClassPackageKind,
     #[nh_context_serde(entity)]
     pub contained_elements: Vec<UmlClassElement>,
+    /// Names in other languages, by language identifier
+    #[nh_context_serde(default)]
+    pub name_translations: ContentTranslations,
 
     pub comment: Arc<String>,
+    #[nh_context_serde(default)]
+    pub comment_translations: ContentTranslations,
 }
 
 impl UmlClassPackage {
@@ -734,7 +808,9 @@ impl UmlClassPackage {
             stereotype: Arc::new(stereotype),
             kind,
             contained_elements,
+            name_translations: BTreeMap::new(),
             comment: Arc::new("".to_owned()),
+            comment_translations: BTreeMap::new(),
         }
     }
     pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
@@ -744,7 +820,9 @@ impl UmlClassPackage {
             stereotype: self.stereotype.clone(),
             kind: self.kind,
             contained_elements: self.contained_elements.clone(),
+            name_translations: self.name_translations.clone(),
             comment: self.comment.clone(),
+            comment_translations: self.comment_translations.clone(),
         })
     }
 }
@@ -1108,8 +1186,16 @@ pub struct UmlClass {
     pub properties: Vec<ERef<UmlClassProperty>>,
     #[nh_context_serde(entity)]
     pub operations: Vec<ERef<UmlClassOperation>>,
+    /// Contents of profile-defined compartments, by compartment name
+    #[nh_context_serde(default)]
+    pub compartments: BTreeMap<String, Arc<String>>,
+    /// Names in other languages, by language identifier
+    #[nh_context_serde(default)]
+    pub name_translations: ContentTranslations,
 
     pub comment: Arc<String>,
+    #[nh_context_serde(default)]
+    pub comment_translations: ContentTranslations,
 }
 
 impl UmlClass {
@@ -1133,7 +1219,10 @@ impl UmlClass {
             is_abstract,
             properties,
             operations,
+            compartments: BTreeMap::new(),
+            name_translations: BTreeMap::new(),
             comment: Arc::new("".to_owned()),
+            comment_translations: BTreeMap::new(),
         }
     }
     pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
@@ -1145,7 +1234,10 @@ impl UmlClass {
             is_abstract: self.is_abstract,
             properties: self.properties.clone(),
             operations: self.operations.clone(),
+            compartments: self.compartments.clone(),
+            name_translations: self.name_translations.clone(),
             comment: self.comment.clone(),
+            comment_translations: self.comment_translations.clone(),
         })
     }
     pub fn move_element(
@@ -1788,6 +1880,10 @@ pub struct UmlClassComment {
     pub uuid: Arc<ModelUuid>,
     pub stereotype: Arc<String>,
     pub text: Arc<String>,
+    /// Texts in other languages, by language identifier
+    #[full_text_searchable(skip)]
+    #[nh_context_serde(default)]
+    pub text_translations: ContentTranslations,
 }
 
 impl UmlClassComment {
@@ -1796,6 +1892,7 @@ impl UmlClassComment {
             uuid: Arc::new(uuid),
             stereotype: Arc::new(stereotype),
             text: Arc::new(text),
+            text_translations: BTreeMap::new(),
         }
     }
     pub fn clone_with(&self, uuid: ModelUuid) -> ERef<Self> {
@@ -1803,6 +1900,7 @@ impl UmlClassComment {
             uuid: Arc::new(uuid),
             stereotype: self.stereotype.clone(),
             text: self.text.clone(),
+            text_translations: self.text_translations.clone(),
         })
     }
 }
@@ -1,5 +1,11 @@
+use std::collections::HashSet;
+
 use crate::{
-    common::{controller::Model, uuid::ModelUuid},
+    common::{
+        controller::{Model, TextExportFormat},
+        fluent::localized,
+        uuid::ModelUuid,
+    },
     domains::umlclass::umlclass_models::{
         UmlClass, UmlClassAssociation, UmlClassAssociationAggregation,
         UmlClassAssociationNavigability, UmlClassComment, UmlClassCommentLink, UmlClassDependency,
@@ -8,18 +14,32 @@ use crate::{
     },
 };
 
+pub const PLANTUML_FORMAT: TextExportFormat = TextExportFormat {
+    name: "PlantUML",
+    extensions: &["puml", "plantuml"],
+};
+
 pub struct UmlClassPlantUmlCollector {
+    languages: Vec<unic_langid::LanguageIdentifier>,
     plantuml_structures: String,
     plantuml_links: String,
+    /// Only the selected elements and relationships among them are collected, if set
+    selection: Option<HashSet<ModelUuid>>,
 }
 
 impl UmlClassPlantUmlCollector {
-    pub fn new() -> Self {
+    pub fn new(languages: &[unic_langid::LanguageIdentifier]) -> Self {
         Self {
+            languages: languages.to_vec(),
             plantuml_structures: "".to_owned(),
             plantuml_links: "".to_owned(),
+            selection: None,
         }
     }
+    pub fn with_selection(mut self, selection: HashSet<ModelUuid>) -> Self {
+        self.selection = Some(selection);
+        self
+    }
     pub fn finish(mut self) -> String {
         self.plantuml_structures.push_str(&self.plantuml_links);
         self.plantuml_structures
@@ -30,6 +50,9 @@ impl UmlClassPlantUmlCollector {
             .chain(uuid.to_string().chars().filter(|e| *e != '-'))
             .collect()
     }
+    fn includes(&self, uuid: &ModelUuid) -> bool {
+        self.selection.as_ref().is_none_or(|e| e.contains(uuid))
+    }
     fn replace_special_chars(s: &str) -> String {
         s.replace("\n", "\\n").replace("\t", "\\t")
     }
@@ -37,10 +60,16 @@ impl UmlClassPlantUmlCollector {
 
 impl UmlClassVisitor for UmlClassPlantUmlCollector {
     fn visit_package(&mut self, package: &UmlClassPackage) {
+        if !self.includes(&package.uuid) {
+            for e in &package.contained_elements {
+                e.accept_uml(self);
+            }
+            return;
+        }
         self.plantuml_structures.push_str(&format!(
             "package {} as {:?} ",
             Self::stringify_uuid(&package.uuid),
-            package.name
+            localized(&package.name, &package.name_translations, &self.languages),
         ));
         if !package.stereotype.is_empty() {
             self.plantuml_structures
@@ -55,6 +84,9 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         self.plantuml_structures.push_str("}\n");
     }
     fn visit_instance(&mut self, instance: &UmlClassInstance) {
+        if !self.includes(&instance.uuid) {
+            return;
+        }
         let id = Self::stringify_uuid(&instance.uuid);
         self.plantuml_structures.push_str(&format!(
             "object {} as {:?}",
@@ -76,10 +108,18 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         }
     }
     fn visit_class(&mut self, class: &UmlClass) {
+        if !self.includes(&class.uuid) {
+            return;
+        }
         self.plantuml_structures.push_str(&format!(
-            "class {} as {:?} ",
+            "{} {} as {:?} ",
+            if class.is_abstract {
+                "abstract class"
+            } else {
+                "class"
+            },
             Self::stringify_uuid(&class.uuid),
-            class.name,
+            localized(&class.name, &class.name_translations, &self.languages),
         ));
         if !class.template_parameters.is_empty() {
             self.plantuml_structures.push_str(&format!(
@@ -120,24 +160,39 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         self.plantuml_structures.push_str("}\n");
     }
     fn visit_generalization(&mut self, link: &UmlClassGeneralization) {
-        for source in link
+        if !self.includes(&link.uuid) {
+            return;
+        }
+        let sources: Vec<_> = link
             .sources
             .iter()
-            .map(|e| Self::stringify_uuid(&e.read().uuid))
-        {
-            for target in link
-                .targets
-                .iter()
-                .map(|e| Self::stringify_uuid(&e.read().uuid))
-            {
-                self.plantuml_links.push_str(&source);
+            .map(|e| *e.read().uuid)
+            .filter(|e| self.includes(e))
+            .map(|e| Self::stringify_uuid(&e))
+            .collect();
+        let targets: Vec<_> = link
+            .targets
+            .iter()
+            .map(|e| *e.read().uuid)
+            .filter(|e| self.includes(e))
+            .map(|e| Self::stringify_uuid(&e))
+            .collect();
+        for source in &sources {
+            for target in &targets {
+                self.plantuml_links.push_str(source);
                 self.plantuml_links.push_str(" --|> ");
-                self.plantuml_links.push_str(&target);
+                self.plantuml_links.push_str(target);
                 self.plantuml_links.push('\n');
             }
         }
     }
     fn visit_dependency(&mut self, link: &UmlClassDependency) {
+        if ![*link.uuid, *link.source.uuid(), *link.target.uuid()]
+            .iter()
+            .all(|e| self.includes(e))
+        {
+            return;
+        }
         let source = Self::stringify_uuid(&link.source.uuid());
         let target = Self::stringify_uuid(&link.target.uuid());
 
@@ -155,6 +210,12 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         self.plantuml_links.push('\n');
     }
     fn visit_association(&mut self, link: &UmlClassAssociation) {
+        if ![*link.uuid, *link.source.uuid(), *link.target.uuid()]
+            .iter()
+            .all(|e| self.includes(e))
+        {
+            return;
+        }
         let source = Self::stringify_uuid(&link.source.uuid());
         let target = Self::stringify_uuid(&link.target.uuid());
 
@@ -201,6 +262,9 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         self.plantuml_links.push('\n');
     }
     fn visit_comment(&mut self, comment: &UmlClassComment) {
+        if !self.includes(&comment.uuid) {
+            return;
+        }
         let s = {
             let mut s = String::new();
             if !comment.stereotype.is_empty() {
@@ -208,7 +272,11 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
                 s.push_str(&comment.stereotype);
                 s.push_str(">>\n");
             }
-            s.push_str(&comment.text);
+            s.push_str(localized(
+                &comment.text,
+                &comment.text_translations,
+                &self.languages,
+            ));
             s
         };
         self.plantuml_structures.push_str(&format!(
@@ -218,6 +286,16 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         ));
     }
     fn visit_commentlink(&mut self, comment_link: &UmlClassCommentLink) {
+        if ![
+            *comment_link.uuid,
+            *comment_link.source.read().uuid,
+            *comment_link.target.uuid(),
+        ]
+        .iter()
+        .all(|e| self.includes(e))
+        {
+            return;
+        }
         self.plantuml_links.push_str(&format!(
             "{} .. {}\n",
             Self::stringify_uuid(&comment_link.source.read().uuid),
@@ -226,6 +304,9 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
     }
 
     fn visit_usecase(&mut self, usecase: &UmlUseCase) {
+        if !self.includes(&usecase.uuid) {
+            return;
+        }
         self.plantuml_structures.push_str(&format!(
             "class {} as {:?} <<usecase>> ",
             Self::stringify_uuid(&usecase.uuid),
@@ -240,21 +321,173 @@ impl UmlClassVisitor for UmlClassPlantUmlCollector {
         self.plantuml_structures.push_str("{}\n");
     }
     fn visit_usecasegeneralization(&mut self, g: &UmlUseCaseGeneralization) {
-        for source in g
+        if !self.includes(&g.uuid) {
+            return;
+        }
+        let sources: Vec<_> = g
             .sources
             .iter()
-            .map(|e| Self::stringify_uuid(&e.read().uuid))
-        {
-            for target in g
-                .targets
-                .iter()
-                .map(|e| Self::stringify_uuid(&e.read().uuid))
-            {
-                self.plantuml_links.push_str(&source);
+            .map(|e| *e.read().uuid)
+            .filter(|e| self.includes(e))
+            .map(|e| Self::stringify_uuid(&e))
+            .collect();
+        let targets: Vec<_> = g
+            .targets
+            .iter()
+            .map(|e| *e.read().uuid)
+            .filter(|e| self.includes(e))
+            .map(|e| Self::stringify_uuid(&e))
+            .collect();
+        for source in &sources {
+            for target in &targets {
+                self.plantuml_links.push_str(source);
                 self.plantuml_links.push_str(" --|> ");
-                self.plantuml_links.push_str(&target);
+                self.plantuml_links.push_str(target);
                 self.plantuml_links.push('\n');
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::{eref::ERef, uuid::ModelUuid},
+        domains::umlclass::umlclass_models::{
+            UmlClass, UmlClassDiagram, UmlClassElement, UmlClassGeneralization, UmlClassPackage,
+            UmlClassPackageKind,
+        },
+    };
+
+    #[test]
+    fn document_contains_packages_and_generalizations() {
+        let class = |name: &str, is_abstract| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                is_abstract,
+                vec![],
+                vec![],
+            ))
+        };
+        let (vehicle, car) = (class("Vehicle", true), class("Car", false));
+        let package = UmlClassPackage::new(
+            ModelUuid::now_v7(),
+            "vehicles".to_owned(),
+            String::new(),
+            UmlClassPackageKind::Package,
+            vec![
+                UmlClassElement::Class(vehicle.clone()),
+                UmlClassElement::Class(car.clone()),
+            ],
+        );
+        let generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            vec![car],
+            vec![vehicle],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Fleet".to_owned(),
+            vec![
+                UmlClassElement::Package(ERef::new(package)),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+            ],
+        );
+
+        let document = diagram.plantuml_document(&[]);
+        assert!(document.starts_with("@startuml\ntitle Fleet\npackage "));
+        assert!(document.contains("abstract class "));
+        assert!(document.contains(" --|> "));
+        assert!(document.ends_with("@enduml\n"));
+    }
+
+    #[test]
+    fn selection_keeps_only_relationships_among_selected_elements() {
+        let class = |name: &str| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                false,
+                vec![],
+                vec![],
+            ))
+        };
+        let (vehicle, car, bike) = (class("Vehicle"), class("Car"), class("Bike"));
+        let generalization = UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            vec![car.clone(), bike.clone()],
+            vec![vehicle.clone()],
+        );
+        let generalization_uuid = *generalization.uuid;
+        let package = UmlClassPackage::new(
+            ModelUuid::now_v7(),
+            "vehicles".to_owned(),
+            String::new(),
+            UmlClassPackageKind::Package,
+            vec![
+                UmlClassElement::Class(vehicle.clone()),
+                UmlClassElement::Class(car.clone()),
+                UmlClassElement::Class(bike),
+            ],
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Fleet".to_owned(),
+            vec![
+                UmlClassElement::Package(ERef::new(package)),
+                UmlClassElement::Generalization(ERef::new(generalization)),
+            ],
+        );
+
+        let selection = [*vehicle.read().uuid, *car.read().uuid, generalization_uuid]
+            .into_iter()
+            .collect();
+        let document = diagram.plantuml_document_of(&[], &selection);
+        assert!(document.starts_with("@startuml\nclass "));
+        assert!(!document.contains("package "));
+        assert!(document.contains("\"Vehicle\""));
+        assert!(!document.contains("\"Bike\""));
+        assert_eq!(document.matches(" --|> ").count(), 1);
+        assert!(document.ends_with("@enduml\n"));
+    }
+
+    #[test]
+    fn class_names_follow_requested_language() {
+        let mut class = UmlClass::new(
+            ModelUuid::now_v7(),
+            "Vehicle".to_owned(),
+            String::new(),
+            String::new(),
+            false,
+            vec![],
+            vec![],
+        );
+        class
+            .name_translations
+            .insert("cs-CZ".to_owned(), "Vozidlo".to_owned().into());
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Fleet".to_owned(),
+            vec![UmlClassElement::Class(ERef::new(class))],
+        );
+
+        assert!(diagram.plantuml(&[]).contains("\"Vehicle\""));
+        assert!(
+            diagram
+                .plantuml(&[unic_langid::langid!("en-US")])
+                .contains("\"Vehicle\"")
+        );
+        assert!(
+            diagram
+                .plantuml(&[unic_langid::langid!("cs-CZ"), unic_langid::langid!("en-US")])
+                .contains("\"Vozidlo\"")
+        );
+    }
+}
@@ -0,0 +1,852 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        uuid::ViewUuid,
+    },
+    domains::umlclass::umlclass_models::{
+        UmlClassAssociationAggregation, UmlClassAssociationNavigability, UmlClassVisibilityKind,
+    },
+};
+
+pub struct PlantUmlPackage {
+    pub name: String,
+    pub stereotype: String,
+    pub parent: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct PlantUmlMember {
+    pub visibility: Option<UmlClassVisibilityKind>,
+    pub name: String,
+    /// Type of properties, return type of operations
+    pub value_type: String,
+    /// Parameters of operations, None for properties
+    pub parameters: Option<String>,
+    pub multiplicity: String,
+    pub default_value: String,
+    pub stereotype: String,
+}
+
+pub struct PlantUmlClass {
+    pub name: String,
+    pub stereotype: String,
+    pub is_abstract: bool,
+    pub members: Vec<PlantUmlMember>,
+    pub package: Option<usize>,
+}
+
+#[derive(Clone, Default)]
+pub struct PlantUmlAssociationEnd {
+    pub navigability: UmlClassAssociationNavigability,
+    pub aggregation: UmlClassAssociationAggregation,
+    pub role: String,
+}
+
+pub enum PlantUmlLinkKind {
+    Generalization,
+    Realization,
+    Dependency,
+    Association(PlantUmlAssociationEnd, PlantUmlAssociationEnd),
+}
+
+pub struct PlantUmlLink {
+    pub kind: PlantUmlLinkKind,
+    pub source: usize,
+    pub target: usize,
+    pub source_multiplicity: String,
+    pub target_multiplicity: String,
+    pub label: String,
+    /// Stereotype of associations
+    pub stereotype: String,
+}
+
+#[derive(Default)]
+pub struct PlantUmlClassDiagram {
+    pub title: Option<String>,
+    pub packages: Vec<PlantUmlPackage>,
+    pub classes: Vec<PlantUmlClass>,
+    pub links: Vec<PlantUmlLink>,
+    /// Numbers of lines that were not understood
+    pub skipped_lines: Vec<usize>,
+}
+
+const IGNORED_PREFIXES: [&str; 8] = [
+    "@start",
+    "@end",
+    "skinparam",
+    "hide ",
+    "show ",
+    "left to right direction",
+    "top to bottom direction",
+    "!",
+];
+
+const CLASS_KEYWORDS: [(&str, &str, bool); 8] = [
+    ("abstract class", "", true),
+    ("abstract", "", true),
+    ("class", "", false),
+    ("interface", "interface", false),
+    ("enum", "enumeration", false),
+    ("annotation", "annotation", false),
+    ("entity", "entity", false),
+    ("struct", "struct", false),
+];
+
+const PACKAGE_KEYWORDS: [&str; 4] = ["package", "namespace", "folder", "frame"];
+
+/// Splits the line into whitespace separated tokens, keeping quoted strings (with the quotes) together.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => {
+                if start.is_none() {
+                    start = Some(i);
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if let Some(s) = start.take() {
+                    tokens.push(&line[s..i]);
+                }
+            }
+            _ => {
+                if start.is_none() {
+                    start = Some(i);
+                }
+            }
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&line[s..]);
+    }
+    tokens
+}
+
+fn unquote(token: &str) -> &str {
+    token
+        .strip_prefix('"')
+        .and_then(|e| e.strip_suffix('"'))
+        .unwrap_or(token)
+}
+
+fn is_quoted(token: &str) -> bool {
+    token.len() >= 2 && token.starts_with('"') && token.ends_with('"')
+}
+
+/// Finds the first occurence of the character outside of quotes
+fn find_unquoted(line: &str, needle: char) -> Option<usize> {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c == needle && !in_quotes => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn strip_keyword<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(keyword)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim_start())
+}
+
+struct Declaration {
+    id: String,
+    name: String,
+    stereotype: Option<String>,
+    extends: Vec<String>,
+    implements: Vec<String>,
+    opens_body: bool,
+}
+
+/// Parses `Name [as Alias] [<T>] [<<stereotype>>] [extends A] [implements B, C] [#color] [{]`
+fn parse_declaration(rest: &str) -> Option<Declaration> {
+    let mut rest = rest.trim().to_owned();
+    let opens_body = if let Some(r) = rest.strip_suffix("{}") {
+        rest = r.trim_end().to_owned();
+        false
+    } else if let Some(r) = rest.strip_suffix('{') {
+        rest = r.trim_end().to_owned();
+        true
+    } else {
+        false
+    };
+
+    let mut stereotype = None;
+    if let Some(start) = rest.find("<<")
+        && let Some(end) = rest[start..].find(">>")
+    {
+        stereotype = Some(rest[start + 2..start + end].trim().to_owned());
+        rest.replace_range(start..start + end + 2, " ");
+    }
+    // Template parameters are not imported
+    if let Some(start) = find_unquoted(&rest, '<')
+        && let Some(end) = rest[start..].find('>')
+    {
+        rest.replace_range(start..start + end + 1, " ");
+    }
+
+    let tokens: Vec<_> = tokenize(&rest)
+        .into_iter()
+        .filter(|e| !e.starts_with('#'))
+        .collect();
+    let first = *tokens.first()?;
+    let (mut id, mut name) = (unquote(first).to_owned(), unquote(first).to_owned());
+    let mut idx = 1;
+    if tokens.get(1) == Some(&"as") {
+        let alias = tokens.get(2)?;
+        if is_quoted(alias) && !is_quoted(first) {
+            name = unquote(alias).to_owned();
+        } else {
+            id = unquote(alias).to_owned();
+        }
+        idx = 3;
+    }
+
+    let (mut extends, mut implements) = (Vec::new(), Vec::new());
+    let mut current = None;
+    for t in &tokens[idx..] {
+        match *t {
+            "extends" => current = Some(&mut extends),
+            "implements" => current = Some(&mut implements),
+            other => {
+                let Some(list) = current.as_mut() else {
+                    continue;
+                };
+                list.extend(
+                    other
+                        .split(',')
+                        .filter(|e| !e.is_empty())
+                        .map(|e| unquote(e).to_owned()),
+                );
+            }
+        }
+    }
+
+    Some(Declaration {
+        id,
+        name,
+        stereotype,
+        extends,
+        implements,
+        opens_body,
+    })
+}
+
+/// Parses a class member line, returning None for compartment separators
+pub(crate) fn parse_member(line: &str) -> Option<PlantUmlMember> {
+    if line.starts_with("--")
+        || line.starts_with("==")
+        || line.starts_with("..")
+        || line.starts_with("__")
+    {
+        return None;
+    }
+    let mut s = line.to_owned();
+    for modifier in [
+        "{abstract}",
+        "{static}",
+        "{classifier}",
+        "{field}",
+        "{method}",
+    ] {
+        s = s.replace(modifier, "");
+    }
+    let mut s = s.trim();
+
+    let visibility = match s.chars().next()? {
+        '+' => Some(UmlClassVisibilityKind::Public),
+        '~' => Some(UmlClassVisibilityKind::Package),
+        '#' => Some(UmlClassVisibilityKind::Protected),
+        '-' => Some(UmlClassVisibilityKind::Private),
+        _ => None,
+    };
+    if visibility.is_some() {
+        s = s[1..].trim_start();
+    }
+
+    /// Splits "Type name" into ("Type", "name")
+    fn split_c_style(s: &str) -> (String, String) {
+        match s.rsplit_once(char::is_whitespace) {
+            Some((t, n)) => (t.trim().to_owned(), n.trim().to_owned()),
+            None => (String::new(), s.to_owned()),
+        }
+    }
+
+    if let Some(open) = s.find('(')
+        && let Some(close) = s.rfind(')')
+        && open < close
+    {
+        let (mut value_type, name) = split_c_style(s[..open].trim());
+        if let Some(return_type) = s[close + 1..].trim().strip_prefix(':') {
+            value_type = return_type.trim().to_owned();
+        }
+        return Some(PlantUmlMember {
+            visibility,
+            name,
+            value_type,
+            parameters: Some(s[open + 1..close].trim().to_owned()),
+            ..PlantUmlMember::default()
+        });
+    }
+
+    let (value_type, name) = match s.split_once(':') {
+        Some((n, t)) => (t.trim().to_owned(), n.trim().to_owned()),
+        None => split_c_style(s),
+    };
+    (!name.is_empty()).then_some(PlantUmlMember {
+        visibility,
+        name,
+        value_type,
+        ..PlantUmlMember::default()
+    })
+}
+
+struct Arrow<'a> {
+    left_head: &'a str,
+    right_head: &'a str,
+    dotted: bool,
+}
+
+/// Recognizes relationship arrows such as `<|--`, `*-->`, `..>` or `-up->`
+fn parse_arrow(token: &str) -> Option<Arrow<'_>> {
+    const HEAD_CHARS: &str = "<>|*ox^#+";
+    let body_start = token.find(['-', '.'])?;
+    let body_end = token.rfind(['-', '.'])? + 1;
+    let (left_head, body, right_head) = (
+        &token[..body_start],
+        &token[body_start..body_end],
+        &token[body_end..],
+    );
+    if !left_head
+        .chars()
+        .chain(right_head.chars())
+        .all(|e| HEAD_CHARS.contains(e))
+    {
+        return None;
+    }
+
+    // Inline styles such as `-[#red]->` and direction hints such as `-up->` are ignored
+    let unstyled: String = body.split(['[', ']']).step_by(2).collect();
+    let direction = unstyled.replace(['-', '.'], "");
+    if !["", "up", "down", "left", "right", "u", "d", "l", "r"].contains(&direction.as_str()) {
+        return None;
+    }
+
+    Some(Arrow {
+        left_head,
+        right_head,
+        dotted: unstyled.contains('.'),
+    })
+}
+
+fn association_end(head: &str) -> PlantUmlAssociationEnd {
+    let mut end = PlantUmlAssociationEnd::default();
+    match head {
+        "*" => end.aggregation = UmlClassAssociationAggregation::Composite,
+        "o" => end.aggregation = UmlClassAssociationAggregation::Shared,
+        "<" | ">" => end.navigability = UmlClassAssociationNavigability::Navigable,
+        "x" => end.navigability = UmlClassAssociationNavigability::NonNavigable,
+        _ => {}
+    }
+    end
+}
+
+struct Parser {
+    diagram: PlantUmlClassDiagram,
+    ids: HashMap<String, usize>,
+    package_stack: Vec<usize>,
+}
+
+impl Parser {
+    fn class_index(&mut self, id: &str) -> usize {
+        if let Some(idx) = self.ids.get(id) {
+            return *idx;
+        }
+        self.diagram.classes.push(PlantUmlClass {
+            name: id.to_owned(),
+            stereotype: String::new(),
+            is_abstract: false,
+            members: Vec::new(),
+            package: self.package_stack.last().copied(),
+        });
+        self.ids
+            .insert(id.to_owned(), self.diagram.classes.len() - 1);
+        self.diagram.classes.len() - 1
+    }
+
+    fn push_link(&mut self, kind: PlantUmlLinkKind, source: &str, target: &str) {
+        let (source, target) = (self.class_index(source), self.class_index(target));
+        self.diagram.links.push(PlantUmlLink {
+            kind,
+            source,
+            target,
+            source_multiplicity: String::new(),
+            target_multiplicity: String::new(),
+            label: String::new(),
+            stereotype: String::new(),
+        });
+    }
+
+    /// Parses `A ["m"] arrow ["m"] B [: label]`
+    fn try_relationship(&mut self, line: &str) -> bool {
+        let (head, label) = match find_unquoted(line, ':') {
+            Some(i) => (&line[..i], line[i + 1..].trim()),
+            None => (line, ""),
+        };
+        let tokens = tokenize(head);
+        let Some(arrow_idx) = tokens
+            .iter()
+            .position(|e| !is_quoted(e) && parse_arrow(e).is_some())
+        else {
+            return false;
+        };
+        let arrow = parse_arrow(tokens[arrow_idx]).unwrap();
+        let (left, right) = (&tokens[..arrow_idx], &tokens[arrow_idx + 1..]);
+        let (left, left_multiplicity) = match left {
+            [e] => (unquote(e), ""),
+            [e, m] if is_quoted(m) => (unquote(e), unquote(m)),
+            _ => return false,
+        };
+        let (right, right_multiplicity) = match right {
+            [e] => (unquote(e), ""),
+            [m, e] if is_quoted(m) => (unquote(e), unquote(m)),
+            _ => return false,
+        };
+        let label = label
+            .trim_start_matches(['<', '>'])
+            .trim_end_matches(['<', '>'])
+            .trim();
+
+        match (arrow.left_head, arrow.right_head) {
+            ("<|" | "^", _) | (_, "|>" | "^") => {
+                let kind = if arrow.dotted {
+                    PlantUmlLinkKind::Realization
+                } else {
+                    PlantUmlLinkKind::Generalization
+                };
+                let (source, target) = if matches!(arrow.left_head, "<|" | "^") {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                self.push_link(kind, source, target);
+            }
+            (l, _) if arrow.dotted => {
+                let (source, target) = if l == "<" {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                self.push_link(PlantUmlLinkKind::Dependency, source, target);
+            }
+            (l, r) => {
+                self.push_link(
+                    PlantUmlLinkKind::Association(association_end(l), association_end(r)),
+                    left,
+                    right,
+                );
+                let link = self.diagram.links.last_mut().unwrap();
+                link.source_multiplicity = left_multiplicity.to_owned();
+                link.target_multiplicity = right_multiplicity.to_owned();
+            }
+        }
+        self.diagram.links.last_mut().unwrap().label = label.to_owned();
+        true
+    }
+}
+
+/// Parses the class diagram subset of PlantUML.
+///
+/// Classes, interfaces, enums, packages, members and relationships are recognized,
+/// other statements such as notes or styling are skipped. Classes only referenced
+/// by relationships are created in the package the relationship appears in.
+pub fn parse_plantuml(source: &str) -> Result<PlantUmlClassDiagram, String> {
+    let mut p = Parser {
+        diagram: PlantUmlClassDiagram::default(),
+        ids: HashMap::new(),
+        package_stack: Vec::new(),
+    };
+    let mut open_class: Option<(usize, usize)> = None;
+    let mut in_block_comment = false;
+    let mut in_note = false;
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+
+        if in_block_comment {
+            in_block_comment = !line.contains("'/");
+            continue;
+        }
+        if line.starts_with("/'") {
+            in_block_comment = !line.contains("'/");
+            continue;
+        }
+        if in_note {
+            in_note = !line.starts_with("end note");
+            continue;
+        }
+        if line.is_empty() || line.starts_with('\'') {
+            continue;
+        }
+
+        if let Some((class, _)) = open_class {
+            if line == "}" {
+                open_class = None;
+            } else if let Some(m) = parse_member(line) {
+                p.diagram.classes[class].members.push(m);
+            }
+            continue;
+        }
+
+        if IGNORED_PREFIXES.iter().any(|e| line.starts_with(e)) {
+            continue;
+        }
+        if let Some(title) = strip_keyword(line, "title") {
+            p.diagram.title = Some(title.to_owned());
+            continue;
+        }
+        if line == "}" {
+            if p.package_stack.pop().is_none() {
+                return Err(format!("line {}: unmatched '}}'", line_no));
+            }
+            continue;
+        }
+        if strip_keyword(line, "note").is_some() {
+            in_note = !line.contains(':') && !line.contains('"');
+            p.diagram.skipped_lines.push(line_no);
+            continue;
+        }
+
+        if let Some(rest) = PACKAGE_KEYWORDS.iter().find_map(|k| strip_keyword(line, k)) {
+            let Some(d) = parse_declaration(rest) else {
+                return Err(format!("line {}: package without a name", line_no));
+            };
+            p.diagram.packages.push(PlantUmlPackage {
+                name: d.name,
+                stereotype: d.stereotype.unwrap_or_default(),
+                parent: p.package_stack.last().copied(),
+            });
+            if d.opens_body {
+                p.package_stack.push(p.diagram.packages.len() - 1);
+            }
+            continue;
+        }
+
+        if let Some((rest, stereotype, is_abstract)) = CLASS_KEYWORDS
+            .iter()
+            .find_map(|(k, s, a)| strip_keyword(line, k).map(|r| (r, *s, *a)))
+        {
+            let Some(d) = parse_declaration(rest) else {
+                return Err(format!("line {}: class without a name", line_no));
+            };
+            let class = p.class_index(&d.id);
+            let c = &mut p.diagram.classes[class];
+            c.name = d.name;
+            c.stereotype = d.stereotype.unwrap_or_else(|| stereotype.to_owned());
+            c.is_abstract = is_abstract;
+            c.package = p.package_stack.last().copied();
+            for e in &d.extends {
+                p.push_link(PlantUmlLinkKind::Generalization, &d.id, e);
+            }
+            for e in &d.implements {
+                p.push_link(PlantUmlLinkKind::Realization, &d.id, e);
+            }
+            if d.opens_body {
+                open_class = Some((class, line_no));
+            }
+            continue;
+        }
+
+        if p.try_relationship(line) {
+            continue;
+        }
+
+        // Members can also be added as `Class : member`
+        if let Some(i) = find_unquoted(line, ':')
+            && let Some(class) = p.ids.get(unquote(line[..i].trim())).copied()
+            && let Some(m) = parse_member(line[i + 1..].trim())
+        {
+            p.diagram.classes[class].members.push(m);
+            continue;
+        }
+
+        p.diagram.skipped_lines.push(line_no);
+    }
+
+    if let Some((_, line_no)) = open_class {
+        return Err(format!("line {}: class body is not closed", line_no));
+    }
+    if !p.package_stack.is_empty() {
+        return Err("package body is not closed".to_owned());
+    }
+    Ok(p.diagram)
+}
+
+pub const CLASS_WIDTH: f32 = 180.0;
+const GRID_GAP: egui::Vec2 = egui::Vec2::new(60.0, 60.0);
+const GRID_COLUMNS: usize = 4;
+const PACKAGE_PADDING: egui::Vec2 = egui::Vec2::new(20.0, 40.0);
+
+/// Places classes and packages on a grid, laying out the contents of each package the same way.
+///
+/// Returns the center of each class and the bounds of each package.
+pub fn grid_layout(diagram: &PlantUmlClassDiagram) -> (Vec<egui::Pos2>, Vec<egui::Rect>) {
+    #[derive(Clone, Copy)]
+    enum Item {
+        Package(usize),
+        Class(usize),
+    }
+
+    let mut children: HashMap<Option<usize>, Vec<Item>> = HashMap::new();
+    for (i, p) in diagram.packages.iter().enumerate() {
+        children.entry(p.parent).or_default().push(Item::Package(i));
+    }
+    for (i, c) in diagram.classes.iter().enumerate() {
+        children.entry(c.package).or_default().push(Item::Class(i));
+    }
+
+    fn class_size(c: &PlantUmlClass) -> egui::Vec2 {
+        egui::Vec2::new(CLASS_WIDTH, 50.0 + 18.0 * c.members.len() as f32)
+    }
+
+    struct Layout<'a> {
+        diagram: &'a PlantUmlClassDiagram,
+        children: &'a HashMap<Option<usize>, Vec<Item>>,
+        classes: Vec<egui::Pos2>,
+        packages: Vec<egui::Rect>,
+    }
+
+    impl Layout<'_> {
+        fn size(&self, item: Item) -> egui::Vec2 {
+            match item {
+                Item::Class(i) => class_size(&self.diagram.classes[i]),
+                Item::Package(i) => self.contents_size(Some(i)) + 2.0 * PACKAGE_PADDING,
+            }
+        }
+        fn rows(&self, container: Option<usize>) -> Vec<Vec<(Item, egui::Vec2)>> {
+            let items = self.children.get(&container).map(|e| e.as_slice());
+            items
+                .unwrap_or_default()
+                .chunks(GRID_COLUMNS)
+                .map(|row| row.iter().map(|e| (*e, self.size(*e))).collect())
+                .collect()
+        }
+        fn contents_size(&self, container: Option<usize>) -> egui::Vec2 {
+            let rows = self.rows(container);
+            let width = rows
+                .iter()
+                .map(|r| r.iter().map(|e| e.1.x).sum::<f32>() + GRID_GAP.x * (r.len() - 1) as f32)
+                .fold(CLASS_WIDTH, f32::max);
+            let height = rows
+                .iter()
+                .map(|r| r.iter().map(|e| e.1.y).fold(0.0, f32::max))
+                .sum::<f32>()
+                + GRID_GAP.y * rows.len().saturating_sub(1) as f32;
+            egui::Vec2::new(width, height.max(50.0))
+        }
+        fn place(&mut self, container: Option<usize>, origin: egui::Pos2) {
+            let mut y = origin.y;
+            for row in self.rows(container) {
+                let mut x = origin.x;
+                let row_height = row.iter().map(|e| e.1.y).fold(0.0, f32::max);
+                for (item, size) in row {
+                    let rect = egui::Rect::from_min_size(egui::Pos2::new(x, y), size);
+                    match item {
+                        Item::Class(i) => self.classes[i] = rect.center(),
+                        Item::Package(i) => {
+                            self.packages[i] = rect;
+                            self.place(Some(i), rect.min + PACKAGE_PADDING);
+                        }
+                    }
+                    x += size.x + GRID_GAP.x;
+                }
+                y += row_height + GRID_GAP.y;
+            }
+        }
+    }
+
+    let mut layout = Layout {
+        diagram,
+        children: &children,
+        classes: vec![egui::Pos2::ZERO; diagram.classes.len()],
+        packages: vec![egui::Rect::NOTHING; diagram.packages.len()],
+    };
+    layout.place(None, egui::Pos2::new(50.0, 50.0));
+    (layout.classes, layout.packages)
+}
+
+pub struct PlantUmlImportTab {
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for PlantUmlImportTab {
+    fn default() -> Self {
+        Self {
+            source: "@startuml\npackage shapes {\n  abstract class Shape {\n    +area() : double\n  }\n  class Circle {\n    -radius : double\n  }\n}\nShape <|-- Circle\nclass Canvas\nCanvas \"1\" o-- \"*\" Shape : contains\n@enduml\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for PlantUmlImportTab {
+    fn title(&self) -> String {
+        "PlantUML Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("PlantUML class diagram");
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_plantuml(&self.source) {
+                    Ok(diagram) => {
+                        let (uuid, controller) =
+                            super::umlclass_controllers::from_plantuml(&diagram);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        let mut message = format!(
+                            "Imported {} classes, {} packages and {} relationships",
+                            diagram.classes.len(),
+                            diagram.packages.len(),
+                            diagram.links.len(),
+                        );
+                        if !diagram.skipped_lines.is_empty() {
+                            message.push_str(&format!(
+                                ", skipped lines {}",
+                                diagram
+                                    .skipped_lines
+                                    .iter()
+                                    .map(|e| e.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ));
+                        }
+                        Ok(message)
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_packages_and_relationships_are_parsed() {
+        let d = parse_plantuml(
+            "@startuml\n\
+             title Shapes\n\
+             package geometry <<core>> {\n\
+               abstract class Shape {\n\
+                 +area() : double\n\
+                 --\n\
+                 #String name\n\
+               }\n\
+               class \"Round circle\" as Circle extends Shape\n\
+             }\n\
+             interface Drawable\n\
+             Circle ..|> Drawable\n\
+             Canvas \"1\" *-up-> \"0..*\" Shape : contains >\n\
+             Circle : -radius : double\n\
+             note left of Canvas\n\
+               ignored\n\
+             end note\n\
+             @enduml\n",
+        )
+        .unwrap();
+
+        assert_eq!(d.title.as_deref(), Some("Shapes"));
+        assert_eq!(d.packages.len(), 1);
+        assert_eq!(d.packages[0].stereotype, "core");
+        let names: Vec<_> = d.classes.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Shape", "Round circle", "Drawable", "Canvas"]);
+        assert!(d.classes[0].is_abstract);
+        assert_eq!(d.classes[1].package, Some(0));
+        assert_eq!(d.classes[2].stereotype, "interface");
+        assert_eq!(d.classes[3].package, None);
+
+        let shape = &d.classes[0].members;
+        assert_eq!(shape.len(), 2);
+        assert_eq!(shape[0].parameters.as_deref(), Some(""));
+        assert_eq!(shape[0].value_type, "double");
+        assert_eq!(
+            (shape[1].name.as_str(), shape[1].value_type.as_str()),
+            ("name", "String")
+        );
+        assert_eq!(d.classes[1].members[0].name, "radius");
+
+        assert_eq!(d.links.len(), 3);
+        assert!(matches!(d.links[0].kind, PlantUmlLinkKind::Generalization));
+        assert_eq!((d.links[0].source, d.links[0].target), (1, 0));
+        assert!(matches!(d.links[1].kind, PlantUmlLinkKind::Realization));
+        let PlantUmlLinkKind::Association(source, target) = &d.links[2].kind else {
+            panic!("expected an association");
+        };
+        assert!(source.aggregation == UmlClassAssociationAggregation::Composite);
+        assert!(target.navigability == UmlClassAssociationNavigability::Navigable);
+        assert_eq!(d.links[2].source_multiplicity, "1");
+        assert_eq!(d.links[2].target_multiplicity, "0..*");
+        assert_eq!(d.links[2].label, "contains");
+        assert_eq!(d.skipped_lines, [15]);
+    }
+
+    #[test]
+    fn unclosed_bodies_are_errors() {
+        assert!(parse_plantuml("class A {\n+x : int\n").is_err());
+        assert!(parse_plantuml("package p {\nclass A\n").is_err());
+        assert!(parse_plantuml("}\n").is_err());
+    }
+
+    #[test]
+    fn packages_contain_their_classes() {
+        let d = parse_plantuml("package p {\nclass A\nclass B\n}\nclass C\n").unwrap();
+        let (classes, packages) = grid_layout(&d);
+        assert!(packages[0].contains(classes[0]) && packages[0].contains(classes[1]));
+        assert!(!packages[0].contains(classes[2]));
+    }
+}
@@ -0,0 +1,793 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::{
+    CustomTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand, TextExportFormat},
+        eref::ERef,
+        naming::NamingConvention,
+        uuid::ModelUuid,
+    },
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassComment, UmlClassCommentLink,
+        UmlClassDependency, UmlClassDiagram, UmlClassGeneralization, UmlClassInstance,
+        UmlClassPackage, UmlClassVisitor, UmlUseCase, UmlUseCaseGeneralization,
+        multiplicity_bounds,
+    },
+};
+
+pub const SQL_DDL_FORMAT: TextExportFormat = TextExportFormat {
+    name: "SQL DDL",
+    extensions: &["sql"],
+};
+
+/// Words which have to be quoted when used as table or column names
+const RESERVED_WORDS: [&str; 24] = [
+    "all",
+    "and",
+    "as",
+    "by",
+    "check",
+    "column",
+    "constraint",
+    "create",
+    "default",
+    "foreign",
+    "from",
+    "group",
+    "in",
+    "key",
+    "not",
+    "null",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "user",
+    "where",
+];
+
+fn quote(name: &str) -> String {
+    let is_plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED_WORDS.iter().any(|e| e.eq_ignore_ascii_case(name));
+    if is_plain {
+        name.to_owned()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SqlDdlOptions {
+    pub table_naming: NamingConvention,
+    pub column_naming: NamingConvention,
+    /// SQL types of UML types, types without a mapping are used as they are
+    pub type_mappings: Vec<(String, String)>,
+    /// Type of properties without one
+    pub default_type: String,
+    /// Type of keys added to classes without an identifying property
+    pub key_type: String,
+}
+
+impl Default for SqlDdlOptions {
+    fn default() -> Self {
+        Self {
+            table_naming: NamingConvention::SnakeCase,
+            column_naming: NamingConvention::SnakeCase,
+            type_mappings: [
+                ("String", "varchar(255)"),
+                ("Integer", "integer"),
+                ("int", "integer"),
+                ("Long", "bigint"),
+                ("UnlimitedNatural", "integer"),
+                ("Real", "double precision"),
+                ("Double", "double precision"),
+                ("Float", "real"),
+                ("Decimal", "numeric"),
+                ("Boolean", "boolean"),
+                ("bool", "boolean"),
+                ("Date", "date"),
+                ("DateTime", "timestamp"),
+                ("Time", "time"),
+            ]
+            .into_iter()
+            .map(|(a, b)| (a.to_owned(), b.to_owned()))
+            .collect(),
+            default_type: "text".to_owned(),
+            key_type: "integer".to_owned(),
+        }
+    }
+}
+
+impl SqlDdlOptions {
+    fn sql_type(&self, uml_type: &str) -> String {
+        let uml_type = uml_type.trim();
+        if uml_type.is_empty() {
+            return self.default_type.clone();
+        }
+        self.type_mappings
+            .iter()
+            .find(|e| e.0.trim().eq_ignore_ascii_case(uml_type))
+            .map(|e| e.1.clone())
+            .unwrap_or_else(|| uml_type.to_owned())
+    }
+}
+
+struct PropertyInfo {
+    name: String,
+    value_type: String,
+    multiplicity: String,
+    default_value: String,
+    is_key: bool,
+}
+
+struct ClassInfo {
+    uuid: ModelUuid,
+    name: String,
+    schema: Option<String>,
+    properties: Vec<PropertyInfo>,
+}
+
+/// Reference from rows of one class to rows of another one
+struct ReferenceInfo {
+    source: ModelUuid,
+    target: ModelUuid,
+    /// Name of the referenced end, used to name the columns
+    role: String,
+    /// Columns already present in the source to use, if any
+    columns: Vec<String>,
+    is_optional: bool,
+    is_unique: bool,
+}
+
+struct JoinInfo {
+    name: String,
+    ends: [(ModelUuid, String); 2],
+}
+
+#[derive(Default)]
+struct SqlDdlCollector {
+    schemas: Vec<String>,
+    classes: Vec<ClassInfo>,
+    references: Vec<ReferenceInfo>,
+    joins: Vec<JoinInfo>,
+}
+
+impl UmlClassVisitor for SqlDdlCollector {
+    fn visit_package(&mut self, package: &UmlClassPackage) {
+        let is_schema = package.stereotype.eq_ignore_ascii_case("schema");
+        if is_schema {
+            self.schemas.push((*package.name).clone());
+        }
+        for e in &package.contained_elements {
+            e.accept_uml(self);
+        }
+        if is_schema {
+            self.schemas.pop();
+        }
+    }
+    fn visit_instance(&mut self, _instance: &UmlClassInstance) {}
+    fn visit_class(&mut self, class: &UmlClass) {
+        let properties = class
+            .properties
+            .iter()
+            .map(|e| e.read())
+            .filter(|e| !e.is_static && !e.is_derived)
+            .map(|e| PropertyInfo {
+                name: (*e.name).clone(),
+                value_type: (*e.value_type).clone(),
+                multiplicity: (*e.multiplicity).clone(),
+                default_value: (*e.default_value).clone(),
+                is_key: e.is_id
+                    || e.stereotype
+                        .split(',')
+                        .any(|s| s.trim().eq_ignore_ascii_case("PK")),
+            })
+            .collect();
+        self.classes.push(ClassInfo {
+            uuid: *class.uuid,
+            name: (*class.name).clone(),
+            schema: self.schemas.last().cloned(),
+            properties,
+        });
+    }
+    fn visit_usecase(&mut self, _usecase: &UmlUseCase) {}
+    fn visit_generalization(&mut self, generalization: &UmlClassGeneralization) {
+        for source in &generalization.sources {
+            for target in &generalization.targets {
+                let target = target.read();
+                self.references.push(ReferenceInfo {
+                    source: *source.read().uuid,
+                    target: *target.uuid,
+                    role: (*target.name).clone(),
+                    columns: vec![],
+                    is_optional: false,
+                    is_unique: true,
+                });
+            }
+        }
+    }
+    fn visit_dependency(&mut self, _dependency: &UmlClassDependency) {}
+    fn visit_association(&mut self, association: &UmlClassAssociation) {
+        let (UmlClassAssociable::Class(source), UmlClassAssociable::Class(target)) =
+            (&association.source, &association.target)
+        else {
+            return;
+        };
+        let (source, target) = (source.read(), target.read());
+        let (source_lower, source_many) =
+            multiplicity_bounds(&association.source_label_multiplicity);
+        let (target_lower, target_many) =
+            multiplicity_bounds(&association.target_label_multiplicity);
+        // The name of associations read from schemas lists the key columns
+        let columns = association
+            .name
+            .split(',')
+            .map(|e| e.trim().to_owned())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        if !target_many {
+            self.references.push(ReferenceInfo {
+                source: *source.uuid,
+                target: *target.uuid,
+                role: if association.target_label_role.is_empty() {
+                    (*target.name).clone()
+                } else {
+                    (*association.target_label_role).clone()
+                },
+                columns,
+                is_optional: target_lower == 0,
+                is_unique: !source_many,
+            });
+        } else if !source_many {
+            self.references.push(ReferenceInfo {
+                source: *target.uuid,
+                target: *source.uuid,
+                role: if association.source_label_role.is_empty() {
+                    (*source.name).clone()
+                } else {
+                    (*association.source_label_role).clone()
+                },
+                columns,
+                is_optional: source_lower == 0,
+                is_unique: false,
+            });
+        } else {
+            let role = |role: &str, class: &UmlClass| {
+                if role.is_empty() {
+                    (*class.name).clone()
+                } else {
+                    role.to_owned()
+                }
+            };
+            self.joins.push(JoinInfo {
+                name: if association.name.is_empty() {
+                    format!("{} {}", source.name, target.name)
+                } else {
+                    (*association.name).clone()
+                },
+                ends: [
+                    (*source.uuid, role(&association.source_label_role, &source)),
+                    (*target.uuid, role(&association.target_label_role, &target)),
+                ],
+            });
+        }
+    }
+    fn visit_usecasegeneralization(&mut self, _usecasegen: &UmlUseCaseGeneralization) {}
+    fn visit_comment(&mut self, _comment: &UmlClassComment) {}
+    fn visit_commentlink(&mut self, _commentlink: &UmlClassCommentLink) {}
+}
+
+struct Column {
+    name: String,
+    sql_type: String,
+    is_not_null: bool,
+    default_value: String,
+}
+
+struct ForeignKey {
+    columns: Vec<String>,
+    target: usize,
+}
+
+struct Table {
+    schema: Option<String>,
+    /// Unquoted name, columns of foreign keys are named after it
+    name: String,
+    columns: Vec<Column>,
+    primary_key: Vec<String>,
+    unique_keys: Vec<Vec<String>>,
+    foreign_keys: Vec<ForeignKey>,
+}
+
+impl Table {
+    fn qualified_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote(schema), quote(&self.name)),
+            None => quote(&self.name),
+        }
+    }
+    fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|e| e.name == name)
+    }
+    fn unique_column_name(&self, name: String) -> String {
+        if self.column(&name).is_none() {
+            return name;
+        }
+        (2..)
+            .map(|e| format!("{}{}", name, e))
+            .find(|e| self.column(e).is_none())
+            .unwrap()
+    }
+    /// Names and types of columns referencing the primary key
+    fn key_references(&self) -> Vec<(String, String)> {
+        self.primary_key
+            .iter()
+            .map(|k| {
+                let sql_type = self.column(k).map(|e| e.sql_type.as_str()).unwrap_or("");
+                (k.clone(), key_reference_type(sql_type))
+            })
+            .collect()
+    }
+    /// Adds columns referencing the given key, reusing existing columns
+    fn add_foreign_key(
+        &mut self,
+        options: &SqlDdlOptions,
+        target: usize,
+        target_keys: &[(String, String)],
+        role: &str,
+        columns: &[String],
+        is_optional: bool,
+    ) -> Vec<String> {
+        let use_given =
+            columns.len() == target_keys.len() && columns.iter().all(|e| self.column(e).is_some());
+        let mut names = Vec::new();
+        for (idx, (key, sql_type)) in target_keys.iter().enumerate() {
+            if use_given {
+                names.push(columns[idx].clone());
+                continue;
+            }
+            let name = options.column_naming.name(&[role, key]);
+            if self.column(&name).is_none() {
+                self.columns.push(Column {
+                    name: name.clone(),
+                    sql_type: sql_type.clone(),
+                    is_not_null: !is_optional,
+                    default_value: String::new(),
+                });
+            }
+            names.push(name);
+        }
+        self.foreign_keys.push(ForeignKey {
+            columns: names.clone(),
+            target,
+        });
+        names
+    }
+}
+
+/// Type of columns referencing a key of the given type, auto-incrementing types reference plain ones
+fn key_reference_type(sql_type: &str) -> String {
+    match sql_type.to_ascii_lowercase().as_str() {
+        "serial" => "integer".to_owned(),
+        "bigserial" => "bigint".to_owned(),
+        "smallserial" => "smallint".to_owned(),
+        _ => sql_type.to_owned(),
+    }
+}
+
+/// Generates CREATE TABLE statements for the classes of the diagram.
+///
+/// Associations and generalizations become foreign keys, many-to-many associations become
+/// join tables. Properties typed by a class of the diagram reference its table.
+pub fn sql_ddl(diagram: &UmlClassDiagram, options: &SqlDdlOptions) -> String {
+    let mut collector = SqlDdlCollector::default();
+    for e in &diagram.contained_elements {
+        e.accept_uml(&mut collector);
+    }
+
+    let indices: HashMap<ModelUuid, usize> = collector
+        .classes
+        .iter()
+        .enumerate()
+        .map(|(idx, e)| (e.uuid, idx))
+        .collect();
+    let class_by_name = |name: &str| collector.classes.iter().find(|e| e.name == name.trim());
+
+    let mut tables = Vec::new();
+    let mut references = Vec::new();
+    for class in &collector.classes {
+        let mut table = Table {
+            schema: class.schema.clone(),
+            name: options.table_naming.name(&[&class.name]),
+            columns: vec![],
+            primary_key: vec![],
+            unique_keys: vec![],
+            foreign_keys: vec![],
+        };
+        for p in &class.properties {
+            let (lower, many) = multiplicity_bounds(&p.multiplicity);
+            if let Some(target) = class_by_name(&p.value_type) {
+                if many {
+                    collector.joins.push(JoinInfo {
+                        name: format!("{} {}", class.name, p.name),
+                        ends: [
+                            (class.uuid, class.name.clone()),
+                            (target.uuid, p.name.clone()),
+                        ],
+                    });
+                } else {
+                    references.push(ReferenceInfo {
+                        source: class.uuid,
+                        target: target.uuid,
+                        role: p.name.clone(),
+                        columns: vec![],
+                        is_optional: lower == 0,
+                        is_unique: false,
+                    });
+                }
+                continue;
+            }
+
+            let name = table.unique_column_name(options.column_naming.name(&[&p.name]));
+            if p.is_key {
+                table.primary_key.push(name.clone());
+            }
+            table.columns.push(Column {
+                name,
+                sql_type: options.sql_type(&p.value_type),
+                is_not_null: p.is_key || lower > 0,
+                default_value: p.default_value.clone(),
+            });
+        }
+        if table.primary_key.is_empty() {
+            let name = table.unique_column_name(options.column_naming.name(&["id"]));
+            table.primary_key.push(name.clone());
+            table.columns.insert(
+                0,
+                Column {
+                    name,
+                    sql_type: options.key_type.clone(),
+                    is_not_null: true,
+                    default_value: String::new(),
+                },
+            );
+        }
+        tables.push(table);
+    }
+
+    references.extend(collector.references);
+    for r in &references {
+        let (Some(&source), Some(&target)) = (indices.get(&r.source), indices.get(&r.target))
+        else {
+            continue;
+        };
+        let target_keys = tables[target].key_references();
+        let columns = tables[source].add_foreign_key(
+            options,
+            target,
+            &target_keys,
+            &r.role,
+            &r.columns,
+            r.is_optional,
+        );
+        if r.is_unique && columns != tables[source].primary_key {
+            tables[source].unique_keys.push(columns);
+        }
+    }
+
+    for j in &collector.joins {
+        let (Some(&a), Some(&b)) = (indices.get(&j.ends[0].0), indices.get(&j.ends[1].0)) else {
+            continue;
+        };
+        let mut table = Table {
+            schema: tables[a].schema.clone(),
+            name: options.table_naming.name(&[&j.name]),
+            columns: vec![],
+            primary_key: vec![],
+            unique_keys: vec![],
+            foreign_keys: vec![],
+        };
+        for (target, role) in [(a, &j.ends[0].1), (b, &j.ends[1].1)] {
+            // Both ends get their own columns, even when the same table is referenced twice
+            let target_keys: Vec<_> = tables[target]
+                .key_references()
+                .into_iter()
+                .map(|(k, t)| {
+                    (
+                        table.unique_column_name(options.column_naming.name(&[role, &k])),
+                        t,
+                    )
+                })
+                .collect();
+            let columns: Vec<_> = target_keys.iter().map(|e| e.0.clone()).collect();
+            for (name, sql_type) in target_keys {
+                table.columns.push(Column {
+                    name,
+                    sql_type,
+                    is_not_null: true,
+                    default_value: String::new(),
+                });
+            }
+            table.primary_key.extend(columns.iter().cloned());
+            table.foreign_keys.push(ForeignKey { columns, target });
+        }
+        tables.push(table);
+    }
+
+    write_ddl(&diagram.name, &tables)
+}
+
+fn write_ddl(title: &str, tables: &[Table]) -> String {
+    let list = |columns: &[String]| {
+        columns
+            .iter()
+            .map(|e| quote(e))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut ddl = format!("-- {}\n\n", title.replace('\n', " "));
+    let mut schemas: Vec<_> = tables.iter().filter_map(|e| e.schema.as_ref()).collect();
+    schemas.sort();
+    schemas.dedup();
+    for s in &schemas {
+        ddl.push_str(&format!("CREATE SCHEMA IF NOT EXISTS {};\n", quote(s)));
+    }
+    if !schemas.is_empty() {
+        ddl.push('\n');
+    }
+
+    for t in tables {
+        let mut lines: Vec<_> = t
+            .columns
+            .iter()
+            .map(|c| {
+                let mut line = format!("{} {}", quote(&c.name), c.sql_type);
+                if c.is_not_null {
+                    line.push_str(" NOT NULL");
+                }
+                if !c.default_value.is_empty() {
+                    line.push_str(&format!(" DEFAULT {}", c.default_value));
+                }
+                line
+            })
+            .collect();
+        lines.push(format!("PRIMARY KEY ({})", list(&t.primary_key)));
+        for u in &t.unique_keys {
+            lines.push(format!("UNIQUE ({})", list(u)));
+        }
+        ddl.push_str(&format!(
+            "CREATE TABLE {} (\n    {}\n);\n\n",
+            t.qualified_name(),
+            lines.join(",\n    ")
+        ));
+    }
+
+    // Foreign keys are added after all tables exist, so that tables can reference each other
+    for t in tables {
+        for k in &t.foreign_keys {
+            let target = &tables[k.target];
+            ddl.push_str(&format!(
+                "ALTER TABLE {} ADD FOREIGN KEY ({}) REFERENCES {} ({});\n",
+                t.qualified_name(),
+                list(&k.columns),
+                target.qualified_name(),
+                list(&target.primary_key),
+            ));
+        }
+    }
+
+    ddl
+}
+
+pub struct SqlDdlTab {
+    diagram: ERef<UmlClassDiagram>,
+    options: SqlDdlOptions,
+    ddl: String,
+}
+
+impl SqlDdlTab {
+    pub fn new(diagram: ERef<UmlClassDiagram>) -> Self {
+        let options = SqlDdlOptions::default();
+        let ddl = sql_ddl(&diagram.read(), &options);
+        Self {
+            diagram,
+            options,
+            ddl,
+        }
+    }
+}
+
+impl CustomTab for SqlDdlTab {
+    fn title(&self) -> String {
+        "SQL DDL".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+        let mut modified = false;
+
+        ui.horizontal(|ui| {
+            for (label, naming) in [
+                ("Tables", &mut self.options.table_naming),
+                ("Columns", &mut self.options.column_naming),
+            ] {
+                egui::ComboBox::new(label, label)
+                    .selected_text(naming.as_str())
+                    .show_ui(ui, |ui| {
+                        for e in NamingConvention::VARIANTS {
+                            modified |= ui.selectable_value(naming, e, e.as_str()).clicked();
+                        }
+                    });
+            }
+        });
+
+        egui::CollapsingHeader::new("Type mappings").show(ui, |ui| {
+            egui::Grid::new("type mappings").show(ui, |ui| {
+                ui.label("UML type");
+                ui.label("SQL type");
+                ui.end_row();
+
+                let mut removed = None;
+                for (idx, (uml_type, sql_type)) in self.options.type_mappings.iter_mut().enumerate()
+                {
+                    modified |= ui.text_edit_singleline(uml_type).changed();
+                    modified |= ui.text_edit_singleline(sql_type).changed();
+                    if ui.button("Remove").clicked() {
+                        removed = Some(idx);
+                    }
+                    ui.end_row();
+                }
+                if let Some(idx) = removed {
+                    self.options.type_mappings.remove(idx);
+                    modified = true;
+                }
+
+                ui.label("No type");
+                modified |= ui
+                    .text_edit_singleline(&mut self.options.default_type)
+                    .changed();
+                ui.end_row();
+                ui.label("Added keys");
+                modified |= ui
+                    .text_edit_singleline(&mut self.options.key_type)
+                    .changed();
+                ui.end_row();
+            });
+            if ui.button("Add mapping").clicked() {
+                self.options
+                    .type_mappings
+                    .push((String::new(), String::new()));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Refresh").clicked() || modified {
+                self.ddl = sql_ddl(&self.diagram.read(), &self.options);
+            }
+            if ui.button("Copy").clicked() {
+                ui.ctx().copy_text(self.ddl.clone());
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.ddl.as_str()).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ufoption::UFOption;
+    use crate::domains::umlclass::{
+        umlclass_models::{UmlClassElement, UmlClassProperty},
+        umlclass_sql_import::parse_sql_schema,
+    };
+
+    #[test]
+    fn generated_tables_are_read_back() {
+        let property = |name: &str, value_type: &str, multiplicity: &str| {
+            ERef::new(UmlClassProperty::new(
+                ModelUuid::now_v7(),
+                UFOption::None,
+                name.to_owned(),
+                value_type.to_owned(),
+                multiplicity.to_owned(),
+                String::new(),
+                String::new(),
+            ))
+        };
+        let class = |name: &str, properties| {
+            ERef::new(UmlClass::new(
+                ModelUuid::now_v7(),
+                name.to_owned(),
+                String::new(),
+                String::new(),
+                false,
+                properties,
+                vec![],
+            ))
+        };
+        let customer = class(
+            "Customer",
+            vec![
+                property("fullName", "String", ""),
+                property("email", "", "0..1"),
+            ],
+        );
+        let order = class(
+            "Order",
+            vec![
+                property("createdAt", "DateTime", ""),
+                property("billedTo", "Customer", "0..1"),
+            ],
+        );
+        let product = class("Product", vec![property("price", "Decimal", "")]);
+        let placed = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            "places".to_owned(),
+            customer.clone().into(),
+            "1".to_owned(),
+            order.clone().into(),
+            "*".to_owned(),
+        );
+        let items = UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            String::new(),
+            "order items".to_owned(),
+            order.clone().into(),
+            "*".to_owned(),
+            product.clone().into(),
+            "1..*".to_owned(),
+        );
+        let diagram = UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Shop".to_owned(),
+            vec![
+                UmlClassElement::Class(customer),
+                UmlClassElement::Class(order),
+                UmlClassElement::Class(product),
+                UmlClassElement::Association(ERef::new(placed)),
+                UmlClassElement::Association(ERef::new(items)),
+            ],
+        );
+
+        let ddl = sql_ddl(&diagram, &SqlDdlOptions::default());
+        assert!(ddl.contains("CREATE TABLE customer (\n    id integer NOT NULL,\n    full_name varchar(255) NOT NULL,\n    email text,\n"));
+        assert!(ddl.contains("CREATE TABLE \"order\" ("));
+        assert!(ddl.contains("billed_to_id integer,"));
+        assert!(ddl.contains("customer_id integer NOT NULL"));
+        assert!(ddl.contains("price numeric NOT NULL"));
+        assert!(ddl.contains(
+            "ALTER TABLE order_items ADD FOREIGN KEY (product_id) REFERENCES product (id);"
+        ));
+
+        assert_eq!(quote("order"), "\"order\"");
+        assert_eq!(quote("Order Line"), "\"Order Line\"");
+
+        let schema = parse_sql_schema(&ddl).unwrap();
+        assert_eq!(schema.classes.len(), 4);
+        assert_eq!(schema.links.len(), 4);
+        assert!(schema.skipped_lines.is_empty());
+    }
+}
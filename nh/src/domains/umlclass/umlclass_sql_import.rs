@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use super::umlclass_plantuml_import::{
+    PlantUmlAssociationEnd, PlantUmlClass, PlantUmlClassDiagram, PlantUmlLink, PlantUmlLinkKind,
+    PlantUmlMember, PlantUmlPackage,
+};
+use crate::{
+    CustomTab, NHTab,
+    common::{
+        controller::{GlobalDrawingContext, ProjectCommand},
+        uuid::ViewUuid,
+    },
+    domains::umlclass::umlclass_models::UmlClassAssociationNavigability,
+};
+
+/// Schemas tables are created in when none is given, they are not shown as packages
+const DEFAULT_SCHEMAS: [&str; 3] = ["public", "main", "dbo"];
+
+/// Keywords starting a constraint of a column, ending its type or default value
+const COLUMN_CONSTRAINT_KEYWORDS: [&str; 12] = [
+    "CONSTRAINT",
+    "NOT",
+    "NULL",
+    "PRIMARY",
+    "UNIQUE",
+    "REFERENCES",
+    "DEFAULT",
+    "CHECK",
+    "COLLATE",
+    "GENERATED",
+    "AUTOINCREMENT",
+    "AUTO_INCREMENT",
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TokenKind {
+    Word,
+    /// Quoted identifier, the text is without the quotes
+    Identifier,
+    Literal,
+    Symbol,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    line: usize,
+    /// Byte range in the source, including quotes
+    start: usize,
+    end: usize,
+}
+
+impl Token<'_> {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+    fn is_symbol(&self, symbol: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == symbol
+    }
+}
+
+/// Length of the quoted string at the start of `s`, including the quotes.
+/// Doubled closing quotes are part of the string.
+fn quoted_len(s: &str, close: char) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == close {
+            if close != ']' && chars.peek().is_some_and(|e| e.1 == close) {
+                chars.next();
+                continue;
+            }
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+/// Splits the source into tokens, dropping whitespace and comments.
+///
+/// PostgreSQL dollar-quoted strings, such as bodies of functions, are single literals.
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, String> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while let Some(c) = source[i..].chars().next() {
+        let rest = &source[i..];
+        let previous = source[..i].chars().next_back();
+        let (kind, len, text) = if c.is_whitespace() {
+            (None, c.len_utf8(), "")
+        } else if rest.starts_with("--") {
+            (None, rest.find('\n').unwrap_or(rest.len()), "")
+        } else if rest.starts_with("/*") {
+            let len = rest
+                .find("*/")
+                .ok_or_else(|| format!("line {}: comment is not closed", line))?;
+            (None, len + 2, "")
+        } else if matches!(c, '\'' | '"' | '`')
+            // Brackets quote identifiers in SQLite, unless they follow a type as array bounds
+            || (c == '[' && !previous.is_some_and(|e| is_word_char(e) || e == ')' || e == ']'))
+        {
+            let close = if c == '[' { ']' } else { c };
+            let len = quoted_len(rest, close)
+                .ok_or_else(|| format!("line {}: quotes are not closed", line))?;
+            let kind = if c == '\'' {
+                TokenKind::Literal
+            } else {
+                TokenKind::Identifier
+            };
+            (Some(kind), len, &rest[1..len - 1])
+        } else if c == '$'
+            && !previous.is_some_and(is_word_char)
+            && let Some(tag_len) = rest[1..].find(|e: char| !is_word_char(e) || e == '$')
+            && rest[1 + tag_len..].starts_with('$')
+        {
+            let tag = &rest[..tag_len + 2];
+            let len = rest[tag.len()..]
+                .find(tag)
+                .ok_or_else(|| format!("line {}: {} quotes are not closed", line, tag))?
+                + 2 * tag.len();
+            (
+                Some(TokenKind::Literal),
+                len,
+                &rest[tag.len()..len - tag.len()],
+            )
+        } else if is_word_char(c) {
+            let len = rest.find(|e| !is_word_char(e)).unwrap_or(rest.len());
+            (Some(TokenKind::Word), len, &rest[..len])
+        } else {
+            (Some(TokenKind::Symbol), c.len_utf8(), &rest[..c.len_utf8()])
+        };
+
+        if let Some(kind) = kind {
+            tokens.push(Token {
+                kind,
+                text,
+                line,
+                start: i,
+                end: i + len,
+            });
+        }
+        line += rest[..len].matches('\n').count();
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// Splits the tokens by the symbol, except inside of parentheses
+fn split_top_level<'a, 't>(tokens: &'t [Token<'a>], symbol: &str) -> Vec<&'t [Token<'a>]> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, t) in tokens.iter().enumerate() {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && t.is_symbol(symbol) {
+            parts.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+struct Cursor<'a, 't> {
+    source: &'a str,
+    tokens: &'t [Token<'a>],
+    pos: usize,
+}
+
+impl<'a, 't> Cursor<'a, 't> {
+    fn new(source: &'a str, tokens: &'t [Token<'a>]) -> Self {
+        Self {
+            source,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consumes the keyword if it is next
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|e| e.is_keyword(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consumes the keywords only if all of them follow in order
+    fn keywords(&mut self, keywords: &[&str]) -> bool {
+        let found = keywords.iter().enumerate().all(|(i, k)| {
+            self.tokens
+                .get(self.pos + i)
+                .is_some_and(|e| e.is_keyword(k))
+        });
+        if found {
+            self.pos += keywords.len();
+        }
+        found
+    }
+
+    fn name(&mut self) -> Option<&'a str> {
+        let t = self
+            .peek()
+            .filter(|e| matches!(e.kind, TokenKind::Word | TokenKind::Identifier))?;
+        let text = t.text;
+        self.pos += 1;
+        Some(text)
+    }
+
+    /// Reads `[database.][schema.]name`, tables in default schemas have no schema
+    fn qualified_name(&mut self) -> Option<(Option<String>, String)> {
+        let mut parts = vec![self.name()?];
+        while self.peek().is_some_and(|e| e.is_symbol(".")) {
+            self.pos += 1;
+            parts.push(self.name()?);
+        }
+        let name = parts.pop()?.to_owned();
+        let schema = parts
+            .pop()
+            .filter(|e| !DEFAULT_SCHEMAS.iter().any(|d| e.eq_ignore_ascii_case(d)))
+            .map(|e| e.to_owned());
+        Some((schema, name))
+    }
+
+    /// Consumes a parenthesized group, returning the tokens inside of it
+    fn group(&mut self) -> Option<&'t [Token<'a>]> {
+        if !self.peek()?.is_symbol("(") {
+            return None;
+        }
+        let mut depth = 0;
+        for (i, t) in self.tokens.iter().enumerate().skip(self.pos) {
+            if t.is_symbol("(") {
+                depth += 1;
+            } else if t.is_symbol(")") {
+                depth -= 1;
+                if depth == 0 {
+                    let inner = &self.tokens[self.pos + 1..i];
+                    self.pos = i + 1;
+                    return Some(inner);
+                }
+            }
+        }
+        None
+    }
+
+    /// Reads a parenthesized list of column names, ignoring their ordering or lengths
+    fn name_list(&mut self) -> Option<Vec<String>> {
+        let source = self.source;
+        split_top_level(self.group()?, ",")
+            .into_iter()
+            .map(|e| Cursor::new(source, e).name().map(|e| e.to_owned()))
+            .collect()
+    }
+
+    /// Consumes one token, or a whole group when at its start
+    fn skip(&mut self) {
+        if self.group().is_none() {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes tokens until a column constraint, returning their text with normalized whitespace
+    fn until_column_constraint(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|t| !COLUMN_CONSTRAINT_KEYWORDS.iter().any(|k| t.is_keyword(k)))
+        {
+            self.skip();
+        }
+        self.text(start)
+    }
+
+    fn text(&self, start: usize) -> String {
+        match (self.tokens.get(start), self.tokens.get(self.pos.max(1) - 1)) {
+            (Some(first), Some(last)) if start < self.pos => self.source[first.start..last.end]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => String::new(),
+        }
+    }
+}
+
+struct Column {
+    name: String,
+    data_type: String,
+    nullable: bool,
+    default_value: String,
+}
+
+struct ForeignKey {
+    columns: Vec<String>,
+    table: (Option<String>, String),
+}
+
+struct Table {
+    schema: Option<String>,
+    name: String,
+    columns: Vec<Column>,
+    primary_key: Vec<String>,
+    unique_keys: Vec<Vec<String>>,
+    foreign_keys: Vec<ForeignKey>,
+}
+
+impl Table {
+    fn column(&self, name: &str) -> Option<&Column> {
+        self.columns
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut Column> {
+        self.columns
+            .iter_mut()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    fn set_primary_key(&mut self, columns: Vec<String>) {
+        for c in &columns {
+            if let Some(c) = self.column_mut(c) {
+                c.nullable = false;
+            }
+        }
+        self.unique_keys.push(columns.clone());
+        self.primary_key = columns;
+    }
+
+    /// Parses a table constraint, such as `PRIMARY KEY (id)`, returns None if it is malformed
+    fn parse_constraint(&mut self, c: &mut Cursor) -> Option<()> {
+        if c.keyword("CONSTRAINT") {
+            c.name()?;
+        }
+        if c.keywords(&["PRIMARY", "KEY"]) {
+            let columns = c.name_list()?;
+            self.set_primary_key(columns);
+        } else if c.keywords(&["FOREIGN", "KEY"]) {
+            let columns = c.name_list()?;
+            if !c.keyword("REFERENCES") {
+                return None;
+            }
+            let table = c.qualified_name()?;
+            self.foreign_keys.push(ForeignKey { columns, table });
+        } else if c.keyword("UNIQUE") {
+            c.keywords(&["NULLS", "NOT", "DISTINCT"]);
+            let columns = c.name_list()?;
+            self.unique_keys.push(columns);
+        }
+        // Other constraints, such as CHECK or EXCLUDE, do not affect the structure
+        Some(())
+    }
+
+    fn parse_column(&mut self, c: &mut Cursor) -> Option<()> {
+        let name = c.name()?.to_owned();
+        let mut column = Column {
+            name: name.clone(),
+            data_type: c.until_column_constraint(),
+            nullable: true,
+            default_value: String::new(),
+        };
+        let mut primary_key = false;
+        while c.peek().is_some() {
+            if c.keyword("CONSTRAINT") {
+                c.name()?;
+            } else if c.keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if c.keywords(&["PRIMARY", "KEY"]) {
+                primary_key = true;
+            } else if c.keyword("UNIQUE") {
+                self.unique_keys.push(vec![name.clone()]);
+            } else if c.keyword("DEFAULT") {
+                let start = c.pos;
+                c.skip();
+                c.until_column_constraint();
+                column.default_value = c.text(start);
+            } else if c.keyword("REFERENCES") {
+                let table = c.qualified_name()?;
+                self.foreign_keys.push(ForeignKey {
+                    columns: vec![name.clone()],
+                    table,
+                });
+            } else {
+                c.skip();
+            }
+        }
+        self.columns.push(column);
+        if primary_key {
+            self.set_primary_key(vec![name]);
+        }
+        Some(())
+    }
+}
+
+/// Tables are matched case insensitively, tables in other schemas only by name
+fn find_table(tables: &[Table], (schema, name): &(Option<String>, String)) -> Option<usize> {
+    let same_name = |t: &Table| t.name.eq_ignore_ascii_case(name);
+    tables
+        .iter()
+        .position(|t| {
+            same_name(t)
+                && match (&t.schema, schema) {
+                    (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                    (a, b) => a.is_none() && b.is_none(),
+                }
+        })
+        .or_else(|| tables.iter().position(same_name))
+}
+
+fn is_table_constraint(tokens: &[Token]) -> bool {
+    let keyword = |i: usize, k: &str| tokens.get(i).is_some_and(|e| e.is_keyword(k));
+    ["CONSTRAINT", "UNIQUE", "CHECK", "EXCLUDE"]
+        .iter()
+        .any(|k| keyword(0, k))
+        || (keyword(0, "PRIMARY") && keyword(1, "KEY"))
+        || (keyword(0, "FOREIGN") && keyword(1, "KEY"))
+}
+
+fn parse_create_table(c: &mut Cursor) -> Option<Table> {
+    let (schema, name) = c.qualified_name()?;
+    // Tables created by `AS SELECT` have no column definitions
+    let body = c.group()?;
+    let mut table = Table {
+        schema,
+        name,
+        columns: Vec::new(),
+        primary_key: Vec::new(),
+        unique_keys: Vec::new(),
+        foreign_keys: Vec::new(),
+    };
+    for element in split_top_level(body, ",") {
+        let mut c = Cursor::new(c.source, element);
+        if is_table_constraint(element) {
+            table.parse_constraint(&mut c)?;
+        } else {
+            table.parse_column(&mut c)?;
+        }
+    }
+    Some(table)
+}
+
+/// Adds constraints to an already created table, as `pg_dump` does
+fn parse_alter_table(c: &mut Cursor, tables: &mut [Table]) -> Option<()> {
+    let name = c.qualified_name()?;
+    let Some(table) = find_table(tables, &name) else {
+        return Some(());
+    };
+    for action in split_top_level(&c.tokens[c.pos..], ",") {
+        let mut c = Cursor::new(c.source, action);
+        if c.keyword("ADD") && !c.keyword("COLUMN") {
+            tables[table].parse_constraint(&mut c)?;
+        }
+    }
+    Some(())
+}
+
+/// Reads tables and foreign keys from a schema dump, such as the output of
+/// `pg_dump --schema-only` or of the `.schema` command of `sqlite3`.
+///
+/// Only `CREATE TABLE` and `ALTER TABLE ... ADD` statements are used, other statements are ignored.
+/// Line numbers of table statements which could not be read are returned as skipped lines.
+pub fn parse_sql_schema(source: &str) -> Result<PlantUmlClassDiagram, String> {
+    let tokens = tokenize(source)?;
+    let mut tables: Vec<Table> = Vec::new();
+    let mut skipped_lines = Vec::new();
+
+    for statement in split_top_level(&tokens, ";") {
+        let Some(first) = statement.first() else {
+            continue;
+        };
+        let mut c = Cursor::new(source, statement);
+        let parsed = if c.keyword("CREATE") {
+            while ["GLOBAL", "LOCAL", "TEMP", "TEMPORARY", "UNLOGGED"]
+                .iter()
+                .any(|k| c.keyword(k))
+            {}
+            if !c.keyword("TABLE") {
+                continue;
+            }
+            c.keywords(&["IF", "NOT", "EXISTS"]);
+            parse_create_table(&mut c).map(|e| tables.push(e))
+        } else if c.keywords(&["ALTER", "TABLE"]) {
+            c.keywords(&["IF", "EXISTS"]);
+            c.keyword("ONLY");
+            parse_alter_table(&mut c, &mut tables)
+        } else {
+            continue;
+        };
+        if parsed.is_none() {
+            skipped_lines.push(first.line);
+        }
+    }
+
+    Ok(tables_to_diagram(&tables, skipped_lines))
+}
+
+fn tables_to_diagram(tables: &[Table], skipped_lines: Vec<usize>) -> PlantUmlClassDiagram {
+    let mut diagram = PlantUmlClassDiagram {
+        title: Some("Imported database schema".to_owned()),
+        skipped_lines,
+        ..Default::default()
+    };
+
+    let mut schema_packages: HashMap<String, usize> = HashMap::new();
+    for t in tables {
+        let package = t.schema.as_ref().map(|s| {
+            *schema_packages.entry(s.to_lowercase()).or_insert_with(|| {
+                diagram.packages.push(PlantUmlPackage {
+                    name: s.clone(),
+                    stereotype: "schema".to_owned(),
+                    parent: None,
+                });
+                diagram.packages.len() - 1
+            })
+        });
+
+        let contains =
+            |columns: &[String], name: &str| columns.iter().any(|e| e.eq_ignore_ascii_case(name));
+        let members = t
+            .columns
+            .iter()
+            .map(|c| {
+                let primary = contains(&t.primary_key, &c.name);
+                let foreign = t.foreign_keys.iter().any(|k| contains(&k.columns, &c.name));
+                PlantUmlMember {
+                    name: c.name.clone(),
+                    value_type: c.data_type.clone(),
+                    multiplicity: if c.nullable { "0..1" } else { "" }.to_owned(),
+                    default_value: c.default_value.clone(),
+                    stereotype: match (primary, foreign) {
+                        (true, true) => "PK, FK",
+                        (true, false) => "PK",
+                        (false, true) => "FK",
+                        (false, false) => "",
+                    }
+                    .to_owned(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        diagram.classes.push(PlantUmlClass {
+            name: t.name.clone(),
+            stereotype: "table".to_owned(),
+            is_abstract: false,
+            members,
+            package,
+        });
+    }
+
+    for (source, t) in tables.iter().enumerate() {
+        for k in &t.foreign_keys {
+            // References to tables which are not part of the dump are dropped
+            let Some(target) = find_table(tables, &k.table) else {
+                continue;
+            };
+            // Referencing rows are unique when the key columns contain a unique key
+            let is_unique = t.unique_keys.iter().any(|u| {
+                !u.is_empty()
+                    && u.iter()
+                        .all(|e| k.columns.iter().any(|c| c.eq_ignore_ascii_case(e)))
+            });
+            let is_optional = k
+                .columns
+                .iter()
+                .any(|c| t.column(c).is_none_or(|c| c.nullable));
+            diagram.links.push(PlantUmlLink {
+                kind: PlantUmlLinkKind::Association(
+                    PlantUmlAssociationEnd::default(),
+                    PlantUmlAssociationEnd {
+                        navigability: UmlClassAssociationNavigability::Navigable,
+                        ..Default::default()
+                    },
+                ),
+                source,
+                target,
+                source_multiplicity: if is_unique { "0..1" } else { "*" }.to_owned(),
+                target_multiplicity: if is_optional { "0..1" } else { "1" }.to_owned(),
+                label: k.columns.join(", "),
+                stereotype: String::new(),
+            });
+        }
+    }
+
+    diagram
+}
+
+pub struct SqlSchemaImportTab {
+    source: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for SqlSchemaImportTab {
+    fn default() -> Self {
+        Self {
+            source: "CREATE TABLE customer (\n  id integer PRIMARY KEY,\n  name varchar(100) NOT NULL,\n  email text\n);\n\nCREATE TABLE orders (\n  id integer PRIMARY KEY,\n  customer_id integer NOT NULL REFERENCES customer(id),\n  created_at timestamp DEFAULT CURRENT_TIMESTAMP\n);\n"
+                .to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl CustomTab for SqlSchemaImportTab {
+    fn title(&self) -> String {
+        "SQL Schema Import".to_owned()
+    }
+
+    fn show(
+        &mut self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<ProjectCommand>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("PostgreSQL or SQLite schema dump");
+            if ui.button("Import").clicked() {
+                self.status = Some(match parse_sql_schema(&self.source) {
+                    Ok(diagram) => {
+                        let (uuid, controller) =
+                            super::umlclass_controllers::from_plantuml(&diagram);
+                        commands.push(ProjectCommand::AddNewDiagram(
+                            ViewUuid::nil(),
+                            uuid,
+                            controller,
+                        ));
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid },
+                            None,
+                        ));
+                        let mut message = format!(
+                            "Imported {} tables and {} foreign keys",
+                            diagram.classes.len(),
+                            diagram.links.len(),
+                        );
+                        if !diagram.skipped_lines.is_empty() {
+                            message.push_str(&format!(
+                                ", skipped statements on lines {}",
+                                diagram
+                                    .skipped_lines
+                                    .iter()
+                                    .map(|e| e.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ));
+                        }
+                        Ok(message)
+                    }
+                    Err(e) => Err(e),
+                });
+            }
+        });
+
+        match &self.status {
+            None => {}
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_sized(
+                (ui.available_width(), 20.0),
+                egui::TextEdit::multiline(&mut self.source).code_editor(),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pg_dump_tables_and_foreign_keys_are_read() {
+        let d = parse_sql_schema(
+            "-- PostgreSQL database dump\n\
+             SET statement_timeout = 0;\n\
+             CREATE FUNCTION public.touch() RETURNS trigger AS $$ BEGIN NEW.x := 1; RETURN NEW; END; $$ LANGUAGE plpgsql;\n\
+             CREATE TABLE public.customer (\n\
+                 id integer NOT NULL,\n\
+                 name character varying(100) DEFAULT 'n/a'::character varying NOT NULL\n\
+             );\n\
+             CREATE TABLE billing.\"Invoice\" (\n\
+                 id integer NOT NULL,\n\
+                 customer_id integer,\n\
+                 total numeric(10,2) /* in EUR */\n\
+             );\n\
+             ALTER TABLE ONLY public.customer\n\
+                 ADD CONSTRAINT customer_pkey PRIMARY KEY (id);\n\
+             ALTER TABLE ONLY billing.\"Invoice\"\n\
+                 ADD CONSTRAINT invoice_customer_fkey FOREIGN KEY (customer_id) REFERENCES public.customer(id) ON DELETE CASCADE;\n\
+             CREATE INDEX invoice_customer ON billing.\"Invoice\" USING btree (customer_id);\n",
+        )
+        .unwrap();
+
+        assert_eq!(d.packages.len(), 1);
+        assert_eq!(d.packages[0].name, "billing");
+        let names: Vec<_> = d.classes.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["customer", "Invoice"]);
+        assert_eq!(d.classes[0].package, None);
+        assert_eq!(d.classes[1].package, Some(0));
+
+        let customer = &d.classes[0].members;
+        assert_eq!(customer[0].stereotype, "PK");
+        assert_eq!(customer[1].value_type, "character varying(100)");
+        assert_eq!(customer[1].default_value, "'n/a'::character varying");
+        assert_eq!(customer[1].multiplicity, "");
+        let invoice = &d.classes[1].members;
+        assert_eq!(invoice[1].stereotype, "FK");
+        assert_eq!(invoice[1].multiplicity, "0..1");
+        assert_eq!(invoice[2].value_type, "numeric(10,2)");
+
+        assert_eq!(d.links.len(), 1);
+        assert_eq!((d.links[0].source, d.links[0].target), (1, 0));
+        assert_eq!(d.links[0].source_multiplicity, "*");
+        assert_eq!(d.links[0].target_multiplicity, "0..1");
+        assert_eq!(d.links[0].label, "customer_id");
+        assert!(d.skipped_lines.is_empty());
+    }
+
+    #[test]
+    fn sqlite_inline_references_are_read() {
+        let d = parse_sql_schema(
+            "CREATE TABLE [author] (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);\n\
+             CREATE TABLE IF NOT EXISTS `profile` (\n\
+               author_id INTEGER NOT NULL UNIQUE REFERENCES author(id),\n\
+               bio\n\
+             );\n\
+             CREATE TABLE book (\n\
+               id INTEGER,\n\
+               author_id INTEGER NOT NULL,\n\
+               tags TEXT[],\n\
+               PRIMARY KEY (id),\n\
+               FOREIGN KEY (author_id) REFERENCES author (id)\n\
+             );\n\
+             CREATE TABLE broken (id INTEGER,\n\
+               FOREIGN KEY (id) REFERENCES);\n",
+        )
+        .unwrap();
+
+        let names: Vec<_> = d.classes.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["author", "profile", "book"]);
+        assert_eq!(d.classes[1].members[1].name, "bio");
+        assert_eq!(d.classes[1].members[1].value_type, "");
+        assert_eq!(d.classes[2].members[0].stereotype, "PK");
+        assert_eq!(d.classes[2].members[2].value_type, "TEXT[]");
+
+        assert_eq!(d.links.len(), 2);
+        assert_eq!((d.links[0].source, d.links[0].target), (1, 0));
+        assert_eq!(d.links[0].source_multiplicity, "0..1");
+        assert_eq!(d.links[0].target_multiplicity, "1");
+        assert_eq!((d.links[1].source, d.links[1].target), (2, 0));
+        assert_eq!(d.links[1].source_multiplicity, "*");
+        assert_eq!(d.skipped_lines, [13]);
+    }
+
+    #[test]
+    fn unclosed_quotes_are_errors() {
+        assert!(parse_sql_schema("CREATE TABLE \"a (id int);").is_err());
+        assert!(parse_sql_schema("CREATE FUNCTION f() AS $body$ SELECT 1;").is_err());
+        assert!(parse_sql_schema("/* CREATE TABLE a (id int);").is_err());
+    }
+}
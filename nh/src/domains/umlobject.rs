@@ -0,0 +1,2 @@
+pub mod umlobject_controllers;
+pub mod umlobject_validations;
@@ -0,0 +1,466 @@
+use super::super::umlclass::{
+    umlclass_controllers::{
+        LinkType, UmlClassDiagramAdapter, UmlClassDomain, UmlClassElementView,
+        UmlClassInstanceView, UmlClassNullProfile, UmlClassRenderStyle, UmlClassToolStage,
+        UmlClassView, new_umlclass_association, new_umlclass_class, new_umlclass_instance,
+        new_umlclass_property,
+    },
+    umlclass_models::UmlClassDiagram,
+};
+use crate::{
+    DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF, DiagramConstructorF,
+    DiagramCreationData, DiagramInfo,
+    common::{
+        controller::{
+            BucketNoT, CheckProblem, ControllerAdapter, DiagramController, DiagramControllerGen2,
+            DiagramSettings, GlobalDrawingContext, MGlobalColor, MultiDiagramController,
+            PositionNoT,
+        },
+        eref::ERef,
+        project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer},
+        ufoption::UFOption,
+        uuid::{ControllerUuid, ModelUuid, ViewUuid},
+    },
+    domains::umlclass::{
+        umlclass_controllers::PartialUmlClassElement,
+        umlclass_models::{UmlClass, UmlClassElement, UmlClassInstance},
+    },
+};
+use eframe::egui;
+use std::collections::HashSet;
+
+type UmlObjectDiagramView = DiagramControllerGen2<
+    UmlClassDomain<UmlClassNullProfile>,
+    UmlClassDiagramAdapter<UmlClassNullProfile>,
+>;
+
+/// Object diagram, binding instances to the classes of its model by their type
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct UmlObjectControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<UmlClassDiagram>,
+}
+
+impl ControllerAdapter<UmlClassDomain<UmlClassNullProfile>> for UmlObjectControllerAdapter {
+    type DiagramViewT = UmlObjectDiagramView;
+
+    fn model(&self) -> ERef<UmlClassDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<UmlClassDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "umlclass-object"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::super::umlclass::umlclass_models::transitive_closure(
+            &self.model.read(),
+            when_deleting,
+        )
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: UmlClassElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, UmlClassElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("UML Object Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Shared UML Object Diagram".to_owned().into(),
+                UmlClassDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+
+    fn check_problems(&self) -> Vec<CheckProblem> {
+        super::umlobject_validations::check_problems(&self.model)
+    }
+}
+
+/// Object diagram over the given model, which may be shared with class diagrams
+pub fn new_controlller(
+    model: ERef<UmlClassDiagram>,
+    name: String,
+    elements: Vec<UmlClassElementView<UmlClassNullProfile>>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            UmlObjectControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                UmlClassDiagramAdapter::<UmlClassNullProfile>::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New UML object diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (person_model, person_view) = new_class(
+        "Person",
+        &[("name", "String")],
+        egui::Pos2::new(200.0, 100.0),
+    );
+    let (company_model, company_view) = new_class(
+        "Company",
+        &[("name", "String"), ("founded", "Integer")],
+        egui::Pos2::new(550.0, 100.0),
+    );
+    let (employment_model, employment_view) = new_umlclass_association(
+        "",
+        "employs",
+        "0..*",
+        "1..*",
+        None,
+        (company_model.clone().into(), company_view.clone().into()),
+        (person_model.clone().into(), person_view.clone().into()),
+    );
+
+    let (alice_model, alice_view) = new_object(
+        "alice",
+        "Person",
+        "name = \"Alice\"",
+        egui::Pos2::new(100.0, 350.0),
+    );
+    let (bob_model, bob_view) = new_object(
+        "bob",
+        "Person",
+        "name = \"Bob\"",
+        egui::Pos2::new(300.0, 350.0),
+    );
+    let (acme_model, acme_view) = new_object(
+        "acme",
+        "Company",
+        "name = \"ACME\"\nfounded = 1949",
+        egui::Pos2::new(550.0, 350.0),
+    );
+    let (alice_link_model, alice_link_view) = new_link(
+        (acme_model.clone(), acme_view.clone()),
+        (alice_model.clone(), alice_view.clone()),
+    );
+    let (bob_link_model, bob_link_view) = new_link(
+        (acme_model.clone(), acme_view.clone()),
+        (bob_model.clone(), bob_view.clone()),
+    );
+
+    let name = format!("Demo UML object diagram {}", no);
+    let diagram = ERef::new(UmlClassDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![
+            person_model.into(),
+            company_model.into(),
+            employment_model.into(),
+            alice_model.into(),
+            bob_model.into(),
+            acme_model.into(),
+            alice_link_model.into(),
+            bob_link_model.into(),
+        ],
+    ));
+    new_controlller(
+        diagram,
+        name,
+        vec![
+            person_view.into(),
+            company_view.into(),
+            employment_view.into(),
+            alice_view.into(),
+            bob_view.into(),
+            acme_view.into(),
+            alice_link_view.into(),
+            bob_link_view.into(),
+        ],
+    )
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        UmlClassDomain<UmlClassNullProfile>,
+        UmlObjectControllerAdapter,
+        UmlObjectDiagramView,
+    >>(&uuid)?)
+}
+
+mod buttons {
+    use super::*;
+
+    fn instance_link(
+        m: ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<UmlClassNullProfile>,
+        bool,
+    ) {
+        let link_type = LinkType::Association {
+            stereotype: "".to_owned(),
+            source_multiplicity: "".to_owned(),
+            target_multiplicity: "".to_owned(),
+        };
+        (
+            UmlClassToolStage::LinkStart {
+                link_type: link_type.clone(),
+            },
+            UmlClassToolStage::LinkEnd,
+            PartialUmlClassElement::Link {
+                link_type,
+                source: m.into(),
+                dest: None,
+            },
+            true,
+        )
+    }
+    type InstanceButtonF = dyn Fn(
+        ERef<UmlClassInstance>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<UmlClassNullProfile>,
+        bool,
+    );
+    pub const INSTANCE_BUTTONS: &[(usize, usize, &str, &InstanceButtonF)] =
+        &[(0, 0, "\\", &instance_link as &InstanceButtonF)];
+    fn class_property(
+        _m: ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<UmlClassNullProfile>,
+        bool,
+    ) {
+        let stage = UmlClassToolStage::ClassProperty {
+            name: "attribute".to_owned(),
+            property_type: "String".to_owned(),
+            stereotype: "".to_owned(),
+        };
+        (stage.clone(), stage, PartialUmlClassElement::None, false)
+    }
+    type ClassButtonF = dyn Fn(
+        ERef<UmlClass>,
+    ) -> (
+        UmlClassToolStage,
+        UmlClassToolStage,
+        PartialUmlClassElement<UmlClassNullProfile>,
+        bool,
+    );
+    pub const CLASS_BUTTONS: &[(usize, usize, &str, &ClassButtonF)] =
+        &[(0, 0, "P", &class_property as &ClassButtonF)];
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let palette_items = vec![
+        (
+            "Objects",
+            vec![
+                (
+                    UmlClassToolStage::Instance {
+                        instance_name: "o".to_owned(),
+                        instance_type: "Type".to_owned(),
+                        stereotype: "".to_owned(),
+                        background_color: MGlobalColor::None,
+                    },
+                    "Object",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num1,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::LinkStart {
+                        link_type: LinkType::Association {
+                            stereotype: "".to_owned(),
+                            source_multiplicity: "".to_owned(),
+                            target_multiplicity: "".to_owned(),
+                        },
+                    },
+                    "Link",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num2,
+                    )),
+                ),
+            ],
+        ),
+        (
+            "Classes",
+            vec![
+                (
+                    UmlClassToolStage::Class {
+                        name: "Type".to_owned(),
+                        stereotype: "".to_owned(),
+                        is_abstract: false,
+                        render_style: UmlClassRenderStyle::Class,
+                        background_color: MGlobalColor::None,
+                    },
+                    "Class",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num3,
+                    )),
+                ),
+                (
+                    UmlClassToolStage::ClassProperty {
+                        name: "attribute".to_owned(),
+                        property_type: "String".to_owned(),
+                        stereotype: "".to_owned(),
+                    },
+                    "Attribute",
+                    None,
+                ),
+                (
+                    UmlClassToolStage::LinkStart {
+                        link_type: LinkType::Association {
+                            stereotype: "".to_owned(),
+                            source_multiplicity: "0..*".to_owned(),
+                            target_multiplicity: "1..1".to_owned(),
+                        },
+                    },
+                    "Association",
+                    None,
+                ),
+            ],
+        ),
+        (
+            "Other",
+            vec![
+                (
+                    UmlClassToolStage::Comment {
+                        stereotype: "".to_owned(),
+                        text: "a comment".to_owned(),
+                        align: egui::Align2::CENTER_CENTER,
+                    },
+                    "Comment",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num9,
+                    )),
+                ),
+                (UmlClassToolStage::CommentLinkStart, "Comment Link", None),
+            ],
+        ),
+    ];
+
+    super::super::umlclass::umlclass_controllers::default_settings_helper::<UmlClassNullProfile>(
+        palette_items,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    super::super::umlclass::umlclass_controllers::settings_deserializer_helper::<UmlClassNullProfile>(
+        value,
+        buttons::INSTANCE_BUTTONS.to_vec(),
+        buttons::CLASS_BUTTONS.to_vec(),
+    )
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "umlclass-object",
+    pretty_name: "UML Object diagram",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "/Unified Modeling Language",
+        description: "UML Object diagram (objects and links checked against the classes and associations of the model)",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+fn new_class(
+    name: &str,
+    attributes: &[(&str, &str)],
+    position: egui::Pos2,
+) -> (ERef<UmlClass>, ERef<UmlClassView<UmlClassNullProfile>>) {
+    new_umlclass_class(
+        name,
+        "",
+        false,
+        attributes
+            .iter()
+            .map(|(name, value_type)| {
+                new_umlclass_property(UFOption::None, name, value_type, "", "", "")
+            })
+            .collect(),
+        Vec::new(),
+        position,
+        UmlClassRenderStyle::Class,
+        MGlobalColor::None,
+    )
+}
+
+type ObjectRefs = (
+    ERef<UmlClassInstance>,
+    ERef<UmlClassInstanceView<UmlClassNullProfile>>,
+);
+
+fn new_object(name: &str, instance_type: &str, slots: &str, position: egui::Pos2) -> ObjectRefs {
+    new_umlclass_instance(name, instance_type, "", slots, position, MGlobalColor::None)
+}
+
+fn new_link(
+    source: ObjectRefs,
+    target: ObjectRefs,
+) -> (
+    ERef<super::super::umlclass::umlclass_models::UmlClassAssociation>,
+    impl Into<UmlClassElementView<UmlClassNullProfile>>,
+) {
+    new_umlclass_association(
+        "",
+        "",
+        "",
+        "",
+        None,
+        (source.0.into(), source.1.into()),
+        (target.0.into(), target.1.into()),
+    )
+}
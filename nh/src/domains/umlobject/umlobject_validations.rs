@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    common::{controller::CheckProblem, eref::ERef, uuid::ModelUuid},
+    domains::umlclass::umlclass_models::{
+        UmlClass, UmlClassAssociable, UmlClassAssociation, UmlClassDiagram, UmlClassElement,
+        UmlClassGeneralization, UmlClassInstance,
+    },
+};
+
+#[derive(Default)]
+struct Collected {
+    instances: Vec<ERef<UmlClassInstance>>,
+    classes: Vec<ERef<UmlClass>>,
+    generalizations: Vec<ERef<UmlClassGeneralization>>,
+    associations: Vec<ERef<UmlClassAssociation>>,
+}
+
+fn collect(elements: &[UmlClassElement], into: &mut Collected) {
+    for e in elements {
+        match e {
+            UmlClassElement::Package(inner) => collect(&inner.read().contained_elements, into),
+            UmlClassElement::Instance(inner) => into.instances.push(inner.clone()),
+            UmlClassElement::Class(inner) => into.classes.push(inner.clone()),
+            UmlClassElement::Generalization(inner) => into.generalizations.push(inner.clone()),
+            UmlClassElement::Association(inner) => into.associations.push(inner.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// Name of the attribute a slot line assigns, e.g. `name` in `name: String = "x"`
+fn slot_name(line: &str) -> &str {
+    let end = line.find(['=', ':']).unwrap_or(line.len());
+    line[..end].trim()
+}
+
+/// Instances bound to classes of the model by their type, with slots checked against
+/// the attributes of the class and its ancestors, and links against associations.
+///
+/// Instances without a type are not bound and are not checked.
+pub fn check_problems(model: &ERef<UmlClassDiagram>) -> Vec<CheckProblem> {
+    let mut c = Collected::default();
+    collect(&model.read().contained_elements, &mut c);
+
+    let mut by_name = HashMap::<String, Vec<ModelUuid>>::new();
+    let mut attributes = HashMap::<ModelUuid, HashSet<String>>::new();
+    for e in &c.classes {
+        let r = e.read();
+        by_name
+            .entry(r.name.trim().to_owned())
+            .or_default()
+            .push(*r.uuid);
+        attributes.insert(
+            *r.uuid,
+            r.properties
+                .iter()
+                .map(|e| e.read().name.trim().to_owned())
+                .collect(),
+        );
+    }
+    let mut parents = HashMap::<ModelUuid, Vec<ModelUuid>>::new();
+    for e in &c.generalizations {
+        let r = e.read();
+        for s in &r.sources {
+            parents
+                .entry(*s.read().uuid)
+                .or_default()
+                .extend(r.targets.iter().map(|e| *e.read().uuid));
+        }
+    }
+    let ancestors_or_self = |class: ModelUuid| {
+        let mut seen = HashSet::new();
+        let mut stack = vec![class];
+        while let Some(e) = stack.pop() {
+            if seen.insert(e) {
+                stack.extend(parents.get(&e).into_iter().flatten());
+            }
+        }
+        seen
+    };
+
+    let mut problems = Vec::new();
+    let mut bindings = HashMap::<ModelUuid, HashSet<ModelUuid>>::new();
+    for e in &c.instances {
+        let r = e.read();
+        let type_name = r.instance_type.trim();
+        if type_name.is_empty() {
+            continue;
+        }
+        let Some(classes) = by_name.get(type_name) else {
+            problems.push(CheckProblem {
+                view: None,
+                model: Some(*r.uuid),
+                message: format!("Instance type {} is not a class of the model", type_name),
+            });
+            continue;
+        };
+        let classes: HashSet<_> = classes.iter().flat_map(|e| ancestors_or_self(*e)).collect();
+        for slot in r.instance_slots.lines().map(slot_name) {
+            if !slot.is_empty()
+                && !classes
+                    .iter()
+                    .any(|e| attributes.get(e).is_some_and(|a| a.contains(slot)))
+            {
+                problems.push(CheckProblem {
+                    view: None,
+                    model: Some(*r.uuid),
+                    message: format!("Slot {} is not an attribute of {}", slot, type_name),
+                });
+            }
+        }
+        bindings.insert(*r.uuid, classes);
+    }
+
+    let class_of = |e: &UmlClassAssociable| match e {
+        UmlClassAssociable::Class(inner) => Some(*inner.read().uuid),
+        _ => None,
+    };
+    let associated: Vec<_> = c
+        .associations
+        .iter()
+        .flat_map(|e| {
+            let r = e.read();
+            Some((class_of(&r.source)?, class_of(&r.target)?))
+        })
+        .collect();
+    for e in &c.associations {
+        let r = e.read();
+        let (UmlClassAssociable::Instance(source), UmlClassAssociable::Instance(target)) =
+            (&r.source, &r.target)
+        else {
+            continue;
+        };
+        let (Some(source), Some(target)) = (
+            bindings.get(&*source.read().uuid),
+            bindings.get(&*target.read().uuid),
+        ) else {
+            continue;
+        };
+        if !associated.iter().any(|(a, b)| {
+            (source.contains(a) && target.contains(b)) || (source.contains(b) && target.contains(a))
+        }) {
+            problems.push(CheckProblem {
+                view: None,
+                model: Some(*r.uuid),
+                message: "Link does not correspond to any association between the classes"
+                    .to_owned(),
+            });
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::ufoption::UFOption, domains::umlclass::umlclass_models::UmlClassProperty};
+
+    fn new_class(name: &str, attributes: &[&str]) -> ERef<UmlClass> {
+        ERef::new(UmlClass::new(
+            ModelUuid::now_v7(),
+            name.to_owned(),
+            "".to_owned(),
+            "".to_owned(),
+            false,
+            attributes
+                .iter()
+                .map(|e| {
+                    ERef::new(UmlClassProperty::new(
+                        ModelUuid::now_v7(),
+                        UFOption::None,
+                        (*e).to_owned(),
+                        "String".to_owned(),
+                        "".to_owned(),
+                        "".to_owned(),
+                        "".to_owned(),
+                    ))
+                })
+                .collect(),
+            vec![],
+        ))
+    }
+
+    fn new_instance(name: &str, type_name: &str, slots: &str) -> ERef<UmlClassInstance> {
+        ERef::new(UmlClassInstance::new(
+            ModelUuid::now_v7(),
+            name.to_owned(),
+            type_name.to_owned(),
+            "".to_owned(),
+            slots.to_owned(),
+        ))
+    }
+
+    fn new_association(
+        source: impl Into<UmlClassAssociable>,
+        target: impl Into<UmlClassAssociable>,
+    ) -> ERef<UmlClassAssociation> {
+        ERef::new(UmlClassAssociation::new(
+            ModelUuid::now_v7(),
+            "".to_owned(),
+            "".to_owned(),
+            source.into(),
+            "".to_owned(),
+            target.into(),
+            "".to_owned(),
+        ))
+    }
+
+    #[test]
+    fn instances_are_checked_against_classes() {
+        let person = new_class("Person", &["name"]);
+        let employee = new_class("Employee", &["salary"]);
+        let company = new_class("Company", &["name"]);
+        let employment = new_association(company.clone(), person.clone());
+        let generalization = ERef::new(UmlClassGeneralization::new(
+            ModelUuid::now_v7(),
+            "".to_owned(),
+            vec![employee.clone()],
+            vec![person.clone()],
+        ));
+
+        let alice = new_instance("alice", "Employee", "name = \"Alice\"\nsalary = 10");
+        let acme = new_instance("acme", "Company", "name: String = \"ACME\"\nsize = 3");
+        let bob = new_instance("bob", "Robot", "");
+        let anonymous = new_instance("", "", "anything = 1");
+        let works_for = new_association(alice.clone(), acme.clone());
+        let friend_of = new_association(alice.clone(), alice.clone());
+        let unbound = new_association(alice.clone(), anonymous.clone());
+
+        let diagram = ERef::new(UmlClassDiagram::new(
+            ModelUuid::now_v7(),
+            "Objects".to_owned(),
+            vec![
+                person.into(),
+                employee.into(),
+                company.into(),
+                employment.into(),
+                generalization.into(),
+                alice.into(),
+                acme.clone().into(),
+                bob.clone().into(),
+                anonymous.into(),
+                works_for.into(),
+                friend_of.clone().into(),
+                unbound.into(),
+            ],
+        ));
+
+        let problems: Vec<_> = check_problems(&diagram)
+            .into_iter()
+            .map(|e| (e.model.unwrap(), e.message))
+            .collect();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems.contains(&(
+            *acme.read().uuid,
+            "Slot size is not an attribute of Company".to_owned()
+        )));
+        assert!(problems.iter().any(|e| e.0 == *bob.read().uuid));
+        assert!(problems.iter().any(|e| e.0 == *friend_of.read().uuid));
+    }
+
+    #[test]
+    fn slot_names_are_parsed() {
+        assert_eq!(slot_name(" name = \"a = b\""), "name");
+        assert_eq!(slot_name("age: Integer = 3"), "age");
+        assert_eq!(slot_name("flag"), "flag");
+    }
+}
@@ -74,7 +74,7 @@ impl UmlSequenceOrdinalMovement {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum UmlSequencePropChange {
     NameChange(Arc<String>),
     StereotypeChange(Arc<String>),
@@ -787,6 +787,9 @@ impl DiagramAdapter<UmlSequenceDomain> for UmlSequenceDiagramBoardAdapter {
     fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
         egui::Color32::from_rgb(220, 220, 220)
     }
+    fn frame_kind(&self) -> &'static str {
+        "sd"
+    }
     fn enable_headers(&self) -> (bool, bool) {
         (true, false)
     }
@@ -1671,7 +1674,7 @@ pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettin
         return Err(());
     };
     Ok(Box::new(UmlSequenceSettings {
-        palette: ToolPalette::deserialize(value.get("palette").unwrap().clone(), view_for_stage)?
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
             .into(),
         palette_edit_buffer: PaletteEditBuffer::None.into(),
     }))
@@ -2932,7 +2935,7 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceDiagramView {
 
                 if let Some(tool) = tool {
                     let horizontal_place = self.horizontal_insertion_place(pos);
-                    tool.add_position(*event.mouse_position());
+                    tool.add_position(ehc.snap_manager.snap_to_grid(*event.mouse_position()));
                     tool.add_section(self.model.clone().into());
                     if let Some(h) = &horizontal_place {
                         tool.add_section(h.1.clone().into());
@@ -3554,6 +3557,7 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceDiagramView {
 
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
@@ -4583,6 +4587,7 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCombinedFragmentVie
                 }
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
@@ -4920,7 +4925,7 @@ impl UmlSequenceCombinedFragmentSectionView {
 
                 if let Some(tool) = tool {
                     let horizontal_place = self.horizontal_insertion_place(lifeline_views, pos);
-                    tool.add_position(*event.mouse_position());
+                    tool.add_position(ehc.snap_manager.snap_to_grid(*event.mouse_position()));
                     tool.add_section(self.model.clone().into());
                     if let Some(h) = &horizontal_place {
                         tool.add_section(h.1.clone().into());
@@ -5433,6 +5438,7 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCombinedFragmentSec
                 }
                 recurse!();
             }
+            InsensitiveCommand::ElementStyleChange(..) | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::Macro(..) => unreachable!(),
         }
     }
@@ -6277,7 +6283,9 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceLifelineView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -7459,7 +7467,9 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceRefView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&self.uuid) {
                     let mut model = self.model.write();
@@ -7648,6 +7658,12 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCommentView {
             ));
         }
 
+        egui::CollapsingHeader::new("Preview")
+            .default_open(true)
+            .show(ui, |ui| {
+                crate::common::rich_text::show_markdown(ui, &self.text_buffer);
+            });
+
         ui.label("View properties");
 
         ui.horizontal(|ui| {
@@ -7739,18 +7755,18 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCommentView {
                 egui::Align::Max => -Self::CORNER_SIZE,
             },
         };
-        self.bounds_rect = canvas
-            .measure_text(
-                self.position,
-                self.align,
-                &read.text,
-                canvas::CLASS_MIDDLE_FONT_SIZE,
-            )
-            .expand2(egui::Vec2 {
-                x: Self::CORNER_SIZE,
-                y: Self::CORNER_SIZE,
-            })
-            .translate(align_offset);
+        self.bounds_rect = crate::common::rich_text::measure_markdown(
+            canvas,
+            self.position,
+            self.align,
+            &read.text,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        )
+        .expand2(egui::Vec2 {
+            x: Self::CORNER_SIZE,
+            y: Self::CORNER_SIZE,
+        })
+        .translate(align_offset);
 
         canvas.draw_polygon(
             [
@@ -7799,7 +7815,8 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCommentView {
             canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
             self.highlight,
         );
-        canvas.draw_text(
+        crate::common::rich_text::draw_markdown(
+            canvas,
             self.position + align_offset,
             self.align,
             &read.text,
@@ -7988,7 +8005,9 @@ impl ElementControllerGen2<UmlSequenceDomain> for UmlSequenceCommentView {
             | InsensitiveCommand::AddDependency { .. }
             | InsensitiveCommand::RemoveDependency { .. }
             | InsensitiveCommand::ArrangeSpecificElements(..)
-            | InsensitiveCommand::MoveOrdinal(..) => {}
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
             InsensitiveCommand::PropertyChange(uuids, property) => {
                 if uuids.contains(&*self.uuid) {
                     affected_models.insert(*self.model.read().uuid);
@@ -0,0 +1,2 @@
+pub mod wireframe_controllers;
+pub mod wireframe_models;
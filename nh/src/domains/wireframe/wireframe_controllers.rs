@@ -0,0 +1,2147 @@
+use super::wireframe_models::{
+    WireframeDiagram, WireframeElement, WireframeFrame, WireframeWidget, WireframeWidgetKind,
+};
+use crate::common::canvas::{self, NHCanvas, NHShape};
+use crate::common::controller::{
+    BucketNoT, ColorBundle, ColorChangeData, ContainerModel, ControllerAdapter, DiagramAdapter,
+    DiagramController, DiagramControllerGen2, DiagramSettings, DiagramSettings2, Domain,
+    ElementController, ElementControllerGen2, EventHandlingContext, EventHandlingStatus,
+    GenericQueryable, GlobalDrawingContext, InputEvent, InsensitiveCommand, MGlobalColor, Model,
+    MultiDiagramController, NAME_PATTERN_HINT, PaletteEditBuffer, PositionNoT, ProjectCommand,
+    PropertiesStatus, Queryable, SelectionStatus, ShowSettingsResult, SnapManager,
+    TargettingStatus, Tool, ToolPalette, TryMerge, View, expand_name_pattern,
+};
+use crate::common::entity::{Entity, EntityUuid};
+use crate::common::eref::ERef;
+use crate::common::project_serde::{NHDeserializeError, NHDeserializeInstantiator, NHDeserializer};
+use crate::common::ui_ext::UiExt;
+use crate::common::uuid::{ControllerUuid, ModelUuid, ViewUuid};
+use crate::common::views::multiconnection_view::VertexInformation;
+use crate::common::views::package_view::{PackageAdapter, PackageView};
+use crate::{
+    CustomModal, CustomModalResult, DefaultSettingsF, DeserializeControllerF, DeserializeSettingsF,
+    DiagramConstructorF, DiagramCreationData, DiagramInfo, SetShortcut,
+};
+use eframe::{egui, epaint};
+use std::collections::HashSet;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::{Arc, RwLock},
+};
+
+const RESIZE_HANDLE_SIZE: f32 = 8.0;
+
+fn default_widget_size(kind: WireframeWidgetKind) -> egui::Vec2 {
+    match kind {
+        WireframeWidgetKind::Button => egui::Vec2::new(100.0, 30.0),
+        WireframeWidgetKind::Input => egui::Vec2::new(160.0, 30.0),
+        WireframeWidgetKind::Label => egui::Vec2::new(100.0, 20.0),
+        WireframeWidgetKind::Image => egui::Vec2::new(120.0, 90.0),
+        WireframeWidgetKind::Symbol(..) => egui::Vec2::new(80.0, 80.0),
+    }
+}
+
+pub struct WireframeDomain;
+impl Domain for WireframeDomain {
+    type SettingsT = WireframeSettings;
+    type CommonElementT = WireframeElement;
+    type DiagramModelT = WireframeDiagram;
+    type CommonElementViewT = WireframeElementView;
+    type ViewTargettingSectionT = WireframeElement;
+    type QueryableT<'a> = GenericQueryable<'a, Self>;
+    type ToolT = NaiveWireframeTool;
+    type OrdinalMovementT = WireframeOrdinalMovement;
+    type AddCommandElementT = WireframeElementOrVertex;
+    type PropChangeT = WireframePropChange;
+}
+
+type PackageViewT = PackageView<WireframeDomain, WireframeFrameAdapter>;
+
+/// Dark profile with the light gray frame titles and image placeholders darkened
+const DARK_COLOR_PROFILE: canvas::ColorProfile = canvas::ColorProfile::DARK.with_fills(&[
+    (
+        egui::Color32::from_rgb(230, 230, 230),
+        egui::Color32::from_rgb(70, 70, 70),
+    ),
+    (
+        egui::Color32::from_rgb(245, 245, 245),
+        egui::Color32::from_rgb(55, 55, 55),
+    ),
+]);
+
+#[derive(Clone, Copy, Debug)]
+pub struct WireframeOrdinalMovement {}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum WireframePropChange {
+    Name(Arc<String>),
+    Text(Arc<String>),
+    WidgetKind(WireframeWidgetKind),
+
+    Color(ColorChangeData),
+    Comment(Arc<String>),
+}
+
+impl Debug for WireframePropChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "WireframePropChange::{}",
+            match self {
+                Self::Name(name) => format!("Name({})", name),
+                Self::Text(text) => format!("Text({})", text),
+                Self::WidgetKind(kind) => format!("WidgetKind({})", kind.as_str()),
+
+                Self::Color(_color) => "Color(..)".to_string(),
+                Self::Comment(comment) => format!("Comment({})", comment),
+            }
+        )
+    }
+}
+
+impl From<ColorChangeData> for WireframePropChange {
+    fn from(value: ColorChangeData) -> Self {
+        WireframePropChange::Color(value)
+    }
+}
+impl TryFrom<WireframePropChange> for ColorChangeData {
+    type Error = ();
+
+    fn try_from(value: WireframePropChange) -> Result<Self, Self::Error> {
+        match value {
+            WireframePropChange::Color(v) => Ok(v),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryMerge for WireframePropChange {
+    fn try_merge(&self, newer: &Self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match (self, newer) {
+            (Self::Name(_), newer @ Self::Name(_))
+            | (Self::Text(_), newer @ Self::Text(_))
+            | (Self::Comment(_), newer @ Self::Comment(_)) => Some(newer.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, derive_more::From, derive_more::TryInto)]
+pub enum WireframeElementOrVertex {
+    Element(WireframeElementView),
+    Vertex(VertexInformation),
+}
+
+impl Debug for WireframeElementOrVertex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "WireframeElementOrVertex::???")
+    }
+}
+
+#[derive(Clone, derive_more::From, nh_derive::View, nh_derive::NHContextSerDeTag)]
+#[view(default_passthrough = "eref", domain = "WireframeDomain")]
+#[nh_context_serde(uuid_type = ViewUuid)]
+pub enum WireframeElementView {
+    Frame(ERef<PackageViewT>),
+    Widget(ERef<WireframeWidgetView>),
+}
+
+#[derive(serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+pub struct WireframeControllerAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<WireframeDiagram>,
+}
+
+impl ControllerAdapter<WireframeDomain> for WireframeControllerAdapter {
+    type DiagramViewT = DiagramControllerGen2<WireframeDomain, WireframeDiagramAdapter>;
+
+    fn model(&self) -> ERef<WireframeDiagram> {
+        self.model.clone()
+    }
+    fn clone_with_model(&self, new_model: ERef<WireframeDiagram>) -> Self {
+        Self { model: new_model }
+    }
+    fn controller_type(&self) -> &'static str {
+        "wireframe"
+    }
+
+    fn model_transitive_closure(&self, when_deleting: HashSet<ModelUuid>) -> HashSet<ModelUuid> {
+        super::wireframe_models::transitive_closure(&self.model.read(), when_deleting)
+    }
+
+    fn insert_element(
+        &mut self,
+        parent: ModelUuid,
+        element: WireframeElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        self.model
+            .write()
+            .insert_element_into(parent, element, b, p)
+    }
+
+    fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, WireframeElement, BucketNoT, PositionNoT)>,
+    ) {
+        self.model.write().delete_elements(uuids, undo)
+    }
+
+    fn show_add_shared_diagram_menu(
+        &self,
+        _gdc: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ERef<Self::DiagramViewT>> {
+        if ui.button("Wireframe Diagram").clicked() {
+            return Some(Self::DiagramViewT::new(
+                ViewUuid::now_v7().into(),
+                "New Wireframe Diagram".to_owned().into(),
+                WireframeDiagramAdapter::new(self.model.clone()),
+                vec![],
+            ));
+        }
+        None
+    }
+}
+
+#[derive(
+    Clone, serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize,
+)]
+pub struct WireframeDiagramAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<WireframeDiagram>,
+    background_color: MGlobalColor,
+    #[serde(skip)]
+    #[nh_context_serde(skip_and_default)]
+    buffer: WireframeDiagramBuffer,
+}
+
+#[derive(Clone, Default)]
+struct WireframeDiagramBuffer {
+    name: String,
+    comment: String,
+}
+
+impl WireframeDiagramAdapter {
+    fn new(model: ERef<WireframeDiagram>) -> Self {
+        let m = model.read();
+        Self {
+            model: model.clone(),
+            background_color: MGlobalColor::None,
+            buffer: WireframeDiagramBuffer {
+                name: (*m.name).clone(),
+                comment: (*m.comment).clone(),
+            },
+        }
+    }
+}
+
+impl DiagramAdapter<WireframeDomain> for WireframeDiagramAdapter {
+    fn model(&self) -> ERef<WireframeDiagram> {
+        self.model.clone()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid()
+    }
+    fn model_name(&self) -> Arc<String> {
+        self.model.read().name.clone()
+    }
+
+    fn get_element_pos_in(
+        &self,
+        parent: &ModelUuid,
+        model_uuid: &ModelUuid,
+    ) -> Option<(BucketNoT, PositionNoT)> {
+        self.model.read().get_element_pos_in(parent, model_uuid)
+    }
+
+    fn create_new_view_for(
+        &self,
+        _q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        element: WireframeElement,
+    ) -> Result<WireframeElementView, HashSet<ModelUuid>> {
+        let v = match element {
+            WireframeElement::Frame(rw_lock) => {
+                WireframeElementView::from(new_wireframe_frame_view(
+                    rw_lock,
+                    egui::Rect {
+                        min: egui::Pos2::ZERO,
+                        max: egui::Pos2::new(360.0, 240.0),
+                    },
+                ))
+            }
+            WireframeElement::Widget(rw_lock) => {
+                WireframeElementView::from(new_wireframe_widget_view(rw_lock, egui::Pos2::ZERO))
+            }
+        };
+
+        Ok(v)
+    }
+    fn label_for(&self, e: &WireframeElement) -> Arc<String> {
+        match e {
+            WireframeElement::Frame(inner) => inner.read().name.clone(),
+            WireframeElement::Widget(inner) => inner.read().text.clone(),
+        }
+    }
+
+    fn background_color(&self, global_colors: &ColorBundle) -> egui::Color32 {
+        global_colors
+            .get(&self.background_color)
+            .unwrap_or(egui::Color32::WHITE)
+    }
+    fn gridlines_color(&self, _global_colors: &ColorBundle) -> egui::Color32 {
+        egui::Color32::from_rgb(220, 220, 220)
+    }
+    fn color_profile(&self, dark: bool) -> canvas::ColorProfile {
+        if dark {
+            DARK_COLOR_PROFILE
+        } else {
+            canvas::ColorProfile::LIGHT
+        }
+    }
+    fn show_view_props_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        drawing_context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) {
+        ui.label("Background color:");
+        if let Some(new_color) = crate::common::controller::mglobalcolor_edit_button(
+            drawing_context,
+            ui,
+            &self.background_color,
+        ) {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                WireframePropChange::Color((0, new_color).into()),
+            ));
+        }
+    }
+    fn show_model_props_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        _drawing_context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) {
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.buffer.name)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                WireframePropChange::Name(Arc::new(self.buffer.name.clone())),
+            ));
+        };
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.buffer.comment)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                std::iter::once(*view_uuid).collect(),
+                WireframePropChange::Comment(Arc::new(self.buffer.comment.clone())),
+            ));
+        }
+    }
+
+    fn apply_property_change_fun(
+        &mut self,
+        view_uuid: &ViewUuid,
+        command: &InsensitiveCommand<
+            WireframeOrdinalMovement,
+            WireframeElementOrVertex,
+            WireframePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) {
+        if let InsensitiveCommand::PropertyChange(_, property) = command {
+            let mut model = self.model.write();
+            match property {
+                WireframePropChange::Name(name) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Name(model.name.clone()),
+                    ));
+                    model.name = name.clone();
+                }
+                WireframePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Color(ColorChangeData {
+                            slot: 0,
+                            color: self.background_color,
+                        }),
+                    ));
+                    self.background_color = *color;
+                }
+                WireframePropChange::Comment(comment) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Comment(model.comment.clone()),
+                    ));
+                    model.comment = comment.clone();
+                }
+                _ => {}
+            }
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.buffer.name = (*model.name).clone();
+        self.buffer.comment = (*model.comment).clone();
+    }
+
+    fn menubar_options_fun(
+        &self,
+        _view_uuid: &ViewUuid,
+        _ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) {
+    }
+    fn try_handle_custom_shortcut(
+        &mut self,
+        settings: &WireframeSettings,
+        modifiers: egui::Modifiers,
+        key: egui::Key,
+    ) -> PropertiesStatus<WireframeDomain> {
+        if let Some((uuid, ts)) = settings
+            .palette
+            .read()
+            .unwrap()
+            .find_matching_tool_stage(modifiers, key)
+        {
+            PropertiesStatus::ToolRequest(Some(NaiveWireframeTool {
+                uuid,
+                initial_stage: ts.clone(),
+                current_stage: ts,
+                result: PartialWireframeElement::None,
+                event_lock: false,
+                is_spent: None,
+            }))
+        } else {
+            PropertiesStatus::Shown
+        }
+    }
+
+    fn deep_copy(&self) -> (Self, HashMap<ModelUuid, WireframeElement>) {
+        let (new_model, models) = super::wireframe_models::deep_copy_diagram(&self.model.read());
+        (
+            Self {
+                model: new_model,
+                ..self.clone()
+            },
+            models,
+        )
+    }
+
+    fn enumerate_models(&self) -> (Self, HashMap<ModelUuid, WireframeElement>) {
+        let models = super::wireframe_models::enumerate_diagram(&self.model.read());
+        (self.clone(), models)
+    }
+}
+
+fn new_controlller(
+    model: ERef<WireframeDiagram>,
+    name: String,
+    elements: Vec<WireframeElementView>,
+) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let uuid = ViewUuid::now_v7();
+    (
+        uuid,
+        ERef::new(MultiDiagramController::new(
+            ControllerUuid::now_v7(),
+            WireframeControllerAdapter {
+                model: model.clone(),
+            },
+            vec![DiagramControllerGen2::new(
+                uuid.into(),
+                name.into(),
+                WireframeDiagramAdapter::new(model),
+                elements,
+            )],
+        )),
+    )
+}
+
+pub fn new(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let name = format!("New Wireframe diagram {}", no);
+
+    let diagram = ERef::new(WireframeDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![],
+    ));
+    new_controlller(diagram, name, vec![])
+}
+
+pub fn demo(no: u32) -> (ViewUuid, ERef<dyn DiagramController>) {
+    let (title, title_view) = new_wireframe_widget(
+        WireframeWidgetKind::Label,
+        "Sign in",
+        egui::Pos2::new(140.0, 80.0),
+    );
+    let (logo, logo_view) = new_wireframe_widget(
+        WireframeWidgetKind::Image,
+        "Logo",
+        egui::Pos2::new(340.0, 130.0),
+    );
+    let (username, username_view) = new_wireframe_widget(
+        WireframeWidgetKind::Input,
+        "Username",
+        egui::Pos2::new(170.0, 130.0),
+    );
+    let (password, password_view) = new_wireframe_widget(
+        WireframeWidgetKind::Input,
+        "Password",
+        egui::Pos2::new(170.0, 170.0),
+    );
+    let (submit, submit_view) = new_wireframe_widget(
+        WireframeWidgetKind::Button,
+        "Log in",
+        egui::Pos2::new(140.0, 220.0),
+    );
+    let (login, login_view) = new_wireframe_frame(
+        "Login screen",
+        egui::Rect::from_min_max(egui::Pos2::new(60.0, 40.0), egui::Pos2::new(420.0, 260.0)),
+        vec![
+            title.into(),
+            logo.into(),
+            username.into(),
+            password.into(),
+            submit.into(),
+        ],
+        vec![
+            title_view.into(),
+            logo_view.into(),
+            username_view.into(),
+            password_view.into(),
+            submit_view.into(),
+        ],
+    );
+
+    let name = format!("Demo Wireframe diagram {}", no);
+    let diagram = ERef::new(WireframeDiagram::new(
+        ModelUuid::now_v7(),
+        name.clone(),
+        vec![login.into()],
+    ));
+    new_controlller(diagram, name, vec![login_view.into()])
+}
+
+pub fn deserializer(
+    uuid: ControllerUuid,
+    d: &mut NHDeserializer,
+) -> Result<ERef<dyn DiagramController>, NHDeserializeError> {
+    Ok(d.get_entity::<MultiDiagramController<
+        WireframeDomain,
+        WireframeControllerAdapter,
+        DiagramControllerGen2<WireframeDomain, WireframeDiagramAdapter>,
+    >>(&uuid)?)
+}
+
+pub struct WireframeSettings {
+    palette: RwLock<ToolPalette<WireframeToolStage, WireframeDomain>>,
+    palette_edit_buffer: RwLock<PaletteEditBuffer<WireframeToolStage, WireframeElementView>>,
+}
+impl DiagramSettings for WireframeSettings {
+    fn show(
+        &mut self,
+        gdc: &mut GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        shortcut_being_set: &Option<SetShortcut>,
+    ) -> ShowSettingsResult {
+        let mut w = self.palette.write().unwrap();
+        let mut buffer = self.palette_edit_buffer.write().unwrap();
+        let mut ret = ShowSettingsResult::None;
+
+        ui.columns(2, |columns| {
+            w.show_treeview(gdc, &mut columns[0]);
+
+            let selected = w.get_selected();
+            if selected.uuid() != buffer.uuid() {
+                *buffer = w.get_buffer(selected.uuid().cloned());
+            }
+            match &mut *buffer {
+                PaletteEditBuffer::None => {}
+                PaletteEditBuffer::Group(_uuid, name) => {
+                    if columns[1]
+                        .labeled_text_edit_singleline("Label", name)
+                        .changed()
+                    {
+                        w.set_from_buffer(buffer.clone());
+                    }
+                }
+                PaletteEditBuffer::Tool(uuid, name, tool, view, ksc) => {
+                    let mut modified = false;
+                    modified |= columns[1]
+                        .labeled_text_edit_singleline("Label", name)
+                        .changed();
+
+                    match crate::common::controller::show_shortcut(
+                        &mut columns[1],
+                        ksc,
+                        shortcut_being_set
+                            .as_ref()
+                            .is_some_and(|e| e.is_diagram(uuid)),
+                    ) {
+                        crate::common::controller::ShortCutStatus::NoChange => {}
+                        crate::common::controller::ShortCutStatus::Cleared => modified = true,
+                        crate::common::controller::ShortCutStatus::Set => {
+                            ret = ShowSettingsResult::SetShortcut(*uuid);
+                        }
+                        crate::common::controller::ShortCutStatus::CancelSet => {
+                            ret = ShowSettingsResult::CancelShortcutSetting;
+                        }
+                    }
+
+                    match tool {
+                        WireframeToolStage::Widget { kind, text, size } => {
+                            modified |= columns[1]
+                                .labeled_text_edit_singleline("Text", text)
+                                .on_hover_text(NAME_PATTERN_HINT)
+                                .changed();
+                            if let WireframeWidgetKind::Symbol(uuid) = kind {
+                                modified |= show_symbol_picker(gdc, &mut columns[1], uuid);
+                            }
+
+                            let mut custom_size = size.is_some();
+                            if columns[1]
+                                .checkbox(&mut custom_size, "Custom size")
+                                .changed()
+                            {
+                                *size = custom_size.then(|| default_widget_size(*kind));
+                                modified = true;
+                            }
+                            if let Some(size) = size {
+                                columns[1].horizontal(|ui| {
+                                    modified |= ui
+                                        .add(
+                                            egui::DragValue::new(&mut size.x).range(1.0..=f32::MAX),
+                                        )
+                                        .changed();
+                                    ui.label("×");
+                                    modified |= ui
+                                        .add(
+                                            egui::DragValue::new(&mut size.y).range(1.0..=f32::MAX),
+                                        )
+                                        .changed();
+                                });
+                            }
+                        }
+                        WireframeToolStage::FrameStart { name } => {
+                            modified |= columns[1]
+                                .labeled_text_edit_singleline("Name", name)
+                                .on_hover_text(NAME_PATTERN_HINT)
+                                .changed();
+                        }
+                        WireframeToolStage::FrameEnd => unreachable!(),
+                    }
+
+                    if modified {
+                        *view = view_for_stage(tool);
+                        w.set_from_buffer(buffer.clone());
+                    }
+                }
+            }
+        });
+
+        ret
+    }
+
+    fn try_set_shortcut(&mut self, tool: uuid::Uuid, shortcut: egui::KeyboardShortcut) {
+        let mut wp = self.palette.write().unwrap();
+        wp.set_shortcut(tool, Some(shortcut));
+        let mut wb = self.palette_edit_buffer.write().unwrap();
+        *wb = wp.get_buffer(wb.uuid().cloned());
+    }
+
+    fn serialize(&self) -> Result<toml::Value, ()> {
+        let mut table = toml::Table::new();
+        table.insert(
+            "palette".to_owned(),
+            self.palette.read().unwrap().serialize()?,
+        );
+        Ok(table.into())
+    }
+}
+impl DiagramSettings2<WireframeDomain> for WireframeSettings {
+    fn palette_for_each_mut<F>(&self, f: F)
+    where
+        F: FnMut(
+            &mut (
+                uuid::Uuid,
+                String,
+                Vec<(
+                    uuid::Uuid,
+                    WireframeToolStage,
+                    String,
+                    WireframeElementView,
+                    Option<egui::KeyboardShortcut>,
+                )>,
+            ),
+        ),
+    {
+        self.palette.write().unwrap().for_each_mut(f);
+    }
+}
+
+pub fn default_settings() -> Box<dyn DiagramSettings> {
+    let palette_items = vec![
+        (
+            "Widgets",
+            vec![
+                (
+                    WireframeToolStage::Widget {
+                        kind: WireframeWidgetKind::Button,
+                        text: "Button".to_owned(),
+                        size: None,
+                    },
+                    "Button",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num1,
+                    )),
+                ),
+                (
+                    WireframeToolStage::Widget {
+                        kind: WireframeWidgetKind::Input,
+                        text: "Input".to_owned(),
+                        size: None,
+                    },
+                    "Input",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num2,
+                    )),
+                ),
+                (
+                    WireframeToolStage::Widget {
+                        kind: WireframeWidgetKind::Label,
+                        text: "Label".to_owned(),
+                        size: None,
+                    },
+                    "Label",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num3,
+                    )),
+                ),
+                (
+                    WireframeToolStage::Widget {
+                        kind: WireframeWidgetKind::Image,
+                        text: "Image".to_owned(),
+                        size: None,
+                    },
+                    "Image",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num4,
+                    )),
+                ),
+                (
+                    WireframeToolStage::Widget {
+                        kind: WireframeWidgetKind::Symbol(uuid::Uuid::nil()),
+                        text: "".to_owned(),
+                        size: None,
+                    },
+                    "Symbol",
+                    Some(egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::Num6,
+                    )),
+                ),
+            ],
+        ),
+        (
+            "Other",
+            vec![(
+                WireframeToolStage::FrameStart {
+                    name: "Screen {n}".to_owned(),
+                },
+                "Frame",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Num5,
+                )),
+            )],
+        ),
+    ]
+    .into_iter()
+    .map(|e| {
+        (
+            e.0,
+            e.1.into_iter()
+                .map(|e| {
+                    let v = view_for_stage(&e.0);
+                    (e.0, e.1, v, e.2)
+                })
+                .collect(),
+        )
+    })
+    .collect();
+
+    Box::new(WireframeSettings {
+        palette: RwLock::new(ToolPalette::new(palette_items)),
+        palette_edit_buffer: RwLock::new(PaletteEditBuffer::None),
+    })
+}
+
+fn view_for_stage(s: &WireframeToolStage) -> WireframeElementView {
+    match s {
+        WireframeToolStage::Widget { kind, text, .. } => {
+            let text = expand_name_pattern(text, |_| false);
+            let widget_view = new_wireframe_widget(*kind, &text, egui::Pos2::ZERO).1;
+            widget_view.into()
+        }
+        WireframeToolStage::FrameStart { name } => {
+            let frame_view = new_wireframe_frame(
+                &expand_name_pattern(name, |_| false),
+                egui::Rect {
+                    min: egui::Pos2::ZERO,
+                    max: egui::Pos2::new(150.0, 100.0),
+                },
+                Vec::new(),
+                Vec::new(),
+            )
+            .1;
+            frame_view.into()
+        }
+        WireframeToolStage::FrameEnd => unreachable!(),
+    }
+}
+
+pub fn settings_deserializer(value: toml::Value) -> Result<Box<dyn DiagramSettings>, ()> {
+    let toml::Value::Table(value) = value else {
+        return Err(());
+    };
+    Ok(Box::new(WireframeSettings {
+        palette: ToolPalette::deserialize(value.get("palette").ok_or(())?.clone(), view_for_stage)?
+            .into(),
+        palette_edit_buffer: PaletteEditBuffer::None.into(),
+    }))
+}
+
+inventory::submit! {DiagramInfo {
+    type_indentifier: "wireframe",
+    pretty_name: "Wireframe",
+    default_settings: &(default_settings as DefaultSettingsF),
+    settings_deserializer: &(settings_deserializer as DeserializeSettingsF),
+    diagram_creation_data: DiagramCreationData {
+        directory: "",
+        description: "Low-fidelity user interface sketch",
+        constructors: &[
+            ("empty", &(new as DiagramConstructorF)),
+            ("demo", &(demo as DiagramConstructorF)),
+        ],
+    },
+    deserializer: &(deserializer as DeserializeControllerF),
+}}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum WireframeToolStage {
+    Widget {
+        kind: WireframeWidgetKind,
+        /// Name pattern, see [`expand_name_pattern`]
+        text: String,
+        /// Overrides the default size of the widget kind
+        #[serde(default)]
+        size: Option<egui::Vec2>,
+    },
+    FrameStart {
+        name: String,
+    },
+    FrameEnd,
+}
+
+enum PartialWireframeElement {
+    None,
+    Some(WireframeElementView),
+    Frame {
+        name: String,
+        a: egui::Pos2,
+        b: Option<egui::Pos2>,
+    },
+}
+
+pub struct NaiveWireframeTool {
+    uuid: uuid::Uuid,
+    initial_stage: WireframeToolStage,
+    current_stage: WireframeToolStage,
+    result: PartialWireframeElement,
+    event_lock: bool,
+    is_spent: Option<bool>,
+}
+
+impl NaiveWireframeTool {
+    fn try_spend(&mut self) {
+        self.result = PartialWireframeElement::None;
+        self.is_spent = self.is_spent.map(|_| true);
+    }
+}
+
+const TARGETTABLE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(0, 255, 0, 31);
+const NON_TARGETTABLE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(255, 0, 0, 31);
+
+impl Tool<WireframeDomain> for NaiveWireframeTool {
+    type Stage = WireframeToolStage;
+
+    fn new(uuid: uuid::Uuid, initial_stage: WireframeToolStage, repeat: bool) -> Self {
+        Self {
+            uuid,
+            current_stage: initial_stage.clone(),
+            initial_stage,
+            result: PartialWireframeElement::None,
+            event_lock: false,
+            is_spent: if repeat { None } else { Some(false) },
+        }
+    }
+    fn initial_stage_uuid(&self) -> &uuid::Uuid {
+        &self.uuid
+    }
+    fn repeats(&self) -> bool {
+        self.is_spent.is_none()
+    }
+    fn is_spent(&self) -> bool {
+        self.is_spent.is_some_and(|e| e)
+    }
+
+    fn targetting_for_section(&self, element: Option<WireframeElement>) -> egui::Color32 {
+        match element {
+            None => TARGETTABLE_COLOR,
+            Some(WireframeElement::Frame(..)) => match self.current_stage {
+                WireframeToolStage::Widget { .. } => TARGETTABLE_COLOR,
+                WireframeToolStage::FrameStart { .. } | WireframeToolStage::FrameEnd => {
+                    NON_TARGETTABLE_COLOR
+                }
+            },
+            Some(WireframeElement::Widget(..)) => NON_TARGETTABLE_COLOR,
+        }
+    }
+    fn draw_status_hint(
+        &self,
+        _q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        canvas: &mut dyn NHCanvas,
+        pos: egui::Pos2,
+    ) {
+        if let PartialWireframeElement::Frame { a, .. } = &self.result {
+            canvas.draw_rectangle(
+                egui::Rect::from_two_pos(*a, pos),
+                egui::CornerRadius::ZERO,
+                egui::Color32::TRANSPARENT,
+                canvas::Stroke::new_dashed(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+        }
+    }
+
+    fn add_position(&mut self, pos: egui::Pos2) {
+        if self.event_lock {
+            return;
+        }
+
+        match (&self.current_stage, &mut self.result) {
+            (WireframeToolStage::Widget { kind, text, size }, _) => {
+                let (_widget, widget_view) = new_wireframe_widget(*kind, text, pos);
+                if let Some(size) = size {
+                    widget_view.write().bounds_rect = egui::Rect::from_center_size(pos, *size);
+                }
+                self.result = PartialWireframeElement::Some(widget_view.into());
+                self.event_lock = true;
+            }
+            (WireframeToolStage::FrameStart { name }, _) => {
+                self.result = PartialWireframeElement::Frame {
+                    name: name.clone(),
+                    a: pos,
+                    b: None,
+                };
+                self.current_stage = WireframeToolStage::FrameEnd;
+                self.event_lock = true;
+            }
+            (WireframeToolStage::FrameEnd, PartialWireframeElement::Frame { b, .. }) => {
+                *b = Some(pos)
+            }
+            _ => {}
+        }
+    }
+    fn add_section(&mut self, _controller: WireframeElement) {}
+
+    fn try_flush(
+        &mut self,
+        q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        preferred_container: &ViewUuid,
+        preferred_bucket: BucketNoT,
+        preferred_position: Option<PositionNoT>,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                <WireframeDomain as Domain>::OrdinalMovementT,
+                <WireframeDomain as Domain>::AddCommandElementT,
+                <WireframeDomain as Domain>::PropChangeT,
+            >,
+        >,
+    ) -> Result<Option<Box<dyn CustomModal>>, ()> {
+        let mut taken_names = HashSet::new();
+        q.for_each_view(|e| {
+            taken_names.insert(match e.model() {
+                WireframeElement::Frame(inner) => (*inner.read().name).clone(),
+                WireframeElement::Widget(inner) => (*inner.read().text).clone(),
+            });
+        });
+        let expand = |pattern: &str| expand_name_pattern(pattern, |e| taken_names.contains(e));
+
+        match &self.result {
+            PartialWireframeElement::Some(element) => {
+                let element = element.clone();
+                if let WireframeElementView::Widget(inner) = &element {
+                    let mut w = inner.write();
+                    let text = expand(&w.model.read().text);
+                    w.model.write().text = Arc::new(text.clone());
+                    w.text_buffer = text;
+                }
+                let esm: Option<Box<dyn CustomModal>> = match &element {
+                    WireframeElementView::Widget(inner) => {
+                        Some(Box::new(WireframeNameSetupModal::from(
+                            WireframeElement::from(inner.read().model.clone()),
+                        )))
+                    }
+                    WireframeElementView::Frame(..) => unreachable!(),
+                };
+                self.try_spend();
+                commands.push(InsensitiveCommand::AddDependency {
+                    target: *preferred_container,
+                    bucket: preferred_bucket,
+                    position: preferred_position,
+                    element: element.into(),
+                    into_model: true,
+                });
+                Ok(esm)
+            }
+            PartialWireframeElement::Frame {
+                name,
+                a,
+                b: Some(b),
+                ..
+            } => {
+                self.current_stage = self.initial_stage.clone();
+
+                let (frame_model, frame_view) = new_wireframe_frame(
+                    &expand(name),
+                    egui::Rect::from_two_pos(*a, *b),
+                    Vec::new(),
+                    Vec::new(),
+                );
+
+                self.try_spend();
+                commands.push(InsensitiveCommand::AddDependency {
+                    target: *preferred_container,
+                    bucket: preferred_bucket,
+                    position: preferred_position,
+                    element: WireframeElementView::from(frame_view).into(),
+                    into_model: true,
+                });
+                Ok(Some(Box::new(WireframeNameSetupModal::from(
+                    WireframeElement::from(frame_model),
+                ))))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn reset_event_lock(&mut self) {
+        self.event_lock = false;
+    }
+}
+
+struct WireframeNameSetupModal {
+    model: WireframeElement,
+    first_frame: bool,
+    name_buffer: String,
+    /// Only for symbol widgets
+    symbol_buffer: Option<uuid::Uuid>,
+}
+
+impl From<WireframeElement> for WireframeNameSetupModal {
+    fn from(model: WireframeElement) -> Self {
+        let name_buffer = match &model {
+            WireframeElement::Frame(eref) => (*eref.read().name).clone(),
+            WireframeElement::Widget(eref) => (*eref.read().text).clone(),
+        };
+        let symbol_buffer = match &model {
+            WireframeElement::Widget(eref) => match eref.read().kind {
+                WireframeWidgetKind::Symbol(uuid) => Some(uuid),
+                _ => None,
+            },
+            WireframeElement::Frame(..) => None,
+        };
+        Self {
+            model,
+            first_frame: true,
+            name_buffer,
+            symbol_buffer,
+        }
+    }
+}
+
+impl CustomModal for WireframeNameSetupModal {
+    fn show(
+        &mut self,
+        gdc: &mut GlobalDrawingContext,
+        ui: &mut egui::Ui,
+        _commands: &mut Vec<ProjectCommand>,
+    ) -> CustomModalResult {
+        ui.label(match &self.model {
+            WireframeElement::Frame(..) => "Name:",
+            WireframeElement::Widget(..) => "Text:",
+        });
+        let r = ui.text_edit_singleline(&mut self.name_buffer);
+        if let Some(uuid) = self.symbol_buffer.as_mut() {
+            if gdc.symbols.get(uuid).is_none()
+                && let Some(first) = gdc.symbols.first()
+            {
+                *uuid = first;
+            }
+            show_symbol_picker(gdc, ui, uuid);
+        }
+        ui.separator();
+
+        if self.first_frame {
+            r.request_focus();
+            self.first_frame = false;
+        }
+
+        let mut result = CustomModalResult::KeepOpen;
+        ui.horizontal(|ui| {
+            if ui.button(gdc.translate_0("nh-generic-ok")).clicked() {
+                let name = Arc::new(self.name_buffer.clone());
+                match &self.model {
+                    WireframeElement::Frame(inner) => inner.write().name = name,
+                    WireframeElement::Widget(inner) => {
+                        let mut w = inner.write();
+                        w.text = name;
+                        if let Some(uuid) = self.symbol_buffer {
+                            w.kind = WireframeWidgetKind::Symbol(uuid);
+                        }
+                    }
+                }
+                result = CustomModalResult::CloseModified(*self.model.uuid());
+            }
+            if ui.button(gdc.translate_0("nh-generic-cancel")).clicked() {
+                result = CustomModalResult::CloseUnmodified;
+            }
+        });
+
+        result
+    }
+}
+
+/// Returns whether a different symbol was picked
+fn show_symbol_picker(
+    gdc: &GlobalDrawingContext,
+    ui: &mut egui::Ui,
+    uuid: &mut uuid::Uuid,
+) -> bool {
+    if gdc.symbols.symbols.is_empty() {
+        ui.label("The symbol library of the project is empty");
+        return false;
+    }
+    let mut changed = false;
+    egui::ComboBox::from_label("Symbol")
+        .selected_text(
+            gdc.symbols
+                .get(uuid)
+                .map(|e| e.name.clone())
+                .unwrap_or_else(|| "Missing symbol".to_owned()),
+        )
+        .show_ui(ui, |ui| {
+            for (k, e) in gdc.symbols.iter() {
+                changed |= ui.selectable_value(uuid, *k, &e.name).changed();
+            }
+        });
+    changed
+}
+
+fn new_wireframe_frame(
+    name: &str,
+    bounds_rect: egui::Rect,
+    contained_models: Vec<WireframeElement>,
+    contained_views: Vec<WireframeElementView>,
+) -> (ERef<WireframeFrame>, ERef<PackageViewT>) {
+    let frame_model = ERef::new(WireframeFrame::new(
+        ModelUuid::now_v7(),
+        name.to_owned(),
+        contained_models,
+    ));
+    let frame_view = PackageView::new(
+        ViewUuid::now_v7().into(),
+        new_wireframe_frame_adapter(frame_model.clone()),
+        contained_views,
+        bounds_rect,
+    );
+
+    (frame_model, frame_view)
+}
+fn new_wireframe_frame_view(
+    model: ERef<WireframeFrame>,
+    bounds_rect: egui::Rect,
+) -> ERef<PackageViewT> {
+    PackageView::new(
+        ViewUuid::now_v7().into(),
+        new_wireframe_frame_adapter(model),
+        Vec::new(),
+        bounds_rect,
+    )
+}
+fn new_wireframe_frame_adapter(model: ERef<WireframeFrame>) -> WireframeFrameAdapter {
+    let m = model.read();
+    WireframeFrameAdapter {
+        model: model.clone(),
+        background_color: MGlobalColor::None,
+        name_buffer: (*m.name).clone(),
+        comment_buffer: (*m.comment).clone(),
+    }
+}
+
+#[derive(
+    Clone, serde::Serialize, nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize,
+)]
+pub struct WireframeFrameAdapter {
+    #[nh_context_serde(entity)]
+    model: ERef<WireframeFrame>,
+    background_color: MGlobalColor,
+
+    #[nh_context_serde(skip_and_default)]
+    name_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    comment_buffer: String,
+}
+
+impl PackageAdapter<WireframeDomain> for WireframeFrameAdapter {
+    fn model_section(&self) -> WireframeElement {
+        self.model.clone().into()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid.clone()
+    }
+    fn model_name(&self) -> Arc<String> {
+        self.model.read().name.clone()
+    }
+
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        self.model.read().get_element_pos(uuid)
+    }
+    fn insert_element(
+        &mut self,
+        position: Option<PositionNoT>,
+        element: WireframeElement,
+    ) -> Result<PositionNoT, ()> {
+        self.model
+            .write()
+            .insert_element(0, position, element)
+            .map_err(|_| ())
+    }
+    fn delete_element(&mut self, uuid: &ModelUuid) -> Option<PositionNoT> {
+        self.model.write().remove_element(uuid).map(|e| e.1)
+    }
+
+    fn background_color(&self, global_colors: &ColorBundle) -> egui::Color32 {
+        global_colors
+            .get(&self.background_color)
+            .unwrap_or(egui::Color32::WHITE)
+    }
+    fn draw_label_or_get_text(
+        &self,
+        bounds_rect: egui::Rect,
+        _highlight: canvas::Highlight,
+        _q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        context: &GlobalDrawingContext,
+        _settings: &WireframeSettings,
+        canvas: &mut dyn canvas::NHCanvas,
+        _tool: &Option<(egui::Pos2, &NaiveWireframeTool)>,
+    ) -> Result<egui::Rect, Arc<String>> {
+        const PADDING: f32 = 4.0;
+        let text_color = self.text_color(&context.global_colors);
+
+        // Title bar of the sketched window
+        let name = self.model_name();
+        let r = canvas.measure_text(
+            bounds_rect.left_top() + egui::Vec2::splat(PADDING),
+            egui::Align2::LEFT_TOP,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+        );
+        let title_rect = egui::Rect::from_min_max(
+            bounds_rect.left_top(),
+            egui::Pos2::new(bounds_rect.max.x, r.max.y + PADDING),
+        );
+        canvas.draw_rectangle(
+            title_rect,
+            egui::CornerRadius::ZERO,
+            egui::Color32::from_rgb(230, 230, 230),
+            canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+            canvas::Highlight::NONE,
+        );
+        canvas.draw_text(
+            bounds_rect.left_top() + egui::Vec2::splat(PADDING),
+            egui::Align2::LEFT_TOP,
+            &name,
+            canvas::CLASS_MIDDLE_FONT_SIZE,
+            text_color,
+        );
+
+        Ok(title_rect)
+    }
+
+    fn show_model_properties(
+        &mut self,
+        q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) {
+        if ui
+            .labeled_text_edit_singleline("Name:", &mut self.name_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                WireframePropChange::Name(Arc::new(self.name_buffer.clone())),
+            ));
+        }
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                WireframePropChange::Comment(Arc::new(self.comment_buffer.clone())),
+            ));
+        }
+    }
+    fn show_color_property(
+        &mut self,
+        context: &GlobalDrawingContext,
+        ui: &mut egui::Ui,
+    ) -> Option<ColorChangeData> {
+        ui.label("Background color:");
+        crate::common::controller::mglobalcolor_edit_button(context, ui, &self.background_color)
+            .map(|e| (0, e).into())
+    }
+    fn apply_change(
+        &mut self,
+        view_uuid: &ViewUuid,
+        command: &InsensitiveCommand<
+            WireframeOrdinalMovement,
+            WireframeElementOrVertex,
+            WireframePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) {
+        if let InsensitiveCommand::PropertyChange(_, property) = command {
+            let mut model = self.model.write();
+            match property {
+                WireframePropChange::Name(name) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Name(model.name.clone()),
+                    ));
+                    model.name = name.clone();
+                }
+                WireframePropChange::Comment(comment) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Comment(model.comment.clone()),
+                    ));
+                    model.comment = comment.clone();
+                }
+                WireframePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                    undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                        std::iter::once(*view_uuid).collect(),
+                        WireframePropChange::Color(ColorChangeData {
+                            slot: 0,
+                            color: self.background_color,
+                        }),
+                    ));
+                    self.background_color = *color;
+                }
+                _ => {}
+            }
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.name_buffer = (*model.name).clone();
+        self.comment_buffer = (*model.comment).clone();
+    }
+
+    fn deep_copy_init(
+        &self,
+        new_uuid: ModelUuid,
+        m: &mut HashMap<ModelUuid, WireframeElement>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let old_model = self.model.read();
+
+        let model = if let Some(WireframeElement::Frame(m)) = m.get(&old_model.uuid) {
+            m.clone()
+        } else {
+            let modelish = old_model.clone_with(new_uuid);
+            m.insert(*old_model.uuid, modelish.clone().into());
+            modelish
+        };
+        Self {
+            model,
+            background_color: self.background_color,
+            name_buffer: self.name_buffer.clone(),
+            comment_buffer: self.comment_buffer.clone(),
+        }
+    }
+
+    fn deep_copy_finish(&mut self, m: &HashMap<ModelUuid, WireframeElement>) {
+        let mut w = self.model.write();
+        for e in w.contained_elements.iter_mut() {
+            if let Some(new_model) = m.get(&*e.uuid()) {
+                *e = new_model.clone();
+            }
+        }
+    }
+}
+
+fn new_wireframe_widget(
+    kind: WireframeWidgetKind,
+    text: &str,
+    position: egui::Pos2,
+) -> (ERef<WireframeWidget>, ERef<WireframeWidgetView>) {
+    let widget_model = ERef::new(WireframeWidget::new(
+        ModelUuid::now_v7(),
+        kind,
+        text.to_owned(),
+    ));
+    let widget_view = new_wireframe_widget_view(widget_model.clone(), position);
+    (widget_model, widget_view)
+}
+fn new_wireframe_widget_view(
+    model: ERef<WireframeWidget>,
+    position: egui::Pos2,
+) -> ERef<WireframeWidgetView> {
+    let m = model.read();
+
+    ERef::new(WireframeWidgetView {
+        uuid: ViewUuid::now_v7().into(),
+        model: model.clone(),
+
+        text_buffer: (*m.text).clone(),
+        kind_buffer: m.kind,
+        comment_buffer: (*m.comment).clone(),
+
+        dragged_type_and_shape: None,
+        highlight: canvas::Highlight::NONE,
+        bounds_rect: egui::Rect::from_center_size(position, default_widget_size(m.kind)),
+        background_color: MGlobalColor::None,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum WidgetDragType {
+    Move,
+    Resize,
+}
+
+/// Placeholder box standing in for a single user interface control.
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct WireframeWidgetView {
+    uuid: Arc<ViewUuid>,
+    #[nh_context_serde(entity)]
+    pub model: ERef<WireframeWidget>,
+
+    #[nh_context_serde(skip_and_default)]
+    text_buffer: String,
+    #[nh_context_serde(skip_and_default)]
+    kind_buffer: WireframeWidgetKind,
+    #[nh_context_serde(skip_and_default)]
+    comment_buffer: String,
+
+    #[nh_context_serde(skip_and_default)]
+    dragged_type_and_shape: Option<(WidgetDragType, egui::Rect)>,
+    #[nh_context_serde(skip_and_default)]
+    highlight: canvas::Highlight,
+    pub bounds_rect: egui::Rect,
+    background_color: MGlobalColor,
+}
+
+impl WireframeWidgetView {
+    fn resize_handle(&self, ui_scale: f32) -> egui::Rect {
+        egui::Rect::from_center_size(
+            self.bounds_rect.right_bottom(),
+            egui::Vec2::splat(RESIZE_HANDLE_SIZE / ui_scale),
+        )
+    }
+}
+
+impl Entity for WireframeWidgetView {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl View for WireframeWidgetView {
+    fn uuid(&self) -> Arc<ViewUuid> {
+        self.uuid.clone()
+    }
+    fn model_uuid(&self) -> Arc<ModelUuid> {
+        self.model.read().uuid.clone()
+    }
+}
+
+impl ElementController<WireframeElement> for WireframeWidgetView {
+    fn model(&self) -> WireframeElement {
+        self.model.clone().into()
+    }
+
+    fn min_shape(&self) -> NHShape {
+        NHShape::Rect {
+            inner: self.bounds_rect,
+        }
+    }
+
+    fn position(&self) -> egui::Pos2 {
+        self.bounds_rect.center()
+    }
+}
+
+impl ElementControllerGen2<WireframeDomain> for WireframeWidgetView {
+    fn show_properties(
+        &mut self,
+        gdc: &GlobalDrawingContext,
+        q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        ui: &mut egui::Ui,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) -> PropertiesStatus<WireframeDomain> {
+        if !self.highlight.selected {
+            return PropertiesStatus::NotShown;
+        }
+
+        ui.label("Model properties");
+
+        let kind_label = |kind: &WireframeWidgetKind| match kind {
+            WireframeWidgetKind::Symbol(uuid) => gdc
+                .symbols
+                .get(uuid)
+                .map(|e| format!("Symbol: {}", e.name))
+                .unwrap_or_else(|| "Symbol".to_owned()),
+            other => other.as_str().to_owned(),
+        };
+        egui::ComboBox::from_label("Kind")
+            .selected_text(kind_label(&self.kind_buffer))
+            .show_ui(ui, |ui| {
+                for e in WireframeWidgetKind::VARIANTS.into_iter().chain(
+                    gdc.symbols
+                        .iter()
+                        .map(|e| WireframeWidgetKind::Symbol(*e.0)),
+                ) {
+                    if ui
+                        .selectable_value(&mut self.kind_buffer, e, kind_label(&e))
+                        .changed()
+                    {
+                        commands.push(InsensitiveCommand::PropertyChange(
+                            q.selected_views(),
+                            WireframePropChange::WidgetKind(self.kind_buffer),
+                        ));
+                    }
+                }
+            });
+
+        if ui
+            .labeled_text_edit_singleline("Text:", &mut self.text_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                WireframePropChange::Text(Arc::new(self.text_buffer.clone())),
+            ));
+        }
+
+        if ui
+            .labeled_text_edit_multiline("Comment:", &mut self.comment_buffer)
+            .changed()
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                WireframePropChange::Comment(Arc::new(self.comment_buffer.clone())),
+            ));
+        }
+
+        ui.label("View properties");
+
+        ui.horizontal(|ui| {
+            let egui::Pos2 { mut x, mut y } = self.bounds_rect.min;
+
+            ui.label("x");
+            if ui.add(egui::DragValue::new(&mut x).speed(1.0)).changed() {
+                commands.push(InsensitiveCommand::MovePositional(
+                    q.selected_views(),
+                    egui::Vec2::new(x - self.bounds_rect.min.x, 0.0),
+                ));
+            }
+            ui.label("y");
+            if ui.add(egui::DragValue::new(&mut y).speed(1.0)).changed() {
+                commands.push(InsensitiveCommand::MovePositional(
+                    q.selected_views(),
+                    egui::Vec2::new(0.0, y - self.bounds_rect.min.y),
+                ));
+            }
+        });
+        ui.horizontal(|ui| {
+            let egui::Vec2 {
+                x: mut width,
+                y: mut height,
+            } = self.bounds_rect.size();
+
+            ui.label("width");
+            if ui
+                .add(egui::DragValue::new(&mut width).speed(1.0))
+                .changed()
+            {
+                commands.push(InsensitiveCommand::ResizeElementsBy(
+                    q.selected_views(),
+                    egui::Align2::LEFT_TOP,
+                    egui::Vec2::new(width - self.bounds_rect.width(), 0.0),
+                ));
+            }
+            ui.label("height");
+            if ui
+                .add(egui::DragValue::new(&mut height).speed(1.0))
+                .changed()
+            {
+                commands.push(InsensitiveCommand::ResizeElementsBy(
+                    q.selected_views(),
+                    egui::Align2::LEFT_TOP,
+                    egui::Vec2::new(0.0, height - self.bounds_rect.height()),
+                ));
+            }
+        });
+
+        ui.label("Background color:");
+        if let Some(new_color) =
+            crate::common::controller::mglobalcolor_edit_button(gdc, ui, &self.background_color)
+        {
+            commands.push(InsensitiveCommand::PropertyChange(
+                q.selected_views(),
+                WireframePropChange::Color((0, new_color).into()),
+            ));
+        }
+
+        PropertiesStatus::Shown
+    }
+    fn draw_in(
+        &mut self,
+        _q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        gdc: &GlobalDrawingContext,
+        _settings: &WireframeSettings,
+        canvas: &mut dyn NHCanvas,
+        tool: &Option<(egui::Pos2, &NaiveWireframeTool)>,
+    ) -> TargettingStatus {
+        let r = self.bounds_rect;
+        let (kind, text) = {
+            let m = self.model.read();
+            (m.kind, m.text.clone())
+        };
+        let stroke = canvas::Stroke::new_solid(1.0, egui::Color32::BLACK);
+
+        match kind {
+            WireframeWidgetKind::Button => {
+                canvas.draw_rectangle(
+                    r,
+                    egui::CornerRadius::same(6),
+                    gdc.global_colors
+                        .get(&self.background_color)
+                        .unwrap_or(egui::Color32::from_rgb(220, 220, 220)),
+                    stroke,
+                    self.highlight,
+                );
+                canvas.draw_text(
+                    r.center(),
+                    egui::Align2::CENTER_CENTER,
+                    &text,
+                    canvas::CLASS_MIDDLE_FONT_SIZE,
+                    egui::Color32::BLACK,
+                );
+            }
+            WireframeWidgetKind::Input => {
+                canvas.draw_rectangle(
+                    r,
+                    egui::CornerRadius::ZERO,
+                    gdc.global_colors
+                        .get(&self.background_color)
+                        .unwrap_or(egui::Color32::WHITE),
+                    stroke,
+                    self.highlight,
+                );
+                canvas.draw_text(
+                    r.left_center() + egui::Vec2::new(6.0, 0.0),
+                    egui::Align2::LEFT_CENTER,
+                    &text,
+                    canvas::CLASS_MIDDLE_FONT_SIZE,
+                    egui::Color32::GRAY,
+                );
+            }
+            WireframeWidgetKind::Label => {
+                canvas.draw_rectangle(
+                    r,
+                    egui::CornerRadius::ZERO,
+                    gdc.global_colors
+                        .get(&self.background_color)
+                        .unwrap_or(egui::Color32::TRANSPARENT),
+                    canvas::Stroke::NONE,
+                    self.highlight,
+                );
+                canvas.draw_text(
+                    r.left_center(),
+                    egui::Align2::LEFT_CENTER,
+                    &text,
+                    canvas::CLASS_MIDDLE_FONT_SIZE,
+                    egui::Color32::BLACK,
+                );
+            }
+            WireframeWidgetKind::Image => {
+                canvas.draw_rectangle(
+                    r,
+                    egui::CornerRadius::ZERO,
+                    gdc.global_colors
+                        .get(&self.background_color)
+                        .unwrap_or(egui::Color32::from_rgb(245, 245, 245)),
+                    stroke,
+                    self.highlight,
+                );
+                let cross_stroke = canvas::Stroke::new_solid(1.0, egui::Color32::GRAY);
+                canvas.draw_line(
+                    [r.left_top(), r.right_bottom()],
+                    cross_stroke,
+                    canvas::Highlight::NONE,
+                );
+                canvas.draw_line(
+                    [r.right_top(), r.left_bottom()],
+                    cross_stroke,
+                    canvas::Highlight::NONE,
+                );
+                canvas.draw_text(
+                    r.center(),
+                    egui::Align2::CENTER_CENTER,
+                    &text,
+                    canvas::CLASS_MIDDLE_FONT_SIZE,
+                    egui::Color32::BLACK,
+                );
+            }
+            WireframeWidgetKind::Symbol(uuid) => {
+                let symbol = gdc.symbols.get(&uuid);
+                canvas.draw_rectangle(
+                    r,
+                    egui::CornerRadius::ZERO,
+                    gdc.global_colors
+                        .get(&self.background_color)
+                        .unwrap_or(egui::Color32::TRANSPARENT),
+                    if symbol.is_some() {
+                        canvas::Stroke::NONE
+                    } else {
+                        canvas::Stroke::new_dashed(1.0, egui::Color32::GRAY)
+                    },
+                    self.highlight,
+                );
+                let text_height = if text.is_empty() {
+                    0.0
+                } else {
+                    canvas::CLASS_MIDDLE_FONT_SIZE + 4.0
+                };
+                let symbol_rect = egui::Rect::from_min_max(
+                    r.min,
+                    egui::Pos2::new(r.max.x, r.max.y - text_height),
+                );
+                match symbol {
+                    Some(s) => s.draw_in(canvas, symbol_rect, stroke, canvas::Highlight::NONE),
+                    None => canvas.draw_text(
+                        symbol_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        "missing symbol",
+                        canvas::CLASS_MIDDLE_FONT_SIZE,
+                        egui::Color32::GRAY,
+                    ),
+                }
+                if !text.is_empty() {
+                    canvas.draw_text(
+                        r.center_bottom(),
+                        egui::Align2::CENTER_BOTTOM,
+                        &text,
+                        canvas::CLASS_MIDDLE_FONT_SIZE,
+                        egui::Color32::BLACK,
+                    );
+                }
+            }
+        }
+
+        // Draw resize handle
+        if let Some(ui_scale) = canvas.ui_scale().filter(|_| self.highlight.selected) {
+            canvas.draw_rectangle(
+                self.resize_handle(ui_scale),
+                egui::CornerRadius::ZERO,
+                egui::Color32::WHITE,
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+        }
+
+        if canvas.ui_scale().is_some()
+            && let Some(t) = tool
+                .as_ref()
+                .filter(|e| self.min_shape().contains(e.0))
+                .map(|e| e.1)
+        {
+            canvas.draw_rectangle(
+                r,
+                egui::CornerRadius::ZERO,
+                t.targetting_for_section(Some(self.model())),
+                canvas::Stroke::new_solid(1.0, egui::Color32::BLACK),
+                canvas::Highlight::NONE,
+            );
+            TargettingStatus::Drawn
+        } else {
+            TargettingStatus::NotDrawn
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        event: InputEvent,
+        ehc: &EventHandlingContext,
+        _settings: &<WireframeDomain as Domain>::SettingsT,
+        q: &<WireframeDomain as Domain>::QueryableT<'_>,
+        tool: &mut Option<NaiveWireframeTool>,
+        _element_setup_modal: &mut Option<Box<dyn CustomModal>>,
+        commands: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+    ) -> EventHandlingStatus {
+        match event {
+            InputEvent::MouseDown(pos) => {
+                if self.highlight.selected && self.resize_handle(ehc.ui_scale).contains(pos) {
+                    self.dragged_type_and_shape = Some((WidgetDragType::Resize, self.bounds_rect));
+                    return EventHandlingStatus::HandledByElement;
+                }
+                if !self.min_shape().contains(pos) {
+                    return EventHandlingStatus::NotHandled;
+                }
+                self.dragged_type_and_shape = Some((WidgetDragType::Move, self.bounds_rect));
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::MouseUp(_) => {
+                if self.dragged_type_and_shape.is_some() {
+                    self.dragged_type_and_shape = None;
+                    EventHandlingStatus::HandledByElement
+                } else {
+                    EventHandlingStatus::NotHandled
+                }
+            }
+            InputEvent::Click(pos) if self.min_shape().contains(pos) => {
+                if let Some(tool) = tool {
+                    tool.add_section(self.model());
+                }
+
+                EventHandlingStatus::HandledByElement
+            }
+            InputEvent::Drag { delta, .. } => match self.dragged_type_and_shape {
+                Some((WidgetDragType::Move, real_bounds)) => {
+                    let translated_bounds = real_bounds.translate(delta);
+                    self.dragged_type_and_shape = Some((WidgetDragType::Move, translated_bounds));
+                    let translated_real_shape = NHShape::Rect {
+                        inner: translated_bounds,
+                    };
+                    let coerced_pos = if self.highlight.selected {
+                        ehc.snap_manager.coerce(translated_real_shape, |e| {
+                            !ehc.all_elements
+                                .get(e)
+                                .is_some_and(|e| *e != SelectionStatus::NotSelected)
+                        })
+                    } else {
+                        ehc.snap_manager
+                            .coerce(translated_real_shape, |e| *e != *self.uuid)
+                    };
+                    let coerced_delta = coerced_pos - self.position();
+
+                    if self.highlight.selected {
+                        commands.push(InsensitiveCommand::MovePositional(
+                            q.selected_views(),
+                            coerced_delta,
+                        ));
+                    } else {
+                        commands.push(InsensitiveCommand::MovePositional(
+                            std::iter::once(*self.uuid).collect(),
+                            coerced_delta,
+                        ));
+                    }
+                    EventHandlingStatus::HandledByElement
+                }
+                Some((WidgetDragType::Resize, real_bounds)) => {
+                    let new_real_bounds = real_bounds.with_max_x(real_bounds.max.x + delta.x);
+                    let new_real_bounds =
+                        new_real_bounds.with_max_y(new_real_bounds.max.y + delta.y);
+                    self.dragged_type_and_shape = Some((WidgetDragType::Resize, new_real_bounds));
+
+                    let coerced_point = ehc.snap_manager.coerce(
+                        NHShape::Rect {
+                            inner: egui::Rect::from_min_size(
+                                new_real_bounds.right_bottom(),
+                                egui::Vec2::ZERO,
+                            ),
+                        },
+                        |e| {
+                            !ehc.all_elements
+                                .get(e)
+                                .is_some_and(|e| *e != SelectionStatus::NotSelected)
+                        },
+                    );
+                    let coerced_delta = coerced_point - self.bounds_rect.right_bottom();
+
+                    commands.push(InsensitiveCommand::ResizeElementsBy(
+                        q.selected_views(),
+                        egui::Align2::LEFT_TOP,
+                        coerced_delta,
+                    ));
+                    EventHandlingStatus::HandledByElement
+                }
+                None => EventHandlingStatus::NotHandled,
+            },
+            _ => EventHandlingStatus::NotHandled,
+        }
+    }
+
+    fn apply_command(
+        &mut self,
+        command: &InsensitiveCommand<
+            WireframeOrdinalMovement,
+            WireframeElementOrVertex,
+            WireframePropChange,
+        >,
+        undo_accumulator: &mut Vec<
+            InsensitiveCommand<
+                WireframeOrdinalMovement,
+                WireframeElementOrVertex,
+                WireframePropChange,
+            >,
+        >,
+        affected_models: &mut HashSet<ModelUuid>,
+    ) {
+        macro_rules! resize_to {
+            ($rect:expr) => {
+                undo_accumulator.push(InsensitiveCommand::ResizeElementTo(
+                    *self.uuid,
+                    self.bounds_rect,
+                ));
+                self.bounds_rect = $rect;
+            };
+        }
+
+        match command {
+            InsensitiveCommand::HighlightAll(set, h) => {
+                self.highlight = self.highlight.combine(*set, *h);
+            }
+            InsensitiveCommand::HighlightSpecific(uuids, set, h) => {
+                if uuids.contains(&*self.uuid) {
+                    self.highlight = self.highlight.combine(*set, *h);
+                }
+            }
+            InsensitiveCommand::SelectByDrag(rect, retain) => {
+                self.highlight.selected = (self.highlight.selected && *retain)
+                    || self.min_shape().contained_within(*rect);
+            }
+            InsensitiveCommand::MovePositional(uuids, _) if !uuids.contains(&*self.uuid) => {}
+            InsensitiveCommand::MovePositional(_, delta)
+            | InsensitiveCommand::MovePositionalAll(delta) => {
+                self.bounds_rect = self.bounds_rect.translate(*delta);
+                undo_accumulator.push(InsensitiveCommand::MovePositional(
+                    std::iter::once(*self.uuid).collect(),
+                    -*delta,
+                ));
+            }
+            InsensitiveCommand::ResizeElementsBy(uuids, align, delta) => {
+                if uuids.contains(&self.uuid) {
+                    let min_delta_x = 40.0 - self.bounds_rect.width();
+                    let (left, right) = match align.x() {
+                        egui::Align::Min => (0.0, delta.x.max(min_delta_x)),
+                        egui::Align::Center => (0.0, 0.0),
+                        egui::Align::Max => ((-delta.x).max(min_delta_x), 0.0),
+                    };
+                    let min_delta_y = 20.0 - self.bounds_rect.height();
+                    let (top, bottom) = match align.y() {
+                        egui::Align::Min => (0.0, delta.y.max(min_delta_y)),
+                        egui::Align::Center => (0.0, 0.0),
+                        egui::Align::Max => ((-delta.y).max(min_delta_y), 0.0),
+                    };
+
+                    let r = self.bounds_rect
+                        + epaint::MarginF32 {
+                            left,
+                            right,
+                            top,
+                            bottom,
+                        };
+                    resize_to!(r);
+                }
+            }
+            InsensitiveCommand::ResizeElementTo(uuid, rect) => {
+                if *uuid == *self.uuid {
+                    resize_to!(*rect);
+                }
+            }
+            InsensitiveCommand::DeleteSpecificElements(..)
+            | InsensitiveCommand::AddDependency { .. }
+            | InsensitiveCommand::RemoveDependency { .. }
+            | InsensitiveCommand::ArrangeSpecificElements(..)
+            | InsensitiveCommand::MoveOrdinal(..)
+            | InsensitiveCommand::ElementStyleChange(..)
+            | InsensitiveCommand::GridChange(..) => {}
+            InsensitiveCommand::PropertyChange(uuids, property) => {
+                if uuids.contains(&*self.uuid) {
+                    affected_models.insert(*self.model.read().uuid);
+                    let mut model = self.model.write();
+                    match property {
+                        WireframePropChange::Text(text) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                WireframePropChange::Text(model.text.clone()),
+                            ));
+                            model.text = text.clone();
+                        }
+                        WireframePropChange::WidgetKind(kind) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                WireframePropChange::WidgetKind(model.kind),
+                            ));
+                            model.kind = *kind;
+                        }
+                        WireframePropChange::Comment(comment) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                WireframePropChange::Comment(model.comment.clone()),
+                            ));
+                            model.comment = comment.clone();
+                        }
+                        WireframePropChange::Color(ColorChangeData { slot: 0, color }) => {
+                            undo_accumulator.push(InsensitiveCommand::PropertyChange(
+                                std::iter::once(*self.uuid).collect(),
+                                WireframePropChange::Color(ColorChangeData {
+                                    slot: 0,
+                                    color: self.background_color,
+                                }),
+                            ));
+                            self.background_color = *color;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            InsensitiveCommand::Macro(..) => unreachable!(),
+        }
+    }
+    fn refresh_buffers(&mut self) {
+        let model = self.model.read();
+        self.text_buffer = (*model.text).clone();
+        self.kind_buffer = model.kind;
+        self.comment_buffer = (*model.comment).clone();
+    }
+
+    fn head_count(
+        &mut self,
+        _flattened_views: &mut HashMap<ViewUuid, (WireframeElementView, ViewUuid)>,
+        flattened_views_status: &mut HashMap<ViewUuid, SelectionStatus>,
+        flattened_represented_models: &mut HashMap<ModelUuid, ViewUuid>,
+    ) {
+        flattened_views_status.insert(*self.uuid(), self.highlight.selected.into());
+        flattened_represented_models.insert(*self.model_uuid(), *self.uuid);
+    }
+
+    fn deep_copy_clone(
+        &self,
+        uuid_present: &dyn Fn(&ViewUuid) -> bool,
+        tlc: &mut HashMap<ViewUuid, WireframeElementView>,
+        c: &mut HashMap<ViewUuid, WireframeElementView>,
+        m: &mut HashMap<ModelUuid, WireframeElement>,
+    ) {
+        let old_model = self.model.read();
+
+        let (view_uuid, model_uuid) = if uuid_present(&self.uuid) {
+            (ViewUuid::now_v7(), ModelUuid::now_v7())
+        } else {
+            (*self.uuid, *old_model.uuid)
+        };
+
+        let modelish = if let Some(WireframeElement::Widget(m)) = m.get(&old_model.uuid) {
+            m.clone()
+        } else {
+            let modelish = old_model.clone_with(model_uuid);
+            m.insert(*old_model.uuid, modelish.clone().into());
+            modelish
+        };
+
+        let cloneish = ERef::new(Self {
+            uuid: view_uuid.into(),
+            model: modelish,
+            text_buffer: self.text_buffer.clone(),
+            kind_buffer: self.kind_buffer,
+            comment_buffer: self.comment_buffer.clone(),
+            dragged_type_and_shape: None,
+            highlight: self.highlight,
+            bounds_rect: self.bounds_rect,
+            background_color: self.background_color,
+        });
+        tlc.insert(view_uuid, cloneish.clone().into());
+        c.insert(*self.uuid, cloneish.clone().into());
+    }
+}
@@ -0,0 +1,500 @@
+use crate::common::controller::{
+    BucketNoT, ContainerModel, DiagramVisitor, ElementVisitor, Model, PositionNoT,
+    VisitableDiagram, VisitableElement,
+};
+use crate::common::entity::{Entity, EntityUuid};
+use crate::common::eref::ERef;
+use crate::common::search::FullTextSearchable;
+use crate::common::uuid::ModelUuid;
+use std::collections::HashSet;
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(
+    Clone,
+    derive_more::From,
+    nh_derive::Model,
+    nh_derive::ContainerModel,
+    nh_derive::FullTextSearchable,
+    nh_derive::NHContextSerDeTag,
+)]
+#[model(default_passthrough = "eref")]
+#[container_model(element_type = WireframeElement, default_passthrough = "none")]
+#[full_text_searchable(default_passthrough = "eref")]
+#[nh_context_serde(uuid_type = ModelUuid)]
+pub enum WireframeElement {
+    #[container_model(passthrough = "eref")]
+    Frame(ERef<WireframeFrame>),
+    Widget(ERef<WireframeWidget>),
+}
+
+impl VisitableElement for WireframeElement {
+    fn accept(&self, v: &mut dyn ElementVisitor<Self>)
+    where
+        Self: Sized,
+    {
+        match self {
+            WireframeElement::Frame(inner) => {
+                v.open_complex(self);
+                for e in &inner.read().contained_elements {
+                    e.accept(v);
+                }
+                v.close_complex(self);
+            }
+            e => v.visit_simple(e),
+        }
+    }
+}
+
+pub fn deep_copy_diagram(
+    d: &WireframeDiagram,
+) -> (ERef<WireframeDiagram>, HashMap<ModelUuid, WireframeElement>) {
+    fn walk(
+        e: &WireframeElement,
+        into: &mut HashMap<ModelUuid, WireframeElement>,
+    ) -> WireframeElement {
+        let new_uuid = ModelUuid::now_v7();
+        match e {
+            WireframeElement::Frame(inner) => {
+                let model = inner.read();
+
+                let new_model = WireframeFrame {
+                    uuid: new_uuid.into(),
+                    name: model.name.clone(),
+                    contained_elements: model
+                        .contained_elements
+                        .iter()
+                        .map(|e| {
+                            let new_model = walk(e, into);
+                            into.insert(*e.uuid(), new_model.clone());
+                            new_model
+                        })
+                        .collect(),
+                    comment: model.comment.clone(),
+                };
+                WireframeElement::Frame(ERef::new(new_model))
+            }
+            WireframeElement::Widget(inner) => {
+                WireframeElement::Widget(inner.read().clone_with(new_uuid))
+            }
+        }
+    }
+
+    let mut all_models = HashMap::new();
+    let mut new_contained_elements = Vec::new();
+    for e in &d.contained_elements {
+        let new_model = walk(e, &mut all_models);
+        all_models.insert(*e.uuid(), new_model.clone());
+        new_contained_elements.push(new_model);
+    }
+
+    let new_diagram = WireframeDiagram {
+        uuid: ModelUuid::now_v7().into(),
+        name: d.name.clone(),
+        contained_elements: new_contained_elements,
+        comment: d.comment.clone(),
+    };
+    (ERef::new(new_diagram), all_models)
+}
+
+pub fn enumerate_diagram(d: &WireframeDiagram) -> HashMap<ModelUuid, WireframeElement> {
+    let mut all_models = HashMap::new();
+    for e in &d.contained_elements {
+        enumerate_elements(e, &mut all_models);
+    }
+    all_models
+}
+fn enumerate_elements(e: &WireframeElement, into: &mut HashMap<ModelUuid, WireframeElement>) {
+    into.insert(*e.uuid(), e.clone());
+    match e {
+        WireframeElement::Frame(inner) => {
+            for e in &inner.read().contained_elements {
+                enumerate_elements(e, into);
+            }
+        }
+        WireframeElement::Widget(..) => {}
+    }
+}
+
+pub fn transitive_closure(
+    d: &WireframeDiagram,
+    mut when_deleting: HashSet<ModelUuid>,
+) -> HashSet<ModelUuid> {
+    fn walk(e: &WireframeElement, when_deleting: &mut HashSet<ModelUuid>) {
+        match e {
+            WireframeElement::Frame(inner) => {
+                let r = inner.read();
+                if when_deleting.contains(&r.uuid) {
+                    let mut c = Default::default();
+                    enumerate_elements(e, &mut c);
+                    when_deleting.extend(c.into_keys());
+                } else {
+                    for e in &r.contained_elements {
+                        walk(e, when_deleting);
+                    }
+                }
+            }
+            WireframeElement::Widget(..) => {}
+        }
+    }
+    for e in &d.contained_elements {
+        walk(e, &mut when_deleting);
+    }
+
+    when_deleting
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity, is_subset_with = crate::common::project_serde::no_dependencies)]
+pub struct WireframeDiagram {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    #[nh_context_serde(entity)]
+    pub contained_elements: Vec<WireframeElement>,
+
+    pub comment: Arc<String>,
+}
+
+impl WireframeDiagram {
+    pub fn new(uuid: ModelUuid, name: String, contained_elements: Vec<WireframeElement>) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            contained_elements,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+
+    pub fn get_element_pos_in(
+        &self,
+        parent: &ModelUuid,
+        uuid: &ModelUuid,
+    ) -> Option<(BucketNoT, PositionNoT)> {
+        if *parent == *self.uuid {
+            self.get_element_pos(uuid)
+        } else {
+            self.find_element(parent)
+                .and_then(|e| e.0.get_element_pos(uuid))
+        }
+    }
+
+    pub fn insert_element_into(
+        &mut self,
+        parent: ModelUuid,
+        element: WireframeElement,
+        b: BucketNoT,
+        p: Option<PositionNoT>,
+    ) -> Result<(), ()> {
+        if *self.uuid == parent {
+            self.insert_element(b, p, element)
+                .map(|_| ())
+                .map_err(|_| ())
+        } else {
+            self.find_element(&parent).ok_or(()).and_then(|mut e| {
+                e.0.insert_element(b, p, element)
+                    .map(|_| ())
+                    .map_err(|_| ())
+            })
+        }
+    }
+
+    pub fn delete_elements(
+        &mut self,
+        uuids: &HashSet<ModelUuid>,
+        undo: &mut Vec<(ModelUuid, WireframeElement, BucketNoT, PositionNoT)>,
+    ) {
+        fn r(
+            e: &WireframeElement,
+            uuids: &HashSet<ModelUuid>,
+            undo: &mut Vec<(ModelUuid, WireframeElement, BucketNoT, PositionNoT)>,
+        ) {
+            match e {
+                WireframeElement::Frame(inner) => {
+                    let mut w = inner.write();
+                    for (idx, e) in w.contained_elements.iter().enumerate() {
+                        if uuids.contains(&e.uuid()) {
+                            undo.push((*w.uuid, e.clone(), 0, idx));
+                        } else {
+                            r(e, uuids, undo);
+                        }
+                    }
+                    w.contained_elements.retain(|e| !uuids.contains(&e.uuid()));
+                }
+                WireframeElement::Widget(_) => {}
+            }
+        }
+
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if uuids.contains(&e.uuid()) {
+                undo.push((*self.uuid, e.clone(), 0, idx));
+            } else {
+                r(e, uuids, undo);
+            }
+        }
+        self.contained_elements
+            .retain(|e| !uuids.contains(&e.uuid()));
+    }
+}
+
+impl Entity for WireframeDiagram {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for WireframeDiagram {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl VisitableDiagram for WireframeDiagram {
+    fn accept(&self, v: &mut dyn DiagramVisitor<Self>) {
+        v.open_diagram(self);
+        for e in &self.contained_elements {
+            e.accept(v);
+        }
+        v.close_diagram(self);
+    }
+}
+
+impl ContainerModel for WireframeDiagram {
+    type ElementT = WireframeElement;
+
+    fn find_element(&self, uuid: &ModelUuid) -> Option<(WireframeElement, ModelUuid)> {
+        for e in &self.contained_elements {
+            if *e.uuid() == *uuid {
+                return Some((e.clone(), *self.uuid));
+            }
+            if let Some(e) = e.find_element(uuid) {
+                return Some(e);
+            }
+        }
+        None
+    }
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+    fn insert_element(
+        &mut self,
+        bucket: BucketNoT,
+        position: Option<PositionNoT>,
+        element: WireframeElement,
+    ) -> Result<PositionNoT, WireframeElement> {
+        if bucket != 0 {
+            return Err(element);
+        }
+
+        let pos = position.unwrap_or(self.contained_elements.len());
+        self.contained_elements.insert(pos, element);
+        Ok(pos)
+    }
+    fn remove_element(&mut self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                self.contained_elements.remove(idx);
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+}
+
+impl FullTextSearchable for WireframeDiagram {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[&self.uuid.to_string(), &self.name, &self.comment],
+        );
+
+        for e in &self.contained_elements {
+            e.full_text_search(acc);
+        }
+    }
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct WireframeFrame {
+    pub uuid: Arc<ModelUuid>,
+    pub name: Arc<String>,
+    #[nh_context_serde(entity)]
+    pub contained_elements: Vec<WireframeElement>,
+
+    pub comment: Arc<String>,
+}
+
+impl WireframeFrame {
+    pub fn new(uuid: ModelUuid, name: String, contained_elements: Vec<WireframeElement>) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            name: Arc::new(name),
+            contained_elements,
+            comment: Arc::new("".to_owned()),
+        }
+    }
+    pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
+        ERef::new(Self {
+            uuid: Arc::new(new_uuid),
+            name: self.name.clone(),
+            contained_elements: self.contained_elements.clone(),
+            comment: self.comment.clone(),
+        })
+    }
+}
+
+impl Entity for WireframeFrame {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for WireframeFrame {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl ContainerModel for WireframeFrame {
+    type ElementT = WireframeElement;
+
+    fn find_element(&self, uuid: &ModelUuid) -> Option<(WireframeElement, ModelUuid)> {
+        for e in &self.contained_elements {
+            if *e.uuid() == *uuid {
+                return Some((e.clone(), *self.uuid));
+            }
+            if let Some(e) = e.find_element(uuid) {
+                return Some(e);
+            }
+        }
+        None
+    }
+    fn get_element_pos(&self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+    fn insert_element(
+        &mut self,
+        bucket: BucketNoT,
+        position: Option<PositionNoT>,
+        element: WireframeElement,
+    ) -> Result<PositionNoT, WireframeElement> {
+        if bucket != 0 {
+            return Err(element);
+        }
+
+        let pos = position.unwrap_or(self.contained_elements.len());
+        self.contained_elements.insert(pos, element);
+        Ok(pos)
+    }
+    fn remove_element(&mut self, uuid: &ModelUuid) -> Option<(BucketNoT, PositionNoT)> {
+        for (idx, e) in self.contained_elements.iter().enumerate() {
+            if *e.uuid() == *uuid {
+                self.contained_elements.remove(idx);
+                return Some((0, idx));
+            }
+        }
+        None
+    }
+}
+
+impl FullTextSearchable for WireframeFrame {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[&self.uuid.to_string(), &self.name, &self.comment],
+        );
+
+        for e in &self.contained_elements {
+            e.full_text_search(acc);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub enum WireframeWidgetKind {
+    #[default]
+    Button,
+    Input,
+    Label,
+    Image,
+    /// Instance of a symbol from the project symbol library
+    Symbol(uuid::Uuid),
+}
+
+impl WireframeWidgetKind {
+    pub const VARIANTS: [Self; 4] = [Self::Button, Self::Input, Self::Label, Self::Image];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WireframeWidgetKind::Button => "Button",
+            WireframeWidgetKind::Input => "Input",
+            WireframeWidgetKind::Label => "Label",
+            WireframeWidgetKind::Image => "Image",
+            WireframeWidgetKind::Symbol(..) => "Symbol",
+        }
+    }
+}
+
+#[derive(nh_derive::NHContextSerialize, nh_derive::NHContextDeserialize)]
+#[nh_context_serde(is_entity)]
+pub struct WireframeWidget {
+    pub uuid: Arc<ModelUuid>,
+    pub kind: WireframeWidgetKind,
+    pub text: Arc<String>,
+
+    pub comment: Arc<String>,
+}
+
+impl WireframeWidget {
+    pub fn new(uuid: ModelUuid, kind: WireframeWidgetKind, text: String) -> Self {
+        Self {
+            uuid: Arc::new(uuid),
+            kind,
+            text: Arc::new(text),
+            comment: Arc::new("".to_owned()),
+        }
+    }
+    pub fn clone_with(&self, new_uuid: ModelUuid) -> ERef<Self> {
+        ERef::new(Self {
+            uuid: Arc::new(new_uuid),
+            kind: self.kind,
+            text: self.text.clone(),
+            comment: self.comment.clone(),
+        })
+    }
+}
+
+impl Entity for WireframeWidget {
+    fn tagged_uuid(&self) -> EntityUuid {
+        (*self.uuid).into()
+    }
+}
+
+impl Model for WireframeWidget {
+    fn uuid(&self) -> Arc<ModelUuid> {
+        self.uuid.clone()
+    }
+}
+
+impl FullTextSearchable for WireframeWidget {
+    fn full_text_search(&self, acc: &mut crate::common::search::Searcher) {
+        acc.check_element(
+            *self.uuid,
+            &[
+                &self.uuid.to_string(),
+                self.kind.as_str(),
+                &self.text,
+                &self.comment,
+            ],
+        );
+    }
+}
@@ -9,9 +9,10 @@ use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, RwLock};
 
-use common::canvas::{NHCanvas, UiCanvas};
+use common::canvas::{ElementDefaults, NHCanvas, UiCanvas};
 use common::controller::{
     Arrangement, GlobalDrawingContext, HierarchyNode, ProjectCommand, SimpleProjectCommand,
+    SizeMatch,
 };
 use common::project_serde::{NHDeserializeError, NHDeserializer, NHSerializeError};
 use common::uuid::{ControllerUuid, ModelUuid, ViewUuid};
@@ -27,16 +28,31 @@ use rfd::FileHandle;
 mod common;
 mod domains;
 
+use crate::common::abbreviations::{AbbreviationDictionary, AbbreviationEntry};
 use crate::common::canvas::{Highlight, MeasuringCanvas, SVGCanvas};
 use crate::common::controller::{
-    ColorBundle, DeleteKind, DiagramCommand, DiagramController, DiagramSettings, LabelProvider,
-    ModifierKeys, ModifierSettings, ShowSettingsResult, TOOL_PALETTE_MAX_HEIGHT,
-    TOOL_PALETTE_MIN_HEIGHT,
+    ColorBundle, DeleteKind, DiagramCamera, DiagramCommand, DiagramController, DiagramSettings,
+    DoubleClickAction, ElementCategory, ElementLink, LabelProvider, ModifierKeys, ModifierSettings,
+    ShowSettingsResult, TOOL_PALETTE_MAX_HEIGHT, TOOL_PALETTE_MIN_HEIGHT, TextExportFormat,
 };
+use crate::common::drawio::DrawioCanvas;
 use crate::common::eref::ERef;
+use crate::common::external_links::{self, ExternalLink, ExternalLinkStatus};
+use crate::common::images::{ImageLibrary, ProjectImage};
+use crate::common::issue_links::{
+    IssueFetch, IssueFetchResult, IssueInfo, IssueLink, IssueReference,
+};
+use crate::common::layout::DiagramLayout;
+use crate::common::macros::{CommandMacro, MacroRecorder, MacroStep};
 use crate::common::project_serde::{
     FSRawReader, FSRawWriter, FSReadAbstraction, FSWriteAbstraction, ZipFSReader, ZipFSWriter,
 };
+use crate::common::raster::{self, ImageExportFormat};
+use crate::common::symbols::{SymbolLibrary, SymbolLibraryEditor};
+use crate::common::todos::{TodoMarker, TodoPriority};
+use crate::common::variants::VariantDiffTab;
+use crate::common::views::ordered_views::ZOrderLock;
+use crate::domains::umlclass::umlclass_plantuml::PLANTUML_FORMAT;
 
 /// Adds a widget with a label next to it, can be given an extra parameter in order to show a hover text
 macro_rules! labeled_widget {
@@ -80,6 +96,36 @@ fn main() -> eframe::Result<()> {
         std::env::set_var("RUST_BACKTRACE", "1");
     }
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|e| e == "--export") {
+        if let Err(e) = HeadlessExportArgs::parse(&args[1..]).and_then(|e| e.run()) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if args.first().is_some_and(|e| e == "--check") {
+        match headless_check(&args[1..]) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+    if args.first().is_some_and(|e| e == "--repair") {
+        match headless_repair(&args[1..]) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+    let safe_mode = args.iter().any(|e| e == "--safe-mode");
+
     let icon = eframe::icon_data::from_png_bytes(include_bytes!("../assets/icon-256.png"))
         .expect("Invalid icon data");
 
@@ -93,10 +139,232 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Nihonium",
         options,
-        Box::new(|cc| Ok(Box::new(NHApp::load_or_new(cc)))),
+        Box::new(move |cc| Ok(Box::new(NHApp::load_or_new(cc, safe_mode)))),
     )
 }
 
+/// Usage of the batch export mode, printed on invalid arguments
+#[cfg(not(target_arch = "wasm32"))]
+const HEADLESS_EXPORT_USAGE: &str = "Usage: nh --export <project.nhp|project.nhpz> [--diagram <name or uuid>]... [--format <svg|png|jpeg|drawio|plantuml|...>] [--output <folder>]";
+
+/// Exports diagrams of a project file without starting the UI, e.g. for regenerating
+/// documentation images in CI pipelines.
+///
+/// All diagrams are exported when none are given. Each is written to
+/// `<output>/<diagram name>.<extension>`, or `<output>/<diagram name> (<uuid>).<extension>`
+/// when several exported diagrams share the name.
+#[cfg(not(target_arch = "wasm32"))]
+struct HeadlessExportArgs {
+    project: PathBuf,
+    diagrams: Vec<String>,
+    format: String,
+    output: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl HeadlessExportArgs {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut project = None;
+        let mut diagrams = Vec::new();
+        let mut format = "svg".to_owned();
+        let mut output = PathBuf::from(".");
+
+        let mut it = args.iter();
+        while let Some(a) = it.next() {
+            let mut value = || {
+                it.next()
+                    .cloned()
+                    .ok_or_else(|| format!("Missing value for {}\n{}", a, HEADLESS_EXPORT_USAGE))
+            };
+            match a.as_str() {
+                "--diagram" => diagrams.push(value()?),
+                "--format" => format = value()?.to_lowercase(),
+                "--output" => output = value()?.into(),
+                _ if project.is_none() && !a.starts_with("--") => project = Some(a.into()),
+                _ => {
+                    return Err(format!(
+                        "Unexpected argument {:?}\n{}",
+                        a, HEADLESS_EXPORT_USAGE
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            project: project.ok_or_else(|| HEADLESS_EXPORT_USAGE.to_owned())?,
+            diagrams,
+            format,
+            output,
+        })
+    }
+
+    fn run(self) -> Result<(), String> {
+        let mut app = NHApp::new_headless();
+        app.context.import_project_blocking(&self.project)?;
+
+        let mut views: Vec<_> = app
+            .context
+            .diagram_controllers
+            .iter()
+            .map(|(k, v)| (*k, v.clone(), v.read().view_name(k)))
+            .filter(|(k, _, name)| {
+                self.diagrams.is_empty()
+                    || self
+                        .diagrams
+                        .iter()
+                        .any(|e| **e == **name || *e == k.to_string())
+            })
+            .collect();
+        views.sort_by(|a, b| a.2.cmp(&b.2));
+        for e in &self.diagrams {
+            if !views
+                .iter()
+                .any(|(k, _, name)| **e == **name || *e == k.to_string())
+            {
+                return Err(format!("Diagram {:?} not found in the project", e));
+            }
+        }
+        std::fs::create_dir_all(&self.output).map_err(|e| e.to_string())?;
+
+        // Names are compared case-insensitively, as some file systems are
+        let mut name_counts = HashMap::<String, usize>::new();
+        for (_, _, name) in &views {
+            *name_counts
+                .entry(sanitize_file_name(name).to_lowercase())
+                .or_default() += 1;
+        }
+        let file_stem = |v: &ViewUuid, name: &str| {
+            let sanitized = sanitize_file_name(name);
+            if name_counts[&sanitized.to_lowercase()] > 1 {
+                format!("{} ({})", sanitized, v.to_string())
+            } else {
+                sanitized
+            }
+        };
+
+        // Text measurement and rasterization need the fonts of a running egui pass
+        let ctx = egui::Context::default();
+        let mut result = None;
+        let _ = ctx.run_ui(egui::RawInput::default(), |ui| {
+            if result.is_some() {
+                return;
+            }
+            result = Some(views.iter().try_for_each(|(v, c, name)| {
+                let (extension, bytes) = app.context.export_diagram_headless(
+                    ui.ctx(),
+                    v,
+                    &mut *c.write(),
+                    &self.format,
+                )?;
+                let path = self
+                    .output
+                    .join(format!("{}.{}", file_stem(v, name), extension));
+                std::fs::write(&path, bytes).map_err(|e| format!("{:?}: {}", path, e))?;
+                println!("{}", path.display());
+                Ok::<(), String>(())
+            }));
+        });
+        result.unwrap_or(Ok(()))
+    }
+}
+
+/// Checks the consistency of a project without starting the UI, e.g. in pre-commit hooks.
+///
+/// Problems are printed to the standard output as a JSON array.
+/// Returns whether the project has no problems.
+#[cfg(not(target_arch = "wasm32"))]
+fn headless_check(args: &[String]) -> Result<bool, String> {
+    #[derive(serde::Serialize)]
+    struct ReportEntry {
+        diagram: ViewUuid,
+        diagram_name: Arc<String>,
+        view: Option<ViewUuid>,
+        model: Option<ModelUuid>,
+        label: Option<Arc<String>>,
+        message: String,
+    }
+
+    let [project] = args else {
+        return Err("Usage: nh --check <project.nhp|project.nhpz>".to_owned());
+    };
+    let mut app = NHApp::new_headless();
+    app.context
+        .import_project_blocking(std::path::Path::new(project))?;
+
+    let mut diagrams: Vec<_> = app
+        .context
+        .diagram_controllers
+        .iter()
+        .map(|(k, v)| (*k, v.read().view_name(k), v.clone()))
+        .collect();
+    diagrams.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let labels = &app.context.drawing_context.model_labels;
+    let report: Vec<_> = diagrams
+        .into_iter()
+        .flat_map(|(v, name, c)| {
+            let mut seen = HashSet::new();
+            c.read()
+                .check_problems(&v)
+                .into_iter()
+                .filter(move |e| seen.insert(e.clone()))
+                .map(move |e| ReportEntry {
+                    diagram: v,
+                    diagram_name: name.clone(),
+                    view: e.view,
+                    model: e.model,
+                    label: e.model.map(|m| labels.get(&m)),
+                    message: e.message,
+                })
+        })
+        .collect();
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+    );
+    Ok(report.is_empty())
+}
+
+/// Scans a project for unreadable sources, duplicate uuids and dangling references
+/// without loading it, optionally repairing it so that the rest of it can be opened.
+///
+/// Problems are printed to the standard output, one per line.
+/// Returns whether the project has no problems left.
+#[cfg(not(target_arch = "wasm32"))]
+fn headless_repair(args: &[String]) -> Result<bool, String> {
+    const USAGE: &str = "Usage: nh --repair <project.nhp|project.nhpz> [--fix]";
+    let (project, fix) = match args {
+        [project] => (project, false),
+        [project, fix] if fix == "--fix" => (project, true),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let path = std::path::Path::new(project);
+    let mut files = common::project_repair::ProjectFiles::read(path)?;
+
+    let problems = common::project_repair::check_project(&mut files, fix);
+    for e in &problems {
+        println!("{}", e);
+    }
+    if problems.is_empty() || !fix {
+        return Ok(problems.is_empty());
+    }
+    files.write(path)?;
+    println!("Repaired {} problems", problems.len());
+    Ok(true)
+}
+
+/// Replaces characters which are not allowed in file names on common platforms
+#[cfg(not(target_arch = "wasm32"))]
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
+
 #[cfg(target_arch = "wasm32")]
 fn main() {
     use eframe::wasm_bindgen::JsCast as _;
@@ -117,7 +385,7 @@ fn main() {
             .start(
                 canvas,
                 Default::default(),
-                Box::new(|cc| Ok(Box::new(NHApp::load_or_new(cc)))),
+                Box::new(|cc| Ok(Box::new(NHApp::load_or_new(cc, false)))),
             )
             .await;
 
@@ -151,11 +419,30 @@ pub enum NHTab {
     Toolbar,
     Properties,
     GlobalColors,
+    Symbols,
+    Abbreviations,
+    Tasks,
+    ExternalLinks,
+    IssueLinks,
+    DrawOrder,
+    Layers,
     Outline,
-
-    Diagram { uuid: ViewUuid },
-    Document { uuid: ViewUuid },
-    CustomTab { uuid: uuid::Uuid },
+    DocumentOutline,
+
+    Diagram {
+        uuid: ViewUuid,
+    },
+    /// Additional view of a diagram with its own camera
+    DiagramViewport {
+        uuid: ViewUuid,
+        viewport: uuid::Uuid,
+    },
+    Document {
+        uuid: ViewUuid,
+    },
+    CustomTab {
+        uuid: uuid::Uuid,
+    },
 }
 
 impl NHTab {
@@ -172,9 +459,18 @@ impl NHTab {
             NHTab::Toolbar => gdc.translate_0("nh-tab-toolbar"),
             NHTab::Properties => gdc.translate_0("nh-tab-properties"),
             NHTab::GlobalColors => gdc.translate_0("nh-tab-globalcolors"),
+            NHTab::Symbols => gdc.translate_0("nh-tab-symbols"),
+            NHTab::Abbreviations => gdc.translate_0("nh-tab-abbreviations"),
+            NHTab::Tasks => gdc.translate_0("nh-tab-tasks"),
+            NHTab::ExternalLinks => gdc.translate_0("nh-tab-externallinks"),
+            NHTab::IssueLinks => gdc.translate_0("nh-tab-issuelinks"),
+            NHTab::DrawOrder => gdc.translate_0("nh-tab-draworder"),
+            NHTab::Layers => gdc.translate_0("nh-tab-layers"),
             NHTab::Outline => gdc.translate_0("nh-tab-outline"),
+            NHTab::DocumentOutline => gdc.translate_0("nh-tab-documentoutline"),
 
             NHTab::Diagram { .. } => gdc.translate_0("nh-tab-diagram"),
+            NHTab::DiagramViewport { .. } => gdc.translate_0("nh-tab-diagramviewport"),
             NHTab::Document { .. } => gdc.translate_0("nh-tab-document"),
             NHTab::CustomTab { .. } => gdc.translate_0("nh-tab-customtab"),
         }
@@ -183,9 +479,20 @@ impl NHTab {
     pub fn is_persistable(&self) -> bool {
         !matches!(
             self,
-            Self::Diagram { .. } | Self::Document { .. } | Self::CustomTab { .. }
+            Self::Diagram { .. }
+                | Self::DiagramViewport { .. }
+                | Self::Document { .. }
+                | Self::CustomTab { .. }
         )
     }
+
+    /// Diagram shown in the tab, either in its main tab or in an additional viewport
+    pub fn diagram(&self) -> Option<&ViewUuid> {
+        match self {
+            Self::Diagram { uuid } | Self::DiagramViewport { uuid, .. } => Some(uuid),
+            _ => None,
+        }
+    }
 }
 
 pub trait CustomTab {
@@ -214,6 +521,12 @@ pub trait CustomModal {
     ) -> CustomModalResult;
 }
 
+/// Interaction with the side panel of a document
+enum DocumentPanelAction {
+    ScrollTo(usize),
+    FocusElement(ModelUuid),
+}
+
 pub struct ErrorModal {
     message: String,
 }
@@ -248,7 +561,23 @@ enum FileIOOperation {
         Result<Box<dyn FSReadAbstraction + Send>, NHDeserializeError>,
     ),
     Save(FileHandle),
-    ImageExport(FileHandle, ViewUuid, ERef<dyn DiagramController>),
+    ImageExport(
+        FileHandle,
+        ViewUuid,
+        ERef<dyn DiagramController>,
+        ImageExportFormat,
+    ),
+    TextExport(FileHandle, ERef<dyn DiagramController>, TextExportFormat),
+    SettingsExport(FileHandle),
+    SettingsImport(Vec<u8>),
+    BackgroundImage(ViewUuid, /*file name:*/ String, Vec<u8>),
+    ImageElement(
+        ViewUuid,
+        Option<egui::Pos2>,
+        /*file name:*/ String,
+        Vec<u8>,
+    ),
+    DocumentImport(/*file name:*/ String, Vec<u8>),
     Error(String),
 }
 
@@ -314,6 +643,12 @@ struct NHContext {
     diagram_deserializers: HashMap<String, &'static DeserializeControllerF>,
     new_diagram_no: u32,
     documents: HashMap<ViewUuid, (String, String)>,
+    /// Diagrams duplicated as variants, mapped to the diagrams they were duplicated from
+    diagram_variants: HashMap<ViewUuid, ViewUuid>,
+    /// Diagrams new diagrams of the given type are copied from, overriding `default_constructors`
+    diagram_templates: HashMap<String, ViewUuid>,
+    /// Diagrams double-clicking elements drills down into
+    sub_diagrams: HashMap<ModelUuid, ViewUuid>,
     clipboard: Vec<Box<dyn Any>>,
     pub custom_tabs: HashMap<uuid::Uuid, Arc<RwLock<dyn CustomTab>>>,
     custom_modal: Option<Box<dyn CustomModal>>,
@@ -326,8 +661,10 @@ struct NHContext {
     diagram_settings_functions: HashMap<&'static str, &'static DefaultSettingsF>,
     shades_profiles: Vec<ShadesProfile>,
     selected_shades_profile: usize,
+    macro_recorder: MacroRecorder,
     selected_language: usize,
-    languages_order: Vec<unic_langid::LanguageIdentifier>,
+    /// Language of translated model content in text exports, the UI language order if None
+    export_language: Option<unic_langid::LanguageIdentifier>,
     shortcut_top_order: Vec<(SimpleProjectCommand, egui::KeyboardShortcut)>,
     modifier_settings: ModifierSettings,
     drawing_context: GlobalDrawingContext,
@@ -335,6 +672,12 @@ struct NHContext {
     new_diagram_data: HashMap<usize, (&'static str, DiagramCreationData)>,
     new_diagram_selected_kind: usize,
     new_diagram_selected_constructor: usize,
+    /// Names of constructors preselected for new diagrams of the given type
+    default_constructors: HashMap<String, String>,
+    /// Whether saving the project also refreshes an SVG image of every diagram
+    svg_backup_enabled: bool,
+    /// Folder for the SVG images, relative to the folder of the project file
+    svg_backup_folder: String,
 
     unprocessed_commands: Vec<ProjectCommand>,
     affected_models: HashSet<ModelUuid>,
@@ -342,7 +685,12 @@ struct NHContext {
     has_unsaved_changes: bool,
 
     open_unique_tabs: HashSet<NHTab>,
+    /// Cameras of the additional viewports of diagrams, swapped in while they are shown
+    diagram_viewports: HashMap<uuid::Uuid, DiagramCamera>,
     last_focused_diagram: Option<ViewUuid>,
+    last_focused_document: Option<ViewUuid>,
+    /// Document and character offset its editor should scroll to
+    document_scroll_request: Option<(ViewUuid, usize)>,
     svg_export_menu: Option<(
         ViewUuid,
         ERef<dyn DiagramController>,
@@ -352,10 +700,25 @@ struct NHContext {
         Highlight,
         f32,
         f32,
+        ImageExportFormat,
+        f32,
     )>,
     confirm_modal_reason: Option<SimpleProjectCommand>,
     shortcut_being_set: Option<SetShortcut>,
     new_global_color_name: String,
+    new_layer_name: String,
+    todos: Vec<TodoMarker>,
+    external_links: Vec<ExternalLink>,
+    /// Last modification times of linked files, None if they cannot be read
+    external_files_modified: HashMap<PathBuf, Option<u64>>,
+    external_links_checked_at: f64,
+    issue_links: Vec<IssueLink>,
+    /// Titles and states of linked issues by their API URL, None while being fetched
+    issue_infos: HashMap<String, Option<IssueFetchResult>>,
+    issue_info_channel: (Sender<IssueFetch>, Receiver<IssueFetch>),
+    tasks_filter: String,
+    tasks_min_priority: TodoPriority,
+    symbol_library_editor: SymbolLibraryEditor,
 
     search_query: String,
     search_error: String,
@@ -372,6 +735,8 @@ struct NHContext {
 
 impl NHContext {
     const DEFAULT_TOOL_ITEM_HEIGHT: u32 = 60;
+    const DEFAULT_SNAP_TOLERANCE: f32 = 10.0;
+    const MAX_SNAP_TOLERANCE: f32 = 50.0;
 
     fn default_shades_profiles() -> Vec<ShadesProfile> {
         vec![
@@ -485,6 +850,14 @@ impl NHContext {
             DiagramCommand::PanCamera(-1, 0).into(),
             egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::ArrowLeft),
         );
+        shortcuts.insert(
+            DiagramCommand::AddChildElement.into(),
+            egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Tab),
+        );
+        shortcuts.insert(
+            DiagramCommand::AddSiblingElement.into(),
+            egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Enter),
+        );
         shortcuts.insert(
             DiagramCommand::PanCamera(1, 0).into(),
             egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::ArrowRight),
@@ -514,6 +887,15 @@ impl TabViewer for NHContext {
                 let c = self.diagram_controllers.get(uuid).unwrap().read();
                 (&*c.view_name(uuid)).into()
             }
+            NHTab::DiagramViewport { uuid, .. } => {
+                let c = self.diagram_controllers.get(uuid).unwrap().read();
+                format!(
+                    "{} ({})",
+                    c.view_name(uuid),
+                    self.drawing_context.translate_0("nh-tab-diagramviewport")
+                )
+                .into()
+            }
             NHTab::Document { uuid } => self.documents.get(uuid).unwrap().0.clone().into(),
             NHTab::CustomTab { uuid } => self
                 .custom_tabs
@@ -544,20 +926,48 @@ impl TabViewer for NHContext {
             NHTab::Toolbar => self.show_toolbar(ui),
             NHTab::Properties => self.show_properties(ui),
             NHTab::GlobalColors => self.show_global_colors(ui),
+            NHTab::Symbols => self.symbol_library_editor.show(
+                &mut self.drawing_context.symbols,
+                ui,
+                &mut self.unprocessed_commands,
+            ),
+            NHTab::Abbreviations => self.show_abbreviations(ui),
+            NHTab::Tasks => self.show_tasks(ui),
+            NHTab::ExternalLinks => self.show_external_links(ui),
+            NHTab::IssueLinks => self.show_issue_links(ui),
+            NHTab::DrawOrder => self.show_draw_order(ui),
+            NHTab::Layers => self.show_layers(ui),
             NHTab::Outline => self.show_outline(ui),
+            NHTab::DocumentOutline => self.show_document_outline(ui),
 
             NHTab::Diagram { uuid } => self.show_diagram_tab(uuid, ui),
+            NHTab::DiagramViewport { uuid, viewport } => {
+                self.show_diagram_viewport_tab(uuid, viewport, ui)
+            }
             NHTab::Document { uuid } => self.show_document_tab(uuid, ui),
             NHTab::CustomTab { uuid } => self.show_custom_tab(uuid, ui),
         }
     }
 
+    fn on_tab_button(&mut self, tab: &mut Self::Tab, response: &egui::Response) {
+        // Long documents show the heading the cursor is under
+        if let NHTab::Document { uuid } = tab
+            && response.hovered()
+            && let Some(heading) = self.current_document_heading(uuid, &response.ctx)
+        {
+            response.clone().on_hover_text(heading);
+        }
+    }
+
     fn context_menu(&mut self, ui: &mut egui::Ui, _tab: &mut Self::Tab, _node: NodePath) {
         ui.label("This is a tab context menu");
     }
 
     fn on_close(&mut self, tab: &mut Self::Tab) -> OnCloseResponse {
         self.open_unique_tabs.remove(tab);
+        if let NHTab::DiagramViewport { viewport, .. } = tab {
+            self.diagram_viewports.remove(viewport);
+        }
         OnCloseResponse::Close
     }
 }
@@ -576,6 +986,12 @@ fn add_project_element_block(
             "New Document".to_owned(),
         ));
     }
+    if ui
+        .button(gdc.translate_0("nh-project-importdocuments"))
+        .clicked()
+    {
+        commands.push(ProjectCommand::ImportDocuments);
+    }
 
     if ui
         .button(gdc.translate_0("nh-project-addnewdiagram"))
@@ -680,15 +1096,29 @@ impl NHContext {
                 .into());
         };
 
+        for e in self.diagram_controllers.values() {
+            e.write().prune_view_state();
+        }
         common::project_serde::NHProjectSerialization::write_to(
             wa,
-            project_name,
-            sources_folder_name,
-            self.new_diagram_no as usize,
-            children,
-            &self.drawing_context.global_colors,
-            &self.diagram_controllers,
-            &self.documents,
+            &common::project_serde::ProjectSaveState {
+                project_name,
+                sources_root: sources_folder_name,
+                new_diagram_no_counter: self.new_diagram_no as usize,
+                hierarchy: children,
+                global_colors: &self.drawing_context.global_colors,
+                symbols: &self.drawing_context.symbols,
+                images: &self.drawing_context.images,
+                abbreviations: &self.drawing_context.abbreviations,
+                todos: &self.todos,
+                external_links: &self.external_links,
+                issue_links: &self.issue_links,
+                diagram_controllers: &self.diagram_controllers,
+                diagram_variants: &self.diagram_variants,
+                diagram_templates: &self.diagram_templates,
+                sub_diagrams: &self.sub_diagrams,
+                documents: &self.documents,
+            },
         )
     }
     fn import_project(&mut self, fh: FileHandle) -> Result<(), NHDeserializeError> {
@@ -735,6 +1165,307 @@ impl NHContext {
             _otherwise => Err(supported_extensions!(project_file_name).into()),
         }
     }
+    /// Loads a project synchronously, for use outside of the UI
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_project_blocking(&mut self, path: &std::path::Path) -> Result<(), String> {
+        let extension = path.extension().and_then(|e| e.to_str());
+        let result = match extension {
+            Some("nhp") => {
+                let containing_folder = path
+                    .parent()
+                    .ok_or_else(|| format!("Path {:?} does not have a valid parent", path))?;
+                let file_name = path
+                    .file_name()
+                    .ok_or_else(|| supported_extensions!(path))?;
+                let mut rr =
+                    FSRawReader::new(containing_folder.to_path_buf(), file_name.to_os_string())
+                        .map_err(|e| e.to_string())?;
+                self.import_project_nhp(&mut rr)
+            }
+            Some("nhpz") => {
+                let bytes = std::fs::read(path).map_err(|e| format!("{:?}: {}", path, e))?;
+                let mut zfsr =
+                    ZipFSReader::new(bytes, "project.nhp", "project").map_err(|e| e.to_string())?;
+                self.import_project_nhp(&mut zfsr)
+            }
+            _ => return Err(supported_extensions!(path)),
+        };
+        result.map_err(|e| format!("Error opening {:?}: {:?}", path, e))?;
+        self.set_project_path(Some(path.to_path_buf()));
+        Ok(())
+    }
+    /// Area covered by the whole diagram
+    fn diagram_bounds(
+        &self,
+        painter: &egui::Painter,
+        v: &ViewUuid,
+        controller: &mut dyn DiagramController,
+        s: &dyn DiagramSettings,
+    ) -> egui::Rect {
+        // Views update their sizes while being drawn and connections follow them one pass later,
+        // so the diagram is laid out before being measured
+        for _ in 0..2 {
+            controller.draw_in(
+                v,
+                &self.drawing_context,
+                s,
+                &mut MeasuringCanvas::new(painter),
+                None,
+            );
+        }
+        let mut measuring_canvas = MeasuringCanvas::new(painter);
+        controller.draw_in(v, &self.drawing_context, s, &mut measuring_canvas, None);
+        measuring_canvas.bounds()
+    }
+    /// Whole diagram as an SVG image, along with the offset of diagram coordinates in it
+    fn diagram_svg(
+        &self,
+        ctx: &egui::Context,
+        v: &ViewUuid,
+        controller: &mut dyn DiagramController,
+    ) -> Option<(String, egui::Vec2)> {
+        const PADDING: f32 = 10.0;
+
+        let s = self.diagram_settings.get(controller.controller_type())?;
+        let painter = egui::Painter::new(
+            ctx.clone(),
+            egui::LayerId::background(),
+            egui::Rect::EVERYTHING,
+        );
+        let bounds = self.diagram_bounds(&painter, v, controller, s.as_ref());
+        if !bounds.is_positive() {
+            return None;
+        }
+        let offset = -1.0 * bounds.min + egui::Vec2::splat(PADDING);
+        let mut svg_canvas = SVGCanvas::new(
+            offset,
+            bounds.size() + egui::Vec2::splat(2.0 * PADDING),
+            Highlight::NONE,
+            &painter,
+        );
+        controller.draw_in(v, &self.drawing_context, s.as_ref(), &mut svg_canvas, None);
+        let svg = String::from_utf8(svg_canvas.into_bytes().ok()?).ok()?;
+        Some((svg, offset.to_vec2()))
+    }
+    /// Whole diagram as a standalone HTML page with clickable elements
+    fn diagram_html(
+        &self,
+        ctx: &egui::Context,
+        v: &ViewUuid,
+        controller: &mut dyn DiagramController,
+    ) -> Option<String> {
+        let (svg, offset) = self.diagram_svg(ctx, v, controller)?;
+        let layout = controller.layout(v);
+        let elements: Vec<_> = controller
+            .element_descriptions()
+            .into_iter()
+            .flat_map(|e| {
+                let bounds = layout.elements.get(&e.model)?.bounds;
+                let name = self.drawing_context.model_labels.get(&e.model);
+                if name.is_empty() {
+                    return None;
+                }
+                Some(common::html_export::HtmlElement {
+                    name: (*name).clone(),
+                    stereotype: (*e.stereotype).clone(),
+                    comment: (*e.comment).clone(),
+                    bounds: bounds.translate(offset),
+                })
+            })
+            .collect();
+        Some(common::html_export::interactive_html(
+            &controller.view_name(v),
+            &svg,
+            &elements,
+        ))
+    }
+    /// Refreshes an SVG image of every diagram in the backup folder, if enabled
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg_backups(&self, ctx: &egui::Context) -> Result<(), String> {
+        let Some(project_folder) = self.project_folder().filter(|_| self.svg_backup_enabled) else {
+            return Ok(());
+        };
+        let folder = project_folder.join(&self.svg_backup_folder);
+        std::fs::create_dir_all(&folder).map_err(|e| format!("{:?}: {}", folder, e))?;
+
+        let mut views: Vec<_> = self
+            .diagram_controllers
+            .iter()
+            .map(|(k, v)| (*k, v.clone(), v.read().view_name(k)))
+            .collect();
+        views.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(&b.0)));
+        let mut file_names = HashSet::new();
+        for (v, c, name) in views {
+            let Some((svg, _)) = self.diagram_svg(ctx, &v, &mut *c.write()) else {
+                continue;
+            };
+            let mut file_name = sanitize_file_name(&name);
+            if !file_names.insert(file_name.clone()) {
+                file_name = format!("{} ({})", file_name, v.to_string());
+            }
+            let path = folder.join(format!("{}.svg", file_name));
+            std::fs::write(&path, svg).map_err(|e| format!("{:?}: {}", path, e))?;
+        }
+        Ok(())
+    }
+    /// Markdown documentation of the whole project, with a section for each folder,
+    /// an image and a table of elements for each diagram and contents of documents
+    fn documentation_report(&self, ctx: &egui::Context) -> String {
+        fn section(
+            context: &NHContext,
+            ctx: &egui::Context,
+            report: &mut common::report::MarkdownReport,
+            node: &HierarchyNode,
+            level: usize,
+        ) {
+            let gdc = &context.drawing_context;
+            match node {
+                HierarchyNode::Folder(_, name, children) => {
+                    report.heading(level, name);
+                    for e in children {
+                        section(context, ctx, report, e, level + 1);
+                    }
+                }
+                HierarchyNode::Diagram(v, c) => {
+                    let mut c = c.write();
+                    report.heading(level, &c.view_name(v));
+                    if let Some((svg, _)) = context.diagram_svg(ctx, v, &mut *c) {
+                        report.svg(&svg);
+                    }
+                    let rows: Vec<_> = c
+                        .element_descriptions()
+                        .into_iter()
+                        .map(|e| (gdc.model_labels.get(&e.model), e))
+                        .filter(|(label, _)| !label.is_empty())
+                        .map(|(label, e)| {
+                            vec![
+                                (*label).clone(),
+                                (*e.stereotype).clone(),
+                                (*e.comment).clone(),
+                            ]
+                        })
+                        .collect();
+                    if !rows.is_empty() {
+                        report.table(
+                            &[
+                                &gdc.translate_0("nh-project-generatedocumentation-name"),
+                                &gdc.translate_0("nh-project-generatedocumentation-stereotype"),
+                                &gdc.translate_0("nh-project-generatedocumentation-comment"),
+                            ],
+                            &rows,
+                        );
+                    }
+                }
+                HierarchyNode::Document(v) => {
+                    if let Some((name, content)) = context.documents.get(v) {
+                        // The name is the first line of the document
+                        report.heading(level, name.trim_start_matches('#'));
+                        let rest = content.split_once('\n').map_or("", |e| e.1);
+                        report.text(rest, level);
+                    }
+                }
+            }
+        }
+
+        let mut report = common::report::MarkdownReport::default();
+        section(self, ctx, &mut report, &self.project_hierarchy, 1);
+        report.finish()
+    }
+    /// Exports a whole diagram as the named image or text format, returning the file extension
+    /// and contents
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_diagram_headless(
+        &self,
+        ctx: &egui::Context,
+        v: &ViewUuid,
+        controller: &mut dyn DiagramController,
+        format: &str,
+    ) -> Result<(&'static str, Vec<u8>), String> {
+        const PADDING: f32 = 10.0;
+
+        if let Some(f) = controller
+            .text_export_formats()
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(format) || e.extensions.contains(&format))
+        {
+            let languages = match &self.export_language {
+                Some(l) => vec![l.clone()],
+                None => self.drawing_context.languages_order.clone(),
+            };
+            let text = controller.export_text(f, &languages).ok_or_else(|| {
+                format!("Could not export {} as {}", controller.view_name(v), f.name)
+            })?;
+            return Ok((
+                f.extensions.first().copied().unwrap_or("txt"),
+                text.into_bytes(),
+            ));
+        }
+
+        let Some(f) = ImageExportFormat::ALL
+            .into_iter()
+            .find(|e| e.name().eq_ignore_ascii_case(format) || e.extensions().contains(&format))
+        else {
+            return Err(format!(
+                "Unknown format {:?}, expected one of {}",
+                format,
+                ImageExportFormat::ALL
+                    .iter()
+                    .map(|e| e.extensions()[0])
+                    .chain(
+                        controller
+                            .text_export_formats()
+                            .iter()
+                            .flat_map(|e| e.extensions.first().copied())
+                    )
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        };
+        let s = self
+            .diagram_settings
+            .get(controller.controller_type())
+            .ok_or_else(|| format!("Unknown diagram type {}", controller.controller_type()))?;
+
+        let painter = egui::Painter::new(
+            ctx.clone(),
+            egui::LayerId::background(),
+            egui::Rect::EVERYTHING,
+        );
+        let bounds = self.diagram_bounds(&painter, v, controller, s.as_ref());
+        let canvas_offset = -1.0 * bounds.min + egui::Vec2::splat(PADDING);
+        let canvas_size = bounds.size() + egui::Vec2::splat(2.0 * PADDING);
+
+        let bytes = if f.is_raster() {
+            let image = raster::render_to_image(
+                ctx,
+                [canvas_size.x.ceil() as usize, canvas_size.y.ceil() as usize],
+                canvas_offset,
+                1.0,
+                Highlight::NONE,
+                |canvas| {
+                    controller.draw_in(v, &self.drawing_context, s.as_ref(), canvas, None);
+                },
+            );
+            let (background_color, _) = controller.canvas_colors(v, &self.drawing_context);
+            raster::encode(&image, f, background_color).map_err(|e| e.to_string())?
+        } else if f == ImageExportFormat::DrawIo {
+            let mut drawio_canvas = DrawioCanvas::new(canvas_offset, Highlight::NONE, &painter);
+            controller.draw_in(
+                v,
+                &self.drawing_context,
+                s.as_ref(),
+                &mut drawio_canvas,
+                None,
+            );
+            drawio_canvas.into_bytes(&controller.view_name(v))
+        } else {
+            let mut svg_canvas =
+                SVGCanvas::new(canvas_offset, canvas_size, Highlight::NONE, &painter);
+            controller.draw_in(v, &self.drawing_context, s.as_ref(), &mut svg_canvas, None);
+            svg_canvas.into_bytes().map_err(|e| format!("{:?}", e))?
+        };
+        Ok((f.extensions()[0], bytes))
+    }
     fn import_project_nhp(
         &mut self,
         ra: &mut dyn FSReadAbstraction,
@@ -744,6 +1475,7 @@ impl NHContext {
         let pdto: common::project_serde::NHProjectSerialization = toml::from_str(project_file_str)?;
         let (hierarchy, top_level_views, documents) =
             pdto.deserialize_all(ra, &self.diagram_deserializers)?;
+        let images = pdto.images(ra)?;
 
         // All good, clear and set fields
         self.clear_project_data();
@@ -763,6 +1495,17 @@ impl NHContext {
         self.diagram_controllers = top_level_views;
         self.documents = documents;
         self.drawing_context.global_colors = pdto.global_colors();
+        self.drawing_context.symbols = pdto.symbols();
+        self.drawing_context.images = images;
+        self.drawing_context.abbreviations = pdto.abbreviations();
+        self.todos = pdto.todos();
+        self.external_links = pdto.external_links();
+        self.external_links_checked_at = f64::NEG_INFINITY;
+        self.issue_links = pdto.issue_links();
+        self.refresh_issue_linked_models();
+        self.diagram_variants = pdto.diagram_variants();
+        self.diagram_templates = pdto.diagram_templates();
+        self.sub_diagrams = pdto.sub_diagrams();
 
         Ok(())
     }
@@ -773,18 +1516,169 @@ impl NHContext {
             HierarchyNode::Folder(ViewUuid::nil(), "New Project".to_owned().into(), vec![]);
         self.new_diagram_no = 1;
         self.documents.clear();
+        self.diagram_variants.clear();
+        self.diagram_templates.clear();
+        self.sub_diagrams.clear();
         self.custom_tabs.clear();
         self.drawing_context.global_colors.clear();
+        self.drawing_context.symbols.clear();
+        self.drawing_context.images.clear();
+        self.drawing_context.abbreviations.clear();
+        self.todos.clear();
+        self.external_links.clear();
+        self.external_files_modified.clear();
+        self.drawing_context.outdated_models.clear();
+        self.issue_links.clear();
+        self.drawing_context.issue_linked_models.clear();
 
         self.unprocessed_commands.clear();
         self.should_change_title = true;
         self.has_unsaved_changes = false;
 
         self.last_focused_diagram = None;
+        self.last_focused_document = None;
+        self.document_scroll_request = None;
         self.svg_export_menu = None;
         self.confirm_modal_reason = None;
     }
 
+    fn export_settings_profile(&self, dark_mode: bool) -> Result<String, String> {
+        let profile = NHSettingsProfile {
+            zoom_factor: Some(self.zoom_factor),
+            dark_mode: Some(dark_mode),
+            style: self.style.clone(),
+            tool_palette_item_height: Some(self.drawing_context.tool_palette_item_height),
+            animate_transitions: Some(self.drawing_context.animate_transitions),
+            snap_tolerance: Some(self.drawing_context.snap_tolerance),
+            element_defaults: Some(self.drawing_context.element_defaults.clone()),
+            svg_backup_enabled: Some(self.svg_backup_enabled),
+            svg_backup_folder: Some(self.svg_backup_folder.clone()),
+            modifier_settings: Some(self.modifier_settings),
+            shortcuts: Some(
+                self.drawing_context
+                    .shortcuts
+                    .iter()
+                    .map(|(k, v)| (*k, *v))
+                    .collect(),
+            ),
+            languages_order: Some(
+                self.drawing_context
+                    .languages_order
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect(),
+            ),
+            selected_shades_profile: Some(self.selected_shades_profile),
+            shades_profiles: Some(self.shades_profiles.clone()),
+            macros: Some(self.macro_recorder.macros.clone()),
+            default_constructors: Some(self.default_constructors.clone()),
+            diagram_specific_settings: Some(
+                self.diagram_settings
+                    .iter()
+                    .flat_map(|(k, v)| Some(((*k).to_owned(), v.serialize().ok()?)))
+                    .collect(),
+            ),
+        };
+        serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())
+    }
+
+    fn import_settings_profile(&mut self, ctx: &egui::Context, bytes: &[u8]) -> Result<(), String> {
+        let profile: NHSettingsProfile =
+            serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+
+        if let Some(zoom_factor) = profile.zoom_factor {
+            self.zoom_factor = zoom_factor;
+        }
+        if let Some(dark_mode) = profile.dark_mode {
+            if dark_mode {
+                ctx.set_theme(egui::Theme::Dark);
+                ctx.set_visuals(egui::Visuals::dark());
+            } else {
+                ctx.set_theme(egui::Theme::Light);
+                ctx.set_visuals(egui::Visuals::light());
+            }
+            self.style = Some(Style::from_egui(&ctx.global_style()));
+        }
+        if let Some(style) = profile.style {
+            self.style = Some(style);
+        }
+        if let Some(height) = profile.tool_palette_item_height {
+            self.drawing_context.tool_palette_item_height =
+                height.clamp(TOOL_PALETTE_MIN_HEIGHT, TOOL_PALETTE_MAX_HEIGHT);
+        }
+        if let Some(animate_transitions) = profile.animate_transitions {
+            self.drawing_context.animate_transitions = animate_transitions;
+        }
+        if let Some(snap_tolerance) = profile.snap_tolerance {
+            self.drawing_context.snap_tolerance =
+                snap_tolerance.clamp(0.0, Self::MAX_SNAP_TOLERANCE);
+        }
+        if let Some(element_defaults) = profile.element_defaults {
+            self.drawing_context.element_defaults = element_defaults;
+        }
+        if let Some(svg_backup_enabled) = profile.svg_backup_enabled {
+            self.svg_backup_enabled = svg_backup_enabled;
+        }
+        if let Some(svg_backup_folder) = profile.svg_backup_folder {
+            self.svg_backup_folder = svg_backup_folder;
+        }
+        if let Some(mut modifier_settings) = profile.modifier_settings {
+            modifier_settings.sort_delete_kinds();
+            self.modifier_settings = modifier_settings;
+        }
+        if let Some(shortcuts) = profile.shortcuts {
+            self.drawing_context.shortcuts = shortcuts.into_iter().collect();
+            self.sort_shortcuts();
+        }
+        if let Some(languages) = profile.languages_order {
+            // Unknown and repeated languages are dropped, missing ones are appended in their order
+            let mut languages_order: Vec<unic_langid::LanguageIdentifier> = Vec::new();
+            for l in languages.iter().flat_map(|e| e.parse().ok()) {
+                if common::fluent::AVAILABLE_LANGUAGES.iter().any(|e| e.0 == l)
+                    && !languages_order.contains(&l)
+                {
+                    languages_order.push(l);
+                }
+            }
+            for (l, _) in common::fluent::AVAILABLE_LANGUAGES {
+                if !languages_order.contains(l) {
+                    languages_order.push(l.clone());
+                }
+            }
+            self.drawing_context.fluent_bundle =
+                common::fluent::create_fluent_bundle(&languages_order)?;
+            self.drawing_context.languages_order = languages_order;
+            self.selected_language = 0;
+        }
+        if let Some(shades_profiles) = profile.shades_profiles
+            && !shades_profiles.is_empty()
+        {
+            self.shades_profiles = shades_profiles;
+            self.selected_shades_profile = 0;
+        }
+        if let Some(selected) = profile.selected_shades_profile {
+            self.selected_shades_profile = selected.min(self.shades_profiles.len() - 1);
+        }
+        if let Some(macros) = profile.macros {
+            self.macro_recorder.macros = macros;
+        }
+        if let Some(default_constructors) = profile.default_constructors {
+            self.default_constructors = default_constructors;
+        }
+        if let Some(diagram_specific_settings) = profile.diagram_specific_settings {
+            for info in inventory::iter::<DiagramInfo> {
+                if let Some(value) = diagram_specific_settings.get(info.type_indentifier)
+                    && let Ok(settings) = (info.settings_deserializer)(value.clone())
+                {
+                    self.diagram_settings
+                        .insert(info.type_indentifier, settings);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn sort_shortcuts(&mut self) {
         self.shortcut_top_order = self
             .drawing_context
@@ -850,11 +1744,18 @@ impl NHContext {
             }
         });
 
+        /// Relations of diagrams to other diagrams of the project
+        struct DiagramRoles {
+            origins: HashMap<ViewUuid, (ViewUuid, ERef<dyn DiagramController>)>,
+            templates: HashSet<ViewUuid>,
+        }
+
         fn hierarchy(
             builder: &mut egui_ltreeview::TreeViewBuilder<ViewUuid>,
             gdc: &GlobalDrawingContext,
             hn: &HierarchyNode,
             docs: &HashMap<ViewUuid, (String, String)>,
+            roles: &DiagramRoles,
             cma: &mut Option<ContextMenuAction>,
             commands: &mut Vec<ProjectCommand>,
         ) {
@@ -914,73 +1815,130 @@ impl NHContext {
                     }));
 
                     for c in children {
-                        hierarchy(builder, gdc, c, docs, cma, commands);
+                        hierarchy(builder, gdc, c, docs, roles, cma, commands);
                     }
 
                     builder.close_dir();
                 }
                 HierarchyNode::Diagram(uuid, c) => {
                     let view_name = c.read().view_name(uuid);
-                    builder.node(
-                        NodeBuilder::leaf(*uuid)
-                            .label(&*view_name)
-                            .context_menu(|ui| {
-                                ui.set_min_width(MIN_MENU_WIDTH);
+                    let origin = roles.origins.get(uuid);
+                    let label = match origin {
+                        None => (*view_name).clone(),
+                        Some((origin_uuid, origin_c)) => {
+                            let origin_name = origin_c.read().view_name(origin_uuid);
+                            let mut args = fluent_bundle::FluentArgs::new();
+                            args.set("name", &*view_name);
+                            args.set("origin", &*origin_name);
+                            gdc.fluent_bundle
+                                .format_pattern(
+                                    gdc.fluent_bundle
+                                        .get_message("nh-tab-projecthierarchy-variantof")
+                                        .unwrap()
+                                        .value()
+                                        .unwrap(),
+                                    Some(&args),
+                                    &mut vec![],
+                                )
+                                .into_owned()
+                        }
+                    };
+                    builder.node(NodeBuilder::leaf(*uuid).label(&label).context_menu(|ui| {
+                        ui.set_min_width(MIN_MENU_WIDTH);
 
-                                if ui
-                                    .button(gdc.translate_0("nh-tab-projecthierarchy-open"))
-                                    .clicked()
-                                {
-                                    commands.push(ProjectCommand::OpenAndFocusTab(
-                                        NHTab::Diagram { uuid: *uuid },
-                                        None,
-                                    ));
-                                    ui.close();
-                                }
-                                ui.separator();
-                                if ui
-                                    .button(gdc.translate_0("nh-tab-projecthierarchy-newfolder"))
-                                    .clicked()
-                                {
-                                    *cma = Some(ContextMenuAction::NewFolder(ViewUuid::nil()));
-                                    ui.close();
-                                }
+                        if ui
+                            .button(gdc.translate_0("nh-tab-projecthierarchy-open"))
+                            .clicked()
+                        {
+                            commands.push(ProjectCommand::OpenAndFocusTab(
+                                NHTab::Diagram { uuid: *uuid },
+                                None,
+                            ));
+                            ui.close();
+                        }
+                        ui.separator();
+                        if ui
+                            .button(gdc.translate_0("nh-tab-projecthierarchy-newfolder"))
+                            .clicked()
+                        {
+                            *cma = Some(ContextMenuAction::NewFolder(ViewUuid::nil()));
+                            ui.close();
+                        }
 
-                                add_project_element_block(gdc, ui, commands);
+                        add_project_element_block(gdc, ui, commands);
 
-                                if let Some((new_uuid, new_c)) =
-                                    c.write().show_duplication_menu(gdc, ui, uuid)
-                                {
-                                    let new_c = new_c.unwrap_or_else(|| c.clone());
-                                    commands.push(ProjectCommand::AddNewDiagram(
-                                        ViewUuid::nil(),
-                                        new_uuid,
-                                        new_c,
-                                    ));
-                                    commands.push(ProjectCommand::OpenAndFocusTab(
-                                        NHTab::Diagram { uuid: new_uuid },
-                                        None,
-                                    ));
-                                }
+                        if let Some((new_uuid, new_c)) =
+                            c.write().show_duplication_menu(gdc, ui, uuid)
+                        {
+                            let new_c = new_c.unwrap_or_else(|| c.clone());
+                            commands.push(ProjectCommand::AddNewDiagram(
+                                ViewUuid::nil(),
+                                new_uuid,
+                                new_c,
+                            ));
+                            commands.push(ProjectCommand::OpenAndFocusTab(
+                                NHTab::Diagram { uuid: new_uuid },
+                                None,
+                            ));
+                        }
+                        if ui
+                            .button(gdc.translate_0("nh-tab-projecthierarchy-duplicatevariant"))
+                            .clicked()
+                        {
+                            let (new_uuid, new_c) = c.read().duplicate_view(uuid);
+                            commands.push(ProjectCommand::AddNewDiagram(
+                                ViewUuid::nil(),
+                                new_uuid,
+                                new_c,
+                            ));
+                            commands.push(ProjectCommand::MarkDiagramVariant(new_uuid, *uuid));
+                            commands.push(ProjectCommand::OpenAndFocusTab(
+                                NHTab::Diagram { uuid: new_uuid },
+                                None,
+                            ));
+                            ui.close();
+                        }
+                        let is_template = roles.templates.contains(uuid);
+                        if ui
+                            .selectable_label(
+                                is_template,
+                                gdc.translate_0("nh-tab-projecthierarchy-usetemplate"),
+                            )
+                            .clicked()
+                        {
+                            commands.push(ProjectCommand::SetDiagramTemplate(*uuid, !is_template));
+                            ui.close();
+                        }
+                        if let Some(origin) = origin
+                            && ui
+                                .button(gdc.translate_0("nh-tab-projecthierarchy-diffvariant"))
+                                .clicked()
+                        {
+                            let tab = VariantDiffTab::new(origin.clone(), (*uuid, c.clone()));
+                            commands.push(ProjectCommand::AddCustomTab(
+                                uuid::Uuid::now_v7(),
+                                Arc::new(RwLock::new(tab)),
+                            ));
+                            ui.close();
+                        }
 
-                                ui.separator();
-                                if ui
-                                    .button(gdc.translate_0("nh-tab-projecthierarchy-rename"))
-                                    .clicked()
-                                {
-                                    *cma = Some(ContextMenuAction::RenameElement(*uuid));
-                                    ui.close();
-                                }
-                                ui.separator();
-                                if ui
-                                    .button(gdc.translate_0("nh-tab-projecthierarchy-delete"))
-                                    .clicked()
-                                {
-                                    commands.push(ProjectCommand::DeleteDiagram(*uuid));
-                                    ui.close();
-                                }
-                            }),
-                    );
+                        ui.separator();
+                        if ui
+                            .button(gdc.translate_0("nh-tab-projecthierarchy-rename"))
+                            .clicked()
+                        {
+                            *cma = Some(ContextMenuAction::RenameElement(*uuid));
+                            ui.close();
+                        }
+                        ui.separator();
+                        if ui
+                            .button(gdc.translate_0("nh-tab-projecthierarchy-delete"))
+                            .clicked()
+                        {
+                            commands.push(ProjectCommand::DeleteDiagram(*uuid));
+                            ui.close();
+                        }
+                    }));
                 }
                 HierarchyNode::Document(uuid) => {
                     builder.node(
@@ -1040,6 +1998,19 @@ impl NHContext {
         }
 
         let mut commands = Vec::new();
+        let origins = self
+            .diagram_variants
+            .iter()
+            .flat_map(|(k, v)| {
+                self.diagram_controllers
+                    .get(v)
+                    .map(|c| (*k, (*v, c.clone())))
+            })
+            .collect();
+        let roles = DiagramRoles {
+            origins,
+            templates: self.diagram_templates.values().copied().collect(),
+        };
 
         egui::ScrollArea::vertical()
             .auto_shrink(false)
@@ -1052,6 +2023,7 @@ impl NHContext {
                             &self.drawing_context,
                             &self.project_hierarchy,
                             &self.documents,
+                            &roles,
                             &mut context_menu_action,
                             &mut commands,
                         );
@@ -1424,14 +2396,19 @@ impl NHContext {
             return;
         };
 
+        let mut property_changes = Vec::new();
         if let Some(m) = c.write().show_properties(
             last_focused_diagram,
             &self.drawing_context,
             ui,
             &mut self.affected_models,
+            &mut property_changes,
         ) {
             self.custom_modal = Some(m);
         }
+        for e in property_changes {
+            self.macro_recorder.record(MacroStep::PropertyChange(e));
+        }
     }
 
     fn show_global_colors(&mut self, ui: &mut egui::Ui) {
@@ -1441,6 +2418,22 @@ impl NHContext {
             };
         }
         let mut color_to_remove = None;
+        let mut color_to_move = None;
+        let mut colors_to_merge = None;
+        let colors_count = gc!().colors_order.len();
+        let names: Vec<_> = gc!()
+            .colors_order
+            .iter()
+            .flat_map(|k| gc!().colors.get(k).map(|e| (*k, e.0.clone(), e.1)))
+            .collect();
+        let merge_label = self
+            .drawing_context
+            .translate_0("nh-tab-globalcolors-mergeinto")
+            .into_owned();
+        let merge_tooltip = self
+            .drawing_context
+            .translate_0("nh-tab-globalcolors-mergeinto-tooltip")
+            .into_owned();
         for (idx, id) in gc!().colors_order.iter().enumerate() {
             ui.horizontal(|ui| {
                 if let Some(c) = gc!().colors.get_mut(id) {
@@ -1452,6 +2445,32 @@ impl NHContext {
 
                     ui.text_edit_singleline(&mut c.0);
 
+                    if ui.add_enabled(idx > 0, egui::Button::new("⏶")).clicked() {
+                        color_to_move = Some((idx, idx - 1));
+                    }
+                    if ui
+                        .add_enabled(idx + 1 < colors_count, egui::Button::new("⏷"))
+                        .clicked()
+                    {
+                        color_to_move = Some((idx, idx + 1));
+                    }
+                    ui.menu_button(&merge_label, |ui| {
+                        for (k, name, color) in names.iter().filter(|e| e.0 != *id) {
+                            ui.horizontal(|ui| {
+                                egui::widgets::color_picker::show_color(
+                                    ui,
+                                    *color,
+                                    egui::Vec2::new(20.0, 12.0),
+                                );
+                                if ui.button(name).clicked() {
+                                    colors_to_merge = Some((*id, *k));
+                                }
+                            });
+                        }
+                    })
+                    .response
+                    .on_hover_text(&merge_tooltip);
+
                     if ui.button("X").clicked() {
                         color_to_remove = Some(idx);
                     }
@@ -1462,6 +2481,14 @@ impl NHContext {
             let id = gc!().colors_order.remove(idx);
             gc!().colors.remove(&id);
         }
+        if let Some((from, to)) = color_to_move {
+            gc!().move_color(from, to);
+            self.set_has_unsaved_changes(true);
+        }
+        if let Some((from, into)) = colors_to_merge {
+            gc!().merge(from, into);
+            self.set_has_unsaved_changes(true);
+        }
 
         ui.horizontal(|ui| {
             let r = ui.text_edit_singleline(&mut self.new_global_color_name);
@@ -1487,136 +2514,966 @@ impl NHContext {
         });
     }
 
-    fn show_outline(&mut self, ui: &mut egui::Ui) {
-        let Some(last_focused_diagram) = &self.last_focused_diagram else {
-            return;
-        };
-        let Some(c) = self.diagram_controllers.get(last_focused_diagram) else {
-            return;
-        };
-        let mut c = c.write();
-        let ctype = c.controller_type();
-        let Some(s) = self.diagram_settings.get(ctype) else {
-            return;
-        };
-        let outline_rect = ui.content_rect();
-
-        c.show_outline(last_focused_diagram, &self.drawing_context, s.as_ref(), ui);
+    fn show_abbreviations(&mut self, ui: &mut egui::Ui) {
+        let gdc = &mut self.drawing_context;
+        let mut entry_to_remove = None;
+        egui::Grid::new("abbreviations").show(ui, |ui| {
+            for (idx, e) in gdc.abbreviations.entries.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut e.abbreviation).desired_width(80.0));
+                ui.text_edit_singleline(&mut e.expansion);
+                if ui.button("X").clicked() {
+                    entry_to_remove = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = entry_to_remove {
+            gdc.abbreviations.entries.remove(idx);
+        }
+        if ui
+            .button(gdc.translate_0("nh-tab-abbreviations-addnew"))
+            .clicked()
+        {
+            gdc.abbreviations.entries.push(AbbreviationEntry::default());
+        }
 
-        let shade_color = self.shades_profiles[self.selected_shades_profile].get(ctype);
-        ui.painter().rect(
-            outline_rect,
-            egui::CornerRadius::ZERO,
-            shade_color,
-            egui::Stroke::NONE,
-            egui::StrokeKind::Middle,
+        ui.separator();
+        let require_defined_label = gdc
+            .translate_0("nh-tab-abbreviations-requiredefined")
+            .into_owned();
+        ui.checkbox(
+            &mut gdc.abbreviations.require_defined,
+            require_defined_label,
         );
+        if gdc.abbreviations.require_defined {
+            let mut problems: Vec<_> = gdc
+                .model_labels
+                .iter()
+                .flat_map(|(_, label)| {
+                    let undefined = gdc.abbreviations.undefined_acronyms(label);
+                    (!undefined.is_empty()).then(|| format!("{}: {}", label, undefined.join(", ")))
+                })
+                .collect();
+            problems.sort();
+            if problems.is_empty() {
+                ui.label(gdc.translate_0("nh-tab-abbreviations-alldefined"));
+            }
+            for p in problems {
+                ui.colored_label(egui::Color32::ORANGE, p);
+            }
+        }
     }
 
-    fn show_newdiagram_tab(&mut self, ui: &mut egui::Ui) {
-        ui.heading(self.drawing_context.translate_0("nh-project-addnewdiagram"));
+    /// Diagram showing the element, preferring the last focused one
+    fn diagram_shows(&self, view_uuid: &ViewUuid, element: &ModelUuid) -> bool {
+        self.diagram_controllers
+            .get(view_uuid)
+            .and_then(|c| c.read().get(view_uuid))
+            .is_some_and(|v| {
+                let v = v.read();
+                *v.model_uuid() == *element || v.represented_models().contains_key(element)
+            })
+    }
 
-        ui.columns(2, |columns| {
-            let (_, actions) = egui_ltreeview::TreeView::new(
-                columns[0].make_persistent_id("new diagram modal hierarchy"),
-            )
-            .allow_drag_and_drop(false)
-            .allow_multi_selection(false)
-            .show(&mut columns[0], |builder| {
-                fn h<'a, 'ui>(
-                    e: &'a DiagramTypeHierarchyNode,
-                    builder: &mut egui_ltreeview::TreeViewBuilder<'ui, usize>,
-                ) {
-                    match e {
-                        DiagramTypeHierarchyNode::Folder(id, name, children) => {
-                            builder.dir(*id, name);
+    fn diagram_showing(&self, element: &ModelUuid) -> Option<ViewUuid> {
+        self.last_focused_diagram
+            .filter(|e| self.diagram_shows(e, element))
+            .or_else(|| {
+                self.diagram_controllers
+                    .keys()
+                    .find(|e| self.diagram_shows(e, element))
+                    .copied()
+            })
+    }
 
-                            for e in children {
-                                h(e, builder);
-                            }
+    /// Diagrams containing elements which drill down into the diagram, outermost first
+    fn sub_diagram_ancestors(&self, view_uuid: &ViewUuid) -> Vec<ViewUuid> {
+        let mut ancestors = Vec::new();
+        let mut current = *view_uuid;
+        while let Some(parent) = self
+            .sub_diagrams
+            .iter()
+            .filter(|e| *e.1 == current)
+            .find_map(|(element, _)| {
+                self.diagram_controllers
+                    .keys()
+                    .find(|e| **e != current && self.diagram_shows(e, element))
+            })
+            .filter(|e| *e != view_uuid && !ancestors.contains(*e))
+        {
+            ancestors.push(*parent);
+            current = *parent;
+        }
+        ancestors.reverse();
+        ancestors
+    }
 
-                            builder.close_dir();
-                        }
-                        DiagramTypeHierarchyNode::DiagramType(id, _dtype, dname) => {
-                            builder.leaf(*id, dname);
-                        }
+    fn show_breadcrumbs(&mut self, view_uuid: &ViewUuid, ui: &mut egui::Ui) {
+        let ancestors = self.sub_diagram_ancestors(view_uuid);
+        if ancestors.is_empty() {
+            return;
+        }
+        let name = |uuid: &ViewUuid| {
+            self.diagram_controllers
+                .get(uuid)
+                .map(|e| e.read().view_name(uuid))
+                .unwrap_or_default()
+        };
+        ui.horizontal(|ui| {
+            for e in &ancestors {
+                if ui.link(name(e).as_str()).clicked() {
+                    self.unprocessed_commands
+                        .push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::Diagram { uuid: *e },
+                            None,
+                        ));
+                }
+                ui.label("›");
+            }
+            ui.strong(name(view_uuid).as_str());
+        });
+    }
+
+    fn show_tasks(&mut self, ui: &mut egui::Ui) {
+        let gdc = &self.drawing_context;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.tasks_filter)
+                    .hint_text(gdc.translate_0("nh-tab-tasks-filter")),
+            );
+            egui::ComboBox::from_id_salt("tasks min priority")
+                .selected_text(gdc.translate_0(self.tasks_min_priority.message_name()))
+                .show_ui(ui, |ui| {
+                    for p in TodoPriority::ALL {
+                        ui.selectable_value(
+                            &mut self.tasks_min_priority,
+                            p,
+                            gdc.translate_0(p.message_name()),
+                        );
                     }
+                });
+        });
+        ui.separator();
+
+        let mut todo_to_remove = None;
+        let mut element_to_focus = None;
+        let mut modified = false;
+        egui::Grid::new("tasks").striped(true).show(ui, |ui| {
+            for (idx, todo) in self.todos.iter_mut().enumerate() {
+                let label = gdc.model_labels.get(&todo.element);
+                if !todo.matches(&label, &self.tasks_filter, self.tasks_min_priority) {
+                    continue;
                 }
-                let DiagramTypeHierarchyNode::Folder(.., children) = &self.diagram_type_hierarchy
-                else {
-                    return;
-                };
-                for e in children {
-                    h(e, builder);
+
+                egui::ComboBox::from_id_salt(("todo priority", idx))
+                    .selected_text(gdc.translate_0(todo.priority.message_name()))
+                    .show_ui(ui, |ui| {
+                        for p in TodoPriority::ALL {
+                            modified |= ui
+                                .selectable_value(
+                                    &mut todo.priority,
+                                    p,
+                                    gdc.translate_0(p.message_name()),
+                                )
+                                .changed();
+                        }
+                    });
+                if ui.link(&*label).clicked() {
+                    element_to_focus = Some(todo.element);
                 }
-            });
-            for e in actions {
-                if let egui_ltreeview::Action::SetSelected(items) = e {
-                    self.new_diagram_selected_kind = items.first().map_or(0, |e| *e);
+                modified |= ui.text_edit_singleline(&mut todo.text).changed();
+                if ui.button("X").clicked() {
+                    todo_to_remove = Some(idx);
                 }
+                ui.end_row();
             }
+        });
 
-            columns[1].vertical(|ui| {
-                if let Some((_dt, dd)) = self.new_diagram_data.get(&self.new_diagram_selected_kind)
-                {
-                    ui.label(dd.description);
-                    ui.separator();
+        if let Some(idx) = todo_to_remove {
+            self.todos.remove(idx);
+            modified = true;
+        }
+        if modified {
+            self.set_has_unsaved_changes(true);
+        }
+        if let Some(element) = element_to_focus {
+            self.focus_element(&element);
+        }
+    }
 
-                    for (idx, e) in dd.constructors.iter().enumerate() {
-                        ui.radio_value(&mut self.new_diagram_selected_constructor, idx, e.0);
-                    }
-                    ui.separator();
+    /// Lists top-level views of the last focused diagram from the front to the back
+    fn show_draw_order(&mut self, ui: &mut egui::Ui) {
+        let Some(diagram) = self.last_focused_diagram else {
+            return;
+        };
+        let Some(c) = self.diagram_controllers.get(&diagram) else {
+            return;
+        };
+        let draw_order = c.read().draw_order(&diagram);
+        let gdc = &self.drawing_context;
 
-                    ui.horizontal(|ui| {
-                        let enable = self.new_diagram_selected_constructor < dd.constructors.len();
-                        if ui
-                            .add_enabled(
-                                enable,
-                                egui::Button::new(
-                                    self.drawing_context.translate_0("nh-tab-newdiagram-open"),
-                                ),
-                            )
+        let mut commands = Vec::new();
+        for (idx, (view, model, lock)) in draw_order.iter().enumerate().rev() {
+            let row = ui.horizontal(|ui| {
+                let label = gdc.model_labels.get(model);
+                // The drag source only senses dragging, clicks select the view
+                let clicked = ui
+                    .dnd_drag_source(egui::Id::new(("draw order", view)), idx, |ui| {
+                        ui.add(egui::Label::new(format!("☰ {}", label)).sense(egui::Sense::click()))
                             .clicked()
-                        {
-                            let (uuid, c) = dd.constructors[self.new_diagram_selected_constructor]
-                                .1(self.new_diagram_no);
-                            self.unprocessed_commands
-                                .push(ProjectCommand::SetNewDiagramNumber(self.new_diagram_no + 1));
-                            self.unprocessed_commands
-                                .push(ProjectCommand::AddNewDiagram(ViewUuid::nil(), uuid, c));
-                            self.unprocessed_commands
-                                .push(ProjectCommand::OpenAndFocusTab(
-                                    NHTab::Diagram { uuid },
-                                    None,
-                                ));
-                        }
+                    })
+                    .inner;
+                if clicked {
+                    commands.push(DiagramCommand::HighlightAllElements(
+                        false,
+                        Highlight::SELECTED,
+                    ));
+                    commands.push(DiagramCommand::HighlightElement(
+                        (*view).into(),
+                        true,
+                        Highlight::SELECTED,
+                    ));
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    for (l, name) in [
+                        (ZOrderLock::Front, "nh-tab-draworder-lockfront"),
+                        (ZOrderLock::Back, "nh-tab-draworder-lockback"),
+                    ] {
                         if ui
-                            .add_enabled(
-                                enable,
-                                egui::Button::new(
-                                    self.drawing_context
-                                        .translate_0("nh-tab-newdiagram-background"),
-                                ),
-                            )
+                            .selectable_label(*lock == Some(l), gdc.translate_0(name))
                             .clicked()
                         {
-                            let (uuid, c) = dd.constructors[self.new_diagram_selected_constructor]
-                                .1(self.new_diagram_no);
-                            self.unprocessed_commands
-                                .push(ProjectCommand::SetNewDiagramNumber(self.new_diagram_no + 1));
-                            self.unprocessed_commands
-                                .push(ProjectCommand::AddNewDiagram(ViewUuid::nil(), uuid, c));
+                            commands.push(DiagramCommand::LockInDrawOrder(
+                                *view,
+                                (*lock != Some(l)).then_some(l),
+                            ));
                         }
-                    });
-                }
+                    }
+                });
             });
-        });
-    }
 
-    fn show_settings_tab(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Settings");
+            let response = row.response;
+            if let Some(dragged) = response.dnd_hover_payload::<usize>()
+                && *dragged != idx
+            {
+                let y = if *dragged < idx {
+                    response.rect.top()
+                } else {
+                    response.rect.bottom()
+                };
+                ui.painter()
+                    .hline(response.rect.x_range(), y, ui.visuals().selection.stroke);
+            }
+            if let Some(dragged) = response.dnd_release_payload::<usize>()
+                && let Some((dragged_view, ..)) = draw_order.get(*dragged)
+            {
+                commands.push(DiagramCommand::MoveInDrawOrder(*dragged_view, idx));
+            }
+        }
 
-        let visuals_response = egui::CollapsingHeader::new("Visuals")
+        if commands.iter().any(|e| {
+            !matches!(
+                e,
+                DiagramCommand::HighlightAllElements(..) | DiagramCommand::HighlightElement(..)
+            )
+        }) {
+            self.set_has_unsaved_changes(true);
+        }
+        self.unprocessed_commands.extend(
+            commands
+                .into_iter()
+                .map(|e| SimpleProjectCommand::SpecificDiagramCommand(diagram, e).into()),
+        );
+    }
+
+    /// Lists layers of the last focused diagram from the front to the back
+    fn show_layers(&mut self, ui: &mut egui::Ui) {
+        let Some(diagram) = self.last_focused_diagram else {
+            return;
+        };
+        let Some(c) = self.diagram_controllers.get(&diagram) else {
+            return;
+        };
+        let overview = c.read().layers(&diagram);
+        let gdc = &self.drawing_context;
+
+        let mut commands = Vec::new();
+        let mut project_commands = Vec::new();
+        let mut annotations_visible = overview.annotations_visible;
+        if ui
+            .checkbox(
+                &mut annotations_visible,
+                gdc.translate_0("nh-tab-layers-annotations"),
+            )
+            .changed()
+        {
+            commands.push(DiagramCommand::SetAnnotationsVisible(annotations_visible));
+        }
+        ui.separator();
+
+        for (idx, (layer, count)) in overview.layers.iter().enumerate().rev() {
+            let row = ui.horizontal(|ui| {
+                ui.dnd_drag_source(egui::Id::new(("layer", layer.uuid)), idx, |ui| {
+                    ui.label("☰");
+                });
+                let mut visible = layer.visible;
+                if ui
+                    .checkbox(&mut visible, "")
+                    .on_hover_text(gdc.translate_0("nh-tab-layers-visible"))
+                    .changed()
+                {
+                    commands.push(DiagramCommand::SetLayerVisible(layer.uuid, visible));
+                }
+                let mut locked = layer.locked;
+                if ui
+                    .toggle_value(&mut locked, "🔒")
+                    .on_hover_text(gdc.translate_0("nh-tab-layers-locked"))
+                    .changed()
+                {
+                    commands.push(DiagramCommand::SetLayerLocked(layer.uuid, locked));
+                }
+                let mut name = layer.name.clone();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut name).desired_width(120.0))
+                    .changed()
+                {
+                    project_commands.push(ProjectCommand::RenameLayer(diagram, layer.uuid, name));
+                }
+                ui.label(format!("({})", count));
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("X").clicked() {
+                        commands.push(DiagramCommand::RemoveLayer(layer.uuid));
+                    }
+                    if ui.button(gdc.translate_0("nh-tab-layers-assign")).clicked() {
+                        commands.push(DiagramCommand::AssignSelectedToLayer(Some(layer.uuid)));
+                    }
+                });
+            });
+
+            let response = row.response;
+            if let Some(dragged) = response.dnd_hover_payload::<usize>()
+                && *dragged != idx
+            {
+                let y = if *dragged < idx {
+                    response.rect.top()
+                } else {
+                    response.rect.bottom()
+                };
+                ui.painter()
+                    .hline(response.rect.x_range(), y, ui.visuals().selection.stroke);
+            }
+            if let Some(dragged) = response.dnd_release_payload::<usize>()
+                && let Some((dragged_layer, _)) = overview.layers.get(*dragged)
+            {
+                commands.push(DiagramCommand::MoveLayer(dragged_layer.uuid, idx));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} ({})",
+                gdc.translate_0("nh-tab-layers-default"),
+                overview.default_layer_count
+            ));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(gdc.translate_0("nh-tab-layers-assign")).clicked() {
+                    commands.push(DiagramCommand::AssignSelectedToLayer(None));
+                }
+            });
+        });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let r = ui.text_edit_singleline(&mut self.new_layer_name);
+
+            if (r.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                || ui.button(gdc.translate_0("nh-tab-layers-addnew")).clicked()
+            {
+                let name = std::mem::take(&mut self.new_layer_name);
+                let name = if name.trim().is_empty() {
+                    format!(
+                        "{} {}",
+                        gdc.translate_0("nh-tab-layers-layer"),
+                        overview.layers.len() + 1
+                    )
+                } else {
+                    name
+                };
+                project_commands.push(ProjectCommand::AddLayer(diagram, name));
+            }
+        });
+        ui.separator();
+
+        ui.label(format!(
+            "{} ({})",
+            gdc.translate_0("nh-tab-layers-lockedelements"),
+            overview.locked_count
+        ));
+        ui.horizontal(|ui| {
+            if ui
+                .button(gdc.translate_0("nh-tab-layers-lockselected"))
+                .clicked()
+            {
+                commands.push(DiagramCommand::LockSelectedElements(true));
+            }
+            if ui
+                .button(gdc.translate_0("nh-tab-layers-unlockselected"))
+                .clicked()
+            {
+                commands.push(DiagramCommand::LockSelectedElements(false));
+            }
+        });
+
+        if !commands.is_empty() {
+            self.set_has_unsaved_changes(true);
+        }
+        self.unprocessed_commands.extend(
+            commands
+                .into_iter()
+                .map(|e| SimpleProjectCommand::SpecificDiagramCommand(diagram, e).into())
+                .chain(project_commands),
+        );
+    }
+
+    /// Selects and pans to the element in a diagram showing it
+    fn focus_element(&mut self, element: &ModelUuid) {
+        let Some(diagram) = self.diagram_showing(element) else {
+            return;
+        };
+        self.unprocessed_commands
+            .push(ProjectCommand::OpenAndFocusTab(
+                NHTab::Diagram { uuid: diagram },
+                None,
+            ));
+        self.unprocessed_commands.extend_from_slice(
+            &[
+                DiagramCommand::HighlightAllElements(
+                    false,
+                    crate::common::canvas::Highlight::SELECTED,
+                ),
+                DiagramCommand::HighlightElement(
+                    (*element).into(),
+                    true,
+                    crate::common::canvas::Highlight::SELECTED,
+                ),
+                DiagramCommand::PanToElement((*element).into(), true),
+            ]
+            .map(|e| SimpleProjectCommand::SpecificDiagramCommand(diagram, e).into()),
+        );
+    }
+
+    fn project_folder(&self) -> Option<&std::path::Path> {
+        self.project_path.as_ref().and_then(|e| e.parent())
+    }
+
+    fn external_link_status(&self, link: &ExternalLink) -> Option<ExternalLinkStatus> {
+        self.external_files_modified
+            .get(&link.resolve(self.project_folder()))
+            .map(|e| link.status(*e))
+    }
+
+    fn refresh_outdated_models(&mut self) {
+        self.drawing_context.outdated_models = self
+            .external_links
+            .iter()
+            .filter(|e| self.external_link_status(e) == Some(ExternalLinkStatus::Outdated))
+            .map(|e| e.element)
+            .collect();
+    }
+
+    /// Rereads modification times of linked files every few seconds
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watch_external_links(&mut self, ctx: &egui::Context) {
+        const INTERVAL_SECONDS: f64 = 2.0;
+        if self.external_links.is_empty() {
+            return;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(INTERVAL_SECONDS));
+        let time = ctx.input(|i| i.time);
+        if time - self.external_links_checked_at < INTERVAL_SECONDS {
+            return;
+        }
+        self.external_links_checked_at = time;
+
+        self.external_files_modified = self
+            .external_links
+            .iter()
+            .map(|e| e.resolve(self.project_folder()))
+            .map(|e| {
+                let modified = external_links::file_modified(&e);
+                (e, modified)
+            })
+            .collect();
+        self.refresh_outdated_models();
+    }
+
+    fn show_external_links(&mut self, ui: &mut egui::Ui) {
+        #[cfg(target_arch = "wasm32")]
+        ui.label(
+            self.drawing_context
+                .translate_0("nh-tab-externallinks-notwatched"),
+        );
+
+        let mut link_to_remove = None;
+        let mut element_to_focus = None;
+        let mut modified = false;
+        let statuses: Vec<_> = self
+            .external_links
+            .iter()
+            .map(|e| self.external_link_status(e))
+            .collect();
+        let gdc = &self.drawing_context;
+        egui::Grid::new("external links")
+            .striped(true)
+            .show(ui, |ui| {
+                for (idx, (link, status)) in
+                    self.external_links.iter_mut().zip(statuses).enumerate()
+                {
+                    if ui.link(&*gdc.model_labels.get(&link.element)).clicked() {
+                        element_to_focus = Some(link.element);
+                    }
+                    modified |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut link.path)
+                                .hint_text(gdc.translate_0("nh-tab-externallinks-path")),
+                        )
+                        .changed();
+                    match status {
+                        None => {
+                            ui.label("");
+                        }
+                        Some(ExternalLinkStatus::Outdated) => {
+                            ui.colored_label(
+                                egui::Color32::ORANGE,
+                                gdc.translate_0(ExternalLinkStatus::Outdated.message_name()),
+                            );
+                        }
+                        Some(s) => {
+                            ui.label(gdc.translate_0(s.message_name()));
+                        }
+                    }
+                    if ui
+                        .button(gdc.translate_0("nh-tab-externallinks-markuptodate"))
+                        .clicked()
+                    {
+                        link.last_edit = external_links::now();
+                        modified = true;
+                    }
+                    if ui.button("X").clicked() {
+                        link_to_remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(idx) = link_to_remove {
+            self.external_links.remove(idx);
+            modified = true;
+        }
+        if modified {
+            self.external_links_checked_at = f64::NEG_INFINITY;
+            self.refresh_outdated_models();
+            self.set_has_unsaved_changes(true);
+        }
+        if let Some(element) = element_to_focus {
+            self.focus_element(&element);
+        }
+    }
+
+    fn refresh_issue_linked_models(&mut self) {
+        self.drawing_context.issue_linked_models = self
+            .issue_links
+            .iter()
+            .filter(|e| IssueReference::parse(&e.reference).is_some())
+            .map(|e| e.element)
+            .collect();
+    }
+
+    /// Fetches titles and states of linked issues which were not fetched yet
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch_issue_infos(&mut self, ctx: &egui::Context) {
+        while let Ok((url, info)) = self.issue_info_channel.1.try_recv() {
+            self.issue_infos.insert(url, Some(info));
+        }
+        for r in self
+            .issue_links
+            .iter()
+            .flat_map(|e| IssueReference::parse(&e.reference))
+        {
+            let url = r.api_url();
+            if self.issue_infos.contains_key(&url) {
+                continue;
+            }
+            self.issue_infos.insert(url.clone(), None);
+
+            let mut request = ehttp::Request::get(&url);
+            request.headers.insert("User-Agent", "nihonium");
+            let s = self.issue_info_channel.0.clone();
+            let ctx = ctx.clone();
+            ehttp::fetch(request, move |response| {
+                let info = response.and_then(|e| {
+                    if !e.ok {
+                        return Err(format!("{} {}", e.status, e.status_text));
+                    }
+                    IssueInfo::from_response(&e.bytes)
+                        .ok_or_else(|| "Unexpected response".to_owned())
+                });
+                let _ = s.send((url, info));
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    fn issue_description(&self, reference: &IssueReference) -> String {
+        let gdc = &self.drawing_context;
+        match self.issue_infos.get(&reference.api_url()) {
+            None => reference.label(),
+            Some(None) => format!(
+                "{}: {}",
+                reference.label(),
+                gdc.translate_0("nh-tab-issuelinks-loading")
+            ),
+            Some(Some(Ok(info))) => {
+                format!("{}: {} ({})", reference.label(), info.title, info.state)
+            }
+            Some(Some(Err(e))) => format!(
+                "{}: {} ({})",
+                reference.label(),
+                gdc.translate_0("nh-tab-issuelinks-unavailable"),
+                e
+            ),
+        }
+    }
+
+    fn issue_closed(&self, reference: &IssueReference) -> bool {
+        self.issue_infos
+            .get(&reference.api_url())
+            .is_some_and(|e| matches!(e, Some(Ok(info)) if info.is_closed()))
+    }
+
+    fn show_issue_links(&mut self, ui: &mut egui::Ui) {
+        #[cfg(target_arch = "wasm32")]
+        ui.label(
+            self.drawing_context
+                .translate_0("nh-tab-issuelinks-notfetched"),
+        );
+
+        let mut link_to_remove = None;
+        let mut element_to_focus = None;
+        let mut modified = false;
+        let descriptions: Vec<_> = self
+            .issue_links
+            .iter()
+            .map(|e| {
+                IssueReference::parse(&e.reference)
+                    .map(|r| (self.issue_description(&r), self.issue_closed(&r), r))
+            })
+            .collect();
+        let gdc = &self.drawing_context;
+        egui::Grid::new("issue links").striped(true).show(ui, |ui| {
+            for (idx, (link, description)) in
+                self.issue_links.iter_mut().zip(descriptions).enumerate()
+            {
+                if ui.link(&*gdc.model_labels.get(&link.element)).clicked() {
+                    element_to_focus = Some(link.element);
+                }
+                modified |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut link.reference)
+                            .hint_text(gdc.translate_0("nh-tab-issuelinks-reference")),
+                    )
+                    .changed();
+                match &description {
+                    None if link.reference.trim().is_empty() => {
+                        ui.label("");
+                    }
+                    None => {
+                        ui.colored_label(
+                            egui::Color32::ORANGE,
+                            gdc.translate_0("nh-tab-issuelinks-invalid"),
+                        );
+                    }
+                    Some((d, true, _)) => {
+                        ui.weak(d);
+                    }
+                    Some((d, false, _)) => {
+                        ui.label(d);
+                    }
+                }
+                if ui
+                    .add_enabled(
+                        description.is_some(),
+                        egui::Button::new(gdc.translate_0("nh-tab-issuelinks-open")),
+                    )
+                    .clicked()
+                    && let Some((_, _, r)) = &description
+                {
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(r.web_url()));
+                }
+                if ui.button("X").clicked() {
+                    link_to_remove = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(idx) = link_to_remove {
+            self.issue_links.remove(idx);
+            modified = true;
+        }
+        if modified {
+            self.refresh_issue_linked_models();
+            self.set_has_unsaved_changes(true);
+        }
+        if let Some(element) = element_to_focus {
+            self.focus_element(&element);
+        }
+    }
+
+    fn show_outline(&mut self, ui: &mut egui::Ui) {
+        let Some(last_focused_diagram) = &self.last_focused_diagram else {
+            return;
+        };
+        let Some(c) = self.diagram_controllers.get(last_focused_diagram) else {
+            return;
+        };
+        let mut c = c.write();
+        let ctype = c.controller_type();
+        let Some(s) = self.diagram_settings.get(ctype) else {
+            return;
+        };
+        let outline_rect = ui.content_rect();
+
+        c.show_outline(last_focused_diagram, &self.drawing_context, s.as_ref(), ui);
+
+        let shade_color = self.shades_profiles[self.selected_shades_profile].get(ctype);
+        ui.painter().rect(
+            outline_rect,
+            egui::CornerRadius::ZERO,
+            shade_color,
+            egui::Stroke::NONE,
+            egui::StrokeKind::Middle,
+        );
+    }
+
+    /// Constructor initially selected for the selected diagram type, the project template
+    /// takes precedence over the default constructor from the user settings
+    fn preselected_constructor(&self) -> usize {
+        let Some((dt, dd)) = self.new_diagram_data.get(&self.new_diagram_selected_kind) else {
+            return 0;
+        };
+        if self
+            .diagram_templates
+            .get(*dt)
+            .is_some_and(|v| self.diagram_controllers.contains_key(v))
+        {
+            return dd.constructors.len();
+        }
+        self.default_constructors
+            .get(*dt)
+            .and_then(|d| dd.constructors.iter().position(|e| e.0 == d))
+            .unwrap_or(0)
+    }
+
+    /// Creates a diagram using the selected constructor, or by copying the template
+    fn construct_new_diagram(
+        dd: &DiagramCreationData,
+        selected_constructor: usize,
+        no: u32,
+        template: Option<(ViewUuid, &ERef<dyn DiagramController>)>,
+    ) -> (ViewUuid, ERef<dyn DiagramController>) {
+        if let Some(constructor) = dd.constructors.get(selected_constructor) {
+            return constructor.1(no);
+        }
+        let (v, c) = template.expect("template is selected");
+        let c = c.read();
+        let (uuid, new_c) = c.duplicate_view(&v);
+        new_c
+            .read()
+            .set_view_name(&uuid, Arc::new(format!("{} {}", c.view_name(&v), no)));
+        (uuid, new_c)
+    }
+
+    fn show_newdiagram_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading(self.drawing_context.translate_0("nh-project-addnewdiagram"));
+
+        ui.columns(2, |columns| {
+            let (_, actions) = egui_ltreeview::TreeView::new(
+                columns[0].make_persistent_id("new diagram modal hierarchy"),
+            )
+            .allow_drag_and_drop(false)
+            .allow_multi_selection(false)
+            .show(&mut columns[0], |builder| {
+                fn h<'a, 'ui>(
+                    e: &'a DiagramTypeHierarchyNode,
+                    builder: &mut egui_ltreeview::TreeViewBuilder<'ui, usize>,
+                ) {
+                    match e {
+                        DiagramTypeHierarchyNode::Folder(id, name, children) => {
+                            builder.dir(*id, name);
+
+                            for e in children {
+                                h(e, builder);
+                            }
+
+                            builder.close_dir();
+                        }
+                        DiagramTypeHierarchyNode::DiagramType(id, _dtype, dname) => {
+                            builder.leaf(*id, dname);
+                        }
+                    }
+                }
+                let DiagramTypeHierarchyNode::Folder(.., children) = &self.diagram_type_hierarchy
+                else {
+                    return;
+                };
+                for e in children {
+                    h(e, builder);
+                }
+            });
+            for e in actions {
+                if let egui_ltreeview::Action::SetSelected(items) = e {
+                    self.new_diagram_selected_kind = items.first().map_or(0, |e| *e);
+                    self.new_diagram_selected_constructor = self.preselected_constructor();
+                }
+            }
+
+            columns[1].vertical(|ui| {
+                if let Some((dt, dd)) = self.new_diagram_data.get(&self.new_diagram_selected_kind) {
+                    ui.label(dd.description);
+                    ui.separator();
+
+                    for (idx, e) in dd.constructors.iter().enumerate() {
+                        ui.radio_value(&mut self.new_diagram_selected_constructor, idx, e.0);
+                    }
+                    let template = self
+                        .diagram_templates
+                        .get(*dt)
+                        .and_then(|v| Some((*v, self.diagram_controllers.get(v)?)));
+                    if let Some((v, c)) = template {
+                        let label = format!(
+                            "{} {}",
+                            self.drawing_context
+                                .translate_0("nh-tab-newdiagram-template"),
+                            c.read().view_name(&v)
+                        );
+                        ui.radio_value(
+                            &mut self.new_diagram_selected_constructor,
+                            dd.constructors.len(),
+                            label,
+                        );
+                    }
+                    ui.separator();
+
+                    if let Some(e) = dd.constructors.get(self.new_diagram_selected_constructor) {
+                        let is_default = self
+                            .default_constructors
+                            .get(*dt)
+                            .map_or(self.new_diagram_selected_constructor == 0, |d| d == e.0);
+                        if ui
+                            .add_enabled(
+                                !is_default,
+                                egui::Button::new(
+                                    self.drawing_context
+                                        .translate_0("nh-tab-newdiagram-setdefault"),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            self.default_constructors
+                                .insert((*dt).to_owned(), e.0.to_owned());
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        let enable = self.new_diagram_selected_constructor < dd.constructors.len()
+                            || template.is_some();
+                        if ui
+                            .add_enabled(
+                                enable,
+                                egui::Button::new(
+                                    self.drawing_context.translate_0("nh-tab-newdiagram-open"),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            let (uuid, c) = Self::construct_new_diagram(
+                                dd,
+                                self.new_diagram_selected_constructor,
+                                self.new_diagram_no,
+                                template,
+                            );
+                            self.unprocessed_commands
+                                .push(ProjectCommand::SetNewDiagramNumber(self.new_diagram_no + 1));
+                            self.unprocessed_commands
+                                .push(ProjectCommand::AddNewDiagram(ViewUuid::nil(), uuid, c));
+                            self.unprocessed_commands
+                                .push(ProjectCommand::OpenAndFocusTab(
+                                    NHTab::Diagram { uuid },
+                                    None,
+                                ));
+                        }
+                        if ui
+                            .add_enabled(
+                                enable,
+                                egui::Button::new(
+                                    self.drawing_context
+                                        .translate_0("nh-tab-newdiagram-background"),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            let (uuid, c) = Self::construct_new_diagram(
+                                dd,
+                                self.new_diagram_selected_constructor,
+                                self.new_diagram_no,
+                                template,
+                            );
+                            self.unprocessed_commands
+                                .push(ProjectCommand::SetNewDiagramNumber(self.new_diagram_no + 1));
+                            self.unprocessed_commands
+                                .push(ProjectCommand::AddNewDiagram(ViewUuid::nil(), uuid, c));
+                        }
+                    });
+                }
+            });
+        });
+    }
+
+    fn show_settings_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Settings");
+
+        ui.horizontal(|ui| {
+            if ui.button("Export settings…").clicked() {
+                let d = rfd::AsyncFileDialog::new()
+                    .set_file_name("nihonium-settings.json")
+                    .add_filter("JSON files", &["json"])
+                    .add_filter("All files", &["*"])
+                    .save_file();
+                let s = self.file_io_channel.0.clone();
+                execute(async move {
+                    if let Some(fh) = d.await {
+                        let _ = s.send(FileIOOperation::SettingsExport(fh));
+                    }
+                });
+            }
+            if ui.button("Import settings…").clicked() {
+                let d = rfd::AsyncFileDialog::new()
+                    .add_filter("JSON files", &["json"])
+                    .add_filter("All files", &["*"])
+                    .pick_file();
+                let s = self.file_io_channel.0.clone();
+                execute(async move {
+                    if let Some(fh) = d.await {
+                        let _ = s.send(FileIOOperation::SettingsImport(fh.read().await));
+                    }
+                });
+            }
+        });
+
+        let visuals_response = egui::CollapsingHeader::new("Visuals")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.button("Switch light/dark theme").clicked() {
@@ -1636,6 +3493,17 @@ impl NHContext {
                             ui.selectable_value(&mut self.zoom_factor, e, e.to_string());
                         }
                     });
+                ui.checkbox(
+                    &mut self.drawing_context.animate_transitions,
+                    "Animate camera and layout transitions",
+                );
+                ui.add(
+                    Slider::new(
+                        &mut self.drawing_context.snap_tolerance,
+                        0.0..=Self::MAX_SNAP_TOLERANCE,
+                    )
+                    .text("Snap to alignment guides within (0 disables)"),
+                );
 
                 ui.collapsing("Advanced", |ui| {
                     ui.collapsing("DockArea Options", |ui| {
@@ -2042,6 +3910,8 @@ impl NHContext {
         visuals_response.header_response.context_menu(|ui| {
             if ui.button("Reset 'Visuals' settings").clicked() {
                 self.zoom_factor = 1.0;
+                self.drawing_context.animate_transitions = true;
+                self.drawing_context.snap_tolerance = Self::DEFAULT_SNAP_TOLERANCE;
                 self.style = Some(Style::from_egui(&ui.global_style()));
             }
         });
@@ -2092,6 +3962,18 @@ impl NHContext {
                                 self.shortcut_being_set = None;
                             }
                         }
+
+                        ui.collapsing("Defaults for new elements", |ui| {
+                            let all_defaults = &mut self.drawing_context.element_defaults;
+                            let mut defaults =
+                                all_defaults.get(*ctype).copied().unwrap_or_default();
+                            defaults.show_settings(ui);
+                            if defaults.is_default() {
+                                all_defaults.remove(*ctype);
+                            } else {
+                                all_defaults.insert((*ctype).to_owned(), defaults);
+                            }
+                        });
                     })
                     .header_response
                     .context_menu(|ui| {
@@ -2100,14 +3982,28 @@ impl NHContext {
                             .clicked()
                         {
                             *settings = d();
+                            self.drawing_context.element_defaults.remove(*ctype);
                         }
                     });
                 }
             }
         });
 
+        ui.collapsing("Saving", |ui| {
+            ui.checkbox(
+                &mut self.svg_backup_enabled,
+                "Export every diagram as SVG when saving the project",
+            );
+            ui.add_enabled_ui(self.svg_backup_enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Folder relative to the project file:");
+                    ui.text_edit_singleline(&mut self.svg_backup_folder);
+                });
+            });
+        });
+
         ui.collapsing("Languages", |ui| {
-            for (idx, l) in self.languages_order.iter().enumerate() {
+            for (idx, l) in self.drawing_context.languages_order.iter().enumerate() {
                 let text = if idx == self.selected_language {
                     format!("[{}]", l)
                 } else {
@@ -2125,25 +4021,29 @@ impl NHContext {
                 .add_enabled(self.selected_language > 0, egui::Button::new("Up"))
                 .clicked()
             {
-                self.languages_order
+                self.drawing_context
+                    .languages_order
                     .swap(self.selected_language, self.selected_language - 1);
                 self.selected_language -= 1;
                 self.drawing_context.fluent_bundle =
-                    common::fluent::create_fluent_bundle(&self.languages_order).unwrap();
+                    common::fluent::create_fluent_bundle(&self.drawing_context.languages_order)
+                        .unwrap();
             }
 
             if ui
                 .add_enabled(
-                    self.selected_language + 1 < self.languages_order.len(),
+                    self.selected_language + 1 < self.drawing_context.languages_order.len(),
                     egui::Button::new("Down"),
                 )
                 .clicked()
             {
-                self.languages_order
+                self.drawing_context
+                    .languages_order
                     .swap(self.selected_language, self.selected_language + 1);
                 self.selected_language += 1;
                 self.drawing_context.fluent_bundle =
-                    common::fluent::create_fluent_bundle(&self.languages_order).unwrap();
+                    common::fluent::create_fluent_bundle(&self.drawing_context.languages_order)
+                        .unwrap();
             }
         });
 
@@ -2231,6 +4131,27 @@ impl NHContext {
                     "Alternative Tool Mode",
                     &mut modifier_settings.alternative_tool_mode,
                 );
+                row(
+                    ui,
+                    "Follow Element Link",
+                    &mut modifier_settings.follow_element_link,
+                );
+            });
+
+            ui.label("Double-click action");
+            egui::Grid::new("double click grid").show(ui, |ui| {
+                for category in ElementCategory::ALL {
+                    ui.label(category.name());
+                    let action = modifier_settings.double_click_actions.get_mut(category);
+                    egui::ComboBox::from_id_salt(("double click action", category.name()))
+                        .selected_text(action.name())
+                        .show_ui(ui, |ui| {
+                            for e in DoubleClickAction::ALL {
+                                ui.selectable_value(action, e, e.name());
+                            }
+                        });
+                    ui.end_row();
+                }
             });
             self.modifier_settings = modifier_settings;
             self.modifier_settings.sort_delete_kinds();
@@ -2248,6 +4169,34 @@ impl NHContext {
                         SimpleProjectCommand::CycleShadesProfiles,
                     ),
                     ("Save project:", SimpleProjectCommand::SaveProject),
+                    (
+                        "Copy selection as SVG:",
+                        DiagramCommand::CopySelectedAsSvg.into(),
+                    ),
+                    (
+                        "Copy selection as PlantUML:",
+                        DiagramCommand::CopySelectedAsPlantUml.into(),
+                    ),
+                    (
+                        "Copy layout as JSON:",
+                        DiagramCommand::CopyLayoutAsJson.into(),
+                    ),
+                    (
+                        "Apply layout from JSON:",
+                        DiagramCommand::ApplyLayoutFromJson.into(),
+                    ),
+                    (
+                        "Copy as image:",
+                        SimpleProjectCommand::CopyAsImage {
+                            selected_only: false,
+                        },
+                    ),
+                    (
+                        "Copy selection as image:",
+                        SimpleProjectCommand::CopyAsImage {
+                            selected_only: true,
+                        },
+                    ),
                     ("Save project as:", SimpleProjectCommand::SaveProjectAs),
                     (
                         "Arrange - Bring to Front:",
@@ -2265,6 +4214,18 @@ impl NHContext {
                         "Arrange - Send to Back:",
                         DiagramCommand::ArrangeSelected(Arrangement::SendToBack).into(),
                     ),
+                    (
+                        "Match Width:",
+                        DiagramCommand::MatchSizeSelected(SizeMatch::Width).into(),
+                    ),
+                    (
+                        "Match Height:",
+                        DiagramCommand::MatchSizeSelected(SizeMatch::Height).into(),
+                    ),
+                    (
+                        "Match Size:",
+                        DiagramCommand::MatchSizeSelected(SizeMatch::Both).into(),
+                    ),
                     ("Zoom 10 %:", DiagramCommand::AddZoomPercent(10).into()),
                     ("Zoom -10 %:", DiagramCommand::AddZoomPercent(-10).into()),
                     ("Reset Diagram Scale:", DiagramCommand::ResetScale.into()),
@@ -2286,6 +4247,11 @@ impl NHContext {
                         "Reset Diagram Position:",
                         DiagramCommand::ResetPosition.into(),
                     ),
+                    ("Add Child Element:", DiagramCommand::AddChildElement.into()),
+                    (
+                        "Add Sibling Element:",
+                        DiagramCommand::AddSiblingElement.into(),
+                    ),
                     ("Pan Camera Left:", DiagramCommand::PanCamera(-1, 0).into()),
                     (
                         "Pan Camera Left (Faster):",
@@ -2347,78 +4313,621 @@ impl NHContext {
                     self.drawing_context.shortcuts = Self::default_shortcuts();
                 }
             });
+
+        ui.collapsing("Macros", |ui| {
+            if self.macro_recorder.macros.is_empty() {
+                ui.label("No macros recorded yet. Record them in Edit > Macros.");
+            }
+            let mut delete = None;
+            for (idx, m) in self.macro_recorder.macros.iter_mut().enumerate() {
+                ui.push_id(idx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut m.name).desired_width(150.0));
+                        ui.label(format!("{} steps", m.steps.len()));
+                        if ui.button("Delete").clicked() {
+                            delete = Some(idx);
+                        }
+                    });
+                    ui.add(
+                        egui::TextEdit::multiline(&mut m.annotation)
+                            .hint_text("Annotation")
+                            .desired_rows(2),
+                    );
+                });
+                ui.separator();
+            }
+            if let Some(idx) = delete {
+                self.macro_recorder.macros.remove(idx);
+            }
+        });
+    }
+
+    fn show_diagram_viewport_tab(
+        &mut self,
+        tab_uuid: &ViewUuid,
+        viewport: &uuid::Uuid,
+        ui: &mut egui::Ui,
+    ) {
+        let Some(v) = self.diagram_controllers.get(tab_uuid).cloned() else {
+            return;
+        };
+        let mut camera = self.diagram_viewports.remove(viewport).unwrap_or_default();
+        v.write().swap_camera(tab_uuid, &mut camera);
+        self.show_diagram_tab(tab_uuid, ui);
+        v.write().swap_camera(tab_uuid, &mut camera);
+        self.diagram_viewports.insert(*viewport, camera);
+    }
+
+    // In general it should draw first and handle input second, right?
+    fn show_diagram_tab(&mut self, tab_uuid: &ViewUuid, ui: &mut egui::Ui) {
+        let Some(v) = self.diagram_controllers.get(tab_uuid).cloned() else {
+            return;
+        };
+        self.show_breadcrumbs(tab_uuid, ui);
+        let mut diagram_controller = v.write();
+        let ctype = diagram_controller.controller_type();
+        let Some(settings) = self.diagram_settings.get(ctype) else {
+            return;
+        };
+
+        let input_probably_blocked =
+            self.confirm_modal_reason.is_some() || self.custom_modal.is_some();
+        let ui_scale = match input_probably_blocked {
+            true => None,
+            false => Some(self.zoom_factor),
+        };
+        let (mut ui_canvas, response, pos) =
+            diagram_controller.new_ui_canvas(tab_uuid, &self.drawing_context, ui, ui_scale);
+
+        response.context_menu(|ui| {
+            diagram_controller.show_context_menu(
+                tab_uuid,
+                &self.drawing_context,
+                ui,
+                &response,
+                &mut self.unprocessed_commands,
+                &mut self.affected_models,
+            );
+        });
+        if !response.context_menu_opened() {
+            diagram_controller.unset_context_menu(tab_uuid);
+        }
+
+        diagram_controller.draw_in(
+            tab_uuid,
+            &self.drawing_context,
+            settings.as_ref(),
+            ui_canvas.as_mut(),
+            pos,
+        );
+        let shade_color = self.shades_profiles[self.selected_shades_profile].get(ctype);
+        ui_canvas.draw_rectangle(
+            egui::Rect::EVERYTHING,
+            egui::CornerRadius::ZERO,
+            shade_color,
+            common::canvas::Stroke::NONE,
+            Highlight::NONE,
+        );
+
+        diagram_controller.handle_input(
+            tab_uuid,
+            ui,
+            &response,
+            self.modifier_settings,
+            settings.as_ref(),
+            &mut self.custom_modal,
+            &mut self.affected_models,
+        );
+
+        // Explain abbreviations used in the name of the hovered element
+        let abbreviations = &self.drawing_context.abbreviations;
+        if response.hovered()
+            && (!abbreviations.entries.is_empty() || abbreviations.require_defined)
+            && let Some(model_uuid) = diagram_controller.model_at_pointer(tab_uuid, ui, &response)
+        {
+            let label = self.drawing_context.model_labels.get(&model_uuid);
+            let expansions = abbreviations.expansions_in(&label);
+            let undefined = if abbreviations.require_defined {
+                abbreviations.undefined_acronyms(&label)
+            } else {
+                vec![]
+            };
+            if !expansions.is_empty() || !undefined.is_empty() {
+                response.clone().on_hover_ui_at_pointer(|ui| {
+                    for e in expansions {
+                        ui.label(format!("{}: {}", e.abbreviation, e.expansion));
+                    }
+                    if !undefined.is_empty() {
+                        ui.colored_label(
+                            egui::Color32::ORANGE,
+                            format!(
+                                "{}: {}",
+                                self.drawing_context
+                                    .translate_0("nh-tab-abbreviations-undefined"),
+                                undefined.join(", ")
+                            ),
+                        );
+                    }
+                });
+            }
+        }
+
+        // Describe issues linked to the hovered element
+        if response.hovered()
+            && !self.drawing_context.issue_linked_models.is_empty()
+            && let Some(model_uuid) = diagram_controller.model_at_pointer(tab_uuid, ui, &response)
+            && self
+                .drawing_context
+                .issue_linked_models
+                .contains(&model_uuid)
+        {
+            response.clone().on_hover_ui_at_pointer(|ui| {
+                for r in self
+                    .issue_links
+                    .iter()
+                    .filter(|e| e.element == model_uuid)
+                    .flat_map(|e| IssueReference::parse(&e.reference))
+                {
+                    ui.label(self.issue_description(&r));
+                }
+            });
+        }
+
+        if response.double_clicked()
+            && self.modifier_settings.follow_element_link.is_some_and(|e| {
+                ui.input(|i| ModifierKeys::from_egui(&i.modifiers))
+                    .is_superset_of(e)
+            })
+            && let Some(link) = diagram_controller.element_link_at_pointer(tab_uuid, ui, &response)
+        {
+            self.follow_element_link(ui.ctx(), link);
+        } else if response.double_clicked()
+            && let Some(model_uuid) = diagram_controller.model_at_pointer(tab_uuid, ui, &response)
+            && let Some(category) = diagram_controller.element_category(tab_uuid, &model_uuid)
+        {
+            match self.modifier_settings.double_click_actions.get(category) {
+                DoubleClickAction::OpenProperties => {
+                    self.unprocessed_commands
+                        .push(ProjectCommand::OpenAndFocusTab(NHTab::Properties, None));
+                }
+                DoubleClickAction::InlineRename => {
+                    if !diagram_controller.start_inline_rename(tab_uuid, &model_uuid) {
+                        self.unprocessed_commands
+                            .push(ProjectCommand::OpenAndFocusTab(NHTab::Properties, None));
+                    }
+                }
+                DoubleClickAction::DrillDown => {
+                    if let Some(view_uuid) = self.sub_diagrams.get(&model_uuid) {
+                        self.unprocessed_commands
+                            .push(ProjectCommand::OpenAndFocusTab(
+                                NHTab::Diagram { uuid: *view_uuid },
+                                None,
+                            ));
+                    } else if let Some((view_uuid, is_new)) =
+                        diagram_controller.drill_down(tab_uuid, ui, &response)
+                    {
+                        if is_new {
+                            self.unprocessed_commands
+                                .push(ProjectCommand::AddNewDiagram(
+                                    ViewUuid::nil(),
+                                    view_uuid,
+                                    v.clone(),
+                                ));
+                        }
+                        self.unprocessed_commands
+                            .push(ProjectCommand::OpenAndFocusTab(
+                                NHTab::Diagram { uuid: view_uuid },
+                                None,
+                            ));
+                    }
+                }
+                DoubleClickAction::FollowLink => {
+                    self.follow_link(ui.ctx(), &model_uuid);
+                }
+            }
+        }
+    }
+
+    fn follow_element_link(&mut self, ctx: &egui::Context, link: ElementLink) {
+        match link {
+            ElementLink::Diagram(uuid) if self.diagram_controllers.contains_key(&uuid) => {
+                self.unprocessed_commands
+                    .push(ProjectCommand::OpenAndFocusTab(
+                        NHTab::Diagram { uuid },
+                        None,
+                    ));
+            }
+            ElementLink::Document(uuid) if self.documents.contains_key(&uuid) => {
+                self.unprocessed_commands
+                    .push(ProjectCommand::OpenAndFocusTab(
+                        NHTab::Document { uuid },
+                        None,
+                    ));
+            }
+            ElementLink::Url(url) => ctx.open_url(egui::OpenUrl::new_tab(url)),
+            // The target was deleted
+            ElementLink::Diagram(_) | ElementLink::Document(_) => {}
+        }
+    }
+
+    /// Opens the file linked to the element, or else the first document linking to it
+    fn follow_link(&mut self, ctx: &egui::Context, element: &ModelUuid) {
+        if let Some(link) = self.external_links.iter().find(|e| e.element == *element) {
+            let path = link.resolve(self.project_folder());
+            ctx.open_url(egui::OpenUrl::new_tab(format!("file://{}", path.display())));
+            return;
+        }
+        if let Some(r) = self
+            .issue_links
+            .iter()
+            .filter(|e| e.element == *element)
+            .find_map(|e| IssueReference::parse(&e.reference))
+        {
+            ctx.open_url(egui::OpenUrl::new_tab(r.web_url()));
+            return;
+        }
+        let target = element.to_string();
+        let linking = self.documents.iter().find_map(|(uuid, (_, content))| {
+            let offset = content.find(&target)?;
+            common::document_links::element_links(content)
+                .iter()
+                .any(|e| e.1 == *element)
+                .then(|| (*uuid, content[..offset].chars().count()))
+        });
+        if let Some((uuid, offset)) = linking {
+            self.document_scroll_request = Some((uuid, offset));
+            self.unprocessed_commands
+                .push(ProjectCommand::OpenAndFocusTab(
+                    NHTab::Document { uuid },
+                    None,
+                ));
+        }
+    }
+
+    /// Stores the image in the project and places it in the diagram
+    fn add_image_element(
+        &mut self,
+        view: ViewUuid,
+        position: Option<egui::Pos2>,
+        name: String,
+        bytes: Vec<u8>,
+    ) {
+        let Some(c) = self.diagram_controllers.get(&view) else {
+            return;
+        };
+        match ProjectImage::decode(uuid::Uuid::now_v7(), name, bytes) {
+            Err(e) => {
+                self.custom_modal = Some(ErrorModal::new_box(format!("Error opening image: {}", e)))
+            }
+            Ok(image) => {
+                let position = position.filter(|e| e.is_finite());
+                c.write().add_image_element(&view, &image, position);
+                self.drawing_context.images.insert(image);
+                self.set_has_unsaved_changes(true);
+            }
+        }
+    }
+
+    /// Adds image files dropped onto the window to the last focused diagram
+    fn add_dropped_images(&mut self, files: Vec<egui::DroppedFile>) {
+        let Some(view) = self.last_focused_diagram else {
+            return;
+        };
+        for f in files {
+            let name = match &f.path {
+                Some(path) if f.name.is_empty() => path
+                    .file_name()
+                    .map(|e| e.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                _ => f.name.clone(),
+            };
+            let bytes = match (f.bytes, &f.path) {
+                (Some(bytes), _) => bytes.to_vec(),
+                #[cfg(not(target_arch = "wasm32"))]
+                (None, Some(path)) => match std::fs::read(path) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        self.custom_modal = Some(ErrorModal::new_box(format!(
+                            "Error opening {:?}: {}",
+                            path, e
+                        )));
+                        continue;
+                    }
+                },
+                _ => continue,
+            };
+            self.add_image_element(view, None, name, bytes);
+        }
+    }
+
+    /// Adds the file as a document, large files are split into a folder of documents
+    fn import_document(&mut self, file_name: String, bytes: &[u8]) {
+        let content = String::from_utf8_lossy(bytes);
+        let parts = common::document_import::split_document(
+            &content,
+            common::document_import::SPLIT_THRESHOLD,
+        );
+
+        let mut nodes = Vec::new();
+        for part in parts {
+            let uuid = ViewUuid::now_v7();
+            let name = common::document_import::part_name(&part).to_owned();
+            self.documents.insert(uuid, (name, part));
+            nodes.push(HierarchyNode::Document(uuid));
+        }
+        let first = nodes[0].uuid();
+        let node = if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            HierarchyNode::Folder(ViewUuid::now_v7(), Arc::new(file_name), nodes)
+        };
+        if let HierarchyNode::Folder(.., children) = &mut self.project_hierarchy {
+            children.push(node);
+        }
+
+        self.unprocessed_commands
+            .push(ProjectCommand::OpenAndFocusTab(
+                NHTab::Document { uuid: first },
+                None,
+            ));
+        self.set_has_unsaved_changes(true);
+    }
+
+    fn document_editor_id(uuid: &ViewUuid) -> egui::Id {
+        egui::Id::new(("document editor", uuid))
+    }
+
+    /// Title of the heading of the section containing the cursor of the document editor
+    fn current_document_heading(&self, uuid: &ViewUuid, ctx: &egui::Context) -> Option<String> {
+        let state = egui::text_edit::TextEditState::load(ctx, Self::document_editor_id(uuid))?;
+        let cursor = state.cursor.char_range()?.primary.index;
+        let stats = common::document_stats::DocumentStats::of(&self.documents.get(uuid)?.1);
+        stats.heading_at(cursor).map(|e| e.title.clone())
+    }
+
+    fn show_document_tab(&mut self, uuid: &ViewUuid, ui: &mut egui::Ui) {
+        let editor_id = Self::document_editor_id(uuid);
+        match self.show_document_stats(uuid, ui) {
+            Some(DocumentPanelAction::ScrollTo(offset)) => {
+                self.document_scroll_request = Some((*uuid, offset));
+            }
+            Some(DocumentPanelAction::FocusElement(element)) => self.focus_element(&element),
+            None => {}
+        }
+
+        let c = self.documents.get_mut(uuid).unwrap();
+        // Documents containing math fragments get a rendered preview below the editor
+        let preview = match common::math::render_math(&c.1) {
+            std::borrow::Cow::Owned(e) => Some(e),
+            std::borrow::Cow::Borrowed(_) => None,
+        };
+        let editor_size = if preview.is_some() {
+            ui.available_size() * egui::Vec2::new(1.0, 0.5)
+        } else {
+            ui.available_size()
+        };
+        let output = ui
+            .allocate_ui(editor_size, |ui| {
+                egui::TextEdit::multiline(&mut c.1)
+                    .id(editor_id)
+                    .desired_width(f32::INFINITY)
+                    .min_size(editor_size)
+                    .show(ui)
+            })
+            .inner;
+        if output.response.changed() {
+            c.0 = c.1.lines().next().unwrap_or("empty document").to_owned();
+            self.set_has_unsaved_changes(true);
+        }
+        let selection = output
+            .cursor_range
+            .filter(|e| !e.is_empty())
+            .map(|e| e.as_sorted_char_range());
+        output.response.context_menu(|ui| {
+            let button = egui::Button::new(
+                self.drawing_context
+                    .translate_0("nh-document-promoteselection"),
+            );
+            if ui.add_enabled(selection.is_some(), button).clicked()
+                && let Some(range) = selection.clone()
+            {
+                self.unprocessed_commands
+                    .push(ProjectCommand::PromoteToElement(*uuid, range));
+                ui.close();
+            }
+        });
+        if let Some((target, offset)) = self.document_scroll_request
+            && target == *uuid
+        {
+            self.document_scroll_request = None;
+            let cursor = egui::text::CCursor::new(offset);
+            let mut state = output.state;
+            state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+            state.store(ui.ctx(), editor_id);
+            ui.ctx().memory_mut(|m| m.request_focus(editor_id));
+            let rect = output
+                .galley
+                .pos_from_cursor(cursor)
+                .translate(output.galley_pos.to_vec2());
+            ui.scroll_to_rect(rect, Some(egui::Align::TOP));
+        }
+        if let Some(preview) = preview {
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.label(preview);
+            });
+        }
+    }
+
+    /// Headings of the document indented by their level, returns the offset of a clicked heading
+    fn document_outline_ui(
+        ui: &mut egui::Ui,
+        stats: &common::document_stats::DocumentStats,
+        cursor: Option<usize>,
+    ) -> Option<usize> {
+        let current = cursor.and_then(|e| stats.heading_at(e));
+        let mut clicked = None;
+        for h in &stats.headings {
+            ui.horizontal(|ui| {
+                ui.add_space(12.0 * (h.level - 1) as f32);
+                if ui
+                    .selectable_label(current.is_some_and(|e| std::ptr::eq(e, h)), &h.title)
+                    .clicked()
+                {
+                    clicked = Some(h.char_offset);
+                }
+            });
+        }
+        clicked
+    }
+
+    /// Side panel with statistics, outline and element links of the document
+    fn show_document_stats(
+        &self,
+        uuid: &ViewUuid,
+        ui: &mut egui::Ui,
+    ) -> Option<DocumentPanelAction> {
+        use common::document_stats::DocumentStats;
+
+        let gdc = &self.drawing_context;
+        let stats_grid = |ui: &mut egui::Ui, id: &str, stats: &DocumentStats| {
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                ui.label(gdc.translate_0("nh-document-stats-words"));
+                ui.label(stats.words.to_string());
+                ui.end_row();
+                ui.label(gdc.translate_0("nh-document-stats-characters"));
+                ui.label(stats.characters.to_string());
+                ui.end_row();
+                ui.label(gdc.translate_0("nh-document-stats-readingtime"));
+                ui.label(stats.reading_minutes().to_string());
+                ui.end_row();
+            });
+        };
+
+        let content = &self.documents.get(uuid).unwrap().1;
+        let stats = DocumentStats::of(content);
+        let element_links = common::document_links::element_links(content);
+        let cursor = egui::text_edit::TextEditState::load(ui.ctx(), Self::document_editor_id(uuid))
+            .and_then(|e| e.cursor.char_range())
+            .map(|e| e.primary.index);
+        let mut clicked = None;
+        egui::Panel::right(ui.id().with("document stats"))
+            .resizable(true)
+            .default_size(180.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.heading(gdc.translate_0("nh-document-stats"));
+                    stats_grid(ui, "document stats", &stats);
+
+                    if !stats.headings.is_empty() {
+                        ui.separator();
+                        ui.label(gdc.translate_0("nh-document-stats-outline"));
+                        clicked = Self::document_outline_ui(ui, &stats, cursor)
+                            .map(DocumentPanelAction::ScrollTo);
+                    }
+
+                    if !element_links.is_empty() {
+                        ui.separator();
+                        ui.label(gdc.translate_0("nh-document-stats-elements"));
+                        for (text, element) in &element_links {
+                            if ui.link(*text).clicked() {
+                                clicked = Some(DocumentPanelAction::FocusElement(*element));
+                            }
+                        }
+                    }
+
+                    ui.separator();
+                    // Computed only when shown, projects can contain many large documents
+                    egui::CollapsingHeader::new(gdc.translate_0("nh-document-stats-workspace"))
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            let mut total = DocumentStats::default();
+                            for (_, content) in self.documents.values() {
+                                total.add(&DocumentStats::of(content));
+                            }
+                            stats_grid(ui, "workspace stats", &total);
+                        });
+                });
+            });
+        clicked
     }
 
-    // In general it should draw first and handle input second, right?
-    fn show_diagram_tab(&mut self, tab_uuid: &ViewUuid, ui: &mut egui::Ui) {
-        let Some(v) = self.diagram_controllers.get(tab_uuid).cloned() else {
+    /// Headings of the last focused document, clicking one scrolls the editor to it
+    fn show_document_outline(&mut self, ui: &mut egui::Ui) {
+        let Some(uuid) = self.last_focused_document else {
             return;
         };
-        let mut diagram_controller = v.write();
-        let ctype = diagram_controller.controller_type();
-        let Some(settings) = self.diagram_settings.get(ctype) else {
+        let Some((name, content)) = self.documents.get(&uuid) else {
             return;
         };
-
-        let input_probably_blocked =
-            self.confirm_modal_reason.is_some() || self.custom_modal.is_some();
-        let ui_scale = match input_probably_blocked {
-            true => None,
-            false => Some(self.zoom_factor),
-        };
-        let (mut ui_canvas, response, pos) =
-            diagram_controller.new_ui_canvas(tab_uuid, &self.drawing_context, ui, ui_scale);
-
-        response.context_menu(|ui| {
-            diagram_controller.show_context_menu(
-                tab_uuid,
-                &self.drawing_context,
-                ui,
-                &response,
-                &mut self.unprocessed_commands,
-                &mut self.affected_models,
+        let stats = common::document_stats::DocumentStats::of(content);
+        ui.heading(name);
+        if stats.headings.is_empty() {
+            ui.label(
+                self.drawing_context
+                    .translate_0("nh-tab-documentoutline-noheadings"),
             );
-        });
-        if !response.context_menu_opened() {
-            diagram_controller.unset_context_menu(tab_uuid);
+            return;
+        }
+        let cursor =
+            egui::text_edit::TextEditState::load(ui.ctx(), Self::document_editor_id(&uuid))
+                .and_then(|e| e.cursor.char_range())
+                .map(|e| e.primary.index);
+        if let Some(offset) = Self::document_outline_ui(ui, &stats, cursor) {
+            self.document_scroll_request = Some((uuid, offset));
+            self.unprocessed_commands
+                .push(ProjectCommand::OpenAndFocusTab(
+                    NHTab::Document { uuid },
+                    None,
+                ));
         }
-
-        diagram_controller.draw_in(
-            tab_uuid,
-            &self.drawing_context,
-            settings.as_ref(),
-            ui_canvas.as_mut(),
-            pos,
-        );
-        let shade_color = self.shades_profiles[self.selected_shades_profile].get(ctype);
-        ui_canvas.draw_rectangle(
-            egui::Rect::EVERYTHING,
-            egui::CornerRadius::ZERO,
-            shade_color,
-            common::canvas::Stroke::NONE,
-            Highlight::NONE,
-        );
-
-        diagram_controller.handle_input(
-            tab_uuid,
-            ui,
-            &response,
-            self.modifier_settings,
-            settings.as_ref(),
-            &mut self.custom_modal,
-            &mut self.affected_models,
-        );
     }
 
-    fn show_document_tab(&mut self, uuid: &ViewUuid, ui: &mut egui::Ui) {
-        let c = self.documents.get_mut(uuid).unwrap();
-        if ui
-            .add_sized(ui.available_size(), egui::TextEdit::multiline(&mut c.1))
-            .changed()
+    /// Adds an element named after the text to the last focused diagram,
+    /// the text is replaced with a link to the element
+    fn promote_to_element(&mut self, document: &ViewUuid, range: std::ops::Range<usize>) {
+        let Some((_, content)) = self.documents.get(document) else {
+            return;
+        };
+        let byte_offset = |i| content.char_indices().nth(i).map_or(content.len(), |e| e.0);
+        let selected = &content[byte_offset(range.start)..byte_offset(range.end)];
+        let start = byte_offset(range.start) + selected.len() - selected.trim_start().len();
+        let text = selected.trim().to_owned();
+        if text.is_empty() {
+            return;
+        }
+
+        let Some((view, c)) = self.last_focused_diagram() else {
+            self.custom_modal = Some(ErrorModal::new_box(
+                self.drawing_context
+                    .translate_0("nh-document-promoteselection-nodiagram")
+                    .into_owned(),
+            ));
+            return;
+        };
+        let model = ModelUuid::now_v7();
+        let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !c
+            .write()
+            .add_named_element(&view, model, &name, &mut self.affected_models)
         {
-            c.0 = c.1.lines().next().unwrap_or("empty document").to_owned();
-            self.set_has_unsaved_changes(true);
+            self.custom_modal = Some(ErrorModal::new_box(
+                self.drawing_context
+                    .translate_0("nh-document-promoteselection-unsupported")
+                    .into_owned(),
+            ));
+            return;
         }
+
+        let c = self.documents.get_mut(document).unwrap();
+        c.1.replace_range(
+            start..start + text.len(),
+            &common::document_links::element_link(&text, &model),
+        );
+        c.0 = c.1.lines().next().unwrap_or("empty document").to_owned();
+        self.set_has_unsaved_changes(true);
     }
 
     fn show_custom_tab(&mut self, tab_uuid: &uuid::Uuid, ui: &mut egui::Ui) {
@@ -2432,6 +4941,217 @@ impl NHContext {
             .as_ref()
             .and_then(|e| self.diagram_controllers.get(e).map(|c| (*e, c.clone())))
     }
+
+    /// Copies the selected elements of the last focused diagram to the clipboard as text
+    fn copy_selection_as_text(&mut self, ctx: &egui::Context, command: DiagramCommand) {
+        const PADDING: f32 = 10.0;
+
+        let Some((v, c)) = self.last_focused_diagram() else {
+            return;
+        };
+        let mut controller = c.write();
+        let text = if command == DiagramCommand::CopySelectedAsPlantUml {
+            let languages = match &self.export_language {
+                Some(l) => vec![l.clone()],
+                None => self.drawing_context.languages_order.clone(),
+            };
+            let Some(text) = controller.export_selected_text(&v, &PLANTUML_FORMAT, &languages)
+            else {
+                self.custom_modal = Some(ErrorModal::new_box(format!(
+                    "{} is not supported by this diagram",
+                    PLANTUML_FORMAT.name
+                )));
+                return;
+            };
+            text
+        } else {
+            let Some(s) = self.diagram_settings.get(controller.controller_type()) else {
+                return;
+            };
+            let painter = egui::Painter::new(
+                ctx.clone(),
+                egui::LayerId::background(),
+                egui::Rect::EVERYTHING,
+            );
+            let mut measuring_canvas = MeasuringCanvas::new(&painter);
+            controller.draw_selected_in(
+                &v,
+                &self.drawing_context,
+                s.as_ref(),
+                &mut measuring_canvas,
+            );
+            let bounds = measuring_canvas.bounds();
+            if !bounds.is_positive() {
+                return;
+            }
+
+            let mut svg_canvas = SVGCanvas::new(
+                -1.0 * bounds.min + egui::Vec2::splat(PADDING),
+                bounds.size() + egui::Vec2::splat(2.0 * PADDING),
+                Highlight::NONE,
+                &painter,
+            );
+            controller.draw_selected_in(&v, &self.drawing_context, s.as_ref(), &mut svg_canvas);
+            let Some(text) = svg_canvas
+                .into_bytes()
+                .ok()
+                .and_then(|e| String::from_utf8(e).ok())
+            else {
+                return;
+            };
+            text
+        };
+        ctx.copy_text(text);
+    }
+
+    /// Copies positions and sizes of elements of the last focused diagram to the clipboard
+    fn copy_layout_as_json(&self, ctx: &egui::Context) {
+        let Some((v, c)) = self.last_focused_diagram() else {
+            return;
+        };
+        ctx.copy_text(c.read().layout(&v).to_json());
+    }
+
+    /// Asks for a layout to be pasted and applies it to the last focused diagram
+    fn open_layout_import(&mut self) {
+        struct LayoutImportModal {
+            view_uuid: ViewUuid,
+            json_buffer: String,
+            error: Option<String>,
+        }
+
+        impl CustomModal for LayoutImportModal {
+            fn show(
+                &mut self,
+                gdc: &mut GlobalDrawingContext,
+                ui: &mut egui::Ui,
+                commands: &mut Vec<ProjectCommand>,
+            ) -> CustomModalResult {
+                ui.label(gdc.translate_0("nh-edit-applylayout"));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.json_buffer)
+                                .code_editor()
+                                .desired_rows(12),
+                        );
+                    });
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                let mut result = CustomModalResult::KeepOpen;
+                ui.horizontal(|ui| {
+                    if ui.button(gdc.translate_0("nh-generic-ok")).clicked() {
+                        match DiagramLayout::from_json(&self.json_buffer) {
+                            Ok(layout) => {
+                                commands.push(ProjectCommand::ApplyLayout(self.view_uuid, layout));
+                                result = CustomModalResult::CloseUnmodified;
+                            }
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    if ui.button(gdc.translate_0("nh-generic-cancel")).clicked() {
+                        result = CustomModalResult::CloseUnmodified;
+                    }
+                });
+
+                result
+            }
+        }
+
+        let Some((view_uuid, _)) = self.last_focused_diagram() else {
+            return;
+        };
+        self.custom_modal = Some(Box::new(LayoutImportModal {
+            view_uuid,
+            json_buffer: String::new(),
+            error: None,
+        }));
+    }
+
+    /// Renders the last focused diagram, or only the area of its selection, to the clipboard.
+    ///
+    /// Browsers do not reliably accept images in the clipboard, so the web build offers
+    /// the image as a PNG download instead.
+    fn copy_diagram_as_image(&mut self, ctx: &egui::Context, selected_only: bool) {
+        const PADDING: f32 = 10.0;
+        const SCALE: f32 = 2.0;
+
+        let Some((v, c)) = self.last_focused_diagram() else {
+            return;
+        };
+        let mut controller = c.write();
+        let Some(s) = self.diagram_settings.get(controller.controller_type()) else {
+            return;
+        };
+
+        let area = if selected_only {
+            let Some(area) = controller.selection_bounds(&v) else {
+                return;
+            };
+            area
+        } else {
+            let painter = egui::Painter::new(
+                ctx.clone(),
+                egui::LayerId::background(),
+                egui::Rect::EVERYTHING,
+            );
+            let mut measuring_canvas = MeasuringCanvas::new(&painter);
+            controller.draw_in(
+                &v,
+                &self.drawing_context,
+                s.as_ref(),
+                &mut measuring_canvas,
+                None,
+            );
+            measuring_canvas.bounds()
+        }
+        .expand(PADDING);
+        if !area.is_positive() {
+            return;
+        }
+        let (background_color, _) = controller.canvas_colors(&v, &self.drawing_context);
+
+        let pixel_size = area.size() * SCALE;
+        let image = raster::render_to_image(
+            ctx,
+            [pixel_size.x.ceil() as usize, pixel_size.y.ceil() as usize],
+            -1.0 * area.min * SCALE,
+            SCALE,
+            Highlight::NONE,
+            |canvas| {
+                canvas.draw_rectangle(
+                    area,
+                    egui::CornerRadius::ZERO,
+                    background_color,
+                    common::canvas::Stroke::NONE,
+                    Highlight::NONE,
+                );
+                controller.draw_in(&v, &self.drawing_context, s.as_ref(), canvas, None);
+            },
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        ctx.copy_image(image);
+        #[cfg(target_arch = "wasm32")]
+        match raster::encode(&image, ImageExportFormat::Png, background_color) {
+            Err(e) => {
+                self.custom_modal = Some(ErrorModal::new_box(format!("Error exporting: {}", e)))
+            }
+            Ok(bytes) => {
+                let d = rfd::AsyncFileDialog::new()
+                    .set_file_name(format!("{}.png", controller.view_name(&v)))
+                    .save_file();
+                execute(async move {
+                    if let Some(fh) = d.await {
+                        let _ = fh.write(&bytes).await;
+                    }
+                });
+            }
+        }
+    }
 }
 
 struct DiagramInfo {
@@ -2528,26 +5248,95 @@ struct NHStoredApp {
     shortcuts: HashMap<SimpleProjectCommand, egui::KeyboardShortcut>,
     selected_shades_profile: usize,
     shades_profiles: Vec<ShadesProfile>,
+    #[serde(default)]
+    macros: Vec<CommandMacro>,
+    #[serde(default)]
+    default_constructors: HashMap<String, String>,
+    #[serde(default)]
+    animate_transitions: Option<bool>,
+    #[serde(default)]
+    snap_tolerance: Option<f32>,
+    #[serde(default)]
+    element_defaults: HashMap<String, ElementDefaults>,
+    #[serde(default)]
+    svg_backup_enabled: bool,
+    #[serde(default)]
+    svg_backup_folder: Option<String>,
 
     diagram_specific_settings: HashMap<String, toml::Value>,
 
     tree: DockState<NHTab>,
 }
 
+/// Whole user configuration, as exported to a JSON file for moving it between machines.
+///
+/// All fields are optional, so that hand-edited or partial profiles can be imported too.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct NHSettingsProfile {
+    zoom_factor: Option<f32>,
+    dark_mode: Option<bool>,
+    style: Option<Style>,
+    tool_palette_item_height: Option<u32>,
+    animate_transitions: Option<bool>,
+    snap_tolerance: Option<f32>,
+    element_defaults: Option<HashMap<String, ElementDefaults>>,
+    svg_backup_enabled: Option<bool>,
+    svg_backup_folder: Option<String>,
+    modifier_settings: Option<ModifierSettings>,
+    shortcuts: Option<Vec<(SimpleProjectCommand, egui::KeyboardShortcut)>>,
+    languages_order: Option<Vec<String>>,
+    selected_shades_profile: Option<usize>,
+    shades_profiles: Option<Vec<ShadesProfile>>,
+    macros: Option<Vec<CommandMacro>>,
+    default_constructors: Option<HashMap<String, String>>,
+    diagram_specific_settings: Option<HashMap<String, toml::Value>>,
+}
+
 impl NHApp {
-    fn load_or_new(cc: &eframe::CreationContext) -> Self {
+    /// App with default settings, for command line modes without the UI
+    #[cfg(not(target_arch = "wasm32"))]
+    fn new_headless() -> Self {
+        Self::new(
+            1.0,
+            NHContext::default_shortcuts(),
+            0,
+            NHContext::default_shades_profiles(),
+            Vec::new(),
+            HashMap::new(),
+            DockState::new(vec![]),
+        )
+    }
+
+    /// Safe mode skips restoring the stored layout and settings,
+    /// which are then replaced by the defaults on exit
+    fn load_or_new(cc: &eframe::CreationContext, safe_mode: bool) -> Self {
         if let Some(value) = cc
             .storage
+            .filter(|_| !safe_mode)
             .and_then(|e| eframe::get_value::<NHStoredApp>(e, eframe::APP_KEY))
         {
-            return Self::new(
+            let mut app = Self::new(
                 value.zoom_factor,
                 value.shortcuts,
                 value.selected_shades_profile,
                 value.shades_profiles,
+                value.macros,
                 value.diagram_specific_settings,
                 value.tree,
             );
+            app.context.default_constructors = value.default_constructors;
+            if let Some(animate_transitions) = value.animate_transitions {
+                app.context.drawing_context.animate_transitions = animate_transitions;
+            }
+            if let Some(snap_tolerance) = value.snap_tolerance {
+                app.context.drawing_context.snap_tolerance = snap_tolerance;
+            }
+            app.context.drawing_context.element_defaults = value.element_defaults;
+            app.context.svg_backup_enabled = value.svg_backup_enabled;
+            if let Some(svg_backup_folder) = value.svg_backup_folder {
+                app.context.svg_backup_folder = svg_backup_folder;
+            }
+            return app;
         }
 
         let mut tree = DockState::new(vec![NHTab::NewDiagram, NHTab::Settings]);
@@ -2564,20 +5353,23 @@ impl NHApp {
         let [_, c] = tree.main_surface_mut().split_right(
             a,
             0.7,
-            vec![NHTab::Properties, NHTab::GlobalColors],
+            vec![NHTab::Properties, NHTab::GlobalColors, NHTab::Symbols],
         );
         let [_, _] = tree
             .main_surface_mut()
             .split_below(b, 0.7, vec![NHTab::Toolbar]);
-        let [_, _] = tree
-            .main_surface_mut()
-            .split_below(c, 0.5, vec![NHTab::Outline]);
+        let [_, _] = tree.main_surface_mut().split_below(
+            c,
+            0.5,
+            vec![NHTab::Outline, NHTab::DocumentOutline],
+        );
 
         Self::new(
             1.0,
             NHContext::default_shortcuts(),
             0,
             NHContext::default_shades_profiles(),
+            Vec::new(),
             HashMap::new(),
             tree,
         )
@@ -2588,6 +5380,7 @@ impl NHApp {
         shortcuts: HashMap<SimpleProjectCommand, egui::KeyboardShortcut>,
         selected_shades_profile: usize,
         shades_profiles: Vec<ShadesProfile>,
+        macros: Vec<CommandMacro>,
         diagram_specific_settings: HashMap<String, toml::Value>,
         tree: DockState<NHTab>,
     ) -> Self {
@@ -2670,6 +5463,9 @@ impl NHApp {
             diagram_deserializers,
             new_diagram_no: 1,
             documents: HashMap::new(),
+            diagram_variants: HashMap::new(),
+            diagram_templates: HashMap::new(),
+            sub_diagrams: HashMap::new(),
             clipboard: Vec::new(),
             custom_tabs: HashMap::new(),
             custom_modal: None,
@@ -2682,20 +5478,34 @@ impl NHApp {
             diagram_settings_functions,
             shades_profiles,
             selected_shades_profile,
+            macro_recorder: MacroRecorder::new(macros),
             selected_language: 0,
-            languages_order,
+            export_language: None,
             modifier_settings: NHContext::default_modifier_settings(),
             drawing_context: GlobalDrawingContext {
                 global_colors: ColorBundle::new(),
+                symbols: SymbolLibrary::default(),
+                images: ImageLibrary::default(),
+                abbreviations: AbbreviationDictionary::default(),
+                outdated_models: HashSet::new(),
+                issue_linked_models: HashSet::new(),
                 fluent_bundle,
+                languages_order,
                 shortcuts,
                 tool_palette_item_height: NHContext::DEFAULT_TOOL_ITEM_HEIGHT,
+                animate_transitions: true,
+                snap_tolerance: NHContext::DEFAULT_SNAP_TOLERANCE,
+                dark_mode: false,
+                element_defaults: HashMap::new(),
                 model_labels: LabelProvider::new(),
             },
 
             new_diagram_data: diagram_type_creation_data,
             new_diagram_selected_kind: 0,
             new_diagram_selected_constructor: 0,
+            default_constructors: HashMap::new(),
+            svg_backup_enabled: false,
+            svg_backup_folder: "exports".to_owned(),
 
             unprocessed_commands: Vec::new(),
             affected_models: HashSet::new(),
@@ -2705,11 +5515,25 @@ impl NHApp {
             shortcut_top_order: vec![],
 
             open_unique_tabs: tree.iter_all_tabs().map(|e| e.1.clone()).collect(),
+            diagram_viewports: HashMap::new(),
             last_focused_diagram: None,
+            last_focused_document: None,
+            document_scroll_request: None,
             svg_export_menu: None,
             confirm_modal_reason: None,
             shortcut_being_set: None,
             new_global_color_name: String::new(),
+            new_layer_name: String::new(),
+            todos: Vec::new(),
+            external_links: Vec::new(),
+            external_files_modified: HashMap::new(),
+            external_links_checked_at: f64::NEG_INFINITY,
+            issue_links: Vec::new(),
+            issue_infos: HashMap::new(),
+            issue_info_channel: std::sync::mpsc::channel(),
+            tasks_filter: String::new(),
+            tasks_min_priority: TodoPriority::Low,
+            symbol_library_editor: SymbolLibraryEditor::default(),
 
             search_query: "".to_owned(),
             search_error: "".to_owned(),
@@ -2830,9 +5654,13 @@ impl NHApp {
         self.tree.retain_tabs(|e| {
             !matches!(
                 e,
-                NHTab::Diagram { .. } | NHTab::Document { .. } | NHTab::CustomTab { .. }
+                NHTab::Diagram { .. }
+                    | NHTab::DiagramViewport { .. }
+                    | NHTab::Document { .. }
+                    | NHTab::CustomTab { .. }
             )
         });
+        self.context.diagram_viewports.clear();
         for e in self.tree.iter_leaves_mut() {
             if e.1.active.0 > e.1.tabs.len() {
                 e.1.active.0 = 0;
@@ -2862,6 +5690,13 @@ impl eframe::App for NHApp {
         let shortcuts = self.context.drawing_context.shortcuts.clone();
         let selected_shades_profile = self.context.selected_shades_profile;
         let shades_profiles = self.context.shades_profiles.clone();
+        let macros = self.context.macro_recorder.macros.clone();
+        let default_constructors = self.context.default_constructors.clone();
+        let animate_transitions = Some(self.context.drawing_context.animate_transitions);
+        let snap_tolerance = Some(self.context.drawing_context.snap_tolerance);
+        let element_defaults = self.context.drawing_context.element_defaults.clone();
+        let svg_backup_enabled = self.context.svg_backup_enabled;
+        let svg_backup_folder = Some(self.context.svg_backup_folder.clone());
 
         let mut diagram_specific_settings = HashMap::new();
         for (k, v) in self.context.diagram_settings.iter() {
@@ -2875,6 +5710,13 @@ impl eframe::App for NHApp {
             shortcuts,
             selected_shades_profile,
             shades_profiles,
+            macros,
+            default_constructors,
+            animate_transitions,
+            snap_tolerance,
+            element_defaults,
+            svg_backup_enabled,
+            svg_backup_folder,
             diagram_specific_settings,
             tree,
         };
@@ -2883,6 +5725,11 @@ impl eframe::App for NHApp {
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+        self.context.drawing_context.dark_mode = ui.ctx().theme() == egui::Theme::Dark;
+        let dropped_files = ui.ctx().input(|i| i.raw.dropped_files.clone());
+        if !dropped_files.is_empty() {
+            self.context.add_dropped_images(dropped_files);
+        }
         while let Ok(e) = self.context.file_io_channel.1.try_recv() {
             fn get_project_path(fh: &FileHandle) -> PathBuf {
                 #[cfg(not(target_arch = "wasm32"))]
@@ -2928,12 +5775,96 @@ impl eframe::App for NHApp {
                         Ok(_) => {
                             self.context.set_project_path(Some(file_path));
                             self.context.set_has_unsaved_changes(false);
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if let Err(e) = self.context.export_svg_backups(ui.ctx()) {
+                                self.context.custom_modal = Some(ErrorModal::new_box(format!(
+                                    "Error exporting SVG backups: {}",
+                                    e
+                                )));
+                            }
+                        }
+                    }
+                }
+                FileIOOperation::ImageExport(fh, v, c, format) => {
+                    self.context.svg_export_menu = Some((
+                        v,
+                        c,
+                        Some(fh),
+                        !format.supports_transparency(),
+                        false,
+                        Highlight::NONE,
+                        10.0,
+                        10.0,
+                        format,
+                        1.0,
+                    ));
+                }
+                FileIOOperation::TextExport(fh, c, format) => match c.read().export_text(
+                    &format,
+                    &match &self.context.export_language {
+                        Some(l) => vec![l.clone()],
+                        None => self.context.drawing_context.languages_order.clone(),
+                    },
+                ) {
+                    None => {
+                        self.context.custom_modal = Some(ErrorModal::new_box(format!(
+                            "Error exporting: {} export is not supported by this diagram",
+                            format.name
+                        )))
+                    }
+                    Some(text) => execute(async move {
+                        let _ = fh.write(text.as_bytes()).await;
+                    }),
+                },
+                FileIOOperation::SettingsExport(fh) => {
+                    match self
+                        .context
+                        .export_settings_profile(ui.ctx().theme() == egui::Theme::Dark)
+                    {
+                        Ok(json) => execute(async move {
+                            let _ = fh.write(json.as_bytes()).await;
+                        }),
+                        Err(e) => {
+                            self.context.custom_modal = Some(ErrorModal::new_box(format!(
+                                "Error exporting settings: {}",
+                                e
+                            )))
+                        }
+                    }
+                }
+                FileIOOperation::SettingsImport(bytes) => {
+                    if let Err(e) = self.context.import_settings_profile(ui.ctx(), &bytes) {
+                        self.context.custom_modal = Some(ErrorModal::new_box(format!(
+                            "Error importing settings: {}",
+                            e
+                        )));
+                    }
+                }
+                FileIOOperation::BackgroundImage(view, name, bytes) => {
+                    match ProjectImage::decode(uuid::Uuid::now_v7(), name, bytes) {
+                        Err(e) => {
+                            self.context.custom_modal =
+                                Some(ErrorModal::new_box(format!("Error opening image: {}", e)))
+                        }
+                        Ok(image) => {
+                            let image_uuid = image.uuid;
+                            self.context.drawing_context.images.insert(image);
+                            self.context.unprocessed_commands.push(
+                                SimpleProjectCommand::SpecificDiagramCommand(
+                                    view,
+                                    DiagramCommand::SetBackgroundImage(Some(image_uuid)),
+                                )
+                                .into(),
+                            );
+                            self.context.set_has_unsaved_changes(true);
                         }
                     }
                 }
-                FileIOOperation::ImageExport(fh, v, c) => {
-                    self.context.svg_export_menu =
-                        Some((v, c, Some(fh), false, false, Highlight::NONE, 10.0, 10.0));
+                FileIOOperation::ImageElement(view, position, name, bytes) => {
+                    self.context.add_image_element(view, position, name, bytes);
+                }
+                FileIOOperation::DocumentImport(name, bytes) => {
+                    self.context.import_document(name, &bytes);
                 }
                 FileIOOperation::Error(e) => {
                     self.context.custom_modal =
@@ -3002,9 +5933,12 @@ impl eframe::App for NHApp {
         }
 
         // Set self.context.last_focused_diagram
-        if let Some((_, NHTab::Diagram { uuid })) = self.tree.find_active_focused() {
+        if let Some(uuid) = self.tree.find_active_focused().and_then(|e| e.1.diagram()) {
             self.context.last_focused_diagram = Some(*uuid);
         }
+        if let Some((_, NHTab::Document { uuid })) = self.tree.find_active_focused() {
+            self.context.last_focused_document = Some(*uuid);
+        }
 
         // Set window title depending on the project path
         if self.context.should_change_title {
@@ -3052,6 +5986,8 @@ impl eframe::App for NHApp {
             let interact_pos = ui.ctx().pointer_interact_pos();
             let input_probably_blocked =
                 self.context.confirm_modal_reason.is_some() || self.context.custom_modal.is_some();
+            // Tab and Enter add elements only when they are not typed into a text field
+            let text_input_focused = ui.ctx().egui_wants_keyboard_input();
             ui.input(|is| {
                 'outer: for e in is.events.iter() {
                     match e {
@@ -3060,7 +5996,7 @@ impl eframe::App for NHApp {
                         {
                             if matches!(
                                 self.tree.find_active_focused(),
-                                Some((_, NHTab::Diagram { .. }))
+                                Some((_, NHTab::Diagram { .. } | NHTab::DiagramViewport { .. }))
                             ) {
                                 commands.push(
                                     SimpleProjectCommand::from(match e {
@@ -3143,11 +6079,18 @@ impl eframe::App for NHApp {
                                             | DiagramCommand::PasteClipboardElements(..) => {
                                                 if matches!(
                                                     self.tree.find_active_focused(),
-                                                    Some((_, NHTab::Diagram { .. }))
+                                                    Some((
+                                                        _,
+                                                        NHTab::Diagram { .. }
+                                                            | NHTab::DiagramViewport { .. }
+                                                    ))
                                                 ) {
                                                     commands.push(e.into())
                                                 }
                                             }
+                                            DiagramCommand::AddChildElement
+                                            | DiagramCommand::AddSiblingElement
+                                                if text_input_focused => {}
                                             _ => commands.push(e.into()),
                                         }
                                     }
@@ -3237,6 +6180,12 @@ impl eframe::App for NHApp {
                     #[cfg(not(target_arch = "wasm32"))]
                     button!(ui, "nh-project-save", SimpleProjectCommand::SaveProject);
                     button!(ui, "nh-project-saveas", SimpleProjectCommand::SaveProjectAs);
+                    if ui
+                        .button(translate!("nh-project-generatedocumentation"))
+                        .clicked()
+                    {
+                        commands.push(ProjectCommand::GenerateDocumentation);
+                    }
                     ui.separator();
                     button!(
                         ui,
@@ -3247,13 +6196,14 @@ impl eframe::App for NHApp {
                     button!(ui, "nh-project-exit", SimpleProjectCommand::Exit(false));
                 });
 
+                let focused_diagram = self.context.last_focused_diagram();
                 ui.menu_button(translate!("nh-edit"), |ui| {
                     ui.set_min_width(MIN_MENU_WIDTH);
 
                     ui.menu_button(translate!("nh-edit-undo"), |ui| {
                         ui.set_min_width(MIN_MENU_WIDTH);
 
-                        if let Some(e) = self.context.last_focused_diagram() {
+                        if let Some(e) = focused_diagram.clone() {
                             e.1.read().show_undo_stack(
                                 &self.context.drawing_context,
                                 ui,
@@ -3265,7 +6215,7 @@ impl eframe::App for NHApp {
                     ui.menu_button(translate!("nh-edit-redo"), |ui| {
                         ui.set_min_width(MIN_MENU_WIDTH);
 
-                        if let Some(e) = self.context.last_focused_diagram() {
+                        if let Some(e) = focused_diagram.clone() {
                             e.1.read().show_redo_stack(
                                 &self.context.drawing_context,
                                 ui,
@@ -3292,6 +6242,40 @@ impl eframe::App for NHApp {
                             None, None
                         ))
                     );
+                    button!(
+                        ui,
+                        "nh-edit-copyassvg",
+                        SimpleProjectCommand::from(DiagramCommand::CopySelectedAsSvg)
+                    );
+                    button!(
+                        ui,
+                        "nh-edit-copyasplantuml",
+                        SimpleProjectCommand::from(DiagramCommand::CopySelectedAsPlantUml)
+                    );
+                    button!(
+                        ui,
+                        "nh-edit-copylayout",
+                        SimpleProjectCommand::from(DiagramCommand::CopyLayoutAsJson)
+                    );
+                    button!(
+                        ui,
+                        "nh-edit-applylayout",
+                        SimpleProjectCommand::from(DiagramCommand::ApplyLayoutFromJson)
+                    );
+                    button!(
+                        ui,
+                        "nh-edit-copyasimage",
+                        SimpleProjectCommand::CopyAsImage {
+                            selected_only: false
+                        }
+                    );
+                    button!(
+                        ui,
+                        "nh-edit-copyselectionasimage",
+                        SimpleProjectCommand::CopyAsImage {
+                            selected_only: true
+                        }
+                    );
                     ui.separator();
 
                     ui.menu_button(translate!("nh-edit-delete"), |ui| {
@@ -3320,7 +6304,7 @@ impl eframe::App for NHApp {
                     });
                     ui.separator();
 
-                    if let Some((v, c)) = self.context.last_focused_diagram() {
+                    if let Some((v, c)) = focused_diagram.clone() {
                         c.write().show_menubar_edit_options(
                             &v,
                             &self.context.drawing_context,
@@ -3360,6 +6344,41 @@ impl eframe::App for NHApp {
                             ))
                         );
                     });
+                    ui.menu_button(translate!("nh-edit-matchsize"), |ui| {
+                        ui.set_min_width(MIN_MENU_WIDTH);
+                        button!(
+                            ui,
+                            "nh-edit-matchsize-width",
+                            SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(
+                                SizeMatch::Width
+                            ))
+                        );
+                        button!(
+                            ui,
+                            "nh-edit-matchsize-height",
+                            SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(
+                                SizeMatch::Height
+                            ))
+                        );
+                        button!(
+                            ui,
+                            "nh-edit-matchsize-both",
+                            SimpleProjectCommand::from(DiagramCommand::MatchSizeSelected(
+                                SizeMatch::Both
+                            ))
+                        );
+                    });
+                    ui.separator();
+
+                    ui.menu_button(translate!("nh-edit-macros"), |ui| {
+                        ui.set_min_width(MIN_MENU_WIDTH);
+                        self.context.macro_recorder.show_menu(
+                            &self.context.drawing_context,
+                            focused_diagram.clone(),
+                            ui,
+                            &mut commands,
+                        );
+                    });
                 });
 
                 ui.menu_button(translate!("nh-view"), |ui| {
@@ -3372,6 +6391,16 @@ impl eframe::App for NHApp {
                     let Some(s) = self.context.diagram_settings.get(ctype) else {
                         return;
                     };
+                    if ui.button(translate!("nh-view-newviewport")).clicked() {
+                        commands.push(ProjectCommand::OpenAndFocusTab(
+                            NHTab::DiagramViewport {
+                                uuid: v,
+                                viewport: uuid::Uuid::now_v7(),
+                            },
+                            None,
+                        ));
+                    }
+                    ui.separator();
                     let mut c = c.write();
 
                     c.show_menubar_view_options(
@@ -3383,6 +6412,7 @@ impl eframe::App for NHApp {
                     );
                 });
 
+                let mut export_language = self.context.export_language.clone();
                 ui.menu_button(translate!("nh-diagram"), |ui| {
                     ui.set_min_width(MIN_MENU_WIDTH);
 
@@ -3398,6 +6428,24 @@ impl eframe::App for NHApp {
                         &mut commands,
                     );
 
+                    ui.menu_button(translate!("nh-diagram-autolayout"), |ui| {
+                        ui.set_min_width(MIN_MENU_WIDTH);
+                        button!(
+                            ui,
+                            "nh-diagram-autolayout-layered",
+                            SimpleProjectCommand::from(DiagramCommand::LayoutLayered {
+                                upward: false
+                            })
+                        );
+                        button!(
+                            ui,
+                            "nh-diagram-autolayout-layeredupward",
+                            SimpleProjectCommand::from(DiagramCommand::LayoutLayered {
+                                upward: true
+                            })
+                        );
+                    });
+
                     let export_label = {
                         let name = c2.view_name(&v);
                         let b = &self.context.drawing_context.fluent_bundle;
@@ -3416,24 +6464,83 @@ impl eframe::App for NHApp {
                     ui.menu_button(export_label, |ui| {
                         ui.set_min_width(MIN_MENU_WIDTH);
 
-                        if ui.button("SVG").clicked() {
-                            let d = rfd::AsyncFileDialog::new()
-                                .set_file_name(format!("{}.svg", c2.view_name(&v)))
-                                .add_filter("SVG files", &["svg"])
-                                .add_filter("All files", &["*"])
-                                .save_file();
-                            let s = self.context.file_io_channel.0.clone();
-                            let c = c.clone();
-                            execute(async move {
-                                if let Some(fh) = d.await {
-                                    let _ = s.send(FileIOOperation::ImageExport(fh, v, c));
-                                }
-                            });
+                        for format in ImageExportFormat::ALL {
+                            if ui.button(format.name()).clicked() {
+                                let d = rfd::AsyncFileDialog::new()
+                                    .set_file_name(format!(
+                                        "{}.{}",
+                                        c2.view_name(&v),
+                                        format.extensions()[0]
+                                    ))
+                                    .add_filter(
+                                        format!("{} files", format.name()),
+                                        format.extensions(),
+                                    )
+                                    .add_filter("All files", &["*"])
+                                    .save_file();
+                                let s = self.context.file_io_channel.0.clone();
+                                let c = c.clone();
+                                execute(async move {
+                                    if let Some(fh) = d.await {
+                                        let _ =
+                                            s.send(FileIOOperation::ImageExport(fh, v, c, format));
+                                    }
+                                });
 
+                                ui.close();
+                            }
+                        }
+                        if ui.button("Interactive HTML").clicked() {
+                            commands.push(ProjectCommand::ExportInteractiveHtml(v));
                             ui.close();
                         }
+
+                        let text_formats = c2.text_export_formats();
+                        if !text_formats.is_empty() {
+                            ui.separator();
+
+                            egui::ComboBox::from_label("Content language")
+                                .selected_text(match &export_language {
+                                    Some(l) => l.to_string(),
+                                    None => "As displayed".to_owned(),
+                                })
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut export_language, None, "As displayed");
+                                    for (l, _) in common::fluent::AVAILABLE_LANGUAGES {
+                                        ui.selectable_value(
+                                            &mut export_language,
+                                            Some(l.clone()),
+                                            l.to_string(),
+                                        );
+                                    }
+                                });
+                        }
+                        for format in text_formats {
+                            if ui.button(format.name).clicked() {
+                                let d = rfd::AsyncFileDialog::new()
+                                    .set_file_name(format!(
+                                        "{}.{}",
+                                        c2.view_name(&v),
+                                        format.extensions[0]
+                                    ))
+                                    .add_filter(format!("{} files", format.name), format.extensions)
+                                    .add_filter("All files", &["*"])
+                                    .save_file();
+                                let s = self.context.file_io_channel.0.clone();
+                                let c = c.clone();
+                                let format = *format;
+                                execute(async move {
+                                    if let Some(fh) = d.await {
+                                        let _ = s.send(FileIOOperation::TextExport(fh, c, format));
+                                    }
+                                });
+
+                                ui.close();
+                            }
+                        }
                     });
                 });
+                self.context.export_language = export_language;
 
                 ui.menu_button(translate!("nh-windows"), |ui| {
                     ui.set_min_width(MIN_MENU_WIDTH);
@@ -3449,7 +6556,15 @@ impl eframe::App for NHApp {
                         NHTab::Toolbar,
                         NHTab::Properties,
                         NHTab::GlobalColors,
+                        NHTab::Symbols,
+                        NHTab::Abbreviations,
+                        NHTab::Tasks,
+                        NHTab::ExternalLinks,
+                        NHTab::IssueLinks,
+                        NHTab::DrawOrder,
+                        NHTab::Layers,
                         NHTab::Outline,
+                        NHTab::DocumentOutline,
                     ] {
                         if ui
                             .selectable_label(
@@ -3493,14 +6608,26 @@ impl eframe::App for NHApp {
 
         // SVG export options modal
         let mut hide_svg_export_modal = false;
-        if let Some((v, c, fh, background, gridlines, highlight, padding_x, padding_y)) =
-            self.context.svg_export_menu.as_mut()
+        if let Some((
+            v,
+            c,
+            fh,
+            background,
+            gridlines,
+            highlight,
+            padding_x,
+            padding_y,
+            format,
+            scale,
+        )) = self.context.svg_export_menu.as_mut()
         {
             let ctype = c.read().controller_type();
             let Some(s) = self.context.diagram_settings.get(ctype) else {
                 return;
             };
             let mut controller = c.write();
+            let (background_color, gridlines_color) =
+                controller.canvas_colors(v, &self.context.drawing_context);
 
             egui::containers::Window::new(
                 self.context
@@ -3510,11 +6637,14 @@ impl eframe::App for NHApp {
             .id(egui::Id::from("SVG Export Options"))
             .show(ui.ctx(), |ui| {
                 // Change options
-                ui.checkbox(
-                    background,
-                    self.context
-                        .drawing_context
-                        .translate_0("nh-window-svgexport-solidbackground"),
+                ui.add_enabled(
+                    format.supports_transparency(),
+                    egui::Checkbox::new(
+                        background,
+                        self.context
+                            .drawing_context
+                            .translate_0("nh-window-svgexport-solidbackground"),
+                    ),
                 );
                 ui.checkbox(
                     gridlines,
@@ -3564,6 +6694,15 @@ impl eframe::App for NHApp {
                             .translate_0("nh-window-svgexport-verticalpadding"),
                     ),
                 );
+                if format.is_raster() {
+                    ui.add(
+                        egui::Slider::new(scale, 0.25..=8.0).logarithmic(true).text(
+                            self.context
+                                .drawing_context
+                                .translate_0("nh-window-svgexport-scale"),
+                        ),
+                    );
+                }
 
                 ui.separator();
 
@@ -3580,6 +6719,27 @@ impl eframe::App for NHApp {
                     );
                     let diagram_bounds = measuring_canvas.bounds();
 
+                    if format.is_raster() {
+                        let size = (diagram_bounds.size()
+                            + egui::Vec2::new(2.0 * *padding_x, 2.0 * *padding_y))
+                            * *scale;
+                        let mut args = fluent_bundle::FluentArgs::new();
+                        args.set("dpi", (96.0 * *scale).round());
+                        args.set("width", size.x.ceil());
+                        args.set("height", size.y.ceil());
+                        let b = &self.context.drawing_context.fluent_bundle;
+                        ui.label(
+                            b.format_pattern(
+                                b.get_message("nh-window-svgexport-rastersize")
+                                    .unwrap()
+                                    .value()
+                                    .unwrap(),
+                                Some(&args),
+                                &mut vec![],
+                            ),
+                        );
+                    }
+
                     let preview_width = ui.available_width();
                     let camera_scale = preview_width / (diagram_bounds.width() + 2.0 * *padding_x);
                     let preview_height = preview_width
@@ -3597,7 +6757,7 @@ impl eframe::App for NHApp {
                         painter.rect(
                             canvas_rect,
                             egui::CornerRadius::ZERO,
-                            egui::Color32::WHITE, // TODO: load the actual background color
+                            background_color,
                             egui::Stroke::NONE,
                             egui::StrokeKind::Middle,
                         );
@@ -3637,10 +6797,8 @@ impl eframe::App for NHApp {
                         (false, false),
                     );
                     if *gridlines {
-                        ui_canvas.draw_gridlines(
-                            Some((50.0, egui::Color32::from_rgb(220, 220, 220))),
-                            Some((50.0, egui::Color32::from_rgb(220, 220, 220))),
-                        );
+                        let gridlines = Some((controller.grid(v).size, gridlines_color));
+                        ui_canvas.draw_gridlines(gridlines, gridlines);
                     }
                     controller.draw_in(
                         v,
@@ -3672,31 +6830,97 @@ impl eframe::App for NHApp {
                             + egui::Vec2::new(*padding_x, *padding_y);
                         let canvas_size = measuring_canvas.bounds().size()
                             + egui::Vec2::new(2.0 * *padding_x, 2.0 * *padding_y);
-                        let mut svg_canvas =
-                            SVGCanvas::new(canvas_offset, canvas_size, *highlight, ui.painter());
-                        if *background {
-                            svg_canvas.draw_rectangle(
-                                egui::Rect::from_min_size(-1.0 * canvas_offset, canvas_size),
-                                egui::CornerRadius::ZERO,
-                                egui::Color32::WHITE, // TODO: load the actual background color
-                                common::canvas::Stroke::NONE,
-                                common::canvas::Highlight::NONE,
+                        let fh = fh.take().unwrap();
+
+                        if format.is_raster() {
+                            let pixel_size = canvas_size * *scale;
+                            let image = raster::render_to_image(
+                                ui.ctx(),
+                                [pixel_size.x.ceil() as usize, pixel_size.y.ceil() as usize],
+                                canvas_offset * *scale,
+                                *scale,
+                                *highlight,
+                                |canvas| {
+                                    if *background {
+                                        canvas.draw_rectangle(
+                                            egui::Rect::from_min_size(
+                                                -1.0 * canvas_offset,
+                                                canvas_size,
+                                            ),
+                                            egui::CornerRadius::ZERO,
+                                            background_color,
+                                            common::canvas::Stroke::NONE,
+                                            common::canvas::Highlight::NONE,
+                                        );
+                                    }
+                                    if *gridlines {
+                                        let gridlines =
+                                            Some((controller.grid(v).size, gridlines_color));
+                                        canvas.draw_gridlines(gridlines, gridlines);
+                                    }
+                                    controller.draw_in(
+                                        v,
+                                        &self.context.drawing_context,
+                                        s.as_ref(),
+                                        canvas,
+                                        None,
+                                    );
+                                },
+                            );
+                            match raster::encode(&image, *format, background_color) {
+                                Err(e) => {
+                                    self.context.custom_modal =
+                                        Some(ErrorModal::new_box(format!("Error exporting: {}", e)))
+                                }
+                                Ok(bytes) => execute(async move {
+                                    let _ = fh.write(&bytes).await;
+                                }),
+                            }
+                        } else if *format == ImageExportFormat::DrawIo {
+                            let mut drawio_canvas =
+                                DrawioCanvas::new(canvas_offset, *highlight, ui.painter());
+                            controller.draw_in(
+                                v,
+                                &self.context.drawing_context,
+                                s.as_ref(),
+                                &mut drawio_canvas,
+                                None,
                             );
-                        }
-                        controller.draw_in(
-                            v,
-                            &self.context.drawing_context,
-                            s.as_ref(),
-                            &mut svg_canvas,
-                            None,
-                        );
 
-                        let fh = fh.take().unwrap();
-                        match svg_canvas.into_bytes() {
-                            Err(_) => todo!(),
-                            Ok(bytes) => execute(async move {
+                            let bytes = drawio_canvas.into_bytes(&controller.view_name(v));
+                            execute(async move {
                                 let _ = fh.write(&bytes).await;
-                            }),
+                            });
+                        } else {
+                            let mut svg_canvas = SVGCanvas::new(
+                                canvas_offset,
+                                canvas_size,
+                                *highlight,
+                                ui.painter(),
+                            );
+                            if *background {
+                                svg_canvas.draw_rectangle(
+                                    egui::Rect::from_min_size(-1.0 * canvas_offset, canvas_size),
+                                    egui::CornerRadius::ZERO,
+                                    background_color,
+                                    common::canvas::Stroke::NONE,
+                                    common::canvas::Highlight::NONE,
+                                );
+                            }
+                            controller.draw_in(
+                                v,
+                                &self.context.drawing_context,
+                                s.as_ref(),
+                                &mut svg_canvas,
+                                None,
+                            );
+
+                            match svg_canvas.into_bytes() {
+                                Err(_) => todo!(),
+                                Ok(bytes) => execute(async move {
+                                    let _ = fh.write(&bytes).await;
+                                }),
+                            }
                         }
 
                         hide_svg_export_modal = true;
@@ -3886,13 +7110,44 @@ impl eframe::App for NHApp {
 
         macro_rules! send_to_focused_diagram {
             ($command:expr) => {
-                if let Some((_, NHTab::Diagram { uuid })) = self.tree.find_active_focused() {
-                    send_to_diagram!(uuid, $command);
+                match self.tree.find_active_focused() {
+                    Some((_, NHTab::Diagram { uuid })) => {
+                        send_to_diagram!(uuid, $command);
+                    }
+                    // Camera commands move the camera of the viewport
+                    Some((_, NHTab::DiagramViewport { uuid, viewport })) => {
+                        if let Some(ac) = self.context.diagram_controllers.get(uuid) {
+                            let mut camera = self
+                                .context
+                                .diagram_viewports
+                                .remove(viewport)
+                                .unwrap_or_default();
+                            let mut ac = ac.write();
+                            ac.swap_camera(uuid, &mut camera);
+                            ac.apply_diagram_command(
+                                uuid,
+                                $command,
+                                &mut self.context.clipboard,
+                                &mut self.context.affected_models,
+                            );
+                            ac.swap_camera(uuid, &mut camera);
+                            self.context.diagram_viewports.insert(*viewport, camera);
+                        }
+                    }
+                    _ => {}
                 }
             };
         }
 
         for c in commands {
+            if let ProjectCommand::SimpleProjectCommand(
+                SimpleProjectCommand::FocusedDiagramCommand(dc),
+            ) = &c
+                && CommandMacro::is_recordable(dc)
+            {
+                self.context.macro_recorder.record(MacroStep::Command(*dc));
+            }
+
             match c {
                 ProjectCommand::SimpleProjectCommand(spc) => match spc {
                     SimpleProjectCommand::FocusedDiagramCommand(dc) => match dc {
@@ -3909,24 +7164,39 @@ impl eframe::App for NHApp {
                                 ),
                             }
                         }
+                        DiagramCommand::CopySelectedAsSvg
+                        | DiagramCommand::CopySelectedAsPlantUml => {
+                            self.context.copy_selection_as_text(ui.ctx(), dc);
+                        }
+                        DiagramCommand::CopyLayoutAsJson => {
+                            self.context.copy_layout_as_json(ui.ctx());
+                        }
+                        DiagramCommand::ApplyLayoutFromJson => {
+                            self.context.open_layout_import();
+                        }
                         dc => send_to_focused_diagram!(dc),
                     },
                     SimpleProjectCommand::SpecificDiagramCommand(v, dc) => {
                         send_to_diagram!(&v, dc);
                     }
                     SimpleProjectCommand::SwapTopLanguages => {
-                        if self.context.languages_order.len() > 1 {
-                            self.context.languages_order.swap(0, 1);
+                        if self.context.drawing_context.languages_order.len() > 1 {
+                            self.context.drawing_context.languages_order.swap(0, 1);
                         }
                         self.context.drawing_context.fluent_bundle =
-                            common::fluent::create_fluent_bundle(&self.context.languages_order)
-                                .unwrap();
+                            common::fluent::create_fluent_bundle(
+                                &self.context.drawing_context.languages_order,
+                            )
+                            .unwrap();
                     }
                     SimpleProjectCommand::CycleShadesProfiles => {
                         self.context.selected_shades_profile =
                             (self.context.selected_shades_profile + 1)
                                 % self.context.shades_profiles.len();
                     }
+                    SimpleProjectCommand::CopyAsImage { selected_only } => {
+                        self.context.copy_diagram_as_image(ui.ctx(), selected_only);
+                    }
                     SimpleProjectCommand::OpenProject(b) => {
                         if !self.context.has_unsaved_changes || b {
                             let mut dialog = rfd::AsyncFileDialog::new();
@@ -3982,6 +7252,11 @@ impl eframe::App for NHApp {
                                 }
                                 Ok(_) => {
                                     self.context.set_has_unsaved_changes(false);
+                                    if let Err(e) = self.context.export_svg_backups(ui.ctx()) {
+                                        self.context.custom_modal = Some(ErrorModal::new_box(
+                                            format!("Error exporting SVG backups: {}", e),
+                                        ));
+                                    }
                                 }
                             }
                             continue;
@@ -4072,12 +7347,429 @@ impl eframe::App for NHApp {
                 ProjectCommand::DeleteDiagram(view_uuid) => {
                     self.context.project_hierarchy.remove(&view_uuid);
                     self.context.diagram_controllers.remove(&view_uuid);
+                    self.context
+                        .diagram_variants
+                        .retain(|k, v| *k != view_uuid && *v != view_uuid);
+                    self.context
+                        .diagram_templates
+                        .retain(|_, v| *v != view_uuid);
+                    self.context.sub_diagrams.retain(|_, v| *v != view_uuid);
                     self.context
                         .last_focused_diagram
                         .take_if(|e| *e == view_uuid);
                     if let Some(snt) = self.tree.find_tab(&NHTab::Diagram { uuid: view_uuid }) {
                         self.tree.remove_tab(snt);
                     }
+                    self.tree.retain_tabs(
+                        |e| !matches!(e, NHTab::DiagramViewport { uuid, .. } if *uuid == view_uuid),
+                    );
+                }
+                ProjectCommand::MarkDiagramVariant(variant, origin) => {
+                    self.context.diagram_variants.insert(variant, origin);
+                }
+                ProjectCommand::SetDiagramTemplate(view_uuid, is_template) => {
+                    let Some(c) = self.context.diagram_controllers.get(&view_uuid) else {
+                        continue;
+                    };
+                    let controller_type = c.read().controller_type().to_owned();
+                    if is_template {
+                        self.context
+                            .diagram_templates
+                            .insert(controller_type, view_uuid);
+                    } else {
+                        self.context
+                            .diagram_templates
+                            .retain(|_, v| *v != view_uuid);
+                    }
+                    self.context.set_has_unsaved_changes(true);
+                }
+                ProjectCommand::RefreshModels(uuids) => {
+                    self.context.affected_models.extend(uuids);
+                }
+                ProjectCommand::AddExternalLink(element) => {
+                    self.context.external_links.push(ExternalLink::new(element));
+                    self.context.set_has_unsaved_changes(true);
+                }
+                ProjectCommand::AddIssueLink(element) => {
+                    self.context.issue_links.push(IssueLink::new(element));
+                    self.context.set_has_unsaved_changes(true);
+                }
+                ProjectCommand::LinkSubDiagram(element) => {
+                    struct SubDiagramModal {
+                        element: ModelUuid,
+                        selected: Option<ViewUuid>,
+                        diagrams: Vec<(ViewUuid, Arc<String>)>,
+                    }
+
+                    impl CustomModal for SubDiagramModal {
+                        fn show(
+                            &mut self,
+                            gdc: &mut GlobalDrawingContext,
+                            ui: &mut egui::Ui,
+                            commands: &mut Vec<ProjectCommand>,
+                        ) -> CustomModalResult {
+                            ui.label(gdc.translate_0("nh-subdiagram"));
+                            let none = gdc.translate_0("nh-subdiagram-none");
+                            let selected_name = self
+                                .diagrams
+                                .iter()
+                                .find(|e| Some(e.0) == self.selected)
+                                .map(|e| e.1.as_str())
+                                .unwrap_or(&none);
+                            egui::ComboBox::from_id_salt("sub-diagram")
+                                .selected_text(selected_name)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut self.selected, None, none.as_ref());
+                                    for (uuid, name) in &self.diagrams {
+                                        ui.selectable_value(
+                                            &mut self.selected,
+                                            Some(*uuid),
+                                            name.as_str(),
+                                        );
+                                    }
+                                });
+
+                            let mut result = CustomModalResult::KeepOpen;
+                            ui.horizontal(|ui| {
+                                if ui.button(gdc.translate_0("nh-generic-ok")).clicked() {
+                                    commands.push(ProjectCommand::SetSubDiagram(
+                                        self.element,
+                                        self.selected,
+                                    ));
+                                    result = CustomModalResult::CloseUnmodified;
+                                }
+                                if ui.button(gdc.translate_0("nh-generic-cancel")).clicked() {
+                                    result = CustomModalResult::CloseUnmodified;
+                                }
+                            });
+                            result
+                        }
+                    }
+
+                    let mut diagrams: Vec<_> = self
+                        .context
+                        .diagram_controllers
+                        .iter()
+                        .map(|(k, v)| (*k, v.read().view_name(k)))
+                        .collect();
+                    diagrams.sort_by(|a, b| a.1.cmp(&b.1));
+                    self.context.custom_modal = Some(Box::new(SubDiagramModal {
+                        element,
+                        selected: self.context.sub_diagrams.get(&element).copied(),
+                        diagrams,
+                    }));
+                }
+                ProjectCommand::LinkElement(diagram, element) => {
+                    #[derive(PartialEq)]
+                    enum LinkKind {
+                        None,
+                        Diagram,
+                        Document,
+                        Url,
+                    }
+
+                    struct ElementLinkModal {
+                        diagram: ViewUuid,
+                        element: ViewUuid,
+                        kind: LinkKind,
+                        selected_diagram: Option<ViewUuid>,
+                        selected_document: Option<ViewUuid>,
+                        url: String,
+                        diagrams: Vec<(ViewUuid, String)>,
+                        documents: Vec<(ViewUuid, String)>,
+                    }
+
+                    impl CustomModal for ElementLinkModal {
+                        fn show(
+                            &mut self,
+                            gdc: &mut GlobalDrawingContext,
+                            ui: &mut egui::Ui,
+                            commands: &mut Vec<ProjectCommand>,
+                        ) -> CustomModalResult {
+                            ui.label(gdc.translate_0("nh-elementlink"));
+                            ui.horizontal(|ui| {
+                                for (k, label) in [
+                                    (LinkKind::None, "nh-elementlink-none"),
+                                    (LinkKind::Diagram, "nh-elementlink-diagram"),
+                                    (LinkKind::Document, "nh-elementlink-document"),
+                                    (LinkKind::Url, "nh-elementlink-url"),
+                                ] {
+                                    ui.radio_value(&mut self.kind, k, gdc.translate_0(label));
+                                }
+                            });
+
+                            fn combo(
+                                ui: &mut egui::Ui,
+                                id: &str,
+                                selected: &mut Option<ViewUuid>,
+                                options: &[(ViewUuid, String)],
+                            ) {
+                                let selected_name = options
+                                    .iter()
+                                    .find(|e| Some(e.0) == *selected)
+                                    .map(|e| e.1.as_str())
+                                    .unwrap_or("");
+                                egui::ComboBox::from_id_salt(id)
+                                    .selected_text(selected_name)
+                                    .show_ui(ui, |ui| {
+                                        for (uuid, name) in options {
+                                            ui.selectable_value(selected, Some(*uuid), name);
+                                        }
+                                    });
+                            }
+                            let link = match self.kind {
+                                LinkKind::None => Some(None),
+                                LinkKind::Diagram => {
+                                    combo(
+                                        ui,
+                                        "link diagram",
+                                        &mut self.selected_diagram,
+                                        &self.diagrams,
+                                    );
+                                    self.selected_diagram.map(|e| Some(ElementLink::Diagram(e)))
+                                }
+                                LinkKind::Document => {
+                                    combo(
+                                        ui,
+                                        "link document",
+                                        &mut self.selected_document,
+                                        &self.documents,
+                                    );
+                                    self.selected_document
+                                        .map(|e| Some(ElementLink::Document(e)))
+                                }
+                                LinkKind::Url => {
+                                    ui.text_edit_singleline(&mut self.url);
+                                    (!self.url.trim().is_empty())
+                                        .then(|| Some(ElementLink::Url(self.url.trim().to_owned())))
+                                }
+                            };
+
+                            let mut result = CustomModalResult::KeepOpen;
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .add_enabled(
+                                        link.is_some(),
+                                        egui::Button::new(gdc.translate_0("nh-generic-ok")),
+                                    )
+                                    .clicked()
+                                    && let Some(link) = link
+                                {
+                                    commands.push(ProjectCommand::SetElementLink(
+                                        self.diagram,
+                                        self.element,
+                                        link,
+                                    ));
+                                    result = CustomModalResult::CloseUnmodified;
+                                }
+                                if ui.button(gdc.translate_0("nh-generic-cancel")).clicked() {
+                                    result = CustomModalResult::CloseUnmodified;
+                                }
+                            });
+                            result
+                        }
+                    }
+
+                    let Some(c) = self.context.diagram_controllers.get(&diagram) else {
+                        continue;
+                    };
+                    let current = c.read().element_link(&diagram, &element);
+                    let mut diagrams: Vec<_> = self
+                        .context
+                        .diagram_controllers
+                        .iter()
+                        .map(|(k, v)| (*k, (*v.read().view_name(k)).clone()))
+                        .collect();
+                    diagrams.sort_by(|a, b| a.1.cmp(&b.1));
+                    let mut documents: Vec<_> = self
+                        .context
+                        .documents
+                        .iter()
+                        .map(|(k, v)| (*k, v.0.clone()))
+                        .collect();
+                    documents.sort_by(|a, b| a.1.cmp(&b.1));
+                    let mut modal = ElementLinkModal {
+                        diagram,
+                        element,
+                        kind: LinkKind::None,
+                        selected_diagram: None,
+                        selected_document: None,
+                        url: String::new(),
+                        diagrams,
+                        documents,
+                    };
+                    match current {
+                        Some(ElementLink::Diagram(e)) => {
+                            modal.kind = LinkKind::Diagram;
+                            modal.selected_diagram = Some(e);
+                        }
+                        Some(ElementLink::Document(e)) => {
+                            modal.kind = LinkKind::Document;
+                            modal.selected_document = Some(e);
+                        }
+                        Some(ElementLink::Url(e)) => {
+                            modal.kind = LinkKind::Url;
+                            modal.url = e;
+                        }
+                        None => {}
+                    }
+                    self.context.custom_modal = Some(Box::new(modal));
+                }
+                ProjectCommand::SetElementLink(diagram, element, link) => {
+                    if let Some(c) = self.context.diagram_controllers.get(&diagram) {
+                        c.write().set_element_link(&diagram, element, link);
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::AddLayer(diagram, name) => {
+                    if let Some(c) = self.context.diagram_controllers.get(&diagram) {
+                        c.write().add_layer(&diagram, name);
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::RenameLayer(diagram, layer, name) => {
+                    if let Some(c) = self.context.diagram_controllers.get(&diagram) {
+                        c.write().rename_layer(&diagram, &layer, name);
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::SetSubDiagram(element, diagram) => {
+                    match diagram {
+                        Some(diagram) => self.context.sub_diagrams.insert(element, diagram),
+                        None => self.context.sub_diagrams.remove(&element),
+                    };
+                    self.context.set_has_unsaved_changes(true);
+                }
+                ProjectCommand::SetBackgroundImage(view) => {
+                    let d = rfd::AsyncFileDialog::new()
+                        .add_filter("Images", &["png", "jpg", "jpeg"])
+                        .add_filter("All files", &["*"])
+                        .pick_file();
+                    let s = self.context.file_io_channel.0.clone();
+                    execute(async move {
+                        if let Some(fh) = d.await {
+                            let name = fh.file_name();
+                            let _ = s.send(FileIOOperation::BackgroundImage(
+                                view,
+                                name,
+                                fh.read().await,
+                            ));
+                        }
+                    });
+                }
+                ProjectCommand::AddImageElement(view, position) => {
+                    let d = rfd::AsyncFileDialog::new()
+                        .add_filter("Images", &["png", "jpg", "jpeg"])
+                        .add_filter("All files", &["*"])
+                        .pick_file();
+                    let s = self.context.file_io_channel.0.clone();
+                    execute(async move {
+                        if let Some(fh) = d.await {
+                            let name = fh.file_name();
+                            let _ = s.send(FileIOOperation::ImageElement(
+                                view,
+                                Some(position),
+                                name,
+                                fh.read().await,
+                            ));
+                        }
+                    });
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                ProjectCommand::PasteImageElement(view, position) => {
+                    let image = arboard::Clipboard::new()
+                        .and_then(|mut e| e.get_image())
+                        .map_err(|e| e.to_string())
+                        .and_then(|e| common::images::encode_png([e.width, e.height], &e.bytes));
+                    match image {
+                        Err(e) => {
+                            self.context.custom_modal =
+                                Some(ErrorModal::new_box(format!("Error pasting image: {}", e)))
+                        }
+                        Ok(bytes) => self.context.add_image_element(
+                            view,
+                            Some(position),
+                            "clipboard.png".to_owned(),
+                            bytes,
+                        ),
+                    }
+                }
+                #[cfg(target_arch = "wasm32")]
+                ProjectCommand::PasteImageElement(..) => {}
+                ProjectCommand::PlaceSymbol(symbol, target) => {
+                    let (view, position) = match target {
+                        Some((view, position)) => (Some(view), Some(position)),
+                        None => (self.context.last_focused_diagram, None),
+                    };
+                    if let Some(view) = view
+                        && let Some(c) = self.context.diagram_controllers.get(&view)
+                        && let Some(s) = self.context.drawing_context.symbols.get(&symbol)
+                    {
+                        let position = position.filter(|e| e.is_finite());
+                        c.write()
+                            .add_symbol_element(&view, symbol, s.view_box, position);
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::WriteFiles(files) => {
+                    let s = self.context.file_io_channel.0.clone();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        let d = rfd::AsyncFileDialog::new().pick_folder();
+                        execute(async move {
+                            let Some(folder) = d.await else {
+                                return;
+                            };
+                            for (name, content) in files {
+                                if let Err(e) = std::fs::write(folder.path().join(&name), content) {
+                                    let _ = s.send(FileIOOperation::Error(format!(
+                                        "writing {}: {}",
+                                        name, e
+                                    )));
+                                    return;
+                                }
+                            }
+                        });
+                    }
+                    // Folders cannot be picked in browsers, each file is saved separately
+                    #[cfg(target_arch = "wasm32")]
+                    execute(async move {
+                        for (name, content) in files {
+                            let d = rfd::AsyncFileDialog::new().set_file_name(&name).save_file();
+                            if let Some(fh) = d.await
+                                && let Err(e) = fh.write(content.as_bytes()).await
+                            {
+                                let _ = s.send(FileIOOperation::Error(format!(
+                                    "writing {}: {}",
+                                    name, e
+                                )));
+                                return;
+                            }
+                        }
+                    });
+                }
+                ProjectCommand::ApplyLayout(view, layout) => {
+                    if let Some(c) = self.context.diagram_controllers.get(&view) {
+                        c.write()
+                            .apply_layout(&view, &layout, &mut self.context.affected_models);
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::ApplyPropertyChange(view, models, change) => {
+                    if let Some(c) = self.context.diagram_controllers.get(&view)
+                        && c.write().apply_property_change(
+                            &view,
+                            models.as_deref(),
+                            &change,
+                            &mut self.context.affected_models,
+                        )
+                    {
+                        self.context.set_has_unsaved_changes(true);
+                    }
+                }
+                ProjectCommand::AddTodo(element) => {
+                    self.context.todos.push(TodoMarker::new(element));
+                    self.context.set_has_unsaved_changes(true);
                 }
                 ProjectCommand::AddNewDocument(uuid, content) => {
                     let first_line = content
@@ -4092,6 +7784,19 @@ impl eframe::App for NHApp {
                     }
                     push_tab_to_best!(self, NHTab::Document { uuid });
                 }
+                ProjectCommand::ImportDocuments => {
+                    let d = rfd::AsyncFileDialog::new()
+                        .add_filter("Markdown and text files", &["md", "markdown", "txt"])
+                        .add_filter("All files", &["*"])
+                        .pick_files();
+                    let s = self.context.file_io_channel.0.clone();
+                    execute(async move {
+                        for fh in d.await.into_iter().flatten() {
+                            let name = fh.file_name();
+                            let _ = s.send(FileIOOperation::DocumentImport(name, fh.read().await));
+                        }
+                    });
+                }
                 ProjectCommand::DuplicateDocument(_uuid) => {
                     // TODO:
                 }
@@ -4102,10 +7807,73 @@ impl eframe::App for NHApp {
                         self.tree.remove_tab(snt);
                     }
                 }
+                ProjectCommand::PromoteToElement(document, range) => {
+                    self.context.promote_to_element(&document, range);
+                }
+                ProjectCommand::ExportInteractiveHtml(v) => {
+                    let Some(c) = self.context.diagram_controllers.get(&v).cloned() else {
+                        continue;
+                    };
+                    let mut c = c.write();
+                    let Some(html) = self.context.diagram_html(ui.ctx(), &v, &mut *c) else {
+                        continue;
+                    };
+                    let d = rfd::AsyncFileDialog::new()
+                        .set_file_name(format!("{}.html", c.view_name(&v)))
+                        .add_filter("HTML files", &["html", "htm"])
+                        .add_filter("All files", &["*"])
+                        .save_file();
+                    let s = self.context.file_io_channel.0.clone();
+                    execute(async move {
+                        if let Some(fh) = d.await
+                            && let Err(e) = fh.write(html.as_bytes()).await
+                        {
+                            let _ =
+                                s.send(FileIOOperation::Error(format!("Error exporting: {}", e)));
+                        }
+                    });
+                }
+                ProjectCommand::GenerateDocumentation => {
+                    let report = self.context.documentation_report(ui.ctx());
+                    let name = match &self.context.project_hierarchy {
+                        HierarchyNode::Folder(_, name, _) => format!("{}.md", name),
+                        _ => "documentation.md".to_owned(),
+                    };
+                    let d = rfd::AsyncFileDialog::new()
+                        .set_file_name(name)
+                        .add_filter("Markdown files", &["md"])
+                        .add_filter("All files", &["*"])
+                        .save_file();
+                    let s = self.context.file_io_channel.0.clone();
+                    execute(async move {
+                        if let Some(fh) = d.await
+                            && let Err(e) = fh.write(report.as_bytes()).await
+                        {
+                            let _ = s.send(FileIOOperation::Error(format!(
+                                "Error saving documentation: {}",
+                                e
+                            )));
+                        }
+                    });
+                }
             }
         }
 
         if !self.context.affected_models.is_empty() {
+            let now = external_links::now();
+            let mut links_affected = false;
+            for l in self
+                .context
+                .external_links
+                .iter_mut()
+                .filter(|e| self.context.affected_models.contains(&e.element))
+            {
+                l.last_edit = now;
+                links_affected = true;
+            }
+            if links_affected {
+                self.context.refresh_outdated_models();
+            }
             for c in self.context.diagram_controllers.values() {
                 c.write().refresh_buffers(
                     &self.context.affected_models,
@@ -4115,6 +7883,26 @@ impl eframe::App for NHApp {
             self.context.affected_models.clear();
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        self.context.watch_external_links(ui.ctx());
+        #[cfg(not(target_arch = "wasm32"))]
+        self.context.fetch_issue_infos(ui.ctx());
+
+        egui::Panel::bottom("status bar").show_inside(ui, |ui| {
+            let label = format!(
+                "{}: {}",
+                self.context
+                    .drawing_context
+                    .translate_0("nh-tab-tasks-count"),
+                self.context.todos.len(),
+            );
+            if ui.link(label).clicked() {
+                self.context
+                    .unprocessed_commands
+                    .push(ProjectCommand::OpenAndFocusTab(NHTab::Tasks, None));
+            }
+        });
+
         CentralPanel::default()
             // When displaying a DockArea in another UI, it looks better
             // to set inner margins to 0.